@@ -0,0 +1,16 @@
+//! Writes the C plugin header generated from the Rust ABI definitions.
+//!
+//! Usage: `cargo run -p engine_core --example gen_plugin_header [-- <path>]`
+//! (defaults to `engine/engine_plugin_abi.h`, relative to the workspace root).
+
+use engine_core::plugins::abi::c_header;
+use std::path::PathBuf;
+
+fn main() {
+    let path = std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("engine/engine_plugin_abi.h"));
+    std::fs::write(&path, c_header()).expect("Failed to write plugin header");
+    println!("Wrote {}", path.display());
+}
@@ -67,12 +67,10 @@ fn main() {
             cell_metadata.insert(cell, json!({ "terrain": terrain }));
         }
     }
-    let map = Map {
-        topology: Box::new(SquareGridMap {
-            cells,
-            cell_metadata,
-        }),
-    };
+    let map = Map::new(Box::new(SquareGridMap {
+        cells,
+        cell_metadata,
+    }));
     world.map = Some(map);
 
     // Spawn an entity at (4, 2)
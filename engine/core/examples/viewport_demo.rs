@@ -29,12 +29,10 @@ fn main() {
             cell_metadata.insert(cell, json!({ "terrain": terrain }));
         }
     }
-    let map = Map {
-        topology: Box::new(SquareGridMap {
-            cells,
-            cell_metadata,
-        }),
-    };
+    let map = Map::new(Box::new(SquareGridMap {
+        cells,
+        cell_metadata,
+    }));
 
     let schema_dir = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../assets/schemas");
     let allowed_modes = load_allowed_modes().expect("Failed to load allowed modes");
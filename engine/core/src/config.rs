@@ -21,6 +21,9 @@ pub struct GameConfig {
     pub allowed_modes: Vec<String>,
     /// Game plugins
     pub plugins: Option<PluginConfig>,
+    /// Master seed for the world RNG (random if omitted)
+    #[serde(default)]
+    pub seed: Option<u64>,
     // Add more fields as needed
 }
 
@@ -31,4 +34,15 @@ impl GameConfig {
         let config: GameConfig = toml::from_str(&content)?;
         Ok(config)
     }
+
+    /// Load the config file named by `MGE_CONFIG_FILE`, or the workspace's
+    /// `game.toml`.
+    pub fn load_default() -> anyhow::Result<Self> {
+        match std::env::var("MGE_CONFIG_FILE") {
+            Ok(path) => Self::load_from_file(path),
+            Err(_) => {
+                Self::load_from_file(Path::new(env!("CARGO_MANIFEST_DIR")).join("../../game.toml"))
+            }
+        }
+    }
 }
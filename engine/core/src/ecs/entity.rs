@@ -0,0 +1,168 @@
+//! Generational entity handles.
+//!
+//! Entity IDs stay plain `u32` values everywhere (components, saves, scripting
+//! bridges), but the bits pack a slot index and a generation: the low
+//! [`INDEX_BITS`] bits are the index and the remaining high bits the generation.
+//! Despawning an entity bumps the generation of its slot before the slot is reused,
+//! so an old ID held by a script or component (e.g. a job's `assigned_to`) no longer
+//! matches and is detected as stale instead of silently addressing the new entity.
+//!
+//! Generation 0 handles are equal to their index, so IDs of a fresh world are
+//! still `1, 2, 3, ...`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Number of low ID bits holding the slot index.
+pub const INDEX_BITS: u32 = 22;
+/// Largest slot index.
+pub const MAX_INDEX: u32 = (1 << INDEX_BITS) - 1;
+/// Largest generation; a slot reaching it is retired instead of reused.
+pub const MAX_GENERATION: u32 = u32::MAX >> INDEX_BITS;
+
+/// An entity ID split into slot index and generation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Entity {
+    /// Slot index (starts at 1).
+    pub index: u32,
+    /// Number of times the slot was reused.
+    pub generation: u32,
+}
+
+impl Entity {
+    /// Create a handle from an index and generation.
+    pub fn new(index: u32, generation: u32) -> Self {
+        debug_assert!(index <= MAX_INDEX && generation <= MAX_GENERATION);
+        Self { index, generation }
+    }
+
+    /// Split an entity ID into index and generation.
+    pub fn from_id(id: u32) -> Self {
+        Self {
+            index: id & MAX_INDEX,
+            generation: id >> INDEX_BITS,
+        }
+    }
+
+    /// Packed entity ID.
+    pub fn id(self) -> u32 {
+        (self.generation << INDEX_BITS) | self.index
+    }
+}
+
+/// Hands out entity IDs and tracks which ones are live.
+///
+/// IDs whose index was never allocated are unknown rather than stale, so entities
+/// created outside the allocator (e.g. components set on arbitrary IDs by older
+/// scripts) keep working.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntityAllocator {
+    /// Current generation of each slot; slot `i` holds index `i + 1`.
+    generations: Vec<u32>,
+    /// Whether each slot is currently allocated.
+    alive: Vec<bool>,
+    /// Freed indices waiting for reuse, oldest first.
+    free: VecDeque<u32>,
+}
+
+impl EntityAllocator {
+    /// Create an empty allocator.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rebuild an allocator from a legacy world whose IDs below `next_id` were
+    /// handed out and `live` are still alive.
+    ///
+    /// Dead legacy IDs are never reused, so handles to them stay stale. Fails if
+    /// an ID does not fit in [`INDEX_BITS`], since it would read as a later
+    /// generation of a lower index.
+    pub fn from_legacy(next_id: u32, live: &[u32]) -> Result<Self, String> {
+        let last_id = next_id
+            .saturating_sub(1)
+            .max(live.iter().copied().max().unwrap_or(0));
+        if last_id > MAX_INDEX {
+            return Err(format!(
+                "Legacy entity ID {last_id} exceeds the largest entity index {MAX_INDEX}"
+            ));
+        }
+        let slots = next_id.saturating_sub(1) as usize;
+        let mut alive = vec![false; slots];
+        for &id in live {
+            if let Some(slot) = (id as usize).checked_sub(1).filter(|&s| s < slots) {
+                alive[slot] = true;
+            }
+        }
+        Ok(Self {
+            generations: vec![0; slots],
+            alive,
+            free: VecDeque::new(),
+        })
+    }
+
+    /// ID the next call to [`allocate`](Self::allocate) will return.
+    pub fn peek(&self) -> u32 {
+        match self.free.front() {
+            Some(&index) => Entity::new(index, self.generations[index as usize - 1]).id(),
+            None => self.generations.len() as u32 + 1,
+        }
+    }
+
+    /// Allocate an entity ID, reusing the oldest freed slot if there is one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if all [`MAX_INDEX`] slots are in use.
+    pub fn allocate(&mut self) -> u32 {
+        if let Some(index) = self.free.pop_front() {
+            let slot = index as usize - 1;
+            self.alive[slot] = true;
+            return Entity::new(index, self.generations[slot]).id();
+        }
+        let index = self.generations.len() as u32 + 1;
+        assert!(index <= MAX_INDEX, "entity index space exhausted");
+        self.generations.push(0);
+        self.alive.push(true);
+        index
+    }
+
+    /// Free a live entity ID. Returns false if the ID is not live.
+    pub fn free(&mut self, id: u32) -> bool {
+        if !self.is_live(id) {
+            return false;
+        }
+        let index = Entity::from_id(id).index;
+        let slot = index as usize - 1;
+        self.alive[slot] = false;
+        if self.generations[slot] < MAX_GENERATION {
+            self.generations[slot] += 1;
+            self.free.push_back(index);
+        }
+        true
+    }
+
+    /// Returns true if the ID was handed out by this allocator and not freed since.
+    pub fn is_live(&self, id: u32) -> bool {
+        let Entity { index, generation } = Entity::from_id(id);
+        self.slot(index)
+            .is_some_and(|slot| self.alive[slot] && self.generations[slot] == generation)
+    }
+
+    /// Returns true if the ID refers to a despawned entity or an older generation
+    /// of a reused slot.
+    pub fn is_stale(&self, id: u32) -> bool {
+        let index = Entity::from_id(id).index;
+        self.slot(index).is_some() && !self.is_live(id)
+    }
+
+    /// Number of live entities.
+    pub fn live_count(&self) -> usize {
+        self.alive.iter().filter(|&&alive| alive).count()
+    }
+
+    fn slot(&self, index: u32) -> Option<usize> {
+        (index as usize)
+            .checked_sub(1)
+            .filter(|&slot| slot < self.generations.len())
+    }
+}
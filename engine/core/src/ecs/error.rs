@@ -28,3 +28,25 @@ pub enum MigrationError {
     #[error("Data format mismatch")]
     DataFormatError,
 }
+
+/// Errors that can occur while replaying a recorded session.
+#[derive(Debug, Error)]
+pub enum ReplayError {
+    /// The recording's initial snapshot could not be restored.
+    #[error("Failed to restore recording snapshot: {0}")]
+    Restore(#[from] std::io::Error),
+    /// The replayed state diverged from the recorded state.
+    #[error(
+        "Desync at tick {tick} (turn {turn}): expected hash {expected:016x}, got {actual:016x}"
+    )]
+    Desync {
+        /// Index of the first diverging tick in the recording.
+        tick: usize,
+        /// World turn after the diverging tick.
+        turn: u32,
+        /// Hash recorded for the tick.
+        expected: u64,
+        /// Hash produced by the replay.
+        actual: u64,
+    },
+}
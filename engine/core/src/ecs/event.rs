@@ -71,6 +71,12 @@ impl<E> EventBus<E> {
         })
     }
 
+    /// Returns true if no events were sent since the last update and none were
+    /// delivered by it.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty() && self.last_events.is_empty()
+    }
+
     /// Set the current event buffer.
     pub fn set_events(&mut self, events: std::collections::VecDeque<E>) {
         self.events = events;
@@ -6,6 +6,8 @@
 pub mod assets;
 /// Components
 pub mod components;
+/// Generational entity handles
+pub mod entity;
 mod error;
 /// Events
 pub mod event;
@@ -13,17 +15,29 @@ pub mod event;
 pub mod event_bus_registry;
 /// Event logger
 pub mod event_logger;
+/// Entity queries
+pub mod query;
 /// Component registry
 pub mod registry;
+/// Save-game migrations
+pub mod save_migration;
+/// System scheduling: stages, run conditions, fixed timestep and parallel batches
+pub mod schedule;
 /// Schemas
 pub mod schema;
+/// Typed component storage
+pub mod storage;
 /// Systems
 pub mod system;
 /// World
 pub mod world;
 
 pub use components::{Health, Position};
-pub use error::{MigrationError, RegistryError};
+pub use entity::{Entity, EntityAllocator};
+pub use error::{MigrationError, RegistryError, ReplayError};
+pub use query::{FieldOp, Query, QueryRow};
 pub use registry::{Component, ComponentRegistry};
+pub use schedule::{FixedTimestep, RunCondition, SystemStage};
 pub use schema::ComponentSchema;
+pub use storage::{SparseSet, TypedComponentStorage};
 pub use world::World;
@@ -0,0 +1,378 @@
+//! Entity queries.
+//!
+//! A [`Query`] selects entities by the components they have (`with`), must not
+//! have (`without`) or may have (`optional`), by predicates on component JSON
+//! fields, and by change detection: [`changed`](Query::changed) and
+//! [`added`](Query::added) match components written after a change tick. Inside a
+//! system that tick defaults to the system's previous run, so a system only sees
+//! what changed since it last ran.
+//!
+//! Queries are plain data (apart from Rust closure filters) and deserialize from
+//! JSON, which is how scripting bridges build them:
+//!
+//! ```json
+//! { "with": ["Health"], "without": ["Corpse"], "optional": ["Stats"],
+//!   "where": [{ "component": "Health", "path": "current", "op": "<", "value": 5 }],
+//!   "changed": ["Health"], "since": 42 }
+//! ```
+
+use crate::ecs::world::World;
+use crate::ecs::world::wasm::WasmWorld;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Comparison applied by a field predicate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FieldOp {
+    /// Equal (numbers compare by value, so `5` equals `5.0`).
+    #[serde(rename = "==")]
+    Eq,
+    /// Not equal.
+    #[serde(rename = "!=")]
+    Ne,
+    /// Less than (numbers and strings).
+    #[serde(rename = "<")]
+    Lt,
+    /// Less than or equal.
+    #[serde(rename = "<=")]
+    Le,
+    /// Greater than.
+    #[serde(rename = ">")]
+    Gt,
+    /// Greater than or equal.
+    #[serde(rename = ">=")]
+    Ge,
+}
+
+impl FieldOp {
+    /// Parse an operator (`"=="`, `"!="`, `"<"`, `"<="`, `">"` or `">="`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "==" => Some(Self::Eq),
+            "!=" => Some(Self::Ne),
+            "<" => Some(Self::Lt),
+            "<=" => Some(Self::Le),
+            ">" => Some(Self::Gt),
+            ">=" => Some(Self::Ge),
+            _ => None,
+        }
+    }
+
+    /// Compare an actual field value against the expected one.
+    pub fn compare(self, actual: &JsonValue, expected: &JsonValue) -> bool {
+        let ordering = match (actual, expected) {
+            (JsonValue::Number(a), JsonValue::Number(b)) => a
+                .as_f64()
+                .zip(b.as_f64())
+                .and_then(|(a, b)| a.partial_cmp(&b)),
+            (JsonValue::String(a), JsonValue::String(b)) => Some(a.cmp(b)),
+            (JsonValue::Bool(a), JsonValue::Bool(b)) => Some(a.cmp(b)),
+            _ => None,
+        };
+        let equal = ordering.map_or(actual == expected, |o| o == Ordering::Equal);
+        match self {
+            Self::Eq => equal,
+            Self::Ne => !equal,
+            Self::Lt => ordering == Some(Ordering::Less),
+            Self::Le => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+            Self::Gt => ordering == Some(Ordering::Greater),
+            Self::Ge => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+        }
+    }
+}
+
+/// Predicate on a field of a component's JSON value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldPredicate {
+    /// Component holding the field; entities without it do not match.
+    pub component: String,
+    /// Dotted path to the field (`"current"`, `"stats.strength"`, `"parts.0.hp"`);
+    /// empty for the whole value.
+    #[serde(default)]
+    pub path: String,
+    /// Comparison operator.
+    pub op: FieldOp,
+    /// Value to compare with.
+    pub value: JsonValue,
+}
+
+impl FieldPredicate {
+    /// Returns true if the component value satisfies the predicate.
+    pub fn matches(&self, component: &JsonValue) -> bool {
+        lookup_path(component, &self.path)
+            .is_some_and(|actual| self.op.compare(actual, &self.value))
+    }
+}
+
+/// Look up a dotted path in a JSON value. Numeric segments index arrays.
+pub fn lookup_path<'a>(value: &'a JsonValue, path: &str) -> Option<&'a JsonValue> {
+    if path.is_empty() {
+        return Some(value);
+    }
+    path.split('.')
+        .try_fold(value, |current, key| match current {
+            JsonValue::Object(map) => map.get(key),
+            JsonValue::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        })
+}
+
+/// Closure filter on a component value (Rust only).
+pub type ComponentFilter = Arc<dyn Fn(&JsonValue) -> bool + Send + Sync>;
+
+/// An entity query; see the [module documentation](self).
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Query {
+    /// Components an entity must have.
+    pub with: Vec<String>,
+    /// Components an entity must not have.
+    pub without: Vec<String>,
+    /// Components included in [`rows`](Query::rows) when present.
+    pub optional: Vec<String>,
+    /// Predicates on component fields.
+    #[serde(rename = "where")]
+    pub predicates: Vec<FieldPredicate>,
+    /// Components that must have been added or changed after the change tick.
+    pub changed: Vec<String>,
+    /// Components that must have been added after the change tick.
+    pub added: Vec<String>,
+    /// Change tick for `changed`/`added`; defaults to the running system's last run.
+    pub since: Option<u64>,
+    #[serde(skip)]
+    filters: Vec<(String, ComponentFilter)>,
+}
+
+/// An entity matched by a query, with the values of the queried components it has.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueryRow {
+    /// Entity ID.
+    pub entity: u32,
+    /// Component name to value, for every queried component present.
+    pub components: BTreeMap<String, JsonValue>,
+}
+
+/// World data a [`Query`] runs against.
+pub trait QuerySource {
+    /// Entities having all of `with` (every entity when empty), in ascending order.
+    fn query_candidates(&self, with: &[String]) -> Vec<u32>;
+    /// A component value, if the entity has it.
+    fn query_component(&self, entity: u32, name: &str) -> Option<&JsonValue>;
+    /// Change tick a component was last added (`added_only`) or changed at.
+    fn query_change_tick(&self, entity: u32, name: &str, added_only: bool) -> Option<u64>;
+    /// Change tick used when the query does not set one.
+    fn query_default_since(&self) -> Option<u64>;
+}
+
+impl Query {
+    /// Create an empty query (matches every entity).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require a component.
+    pub fn with(mut self, component: &str) -> Self {
+        self.with.push(component.to_string());
+        self
+    }
+
+    /// Exclude entities having a component.
+    pub fn without(mut self, component: &str) -> Self {
+        self.without.push(component.to_string());
+        self
+    }
+
+    /// Include a component in the rows when present, without requiring it.
+    pub fn optional(mut self, component: &str) -> Self {
+        self.optional.push(component.to_string());
+        self
+    }
+
+    /// Require a component field to compare to `value` with `op`.
+    pub fn where_field(
+        mut self,
+        component: &str,
+        path: &str,
+        op: FieldOp,
+        value: JsonValue,
+    ) -> Self {
+        self.predicates.push(FieldPredicate {
+            component: component.to_string(),
+            path: path.to_string(),
+            op,
+            value,
+        });
+        self
+    }
+
+    /// Require a component whose value passes `filter`.
+    pub fn filter<F>(mut self, component: &str, filter: F) -> Self
+    where
+        F: Fn(&JsonValue) -> bool + Send + Sync + 'static,
+    {
+        self.filters.push((component.to_string(), Arc::new(filter)));
+        self
+    }
+
+    /// Require a component added or changed after the change tick.
+    pub fn changed(mut self, component: &str) -> Self {
+        self.changed.push(component.to_string());
+        self
+    }
+
+    /// Require a component added after the change tick.
+    pub fn added(mut self, component: &str) -> Self {
+        self.added.push(component.to_string());
+        self
+    }
+
+    /// Set the change tick `changed` and `added` compare against.
+    pub fn since(mut self, tick: u64) -> Self {
+        self.since = Some(tick);
+        self
+    }
+
+    /// Matching entities, in ascending ID order.
+    pub fn entities(&self, source: &impl QuerySource) -> Vec<u32> {
+        let since = self.since.or_else(|| source.query_default_since());
+        source
+            .query_candidates(&self.with)
+            .into_iter()
+            .filter(|&entity| self.matches_since(source, entity, since))
+            .collect()
+    }
+
+    /// Matching entities with their queried component values.
+    pub fn rows(&self, source: &impl QuerySource) -> Vec<QueryRow> {
+        self.entities(source)
+            .into_iter()
+            .map(|entity| {
+                let components = self
+                    .row_components()
+                    .filter_map(|name| {
+                        source
+                            .query_component(entity, name)
+                            .map(|value| (name.to_string(), value.clone()))
+                    })
+                    .collect();
+                QueryRow { entity, components }
+            })
+            .collect()
+    }
+
+    /// Returns true if an entity matches the query.
+    pub fn matches(&self, source: &impl QuerySource, entity: u32) -> bool {
+        let since = self.since.or_else(|| source.query_default_since());
+        self.matches_since(source, entity, since)
+    }
+
+    fn matches_since(&self, source: &impl QuerySource, entity: u32, since: Option<u64>) -> bool {
+        let has = |name: &str| source.query_component(entity, name).is_some();
+        let newer = |name: &str, added_only: bool| match since {
+            None => has(name),
+            Some(since) => {
+                has(name)
+                    && source
+                        .query_change_tick(entity, name, added_only)
+                        .is_some_and(|tick| tick > since)
+            }
+        };
+        self.with.iter().all(|name| has(name))
+            && !self.without.iter().any(|name| has(name))
+            && self.predicates.iter().all(|p| {
+                source
+                    .query_component(entity, &p.component)
+                    .is_some_and(|value| p.matches(value))
+            })
+            && self.filters.iter().all(|(name, filter)| {
+                source
+                    .query_component(entity, name)
+                    .is_some_and(|value| filter(value))
+            })
+            && self.changed.iter().all(|name| newer(name, false))
+            && self.added.iter().all(|name| newer(name, true))
+    }
+
+    fn row_components(&self) -> impl Iterator<Item = &str> {
+        let mut names: Vec<&str> = self
+            .with
+            .iter()
+            .chain(&self.optional)
+            .chain(&self.changed)
+            .chain(&self.added)
+            .map(String::as_str)
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+        names.into_iter()
+    }
+}
+
+impl QuerySource for World {
+    fn query_candidates(&self, with: &[String]) -> Vec<u32> {
+        if with.is_empty() {
+            let mut all = self.get_entities();
+            all.sort_unstable();
+            all.dedup();
+            return all;
+        }
+        let names: Vec<&str> = with.iter().map(String::as_str).collect();
+        self.get_entities_with_components(&names)
+    }
+
+    fn query_component(&self, entity: u32, name: &str) -> Option<&JsonValue> {
+        self.get_component(entity, name)
+    }
+
+    fn query_change_tick(&self, entity: u32, name: &str, added_only: bool) -> Option<u64> {
+        self.component_ticks(entity, name).map(|ticks| {
+            if added_only {
+                ticks.added
+            } else {
+                ticks.changed
+            }
+        })
+    }
+
+    fn query_default_since(&self) -> Option<u64> {
+        self.system_last_run()
+    }
+}
+
+impl QuerySource for WasmWorld {
+    fn query_candidates(&self, with: &[String]) -> Vec<u32> {
+        let mut ids = if with.is_empty() {
+            self.entities.clone()
+        } else {
+            let names: Vec<&str> = with.iter().map(String::as_str).collect();
+            self.get_entities_with_components(&names)
+        };
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    fn query_component(&self, entity: u32, name: &str) -> Option<&JsonValue> {
+        if self.is_stale_entity(entity) {
+            return None;
+        }
+        self.components.get(name)?.get(&entity)
+    }
+
+    fn query_change_tick(&self, entity: u32, name: &str, added_only: bool) -> Option<u64> {
+        self.component_ticks(entity, name).map(|ticks| {
+            if added_only {
+                ticks.added
+            } else {
+                ticks.changed
+            }
+        })
+    }
+
+    fn query_default_since(&self) -> Option<u64> {
+        None
+    }
+}
@@ -1,7 +1,11 @@
 use crate::ecs::error::{MigrationError, RegistryError};
+use crate::ecs::save_migration::SaveMigrationRegistry;
 use crate::ecs::schema::ComponentSchema;
+use crate::ecs::storage::{ComponentColumn, SparseSet};
 use anyhow::Result;
 pub use semver::Version;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
 use serde_json;
 use std::any::TypeId;
 use std::collections::HashMap;
@@ -10,6 +14,22 @@ use std::collections::HashMap;
 pub struct ComponentRegistry {
     components: HashMap<TypeId, ComponentSchema>,
     external_components: HashMap<String, ComponentSchema>,
+    /// Column constructors for Rust-registered components, by short type name.
+    typed_columns: HashMap<String, fn() -> Box<dyn ComponentColumn>>,
+    save_migrations: SaveMigrationRegistry,
+}
+
+fn new_typed_column<T>() -> Box<dyn ComponentColumn>
+where
+    T: Serialize + DeserializeOwned + Send + Sync + 'static,
+{
+    Box::new(SparseSet::<T>::new())
+}
+
+/// Component name of a Rust type: its type name without the module path.
+fn short_type_name<T>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    name.rsplit("::").next().unwrap_or(name)
 }
 
 /// Trait for ECS components supporting schema, versioning, and migration.
@@ -40,13 +60,19 @@ impl ComponentRegistry {
         Self {
             components: HashMap::new(),
             external_components: HashMap::new(),
+            typed_columns: HashMap::new(),
+            save_migrations: SaveMigrationRegistry::new(),
         }
     }
 
     /// Register a component type and its schema.
-    pub fn register_component<T: super::Component>(
-        &mut self,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    ///
+    /// Worlds sharing this registry keep the component (named by its type name) in
+    /// typed columnar storage, starting with its first write.
+    pub fn register_component<T>(&mut self) -> Result<(), Box<dyn std::error::Error>>
+    where
+        T: super::Component + Serialize + DeserializeOwned,
+    {
         let type_id = TypeId::of::<T>();
         let schema = T::generate_schema();
 
@@ -60,6 +86,8 @@ impl ComponentRegistry {
                 modes: vec![],
             },
         );
+        self.typed_columns
+            .insert(short_type_name::<T>().to_string(), new_typed_column::<T>);
         Ok(())
     }
 
@@ -72,6 +100,12 @@ impl ComponentRegistry {
     pub fn unregister_component<T: super::Component>(&mut self) {
         let type_id = std::any::TypeId::of::<T>();
         self.components.remove(&type_id);
+        self.typed_columns.remove(short_type_name::<T>());
+    }
+
+    /// A new, empty typed column for a component registered with a Rust type.
+    pub fn new_typed_column(&self, name: &str) -> Option<Box<dyn ComponentColumn>> {
+        self.typed_columns.get(name).map(|new_column| new_column())
     }
 
     /// Get the schema for a registered component type.
@@ -200,19 +234,37 @@ impl ComponentRegistry {
     }
 
     /// Update (hot-reload) an external component schema by name, migrating all data.
+    ///
+    /// The migration is also registered as a save migration upgrading the schema
+    /// to `version` (numbered per schema, see
+    /// [`schema_scope`](crate::ecs::save_migration::schema_scope)), so saves
+    /// written before the update are migrated when loaded.
     pub fn update_external_schema_with_migration<F>(
         &mut self,
         schema: ComponentSchema,
+        version: u32,
         component_data: &mut std::collections::HashMap<u32, serde_json::Value>,
         migrate: F,
     ) -> Result<(), RegistryError>
     where
-        F: Fn(&serde_json::Value) -> serde_json::Value,
+        F: Fn(&serde_json::Value) -> serde_json::Value + Send + Sync + 'static,
     {
         for value in component_data.values_mut() {
             *value = migrate(value);
         }
+        self.save_migrations
+            .register_component_migration(version, &schema.name, migrate);
         self.external_components.insert(schema.name.clone(), schema);
         Ok(())
     }
+
+    /// Save migrations applied when loading older saves.
+    pub fn save_migrations(&self) -> &SaveMigrationRegistry {
+        &self.save_migrations
+    }
+
+    /// Mutable access to the save migrations, for registering new ones.
+    pub fn save_migrations_mut(&mut self) -> &mut SaveMigrationRegistry {
+        &mut self.save_migrations
+    }
 }
@@ -0,0 +1,377 @@
+//! Save-game migrations.
+//!
+//! Save files are stamped with a save-format version (see
+//! [`SaveHeader`](crate::ecs::world::SaveHeader)). A [`SaveMigrationRegistry`] holds
+//! ordered migrations keyed by the version they upgrade a save *to*; on load every
+//! migration newer than the file's version runs against the raw save JSON, in order.
+//!
+//! Component schema migrations and mod migrations are registered here too, so old
+//! saves pick them up automatically. Each schema ([`schema_scope`]) and each mod
+//! ([`mod_scope`]) numbers its migrations in its own scope, stamped into the save
+//! header next to the engine's save-format version, so their versions never depend
+//! on registration order or collide with the engine's.
+
+use crate::ecs::entity::EntityAllocator;
+use crate::ecs::world::SAVE_FORMAT_VERSION;
+use serde::Serialize;
+use serde_json::{Map, Value as JsonValue};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+/// Migration function applied to a raw save value.
+pub type SaveMigrationFn =
+    Arc<dyn Fn(&mut JsonValue, &mut MigrationReport) -> Result<(), String> + Send + Sync>;
+
+/// Migration scope of a component schema's migrations.
+pub fn schema_scope(component: &str) -> String {
+    format!("schema:{component}")
+}
+
+/// Migration scope of a mod's migrations.
+pub fn mod_scope(mod_name: &str) -> String {
+    format!("mod:{mod_name}")
+}
+
+/// A single save migration.
+#[derive(Clone)]
+pub struct SaveMigration {
+    /// Scope the version belongs to; `None` for the engine's save format.
+    pub scope: Option<String>,
+    /// Version this migration upgrades to, within its scope.
+    pub version: u32,
+    /// Human-readable migration name, used in reports.
+    pub name: String,
+    migrate: SaveMigrationFn,
+}
+
+/// A component-level change recorded while migrating a save.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MigrationEntry {
+    /// Migration that made the change.
+    pub migration: String,
+    /// Affected component.
+    pub component: String,
+    /// Affected entity, if the change concerns a single entity.
+    pub entity: Option<u32>,
+    /// Reason or description of the change.
+    pub detail: String,
+}
+
+/// Summary of what happened while migrating a save.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct MigrationReport {
+    /// Save-format version of the file.
+    pub from_version: u32,
+    /// Save-format version after migration.
+    pub to_version: u32,
+    /// Names of the migrations that ran, in order.
+    pub applied: Vec<String>,
+    /// Component data that was migrated.
+    pub migrated: Vec<MigrationEntry>,
+    /// Component data that was dropped.
+    pub dropped: Vec<MigrationEntry>,
+    current: String,
+}
+
+impl MigrationReport {
+    /// Returns true if no migration ran.
+    pub fn is_empty(&self) -> bool {
+        self.applied.is_empty()
+    }
+
+    /// Record migrated component data.
+    pub fn record_migrated(&mut self, component: &str, entity: Option<u32>, detail: &str) {
+        self.migrated.push(self.entry(component, entity, detail));
+    }
+
+    /// Record dropped component data.
+    pub fn record_dropped(&mut self, component: &str, entity: Option<u32>, detail: &str) {
+        self.dropped.push(self.entry(component, entity, detail));
+    }
+
+    fn entry(&self, component: &str, entity: Option<u32>, detail: &str) -> MigrationEntry {
+        MigrationEntry {
+            migration: self.current.clone(),
+            component: component.to_string(),
+            entity,
+            detail: detail.to_string(),
+        }
+    }
+}
+
+/// Ordered registry of save migrations.
+#[derive(Clone)]
+pub struct SaveMigrationRegistry {
+    migrations: Vec<SaveMigration>,
+}
+
+impl Default for SaveMigrationRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SaveMigrationRegistry {
+    /// Create a registry containing the engine's built-in migrations.
+    pub fn new() -> Self {
+        let mut registry = Self {
+            migrations: Vec::new(),
+        };
+        registry.register(2, "agent_skills_to_skill_levels", |save, report| {
+            migrate_agent_skills_in_save(save, report);
+            Ok(())
+        });
+        registry.register(3, "generational_entity_ids", |save, _| {
+            migrate_next_id_to_entity_allocator(save)
+        });
+        registry
+    }
+
+    /// Register a migration upgrading saves to save-format `version`.
+    ///
+    /// Migrations run in version order; migrations sharing a version run in
+    /// registration order.
+    pub fn register<F>(&mut self, version: u32, name: &str, migrate: F)
+    where
+        F: Fn(&mut JsonValue, &mut MigrationReport) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.insert(None, version, name, Arc::new(migrate));
+    }
+
+    /// Register a migration upgrading saves to `version` of `scope`.
+    ///
+    /// Scoped migrations run after the engine's, by scope name and then in version
+    /// order. Registering a migration again with the same scope, version and name
+    /// replaces it, so reloading a schema or mod does not run it twice.
+    pub fn register_scoped<F>(&mut self, scope: &str, version: u32, name: &str, migrate: F)
+    where
+        F: Fn(&mut JsonValue, &mut MigrationReport) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.migrations.retain(|m| {
+            m.scope.as_deref() != Some(scope) || m.version != version || m.name != name
+        });
+        self.insert(Some(scope.to_string()), version, name, Arc::new(migrate));
+    }
+
+    fn insert(
+        &mut self,
+        scope: Option<String>,
+        version: u32,
+        name: &str,
+        migrate: SaveMigrationFn,
+    ) {
+        let idx = self
+            .migrations
+            .partition_point(|m| (&m.scope, m.version) <= (&scope, version));
+        self.migrations.insert(
+            idx,
+            SaveMigration {
+                scope,
+                version,
+                name: name.to_string(),
+                migrate,
+            },
+        );
+    }
+
+    /// Register a per-value migration for one component, upgrading saves to
+    /// `version` of the component's [`schema_scope`].
+    pub fn register_component_migration<F>(&mut self, version: u32, component: &str, migrate: F)
+    where
+        F: Fn(&JsonValue) -> JsonValue + Send + Sync + 'static,
+    {
+        let component_name = component.to_string();
+        self.register_scoped(
+            &schema_scope(component),
+            version,
+            &format!("{component} schema migration"),
+            move |save, report| {
+                for_each_component_value(save, &component_name, |eid, value| {
+                    *value = migrate(value);
+                    report.record_migrated(&component_name, Some(eid), "migrated");
+                    true
+                });
+                Ok(())
+            },
+        );
+    }
+
+    /// Latest save-format version known to this registry.
+    pub fn latest_version(&self) -> u32 {
+        self.migrations
+            .iter()
+            .filter(|m| m.scope.is_none())
+            .map(|m| m.version)
+            .max()
+            .unwrap_or(0)
+            .max(SAVE_FORMAT_VERSION)
+    }
+
+    /// Latest version of every scope with registered migrations.
+    pub fn latest_scoped_versions(&self) -> BTreeMap<String, u32> {
+        let mut versions = BTreeMap::new();
+        for migration in &self.migrations {
+            if let Some(scope) = &migration.scope {
+                let latest = versions.entry(scope.clone()).or_insert(0);
+                *latest = migration.version.max(*latest);
+            }
+        }
+        versions
+    }
+
+    /// Registered migrations, in execution order.
+    pub fn migrations(&self) -> &[SaveMigration] {
+        &self.migrations
+    }
+
+    /// Run every migration newer than the save's versions against a raw save value.
+    ///
+    /// `from_version` is the save-format version of the save and `from_scoped` the
+    /// versions of its scopes; scopes missing from it start at 0.
+    pub fn migrate(
+        &self,
+        save: &mut JsonValue,
+        from_version: u32,
+        from_scoped: &BTreeMap<String, u32>,
+    ) -> Result<MigrationReport, String> {
+        let mut report = MigrationReport {
+            from_version,
+            to_version: from_version,
+            ..Default::default()
+        };
+        let pending = self.migrations.iter().filter(|m| match &m.scope {
+            None => m.version > from_version,
+            Some(scope) => m.version > from_scoped.get(scope).copied().unwrap_or(0),
+        });
+        for migration in pending {
+            report.current = migration.name.clone();
+            (migration.migrate)(save, &mut report)
+                .map_err(|e| format!("Save migration '{}' failed: {e}", migration.name))?;
+            report.applied.push(migration.name.clone());
+            if migration.scope.is_none() {
+                report.to_version = migration.version;
+            }
+        }
+        report.to_version = report.to_version.max(self.latest_version());
+        report.current.clear();
+        Ok(report)
+    }
+}
+
+/// Apply an edit to every value of a component in a raw save.
+/// Values for which `edit` returns false are removed.
+pub fn for_each_component_value<F>(save: &mut JsonValue, component: &str, mut edit: F)
+where
+    F: FnMut(u32, &mut JsonValue) -> bool,
+{
+    if let Some(values) = save
+        .get_mut("components")
+        .and_then(|c| c.get_mut(component))
+        .and_then(|m| m.as_object_mut())
+    {
+        values.retain(|eid, value| match eid.parse::<u32>() {
+            Ok(eid) => edit(eid, value),
+            Err(_) => true,
+        });
+    }
+}
+
+/// Populate `SkillLevels` from deprecated `Agent.skills` data.
+///
+/// Entities that already have `SkillLevels` keep it. Returns the migrated entity IDs.
+pub fn migrate_agent_skills(components: &mut HashMap<String, HashMap<u32, JsonValue>>) -> Vec<u32> {
+    let mut migrated = Vec::new();
+    let Some(agents) = components.get("Agent") else {
+        return migrated;
+    };
+    let mut new_levels = Vec::new();
+    for (&eid, agent) in agents {
+        if components
+            .get("SkillLevels")
+            .is_some_and(|map| map.contains_key(&eid))
+        {
+            continue;
+        }
+        if let Some(skills) = agent.get("skills").and_then(|v| v.as_object())
+            && !skills.is_empty()
+        {
+            log::warn!(
+                "DEPRECATION: entity {eid} uses agent.skills — migrating to SkillLevels component. \
+                 agent.skills will be removed in a future milestone."
+            );
+            let mut skill_levels_map = Map::new();
+            skill_levels_map.insert("skills".to_string(), JsonValue::Object(skills.clone()));
+            skill_levels_map.insert("total_xp".to_string(), JsonValue::from(0.0));
+            skill_levels_map.insert("skill_xp".to_string(), JsonValue::Object(Map::new()));
+            skill_levels_map.insert("skill_levels".to_string(), JsonValue::Object(Map::new()));
+            new_levels.push((eid, JsonValue::Object(skill_levels_map)));
+        }
+    }
+    if new_levels.is_empty() {
+        return migrated;
+    }
+    let levels = components.entry("SkillLevels".to_string()).or_default();
+    for (eid, value) in new_levels {
+        levels.insert(eid, value);
+        migrated.push(eid);
+    }
+    migrated.sort_unstable();
+    migrated
+}
+
+fn migrate_agent_skills_in_save(save: &mut JsonValue, report: &mut MigrationReport) {
+    let Some(components) = save.get_mut("components") else {
+        return;
+    };
+    let Ok(mut map) =
+        serde_json::from_value::<HashMap<String, HashMap<u32, JsonValue>>>(components.clone())
+    else {
+        return;
+    };
+    let migrated = migrate_agent_skills(&mut map);
+    if migrated.is_empty() {
+        return;
+    }
+    if let Ok(value) = serde_json::to_value(&map) {
+        *components = value;
+        for eid in migrated {
+            report.record_migrated("SkillLevels", Some(eid), "populated from agent.skills");
+        }
+    }
+}
+
+/// Replace the plain `next_id` counter of older saves with an entity allocator.
+///
+/// Slots of already despawned entities are never reused, so IDs held onto from
+/// before the upgrade are detected as stale.
+fn migrate_next_id_to_entity_allocator(save: &mut JsonValue) -> Result<(), String> {
+    let Some(obj) = save.as_object_mut() else {
+        return Ok(());
+    };
+    let next_id = obj.remove("next_id").and_then(|v| v.as_u64());
+    if obj.contains_key("entity_allocator") {
+        return Ok(());
+    }
+    let to_u32 = |id: u64| u32::try_from(id).map_err(|_| format!("Entity ID {id} is out of range"));
+    let live: Vec<u32> = obj
+        .get("entities")
+        .and_then(|e| e.as_array())
+        .map(|ids| {
+            ids.iter()
+                .filter_map(|id| id.as_u64())
+                .map(to_u32)
+                .collect()
+        })
+        .transpose()?
+        .unwrap_or_default();
+    let next_id = match next_id {
+        Some(id) => to_u32(id)?,
+        None => live.iter().max().map_or(1, |max| max.saturating_add(1)),
+    };
+    let allocator = EntityAllocator::from_legacy(next_id, &live)?;
+    obj.insert(
+        "entity_allocator".to_string(),
+        serde_json::to_value(allocator).map_err(|e| e.to_string())?,
+    );
+    Ok(())
+}
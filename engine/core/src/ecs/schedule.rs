@@ -0,0 +1,357 @@
+//! System scheduling: stages, run conditions, fixed timestep and parallel batches.
+//!
+//! A tick runs the [`SystemStage`]s in order. Within a stage, native systems run
+//! first, then dynamic (scripted) systems. A system only runs when all of its
+//! [`RunCondition`]s hold at the start of its stage. [`FixedTimestep`] turns
+//! real elapsed time into a whole number of ticks of `step` seconds each, and
+//! `step` is the `dt` passed to dynamic systems.
+//!
+//! Native systems within a stage run in deterministic execution order (see
+//! [`crate::systems::order_systems`]). Consecutive systems that declare their
+//! component and resource access ([`System::access`]) and do not conflict are
+//! grouped into a batch. A batch runs in two phases:
+//!
+//! 1. every system's [`System::run_parallel`] computes its effects on the rayon
+//!    thread pool from a read-only [`SystemView`] of the world;
+//! 2. the returned commands are applied one system at a time, in execution order.
+//!
+//! Since no system in a batch writes a component or resource another one reads
+//! or writes, the result is identical to running the batch serially, including
+//! per-system state hashes. Systems without declared access run alone, as before.
+
+use crate::ecs::entity::EntityAllocator;
+use crate::ecs::query::QuerySource;
+use crate::ecs::storage::TypedComponentStorage;
+use crate::ecs::system::{System, SystemAccess};
+use crate::ecs::world::{ChangeTracker, ComponentTicks, TimeOfDay, World};
+use crate::map::Map;
+use crate::map::fov::FovAlgorithm;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::{HashMap, HashSet};
+
+/// Named phase of a tick. Stages run in declaration order.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SystemStage {
+    /// Input handling, spawning and other setup for the tick.
+    PreUpdate,
+    /// Simulation (the default stage).
+    #[default]
+    Update,
+    /// Reactions to the simulation: deaths, cleanup, derived state.
+    PostUpdate,
+    /// Presentation: cameras, UI and other output.
+    Render,
+}
+
+impl SystemStage {
+    /// All stages, in the order a tick runs them.
+    pub const ALL: [SystemStage; 4] = [
+        SystemStage::PreUpdate,
+        SystemStage::Update,
+        SystemStage::PostUpdate,
+        SystemStage::Render,
+    ];
+
+    /// Parse a stage name ("pre_update", "update", "post_update", "render").
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "pre_update" => Some(SystemStage::PreUpdate),
+            "update" => Some(SystemStage::Update),
+            "post_update" => Some(SystemStage::PostUpdate),
+            "render" => Some(SystemStage::Render),
+            _ => None,
+        }
+    }
+
+    /// The stage name used by [`SystemStage::from_name`] and in JSON.
+    pub fn name(&self) -> &'static str {
+        match self {
+            SystemStage::PreUpdate => "pre_update",
+            SystemStage::Update => "update",
+            SystemStage::PostUpdate => "post_update",
+            SystemStage::Render => "render",
+        }
+    }
+}
+
+/// Condition a system needs to run in a tick.
+///
+/// In JSON: `{"type": "in_mode", "mode": "colony"}`,
+/// `{"type": "every_n_ticks", "n": 10, "offset": 0}` or
+/// `{"type": "events_pending", "bus": "DamageEvent"}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RunCondition {
+    /// Run only while the world is in the given mode.
+    InMode {
+        /// Mode name, compared to `world.current_mode`.
+        mode: String,
+    },
+    /// Run on turns where `turn % n == offset % n`.
+    EveryNTicks {
+        /// Interval in ticks; 0 never runs.
+        n: u32,
+        /// Turn offset within the interval.
+        #[serde(default)]
+        offset: u32,
+    },
+    /// Run only when the named JSON event bus holds events (sent this tick or
+    /// delivered by the last update).
+    EventsPending {
+        /// Event bus name.
+        bus: String,
+    },
+}
+
+impl RunCondition {
+    /// Returns true if the condition holds for the world's current state.
+    pub fn is_met(&self, world: &World) -> bool {
+        match self {
+            RunCondition::InMode { mode } => world.current_mode == *mode,
+            RunCondition::EveryNTicks { n, offset } => *n > 0 && world.turn % n == offset % n,
+            RunCondition::EventsPending { bus } => world
+                .get_event_bus::<JsonValue>(bus)
+                .is_some_and(|bus| !bus.lock().unwrap().is_empty()),
+        }
+    }
+}
+
+/// Default length of a fixed tick, in seconds.
+pub const DEFAULT_TIMESTEP: f32 = 1.0 / 60.0;
+
+/// Fixed-timestep accumulator: real elapsed time in, whole ticks out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FixedTimestep {
+    /// Length of one tick in seconds; passed to dynamic systems as `dt`.
+    pub step: f32,
+    /// Elapsed time not yet consumed by a tick.
+    pub accumulator: f32,
+    /// Most ticks a single [`World::advance`] call runs. Time beyond that is
+    /// dropped, so a long stall doesn't snowball into ever longer catch-up frames.
+    pub max_ticks_per_advance: u32,
+}
+
+impl FixedTimestep {
+    /// Create an accumulator with the given step.
+    pub fn new(step: f32) -> Self {
+        Self {
+            step,
+            accumulator: 0.0,
+            max_ticks_per_advance: 8,
+        }
+    }
+
+    /// Add elapsed time and return the number of ticks it pays for.
+    pub fn accumulate(&mut self, elapsed: f32) -> u32 {
+        if self.step <= 0.0 {
+            return 0;
+        }
+        self.accumulator += elapsed.max(0.0);
+        let mut ticks = 0;
+        while self.accumulator >= self.step && ticks < self.max_ticks_per_advance {
+            self.accumulator -= self.step;
+            ticks += 1;
+        }
+        if self.accumulator >= self.step {
+            self.accumulator %= self.step;
+        }
+        ticks
+    }
+
+    /// Fraction of a tick left in the accumulator, for interpolating rendering
+    /// between the last two ticks.
+    pub fn alpha(&self) -> f32 {
+        if self.step <= 0.0 {
+            0.0
+        } else {
+            self.accumulator / self.step
+        }
+    }
+}
+
+impl Default for FixedTimestep {
+    fn default() -> Self {
+        Self::new(DEFAULT_TIMESTEP)
+    }
+}
+
+/// Read-only view of the world handed to [`System::run_parallel`].
+///
+/// Component lookups only see the components the system declared in its
+/// [`SystemAccess`]; anything else reads as absent.
+pub struct SystemView<'w> {
+    pub(crate) access: &'w SystemAccess,
+    /// Declared components that are allowed in the current mode.
+    pub(crate) allowed: HashSet<String>,
+    pub(crate) entities: &'w [u32],
+    pub(crate) components: &'w HashMap<String, HashMap<u32, JsonValue>>,
+    pub(crate) typed_components: &'w TypedComponentStorage,
+    pub(crate) entity_allocator: &'w EntityAllocator,
+    pub(crate) change_tracker: &'w ChangeTracker,
+    pub(crate) since: Option<u64>,
+    pub(crate) map: Option<&'w Map>,
+    pub(crate) fov_algorithm: &'w dyn FovAlgorithm,
+    pub(crate) turn: u32,
+    pub(crate) time_of_day: TimeOfDay,
+}
+
+impl<'w> SystemView<'w> {
+    /// The access the view was built for.
+    pub fn access(&self) -> &SystemAccess {
+        self.access
+    }
+
+    /// Get a declared component of an entity, with the same mode and stale-handle
+    /// checks as [`World::get_component`].
+    pub fn get_component(&self, entity: u32, name: &str) -> Option<&'w JsonValue> {
+        if !self.allowed.contains(name) || self.entity_allocator.is_stale(entity) {
+            return None;
+        }
+        if let Some(column) = self.typed_components.column(name) {
+            return column.get_json(entity);
+        }
+        self.components.get(name)?.get(&entity)
+    }
+
+    /// Live entities holding a declared component allowed in the current mode, in
+    /// ascending ID order.
+    pub fn get_entities_with_component(&self, name: &str) -> Vec<u32> {
+        if !self.allowed.contains(name) {
+            return Vec::new();
+        }
+        let mut ids: Vec<u32> = match self.typed_components.column(name) {
+            Some(column) => column.entity_ids(),
+            None => self
+                .components
+                .get(name)
+                .map(|map| map.keys().copied().collect())
+                .unwrap_or_default(),
+        };
+        ids.retain(|&id| !self.entity_allocator.is_stale(id));
+        ids.sort_unstable();
+        ids
+    }
+
+    /// The world map, if any.
+    pub fn map(&self) -> Option<&'w Map> {
+        self.map
+    }
+
+    /// The active FOV algorithm. Systems using it declare
+    /// [`SystemAccess::FOV_ALGORITHM`] in their access.
+    pub fn fov_algorithm(&self) -> &'w dyn FovAlgorithm {
+        self.fov_algorithm
+    }
+
+    /// Current turn.
+    pub fn turn(&self) -> u32 {
+        self.turn
+    }
+
+    /// Current time of day.
+    pub fn time_of_day(&self) -> TimeOfDay {
+        self.time_of_day
+    }
+
+    /// Change tick the system last ran at, see [`World::system_last_run`].
+    pub fn system_last_run(&self) -> Option<u64> {
+        self.since
+    }
+
+    /// Change ticks of a declared component, see [`World::component_ticks`].
+    pub fn component_ticks(&self, entity: u32, name: &str) -> Option<ComponentTicks> {
+        if !self.access.can_access(name) {
+            return None;
+        }
+        self.change_tracker.ticks(entity, name)
+    }
+
+    /// See [`World::component_changed_since`].
+    pub fn component_changed_since(&self, entity: u32, name: &str, since: u64) -> bool {
+        self.access.can_access(name)
+            && (self
+                .change_tracker
+                .ticks(entity, name)
+                .is_some_and(|ticks| ticks.changed > since)
+                || self
+                    .change_tracker
+                    .removed_at(entity, name)
+                    .is_some_and(|tick| tick > since))
+    }
+}
+
+impl QuerySource for SystemView<'_> {
+    fn query_candidates(&self, with: &[String]) -> Vec<u32> {
+        let Some((first, rest)) = with.split_first() else {
+            let mut all = self.entities.to_vec();
+            all.sort_unstable();
+            all.dedup();
+            return all;
+        };
+        self.get_entities_with_component(first)
+            .into_iter()
+            .filter(|&entity| {
+                rest.iter()
+                    .all(|name| self.get_component(entity, name).is_some())
+            })
+            .collect()
+    }
+
+    fn query_component(&self, entity: u32, name: &str) -> Option<&JsonValue> {
+        self.get_component(entity, name)
+    }
+
+    fn query_change_tick(&self, entity: u32, name: &str, added_only: bool) -> Option<u64> {
+        self.component_ticks(entity, name).map(|ticks| {
+            if added_only {
+                ticks.added
+            } else {
+                ticks.changed
+            }
+        })
+    }
+
+    fn query_default_since(&self) -> Option<u64> {
+        self.since
+    }
+}
+
+/// Group systems (given in execution order with their declared access) into
+/// batches that can run in parallel.
+///
+/// A batch is a run of consecutive systems with declared access and no pairwise
+/// conflicts; systems without declared access get a batch of their own.
+pub fn plan_batches(systems: &[(String, Option<SystemAccess>)]) -> Vec<Vec<String>> {
+    let mut batches: Vec<Vec<String>> = Vec::new();
+    let mut current: Vec<&(String, Option<SystemAccess>)> = Vec::new();
+    for entry in systems {
+        let joins = entry.1.as_ref().is_some_and(|access| {
+            current
+                .iter()
+                .all(|(_, other)| other.as_ref().is_some_and(|o| !o.conflicts_with(access)))
+        });
+        if !joins && !current.is_empty() {
+            batches.push(current.drain(..).map(|(name, _)| name.clone()).collect());
+        }
+        current.push(entry);
+    }
+    if !current.is_empty() {
+        batches.push(current.into_iter().map(|(name, _)| name.clone()).collect());
+    }
+    batches
+}
+
+/// Run a system serially through its parallel phase: build its view, compute
+/// the commands and apply them. Systems implementing [`System::run_parallel`] can
+/// use this as their [`System::run`].
+pub fn run_deferred<S: System + ?Sized>(system: &mut S, world: &mut World) {
+    let access = system.access().unwrap_or_default();
+    let commands = system.run_parallel(&world.system_view(system.name(), &access));
+    if let Some(apply) = commands {
+        apply(world);
+    }
+}
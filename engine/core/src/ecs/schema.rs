@@ -212,10 +212,8 @@ pub fn load_schemas_from_dir_with_modes<P: AsRef<Path>>(
     Ok(map)
 }
 
-/// Loads the allowed modes from the game configuration file (e.g., game.toml).
+/// Loads the allowed modes from the game configuration file (see
+/// [`GameConfig::load_default`](crate::config::GameConfig::load_default)).
 pub fn load_allowed_modes() -> anyhow::Result<Vec<String>> {
-    use crate::config::GameConfig;
-    let config_path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../../game.toml");
-    let config = GameConfig::load_from_file(config_path)?;
-    Ok(config.allowed_modes)
+    Ok(crate::config::GameConfig::load_default()?.allowed_modes)
 }
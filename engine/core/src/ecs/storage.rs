@@ -0,0 +1,352 @@
+//! Typed columnar component storage.
+//!
+//! Components backed by a Rust type (see [`Component`](crate::ecs::Component)) can opt
+//! into dense sparse-set columns so native systems can iterate `&T` / `&mut T` without
+//! cloning or re-parsing JSON. The JSON component API on
+//! [`World`](crate::ecs::world::World) stays available as a lazily materialized view
+//! over each column.
+
+use crate::ecs::Entity;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value as JsonValue;
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Marker for an unused slot in the sparse index.
+const EMPTY: usize = usize::MAX;
+
+/// Sparse-set column holding every instance of one component type.
+///
+/// Values are packed densely (in insertion order, with swap-remove on deletion),
+/// and looked up through a sparse index keyed by the entity's slot index, so the
+/// index stays as small as the number of slots whatever the generations. Lookups
+/// compare the full entity ID, so stale handles to a reused slot find nothing.
+/// Each value carries a cached JSON view that is built on first access and
+/// invalidated on mutation.
+pub struct SparseSet<T> {
+    sparse: Vec<usize>,
+    entities: Vec<u32>,
+    dense: Vec<T>,
+    json: Vec<OnceLock<JsonValue>>,
+}
+
+impl<T> Default for SparseSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SparseSet<T> {
+    /// Create a new, empty column.
+    pub fn new() -> Self {
+        Self {
+            sparse: Vec::new(),
+            entities: Vec::new(),
+            dense: Vec::new(),
+            json: Vec::new(),
+        }
+    }
+
+    /// Number of entities holding this component.
+    pub fn len(&self) -> usize {
+        self.dense.len()
+    }
+
+    /// Returns true if no entity holds this component.
+    pub fn is_empty(&self) -> bool {
+        self.dense.is_empty()
+    }
+
+    fn slot(entity: u32) -> usize {
+        Entity::from_id(entity).index as usize
+    }
+
+    fn index_of(&self, entity: u32) -> Option<usize> {
+        match self.sparse.get(Self::slot(entity)) {
+            Some(&idx) if idx != EMPTY && self.entities[idx] == entity => Some(idx),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the entity holds this component.
+    pub fn contains(&self, entity: u32) -> bool {
+        self.index_of(entity).is_some()
+    }
+
+    /// Get the component value for an entity.
+    pub fn get(&self, entity: u32) -> Option<&T> {
+        self.index_of(entity).map(|idx| &self.dense[idx])
+    }
+
+    /// Get the component value for an entity mutably. Invalidates its JSON view.
+    pub fn get_mut(&mut self, entity: u32) -> Option<&mut T> {
+        let idx = self.index_of(entity)?;
+        self.json[idx].take();
+        Some(&mut self.dense[idx])
+    }
+
+    /// Insert or replace the component value for an entity, returning the old value.
+    pub fn insert(&mut self, entity: u32, value: T) -> Option<T> {
+        if let Some(idx) = self.index_of(entity) {
+            self.json[idx].take();
+            return Some(std::mem::replace(&mut self.dense[idx], value));
+        }
+        let slot = Self::slot(entity);
+        if slot >= self.sparse.len() {
+            self.sparse.resize(slot + 1, EMPTY);
+        }
+        // A value left behind by an earlier generation of the slot is dropped.
+        if self.sparse[slot] != EMPTY {
+            self.remove(self.entities[self.sparse[slot]]);
+        }
+        self.sparse[slot] = self.dense.len();
+        self.entities.push(entity);
+        self.dense.push(value);
+        self.json.push(OnceLock::new());
+        None
+    }
+
+    /// Remove the component from an entity, returning its value.
+    pub fn remove(&mut self, entity: u32) -> Option<T> {
+        let idx = self.index_of(entity)?;
+        self.sparse[Self::slot(entity)] = EMPTY;
+        let last = self.dense.len() - 1;
+        if idx != last {
+            let moved = self.entities[last];
+            self.sparse[Self::slot(moved)] = idx;
+        }
+        self.entities.swap_remove(idx);
+        self.json.swap_remove(idx);
+        Some(self.dense.swap_remove(idx))
+    }
+
+    /// Entity IDs holding this component, in dense order.
+    pub fn entities(&self) -> &[u32] {
+        &self.entities
+    }
+
+    /// Iterate over `(entity, &value)` pairs in dense order.
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &T)> {
+        self.entities.iter().copied().zip(self.dense.iter())
+    }
+
+    /// Iterate over `(entity, &mut value)` pairs in dense order.
+    /// Invalidates the JSON view of every value in the column.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (u32, &mut T)> {
+        for cached in &mut self.json {
+            cached.take();
+        }
+        self.entities.iter().copied().zip(self.dense.iter_mut())
+    }
+
+    /// Remove every value from the column.
+    pub fn clear(&mut self) {
+        self.sparse.clear();
+        self.entities.clear();
+        self.dense.clear();
+        self.json.clear();
+    }
+}
+
+/// Type-erased interface over a [`SparseSet`] column, used by the JSON component API.
+pub trait ComponentColumn: Send + Sync {
+    /// Returns true if the entity holds this component.
+    fn contains(&self, entity: u32) -> bool;
+    /// Entity IDs holding this component.
+    fn entity_ids(&self) -> Vec<u32>;
+    /// Number of entities holding this component.
+    fn count(&self) -> usize;
+    /// JSON view of the component value for an entity.
+    fn get_json(&self, entity: u32) -> Option<&JsonValue>;
+    /// Deserialize a JSON value into the column's type and store it for an entity.
+    fn set_json(&mut self, entity: u32, value: JsonValue) -> Result<(), String>;
+    /// Remove the component from an entity, returning its JSON view.
+    fn remove_json(&mut self, entity: u32) -> Option<JsonValue>;
+    /// Materialize the whole column as an entity → JSON map.
+    fn to_json_map(&self) -> HashMap<u32, JsonValue>;
+    /// Remove every value from the column.
+    fn clear_all(&mut self);
+    /// A new, empty column of the same type.
+    fn new_empty(&self) -> Box<dyn ComponentColumn>;
+    /// Downcast helper.
+    fn as_any(&self) -> &dyn Any;
+    /// Mutable downcast helper.
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T> ComponentColumn for SparseSet<T>
+where
+    T: Serialize + DeserializeOwned + Send + Sync + 'static,
+{
+    fn contains(&self, entity: u32) -> bool {
+        SparseSet::contains(self, entity)
+    }
+
+    fn entity_ids(&self) -> Vec<u32> {
+        self.entities.clone()
+    }
+
+    fn count(&self) -> usize {
+        self.len()
+    }
+
+    fn get_json(&self, entity: u32) -> Option<&JsonValue> {
+        let idx = self.index_of(entity)?;
+        Some(
+            self.json[idx]
+                .get_or_init(|| serde_json::to_value(&self.dense[idx]).unwrap_or(JsonValue::Null)),
+        )
+    }
+
+    fn set_json(&mut self, entity: u32, value: JsonValue) -> Result<(), String> {
+        // Deserialize from a borrowed value so types with borrowed keys (as generated
+        // by `#[component]`) work too.
+        let typed = T::deserialize(&value)
+            .map_err(|e| format!("Typed component deserialization failed: {e}"))?;
+        self.insert(entity, typed);
+        Ok(())
+    }
+
+    fn remove_json(&mut self, entity: u32) -> Option<JsonValue> {
+        let old = self.get_json(entity).cloned()?;
+        self.remove(entity);
+        Some(old)
+    }
+
+    fn to_json_map(&self) -> HashMap<u32, JsonValue> {
+        self.entities
+            .iter()
+            .filter_map(|&eid| self.get_json(eid).map(|v| (eid, v.clone())))
+            .collect()
+    }
+
+    fn clear_all(&mut self) {
+        self.clear();
+    }
+
+    fn new_empty(&self) -> Box<dyn ComponentColumn> {
+        Box::new(SparseSet::<T>::new())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Registry of typed component columns, keyed by component name.
+#[derive(Default)]
+pub struct TypedComponentStorage {
+    columns: HashMap<String, Box<dyn ComponentColumn>>,
+}
+
+impl TypedComponentStorage {
+    /// Create a new, empty storage.
+    pub fn new() -> Self {
+        Self {
+            columns: HashMap::new(),
+        }
+    }
+
+    /// Create a column of type `T` for the named component.
+    /// Returns false if the component already has a column.
+    pub fn register<T>(&mut self, name: &str) -> bool
+    where
+        T: Serialize + DeserializeOwned + Send + Sync + 'static,
+    {
+        if self.columns.contains_key(name) {
+            return false;
+        }
+        self.columns
+            .insert(name.to_string(), Box::new(SparseSet::<T>::new()));
+        true
+    }
+
+    /// Add an existing column for the named component.
+    /// Returns false if the component already has a column.
+    pub fn insert_column(&mut self, name: &str, column: Box<dyn ComponentColumn>) -> bool {
+        if self.columns.contains_key(name) {
+            return false;
+        }
+        self.columns.insert(name.to_string(), column);
+        true
+    }
+
+    /// A storage with the same columns, all empty.
+    pub fn empty_like(&self) -> Self {
+        Self {
+            columns: self
+                .columns
+                .iter()
+                .map(|(name, column)| (name.clone(), column.new_empty()))
+                .collect(),
+        }
+    }
+
+    /// Remove the column for the named component, returning it.
+    pub fn unregister(&mut self, name: &str) -> Option<Box<dyn ComponentColumn>> {
+        self.columns.remove(name)
+    }
+
+    /// Returns true if the named component is backed by a typed column.
+    pub fn is_typed(&self, name: &str) -> bool {
+        self.columns.contains_key(name)
+    }
+
+    /// Names of all components backed by typed columns.
+    pub fn names(&self) -> Vec<String> {
+        self.columns.keys().cloned().collect()
+    }
+
+    /// Get the type-erased column for a component.
+    pub fn column(&self, name: &str) -> Option<&dyn ComponentColumn> {
+        self.columns.get(name).map(|c| c.as_ref())
+    }
+
+    /// Get the type-erased column for a component mutably.
+    pub fn column_mut(&mut self, name: &str) -> Option<&mut (dyn ComponentColumn + 'static)> {
+        self.columns.get_mut(name).map(|c| c.as_mut())
+    }
+
+    /// Get the typed column for a component. Returns None on a type mismatch.
+    pub fn get<T: 'static>(&self, name: &str) -> Option<&SparseSet<T>> {
+        self.columns
+            .get(name)?
+            .as_any()
+            .downcast_ref::<SparseSet<T>>()
+    }
+
+    /// Get the typed column for a component mutably. Returns None on a type mismatch.
+    pub fn get_mut<T: 'static>(&mut self, name: &str) -> Option<&mut SparseSet<T>> {
+        self.columns
+            .get_mut(name)?
+            .as_any_mut()
+            .downcast_mut::<SparseSet<T>>()
+    }
+
+    /// Remove an entity from every column.
+    pub fn remove_entity(&mut self, entity: u32) {
+        for column in self.columns.values_mut() {
+            column.remove_json(entity);
+        }
+    }
+
+    /// Returns true if any column holds a value for the entity.
+    pub fn has_entity(&self, entity: u32) -> bool {
+        self.columns.values().any(|c| c.contains(entity))
+    }
+
+    /// Clear the data of every column whose name satisfies the predicate.
+    pub fn clear_where<F: Fn(&str) -> bool>(&mut self, predicate: F) {
+        for (name, column) in self.columns.iter_mut() {
+            if predicate(name) {
+                column.clear_all();
+            }
+        }
+    }
+}
@@ -1,6 +1,8 @@
+use crate::ecs::schedule::{SystemStage, SystemView};
 use crate::ecs::world::World;
 use indexmap::IndexMap;
 use std::cell::RefCell;
+use std::collections::BTreeSet;
 use topo_sort::{SortResults, TopoSort};
 
 /// A trait for systems
@@ -13,6 +15,113 @@ pub trait System: Send + Sync {
     fn dependencies(&self) -> &'static [&'static str] {
         &[]
     }
+    /// Stage the system runs in, unless overridden with
+    /// [`World::set_system_stage`].
+    fn stage(&self) -> SystemStage {
+        SystemStage::Update
+    }
+    /// Components the system reads and writes.
+    ///
+    /// `None` (the default) means the system may touch any world state and always
+    /// runs alone. Systems declaring their access can share a tick batch with other
+    /// non-conflicting systems, see [`crate::ecs::schedule`].
+    fn access(&self) -> Option<SystemAccess> {
+        None
+    }
+    /// Read phase of a parallel run.
+    ///
+    /// Computes the system's effects from a read-only view of the components it
+    /// declared and returns the commands that apply them. The scheduler calls this
+    /// on a worker thread and applies the commands in execution order. Returning
+    /// `None` (the default) makes the scheduler call [`System::run`] instead.
+    fn run_parallel(&mut self, _view: &SystemView<'_>) -> Option<SystemCommands> {
+        None
+    }
+}
+
+/// Deferred world update returned by [`System::run_parallel`].
+pub type SystemCommands = Box<dyn FnOnce(&mut World) + Send>;
+
+/// Component names and world resources a system reads and writes.
+///
+/// Resources are world state outside the components, named by the constants
+/// on this type (e.g. [`SystemAccess::FOV_ALGORITHM`]).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SystemAccess {
+    /// Components the system only reads.
+    pub reads: BTreeSet<String>,
+    /// Components the system writes (and may read).
+    pub writes: BTreeSet<String>,
+    /// Resources the system only reads.
+    pub resource_reads: BTreeSet<String>,
+    /// Resources the system writes (and may read).
+    pub resource_writes: BTreeSet<String>,
+}
+
+impl SystemAccess {
+    /// The world's FOV algorithm (`World::set_fov_algorithm`).
+    pub const FOV_ALGORITHM: &'static str = "fov_algorithm";
+    /// The cells visible to each entity (`World::set_visible_cells`).
+    pub const VISIBLE_CELLS: &'static str = "visible_cells";
+
+    /// Create an empty access set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declare components the system reads.
+    pub fn read(mut self, components: &[&str]) -> Self {
+        self.reads
+            .extend(components.iter().map(|name| name.to_string()));
+        self
+    }
+
+    /// Declare components the system writes.
+    pub fn write(mut self, components: &[&str]) -> Self {
+        self.writes
+            .extend(components.iter().map(|name| name.to_string()));
+        self
+    }
+
+    /// Declare resources the system reads.
+    pub fn read_resources(mut self, resources: &[&str]) -> Self {
+        self.resource_reads
+            .extend(resources.iter().map(|name| name.to_string()));
+        self
+    }
+
+    /// Declare resources the system writes.
+    pub fn write_resources(mut self, resources: &[&str]) -> Self {
+        self.resource_writes
+            .extend(resources.iter().map(|name| name.to_string()));
+        self
+    }
+
+    /// Returns true if the system may read or write the component.
+    pub fn can_access(&self, component: &str) -> bool {
+        self.reads.contains(component) || self.writes.contains(component)
+    }
+
+    /// Returns true if the system may read or write the resource.
+    pub fn can_access_resource(&self, resource: &str) -> bool {
+        self.resource_reads.contains(resource) || self.resource_writes.contains(resource)
+    }
+
+    /// Returns true if running both systems in the same batch could change the
+    /// outcome, i.e. one of them writes a component or resource the other reads
+    /// or writes.
+    pub fn conflicts_with(&self, other: &SystemAccess) -> bool {
+        self.writes.iter().any(|name| other.can_access(name))
+            || other.writes.iter().any(|name| self.can_access(name))
+            || self
+                .resource_writes
+                .iter()
+                .any(|name| other.can_access_resource(name))
+            || other
+                .resource_writes
+                .iter()
+                .any(|name| self.can_access_resource(name))
+    }
 }
 
 /// A registry of systems
@@ -0,0 +1,199 @@
+//! Per-component change ticks.
+//!
+//! The world keeps a change tick that advances with every component write. Each
+//! write stamps the component with the new tick (`added` when the component was new,
+//! `changed` whenever the value differs from the previous one), and every system run
+//! remembers the tick it ended at, so a system can find the components other code
+//! modified since it last ran, see [`Query::changed`].
+//!
+//! Change ticks are runtime state: they are not saved, and a system that has not run
+//! yet (or ran before the world was restored from a snapshot) sees every component
+//! as changed.
+//!
+//! [`Query::changed`]: crate::ecs::query::Query::changed
+
+use super::World;
+use std::collections::HashMap;
+
+/// Change ticks of one component value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComponentTicks {
+    /// Tick the component was added at.
+    pub added: u64,
+    /// Tick the value last changed at (equal to `added` for new components).
+    pub changed: u64,
+}
+
+/// Change ticks of all components plus the last-run tick of every system.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ChangeTracker {
+    tick: u64,
+    components: HashMap<String, HashMap<u32, ComponentTicks>>,
+    removed: HashMap<String, HashMap<u32, u64>>,
+    last_run: HashMap<String, u64>,
+    running: Option<RunningSystem>,
+}
+
+#[derive(Debug, Clone)]
+struct RunningSystem {
+    name: String,
+    since: Option<u64>,
+}
+
+impl ChangeTracker {
+    pub(crate) fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub(crate) fn ticks(&self, entity: u32, name: &str) -> Option<ComponentTicks> {
+        self.components.get(name)?.get(&entity).copied()
+    }
+
+    pub(crate) fn removed_at(&self, entity: u32, name: &str) -> Option<u64> {
+        self.removed.get(name)?.get(&entity).copied()
+    }
+
+    /// Advance the tick for a change to world data other than components.
+    pub(crate) fn advance(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    pub(crate) fn mark_changed(&mut self, entity: u32, name: &str, added: bool) {
+        self.tick += 1;
+        let tick = self.tick;
+        let entry = self
+            .components
+            .entry(name.to_string())
+            .or_default()
+            .entry(entity)
+            .or_insert(ComponentTicks {
+                added: tick,
+                changed: tick,
+            });
+        if added {
+            entry.added = tick;
+        }
+        entry.changed = tick;
+    }
+
+    pub(crate) fn mark_removed(&mut self, entity: u32, name: &str) {
+        self.tick += 1;
+        if let Some(ticks) = self.components.get_mut(name) {
+            ticks.remove(&entity);
+        }
+        self.removed
+            .entry(name.to_string())
+            .or_default()
+            .insert(entity, self.tick);
+    }
+
+    pub(crate) fn forget_entity(&mut self, entity: u32) {
+        for ticks in self.components.values_mut() {
+            ticks.remove(&entity);
+        }
+        for ticks in self.removed.values_mut() {
+            ticks.remove(&entity);
+        }
+    }
+
+    /// Tick the running system last ran at, or `None` outside systems and on a
+    /// system's first run.
+    pub(crate) fn since(&self) -> Option<u64> {
+        self.running.as_ref().and_then(|running| running.since)
+    }
+
+    /// Tick a system last ran at.
+    pub(crate) fn last_run(&self, name: &str) -> Option<u64> {
+        self.last_run.get(name).copied()
+    }
+
+    pub(crate) fn begin_system(&mut self, name: &str) {
+        self.running = Some(RunningSystem {
+            name: name.to_string(),
+            since: self.last_run(name),
+        });
+    }
+
+    /// Remember the current tick as the system's last run, so its own writes are
+    /// not reported to it as changes on the next run.
+    pub(crate) fn end_system(&mut self) {
+        if let Some(running) = self.running.take() {
+            self.last_run.insert(running.name, self.tick);
+        }
+    }
+
+    pub(crate) fn reset_systems(&mut self) {
+        self.last_run.clear();
+    }
+
+    /// Forget all component ticks and system runs, keeping the current tick.
+    pub(crate) fn clear(&mut self) {
+        self.components.clear();
+        self.removed.clear();
+        self.last_run.clear();
+    }
+}
+
+impl World {
+    /// Current change tick (the tick of the latest component write). Pass it to
+    /// [`Query::since`] later to find the components changed in between.
+    ///
+    /// [`Query::since`]: crate::ecs::query::Query::since
+    pub fn change_tick(&self) -> u64 {
+        self.change_tracker.tick()
+    }
+
+    /// Advance the change tick for a change to world data other than components,
+    /// returning the new tick.
+    pub(crate) fn advance_change_tick(&mut self) -> u64 {
+        self.change_tracker.advance()
+    }
+
+    /// Record a change to a component value written in place, e.g. directly in
+    /// [`World::components`].
+    pub fn mark_component_changed(&mut self, entity: u32, name: &str) {
+        self.change_tracker.mark_changed(entity, name, false);
+    }
+
+    /// Change ticks of a component value, if it exists and was written through
+    /// the component API.
+    pub fn component_ticks(&self, entity: u32, name: &str) -> Option<ComponentTicks> {
+        self.change_tracker.ticks(entity, name)
+    }
+
+    /// Returns true if the component was added, changed or removed after `since`.
+    pub fn component_changed_since(&self, entity: u32, name: &str, since: u64) -> bool {
+        self.change_tracker
+            .ticks(entity, name)
+            .is_some_and(|ticks| ticks.changed > since)
+            || self
+                .change_tracker
+                .removed_at(entity, name)
+                .is_some_and(|tick| tick > since)
+    }
+
+    /// Change tick the running system last ran at.
+    ///
+    /// `None` outside of systems and on a system's first run, in which case every
+    /// component should be treated as changed.
+    pub fn system_last_run(&self) -> Option<u64> {
+        self.change_tracker.since()
+    }
+
+    /// Forget when systems last ran, so every system sees all components as
+    /// changed on its next run.
+    pub fn reset_change_detection(&mut self) {
+        self.change_tracker.reset_systems();
+    }
+
+    /// Mark the start of a system run for change detection.
+    pub(super) fn begin_system_run(&mut self, name: &str) {
+        self.change_tracker.begin_system(name);
+    }
+
+    /// Mark the end of the running system.
+    pub(super) fn end_system_run(&mut self) {
+        self.change_tracker.end_system();
+    }
+}
@@ -1,7 +1,12 @@
-use super::World;
+use super::{ReplayCommand, World};
 use crate::ecs::error::RegistryError;
+use crate::ecs::registry::Component;
 use crate::ecs::schema::ComponentSchema;
+use crate::ecs::storage::SparseSet;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
 use serde_json::{Map, Value as JsonValue, json};
+use std::collections::{HashMap, HashSet};
 
 /// Recursively enforces required fields and default values from the schema on the given value.
 /// - Inserts default values for all fields if missing.
@@ -90,11 +95,26 @@ impl World {
     /// Sets a component value for an entity, validating against its schema if present,
     /// and emits a component_changed event.
     pub fn set_component(
+        &mut self,
+        entity: u32,
+        name: &str,
+        value: JsonValue,
+    ) -> Result<(), String> {
+        let command = self.external_command(|| ReplayCommand::SetComponent {
+            entity,
+            component: name.to_string(),
+            value: value.clone(),
+        });
+        self.recorded(command, |world| world.write_component(entity, name, value))
+    }
+
+    fn write_component(
         &mut self,
         entity: u32,
         name: &str,
         mut value: JsonValue,
     ) -> Result<(), String> {
+        self.check_entity_handle(entity)?;
         if !self.is_component_allowed_in_mode(name, &self.current_mode) {
             return Err(format!(
                 "Component {} not allowed in mode {}",
@@ -120,17 +140,26 @@ impl World {
             }
         }
 
+        self.adopt_typed_column(name);
         // Save old value for event emission
-        let old = self
-            .components
-            .get(name)
-            .and_then(|m| m.get(&entity))
-            .cloned();
+        let old = if let Some(column) = self.typed_components.column_mut(name) {
+            let old = column.get_json(entity).cloned();
+            column.set_json(entity, value.clone())?;
+            old
+        } else {
+            self.components
+                .entry(name.to_string())
+                .or_default()
+                .insert(entity, value.clone())
+        };
 
-        self.components
-            .entry(name.to_string())
-            .or_default()
-            .insert(entity, value.clone());
+        if old.as_ref() != Some(&value) {
+            self.change_tracker
+                .mark_changed(entity, name, old.is_none());
+        }
+        if name == "Position" {
+            self.index_position(entity, &value);
+        }
 
         // Emit component_changed event
         self.send_event(
@@ -148,27 +177,72 @@ impl World {
         Ok(())
     }
 
+    /// Give a component registered with a Rust type typed storage on its first write.
+    ///
+    /// Components that already hold JSON values keep JSON storage; move them with
+    /// [`World::register_typed_component`].
+    fn adopt_typed_column(&mut self, name: &str) {
+        if self.typed_components.is_typed(name) || self.components.contains_key(name) {
+            return;
+        }
+        if let Some(column) = self.registry.lock().unwrap().new_typed_column(name) {
+            self.typed_components.insert_column(name, column);
+        }
+    }
+
     /// Gets a reference to a component value for an entity.
     pub fn get_component(&self, entity: u32, name: &str) -> Option<&JsonValue> {
+        if self.is_stale_entity(entity) {
+            return None;
+        }
         if !self.is_component_allowed_in_mode(name, &self.current_mode) {
             return None;
         }
+        if let Some(column) = self.typed_components.column(name) {
+            return column.get_json(entity);
+        }
         self.components.get(name)?.get(&entity)
     }
 
+    /// Rejects stale entity handles.
+    fn check_entity_handle(&self, entity: u32) -> Result<(), String> {
+        if self.is_stale_entity(entity) {
+            return Err(format!(
+                "Entity {entity} is stale (despawned or its ID was reused)"
+            ));
+        }
+        Ok(())
+    }
+
     /// Removes a component from an entity, enforcing mode restrictions and emitting a component_changed event.
     pub fn remove_component(&mut self, entity: u32, name: &str) -> Result<(), String> {
+        let command = self.external_command(|| ReplayCommand::RemoveComponent {
+            entity,
+            component: name.to_string(),
+        });
+        self.recorded(command, |world| world.erase_component(entity, name))
+    }
+
+    fn erase_component(&mut self, entity: u32, name: &str) -> Result<(), String> {
+        self.check_entity_handle(entity)?;
         if !self.is_component_allowed_in_mode(name, &self.current_mode) {
             return Err(format!(
                 "Component {} not allowed in mode {}",
                 name, self.current_mode
             ));
         }
-        let old = self
-            .components
-            .get_mut(name)
-            .and_then(|m| m.remove(&entity));
+        let old = match self.typed_components.column_mut(name) {
+            Some(column) => column.remove_json(entity),
+            None => self
+                .components
+                .get_mut(name)
+                .and_then(|m| m.remove(&entity)),
+        };
         if old.is_some() {
+            self.change_tracker.mark_removed(entity, name);
+            if name == "Position" {
+                self.spatial_index.remove(entity);
+            }
             // Emit component_changed event
             self.send_event(
                 "component_changed",
@@ -203,6 +277,10 @@ impl World {
             .unwrap()
             .unregister_external_schema(name);
         self.components.remove(name);
+        self.typed_components.unregister(name);
+        if name == "Position" {
+            self.spatial_index.clear();
+        }
     }
 
     /// Unregister a dynamic system by name.
@@ -216,19 +294,46 @@ impl World {
     }
 
     /// Hot-reload a component schema and migrate component data.
+    ///
+    /// `version` numbers the migration within the schema's own migrations and must
+    /// stay the same across runs, so saves are migrated exactly once.
     pub fn hotreload_schema_with_migration<F>(
         &mut self,
         schema: ComponentSchema,
+        version: u32,
         migrate: F,
     ) -> Result<(), RegistryError>
     where
-        F: Fn(&serde_json::Value) -> serde_json::Value,
+        F: Fn(&serde_json::Value) -> serde_json::Value + Send + Sync + 'static,
     {
-        if let Some(data) = self.components.get_mut(&schema.name) {
+        if let Some(column) = self.typed_components.column(&schema.name) {
+            // Migrate a JSON view of the column, then rebuild it. Data that no longer
+            // fits the Rust type falls back to JSON storage.
+            let name = schema.name.clone();
+            let mut data = column.to_json_map();
             self.registry
                 .lock()
                 .unwrap()
-                .update_external_schema_with_migration(schema, data, migrate)
+                .update_external_schema_with_migration(schema, version, &mut data, migrate)?;
+            let column = self.typed_components.column_mut(&name).unwrap();
+            column.clear_all();
+            let fits = data
+                .iter()
+                .all(|(&eid, value)| column.set_json(eid, value.clone()).is_ok());
+            if !fits {
+                log::warn!(
+                    "Migrated data for component '{name}' no longer matches its Rust type; \
+                     falling back to JSON storage"
+                );
+                self.typed_components.unregister(&name);
+                self.components.insert(name, data);
+            }
+            Ok(())
+        } else if let Some(data) = self.components.get_mut(&schema.name) {
+            self.registry
+                .lock()
+                .unwrap()
+                .update_external_schema_with_migration(schema, version, data, migrate)
         } else {
             if let Err(e) = self.registry.lock().unwrap().update_external_schema(schema) {
                 // Handle or log the error as appropriate
@@ -237,4 +342,182 @@ impl World {
             Ok(())
         }
     }
+
+    /// Opt a component into typed columnar storage backed by the Rust type `T`.
+    ///
+    /// Existing JSON data for the component is moved into the column. The JSON API
+    /// (`set_component`, `get_component`, ...) keeps working as a view over the column,
+    /// while native systems can borrow values directly via [`World::typed_component`].
+    /// Components registered with
+    /// [`ComponentRegistry::register_component`](crate::ecs::ComponentRegistry::register_component)
+    /// get a column on their first write; this moves one that already holds JSON data.
+    /// Fails without changing anything if any existing value does not deserialize into `T`.
+    pub fn register_typed_component<T>(&mut self, name: &str) -> Result<(), String>
+    where
+        T: Component + Serialize + DeserializeOwned,
+    {
+        if self.typed_components.is_typed(name) {
+            return Err(format!("Component {name} already uses typed storage"));
+        }
+        let mut column = SparseSet::<T>::new();
+        if let Some(existing) = self.components.get(name) {
+            for (&eid, value) in existing {
+                let typed = T::deserialize(value).map_err(|e| {
+                    format!("Cannot move {name} of entity {eid} into typed storage: {e}")
+                })?;
+                column.insert(eid, typed);
+            }
+        }
+        self.components.remove(name);
+        self.typed_components.register::<T>(name);
+        *self.typed_components.get_mut::<T>(name).unwrap() = column;
+        Ok(())
+    }
+
+    /// Move a component back from typed storage to JSON storage.
+    pub fn unregister_typed_component(&mut self, name: &str) {
+        if let Some(column) = self.typed_components.unregister(name) {
+            let data = column.to_json_map();
+            if !data.is_empty() {
+                self.components.insert(name.to_string(), data);
+            }
+        }
+    }
+
+    /// Returns true if the component uses typed columnar storage.
+    pub fn is_typed_component(&self, name: &str) -> bool {
+        self.typed_components.is_typed(name)
+    }
+
+    /// Borrow the typed column for a component.
+    ///
+    /// Returns None if the component is not typed, `T` does not match its Rust type,
+    /// or the component is not allowed in the current mode.
+    pub fn typed_component<T: 'static>(&self, name: &str) -> Option<&SparseSet<T>> {
+        if !self.is_component_allowed_in_mode(name, &self.current_mode) {
+            return None;
+        }
+        self.typed_components.get::<T>(name)
+    }
+
+    /// Mutably borrow the typed column for a component.
+    ///
+    /// Writes through the column skip schema validation and do not emit
+    /// `component_changed` events. Change detection treats every value in the
+    /// column as changed; use [`World::typed_value_mut`] to edit a single one.
+    pub fn typed_component_mut<T: 'static>(&mut self, name: &str) -> Option<&mut SparseSet<T>> {
+        if !self.is_component_allowed_in_mode(name, &self.current_mode) {
+            return None;
+        }
+        let entities = self.typed_components.get::<T>(name)?.entities().to_vec();
+        for entity in entities {
+            self.change_tracker.mark_changed(entity, name, false);
+        }
+        self.typed_components.get_mut::<T>(name)
+    }
+
+    /// Mutably borrow an entity's value in the typed column for a component,
+    /// marking it changed.
+    ///
+    /// Returns None under the same conditions as [`World::typed_component`], or
+    /// if the entity handle is stale or has no value.
+    pub fn typed_value_mut<T: 'static>(&mut self, entity: u32, name: &str) -> Option<&mut T> {
+        if !self.is_component_allowed_in_mode(name, &self.current_mode)
+            || self.is_stale_entity(entity)
+            || !self.typed_components.get::<T>(name)?.contains(entity)
+        {
+            return None;
+        }
+        self.change_tracker.mark_changed(entity, name, false);
+        self.typed_components.get_mut::<T>(name)?.get_mut(entity)
+    }
+
+    /// Entity IDs holding a component, regardless of its storage.
+    pub(crate) fn component_entity_set(&self, name: &str) -> Option<HashSet<u32>> {
+        match self.typed_components.column(name) {
+            Some(column) => Some(column.entity_ids().into_iter().collect()),
+            None => self
+                .components
+                .get(name)
+                .map(|m| m.keys().copied().collect()),
+        }
+    }
+
+    /// All component data as JSON, merging typed columns into the JSON map.
+    pub fn components_as_json(&self) -> HashMap<String, HashMap<u32, JsonValue>> {
+        let mut all = self.components.clone();
+        for name in self.typed_components.names() {
+            if let Some(column) = self.typed_components.column(&name) {
+                all.insert(name, column.to_json_map());
+            }
+        }
+        all
+    }
+
+    /// Insert a component value, regardless of its storage.
+    ///
+    /// Skips mode checks, schema validation and events; meant for internal writes.
+    pub(crate) fn insert_component_raw(
+        &mut self,
+        entity: u32,
+        name: &str,
+        value: JsonValue,
+    ) -> Result<(), String> {
+        self.adopt_typed_column(name);
+        let old = match self.typed_components.column_mut(name) {
+            Some(column) => {
+                let old = column.get_json(entity).cloned();
+                column.set_json(entity, value)?;
+                old
+            }
+            None => self
+                .components
+                .entry(name.to_string())
+                .or_default()
+                .insert(entity, value),
+        };
+        self.change_tracker
+            .mark_changed(entity, name, old.is_none());
+        Ok(())
+    }
+
+    /// Apply an in-place edit to a component value, regardless of its storage.
+    ///
+    /// Skips mode checks, schema validation and events; meant for internal writes.
+    /// The value is only marked changed if the edit changed it and was stored.
+    pub(crate) fn modify_component_raw<F>(&mut self, entity: u32, name: &str, edit: F) -> bool
+    where
+        F: FnOnce(&mut JsonValue),
+    {
+        if let Some(column) = self.typed_components.column_mut(name) {
+            let Some(old) = column.get_json(entity) else {
+                return false;
+            };
+            let mut value = old.clone();
+            edit(&mut value);
+            if &value == old {
+                return true;
+            }
+            let ok = column.set_json(entity, value).is_ok();
+            if ok {
+                self.change_tracker.mark_changed(entity, name, false);
+            }
+            return ok;
+        }
+        match self
+            .components
+            .get_mut(name)
+            .and_then(|m| m.get_mut(&entity))
+        {
+            Some(value) => {
+                let old = value.clone();
+                edit(value);
+                if *value != old {
+                    self.change_tracker.mark_changed(entity, name, false);
+                }
+                true
+            }
+            None => false,
+        }
+    }
 }
@@ -1,21 +1,52 @@
-use super::World;
+use super::{ReplayCommand, World};
 use crate::ecs::components::position::{Position, PositionComponent};
+use crate::map::CellKey;
 
 impl World {
-    /// Spawn a new entity
+    /// Spawn a new entity.
+    ///
+    /// IDs of despawned entities are reused with a new generation, so old handles
+    /// to them are stale (see [`is_stale_entity`](Self::is_stale_entity)).
     pub fn spawn_entity(&mut self) -> u32 {
-        let id = self.next_id;
-        self.next_id += 1;
-        self.entities.push(id);
-        id
+        let command = self.external_command(|| ReplayCommand::SpawnEntity {
+            entity: self.entity_allocator.peek(),
+        });
+        self.recorded(command, |world| {
+            let id = world.entity_allocator.allocate();
+            world.entities.push(id);
+            id
+        })
     }
 
-    /// Despawn an entity
+    /// Despawn an entity. Despawning a stale handle does nothing.
+    ///
+    /// Children are despawned or reparented according to their
+    /// [`DespawnPolicy`](super::DespawnPolicy).
     pub fn despawn_entity(&mut self, entity: u32) {
-        for comps in self.components.values_mut() {
-            let _existed = comps.remove(&entity).is_some();
+        if self.entity_allocator.is_stale(entity) {
+            return;
         }
-        self.entities.retain(|&id| id != entity);
+        let command = self.external_command(|| ReplayCommand::DespawnEntity { entity });
+        self.recorded(command, |world| {
+            world.despawn_children(entity);
+            for comps in world.components.values_mut() {
+                let _existed = comps.remove(&entity).is_some();
+            }
+            world.typed_components.remove_entity(entity);
+            world.change_tracker.forget_entity(entity);
+            world.spatial_index.remove(entity);
+            world.entities.retain(|&id| id != entity);
+            world.entity_allocator.free(entity);
+        });
+    }
+
+    /// Returns true if the ID refers to a despawned entity, or to an older
+    /// generation of an entity slot that has since been reused.
+    ///
+    /// IDs that were never handed out by [`spawn_entity`](Self::spawn_entity) are
+    /// not stale.
+    pub fn is_stale_entity(&self, entity: u32) -> bool {
+        self.entity_allocator.is_stale(entity)
     }
 
     /// Checks if an entity exists
@@ -24,7 +55,8 @@ impl World {
         let in_any_component = self
             .components
             .values()
-            .any(|comp_map| comp_map.contains_key(&entity));
+            .any(|comp_map| comp_map.contains_key(&entity))
+            || self.typed_components.has_entity(entity);
         in_entities || in_any_component
     }
 
@@ -33,25 +65,34 @@ impl World {
         self.entities.clone()
     }
 
-    /// Get all entities with a given component
+    /// Get all entities with a given component, in ascending ID order
+    /// (so systems iterating them are deterministic).
     pub fn get_entities_with_component(&self, name: &str) -> Vec<u32> {
         if !self.is_component_allowed_in_mode(name, &self.current_mode) {
             return vec![];
         }
-        self.components
-            .get(name)
-            .map(|map| map.keys().cloned().collect())
-            .unwrap_or_default()
+        let mut ids: Vec<u32> = self
+            .component_entity_set(name)
+            .map(|set| set.into_iter().collect())
+            .unwrap_or_default();
+        ids.sort_unstable();
+        ids
     }
 
     /// Checks if an entity has a component
     pub fn has_component(&self, entity: u32, name: &str) -> bool {
+        if self.is_stale_entity(entity) {
+            return false;
+        }
+        if let Some(column) = self.typed_components.column(name) {
+            return column.contains(entity);
+        }
         self.components
             .get(name)
             .is_some_and(|m| m.contains_key(&entity))
     }
 
-    /// Get all entities with a given component
+    /// Get all entities with all of the given components, in ascending ID order
     pub fn get_entities_with_components(&self, names: &[&str]) -> Vec<u32> {
         if names.is_empty() {
             return self.entities.clone();
@@ -65,21 +106,26 @@ impl World {
         }
         let mut sets: Vec<std::collections::HashSet<u32>> = allowed_names
             .iter()
-            .filter_map(|&&name| self.components.get(name))
-            .map(|comps| comps.keys().cloned().collect())
+            .filter_map(|&&name| self.component_entity_set(name))
             .collect();
         if sets.is_empty() {
             return vec![];
         }
         let first = sets.pop().unwrap();
-        sets.into_iter()
+        let mut ids: Vec<u32> = sets
+            .into_iter()
             .fold(first, |acc, set| acc.intersection(&set).cloned().collect())
             .into_iter()
-            .collect()
+            .collect();
+        ids.sort_unstable();
+        ids
     }
 
     /// Move an entity
     pub fn move_entity(&mut self, entity: u32, dx: f32, dy: f32) {
+        if self.is_stale_entity(entity) {
+            return;
+        }
         if let Some(value) = self.get_component(entity, "Position").cloned()
             && let Ok(mut pos_comp) = serde_json::from_value::<PositionComponent>(value)
         {
@@ -98,15 +144,20 @@ impl World {
     /// for distribution by BodyPartDamageSystem. Otherwise falls back to direct
     /// Health subtraction for backward compatibility.
     pub fn damage_entity(&mut self, entity: u32, amount: f32) {
+        if self.is_stale_entity(entity) {
+            return;
+        }
         if self.has_component(entity, "Body") {
             self.append_pending_damage(entity, amount as f64, None);
-        } else if let Some(healths) = self.components.get_mut("Health")
-            && let Some(value) = healths.get_mut(&entity)
-            && let Some(obj) = value.as_object_mut()
-            && let Some(current) = obj.get_mut("current")
-            && let Some(cur_val) = current.as_f64()
-        {
-            *current = serde_json::json!((cur_val - amount as f64).max(0.0));
+        } else {
+            self.modify_component_raw(entity, "Health", |value| {
+                if let Some(obj) = value.as_object_mut()
+                    && let Some(current) = obj.get_mut("current")
+                    && let Some(cur_val) = current.as_f64()
+                {
+                    *current = serde_json::json!((cur_val - amount as f64).max(0.0));
+                }
+            });
         }
     }
 
@@ -115,7 +166,7 @@ impl World {
     /// Appends a PendingDamage entry with the specified `part_name`.
     /// If the entity has no Body component, this is a no-op.
     pub fn damage_entity_part(&mut self, entity: u32, part_name: &str, amount: f32) {
-        if self.has_component(entity, "Body") {
+        if !self.is_stale_entity(entity) && self.has_component(entity, "Body") {
             self.append_pending_damage(entity, amount as f64, Some(part_name));
         }
     }
@@ -135,22 +186,18 @@ impl World {
             "target_part": target_part_val
         });
 
-        if let Some(pending) = self.components.get_mut("PendingDamage")
-            && let Some(value) = pending.get_mut(&entity)
-            && let Some(obj) = value.as_object_mut()
-            && let Some(damages) = obj.get_mut("damages")
-            && let Some(arr) = damages.as_array_mut()
-        {
-            arr.push(damage_entry);
-        } else {
+        let mut entry = Some(damage_entry);
+        self.modify_component_raw(entity, "PendingDamage", |value| {
+            if let Some(arr) = value.get_mut("damages").and_then(|d| d.as_array_mut()) {
+                arr.extend(entry.take());
+            }
+        });
+        if let Some(damage_entry) = entry {
             let pending = json!({
                 "damages": [damage_entry]
             });
             // Bypass schema validation for internal PendingDamage writes
-            self.components
-                .entry("PendingDamage".to_string())
-                .or_default()
-                .insert(entity, pending);
+            let _ = self.insert_component_raw(entity, "PendingDamage", pending);
         }
     }
 
@@ -181,59 +228,106 @@ impl World {
             .count()
     }
 
-    /// Returns all entity IDs in the given cell.
-    pub fn entities_in_cell(&self, cell: &crate::map::CellKey) -> Vec<u32> {
-        self.entities
-            .iter()
-            .copied()
-            .filter(|&eid| {
-                self.get_component(eid, "Position")
-                    .and_then(|val| {
-                        val.get("pos").and_then(|p| {
-                            if let Some(obj) = p.as_object()
-                                && let Some(sq) = obj.get("Square")
-                            {
-                                let x = sq.get("x")?.as_i64()? as i32;
-                                let y = sq.get("y")?.as_i64()? as i32;
-                                let z = sq.get("z")?.as_i64()? as i32;
-                                if let crate::map::CellKey::Square {
-                                    x: cx,
-                                    y: cy,
-                                    z: cz,
-                                } = cell
-                                {
-                                    return Some(*cx == x && *cy == y && *cz == z);
-                                }
-                            }
-                            None
-                        })
-                    })
-                    .unwrap_or(false)
-            })
-            .collect()
+    /// Returns all entity IDs in the given cell, in ascending ID order.
+    pub fn entities_in_cell(&self, cell: &CellKey) -> Vec<u32> {
+        if !self.positions_visible() {
+            return vec![];
+        }
+        self.spatial_index.entities_in_cell(cell)
     }
 
-    /// Returns all entity IDs in the given z-level (for SquareGridMap).
+    /// Returns all entity IDs on the given z-level of a square or hex map.
     pub fn entities_in_zlevel(&self, z: i32) -> Vec<u32> {
-        self.entities
-            .iter()
-            .copied()
-            .filter(|&eid| {
-                self.get_component(eid, "Position")
-                    .and_then(|val| {
-                        val.get("pos").and_then(|p| {
-                            if let Some(obj) = p.as_object()
-                                && let Some(sq) = obj.get("Square")
-                            {
-                                let zval = sq.get("z")?.as_i64()? as i32;
-                                return Some(zval == z);
-                            }
-                            None
-                        })
-                    })
-                    .unwrap_or(false)
-            })
-            .collect()
+        if !self.positions_visible() {
+            return vec![];
+        }
+        self.spatial_index.entities_in_zlevel(z)
+    }
+
+    /// Returns all entity IDs in the box spanned by two square or hex corner cells
+    /// (see [`SpatialIndex::entities_in_rect`](crate::map::SpatialIndex::entities_in_rect)).
+    pub fn entities_in_rect(&self, a: &CellKey, b: &CellKey) -> Vec<u32> {
+        if !self.positions_visible() {
+            return vec![];
+        }
+        self.spatial_index.entities_in_rect(a, b)
+    }
+
+    /// Returns all entity IDs within `radius` of `center` on its z-level.
+    ///
+    /// Square maps use Euclidean distance, hex maps hex steps and province maps
+    /// the number of borders crossed on the world's map.
+    pub fn entities_in_radius(&self, center: &CellKey, radius: f64) -> Vec<u32> {
+        if !self.positions_visible() {
+            return vec![];
+        }
+        self.spatial_index
+            .entities_in_radius(center, radius, |cell| self.map_neighbors(cell))
+    }
+
+    /// Returns the entity with `component` nearest to `center`, optionally no
+    /// further than `max_radius` (distances as in
+    /// [`entities_in_radius`](Self::entities_in_radius)). Ties go to the lowest ID.
+    pub fn nearest_entity_with_component(
+        &self,
+        center: &CellKey,
+        component: &str,
+        max_radius: Option<f64>,
+    ) -> Option<u32> {
+        if !self.positions_visible() {
+            return None;
+        }
+        self.spatial_index.nearest(
+            center,
+            self.get_entities_with_component(component),
+            max_radius,
+            |cell| self.map_neighbors(cell),
+        )
+    }
+
+    /// Returns the cell of an entity's `Position`, if it has one.
+    pub fn entity_cell(&self, entity: u32) -> Option<&CellKey> {
+        if !self.positions_visible() {
+            return None;
+        }
+        self.spatial_index.cell_of(entity)
+    }
+
+    /// Rebuilds the spatial index from the `Position` components.
+    ///
+    /// [`set_component`](Self::set_component), [`remove_component`](Self::remove_component)
+    /// and loading keep the index up to date; call this after writing
+    /// [`components`](Self::components) directly.
+    pub fn rebuild_spatial_index(&mut self) {
+        self.spatial_index.clear();
+        let positions = match self.typed_components.column("Position") {
+            Some(column) => column.to_json_map(),
+            None => self.components.get("Position").cloned().unwrap_or_default(),
+        };
+        for (entity, value) in positions {
+            self.index_position(entity, &value);
+        }
+    }
+
+    /// Files `entity` under the cell of its `Position` value.
+    pub(super) fn index_position(&mut self, entity: u32, value: &serde_json::Value) {
+        match CellKey::from_position(value) {
+            Some(cell) => self.spatial_index.insert(entity, cell),
+            None => {
+                self.spatial_index.remove(entity);
+            }
+        }
+    }
+
+    fn positions_visible(&self) -> bool {
+        self.is_component_allowed_in_mode("Position", &self.current_mode)
+    }
+
+    fn map_neighbors(&self, cell: &CellKey) -> Vec<CellKey> {
+        self.map
+            .as_ref()
+            .map(|map| map.neighbors(cell))
+            .unwrap_or_default()
     }
 
     /// Returns all cells (as serde_json::Value) assigned to the given region_id.
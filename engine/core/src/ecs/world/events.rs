@@ -1,5 +1,6 @@
-use super::World;
+use super::{ReplayCommand, World};
 use crate::ecs::event::{EventBus, SubscriberId};
+use crate::mods::ownership::ModResource;
 use serde_json::Value as JsonValue;
 use std::{
     collections::VecDeque,
@@ -9,6 +10,14 @@ use std::{
 impl World {
     /// Send an event to the given event bus
     pub fn send_event(&mut self, event_type: &str, payload: JsonValue) -> Result<(), String> {
+        let command = self.external_command(|| ReplayCommand::SendEvent {
+            event_type: event_type.to_string(),
+            payload: payload.clone(),
+        });
+        self.recorded(command, |world| world.push_event(event_type, payload))
+    }
+
+    fn push_event(&mut self, event_type: &str, payload: JsonValue) -> Result<(), String> {
         let bus = self
             .event_buses
             .get_event_bus(event_type)
@@ -97,12 +106,21 @@ impl World {
     }
 
     /// Subscribe to an event
-    pub fn subscribe<T, F>(&self, name: &str, handler: F) -> Option<SubscriberId>
+    ///
+    /// Inside a mod scope the subscription is recorded as the mod's, so
+    /// unloading the mod removes it.
+    pub fn subscribe<T, F>(&mut self, name: &str, handler: F) -> Option<SubscriberId>
     where
         T: 'static + Send + Sync + Clone,
         F: Fn(&T) + Send + Sync + 'static,
     {
-        self.event_buses.subscribe::<T, F>(name, handler)
+        let id = self.event_buses.subscribe::<T, F>(name, handler)?;
+        self.record_mod_resource(ModResource::Subscription {
+            bus: name.to_string(),
+            id,
+            unsubscribe: |world, bus, id| world.unsubscribe::<T>(bus, id),
+        });
+        Some(id)
     }
 
     /// Unsubscribe from an event
@@ -0,0 +1,207 @@
+//! Parent/child relationships between entities.
+//!
+//! Each entity has at most one parent. The link stores what happens to the child
+//! when its parent is despawned ([`DespawnPolicy`]): body parts and sub-jobs are
+//! despawned with their parent, while items in a destroyed container can move up
+//! to the container's own parent instead. Links are saved with the world.
+
+use super::{ReplayCommand, World};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// What happens to a child when its parent is despawned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DespawnPolicy {
+    /// Despawn the child (and its own children, per their policies).
+    #[default]
+    Cascade,
+    /// Attach the child to the despawned parent's parent, or make it a root.
+    Reparent,
+}
+
+impl DespawnPolicy {
+    /// Parse a policy name (`"cascade"` or `"reparent"`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "cascade" => Some(Self::Cascade),
+            "reparent" => Some(Self::Reparent),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct ParentLink {
+    parent: u32,
+    #[serde(default)]
+    policy: DespawnPolicy,
+}
+
+/// Parent links of a world, indexed both ways.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Hierarchy {
+    /// Child → link to its parent.
+    parents: BTreeMap<u32, ParentLink>,
+    /// Parent → children, in ascending ID order.
+    children: BTreeMap<u32, BTreeSet<u32>>,
+}
+
+impl Hierarchy {
+    pub(crate) fn parent(&self, child: u32) -> Option<u32> {
+        self.parents.get(&child).map(|link| link.parent)
+    }
+
+    pub(crate) fn children(&self, parent: u32) -> Vec<u32> {
+        self.children
+            .get(&parent)
+            .map(|set| set.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Parent, grandparent, ... up to the root.
+    pub(crate) fn ancestors(&self, entity: u32) -> Vec<u32> {
+        let mut ancestors = Vec::new();
+        let mut current = entity;
+        while let Some(parent) = self.parent(current) {
+            ancestors.push(parent);
+            current = parent;
+        }
+        ancestors
+    }
+
+    /// All entities below `entity`, depth first.
+    pub(crate) fn descendants(&self, entity: u32) -> Vec<u32> {
+        let mut descendants = Vec::new();
+        let mut stack: Vec<u32> = self.children(entity).into_iter().rev().collect();
+        while let Some(next) = stack.pop() {
+            descendants.push(next);
+            stack.extend(self.children(next).into_iter().rev());
+        }
+        descendants
+    }
+
+    pub(crate) fn set_parent(
+        &mut self,
+        child: u32,
+        parent: u32,
+        policy: DespawnPolicy,
+    ) -> Result<(), String> {
+        if child == parent {
+            return Err(format!("Entity {child} cannot be its own parent"));
+        }
+        if self.ancestors(parent).contains(&child) {
+            return Err(format!(
+                "Entity {parent} is a descendant of {child}; parenting would create a cycle"
+            ));
+        }
+        self.remove_parent(child);
+        self.parents.insert(child, ParentLink { parent, policy });
+        self.children.entry(parent).or_default().insert(child);
+        Ok(())
+    }
+
+    pub(crate) fn remove_parent(&mut self, child: u32) -> Option<u32> {
+        let link = self.parents.remove(&child)?;
+        if let Some(siblings) = self.children.get_mut(&link.parent) {
+            siblings.remove(&child);
+            if siblings.is_empty() {
+                self.children.remove(&link.parent);
+            }
+        }
+        Some(link.parent)
+    }
+
+    /// Remove every link of an entity about to be despawned. Returns its former
+    /// parent and its children with their despawn policies.
+    pub(crate) fn detach(&mut self, entity: u32) -> (Option<u32>, Vec<(u32, DespawnPolicy)>) {
+        let parent = self.remove_parent(entity);
+        let children = self
+            .children
+            .remove(&entity)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|child| {
+                let link = self.parents.remove(&child)?;
+                Some((child, link.policy))
+            })
+            .collect();
+        (parent, children)
+    }
+}
+
+impl World {
+    /// Make `parent` the parent of `child`, replacing any previous parent.
+    /// The child is despawned together with its parent.
+    pub fn set_parent(&mut self, child: u32, parent: u32) -> Result<(), String> {
+        self.set_parent_with_policy(child, parent, DespawnPolicy::Cascade)
+    }
+
+    /// Make `parent` the parent of `child` with the given despawn policy.
+    ///
+    /// Fails if either entity does not exist or if the link would create a cycle.
+    pub fn set_parent_with_policy(
+        &mut self,
+        child: u32,
+        parent: u32,
+        policy: DespawnPolicy,
+    ) -> Result<(), String> {
+        for entity in [child, parent] {
+            if !self.entity_exists(entity) {
+                return Err(format!("Entity {entity} does not exist"));
+            }
+        }
+        let command = self.external_command(|| ReplayCommand::SetParent {
+            entity: child,
+            parent: Some(parent),
+            policy,
+        });
+        self.recorded(command, |world| {
+            world.hierarchy.set_parent(child, parent, policy)
+        })
+    }
+
+    /// Detach `child` from its parent. Returns the former parent, if any.
+    pub fn remove_parent(&mut self, child: u32) -> Option<u32> {
+        let command = self.external_command(|| ReplayCommand::SetParent {
+            entity: child,
+            parent: None,
+            policy: DespawnPolicy::default(),
+        });
+        self.recorded(command, |world| world.hierarchy.remove_parent(child))
+    }
+
+    /// Parent of an entity, if any.
+    pub fn parent_of(&self, entity: u32) -> Option<u32> {
+        self.hierarchy.parent(entity)
+    }
+
+    /// Direct children of an entity, in ascending ID order.
+    pub fn children_of(&self, entity: u32) -> Vec<u32> {
+        self.hierarchy.children(entity)
+    }
+
+    /// Ancestors of an entity, nearest first.
+    pub fn ancestors(&self, entity: u32) -> Vec<u32> {
+        self.hierarchy.ancestors(entity)
+    }
+
+    /// All entities below an entity, depth first.
+    pub fn descendants(&self, entity: u32) -> Vec<u32> {
+        self.hierarchy.descendants(entity)
+    }
+
+    /// Unlink a despawning entity and apply its children's despawn policies.
+    pub(super) fn despawn_children(&mut self, entity: u32) {
+        let (parent, children) = self.hierarchy.detach(entity);
+        for (child, policy) in children {
+            match (policy, parent) {
+                (DespawnPolicy::Cascade, _) => self.despawn_entity(child),
+                (DespawnPolicy::Reparent, Some(parent)) => {
+                    let _ = self.hierarchy.set_parent(child, parent, policy);
+                }
+                (DespawnPolicy::Reparent, None) => {}
+            }
+        }
+    }
+}
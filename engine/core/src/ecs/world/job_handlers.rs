@@ -1,4 +1,5 @@
 use crate::ecs::world::World;
+use crate::mods::ownership::ModResource;
 
 /// Extension methods for registering custom job handlers.
 impl World {
@@ -21,5 +22,6 @@ impl World {
             .lock()
             .unwrap()
             .register_handler(job_type, handler);
+        self.record_mod_resource(ModResource::JobType(job_type.to_string()));
     }
 }
@@ -1,5 +1,5 @@
 use super::World;
-use crate::map::Map;
+use crate::map::{CellKey, DijkstraMap, Map, PathfindingStrategy};
 use serde_json::Value as JsonValue;
 use std::sync::Arc;
 
@@ -16,18 +16,129 @@ impl World {
         self.map.as_ref().and_then(|m| m.get_cell_metadata(cell))
     }
 
+    /// Links stairs, ramps and ladders to the level above; see
+    /// [`Map::connect_levels`]. Call after marking connectors with
+    /// [`World::set_cell_metadata`].
+    pub fn connect_levels(&mut self) -> usize {
+        self.map.as_mut().map_or(0, Map::connect_levels)
+    }
+
     /// Find path from start to goal using the world's map and cell metadata.
+    ///
+    /// Searches with [`World::pathfinding_strategy`] and the walker profile.
     pub fn find_path(
         &self,
         start: &crate::map::CellKey,
         goal: &crate::map::CellKey,
     ) -> Option<crate::map::pathfinding::PathfindingResult> {
-        self.map.as_ref()?.find_path(start, goal)
+        self.map.as_ref()?.find_path_using(
+            start,
+            goal,
+            &crate::map::MovementProfile::walker(),
+            self.pathfinding_strategy,
+        )
+    }
+
+    /// Find path from start to goal using the named movement profile.
+    /// Unknown profile names fall back to the walker.
+    pub fn find_path_with_profile(
+        &self,
+        start: &crate::map::CellKey,
+        goal: &crate::map::CellKey,
+        profile: &str,
+    ) -> Option<crate::map::pathfinding::PathfindingResult> {
+        let profile = self.movement_profiles.get_or_walker(Some(profile));
+        self.map
+            .as_ref()?
+            .find_path_using(start, goal, profile, self.pathfinding_strategy)
+    }
+
+    /// Find path from start to goal using the movement profile of `entity`.
+    pub fn find_path_for(
+        &self,
+        entity: u32,
+        start: &crate::map::CellKey,
+        goal: &crate::map::CellKey,
+    ) -> Option<crate::map::pathfinding::PathfindingResult> {
+        let profile = self.movement_profile_of(entity);
+        self.map
+            .as_ref()?
+            .find_path_using(start, goal, profile, self.pathfinding_strategy)
+    }
+
+    /// The Dijkstra map towards `goals` for the named movement profile.
+    /// Unknown profile names fall back to the walker.
+    pub fn dijkstra_map(&self, goals: &[CellKey], profile: &str) -> Option<Arc<DijkstraMap>> {
+        let profile = self.movement_profiles.get_or_walker(Some(profile));
+        Some(self.map.as_ref()?.dijkstra_map(goals, profile))
+    }
+
+    /// The map leading away from `threats` for the named movement profile.
+    pub fn flee_map(
+        &self,
+        threats: &[CellKey],
+        profile: &str,
+        factor: f32,
+    ) -> Option<Arc<DijkstraMap>> {
+        let profile = self.movement_profiles.get_or_walker(Some(profile));
+        Some(self.map.as_ref()?.flee_map(threats, profile, factor))
+    }
+
+    /// The next cell on the way from `from` to the nearest of `goals` for the
+    /// named movement profile, or `None` at a goal or if none is reachable.
+    pub fn flow_step(&self, from: &CellKey, goals: &[CellKey], profile: &str) -> Option<CellKey> {
+        let map = self.map.as_ref()?;
+        self.dijkstra_map(goals, profile)?
+            .next_step(from, |cell| map.neighbors(cell))
+    }
+
+    /// The next cell on the way from `from` away from `threats` for the named
+    /// movement profile, or `None` if no neighbor is safer.
+    pub fn flee_step(
+        &self,
+        from: &CellKey,
+        threats: &[CellKey],
+        profile: &str,
+        factor: f32,
+    ) -> Option<CellKey> {
+        let map = self.map.as_ref()?;
+        self.flee_map(threats, profile, factor)?
+            .next_step(from, |cell| map.neighbors(cell))
+    }
+
+    /// Like [`World::flow_step`], with the movement profile of `entity`.
+    pub fn flow_step_for(&self, entity: u32, from: &CellKey, goals: &[CellKey]) -> Option<CellKey> {
+        let profile = self.movement_profile_of(entity);
+        let map = self.map.as_ref()?;
+        map.dijkstra_map(goals, profile)
+            .next_step(from, |cell| map.neighbors(cell))
+    }
+
+    /// Select the pathfinding strategy by name (`"astar"` or `"hierarchical"`).
+    pub fn set_pathfinding_strategy_by_name(
+        &mut self,
+        name: &str,
+        cluster_size: Option<u32>,
+    ) -> Result<(), String> {
+        self.pathfinding_strategy = PathfindingStrategy::from_name(name, cluster_size)?;
+        Ok(())
+    }
+
+    /// The movement profile named by the entity's `Agent.movement_profile`,
+    /// or the walker if it has none or the name is unknown.
+    pub fn movement_profile_of(&self, entity: u32) -> &crate::map::MovementProfile {
+        let name = self
+            .get_component(entity, "Agent")
+            .and_then(|agent| agent.get("movement_profile"))
+            .and_then(|name| name.as_str());
+        self.movement_profiles.get_or_walker(name)
     }
 
     /// Applies a generated map (from worldgen JSON) to the world and runs all postprocessors/validators.
+    /// Stairs, ramps and ladders are linked to the level above.
     pub fn apply_generated_map(&mut self, map_json: &JsonValue) -> Result<(), String> {
-        let map = Map::from_json(map_json)?;
+        let mut map = Map::from_json(map_json)?;
+        map.connect_levels();
         self.map = Some(map);
 
         let hooks = self.map_postprocessors.clone();
@@ -70,7 +181,8 @@ impl World {
         self.map_postprocessors.clear();
     }
 
-    /// Apply a map chunk (merge into the current map).
+    /// Apply a map chunk (merge into the current map), linking its stairs,
+    /// ramps and ladders to the levels around it.
     pub fn apply_chunk(&mut self, chunk_json: &serde_json::Value) -> Result<(), String> {
         let chunk = Map::from_json(chunk_json)?;
         if let Some(ref mut map) = self.map {
@@ -78,6 +190,9 @@ impl World {
         } else {
             self.map = Some(chunk);
         }
+        if let Some(ref mut map) = self.map {
+            map.connect_levels();
+        }
         Ok(())
     }
 }
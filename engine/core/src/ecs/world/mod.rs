@@ -2,17 +2,31 @@
 //!
 //! Defines the World struct, which holds all entities, components, systems, and loaded assets.
 
+use crate::config::GameConfig;
+use crate::ecs::entity::EntityAllocator;
 use crate::ecs::registry::ComponentRegistry;
+use crate::ecs::schedule::{FixedTimestep, RunCondition, SystemStage};
+use crate::ecs::storage::TypedComponentStorage;
 use crate::ecs::system::SystemRegistry;
 use crate::loot::LootTableRegistry;
 use crate::map::Map;
+use crate::map::MovementProfiles;
+use crate::map::PathfindingStrategy;
+use crate::map::SpatialIndex;
 use crate::map::cell_key::CellKey;
-use crate::map::fov::{BfsFovAlgorithm, FovAlgorithm, RecursiveShadowcasting};
+use crate::map::fov::{
+    BfsFovAlgorithm, FovAlgorithm, RecursiveShadowcasting, builtin_fov_algorithm,
+};
+use crate::mods::ownership::ModOwnership;
 use crate::plugins::dynamic_systems::DynamicSystemRegistry;
+use crate::rng::WorldRng;
 use crate::systems::job::{JobBoard, JobTypeRegistry};
+use crate::systems::research::ResearchPools;
+use crate::tech_effects::TechUnlocks;
+use crate::tech_tree::TechTree;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::sync::{Arc, Mutex};
 
 /// Job handler modules
@@ -22,15 +36,29 @@ pub mod season;
 /// Wasm exports
 pub mod wasm;
 
+pub(crate) use change_detection::ChangeTracker;
+pub use change_detection::ComponentTicks;
+pub use hierarchy::DespawnPolicy;
+pub use replay::{INPUT_EVENT_BUS, RecordedTick, Recording, ReplayCommand};
+pub use save_load::{SAVE_FORMAT_VERSION, SaveFormat, SaveHeader};
 pub use season::Season;
+pub use snapshot::WorldSnapshot;
+pub use state_hash::{SystemHash, TICK_START, first_divergence};
 
+mod change_detection;
 mod component;
 mod entity;
 mod events;
+mod hierarchy;
 mod map;
 mod mode;
+mod ownership;
+mod replay;
 mod resources;
+mod rng;
 mod save_load;
+mod snapshot;
+mod state_hash;
 mod systems;
 
 /// Map postprocessor function
@@ -57,14 +85,27 @@ pub struct World {
     /// List of all entity IDs in the world.
     pub entities: Vec<u32>,
     /// Map from component name to a map of entity IDs to component data.
+    /// Components registered for typed storage live in `typed_components` instead.
+    /// Code writing here directly must call [`World::mark_component_changed`],
+    /// or change detection will not see the write.
     pub components: HashMap<String, HashMap<u32, JsonValue>>,
-    next_id: u32,
+    /// Typed columnar storage for components opted in via `register_typed_component`.
+    #[serde(skip)]
+    pub typed_components: TypedComponentStorage,
+    /// Generational entity ID allocator.
+    entity_allocator: EntityAllocator,
+    /// Parent/child links between entities.
+    #[serde(default)]
+    hierarchy: hierarchy::Hierarchy,
     /// Current game mode.
     pub current_mode: String,
     /// Current turn number.
     pub turn: u32,
     /// Current time of day.
     pub time_of_day: TimeOfDay,
+    /// Seeded RNG streams (persisted in saves so runs are reproducible).
+    #[serde(default)]
+    pub rng: WorldRng,
     /// Component registry
     #[serde(skip)]
     pub registry: Arc<Mutex<ComponentRegistry>>,
@@ -80,8 +121,8 @@ pub struct World {
     /// Job type registry
     #[serde(skip)]
     pub job_types: JobTypeRegistry,
-    /// Loot table registry (runtime-defined, not serialized)
-    #[serde(skip)]
+    /// Loot table registry (runtime-defined, persisted in saves)
+    #[serde(default)]
     pub loot_tables: LootTableRegistry,
     /// Job handler registry
     #[serde(skip)]
@@ -96,8 +137,8 @@ pub struct World {
             >,
         >,
     >,
-    /// Map
-    #[serde(skip)]
+    /// Map (persisted in saves in the generated-map JSON format)
+    #[serde(default)]
     pub map: Option<Map>,
     /// Visible cells per entity (transient FOV state, not serialized)
     #[serde(skip)]
@@ -106,6 +147,10 @@ pub struct World {
     /// Old saves without this field deserialize as empty (backward compatible).
     #[serde(default)]
     pub explored_cells: HashMap<u32, HashSet<CellKey>>,
+    /// Entities by the cell of their `Position` (runtime index, rebuilt on load).
+    #[serde(skip)]
+    spatial_index: SpatialIndex,
+    #[serde(default)]
     event_queues: HashMap<String, (VecDeque<JsonValue>, VecDeque<JsonValue>)>, // (write, read)
     /// Map postprocessors
     #[serde(skip)]
@@ -119,28 +164,84 @@ pub struct World {
 
     // --- Asset/data fields ---
     /// Map from resource kind to resource definition (loaded from assets/resources).
-    #[serde(skip)]
+    #[serde(default)]
     pub resource_definitions: HashMap<String, JsonValue>,
     /// Map from material name to material definition (loaded from assets/materials).
-    #[serde(skip)]
+    #[serde(default)]
     pub material_definitions: HashMap<String, JsonValue>,
     /// Map from recipe name to recipe definition (loaded from assets/recipes).
-    #[serde(skip)]
+    #[serde(default)]
     pub recipes: HashMap<String, JsonValue>,
     /// Map from job name to job definition (loaded from assets/jobs).
-    #[serde(skip)]
+    #[serde(default)]
     pub jobs: HashMap<String, JsonValue>,
-    /// Job board
-    #[serde(skip)]
+    /// Tech tree (loaded from assets/tech and mods).
+    #[serde(default)]
+    pub tech_tree: TechTree,
+    /// Tech effects granted to factions and entities (see [`crate::tech_effects`]).
+    #[serde(default)]
+    pub tech_unlocks: TechUnlocks,
+    /// Shared research points of each faction (see [`crate::systems::research`]).
+    #[serde(default)]
+    pub research_pools: ResearchPools,
+    /// Job board (job queue, scheduling policy and shortage state)
+    #[serde(default)]
     pub job_board: JobBoard,
+    /// Movement profiles available to agents (see [`crate::map::movement`]).
+    #[serde(skip)]
+    pub movement_profiles: MovementProfiles,
+    /// How [`World::find_path`] searches the map.
+    #[serde(default)]
+    pub pathfinding_strategy: PathfindingStrategy,
 
-    /// Active FOV algorithm used by the FOV update system.
-    #[serde(skip, default = "default_fov_algorithm")]
+    /// Active FOV algorithm used by the FOV update system (saved by name).
+    #[serde(
+        default = "default_fov_algorithm",
+        with = "save_load::fov_algorithm_name"
+    )]
     pub fov_algorithm: Box<dyn FovAlgorithm>,
 
     /// Registered FOV algorithm implementations (name → instance).
     #[serde(skip, default = "default_fov_algorithms")]
     pub fov_algorithms: HashMap<String, Box<dyn FovAlgorithm>>,
+
+    /// Active session recorder, if recording (see [`World::start_recording`]).
+    #[serde(skip)]
+    recorder: Option<replay::Recorder>,
+
+    /// Per-system state hash log, when system hash debugging is enabled.
+    #[serde(skip)]
+    system_hashes: Option<Vec<SystemHash>>,
+
+    /// Component change ticks and system last-run ticks (runtime only).
+    #[serde(skip)]
+    change_tracker: change_detection::ChangeTracker,
+
+    /// Run native systems one at a time even when their declared access would
+    /// allow parallel batches (see [`World::set_parallel_systems`]).
+    #[serde(skip)]
+    serial_systems: bool,
+
+    /// Stage overrides per system name (see [`World::set_system_stage`]).
+    #[serde(skip)]
+    system_stages: HashMap<String, SystemStage>,
+
+    /// Run conditions per system name (see [`World::add_run_condition`]).
+    #[serde(skip)]
+    run_conditions: HashMap<String, Vec<RunCondition>>,
+
+    /// Fixed-timestep accumulator driving [`World::advance`].
+    #[serde(skip)]
+    timestep: FixedTimestep,
+
+    /// Resources registered by each mod (see [`World::begin_mod_scope`]).
+    #[serde(skip)]
+    mod_ownership: ModOwnership,
+
+    /// Migration scope versions of the save the world was loaded from, kept in
+    /// later saves (see [`SaveHeader::scoped_versions`]).
+    #[serde(skip)]
+    scoped_save_versions: BTreeMap<String, u32>,
 }
 
 /// Default FOV algorithm factory (used by serde `#[serde(skip, default)]`).
@@ -165,10 +266,13 @@ impl World {
         World {
             entities: Vec::new(),
             components: HashMap::new(),
-            next_id: 1,
+            typed_components: TypedComponentStorage::new(),
+            entity_allocator: EntityAllocator::new(),
+            hierarchy: hierarchy::Hierarchy::default(),
             current_mode: "colony".to_string(),
             turn: 0,
             time_of_day: TimeOfDay::default(),
+            rng: WorldRng::default(),
             registry,
             systems: SystemRegistry::new(),
             event_buses: crate::ecs::event_bus_registry::EventBusRegistry::new(),
@@ -184,6 +288,7 @@ impl World {
             map: None,
             visible_cells: HashMap::new(),
             explored_cells: HashMap::new(),
+            spatial_index: SpatialIndex::new(),
             event_queues: HashMap::new(),
             map_postprocessors: Vec::new(),
             map_validators: Vec::new(),
@@ -193,7 +298,12 @@ impl World {
             material_definitions: HashMap::new(),
             recipes: HashMap::new(),
             jobs: HashMap::new(),
+            tech_tree: TechTree::new(),
+            tech_unlocks: TechUnlocks::default(),
+            research_pools: ResearchPools::default(),
             job_board: JobBoard::default(),
+            movement_profiles: MovementProfiles::default(),
+            pathfinding_strategy: PathfindingStrategy::default(),
             fov_algorithm: Box::new(RecursiveShadowcasting),
             fov_algorithms: {
                 let mut m: HashMap<String, Box<dyn FovAlgorithm>> = HashMap::new();
@@ -204,8 +314,24 @@ impl World {
                 m.insert("bfs_flood_fill".to_string(), Box::new(BfsFovAlgorithm));
                 m
             },
+            recorder: None,
+            system_hashes: None,
+            change_tracker: change_detection::ChangeTracker::default(),
+            serial_systems: false,
+            system_stages: HashMap::new(),
+            run_conditions: HashMap::new(),
+            timestep: FixedTimestep::default(),
+            mod_ownership: ModOwnership::default(),
+            scoped_save_versions: BTreeMap::new(),
         }
     }
+
+    /// Creates a new World for a game config, seeding the RNG from its `seed`.
+    pub fn with_config(registry: Arc<Mutex<ComponentRegistry>>, config: &GameConfig) -> Self {
+        let mut world = Self::new(registry);
+        world.rng = WorldRng::from_config(config);
+        world
+    }
 }
 
 impl World {
@@ -288,20 +414,14 @@ impl World {
         if !self.fov_algorithms.contains_key(name) {
             return Err(format!("FOV algorithm '{name}' is not registered"));
         }
-        match name {
-            "recursive_shadowcasting" => {
-                self.fov_algorithm = Box::new(RecursiveShadowcasting);
-                Ok(())
-            }
-            "bfs_flood_fill" => {
-                self.fov_algorithm = Box::new(BfsFovAlgorithm);
-                Ok(())
-            }
-            _ => Err(format!(
+        let algo = builtin_fov_algorithm(name).ok_or_else(|| {
+            format!(
                 "FOV algorithm '{name}' is registered but cannot be dynamically \
                  constructed. Use set_fov_algorithm() directly."
-            )),
-        }
+            )
+        })?;
+        self.fov_algorithm = algo;
+        Ok(())
     }
 }
 
@@ -11,5 +11,7 @@ impl World {
         self.current_mode = mode.to_string();
         let allowed: Vec<String> = self.registry.lock().unwrap().components_for_mode(mode);
         self.components.retain(|name, _| allowed.contains(name));
+        self.typed_components
+            .clear_where(|name| !allowed.iter().any(|a| a == name));
     }
 }
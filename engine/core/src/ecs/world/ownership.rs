@@ -0,0 +1,67 @@
+use super::World;
+use crate::mods::ownership::{ModOwnership, ModResource};
+use crate::presentation::ui::factory::WIDGET_REGISTRY;
+
+impl World {
+    /// Attribute schemas, dynamic systems, job types, subscriptions and widgets
+    /// registered from now on to `mod_name`, until [`World::end_mod_scope`].
+    /// Scopes nest.
+    pub fn begin_mod_scope(&mut self, mod_name: &str) {
+        self.mod_ownership.push_scope(mod_name);
+    }
+
+    /// Close the innermost mod scope.
+    pub fn end_mod_scope(&mut self) {
+        self.mod_ownership.pop_scope();
+    }
+
+    /// The mod whose code is running, if any.
+    pub fn current_mod(&self) -> Option<&str> {
+        self.mod_ownership.current()
+    }
+
+    /// Record a resource as registered by the current mod (no-op outside a mod scope).
+    pub fn record_mod_resource(&mut self, resource: ModResource) {
+        self.mod_ownership.record(resource);
+    }
+
+    /// Resources registered by a mod.
+    pub fn mod_resources(&self, mod_name: &str) -> &[ModResource] {
+        self.mod_ownership.resources(mod_name)
+    }
+
+    /// Ownership records of all mods.
+    pub fn mod_ownership(&self) -> &ModOwnership {
+        &self.mod_ownership
+    }
+
+    /// Remove everything a mod registered, newest first, and return it.
+    pub fn release_mod_resources(&mut self, mod_name: &str) -> Vec<ModResource> {
+        let resources = self.mod_ownership.take(mod_name);
+        for resource in resources.iter().rev() {
+            match resource {
+                ModResource::Schema(name) => self.unregister_component_and_cleanup(name),
+                ModResource::System(name) => {
+                    self.unregister_dynamic_system(name);
+                    self.system_stages.remove(name);
+                    self.run_conditions.remove(name);
+                }
+                ModResource::JobType(name) => {
+                    self.job_types.unregister(name);
+                    self.job_handler_registry.lock().unwrap().unregister(name);
+                }
+                ModResource::Subscription {
+                    bus,
+                    id,
+                    unsubscribe,
+                } => {
+                    unsubscribe(self, bus, *id);
+                }
+                ModResource::Widget(id) => {
+                    WIDGET_REGISTRY.lock().borrow_mut().remove(id);
+                }
+            }
+        }
+        resources
+    }
+}
@@ -0,0 +1,356 @@
+//! Session recording and headless replay.
+//!
+//! A [`Recording`] holds a snapshot of the world taken when recording started, the
+//! RNG seed, and every command injected from outside the simulation, grouped by the
+//! tick they were applied before. Each tick also stores a hash of the world state it
+//! produced. Replaying restores the snapshot, re-applies the commands and runs
+//! [`World::tick`], checking the hash after every tick so the first diverging tick
+//! of a desync can be found.
+//!
+//! Commands are captured at the recorded entry points ([`World::send_event`],
+//! [`World::spawn_entity`], [`World::despawn_entity`], [`World::set_component`],
+//! [`World::remove_component`], [`World::set_parent_with_policy`],
+//! [`World::remove_parent`] and [`World::push_input`]) when they are called
+//! between ticks. Calls made by systems during a tick, and calls nested inside
+//! another recorded command, are part of the simulation and are not recorded.
+
+use super::save_load::{SaveFormat, decode_save, encode_save};
+use super::snapshot::WorldSnapshot;
+use super::{DespawnPolicy, World};
+use crate::ecs::error::ReplayError;
+use crate::presentation::input::InputEvent;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::cell::RefCell;
+use std::io::{Error as IoError, ErrorKind};
+use std::rc::Rc;
+
+/// Recording file format version.
+const RECORDING_FORMAT_VERSION: u32 = 1;
+
+/// Event bus that [`World::push_input`] sends input events to.
+pub const INPUT_EVENT_BUS: &str = "input";
+
+/// A command injected into the world from outside the simulation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum ReplayCommand {
+    /// An event sent to an event bus.
+    SendEvent {
+        /// Event bus name.
+        event_type: String,
+        /// Event payload.
+        payload: JsonValue,
+    },
+    /// An entity was spawned.
+    SpawnEntity {
+        /// ID the entity was given.
+        entity: u32,
+    },
+    /// An entity was despawned.
+    DespawnEntity {
+        /// Entity ID.
+        entity: u32,
+    },
+    /// A component value was set.
+    SetComponent {
+        /// Entity ID.
+        entity: u32,
+        /// Component name.
+        component: String,
+        /// Component value.
+        value: JsonValue,
+    },
+    /// A component was removed.
+    RemoveComponent {
+        /// Entity ID.
+        entity: u32,
+        /// Component name.
+        component: String,
+    },
+    /// An entity was attached to or detached from a parent.
+    SetParent {
+        /// Child entity ID.
+        entity: u32,
+        /// New parent, or `None` to detach.
+        parent: Option<u32>,
+        /// Despawn policy of the link.
+        #[serde(default)]
+        policy: DespawnPolicy,
+    },
+    /// A presentation-layer input event.
+    Input {
+        /// The input event.
+        event: InputEvent,
+    },
+}
+
+/// Commands applied before a tick and the state hash the tick produced.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedTick {
+    /// Commands injected since the previous tick, in order.
+    #[serde(default)]
+    pub commands: Vec<ReplayCommand>,
+    /// [`World::state_hash`] after the tick.
+    pub hash: u64,
+}
+
+/// A recorded session that can be replayed headless.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+    version: u32,
+    /// RNG master seed when recording started.
+    pub seed: u64,
+    /// World state when recording started.
+    pub initial: WorldSnapshot,
+    /// Recorded ticks, in order.
+    pub ticks: Vec<RecordedTick>,
+}
+
+impl Recording {
+    /// Number of recorded ticks.
+    pub fn len(&self) -> usize {
+        self.ticks.len()
+    }
+
+    /// Returns true if no tick was recorded.
+    pub fn is_empty(&self) -> bool {
+        self.ticks.is_empty()
+    }
+
+    /// Encode the recording in the given save format.
+    pub fn to_bytes(&self, format: SaveFormat) -> Result<Vec<u8>, IoError> {
+        let value = serde_json::to_value(self).map_err(IoError::other)?;
+        encode_save(&value, format)
+    }
+
+    /// Decode a recording written by [`to_bytes`](Self::to_bytes) in any format.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, IoError> {
+        let recording: Recording = serde_json::from_value(decode_save(bytes)?)
+            .map_err(|e| IoError::new(ErrorKind::InvalidData, e))?;
+        if recording.version > RECORDING_FORMAT_VERSION {
+            return Err(IoError::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Recording format version {} is newer than supported version {}",
+                    recording.version, RECORDING_FORMAT_VERSION
+                ),
+            ));
+        }
+        Ok(recording)
+    }
+
+    /// Write the recording to a file in the given save format.
+    pub fn save_to_file(&self, path: &std::path::Path, format: SaveFormat) -> Result<(), IoError> {
+        std::fs::write(path, self.to_bytes(format)?)
+    }
+
+    /// Read a recording from a file in any save format.
+    pub fn load_from_file(path: &std::path::Path) -> Result<Self, IoError> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+}
+
+/// In-progress recording owned by the world.
+pub(super) struct Recorder {
+    recording: Recording,
+    pending: Vec<ReplayCommand>,
+    /// Nesting depth of recorded commands and ticks; only depth 0 is external.
+    depth: u32,
+}
+
+impl World {
+    /// Start recording a session from the current state.
+    ///
+    /// Any recording in progress is discarded. Start recording between ticks,
+    /// after systems and registries are set up, since only saved state is captured.
+    pub fn start_recording(&mut self) -> Result<(), IoError> {
+        let initial = self.snapshot()?;
+        // Replays start from a restored snapshot, where every system sees all
+        // components as changed; do the same here so both runs match.
+        self.reset_change_detection();
+        self.recorder = Some(Recorder {
+            recording: Recording {
+                version: RECORDING_FORMAT_VERSION,
+                seed: self.rng.seed(),
+                initial,
+                ticks: Vec::new(),
+            },
+            pending: Vec::new(),
+            depth: 0,
+        });
+        Ok(())
+    }
+
+    /// Returns true if a session is being recorded.
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    /// Stop recording and return the recorded session.
+    ///
+    /// Commands injected after the last tick are not part of any tick and are dropped.
+    pub fn stop_recording(&mut self) -> Option<Recording> {
+        self.recorder.take().map(|recorder| recorder.recording)
+    }
+
+    /// Send an input event to the `"input"` event bus, recording it if a session
+    /// is being recorded.
+    pub fn push_input(&mut self, event: InputEvent) {
+        let command = self.external_command(|| ReplayCommand::Input {
+            event: event.clone(),
+        });
+        self.recorded(command, |world| {
+            let payload = serde_json::to_value(&event).unwrap_or(JsonValue::Null);
+            world.send_event(INPUT_EVENT_BUS, payload).ok();
+        });
+    }
+
+    /// Apply a recorded command through its regular entry point.
+    pub fn apply_command(&mut self, command: ReplayCommand) -> Result<(), String> {
+        match command {
+            ReplayCommand::SendEvent {
+                event_type,
+                payload,
+            } => self.send_event(&event_type, payload),
+            ReplayCommand::SpawnEntity { entity } => {
+                let spawned = self.spawn_entity();
+                if spawned == entity {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "Spawned entity {spawned}, recording expected {entity}"
+                    ))
+                }
+            }
+            ReplayCommand::DespawnEntity { entity } => {
+                self.despawn_entity(entity);
+                Ok(())
+            }
+            ReplayCommand::SetComponent {
+                entity,
+                component,
+                value,
+            } => self.set_component(entity, &component, value),
+            ReplayCommand::RemoveComponent { entity, component } => {
+                self.remove_component(entity, &component)
+            }
+            ReplayCommand::SetParent {
+                entity,
+                parent: Some(parent),
+                policy,
+            } => self.set_parent_with_policy(entity, parent, policy),
+            ReplayCommand::SetParent {
+                entity,
+                parent: None,
+                ..
+            } => {
+                self.remove_parent(entity);
+                Ok(())
+            }
+            ReplayCommand::Input { event } => {
+                self.push_input(event);
+                Ok(())
+            }
+        }
+    }
+
+    /// Replay a whole recording; see [`replay_ticks`](Self::replay_ticks).
+    pub fn replay(
+        world_rc: Rc<RefCell<World>>,
+        recording: &Recording,
+    ) -> Result<usize, ReplayError> {
+        World::replay_ticks(world_rc, recording, recording.len())
+    }
+
+    /// Restore the recording's initial snapshot and replay up to `count` ticks,
+    /// checking the state hash after each one.
+    ///
+    /// The world must have the same systems registered as the recorded one.
+    /// Returns the number of ticks replayed, or the first desync. Commands that
+    /// failed when recorded fail the same way on replay and are not errors.
+    pub fn replay_ticks(
+        world_rc: Rc<RefCell<World>>,
+        recording: &Recording,
+        count: usize,
+    ) -> Result<usize, ReplayError> {
+        world_rc.borrow_mut().restore(&recording.initial)?;
+        let mut replayed = 0;
+        for (index, tick) in recording.ticks.iter().take(count).enumerate() {
+            {
+                let mut world = world_rc.borrow_mut();
+                for command in &tick.commands {
+                    let _ = world.apply_command(command.clone());
+                }
+            }
+            World::tick(Rc::clone(&world_rc));
+            let world = world_rc.borrow();
+            let actual = world.state_hash();
+            if actual != tick.hash {
+                return Err(ReplayError::Desync {
+                    tick: index,
+                    turn: world.turn,
+                    expected: tick.hash,
+                    actual,
+                });
+            }
+            replayed += 1;
+        }
+        Ok(replayed)
+    }
+
+    /// Build the command for an entry point call if it should be recorded.
+    pub(super) fn external_command(
+        &self,
+        make: impl FnOnce() -> ReplayCommand,
+    ) -> Option<ReplayCommand> {
+        match &self.recorder {
+            Some(recorder) if recorder.depth == 0 => Some(make()),
+            _ => None,
+        }
+    }
+
+    /// Run an entry point, recording `command` (if any) and suppressing recording
+    /// of the calls it makes.
+    pub(super) fn recorded<T>(
+        &mut self,
+        command: Option<ReplayCommand>,
+        apply: impl FnOnce(&mut World) -> T,
+    ) -> T {
+        let Some(command) = command else {
+            return apply(self);
+        };
+        if let Some(recorder) = self.recorder.as_mut() {
+            recorder.depth += 1;
+        }
+        let result = apply(self);
+        if let Some(recorder) = self.recorder.as_mut() {
+            recorder.depth -= 1;
+            recorder.pending.push(command);
+        }
+        result
+    }
+
+    /// Suppress recording while a tick runs.
+    pub(super) fn begin_recorded_tick(&mut self) {
+        if let Some(recorder) = self.recorder.as_mut() {
+            recorder.depth += 1;
+        }
+    }
+
+    /// Close the current tick of the recording.
+    pub(super) fn end_recorded_tick(&mut self) {
+        let hash = match &self.recorder {
+            Some(_) => self.state_hash(),
+            None => return,
+        };
+        if let Some(recorder) = self.recorder.as_mut() {
+            recorder.depth = recorder.depth.saturating_sub(1);
+            let commands = std::mem::take(&mut recorder.pending);
+            recorder
+                .recording
+                .ticks
+                .push(RecordedTick { commands, hash });
+        }
+    }
+}
@@ -8,23 +8,26 @@ impl World {
         kind: &str,
         delta: f64,
     ) -> Result<(), String> {
-        let comp = self
-            .components
-            .get_mut("Resource")
-            .and_then(|map| map.get_mut(&entity_id));
-        if let Some(resource) = comp
-            && let Some(obj) = resource.as_object_mut()
-        {
+        let mut result = Ok(());
+        let found = self.modify_component_raw(entity_id, "Resource", |resource| {
+            let Some(obj) = resource.as_object_mut() else {
+                result = Err("Resource component not found".to_string());
+                return;
+            };
             if obj.get("kind").and_then(|v| v.as_str()) != Some(kind) {
-                return Err("Resource kind mismatch".to_string());
+                result = Err("Resource kind mismatch".to_string());
+                return;
             }
             let amount = obj.get("amount").and_then(|v| v.as_f64()).unwrap_or(0.0);
             let new_amount = amount + delta;
             if new_amount < 0.0 {
-                return Err("Not enough resource".to_string());
+                result = Err("Not enough resource".to_string());
+                return;
             }
             obj.insert("amount".to_string(), serde_json::json!(new_amount));
-            return Ok(());
+        });
+        if found {
+            return result;
         }
         Err("Resource component not found".to_string())
     }
@@ -36,35 +39,34 @@ impl World {
         kind: &str,
         delta: f64,
     ) -> Result<(), String> {
-        let comp = self
-            .components
-            .get_mut("Stockpile")
-            .and_then(|map| map.get_mut(&entity_id));
-        if let Some(stockpile) = comp
-            && let Some(obj) = stockpile.as_object_mut()
-            && let Some(resources) = obj.get_mut("resources").and_then(|v| v.as_object_mut())
-        {
+        let mut result = Err("Stockpile component not found".to_string());
+        self.modify_component_raw(entity_id, "Stockpile", |stockpile| {
+            let Some(resources) = stockpile
+                .get_mut("resources")
+                .and_then(|v| v.as_object_mut())
+            else {
+                return;
+            };
             let current = resources.get(kind).and_then(|v| v.as_f64()).unwrap_or(0.0);
             let new_amount = current + delta;
             if new_amount < 0.0 {
-                return Err("Not enough resource".to_string());
+                result = Err("Not enough resource".to_string());
+                return;
             }
             resources.insert(kind.to_string(), serde_json::json!(new_amount));
-            return Ok(());
-        }
-        Err("Stockpile component not found".to_string())
+            result = Ok(());
+        });
+        result
     }
 
     /// Returns the total amount of a resource kind across all stockpiles.
     pub fn get_global_resource_amount(&self, kind: &str) -> f64 {
         let mut total = 0.0;
-        if let Some(stockpiles) = self.components.get("Stockpile") {
-            for stockpile in stockpiles.values() {
-                if let Some(resources) = stockpile.get("resources").and_then(|v| v.as_object())
-                    && let Some(amount) = resources.get(kind).and_then(|v| v.as_f64())
-                {
-                    total += amount;
-                }
+        for stockpile in self.stockpiles() {
+            if let Some(resources) = stockpile.get("resources").and_then(|v| v.as_object())
+                && let Some(amount) = resources.get(kind).and_then(|v| v.as_f64())
+            {
+                total += amount;
             }
         }
         total
@@ -73,15 +75,20 @@ impl World {
     /// Sets the amount of a resource kind in the first stockpile, or creates a stockpile if none exist.
     pub fn set_global_resource_amount(&mut self, kind: &str, amount: f64) {
         // If there is at least one stockpile, set the resource there
-        if let Some(stockpiles) = self.components.get_mut("Stockpile")
-            && let Some((_eid, stockpile)) = stockpiles.iter_mut().next()
-            && let Some(obj) = stockpile.as_object_mut()
-        {
-            let resources = obj
-                .entry("resources")
-                .or_insert_with(|| serde_json::json!({}));
-            if let Some(res_map) = resources.as_object_mut() {
-                res_map.insert(kind.to_string(), serde_json::json!(amount));
+        if let Some(&eid) = self.get_entities_with_component("Stockpile").first() {
+            let mut updated = false;
+            self.modify_component_raw(eid, "Stockpile", |stockpile| {
+                if let Some(obj) = stockpile.as_object_mut()
+                    && let Some(res_map) = obj
+                        .entry("resources")
+                        .or_insert_with(|| serde_json::json!({}))
+                        .as_object_mut()
+                {
+                    res_map.insert(kind.to_string(), serde_json::json!(amount));
+                    updated = true;
+                }
+            });
+            if updated {
                 return;
             }
         }
@@ -99,13 +106,11 @@ impl World {
     pub fn get_global_resource_scarcity(&self, kind: &str) -> f64 {
         // Example: scan all stockpiles, sum amounts, invert for scarcity
         let mut total = 0;
-        if let Some(stockpiles) = self.components.get("Stockpile") {
-            for stockpile in stockpiles.values() {
-                if let Some(resources) = stockpile.get("resources").and_then(|v| v.as_object())
-                    && let Some(amount) = resources.get(kind).and_then(|v| v.as_i64())
-                {
-                    total += amount;
-                }
+        for stockpile in self.stockpiles() {
+            if let Some(resources) = stockpile.get("resources").and_then(|v| v.as_object())
+                && let Some(amount) = resources.get(kind).and_then(|v| v.as_i64())
+            {
+                total += amount;
             }
         }
         if total <= 0 {
@@ -118,4 +123,11 @@ impl World {
             0.0 // not scarce
         }
     }
+
+    /// Values of all Stockpile components, regardless of their storage.
+    fn stockpiles(&self) -> impl Iterator<Item = &serde_json::Value> {
+        self.get_entities_with_component("Stockpile")
+            .into_iter()
+            .filter_map(|eid| self.get_component(eid, "Stockpile"))
+    }
 }
@@ -0,0 +1,36 @@
+use super::World;
+use crate::loot::LootError;
+use crate::rng::{self, RngStream};
+
+impl World {
+    /// Reseed the world RNG, restarting every stream.
+    pub fn seed_rng(&mut self, seed: u64) {
+        self.rng.reseed(seed);
+    }
+
+    /// Master seed of the world RNG.
+    pub fn rng_seed(&self) -> u64 {
+        self.rng.seed()
+    }
+
+    /// Get a named RNG stream (e.g. [`rng::LOOT`], [`rng::AI`]).
+    pub fn rng_stream(&mut self, name: &str) -> &mut RngStream {
+        self.rng.stream(name)
+    }
+
+    /// Draw a random float in `[0, 1)` from a named stream.
+    pub fn random_f64(&mut self, stream: &str) -> f64 {
+        self.rng.next_f64(stream)
+    }
+
+    /// Draw a random integer in `[min, max]` from a named stream.
+    pub fn random_range(&mut self, stream: &str, min: i64, max: i64) -> i64 {
+        self.rng.range_i64(stream, min, max)
+    }
+
+    /// Roll a loot table using the world's loot stream.
+    pub fn roll_loot(&mut self, table: &str) -> Result<Vec<(String, u32)>, LootError> {
+        let rng = self.rng.stream(rng::LOOT);
+        self.loot_tables.roll(table, rng)
+    }
+}
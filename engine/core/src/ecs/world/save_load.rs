@@ -1,26 +1,305 @@
 use super::World;
 use crate::ecs::registry::ComponentRegistry;
-use serde_json::{Map, Value as JsonValue};
+use crate::ecs::save_migration::{self, MigrationReport};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
+use std::io::{Error as IoError, ErrorKind};
 use std::sync::{Arc, Mutex};
 
+/// Magic bytes at the start of a binary save file.
+const BINARY_SAVE_MAGIC: &[u8; 4] = b"MGEB";
+/// Binary save flag: payload is zstd-compressed.
+const BINARY_FLAG_ZSTD: u8 = 1;
+/// zstd level used for compressed saves.
+const ZSTD_LEVEL: i32 = 3;
+
+/// Save-file format version of the engine itself.
+///
+/// - 1: bare serialized World (no header, no map or registries).
+/// - 2: adds the save header, map topology, loot tables, job board,
+///   asset definitions and the active FOV algorithm.
+/// - 3: replaces the `next_id` counter with the generational entity allocator.
+///
+/// Registered save migrations may raise the version stamped into new saves
+/// (see [`SaveMigrationRegistry`](crate::ecs::save_migration::SaveMigrationRegistry)).
+pub const SAVE_FORMAT_VERSION: u32 = 3;
+
+/// Key under which the [`SaveHeader`] is stored in a save file.
+const SAVE_HEADER_KEY: &str = "save_header";
+
+/// Header stamped into every save file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SaveHeader {
+    /// Save-file format version the file was written with.
+    pub format_version: u32,
+    /// Engine crate version that wrote the file.
+    #[serde(default)]
+    pub engine_version: String,
+    /// Versions of the schema and mod migration scopes the file was written with
+    /// (see [`save_migration`]).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub scoped_versions: BTreeMap<String, u32>,
+}
+
+impl Default for SaveHeader {
+    fn default() -> Self {
+        Self {
+            format_version: SAVE_FORMAT_VERSION,
+            engine_version: env!("CARGO_PKG_VERSION").to_string(),
+            scoped_versions: BTreeMap::new(),
+        }
+    }
+}
+
+impl SaveHeader {
+    /// Remove the header from a save value. Saves without a header are format version 1.
+    pub fn take_from(value: &mut JsonValue) -> Result<Self, serde_json::Error> {
+        match value
+            .as_object_mut()
+            .and_then(|obj| obj.remove(SAVE_HEADER_KEY))
+        {
+            Some(header) => serde_json::from_value(header),
+            None => Ok(Self {
+                format_version: 1,
+                engine_version: String::new(),
+                scoped_versions: BTreeMap::new(),
+            }),
+        }
+    }
+}
+
+/// Encoding used when writing a save.
+///
+/// Loading detects the encoding automatically, so any format can be read back
+/// with [`World::load_from_file`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SaveFormat {
+    /// Pretty-printed JSON. Largest and slowest, but readable for debugging.
+    #[default]
+    Json,
+    /// MessagePack binary encoding of the save data.
+    Binary,
+    /// MessagePack compressed with zstd.
+    CompressedBinary,
+}
+
+impl SaveFormat {
+    /// Parse a format name (`"json"`, `"binary"` or `"compressed"`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "json" => Some(Self::Json),
+            "binary" => Some(Self::Binary),
+            "compressed" => Some(Self::CompressedBinary),
+            _ => None,
+        }
+    }
+}
+
+/// Encode a save value in the given format.
+pub(super) fn encode_save(value: &JsonValue, format: SaveFormat) -> Result<Vec<u8>, IoError> {
+    let (flags, payload) = match format {
+        SaveFormat::Json => return Ok(serde_json::to_vec_pretty(value)?),
+        SaveFormat::Binary => (0, to_msgpack(value)?),
+        SaveFormat::CompressedBinary => (
+            BINARY_FLAG_ZSTD,
+            zstd::encode_all(to_msgpack(value)?.as_slice(), ZSTD_LEVEL)?,
+        ),
+    };
+    let mut bytes = Vec::with_capacity(BINARY_SAVE_MAGIC.len() + 1 + payload.len());
+    bytes.extend_from_slice(BINARY_SAVE_MAGIC);
+    bytes.push(flags);
+    bytes.extend_from_slice(&payload);
+    Ok(bytes)
+}
+
+/// Decode save bytes written in any [`SaveFormat`].
+pub(super) fn decode_save(bytes: &[u8]) -> Result<JsonValue, IoError> {
+    let Some(rest) = bytes.strip_prefix(BINARY_SAVE_MAGIC) else {
+        return Ok(serde_json::from_slice(bytes)?);
+    };
+    let (&flags, payload) = rest
+        .split_first()
+        .ok_or_else(|| IoError::new(ErrorKind::InvalidData, "Truncated binary save header"))?;
+    let decoded;
+    let payload = if flags & BINARY_FLAG_ZSTD != 0 {
+        decoded = zstd::decode_all(payload)?;
+        decoded.as_slice()
+    } else {
+        payload
+    };
+    rmp_serde::from_slice(payload).map_err(|e| IoError::new(ErrorKind::InvalidData, e))
+}
+
+fn to_msgpack(value: &JsonValue) -> Result<Vec<u8>, IoError> {
+    rmp_serde::to_vec(value).map_err(|e| IoError::new(ErrorKind::InvalidData, e))
+}
+
+/// Serde adapter storing the active FOV algorithm by name.
+pub(super) mod fov_algorithm_name {
+    use crate::map::fov::{FovAlgorithm, RecursiveShadowcasting, builtin_fov_algorithm};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    #[allow(clippy::borrowed_box)]
+    pub fn serialize<S: Serializer>(
+        algo: &Box<dyn FovAlgorithm>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(algo.name())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Box<dyn FovAlgorithm>, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(builtin_fov_algorithm(&name).unwrap_or_else(|| {
+            log::warn!("FOV algorithm '{name}' cannot be restored from a save; using default");
+            Box::new(RecursiveShadowcasting)
+        }))
+    }
+}
+
 impl World {
-    /// Save the world to a file
+    /// Serialize the world into a save value, including the save header.
+    pub fn save_to_value(&self) -> Result<JsonValue, serde_json::Error> {
+        let mut value = serde_json::to_value(self)?;
+        // Typed columns are saved as plain JSON so saves stay storage-agnostic.
+        if !self.typed_components.names().is_empty() {
+            value["components"] = serde_json::to_value(self.components_as_json())?;
+        }
+        let migrations = self.registry.lock().unwrap().save_migrations().clone();
+        // Scopes this process has no migrations for keep the versions they were loaded with.
+        let mut scoped_versions = self.scoped_save_versions.clone();
+        merge_versions(&mut scoped_versions, migrations.latest_scoped_versions());
+        let header = SaveHeader {
+            format_version: migrations.latest_version(),
+            scoped_versions,
+            ..SaveHeader::default()
+        };
+        value[SAVE_HEADER_KEY] = serde_json::to_value(header)?;
+        Ok(value)
+    }
+
+    /// Save the world to a file as pretty-printed JSON.
     pub fn save_to_file(&self, path: &std::path::Path) -> Result<(), std::io::Error> {
-        let json = serde_json::to_string_pretty(&self)?;
-        std::fs::write(path, json)
+        self.save_to_file_as(path, SaveFormat::Json)
     }
 
-    /// Load a world from a file, with migration of deprecated fields.
-    pub fn load_from_file(
+    /// Save the world to a file in the given format.
+    pub fn save_to_file_as(
+        &self,
         path: &std::path::Path,
+        format: SaveFormat,
+    ) -> Result<(), std::io::Error> {
+        std::fs::write(path, self.save_to_bytes(format)?)
+    }
+
+    /// Serialize the world into save bytes in the given format.
+    pub fn save_to_bytes(&self, format: SaveFormat) -> Result<Vec<u8>, std::io::Error> {
+        encode_save(&self.save_to_value()?, format)
+    }
+
+    /// Restore a world from a save value, running pending save migrations.
+    ///
+    /// Accepts every save format up to the registry's latest save-format version;
+    /// fields missing from older formats take their defaults.
+    pub fn load_from_value(
+        value: JsonValue,
         registry: Arc<Mutex<ComponentRegistry>>,
     ) -> Result<Self, std::io::Error> {
-        let json = std::fs::read_to_string(path)?;
-        let mut world: Self = serde_json::from_str(&json)?;
+        Self::load_from_value_with_report(value, registry).map(|(world, _)| world)
+    }
+
+    /// Restore a world from a save value, returning a report of the migrations run.
+    ///
+    /// Migrated component values that no longer validate against their schema are
+    /// dropped and listed in the report.
+    pub fn load_from_value_with_report(
+        mut value: JsonValue,
+        registry: Arc<Mutex<ComponentRegistry>>,
+    ) -> Result<(Self, MigrationReport), std::io::Error> {
+        let header = SaveHeader::take_from(&mut value)?;
+        let migrations = registry.lock().unwrap().save_migrations().clone();
+        let latest = migrations.latest_version();
+        if header.format_version > latest {
+            return Err(IoError::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Save format version {} is newer than supported version {latest}",
+                    header.format_version
+                ),
+            ));
+        }
+        let latest_scoped = migrations.latest_scoped_versions();
+        for (scope, &latest) in &latest_scoped {
+            if let Some(&version) = header.scoped_versions.get(scope)
+                && version > latest
+            {
+                return Err(IoError::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "Save version {version} of '{scope}' is newer than supported version {latest}"
+                    ),
+                ));
+            }
+        }
+        let mut report = migrations
+            .migrate(&mut value, header.format_version, &header.scoped_versions)
+            .map_err(|e| IoError::new(ErrorKind::InvalidData, e))?;
+        if !report.is_empty() {
+            drop_invalid_migrated_values(&mut value, &registry, &mut report);
+            log::info!(
+                "Migrated save from format {} to {}: {} migrations, {} values migrated, {} dropped",
+                report.from_version,
+                report.to_version,
+                report.applied.len(),
+                report.migrated.len(),
+                report.dropped.len()
+            );
+        }
+        let mut world: Self = serde_json::from_value(value)?;
         world.registry = registry;
-        // Run migrations after loading
-        world.migrate_agent_skills();
-        Ok(world)
+        world.scoped_save_versions = header.scoped_versions;
+        merge_versions(&mut world.scoped_save_versions, latest_scoped);
+        world.rebuild_spatial_index();
+        Ok((world, report))
+    }
+
+    /// Restore a world from save bytes in any [`SaveFormat`], running pending save migrations.
+    pub fn load_from_bytes(
+        bytes: &[u8],
+        registry: Arc<Mutex<ComponentRegistry>>,
+    ) -> Result<Self, std::io::Error> {
+        Self::load_from_value(decode_save(bytes)?, registry)
+    }
+
+    /// Load a world from a file in any [`SaveFormat`], running pending save migrations.
+    pub fn load_from_file(
+        path: &std::path::Path,
+        registry: Arc<Mutex<ComponentRegistry>>,
+    ) -> Result<Self, std::io::Error> {
+        Self::load_from_file_with_report(path, registry).map(|(world, _)| world)
+    }
+
+    /// Load a world from a file, returning a report of the migrations run.
+    pub fn load_from_file_with_report(
+        path: &std::path::Path,
+        registry: Arc<Mutex<ComponentRegistry>>,
+    ) -> Result<(Self, MigrationReport), std::io::Error> {
+        let bytes = std::fs::read(path)?;
+        Self::load_from_value_with_report(decode_save(&bytes)?, registry)
+    }
+
+    /// Register a save migration upgrading saves to save-format `version`.
+    pub fn register_save_migration<F>(&mut self, version: u32, name: &str, migrate: F)
+    where
+        F: Fn(&mut JsonValue, &mut MigrationReport) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.registry
+            .lock()
+            .unwrap()
+            .save_migrations_mut()
+            .register(version, name, migrate);
     }
 
     /// Migrate deprecated `agent.skills` to `SkillLevels` component (R006).
@@ -28,46 +307,57 @@ impl World {
     /// If an entity has an `Agent` component with `skills` populated but no `SkillLevels`
     /// component, auto-populate `SkillLevels.skills` from `agent.skills`.
     /// If the entity already has `SkillLevels`, it takes precedence (no migration).
-    /// Logs a deprecation warning when migration occurs.
+    /// Logs a deprecation warning when migration occurs. Saves older than format
+    /// version 2 get this migration automatically on load.
     pub fn migrate_agent_skills(&mut self) {
-        let agent_entities: Vec<u32> = self
-            .components
-            .get("Agent")
-            .map(|map| map.keys().copied().collect())
-            .unwrap_or_default();
-
-        for &eid in &agent_entities {
-            // Skip if SkillLevels already exists (EC-006)
-            if self
-                .components
-                .get("SkillLevels")
-                .and_then(|map| map.get(&eid))
-                .is_some()
-            {
-                continue;
-            }
-
-            // Check for deprecated agent.skills
-            if let Some(agent) = self.components.get("Agent").and_then(|map| map.get(&eid))
-                && let Some(skills) = agent.get("skills").and_then(|v| v.as_object())
-                && !skills.is_empty()
-            {
-                log::warn!(
-                    "DEPRECATION: entity {eid} uses agent.skills — migrating to SkillLevels component. \
-                     agent.skills will be removed in a future milestone."
-                );
+        save_migration::migrate_agent_skills(&mut self.components);
+    }
+}
 
-                let mut skill_levels_map = Map::new();
-                skill_levels_map.insert("skills".to_string(), JsonValue::Object(skills.clone()));
-                skill_levels_map.insert("total_xp".to_string(), JsonValue::from(0.0));
-                skill_levels_map.insert("skill_xp".to_string(), JsonValue::Object(Map::new()));
-                skill_levels_map.insert("skill_levels".to_string(), JsonValue::Object(Map::new()));
+/// Raise each version in `into` to the one in `from`, adding missing scopes.
+fn merge_versions(into: &mut BTreeMap<String, u32>, from: BTreeMap<String, u32>) {
+    for (scope, version) in from {
+        let current = into.entry(scope).or_insert(0);
+        *current = version.max(*current);
+    }
+}
 
-                self.components
-                    .entry("SkillLevels".to_string())
-                    .or_default()
-                    .insert(eid, JsonValue::Object(skill_levels_map));
+/// Drop migrated component values that fail validation against the current schema.
+fn drop_invalid_migrated_values(
+    save: &mut JsonValue,
+    registry: &Arc<Mutex<ComponentRegistry>>,
+    report: &mut MigrationReport,
+) {
+    let mut components: Vec<String> = report
+        .migrated
+        .iter()
+        .map(|entry| entry.component.clone())
+        .collect();
+    components.sort();
+    components.dedup();
+    for component in components {
+        let Some(schema) = registry
+            .lock()
+            .unwrap()
+            .get_schema_by_name(&component)
+            .map(|s| s.schema.clone())
+        else {
+            continue;
+        };
+        let Ok(validator) = jsonschema::validator_for(&schema) else {
+            continue;
+        };
+        save_migration::for_each_component_value(save, &component, |eid, value| {
+            if let Some(error) = validator.iter_errors(value).next() {
+                report.record_dropped(
+                    &component,
+                    Some(eid),
+                    &format!("invalid after migration: {error}"),
+                );
+                false
+            } else {
+                true
             }
-        }
+        });
     }
 }
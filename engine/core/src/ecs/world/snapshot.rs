@@ -0,0 +1,181 @@
+use super::World;
+use super::save_load::{SaveFormat, decode_save, encode_save};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value as JsonValue;
+use std::io::{Error as IoError, ErrorKind};
+
+/// In-memory copy of the saved state of a [`World`].
+///
+/// Used for quick-save, editor undo and rollback. A snapshot holds the same data as
+/// a save file (binary encoded), so restoring one goes through the regular load path
+/// while keeping the world's registered systems, registries and callbacks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorldSnapshot {
+    turn: u32,
+    bytes: Vec<u8>,
+}
+
+impl WorldSnapshot {
+    /// Rebuild a snapshot from bytes returned by [`as_bytes`](Self::as_bytes).
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, IoError> {
+        let value = decode_save(&bytes)?;
+        let turn = value.get("turn").and_then(|t| t.as_u64()).unwrap_or(0) as u32;
+        Ok(Self { turn, bytes })
+    }
+
+    /// Turn the snapshot was taken at.
+    pub fn turn(&self) -> u32 {
+        self.turn
+    }
+
+    /// Encoded snapshot data (a binary save).
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Size of the encoded snapshot in bytes.
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Returns true if the snapshot holds no data.
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+}
+
+/// Snapshots serialize as the save value they hold, so they can be embedded in
+/// other files (e.g. replay recordings) in any save format.
+impl Serialize for WorldSnapshot {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        decode_save(&self.bytes)
+            .map_err(serde::ser::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for WorldSnapshot {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = JsonValue::deserialize(deserializer)?;
+        let bytes = encode_save(&value, SaveFormat::Binary).map_err(serde::de::Error::custom)?;
+        WorldSnapshot::from_bytes(bytes).map_err(serde::de::Error::custom)
+    }
+}
+
+impl World {
+    /// Take an in-memory snapshot of the world's saved state.
+    pub fn snapshot(&self) -> Result<WorldSnapshot, IoError> {
+        Ok(WorldSnapshot {
+            turn: self.turn,
+            bytes: encode_save(&self.save_to_value()?, SaveFormat::Binary)?,
+        })
+    }
+
+    /// Restore the world's saved state from a snapshot.
+    ///
+    /// Everything a save file holds is replaced; systems, registries, event buses
+    /// and other runtime state are kept. Transient FOV results and change ticks
+    /// are cleared.
+    pub fn restore(&mut self, snapshot: &WorldSnapshot) -> Result<(), IoError> {
+        let loaded = World::load_from_bytes(&snapshot.bytes, self.registry.clone())?;
+        self.replace_saved_state(loaded)
+    }
+
+    /// Move the saved state of `loaded` into this world.
+    ///
+    /// Destructures every field so that adding a field to [`World`] forces a decision
+    /// here on whether it is saved state or runtime state.
+    fn replace_saved_state(&mut self, loaded: World) -> Result<(), IoError> {
+        let World {
+            entities,
+            mut components,
+            typed_components: _,
+            entity_allocator,
+            hierarchy,
+            current_mode,
+            turn,
+            time_of_day,
+            rng,
+            registry: _,
+            systems: _,
+            event_buses: _,
+            dynamic_systems: _,
+            job_types: _,
+            loot_tables,
+            job_handler_registry: _,
+            effect_processor_registry: _,
+            map,
+            visible_cells: _,
+            explored_cells,
+            spatial_index: _,
+            event_queues,
+            map_postprocessors: _,
+            map_validators: _,
+            ai_event_intents: _,
+            resource_definitions,
+            material_definitions,
+            recipes,
+            jobs,
+            tech_tree,
+            tech_unlocks,
+            research_pools,
+            job_board,
+            movement_profiles: _,
+            pathfinding_strategy,
+            fov_algorithm,
+            fov_algorithms: _,
+            recorder: _,
+            system_hashes: _,
+            change_tracker: _,
+            serial_systems: _,
+            system_stages: _,
+            run_conditions: _,
+            timestep: _,
+            mod_ownership: _,
+            scoped_save_versions,
+        } = loaded;
+
+        // Typed columns were saved as JSON; convert their data into fresh columns
+        // before touching this world, so a bad value leaves it unchanged.
+        let mut typed_components = self.typed_components.empty_like();
+        for name in typed_components.names() {
+            let Some(column) = typed_components.column_mut(&name) else {
+                continue;
+            };
+            for (eid, value) in components.remove(&name).unwrap_or_default() {
+                column
+                    .set_json(eid, value)
+                    .map_err(|e| IoError::new(ErrorKind::InvalidData, e))?;
+            }
+        }
+
+        self.typed_components = typed_components;
+        self.entities = entities;
+        self.components = components;
+        self.entity_allocator = entity_allocator;
+        self.hierarchy = hierarchy;
+        self.current_mode = current_mode;
+        self.turn = turn;
+        self.time_of_day = time_of_day;
+        self.rng = rng;
+        self.loot_tables = loot_tables;
+        self.map = map;
+        self.visible_cells.clear();
+        self.explored_cells = explored_cells;
+        self.event_queues = event_queues;
+        self.resource_definitions = resource_definitions;
+        self.material_definitions = material_definitions;
+        self.recipes = recipes;
+        self.jobs = jobs;
+        self.tech_tree = tech_tree;
+        self.tech_unlocks = tech_unlocks;
+        self.research_pools = research_pools;
+        self.pathfinding_strategy = pathfinding_strategy;
+        self.job_board = job_board;
+        self.fov_algorithm = fov_algorithm;
+        self.scoped_save_versions = scoped_save_versions;
+        self.change_tracker.clear();
+        self.rebuild_spatial_index();
+        Ok(())
+    }
+}
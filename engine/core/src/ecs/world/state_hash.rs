@@ -0,0 +1,250 @@
+//! Stable hashing of the simulated world state.
+//!
+//! [`World::state_hash`] covers entities (with the ID allocator and parent links),
+//! components (JSON with object keys in sorted order), turn, time of day, RNG stream
+//! positions and map metadata. The hash does not depend on hash-map iteration order,
+//! platform or process, so two runs that should be identical can be compared tick
+//! by tick.
+//!
+//! With system hash debugging enabled, [`World::simulation_tick`] also records the hash
+//! after every system, so comparing the logs of two runs with [`first_divergence`]
+//! names the system that introduced a difference.
+
+use super::World;
+use super::hierarchy::Hierarchy;
+use crate::ecs::entity::EntityAllocator;
+use crate::map::CellKey;
+use crate::rng::WorldRng;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+
+/// System name under which the hash at the start of each tick is logged.
+pub const TICK_START: &str = "tick_start";
+
+/// State hash recorded after a system ran.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SystemHash {
+    /// Turn being simulated.
+    pub turn: u32,
+    /// System that just ran, or [`TICK_START`].
+    pub system: String,
+    /// [`World::state_hash`] after the system ran.
+    pub hash: u64,
+}
+
+/// Index of the first entry where two system hash logs differ, if any.
+///
+/// Logs of different length that agree on their common prefix differ at the end
+/// of the shorter one.
+pub fn first_divergence(a: &[SystemHash], b: &[SystemHash]) -> Option<usize> {
+    a.iter()
+        .zip(b)
+        .position(|(x, y)| x != y)
+        .or_else(|| (a.len() != b.len()).then(|| a.len().min(b.len())))
+}
+
+/// Every map cell with its metadata, as hashed by [`HashedState`].
+pub(crate) type HashedCells<'a> = Vec<(CellKey, Option<&'a JsonValue>)>;
+
+/// The parts of a world covered by the state hash, shared by [`World`] and
+/// [`WasmWorld`](super::wasm::WasmWorld) so both hash the same state the same way.
+pub(crate) struct HashedState<'a> {
+    pub entities: &'a [u32],
+    pub entity_allocator: &'a EntityAllocator,
+    pub hierarchy: &'a Hierarchy,
+    pub components: &'a HashMap<String, HashMap<u32, JsonValue>>,
+    pub turn: u32,
+    pub day: u64,
+    pub hour: u8,
+    pub minute: u8,
+    pub rng: &'a WorldRng,
+    /// Topology name and every cell with its metadata, if there is a map.
+    pub map: Option<(&'a str, HashedCells<'a>)>,
+}
+
+impl HashedState<'_> {
+    pub(crate) fn hash(self) -> u64 {
+        let mut hasher = StateHasher::default();
+
+        let mut entities = self.entities.to_vec();
+        entities.sort_unstable();
+        hasher.u64(entities.len() as u64);
+        for entity in entities {
+            hasher.u64(entity as u64);
+        }
+        hasher.json(&serde_json::to_value(self.entity_allocator).unwrap_or(JsonValue::Null));
+        hasher.json(&serde_json::to_value(self.hierarchy).unwrap_or(JsonValue::Null));
+
+        let mut names: Vec<&String> = self.components.keys().collect();
+        names.sort();
+        for name in names {
+            hasher.str(name);
+            hasher.entity_values(&self.components[name]);
+        }
+
+        hasher.u64(self.turn as u64);
+        hasher.u64(self.day);
+        hasher.u64(self.hour as u64);
+        hasher.u64(self.minute as u64);
+        hasher.json(&serde_json::to_value(self.rng).unwrap_or(JsonValue::Null));
+
+        match self.map {
+            Some((topology, mut cells)) => {
+                hasher.str(topology);
+                cells.sort_by(|a, b| a.0.cmp(&b.0));
+                hasher.u64(cells.len() as u64);
+                for (cell, metadata) in &cells {
+                    hasher.json(&serde_json::to_value(cell).unwrap_or(JsonValue::Null));
+                    hasher.json(metadata.unwrap_or(&JsonValue::Null));
+                }
+            }
+            None => hasher.bytes(b"-"),
+        }
+        hasher.finish()
+    }
+}
+
+impl World {
+    /// Stable hash of the simulated state.
+    pub fn state_hash(&self) -> u64 {
+        let components = self.components_as_json();
+        HashedState {
+            entities: &self.entities,
+            entity_allocator: &self.entity_allocator,
+            hierarchy: &self.hierarchy,
+            components: &components,
+            turn: self.turn,
+            day: self.time_of_day.day,
+            hour: self.time_of_day.hour,
+            minute: self.time_of_day.minute,
+            rng: &self.rng,
+            map: self.map.as_ref().map(|map| {
+                let cells = map
+                    .all_cells()
+                    .into_iter()
+                    .map(|cell| {
+                        let metadata = map.get_cell_metadata(&cell);
+                        (cell, metadata)
+                    })
+                    .collect();
+                (map.topology_type(), cells)
+            }),
+        }
+        .hash()
+    }
+
+    /// Enable or disable recording a state hash after every system in
+    /// [`simulation_tick`](Self::simulation_tick). Either way the log is cleared.
+    pub fn set_system_hash_debug(&mut self, enabled: bool) {
+        self.system_hashes = enabled.then(Vec::new);
+    }
+
+    /// Returns true if per-system state hashes are being recorded.
+    pub fn system_hash_debug(&self) -> bool {
+        self.system_hashes.is_some()
+    }
+
+    /// Per-system state hashes recorded so far, oldest first.
+    pub fn system_hashes(&self) -> &[SystemHash] {
+        self.system_hashes.as_deref().unwrap_or_default()
+    }
+
+    /// Take the recorded per-system state hashes, leaving the log empty.
+    pub fn take_system_hashes(&mut self) -> Vec<SystemHash> {
+        self.system_hashes
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// Record the state hash after `system` ran, if system hash debugging is on.
+    pub(super) fn record_system_hash(&mut self, system: &str) {
+        if self.system_hashes.is_none() {
+            return;
+        }
+        let entry = SystemHash {
+            turn: self.turn,
+            system: system.to_string(),
+            hash: self.state_hash(),
+        };
+        if let Some(log) = self.system_hashes.as_mut() {
+            log.push(entry);
+        }
+    }
+}
+
+/// FNV-1a hasher over a canonical encoding, stable across platforms and runs.
+pub(crate) struct StateHasher(u64);
+
+impl Default for StateHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl StateHasher {
+    pub(crate) fn bytes(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    pub(crate) fn u64(&mut self, value: u64) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    pub(crate) fn str(&mut self, value: &str) {
+        self.u64(value.len() as u64);
+        self.bytes(value.as_bytes());
+    }
+
+    /// Hash a JSON value with object keys in sorted order.
+    pub(crate) fn json(&mut self, value: &JsonValue) {
+        match value {
+            JsonValue::Null => self.bytes(b"n"),
+            JsonValue::Bool(b) => self.bytes(if *b { b"t" } else { b"f" }),
+            JsonValue::Number(n) => {
+                self.bytes(b"#");
+                self.str(&n.to_string());
+            }
+            JsonValue::String(s) => {
+                self.bytes(b"s");
+                self.str(s);
+            }
+            JsonValue::Array(items) => {
+                self.bytes(b"[");
+                self.u64(items.len() as u64);
+                for item in items {
+                    self.json(item);
+                }
+            }
+            JsonValue::Object(map) => {
+                self.bytes(b"{");
+                self.u64(map.len() as u64);
+                let mut keys: Vec<&String> = map.keys().collect();
+                keys.sort();
+                for key in keys {
+                    self.str(key);
+                    self.json(&map[key]);
+                }
+            }
+        }
+    }
+
+    /// Hash per-entity values in entity ID order.
+    pub(crate) fn entity_values(&mut self, values: &std::collections::HashMap<u32, JsonValue>) {
+        let mut ids: Vec<&u32> = values.keys().collect();
+        ids.sort();
+        self.u64(ids.len() as u64);
+        for id in ids {
+            self.u64(*id as u64);
+            self.json(&values[id]);
+        }
+    }
+
+    pub(crate) fn finish(&self) -> u64 {
+        self.0
+    }
+}
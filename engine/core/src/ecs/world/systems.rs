@@ -1,6 +1,11 @@
 use super::TimeOfDay;
 use super::World;
+use crate::ecs::schedule::{RunCondition, SystemStage, SystemView, plan_batches};
+use crate::ecs::system::{SystemAccess, SystemCommands};
+use crate::mods::ownership::ModResource;
+use rayon::prelude::*;
 use std::cell::RefCell;
+use std::collections::HashSet;
 use std::rc::Rc;
 
 impl World {
@@ -35,6 +40,7 @@ impl World {
     {
         self.dynamic_systems
             .register_system(name.to_string(), Box::new(run));
+        self.record_mod_resource(ModResource::System(name.to_string()));
     }
 
     /// Register a dynamic system with dependencies
@@ -51,6 +57,7 @@ impl World {
             dependencies,
             Box::new(run),
         );
+        self.record_mod_resource(ModResource::System(name.to_string()));
     }
 
     /// Run a dynamic system
@@ -59,13 +66,16 @@ impl World {
         world_rc: Rc<RefCell<World>>,
         name: &str,
     ) -> Result<(), String> {
-        self.dynamic_systems.run_system(world_rc, name, 0.0)
+        self.dynamic_systems
+            .run_system(world_rc, name, self.timestep.step)
     }
 
     /// Run a system
     pub fn run_system(&mut self, name: &str) -> Result<(), String> {
         if let Some(system) = self.systems.take_system(name) {
+            self.begin_system_run(name);
             system.borrow_mut().run(self);
+            self.end_system_run();
             self.systems.register_system_boxed(name.to_string(), system);
             Ok(())
         } else {
@@ -73,39 +83,239 @@ impl World {
         }
     }
 
+    /// Run a system in `stage` instead of its default stage. Works for native and
+    /// dynamic systems, registered or not yet registered.
+    pub fn set_system_stage(&mut self, name: &str, stage: SystemStage) {
+        self.system_stages.insert(name.to_string(), stage);
+    }
+
+    /// Stage a system runs in: its override, else the native system's
+    /// [`System::stage`](crate::ecs::system::System::stage), else
+    /// [`SystemStage::Update`].
+    pub fn system_stage(&self, name: &str) -> SystemStage {
+        if let Some(stage) = self.system_stages.get(name) {
+            return *stage;
+        }
+        self.systems
+            .get_system(name)
+            .map(|system| system.stage())
+            .unwrap_or_default()
+    }
+
+    /// Add a condition a system needs to run during ticks. All conditions of a
+    /// system must hold; they are checked at the start of the system's stage.
+    /// Explicit [`World::run_system`] calls ignore them.
+    pub fn add_run_condition(&mut self, name: &str, condition: RunCondition) {
+        self.run_conditions
+            .entry(name.to_string())
+            .or_default()
+            .push(condition);
+    }
+
+    /// Remove all run conditions of a system.
+    pub fn clear_run_conditions(&mut self, name: &str) {
+        self.run_conditions.remove(name);
+    }
+
+    /// Run conditions of a system.
+    pub fn run_conditions(&self, name: &str) -> &[RunCondition] {
+        self.run_conditions
+            .get(name)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Returns true if all run conditions of a system hold.
+    pub fn should_run_system(&self, name: &str) -> bool {
+        self.run_conditions(name)
+            .iter()
+            .all(|condition| condition.is_met(self))
+    }
+
+    /// Set the length of a fixed tick in seconds (the `dt` dynamic systems get).
+    pub fn set_fixed_timestep(&mut self, step: f32) {
+        self.timestep.step = step;
+        self.timestep.accumulator = 0.0;
+    }
+
+    /// Length of a fixed tick in seconds.
+    pub fn fixed_timestep(&self) -> f32 {
+        self.timestep.step
+    }
+
+    /// Cap the number of ticks one [`World::advance`] call may run.
+    pub fn set_max_ticks_per_advance(&mut self, max_ticks: u32) {
+        self.timestep.max_ticks_per_advance = max_ticks;
+    }
+
+    /// Fraction of a tick accumulated but not yet run, in `[0, 1)`.
+    pub fn timestep_alpha(&self) -> f32 {
+        self.timestep.alpha()
+    }
+
+    /// Enable or disable parallel batches of native systems (enabled by default).
+    ///
+    /// Results are identical either way; disabling is mainly useful for profiling
+    /// and debugging systems that declare their access incorrectly.
+    pub fn set_parallel_systems(&mut self, enabled: bool) {
+        self.serial_systems = !enabled;
+    }
+
+    /// Returns true if native systems may run in parallel batches.
+    pub fn parallel_systems(&self) -> bool {
+        !self.serial_systems
+    }
+
+    /// Native systems grouped into the batches a tick runs them in, in
+    /// execution order. Systems within a batch run in parallel.
+    /// Batches never span stages; run conditions are not taken into account.
+    pub fn system_batches(&self) -> Vec<Vec<String>> {
+        let names = self.ordered_native_systems();
+        SystemStage::ALL
+            .iter()
+            .flat_map(|&stage| {
+                let in_stage: Vec<String> = names
+                    .iter()
+                    .filter(|name| self.system_stage(name) == stage)
+                    .cloned()
+                    .collect();
+                self.plan_system_batches(&in_stage)
+            })
+            .collect()
+    }
+
+    /// Native system names in deterministic execution order (R011).
+    fn ordered_native_systems(&self) -> Vec<String> {
+        let sorted_names = self.systems.sorted_system_names();
+        crate::systems::order_systems(&sorted_names)
+    }
+
+    /// Systems of `names` due to run in `stage` this tick.
+    fn due_systems(&self, names: &[String], stage: SystemStage) -> Vec<String> {
+        names
+            .iter()
+            .filter(|name| self.system_stage(name) == stage && self.should_run_system(name))
+            .cloned()
+            .collect()
+    }
+
+    fn plan_system_batches(&self, names: &[String]) -> Vec<Vec<String>> {
+        let systems: Vec<(String, Option<SystemAccess>)> = names
+            .iter()
+            .filter_map(|name| {
+                let system = self.systems.get_system(name)?;
+                let access = if self.serial_systems {
+                    None
+                } else {
+                    system.access()
+                };
+                Some((name.clone(), access))
+            })
+            .collect();
+        plan_batches(&systems)
+    }
+
+    /// Read-only view of the components a system declared, for its parallel phase.
+    pub fn system_view<'w>(&'w self, name: &str, access: &'w SystemAccess) -> SystemView<'w> {
+        let allowed: HashSet<String> = access
+            .reads
+            .iter()
+            .chain(&access.writes)
+            .filter(|component| self.is_component_allowed_in_mode(component, &self.current_mode))
+            .cloned()
+            .collect();
+        SystemView {
+            access,
+            allowed,
+            entities: &self.entities,
+            components: &self.components,
+            typed_components: &self.typed_components,
+            entity_allocator: &self.entity_allocator,
+            change_tracker: &self.change_tracker,
+            since: self.change_tracker.last_run(name),
+            map: self.map.as_ref(),
+            fov_algorithm: self.fov_algorithm.as_ref(),
+            turn: self.turn,
+            time_of_day: self.time_of_day,
+        }
+    }
+
+    /// Run native systems (given in execution order) batch by batch.
+    fn run_native_systems(&mut self, names: &[String]) {
+        for batch in self.plan_system_batches(names) {
+            // Take the systems out of the registry while they run
+            let mut taken: Vec<(String, RefCell<Box<dyn crate::ecs::system::System>>)> = batch
+                .into_iter()
+                .filter_map(|name| {
+                    let cell = self.systems.take_system(&name)?;
+                    Some((name, cell))
+                })
+                .collect();
+
+            // Parallel phase: compute every system's commands from a read-only view
+            let mut commands: Vec<Option<SystemCommands>> = Vec::new();
+            if taken.len() > 1 {
+                let accesses: Vec<SystemAccess> = taken
+                    .iter()
+                    .map(|(_, cell)| cell.borrow().access().unwrap_or_default())
+                    .collect();
+                let views: Vec<SystemView<'_>> = taken
+                    .iter()
+                    .zip(&accesses)
+                    .map(|((name, _), access)| self.system_view(name, access))
+                    .collect();
+                commands = taken
+                    .par_iter_mut()
+                    .zip(views.par_iter())
+                    .map(|((_, cell), view)| cell.get_mut().run_parallel(view))
+                    .collect();
+            }
+            commands.resize_with(taken.len(), || None);
+
+            // Apply phase, in execution order
+            for ((name, cell), commands) in taken.into_iter().zip(commands) {
+                self.begin_system_run(&name);
+                match commands {
+                    Some(apply) => apply(self),
+                    None => cell.borrow_mut().run(self),
+                }
+                self.end_system_run();
+                self.record_system_hash(&name);
+                self.systems.register_system_boxed(name, cell);
+            }
+        }
+    }
+
     /// Borrow-safe, idiomatic ECS tick.
+    ///
+    /// Runs the stages in order; each stage runs its due native systems, batched
+    /// by declared component access, then its due dynamic systems.
     pub fn simulation_tick(world_rc: Rc<RefCell<World>>) {
-        // Get system names in deterministic execution order (R011)
-        let sorted_names = world_rc.borrow().systems.sorted_system_names();
-        let system_names: Vec<String> = crate::systems::order_systems(&sorted_names);
+        let system_names = world_rc.borrow().ordered_native_systems();
+        let dynamic_names = world_rc.borrow().dynamic_systems.list_systems();
+        world_rc.borrow_mut().record_system_hash(super::TICK_START);
 
-        for name in &system_names {
-            // Take the system out of the registry, drop the borrow immediately
-            let cell = {
-                let mut world = world_rc.borrow_mut();
-                world.systems.take_system(name)
-            };
-            if let Some(cell) = cell {
-                // Run the system, borrow the world only for this scope
-                {
+        for stage in SystemStage::ALL {
+            let native = world_rc.borrow().due_systems(&system_names, stage);
+            world_rc.borrow_mut().run_native_systems(&native);
+
+            let dynamic = world_rc.borrow().due_systems(&dynamic_names, stage);
+            for name in dynamic {
+                let (system, dt) = {
                     let mut world = world_rc.borrow_mut();
-                    let mut system = cell.borrow_mut();
-                    system.run(&mut world);
+                    world.begin_system_run(&name);
+                    (world.dynamic_systems.get_system(&name), world.timestep.step)
+                };
+                // Run without holding a borrow, so the system can mutate the world
+                if let Some(system) = system {
+                    system(Rc::clone(&world_rc), dt);
                 }
-                // Put the system back into the registry
                 let mut world = world_rc.borrow_mut();
-                world.systems.register_system_boxed(name.clone(), cell);
+                world.end_system_run();
+                world.record_system_hash(&name);
             }
         }
 
-        // Dynamic systems
-        let dynamic_names = world_rc.borrow().dynamic_systems.list_systems();
-        for name in dynamic_names {
-            let _ = world_rc
-                .borrow()
-                .run_dynamic_system(Rc::clone(&world_rc), &name);
-        }
-
         {
             let mut world = world_rc.borrow_mut();
             world.update_event_buses::<serde_json::Value>();
@@ -115,8 +325,21 @@ impl World {
 
     /// Borrow-safe, idiomatic ECS tick
     pub fn tick(world_rc: Rc<RefCell<World>>) {
+        world_rc.borrow_mut().begin_recorded_tick();
         World::simulation_tick(Rc::clone(&world_rc));
-        world_rc.borrow_mut().advance_time_of_day();
+        let mut world = world_rc.borrow_mut();
+        world.advance_time_of_day();
+        world.end_recorded_tick();
+    }
+
+    /// Advance the simulation by real elapsed time (in seconds), running as many
+    /// fixed ticks as the accumulated time pays for. Returns the number of ticks run.
+    pub fn advance(world_rc: Rc<RefCell<World>>, elapsed: f32) -> u32 {
+        let ticks = world_rc.borrow_mut().timestep.accumulate(elapsed);
+        for _ in 0..ticks {
+            World::tick(Rc::clone(&world_rc));
+        }
+        ticks
     }
 
     fn advance_time_of_day(&mut self) {
@@ -1,9 +1,18 @@
-use crate::loot::LootTableRegistry;
-use crate::map::CellKey;
+use super::change_detection::{ChangeTracker, ComponentTicks};
+use super::hierarchy::{DespawnPolicy, Hierarchy};
+use super::state_hash::{HashedState, SystemHash, TICK_START};
+use crate::config::GameConfig;
+use crate::ecs::entity::EntityAllocator;
+use crate::loot::{LootError, LootTableRegistry};
+use crate::map::flow_field::FlowFieldCache;
+use crate::map::{CellKey, DijkstraMap, MovementProfile, SpatialIndex};
+use crate::rng::{self, WorldRng};
+use crate::tech_tree::TechTree;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
+use std::sync::{Arc, Mutex};
 
 /// Time of day
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -63,6 +72,9 @@ pub struct WasmMap {
     pub neighbors: HashMap<String, Vec<String>>,
     /// Per-cell metadata: canonical CellKey JSON string → metadata JSON
     pub cell_metadata: HashMap<String, JsonValue>,
+    /// Links found by the last `connect_levels`, as canonical CellKey JSON strings
+    #[serde(skip)]
+    vertical_links: HashSet<(String, String)>,
 }
 
 /// Injectable input source for `WasmWorld::get_user_input()`.
@@ -85,7 +97,18 @@ pub struct WasmWorld {
     pub entities: Vec<u32>,
     /// Components
     pub components: HashMap<String, HashMap<u32, JsonValue>>,
-    next_id: u32,
+    /// Generational entity ID allocator (rebuilt from `entities` for older saves).
+    #[serde(default)]
+    entity_allocator: EntityAllocator,
+    /// Parent/child links between entities.
+    #[serde(default)]
+    hierarchy: Hierarchy,
+    /// Change ticks of components written through `set_component`.
+    #[serde(skip)]
+    change_tracker: ChangeTracker,
+    /// Entities by the cell of their `Position` (rebuilt on load).
+    #[serde(skip)]
+    spatial_index: SpatialIndex,
     /// Current game mode
     pub current_mode: String,
     /// Current turn
@@ -108,8 +131,17 @@ pub struct WasmWorld {
     #[serde(default)]
     pub component_schemas: HashMap<String, JsonValue>,
     /// Map data for spatial operations
+    ///
+    /// Call [`WasmWorld::mark_map_changed`] after changing it directly, so
+    /// cached Dijkstra maps are recomputed.
     #[serde(default)]
     pub map: Option<WasmMap>,
+    /// Map revision, bumped by every change made through the WasmWorld.
+    #[serde(skip)]
+    map_revision: u64,
+    /// Dijkstra maps for the current map revision.
+    #[serde(skip)]
+    flow_fields: Mutex<FlowFieldCache>,
     /// Export names discovered during WASM module instantiation
     #[serde(default)]
     pub discovered_export_names: Vec<String>,
@@ -198,10 +230,22 @@ pub struct WasmWorld {
     #[serde(skip)]
     pub loot_tables: LootTableRegistry,
 
+    /// Seeded RNG streams (serialized so runs are reproducible).
+    #[serde(default)]
+    pub rng: WorldRng,
+
+    /// Per-system state hashes, recorded while system hash debugging is on.
+    #[serde(skip)]
+    system_hashes: Option<Vec<SystemHash>>,
+
     /// Material definitions loaded at initialization: name → JSON properties.
     #[serde(default)]
     pub material_definitions: HashMap<String, JsonValue>,
 
+    /// Tech tree loaded at initialization.
+    #[serde(default)]
+    pub tech_tree: TechTree,
+
     /// Active FOV algorithm name (for display/debugging).
     #[serde(skip, default = "default_fov_algo_name")]
     pub fov_algorithm_name: String,
@@ -239,13 +283,26 @@ pub fn load_schemas_from_dir(schema_dir: &Path) -> HashMap<String, JsonValue> {
     schemas
 }
 
+/// Serializes a Dijkstra map as a JSON array of `{"cell", "distance"}` entries.
+fn dijkstra_map_to_json(map: &DijkstraMap) -> Option<String> {
+    let entries: Vec<JsonValue> = map
+        .entries()
+        .into_iter()
+        .map(|(cell, distance)| serde_json::json!({ "cell": cell, "distance": distance }))
+        .collect();
+    serde_json::to_string(&entries).ok()
+}
+
 impl WasmWorld {
     /// Create a new world
     pub fn new() -> Self {
         WasmWorld {
             entities: Vec::new(),
             components: HashMap::new(),
-            next_id: 1,
+            entity_allocator: EntityAllocator::new(),
+            hierarchy: Hierarchy::default(),
+            change_tracker: ChangeTracker::default(),
+            spatial_index: SpatialIndex::new(),
             current_mode: "colony".to_string(),
             turn: 0,
             time_of_day: TimeOfDay {
@@ -259,6 +316,8 @@ impl WasmWorld {
             systems: HashMap::new(),
             component_schemas: HashMap::new(),
             map: None,
+            map_revision: 0,
+            flow_fields: Mutex::new(FlowFieldCache::default()),
             discovered_export_names: Vec::new(),
             map_validator_names: Vec::new(),
             map_postprocessor_names: Vec::new(),
@@ -282,28 +341,95 @@ impl WasmWorld {
             ui_event_queue: Vec::new(),
             focused_widget: 0,
             loot_tables: LootTableRegistry::new(),
+            rng: WorldRng::default(),
+            system_hashes: None,
             material_definitions: HashMap::new(),
+            tech_tree: TechTree::new(),
             fov_algorithm_name: "recursive_shadowcasting".to_string(),
             input_source: InputSource::default(),
         }
     }
 
+    /// Create a new world for a game config, seeding the RNG from its `seed`.
+    pub fn with_config(config: &GameConfig) -> Self {
+        let mut world = Self::new();
+        world.rng = WorldRng::from_config(config);
+        world
+    }
+
     /// Spawn a new entity
     pub fn spawn_entity(&mut self) -> u32 {
-        let id = self.next_id;
-        self.next_id += 1;
+        let id = self.entity_allocator.allocate();
         self.entities.push(id);
         id
     }
 
-    /// Despawn an entity
+    /// Despawn an entity. Despawning a stale handle does nothing.
+    /// Children are despawned or reparented according to their despawn policy.
     pub fn despawn_entity(&mut self, entity: u32) {
+        if self.entity_allocator.is_stale(entity) {
+            return;
+        }
+        let (parent, children) = self.hierarchy.detach(entity);
+        for (child, policy) in children {
+            match (policy, parent) {
+                (DespawnPolicy::Cascade, _) => self.despawn_entity(child),
+                (DespawnPolicy::Reparent, Some(parent)) => {
+                    let _ = self.hierarchy.set_parent(child, parent, policy);
+                }
+                (DespawnPolicy::Reparent, None) => {}
+            }
+        }
+        self.entity_allocator.free(entity);
+        self.change_tracker.forget_entity(entity);
+        self.spatial_index.remove(entity);
         for comps in self.components.values_mut() {
             comps.remove(&entity);
         }
         self.entities.retain(|&id| id != entity);
     }
 
+    /// Returns true if the ID refers to a despawned entity or an older generation
+    /// of a reused entity slot.
+    pub fn is_stale_entity(&self, entity: u32) -> bool {
+        self.entity_allocator.is_stale(entity)
+    }
+
+    /// Make `parent` the parent of `child` with the given despawn policy.
+    pub fn set_parent(
+        &mut self,
+        child: u32,
+        parent: u32,
+        policy: DespawnPolicy,
+    ) -> Result<(), String> {
+        for entity in [child, parent] {
+            if !self.entities.contains(&entity) {
+                return Err(format!("Entity {entity} does not exist"));
+            }
+        }
+        self.hierarchy.set_parent(child, parent, policy)
+    }
+
+    /// Detach `child` from its parent. Returns the former parent, if any.
+    pub fn remove_parent(&mut self, child: u32) -> Option<u32> {
+        self.hierarchy.remove_parent(child)
+    }
+
+    /// Parent of an entity, if any.
+    pub fn parent_of(&self, entity: u32) -> Option<u32> {
+        self.hierarchy.parent(entity)
+    }
+
+    /// Direct children of an entity, in ascending ID order.
+    pub fn children_of(&self, entity: u32) -> Vec<u32> {
+        self.hierarchy.children(entity)
+    }
+
+    /// Ancestors of an entity, nearest first.
+    pub fn ancestors(&self, entity: u32) -> Vec<u32> {
+        self.hierarchy.ancestors(entity)
+    }
+
     /// Get all entities
     pub fn get_entities(&self) -> &[u32] {
         &self.entities
@@ -349,6 +475,9 @@ impl WasmWorld {
 
     /// Move an entity
     pub fn move_entity(&mut self, entity_id: u32, dx: f32, dy: f32) {
+        if self.is_stale_entity(entity_id) {
+            return;
+        }
         // This implementation assumes a "Position" component with "x" and "y" fields.
         let comps = self.components.entry("Position".to_string()).or_default();
         let pos = comps
@@ -359,6 +488,7 @@ impl WasmWorld {
         let y = pos.get("y").and_then(|v| v.as_f64()).unwrap_or(0.0) + dy as f64;
 
         *pos = serde_json::json!({"x": x, "y": y});
+        self.index_position(entity_id);
     }
 
     /// Damage an entity.
@@ -366,6 +496,9 @@ impl WasmWorld {
     /// Routes through PendingDamage when the entity has a Body component,
     /// otherwise falls back to direct Health subtraction.
     pub fn damage_entity(&mut self, entity_id: u32, amount: f32) {
+        if self.is_stale_entity(entity_id) {
+            return;
+        }
         let has_body = self
             .components
             .get("Body")
@@ -386,6 +519,9 @@ impl WasmWorld {
 
     /// Apply targeted damage to a specific body part.
     pub fn damage_entity_part(&mut self, entity_id: u32, part_name: &str, amount: f32) {
+        if self.is_stale_entity(entity_id) {
+            return;
+        }
         let has_body = self
             .components
             .get("Body")
@@ -636,17 +772,33 @@ impl WasmWorld {
         component_name: &str,
         json_data: &str,
     ) -> Result<(), String> {
+        if self.is_stale_entity(entity_id) {
+            return Err(format!(
+                "Entity {entity_id} is stale (despawned or its ID was reused)"
+            ));
+        }
         let value: JsonValue = serde_json::from_str(json_data)
             .map_err(|e| format!("Failed to parse component JSON: {e}"))?;
-        self.components
+        let old = self
+            .components
             .entry(component_name.to_string())
             .or_default()
-            .insert(entity_id, value);
+            .insert(entity_id, value.clone());
+        if old.as_ref() != Some(&value) {
+            self.change_tracker
+                .mark_changed(entity_id, component_name, old.is_none());
+        }
+        if component_name == "Position" {
+            self.index_position(entity_id);
+        }
         Ok(())
     }
 
     /// Get a component from an entity as a JSON string.
     pub fn get_component(&self, entity_id: u32, component_name: &str) -> Option<String> {
+        if self.is_stale_entity(entity_id) {
+            return None;
+        }
         self.components
             .get(component_name)?
             .get(&entity_id)
@@ -666,13 +818,28 @@ impl WasmWorld {
         if comps.is_empty() {
             self.components.remove(component_name);
         }
+        self.change_tracker.mark_removed(entity_id, component_name);
+        if component_name == "Position" {
+            self.spatial_index.remove(entity_id);
+        }
         Ok(())
     }
 
+    /// Current change tick (advanced by every `set_component`/`remove_component`).
+    pub fn change_tick(&self) -> u64 {
+        self.change_tracker.tick()
+    }
+
+    /// Change ticks of a component value written through `set_component`.
+    pub fn component_ticks(&self, entity: u32, name: &str) -> Option<ComponentTicks> {
+        self.change_tracker.ticks(entity, name)
+    }
+
     /// Advance the simulation by one tick.
     pub fn tick(&mut self) {
         self.turn += 1;
         self.advance_time_of_day();
+        self.record_system_hash(TICK_START);
     }
 
     /// Returns the current turn number.
@@ -872,8 +1039,24 @@ impl WasmWorld {
     /// Deserializes world state from a JSON file, replacing current state.
     pub fn load_from_file(&mut self, path: &str) -> Result<(), String> {
         let json = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
-        let loaded: WasmWorld = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+        let mut loaded: WasmWorld = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+        if loaded.entity_allocator == EntityAllocator::default() {
+            let next_id = loaded
+                .entities
+                .iter()
+                .max()
+                .map_or(1, |max| max.saturating_add(1));
+            loaded.entity_allocator = EntityAllocator::from_legacy(next_id, &loaded.entities)?;
+        }
         *self = loaded;
+        let positioned: Vec<u32> = self
+            .components
+            .get("Position")
+            .map(|m| m.keys().copied().collect())
+            .unwrap_or_default();
+        for entity in positioned {
+            self.index_position(entity);
+        }
         Ok(())
     }
 
@@ -957,8 +1140,9 @@ impl WasmWorld {
 
     /// Runs a registered system by name.
     /// Currently checks for system existence; execution stub for WASM callback integration.
-    pub fn run_system(&self, name: &str) -> Result<(), String> {
+    pub fn run_system(&mut self, name: &str) -> Result<(), String> {
         if self.systems.contains_key(name) {
+            self.record_system_hash(name);
             Ok(())
         } else {
             Err(format!("System '{name}' not found"))
@@ -1470,7 +1654,7 @@ impl WasmWorld {
 
     /// Adds a square cell at (x, y, z). Sets topology type to "square" if unset.
     pub fn add_cell(&mut self, x: i32, y: i32, z: i32) {
-        let map = self.map.get_or_insert_with(WasmMap::default);
+        let map = self.map_mut();
         if map.topology_type.is_empty() || map.topology_type == "none" {
             map.topology_type = "square".to_string();
         }
@@ -1482,25 +1666,11 @@ impl WasmWorld {
 
     /// Returns neighbors of a cell as a JSON array string.
     pub fn get_neighbors(&self, cell_json: &str) -> String {
-        let map = match self.map.as_ref() {
-            Some(m) => m,
-            None => return "[]".to_string(),
-        };
         let cell_key: CellKey = match serde_json::from_str(cell_json) {
             Ok(k) => k,
             Err(_) => return "[]".to_string(),
         };
-        let key = serde_json::to_string(&cell_key).unwrap_or_default();
-        map.neighbors
-            .get(&key)
-            .map(|neighbors| {
-                let cells: Vec<CellKey> = neighbors
-                    .iter()
-                    .filter_map(|n| serde_json::from_str(n).ok())
-                    .collect();
-                serde_json::to_string(&cells).unwrap_or_else(|_| "[]".to_string())
-            })
-            .unwrap_or_else(|| "[]".to_string())
+        serde_json::to_string(&self.map_neighbors(&cell_key)).unwrap_or_else(|_| "[]".to_string())
     }
 
     /// Adds a bidirectional neighbor edge between two cells.
@@ -1510,7 +1680,7 @@ impl WasmWorld {
         let to_key: CellKey =
             serde_json::from_str(to_json).map_err(|e| format!("Invalid to cell: {e}"))?;
 
-        let map = self.map.get_or_insert_with(WasmMap::default);
+        let map = self.map_mut();
         let from_str = serde_json::to_string(&from_key).unwrap();
         let to_str = serde_json::to_string(&to_key).unwrap();
 
@@ -1531,45 +1701,101 @@ impl WasmWorld {
 
     /// Returns entity IDs whose Position component matches the given cell.
     pub fn entities_in_cell(&self, cell_json: &str) -> Vec<u32> {
-        let cell_key: CellKey = match serde_json::from_str(cell_json) {
-            Ok(k) => k,
-            Err(_) => return vec![],
+        match serde_json::from_str::<CellKey>(cell_json) {
+            Ok(cell) => self.spatial_index.entities_in_cell(&cell),
+            Err(_) => vec![],
+        }
+    }
+
+    /// Returns entity IDs within `radius` of the given cell on its z-level.
+    pub fn entities_in_radius(&self, cell_json: &str, radius: f64) -> Vec<u32> {
+        match serde_json::from_str::<CellKey>(cell_json) {
+            Ok(cell) => self
+                .spatial_index
+                .entities_in_radius(&cell, radius, |c| self.map_neighbors(c)),
+            Err(_) => vec![],
+        }
+    }
+
+    /// Returns entity IDs in the box spanned by two square or hex corner cells.
+    pub fn entities_in_rect(&self, a_json: &str, b_json: &str) -> Vec<u32> {
+        match (
+            serde_json::from_str::<CellKey>(a_json),
+            serde_json::from_str::<CellKey>(b_json),
+        ) {
+            (Ok(a), Ok(b)) => self.spatial_index.entities_in_rect(&a, &b),
+            _ => vec![],
+        }
+    }
+
+    /// Returns the entity with `component` nearest to the given cell.
+    pub fn nearest_entity_with_component(
+        &self,
+        cell_json: &str,
+        component: &str,
+        max_radius: Option<f64>,
+    ) -> Option<u32> {
+        let cell: CellKey = serde_json::from_str(cell_json).ok()?;
+        self.spatial_index.nearest(
+            &cell,
+            self.get_entities_with_component(component),
+            max_radius,
+            |c| self.map_neighbors(c),
+        )
+    }
+
+    /// Files an entity under the cell of its Position. Accepts the core
+    /// `{"pos": {"Square": ...}}` form and the flat `{x, y, z}`, `{q, r, z}`
+    /// and `{id}` forms.
+    fn index_position(&mut self, entity: u32) {
+        let cell = self
+            .components
+            .get("Position")
+            .and_then(|m| m.get(&entity))
+            .and_then(|pos| {
+                CellKey::from_position(pos).or_else(|| {
+                    let coord = |k: &str| pos.get(k).and_then(|v| v.as_f64()).unwrap_or(0.0) as i32;
+                    if let Some(id) = pos.get("id").and_then(|v| v.as_str()) {
+                        Some(CellKey::Province { id: id.to_string() })
+                    } else if pos.get("q").is_some() {
+                        Some(CellKey::Hex {
+                            q: coord("q"),
+                            r: coord("r"),
+                            z: coord("z"),
+                        })
+                    } else if pos.get("x").is_some() {
+                        Some(CellKey::Square {
+                            x: coord("x"),
+                            y: coord("y"),
+                            z: coord("z"),
+                        })
+                    } else {
+                        None
+                    }
+                })
+            });
+        match cell {
+            Some(cell) => self.spatial_index.insert(entity, cell),
+            None => {
+                self.spatial_index.remove(entity);
+            }
+        }
+    }
+
+    fn map_neighbors(&self, cell: &CellKey) -> Vec<CellKey> {
+        let Some(map) = self.map.as_ref() else {
+            return vec![];
         };
-        self.entities
-            .iter()
-            .copied()
-            .filter(|&eid| {
-                self.components
-                    .get("Position")
-                    .and_then(|m| m.get(&eid))
-                    .is_some_and(|pos| match &cell_key {
-                        CellKey::Square {
-                            x: cx,
-                            y: cy,
-                            z: cz,
-                        } => {
-                            let px = pos.get("x").and_then(|v| v.as_f64()).unwrap_or(0.0) as i32;
-                            let py = pos.get("y").and_then(|v| v.as_f64()).unwrap_or(0.0) as i32;
-                            let pz = pos.get("z").and_then(|v| v.as_f64()).unwrap_or(0.0) as i32;
-                            *cx == px && *cy == py && *cz == pz
-                        }
-                        CellKey::Hex {
-                            q: cq,
-                            r: cr,
-                            z: cz,
-                        } => {
-                            let pq = pos.get("q").and_then(|v| v.as_f64()).unwrap_or(0.0) as i32;
-                            let pr = pos.get("r").and_then(|v| v.as_f64()).unwrap_or(0.0) as i32;
-                            let pz = pos.get("z").and_then(|v| v.as_f64()).unwrap_or(0.0) as i32;
-                            *cq == pq && *cr == pr && *cz == pz
-                        }
-                        CellKey::Province { id: cid } => pos
-                            .get("id")
-                            .and_then(|v| v.as_str())
-                            .is_some_and(|pid| pid == cid),
-                    })
+        let key = serde_json::to_string(cell).unwrap_or_default();
+        map.neighbors
+            .get(&key)
+            .map(|neighbors| {
+                neighbors
+                    .iter()
+                    .filter_map(|n| serde_json::from_str(n).ok())
+                    .collect()
             })
-            .collect()
+            .unwrap_or_default()
     }
 
     /// Returns cell metadata for a cell, or None if absent.
@@ -1589,7 +1815,7 @@ impl WasmWorld {
             && let Ok(meta) = serde_json::from_str::<JsonValue>(meta_json)
         {
             let key = serde_json::to_string(&cell_key).unwrap();
-            let map = self.map.get_or_insert_with(WasmMap::default);
+            let map = self.map_mut();
             map.cell_metadata.insert(key, meta);
         }
     }
@@ -1660,11 +1886,194 @@ impl WasmWorld {
         serde_json::to_string(&result).ok()
     }
 
-    /// Replaces the entire WasmMap with parsed JSON.
+    /// Dijkstra map towards a JSON array of goal cells, as a JSON array of
+    /// `{"cell", "distance"}` entries. Honours `walkable` and `cost` metadata.
+    pub fn dijkstra_map(&self, goals_json: &str) -> Option<String> {
+        let goals: Vec<CellKey> = serde_json::from_str(goals_json).ok()?;
+        dijkstra_map_to_json(&self.build_dijkstra_map(&goals))
+    }
+
+    /// Map leading away from a JSON array of threat cells, in the format of
+    /// [`WasmWorld::dijkstra_map`].
+    pub fn flee_map(&self, threats_json: &str, factor: f32) -> Option<String> {
+        let threats: Vec<CellKey> = serde_json::from_str(threats_json).ok()?;
+        dijkstra_map_to_json(&self.build_flee_map(&threats, factor))
+    }
+
+    /// Next cell (JSON) from `cell_json` towards the nearest goal cell, or None.
+    pub fn flow_step(&self, goals_json: &str, cell_json: &str) -> Option<String> {
+        let goals: Vec<CellKey> = serde_json::from_str(goals_json).ok()?;
+        let cell: CellKey = serde_json::from_str(cell_json).ok()?;
+        let next = self
+            .build_dijkstra_map(&goals)
+            .next_step(&cell, |c| self.map_neighbors(c))?;
+        serde_json::to_string(&next).ok()
+    }
+
+    /// Next cell (JSON) from `cell_json` away from the threat cells, or None.
+    pub fn flee_step(&self, threats_json: &str, cell_json: &str, factor: f32) -> Option<String> {
+        let threats: Vec<CellKey> = serde_json::from_str(threats_json).ok()?;
+        let cell: CellKey = serde_json::from_str(cell_json).ok()?;
+        let next = self
+            .build_flee_map(&threats, factor)
+            .next_step(&cell, |c| self.map_neighbors(c))?;
+        serde_json::to_string(&next).ok()
+    }
+
+    /// Bump the map revision, invalidating cached Dijkstra maps.
+    pub fn mark_map_changed(&mut self) {
+        self.map_revision += 1;
+    }
+
+    /// The map for writing; counts as a change.
+    fn map_mut(&mut self) -> &mut WasmMap {
+        self.mark_map_changed();
+        self.map.get_or_insert_with(WasmMap::default)
+    }
+
+    /// Dijkstra map over the WasmMap for the walker profile, cached per goal
+    /// set like [`Map::dijkstra_map`](crate::map::Map::dijkstra_map).
+    fn build_dijkstra_map(&self, goals: &[CellKey]) -> Arc<DijkstraMap> {
+        let walker = MovementProfile::walker();
+        let mut cache = self.flow_fields.lock().unwrap();
+        if let Some(map) = cache.get(self.map_revision, &walker, goals, None) {
+            return map;
+        }
+        let map = Arc::new(match self.map.as_ref() {
+            Some(map) => {
+                let links = self.reverse_links();
+                DijkstraMap::from_seeds_with_climb(
+                    goals
+                        .iter()
+                        .filter(|goal| map.cells.contains(goal))
+                        .map(|goal| (goal.clone(), 0.0)),
+                    |c| links.get(c).cloned().unwrap_or_default(),
+                    |c| walker.cost(self.map_metadata(c)),
+                    |from, to| {
+                        walker.climb_cost(from, to, self.map_metadata(from), self.map_metadata(to))
+                    },
+                )
+            }
+            None => DijkstraMap::default(),
+        });
+        cache.insert(self.map_revision, &walker, goals, None, map.clone());
+        map
+    }
+
+    /// Flee map over the WasmMap for the walker profile, cached like
+    /// [`WasmWorld::build_dijkstra_map`].
+    fn build_flee_map(&self, threats: &[CellKey], factor: f32) -> Arc<DijkstraMap> {
+        let walker = MovementProfile::walker();
+        if let Some(map) =
+            self.flow_fields
+                .lock()
+                .unwrap()
+                .get(self.map_revision, &walker, threats, Some(factor))
+        {
+            return map;
+        }
+        let links = self.reverse_links();
+        let map = Arc::new(self.build_dijkstra_map(threats).flee_with_climb(
+            factor,
+            |c| links.get(c).cloned().unwrap_or_default(),
+            |c| walker.cost(self.map_metadata(c)),
+            |from, to| walker.climb_cost(from, to, self.map_metadata(from), self.map_metadata(to)),
+        ));
+        self.flow_fields.lock().unwrap().insert(
+            self.map_revision,
+            &walker,
+            threats,
+            Some(factor),
+            map.clone(),
+        );
+        map
+    }
+
+    /// Links stairs, ramps and ladders to the level above and drops the links
+    /// of removed connectors, like
+    /// [`Map::connect_levels`](crate::map::Map::connect_levels). Returns the
+    /// number of links added.
+    pub fn connect_levels(&mut self) -> usize {
+        let Some(map) = self.map.as_ref() else {
+            return 0;
+        };
+        let current: HashSet<(String, String)> = crate::map::vertical::vertical_links(
+            &map.cells,
+            |c| map.cells.contains(c),
+            |c| self.map_metadata(c),
+        )
+        .into_iter()
+        .filter_map(|(from, to)| {
+            Some((
+                serde_json::to_string(&from).ok()?,
+                serde_json::to_string(&to).ok()?,
+            ))
+        })
+        .collect();
+        let stale: Vec<(String, String)> =
+            map.vertical_links.difference(&current).cloned().collect();
+        let links: Vec<(String, String)> = current
+            .iter()
+            .filter(|(from, to)| {
+                !map.neighbors
+                    .get(from)
+                    .is_some_and(|neighbors| neighbors.contains(to))
+            })
+            .cloned()
+            .collect();
+        if links.is_empty() && stale.is_empty() {
+            if let Some(map) = self.map.as_mut() {
+                map.vertical_links = current;
+            }
+            return 0;
+        }
+        let map = self.map_mut();
+        map.vertical_links = current;
+        for (from, to) in &stale {
+            if let Some(neighbors) = map.neighbors.get_mut(from) {
+                neighbors.retain(|n| n != to);
+            }
+        }
+        for (from, to) in &links {
+            map.neighbors
+                .entry(from.clone())
+                .or_default()
+                .push(to.clone());
+        }
+        links.len()
+    }
+
+    /// For every cell, the cells with a neighbor link into it.
+    fn reverse_links(&self) -> HashMap<CellKey, Vec<CellKey>> {
+        let mut links: HashMap<CellKey, Vec<CellKey>> = HashMap::new();
+        let Some(map) = self.map.as_ref() else {
+            return links;
+        };
+        for (from, neighbors) in &map.neighbors {
+            let Ok(from) = serde_json::from_str::<CellKey>(from) else {
+                continue;
+            };
+            for to in neighbors {
+                if let Ok(to) = serde_json::from_str(to) {
+                    links.entry(to).or_default().push(from.clone());
+                }
+            }
+        }
+        links
+    }
+
+    fn map_metadata(&self, cell: &CellKey) -> Option<&JsonValue> {
+        let key = serde_json::to_string(cell).ok()?;
+        self.map.as_ref()?.cell_metadata.get(&key)
+    }
+
+    /// Replaces the entire WasmMap with parsed JSON and links its levels.
     pub fn apply_generated_map(&mut self, map_json: &str) -> Result<(), String> {
         let parsed: WasmMap =
             serde_json::from_str(map_json).map_err(|e| format!("Failed to parse map JSON: {e}"))?;
         self.map = Some(parsed);
+        self.mark_map_changed();
+        self.connect_levels();
         Ok(())
     }
 
@@ -1751,7 +2160,7 @@ impl WasmWorld {
 
         // Parse and add metadata
         if let Some(metadata) = chunk.get("metadata").and_then(|v| v.as_object()) {
-            let map = self.map.get_or_insert_with(WasmMap::default);
+            let map = self.map_mut();
             for (cell_key_str, meta) in metadata {
                 map.cell_metadata.insert(cell_key_str.clone(), meta.clone());
             }
@@ -2439,6 +2848,103 @@ impl WasmWorld {
         self.job_event_log.clear();
     }
 
+    // ---- RNG API ----
+
+    /// Reseed the world RNG, restarting every stream.
+    pub fn seed_rng(&mut self, seed: u64) {
+        self.rng.reseed(seed);
+    }
+
+    /// Master seed of the world RNG.
+    pub fn rng_seed(&self) -> u64 {
+        self.rng.seed()
+    }
+
+    /// Draw a random float in `[0, 1)` from a named stream.
+    pub fn random_f64(&mut self, stream: &str) -> f64 {
+        self.rng.next_f64(stream)
+    }
+
+    /// Draw a random integer in `[min, max]` from a named stream.
+    pub fn random_range(&mut self, stream: &str, min: i64, max: i64) -> i64 {
+        self.rng.range_i64(stream, min, max)
+    }
+
+    /// Roll a loot table using the world's loot stream.
+    pub fn roll_loot(&mut self, table: &str) -> Result<Vec<(String, u32)>, LootError> {
+        let rng = self.rng.stream(rng::LOOT);
+        self.loot_tables.roll(table, rng)
+    }
+
+    // ---- State hash ----
+
+    /// Stable hash of the simulated state (entities, components, turn, time of day,
+    /// RNG stream positions and map metadata), as [`World::state_hash`](super::World::state_hash).
+    pub fn state_hash(&self) -> u64 {
+        HashedState {
+            entities: &self.entities,
+            entity_allocator: &self.entity_allocator,
+            hierarchy: &self.hierarchy,
+            components: &self.components,
+            turn: self.turn,
+            day: self.time_of_day.day,
+            hour: self.time_of_day.hour,
+            minute: self.time_of_day.minute,
+            rng: &self.rng,
+            map: self.map.as_ref().map(|map| {
+                let cells = map
+                    .cells
+                    .iter()
+                    .map(|cell| {
+                        let key = serde_json::to_string(cell).unwrap_or_default();
+                        (cell.clone(), map.cell_metadata.get(&key))
+                    })
+                    .collect();
+                (map.topology_type.as_str(), cells)
+            }),
+        }
+        .hash()
+    }
+
+    /// Enable or disable recording a state hash at every [`tick`](Self::tick) and
+    /// after every [`run_system`](Self::run_system). Either way the log is cleared.
+    pub fn set_system_hash_debug(&mut self, enabled: bool) {
+        self.system_hashes = enabled.then(Vec::new);
+    }
+
+    /// Returns true if per-system state hashes are being recorded.
+    pub fn system_hash_debug(&self) -> bool {
+        self.system_hashes.is_some()
+    }
+
+    /// Per-system state hashes recorded so far, oldest first.
+    pub fn system_hashes(&self) -> &[SystemHash] {
+        self.system_hashes.as_deref().unwrap_or_default()
+    }
+
+    /// Take the recorded per-system state hashes, leaving the log empty.
+    pub fn take_system_hashes(&mut self) -> Vec<SystemHash> {
+        self.system_hashes
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// Record the state hash after `system` ran, if system hash debugging is on.
+    fn record_system_hash(&mut self, system: &str) {
+        if self.system_hashes.is_none() {
+            return;
+        }
+        let entry = SystemHash {
+            turn: self.turn,
+            system: system.to_string(),
+            hash: self.state_hash(),
+        };
+        if let Some(log) = self.system_hashes.as_mut() {
+            log.push(entry);
+        }
+    }
+
     fn advance_time_of_day(&mut self) {
         self.time_of_day.minute += 1;
         if self.time_of_day.minute >= 60 {
@@ -2474,3 +2980,13 @@ impl ResourceReservationOps for WasmWorld {
         self.set_component(entity, name, &json_str)
     }
 }
+
+impl crate::tech_tree::ResearchOps for WasmWorld {
+    fn research_tech_tree(&self) -> &TechTree {
+        &self.tech_tree
+    }
+
+    fn send_research_event(&mut self, event_type: &str, payload: JsonValue) -> Result<(), String> {
+        self.send_event(event_type, &payload.to_string())
+    }
+}
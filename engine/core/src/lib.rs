@@ -22,8 +22,12 @@ pub mod mods;
 pub mod plugins;
 /// Presentation module
 pub mod presentation;
+/// Seeded, world-owned RNG streams
+pub mod rng;
 /// Systems module
 pub mod systems;
+/// Tech effects: recipe and job unlocks, stat modifiers and production multipliers
+pub mod tech_effects;
 /// Tech tree and research system
 pub mod tech_tree;
 /// Worldgen module
@@ -61,9 +61,9 @@ impl std::error::Error for LootError {}
 
 /// Registry holding all defined loot tables.
 ///
-/// Tables are defined at runtime via `define_table()` and are not serialized
-/// (the `World` field is marked `#[serde(skip)]`).
-#[derive(Debug, Default)]
+/// Tables are defined at runtime via `define_table()` and are persisted with
+/// the `World` in save files.
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct LootTableRegistry {
     tables: HashMap<String, LootTable>,
 }
@@ -119,10 +119,18 @@ impl LootTableRegistry {
     /// and returns the entry whose cumulative weight range contains the pick.
     /// Exactly one entry is returned per roll call (with its randomized count).
     ///
+    /// Randomness comes from `rng`, normally the world's loot stream
+    /// (see [`World::roll_loot`](crate::ecs::world::World::roll_loot)), so rolls
+    /// are reproducible from a seed.
+    ///
     /// Returns `Err(LootError::TableNotFound)` if the table was never defined,
     /// or `Err(LootError::EmptyTable)` if the table has no entries or all
     /// entries have zero weight.
-    pub fn roll(&self, name: &str) -> Result<Vec<(String, u32)>, LootError> {
+    pub fn roll<R: Rng + ?Sized>(
+        &self,
+        name: &str,
+        rng: &mut R,
+    ) -> Result<Vec<(String, u32)>, LootError> {
         let table = self
             .tables
             .get(name)
@@ -132,8 +140,6 @@ impl LootTableRegistry {
             return Err(LootError::EmptyTable(name.to_string()));
         }
 
-        let mut rng = rand::rng();
-
         // Sum all weights for weighted selection
         let total_weight: u64 = table.entries.iter().map(|e| e.weight as u64).sum();
 
@@ -161,6 +167,11 @@ impl LootTableRegistry {
         Err(LootError::EmptyTable(name.to_string()))
     }
 
+    /// Get a table by name.
+    pub fn get_table(&self, name: &str) -> Option<&LootTable> {
+        self.tables.get(name)
+    }
+
     /// Check if a table with the given name exists.
     pub fn has_table(&self, name: &str) -> bool {
         self.tables.contains_key(name)
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 /// Represents a key for a cell.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum CellKey {
     /// Represents a key for a square cell.
     Square {
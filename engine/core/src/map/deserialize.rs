@@ -36,6 +36,14 @@ pub fn map_from_json(value: &Value) -> Option<Map> {
         eprintln!("Map schema validation failed: {e}");
         return None;
     }
+    parse_map_json(value)
+}
+
+/// Convert a JSON map to a Map object without schema validation.
+///
+/// Used for engine-produced map JSON (e.g. save files), where cell metadata
+/// is not restricted to objects.
+pub fn parse_map_json(value: &Value) -> Option<Map> {
     let topology = value.get("topology")?.as_str()?;
     match topology {
         "square" => {
@@ -0,0 +1,320 @@
+//! Dijkstra maps (flow fields).
+//!
+//! A Dijkstra map stores, for every cell that can reach one of a set of goals,
+//! the cost of the cheapest walk to the nearest goal. Any number of agents
+//! heading for the same goals can then step down the gradient instead of each
+//! running its own search: monsters converging on the player, haulers bringing
+//! goods to a stockpile.
+//!
+//! Costs are those of a [`MovementProfile`]: a step pays the cost of the cell
+//! it enters, plus a climbing cost when it changes z-level. Maps are built by
+//! following neighbor links backwards from the goals, so one-way links are
+//! honoured.
+//!
+//! Seeding cells with values other than zero gives weighted "desire" maps, and
+//! [`DijkstraMap::flee`] turns a map into one that leads away from its goals.
+
+use super::movement::MovementProfile;
+use super::{CellKey, MapTopology};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::Arc;
+
+/// Flee factor used when none is given. Values above 1 make fleeing agents
+/// prefer running past a threat towards open space over cornering themselves.
+pub const DEFAULT_FLEE_FACTOR: f32 = 1.2;
+
+/// Maximum number of maps a [`FlowFieldCache`] holds before it is emptied.
+pub const FLOW_FIELD_CACHE_CAPACITY: usize = 64;
+
+/// Cost-to-goal of every cell that can reach a goal.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DijkstraMap {
+    distances: HashMap<CellKey, f32>,
+}
+
+impl DijkstraMap {
+    /// Build the map for `goals` over a topology, with a movement profile's costs.
+    pub fn build(map: &dyn MapTopology, goals: &[CellKey], profile: &MovementProfile) -> Self {
+        let links = reverse_links(map);
+        let seeds = goals
+            .iter()
+            .filter(|goal| map.contains(goal))
+            .map(|goal| (goal.clone(), 0.0));
+        Self::from_seeds_with_climb(
+            seeds,
+            |cell| links.get(cell).cloned().unwrap_or_default(),
+            |cell| profile.cost(map.get_cell_metadata(cell)),
+            |from, to| {
+                profile.climb_cost(
+                    from,
+                    to,
+                    map.get_cell_metadata(from),
+                    map.get_cell_metadata(to),
+                )
+            },
+        )
+    }
+
+    /// Build a map from seed cells and their starting values.
+    ///
+    /// `sources` lists the cells with a link into a cell (on maps with
+    /// two-way links, its neighbors) and `cost` is the cost of entering a cell
+    /// (`f32::INFINITY` if it cannot be entered). Lower seed values attract
+    /// more; goals are usually seeded with 0.
+    pub fn from_seeds<S, N, C>(seeds: S, sources: N, cost: C) -> Self
+    where
+        S: IntoIterator<Item = (CellKey, f32)>,
+        N: Fn(&CellKey) -> Vec<CellKey>,
+        C: Fn(&CellKey) -> f32,
+    {
+        Self::from_seeds_with_climb(seeds, sources, cost, |_, _| 0.0)
+    }
+
+    /// Like [`from_seeds`](Self::from_seeds), with `climb(from, to)` added to
+    /// every step from `from` into `to`, such as a level change's climbing cost.
+    pub fn from_seeds_with_climb<S, N, C, V>(seeds: S, sources: N, cost: C, climb: V) -> Self
+    where
+        S: IntoIterator<Item = (CellKey, f32)>,
+        N: Fn(&CellKey) -> Vec<CellKey>,
+        C: Fn(&CellKey) -> f32,
+        V: Fn(&CellKey, &CellKey) -> f32,
+    {
+        let mut distances: HashMap<CellKey, f32> = HashMap::new();
+        let mut open = BinaryHeap::new();
+        for (cell, value) in seeds {
+            if distances.get(&cell).is_none_or(|d| value < *d) {
+                distances.insert(cell.clone(), value);
+                open.push(Entry(value, cell));
+            }
+        }
+        let mut step_costs: HashMap<CellKey, f32> = HashMap::new();
+        while let Some(Entry(value, cell)) = open.pop() {
+            if distances.get(&cell).is_some_and(|d| value > *d) {
+                continue;
+            }
+            // Stepping from a source into `cell` pays the cost of `cell`.
+            let step = *step_costs
+                .entry(cell.clone())
+                .or_insert_with(|| cost(&cell));
+            if !step.is_finite() {
+                continue;
+            }
+            for source in sources(&cell) {
+                let enterable = *step_costs
+                    .entry(source.clone())
+                    .or_insert_with(|| cost(&source));
+                if !enterable.is_finite() {
+                    continue;
+                }
+                let candidate = value + step + climb(&source, &cell);
+                if distances.get(&source).is_none_or(|d| candidate < *d) {
+                    distances.insert(source.clone(), candidate);
+                    open.push(Entry(candidate, source));
+                }
+            }
+        }
+        Self { distances }
+    }
+
+    /// A map leading away from this map's goals.
+    ///
+    /// Every value is multiplied by `-factor` and the result is rescanned, so
+    /// stepping down it moves away from the goals without running into dead
+    /// ends. Pass the same `sources` and `cost` used to build this map.
+    pub fn flee<N, C>(&self, factor: f32, sources: N, cost: C) -> Self
+    where
+        N: Fn(&CellKey) -> Vec<CellKey>,
+        C: Fn(&CellKey) -> f32,
+    {
+        self.flee_with_climb(factor, sources, cost, |_, _| 0.0)
+    }
+
+    /// Like [`flee`](Self::flee), with the `climb` of
+    /// [`from_seeds_with_climb`](Self::from_seeds_with_climb).
+    pub fn flee_with_climb<N, C, V>(&self, factor: f32, sources: N, cost: C, climb: V) -> Self
+    where
+        N: Fn(&CellKey) -> Vec<CellKey>,
+        C: Fn(&CellKey) -> f32,
+        V: Fn(&CellKey, &CellKey) -> f32,
+    {
+        let seeds = self
+            .distances
+            .iter()
+            .map(|(cell, distance)| (cell.clone(), -factor * distance));
+        Self::from_seeds_with_climb(seeds, sources, cost, climb)
+    }
+
+    /// Value of a cell, or `None` if it cannot reach a goal.
+    pub fn distance(&self, cell: &CellKey) -> Option<f32> {
+        self.distances.get(cell).copied()
+    }
+
+    /// The neighbor of `cell` with the lowest value below that of `cell`, if
+    /// any. Ties go to the lowest cell key.
+    pub fn next_step<N>(&self, cell: &CellKey, neighbors: N) -> Option<CellKey>
+    where
+        N: Fn(&CellKey) -> Vec<CellKey>,
+    {
+        let here = self.distance(cell)?;
+        neighbors(cell)
+            .into_iter()
+            .filter_map(|next| {
+                let value = self.distance(&next)?;
+                (value < here).then_some((value, next))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0).then_with(|| a.1.cmp(&b.1)))
+            .map(|(_, next)| next)
+    }
+
+    /// The cells visited stepping down from `cell` until no neighbor is lower,
+    /// starting with `cell`. `None` if `cell` cannot reach a goal.
+    pub fn descend<N>(&self, cell: &CellKey, neighbors: N) -> Option<Vec<CellKey>>
+    where
+        N: Fn(&CellKey) -> Vec<CellKey>,
+    {
+        self.distance(cell)?;
+        let mut path = vec![cell.clone()];
+        while let Some(next) = self.next_step(path.last()?, &neighbors) {
+            path.push(next);
+        }
+        Some(path)
+    }
+
+    /// Number of cells with a value.
+    pub fn len(&self) -> usize {
+        self.distances.len()
+    }
+
+    /// Returns true if no cell can reach a goal.
+    pub fn is_empty(&self) -> bool {
+        self.distances.is_empty()
+    }
+
+    /// Cells and their values, in ascending cell order.
+    pub fn entries(&self) -> Vec<(CellKey, f32)> {
+        let mut entries: Vec<_> = self
+            .distances
+            .iter()
+            .map(|(cell, distance)| (cell.clone(), *distance))
+            .collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        entries
+    }
+}
+
+/// For every cell, the cells with a neighbor link into it.
+pub fn reverse_links(map: &dyn MapTopology) -> HashMap<CellKey, Vec<CellKey>> {
+    let mut links: HashMap<CellKey, Vec<CellKey>> = HashMap::new();
+    for cell in map.all_cells() {
+        for next in map.neighbors(&cell) {
+            links.entry(next).or_default().push(cell.clone());
+        }
+    }
+    links
+}
+
+/// Cache key: profile name, sorted goals and the flee factor's bits.
+type FlowKey = (String, Vec<CellKey>, Option<u32>);
+
+/// Cache of Dijkstra maps per movement profile, goal set and flee factor.
+///
+/// Like [`PathCache`](super::pathfinding::PathCache), maps are tagged with the
+/// map's topology revision; a lookup with a newer revision empties the cache,
+/// as does a profile whose rules changed.
+#[derive(Debug, Default)]
+pub struct FlowFieldCache {
+    revision: u64,
+    profiles: HashMap<String, MovementProfile>,
+    maps: HashMap<FlowKey, Arc<DijkstraMap>>,
+}
+
+impl FlowFieldCache {
+    /// The cached map for `goals`, fled with `flee` if given.
+    pub fn get(
+        &mut self,
+        revision: u64,
+        profile: &MovementProfile,
+        goals: &[CellKey],
+        flee: Option<f32>,
+    ) -> Option<Arc<DijkstraMap>> {
+        self.sync(revision, profile);
+        self.maps.get(&Self::key(profile, goals, flee)).cloned()
+    }
+
+    /// Stores a map.
+    pub fn insert(
+        &mut self,
+        revision: u64,
+        profile: &MovementProfile,
+        goals: &[CellKey],
+        flee: Option<f32>,
+        map: Arc<DijkstraMap>,
+    ) {
+        self.sync(revision, profile);
+        if self.maps.len() >= FLOW_FIELD_CACHE_CAPACITY {
+            self.maps.clear();
+        }
+        self.maps.insert(Self::key(profile, goals, flee), map);
+    }
+
+    /// Number of cached maps.
+    pub fn len(&self) -> usize {
+        self.maps.len()
+    }
+
+    /// Returns true if nothing is cached.
+    pub fn is_empty(&self) -> bool {
+        self.maps.is_empty()
+    }
+
+    /// Drops all cached maps.
+    pub fn clear(&mut self) {
+        self.maps.clear();
+        self.profiles.clear();
+    }
+
+    fn key(profile: &MovementProfile, goals: &[CellKey], flee: Option<f32>) -> FlowKey {
+        let mut goals = goals.to_vec();
+        goals.sort();
+        goals.dedup();
+        (profile.name.clone(), goals, flee.map(f32::to_bits))
+    }
+
+    fn sync(&mut self, revision: u64, profile: &MovementProfile) {
+        if revision != self.revision {
+            self.clear();
+            self.revision = revision;
+        }
+        if self.profiles.get(&profile.name) != Some(profile) {
+            self.maps.retain(|(name, _, _), _| name != &profile.name);
+            self.profiles.insert(profile.name.clone(), profile.clone());
+        }
+    }
+}
+
+/// Min-heap entry ordered by value.
+struct Entry(f32, CellKey);
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Entry {}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .0
+            .total_cmp(&self.0)
+            .then_with(|| other.1.cmp(&self.1))
+    }
+}
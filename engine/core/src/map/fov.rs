@@ -14,12 +14,17 @@
 //! Uses integer fractions for slope tracking — no floating point.
 //! Processes 4 quadrants (north, south, east, west), scanning rows outward
 //! and splitting the visible cone when walls are encountered.
+//!
+//! Both built-in algorithms also look down open shafts: every visible cell
+//! with `"vertical": "open"` reveals the cells below it, level by level, until
+//! a cell with a floor (see [`super::vertical`]).
 
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet, VecDeque};
 
 use super::cell_key::CellKey;
 use super::topology::MapTopology;
+use super::vertical::shaft_below;
 
 /// A pluggable, topology-agnostic field-of-view algorithm.
 ///
@@ -48,6 +53,23 @@ pub trait FovAlgorithm: Send + Sync {
 // Helpers shared by all algorithms
 // ---------------------------------------------------------------------------
 
+/// Adds the cells seen down open shafts among `visible`, at most `range`
+/// levels deep.
+fn reveal_shafts(visible: &mut HashSet<CellKey>, range: u32, topology: &dyn MapTopology) {
+    let below: Vec<CellKey> = visible
+        .iter()
+        .flat_map(|cell| {
+            shaft_below(
+                cell,
+                range,
+                |c| topology.contains(c),
+                |c| topology.get_cell_metadata(c),
+            )
+        })
+        .collect();
+    visible.extend(below);
+}
+
 /// Check whether a cell is transparent (does not block line of sight).
 /// Cells with no metadata default to transparent.
 fn is_transparent(topology: &dyn MapTopology, cell: &CellKey) -> bool {
@@ -112,6 +134,7 @@ impl FovAlgorithm for RecursiveShadowcasting {
             };
             ctx.scan(1, Slope::neg_one(), Slope::one());
         }
+        reveal_shafts(&mut visible, range as u32, topology);
 
         visible.into_iter().collect()
     }
@@ -181,6 +204,7 @@ impl FovAlgorithm for BfsFovAlgorithm {
                 }
             }
         }
+        reveal_shafts(&mut visible, range as u32, topology);
 
         visible.into_iter().collect()
     }
@@ -190,6 +214,15 @@ impl FovAlgorithm for BfsFovAlgorithm {
     }
 }
 
+/// Construct one of the built-in FOV algorithms by name.
+pub fn builtin_fov_algorithm(name: &str) -> Option<Box<dyn FovAlgorithm>> {
+    match name {
+        "recursive_shadowcasting" => Some(Box::new(RecursiveShadowcasting)),
+        "bfs_flood_fill" => Some(Box::new(BfsFovAlgorithm)),
+        _ => None,
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Internal helpers (shared by RecursiveShadowcasting)
 // ---------------------------------------------------------------------------
@@ -46,6 +46,22 @@ impl HexGridMap {
             });
     }
 
+    /// Remove a neighbor from a cell
+    pub fn remove_neighbor(&mut self, from: (i32, i32, i32), to: (i32, i32, i32)) {
+        let from = CellKey::Hex {
+            q: from.0,
+            r: from.1,
+            z: from.2,
+        };
+        if let Some(neighbors) = self.cells.get_mut(&from) {
+            neighbors.remove(&CellKey::Hex {
+                q: to.0,
+                r: to.1,
+                z: to.2,
+            });
+        }
+    }
+
     /// Merge another HexGridMap into this one
     pub fn merge_from(&mut self, other: &HexGridMap) {
         for (cell, neighbors) in &other.cells {
@@ -62,6 +78,13 @@ impl HexGridMap {
     }
 }
 
+/// Number of hex steps between two axial `(q, r)` coordinates.
+pub fn hex_distance(a: (i32, i32), b: (i32, i32)) -> u32 {
+    let dq = a.0 as i64 - b.0 as i64;
+    let dr = a.1 as i64 - b.1 as i64;
+    ((dq.abs() + dr.abs() + (dq + dr).abs()) / 2) as u32
+}
+
 impl Default for HexGridMap {
     fn default() -> Self {
         Self::new()
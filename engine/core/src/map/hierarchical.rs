@@ -0,0 +1,599 @@
+//! Hierarchical pathfinding (HPA*) for square grids.
+//!
+//! The grid is split into clusters of `cluster_size` x `cluster_size` cells on
+//! each z-level. Every contiguous opening between two clusters gets one
+//! transition, whose cells become the clusters' entrances. The costs between
+//! the entrances of a cluster are computed the first time a search reaches it
+//! and kept. A search runs A* over this entrance graph and then refines each
+//! step with a search confined to one cluster, so long paths only touch a small
+//! part of the grid. Paths are near-optimal rather than shortest.
+//!
+//! Changing a cell's metadata marks its cluster dirty; only dirty clusters and
+//! the clusters next to them are recomputed before the next search. The graph
+//! assumes neighbor links are symmetric, as they are in generated maps.
+
+use super::movement::MovementProfile;
+use super::pathfinding::{PathCache, PathfindingResult, default_heuristic};
+use super::{CellKey, MapTopology};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, BinaryHeap, HashMap, HashSet};
+
+/// Cluster side length used when none is given.
+pub const DEFAULT_CLUSTER_SIZE: u32 = 16;
+
+/// How [`World::find_path`](crate::ecs::world::World::find_path) searches the map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PathfindingStrategy {
+    /// A* over every cell.
+    #[default]
+    #[serde(rename = "astar")]
+    AStar,
+    /// HPA* over clusters of `cluster_size` x `cluster_size` cells.
+    /// Only square grids are clustered; other topologies use A*.
+    Hierarchical {
+        /// Side length of a cluster, in cells
+        cluster_size: u32,
+    },
+}
+
+impl PathfindingStrategy {
+    /// Parses a strategy name (`"astar"` or `"hierarchical"`).
+    pub fn from_name(name: &str, cluster_size: Option<u32>) -> Result<Self, String> {
+        match name {
+            "astar" => Ok(Self::AStar),
+            "hierarchical" => match cluster_size.unwrap_or(DEFAULT_CLUSTER_SIZE) {
+                0 => Err("Cluster size must be at least 1".to_string()),
+                cluster_size => Ok(Self::Hierarchical { cluster_size }),
+            },
+            _ => Err(format!("Unknown pathfinding strategy '{name}'")),
+        }
+    }
+
+    /// The strategy's name, as accepted by [`PathfindingStrategy::from_name`].
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::AStar => "astar",
+            Self::Hierarchical { .. } => "hierarchical",
+        }
+    }
+}
+
+/// Cluster coordinates: `(x / size, y / size, z)`.
+type ClusterKey = (i32, i32, i32);
+
+/// Abstract edges leaving a node.
+type Edges = HashMap<CellKey, Vec<(CellKey, f32)>>;
+
+/// Cost of entering each cell, memoized during a search.
+type StepCosts = HashMap<CellKey, f32>;
+
+/// A step from an entrance of one cluster into an entrance of another.
+#[derive(Debug, Clone)]
+struct Transition {
+    from: CellKey,
+    to: CellKey,
+    cost: f32,
+}
+
+/// Entrance graph of a square grid for one movement profile.
+#[derive(Debug, Clone)]
+pub struct HierarchicalGraph {
+    cluster_size: i32,
+    profile: MovementProfile,
+    /// Cells of each cluster with a neighbor in another cluster, sorted.
+    borders: HashMap<ClusterKey, Vec<CellKey>>,
+    /// Clusters linked to each cluster by at least one neighbor link.
+    adjacent: HashMap<ClusterKey, BTreeSet<ClusterKey>>,
+    /// Transitions by source cluster, then target cluster.
+    transitions: HashMap<ClusterKey, HashMap<ClusterKey, Vec<Transition>>>,
+    /// Transitions by source cell.
+    exits: Edges,
+    /// Costs between the entrances of each cluster, computed when a search
+    /// first reaches the cluster.
+    intra: HashMap<ClusterKey, Edges>,
+    dirty: HashSet<ClusterKey>,
+}
+
+impl HierarchicalGraph {
+    /// Clusters `map` and computes its entrance graph for `profile`.
+    pub fn build(map: &dyn MapTopology, profile: &MovementProfile, cluster_size: u32) -> Self {
+        let mut graph = Self {
+            cluster_size: cluster_size.max(1) as i32,
+            profile: profile.clone(),
+            borders: HashMap::new(),
+            adjacent: HashMap::new(),
+            transitions: HashMap::new(),
+            exits: HashMap::new(),
+            intra: HashMap::new(),
+            dirty: HashSet::new(),
+        };
+        for cell in map.all_cells() {
+            let Some(cluster) = graph.cluster_of(&cell) else {
+                continue;
+            };
+            let others: BTreeSet<ClusterKey> = map
+                .neighbors(&cell)
+                .iter()
+                .filter_map(|neighbor| graph.cluster_of(neighbor))
+                .filter(|other| *other != cluster)
+                .collect();
+            for other in &others {
+                graph.adjacent.entry(cluster).or_default().insert(*other);
+                graph.adjacent.entry(*other).or_default().insert(cluster);
+            }
+            let border = graph.borders.entry(cluster).or_default();
+            if !others.is_empty() {
+                border.push(cell);
+            }
+        }
+        for cells in graph.borders.values_mut() {
+            cells.sort();
+        }
+        graph.dirty = graph.borders.keys().copied().collect();
+        graph.update(map);
+        graph
+    }
+
+    /// Side length of a cluster, in cells.
+    pub fn cluster_size(&self) -> u32 {
+        self.cluster_size as u32
+    }
+
+    /// The movement profile the graph was built for.
+    pub fn profile(&self) -> &MovementProfile {
+        &self.profile
+    }
+
+    /// Number of clusters.
+    pub fn cluster_count(&self) -> usize {
+        self.borders.len()
+    }
+
+    /// Number of entrance cells over all clusters.
+    pub fn entrance_count(&self) -> usize {
+        self.borders
+            .keys()
+            .map(|cluster| self.entrances(*cluster).len())
+            .sum()
+    }
+
+    /// Number of clusters waiting to be recomputed.
+    pub fn dirty_count(&self) -> usize {
+        self.dirty.len()
+    }
+
+    /// Marks the cluster of `cell` for recomputation, e.g. after its metadata
+    /// changed. Adding or removing cells or neighbor links needs a new graph.
+    pub fn mark_dirty(&mut self, cell: &CellKey) {
+        if let Some(cluster) = self.cluster_of(cell)
+            && self.borders.contains_key(&cluster)
+        {
+            self.dirty.insert(cluster);
+        }
+    }
+
+    /// Recomputes the transitions of dirty clusters and drops the entrance
+    /// costs of those clusters and the clusters next to them.
+    pub fn update(&mut self, map: &dyn MapTopology) {
+        if self.dirty.is_empty() {
+            return;
+        }
+        let mut borders = HashSet::new();
+        let mut affected = HashSet::new();
+        for cluster in self.dirty.drain() {
+            affected.insert(cluster);
+            for other in self.adjacent.get(&cluster).into_iter().flatten() {
+                borders.insert((cluster, *other));
+                borders.insert((*other, cluster));
+                affected.insert(*other);
+            }
+        }
+        for (from, to) in borders {
+            let transitions = self.border_transitions(map, from, to);
+            let outgoing = self.transitions.entry(from).or_default();
+            if transitions.is_empty() {
+                outgoing.remove(&to);
+            } else {
+                outgoing.insert(to, transitions);
+            }
+        }
+        self.exits.clear();
+        for transition in self.transitions.values().flat_map(|t| t.values()).flatten() {
+            self.exits
+                .entry(transition.from.clone())
+                .or_default()
+                .push((transition.to.clone(), transition.cost));
+        }
+        for cluster in affected {
+            self.intra.remove(&cluster);
+        }
+    }
+
+    /// Finds a path from `start` to `goal`, recomputing dirty clusters first.
+    pub fn find_path(
+        &mut self,
+        map: &dyn MapTopology,
+        start: &CellKey,
+        goal: &CellKey,
+    ) -> Option<PathfindingResult> {
+        self.update(map);
+        let start_cluster = self.cluster_of(start)?;
+        let goal_cluster = self.cluster_of(goal)?;
+        if !map.contains(start) || !map.contains(goal) {
+            return None;
+        }
+        if start == goal {
+            return Some(PathfindingResult {
+                path: vec![start.clone()],
+                total_cost: 0.0,
+            });
+        }
+
+        // Link start and goal into the entrance graph for this search only.
+        let mut steps = StepCosts::new();
+        let mut extra: Edges = HashMap::new();
+        let (from_start, _) = self.local_search(map, &mut steps, start, start_cluster, None);
+        let mut targets = self.entrances(start_cluster);
+        if start_cluster == goal_cluster {
+            targets.insert(goal.clone());
+        }
+        for target in targets {
+            if &target != start
+                && let Some(cost) = from_start.get(&target)
+            {
+                extra
+                    .entry(start.clone())
+                    .or_default()
+                    .push((target, *cost));
+            }
+        }
+        for entrance in self.entrances(goal_cluster) {
+            if &entrance == goal {
+                continue;
+            }
+            let (costs, _) =
+                self.local_search(map, &mut steps, &entrance, goal_cluster, Some(goal));
+            if let Some(cost) = costs.get(goal) {
+                extra
+                    .entry(entrance)
+                    .or_default()
+                    .push((goal.clone(), *cost));
+            }
+        }
+
+        let waypoints = self.abstract_search(map, start, goal, &extra)?;
+        let mut path = vec![start.clone()];
+        for step in waypoints.windows(2) {
+            let (from, to) = (&step[0], &step[1]);
+            let cluster = self.cluster_of(from)?;
+            if self.cluster_of(to) != Some(cluster) {
+                path.push(to.clone());
+                continue;
+            }
+            let (costs, came_from) = self.local_search(map, &mut steps, from, cluster, Some(to));
+            if !costs.contains_key(to) {
+                return None;
+            }
+            let mut segment = vec![to.clone()];
+            let mut current = to;
+            while let Some(prev) = came_from.get(current) {
+                if prev == from {
+                    break;
+                }
+                segment.push(prev.clone());
+                current = prev;
+            }
+            segment.reverse();
+            path.extend(segment);
+        }
+        let total_cost = path
+            .windows(2)
+            .map(|step| self.link_cost(map, &step[0], &step[1]))
+            .sum();
+        Some(PathfindingResult { path, total_cost })
+    }
+
+    /// A* over entrances, plus the start and goal links in `extra`.
+    fn abstract_search(
+        &mut self,
+        map: &dyn MapTopology,
+        start: &CellKey,
+        goal: &CellKey,
+        extra: &Edges,
+    ) -> Option<Vec<CellKey>> {
+        let mut open = BinaryHeap::new();
+        let mut best: HashMap<CellKey, f32> = HashMap::from([(start.clone(), 0.0)]);
+        let mut came_from: HashMap<CellKey, CellKey> = HashMap::new();
+        let mut closed = HashSet::new();
+        open.push(Entry(default_heuristic(start, goal), start.clone()));
+        while let Some(Entry(_, node)) = open.pop() {
+            if &node == goal {
+                let mut waypoints = vec![node];
+                while let Some(prev) = came_from.get(waypoints.last()?) {
+                    waypoints.push(prev.clone());
+                }
+                waypoints.reverse();
+                return Some(waypoints);
+            }
+            if !closed.insert(node.clone()) {
+                continue;
+            }
+            let cost = best[&node];
+            let cluster = self.cluster_of(&node)?;
+            if !self.intra.contains_key(&cluster) {
+                let edges = self.entrance_costs(map, cluster);
+                self.intra.insert(cluster, edges);
+            }
+            let intra = self.intra.get(&cluster).and_then(|edges| edges.get(&node));
+            let edges = [intra, self.exits.get(&node), extra.get(&node)];
+            for (next, step) in edges.into_iter().flatten().flatten() {
+                let tentative = cost + step;
+                if tentative < *best.get(next).unwrap_or(&f32::INFINITY) {
+                    best.insert(next.clone(), tentative);
+                    came_from.insert(next.clone(), node.clone());
+                    open.push(Entry(
+                        tentative + default_heuristic(next, goal),
+                        next.clone(),
+                    ));
+                }
+            }
+        }
+        None
+    }
+
+    /// One transition per contiguous opening from cluster `from` into `to`.
+    fn border_transitions(
+        &self,
+        map: &dyn MapTopology,
+        from: ClusterKey,
+        to: ClusterKey,
+    ) -> Vec<Transition> {
+        let mut links = Vec::new();
+        for cell in self.borders.get(&from).into_iter().flatten() {
+            if !self.step_cost(map, cell).is_finite() {
+                continue;
+            }
+            for neighbor in map.neighbors(cell) {
+                if self.cluster_of(&neighbor) != Some(to) {
+                    continue;
+                }
+                let cost = self.link_cost(map, cell, &neighbor);
+                if cost.is_finite() {
+                    links.push(Transition {
+                        from: cell.clone(),
+                        to: neighbor,
+                        cost,
+                    });
+                }
+            }
+        }
+        links.sort_by(|a, b| a.from.cmp(&b.from).then_with(|| a.to.cmp(&b.to)));
+
+        // Links whose cells are neighbors on both sides belong to one opening.
+        let touching: HashMap<&CellKey, HashSet<CellKey>> = links
+            .iter()
+            .flat_map(|link| [&link.from, &link.to])
+            .map(|cell| (cell, map.neighbors(cell).into_iter().collect()))
+            .collect();
+        let touch =
+            |a: &CellKey, b: &CellKey| a == b || touching[a].contains(b) || touching[b].contains(a);
+        let mut opening: Vec<usize> = (0..links.len()).collect();
+        fn root(opening: &mut [usize], mut i: usize) -> usize {
+            while opening[i] != i {
+                opening[i] = opening[opening[i]];
+                i = opening[i];
+            }
+            i
+        }
+        for i in 0..links.len() {
+            for j in i + 1..links.len() {
+                if touch(&links[i].from, &links[j].from) && touch(&links[i].to, &links[j].to) {
+                    let (a, b) = (root(&mut opening, i), root(&mut opening, j));
+                    opening[b] = a;
+                }
+            }
+        }
+        let mut openings: BTreeSet<usize> = BTreeSet::new();
+        let mut grouped: HashMap<usize, Vec<usize>> = HashMap::new();
+        for i in 0..links.len() {
+            let r = root(&mut opening, i);
+            openings.insert(r);
+            grouped.entry(r).or_default().push(i);
+        }
+        openings
+            .into_iter()
+            .map(|r| {
+                let group = &grouped[&r];
+                links[group[group.len() / 2]].clone()
+            })
+            .collect()
+    }
+
+    /// Costs between every pair of entrances of `cluster`, moving inside it.
+    fn entrance_costs(&self, map: &dyn MapTopology, cluster: ClusterKey) -> Edges {
+        let entrances = self.entrances(cluster);
+        let mut steps = StepCosts::new();
+        let mut edges = HashMap::new();
+        for entrance in &entrances {
+            let (costs, _) = self.local_search(map, &mut steps, entrance, cluster, None);
+            let reachable = entrances
+                .iter()
+                .filter(|other| *other != entrance)
+                .filter_map(|other| costs.get(other).map(|cost| (other.clone(), *cost)))
+                .collect();
+            edges.insert(entrance.clone(), reachable);
+        }
+        edges
+    }
+
+    /// Cells of `cluster` used by its transitions in either direction.
+    fn entrances(&self, cluster: ClusterKey) -> BTreeSet<CellKey> {
+        let mut entrances = BTreeSet::new();
+        if let Some(outgoing) = self.transitions.get(&cluster) {
+            entrances.extend(outgoing.values().flatten().map(|t| t.from.clone()));
+        }
+        for other in self.adjacent.get(&cluster).into_iter().flatten() {
+            if let Some(incoming) = self.transitions.get(other).and_then(|t| t.get(&cluster)) {
+                entrances.extend(incoming.iter().map(|t| t.to.clone()));
+            }
+        }
+        entrances
+    }
+
+    /// Search from `start` over the cells of `cluster`: Dijkstra, or A* stopping
+    /// at `goal` if given. Returns the cost to reach each visited cell and its
+    /// predecessor. Step costs are memoized in `steps`.
+    fn local_search(
+        &self,
+        map: &dyn MapTopology,
+        steps: &mut StepCosts,
+        start: &CellKey,
+        cluster: ClusterKey,
+        goal: Option<&CellKey>,
+    ) -> (HashMap<CellKey, f32>, HashMap<CellKey, CellKey>) {
+        let mut costs = HashMap::from([(start.clone(), 0.0)]);
+        let mut came_from = HashMap::new();
+        let mut closed = HashSet::new();
+        let mut open = BinaryHeap::from([Entry(0.0, start.clone())]);
+        while let Some(Entry(_, cell)) = open.pop() {
+            if goal == Some(&cell) {
+                break;
+            }
+            if !closed.insert(cell.clone()) {
+                continue;
+            }
+            let cost = costs[&cell];
+            for neighbor in map.neighbors(&cell) {
+                if self.cluster_of(&neighbor) != Some(cluster) || closed.contains(&neighbor) {
+                    continue;
+                }
+                let step = match steps.get(&neighbor) {
+                    Some(step) => *step,
+                    None => {
+                        let step = self.step_cost(map, &neighbor);
+                        steps.insert(neighbor.clone(), step);
+                        step
+                    }
+                };
+                if !step.is_finite() {
+                    continue;
+                }
+                let tentative = cost + step;
+                if tentative < *costs.get(&neighbor).unwrap_or(&f32::INFINITY) {
+                    costs.insert(neighbor.clone(), tentative);
+                    came_from.insert(neighbor.clone(), cell.clone());
+                    let estimate = goal.map_or(0.0, |goal| default_heuristic(&neighbor, goal));
+                    open.push(Entry(tentative + estimate, neighbor));
+                }
+            }
+        }
+        (costs, came_from)
+    }
+
+    fn step_cost(&self, map: &dyn MapTopology, cell: &CellKey) -> f32 {
+        self.profile.cost(map.get_cell_metadata(cell))
+    }
+
+    /// Cost of stepping from `from` into `to`, climbing included. Steps within
+    /// a cluster never change level, so only transitions and totals need it.
+    fn link_cost(&self, map: &dyn MapTopology, from: &CellKey, to: &CellKey) -> f32 {
+        self.profile.step_cost(
+            from,
+            to,
+            map.get_cell_metadata(from),
+            map.get_cell_metadata(to),
+        )
+    }
+
+    fn cluster_of(&self, cell: &CellKey) -> Option<ClusterKey> {
+        match cell {
+            CellKey::Square { x, y, z } => Some((
+                x.div_euclid(self.cluster_size),
+                y.div_euclid(self.cluster_size),
+                *z,
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Entrance graphs per movement profile, with their cached results.
+#[derive(Debug, Default)]
+pub(crate) struct HierarchicalPathfinder {
+    graphs: HashMap<String, HierarchicalGraph>,
+    paths: PathCache,
+}
+
+impl HierarchicalPathfinder {
+    /// Finds a path, building the profile's graph on first use.
+    pub(crate) fn find_path(
+        &mut self,
+        map: &dyn MapTopology,
+        revision: u64,
+        profile: &MovementProfile,
+        cluster_size: u32,
+        start: &CellKey,
+        goal: &CellKey,
+    ) -> Option<PathfindingResult> {
+        let stale = self.graphs.get(&profile.name).is_none_or(|graph| {
+            graph.profile != *profile || graph.cluster_size() != cluster_size.max(1)
+        });
+        if stale {
+            self.paths.clear();
+            self.graphs.insert(
+                profile.name.clone(),
+                HierarchicalGraph::build(map, profile, cluster_size),
+            );
+        } else if let Some(result) = self.paths.get(revision, profile, start, goal) {
+            return result;
+        }
+        let result = self
+            .graphs
+            .get_mut(&profile.name)?
+            .find_path(map, start, goal);
+        self.paths
+            .insert(revision, profile, start, goal, result.clone());
+        result
+    }
+
+    /// Marks the cluster of `cell` dirty in every graph.
+    pub(crate) fn cell_changed(&mut self, cell: &CellKey) {
+        for graph in self.graphs.values_mut() {
+            graph.mark_dirty(cell);
+        }
+    }
+
+    /// Drops all graphs, e.g. after cells or neighbor links changed.
+    pub(crate) fn clear(&mut self) {
+        self.graphs.clear();
+        self.paths.clear();
+    }
+}
+
+/// Min-heap entry ordered by cost.
+struct Entry(f32, CellKey);
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Entry {}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .0
+            .total_cmp(&self.0)
+            .then_with(|| other.1.cmp(&self.1))
+    }
+}
@@ -6,38 +6,92 @@
 pub mod cell_key;
 /// Map deserialization module.
 pub mod deserialize;
+/// Dijkstra maps (flow fields) for many agents sharing goals.
+pub mod flow_field;
 /// Field-of-view module with recursive shadowcasting.
 pub mod fov;
 /// Hex grid map module.
 pub mod hex;
+/// Hierarchical pathfinding for square grids.
+pub mod hierarchical;
+/// Movement profiles for pathfinding.
+pub mod movement;
 /// Map pathfinding module.
 pub mod pathfinding;
 /// Province map module.
 pub mod province;
+/// Map serialization module.
+pub mod serialize;
+/// Spatial index of entity positions.
+pub mod spatial_index;
 /// Square grid map module.
 pub mod square;
 /// Map topology module.
 pub mod topology;
+/// Stairs, ramps, ladders and open shafts between z-levels.
+pub mod vertical;
 
 pub use cell_key::CellKey;
+pub use flow_field::DijkstraMap;
+use flow_field::FlowFieldCache;
 pub use fov::{BfsFovAlgorithm, FovAlgorithm, RecursiveShadowcasting, compute_fov};
 pub use hex::HexGridMap;
+use hierarchical::HierarchicalPathfinder;
+pub use hierarchical::{HierarchicalGraph, PathfindingStrategy};
+pub use movement::{MovementProfile, MovementProfiles};
+use pathfinding::{PathCache, ProvinceCentroids};
 pub use pathfinding::{PathfindingResult, find_path as pathfinding_find_path};
 pub use province::ProvinceMap;
 use serde_json::Value;
+pub use spatial_index::{SpatialIndex, cell_distance};
 pub use square::SquareGridMap;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
 pub use topology::MapTopology;
+pub use vertical::VerticalKind;
 
 /// The main Map type (boxed trait object for dynamic dispatch).
 pub struct Map {
     /// The underlying MapTopology.
+    ///
+    /// Call [`Map::mark_changed`] after changing it directly, so cached paths
+    /// and Dijkstra maps are recomputed.
     pub topology: Box<dyn MapTopology>,
+    /// Topology revision, bumped by every change made through the Map.
+    revision: u64,
+    /// Pathfinding results for the current revision.
+    path_cache: Mutex<PathCache>,
+    /// Dijkstra maps for the current revision.
+    flow_fields: Mutex<FlowFieldCache>,
+    /// Entrance graphs for [`PathfindingStrategy::Hierarchical`].
+    hierarchy: Mutex<HierarchicalPathfinder>,
+    /// Links found by the last [`Map::connect_levels`].
+    vertical_links: HashSet<(CellKey, CellKey)>,
 }
 
 impl Map {
     /// Create a new Map.
     pub fn new(topology: Box<dyn MapTopology>) -> Self {
-        Self { topology }
+        Self {
+            topology,
+            revision: 0,
+            path_cache: Mutex::new(PathCache::default()),
+            flow_fields: Mutex::new(FlowFieldCache::default()),
+            hierarchy: Mutex::new(HierarchicalPathfinder::default()),
+            vertical_links: HashSet::new(),
+        }
+    }
+
+    /// Topology revision; changes whenever cells, neighbors or metadata change.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Bump the topology revision, invalidating cached paths and
+    /// hierarchical pathfinding graphs.
+    pub fn mark_changed(&mut self) {
+        self.revision += 1;
+        self.hierarchy.get_mut().unwrap().clear();
     }
 
     /// Deserialize a Map from a JSON value.
@@ -46,6 +100,11 @@ impl Map {
             .ok_or_else(|| "Map parse error: could not parse map from JSON".to_string())
     }
 
+    /// Serialize the Map to JSON in the generated-map format.
+    pub fn to_json(&self) -> Value {
+        crate::map::serialize::map_to_json(self)
+    }
+
     /// Check if the Map contains a cell.
     pub fn contains(&self, cell: &CellKey) -> bool {
         self.topology.contains(cell)
@@ -72,12 +131,17 @@ impl Map {
     }
 
     /// Get the underlying MapTopology as a mutable reference.
+    ///
+    /// Bumps the topology revision, since the caller may change the map.
     pub fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self.mark_changed();
         self.topology.as_any_mut()
     }
 
     /// Set cell metadata for the Map.
     pub fn set_cell_metadata(&mut self, cell: &CellKey, data: Value) {
+        self.revision += 1;
+        self.hierarchy.get_mut().unwrap().cell_changed(cell);
         self.topology.set_cell_metadata(cell, data);
     }
 
@@ -86,20 +150,218 @@ impl Map {
         self.topology.get_cell_metadata(cell)
     }
 
-    /// Find the path between two cells.
+    /// Find the path between two cells for the default walker profile.
     pub fn find_path(&self, start: &CellKey, goal: &CellKey) -> Option<PathfindingResult> {
-        crate::map::pathfinding::find_path(
-            self.topology.as_ref(),
-            start,
-            goal,
-            &crate::map::pathfinding::default_cost_fn,
-            &crate::map::pathfinding::default_heuristic,
-            &|cell| self.get_cell_metadata(cell),
+        self.find_path_with_profile(start, goal, &MovementProfile::walker())
+    }
+
+    /// Find the path between two cells for a movement profile.
+    ///
+    /// Results are cached until the topology revision changes. The cache is not
+    /// locked during the search, so other threads can look up paths meanwhile.
+    pub fn find_path_with_profile(
+        &self,
+        start: &CellKey,
+        goal: &CellKey,
+        profile: &MovementProfile,
+    ) -> Option<PathfindingResult> {
+        let cached = self
+            .path_cache
+            .lock()
+            .unwrap()
+            .get(self.revision, profile, start, goal);
+        if let Some(result) = cached {
+            return result;
+        }
+        let step_cost = |from: &CellKey, to: &CellKey| {
+            profile.step_cost(
+                from,
+                to,
+                self.get_cell_metadata(from),
+                self.get_cell_metadata(to),
+            )
+        };
+        let result = if matches!(start, CellKey::Province { .. }) {
+            let centroids = ProvinceCentroids::from_map(self.topology.as_ref());
+            crate::map::pathfinding::find_path_by_step(
+                self.topology.as_ref(),
+                start,
+                goal,
+                &step_cost,
+                &|a, b| centroids.estimate(a, b),
+            )
+        } else {
+            crate::map::pathfinding::find_path_by_step(
+                self.topology.as_ref(),
+                start,
+                goal,
+                &step_cost,
+                &crate::map::pathfinding::default_heuristic,
+            )
+        };
+        self.path_cache
+            .lock()
+            .unwrap()
+            .insert(self.revision, profile, start, goal, result.clone());
+        result
+    }
+
+    /// Find the path between two cells for a movement profile with the given
+    /// strategy. Hierarchical searches only apply to square grids.
+    pub fn find_path_using(
+        &self,
+        start: &CellKey,
+        goal: &CellKey,
+        profile: &MovementProfile,
+        strategy: PathfindingStrategy,
+    ) -> Option<PathfindingResult> {
+        match strategy {
+            PathfindingStrategy::Hierarchical { cluster_size }
+                if self.topology_type() == "square" =>
+            {
+                self.hierarchy.lock().unwrap().find_path(
+                    self.topology.as_ref(),
+                    self.revision,
+                    profile,
+                    cluster_size,
+                    start,
+                    goal,
+                )
+            }
+            _ => self.find_path_with_profile(start, goal, profile),
+        }
+    }
+
+    /// The Dijkstra map towards `goals` for a movement profile.
+    ///
+    /// Maps are cached per goal set until the topology revision changes.
+    pub fn dijkstra_map(&self, goals: &[CellKey], profile: &MovementProfile) -> Arc<DijkstraMap> {
+        let mut cache = self.flow_fields.lock().unwrap();
+        if let Some(map) = cache.get(self.revision, profile, goals, None) {
+            return map;
+        }
+        let map = Arc::new(DijkstraMap::build(self.topology.as_ref(), goals, profile));
+        cache.insert(self.revision, profile, goals, None, map.clone());
+        map
+    }
+
+    /// The map leading away from `threats` for a movement profile; see
+    /// [`DijkstraMap::flee`]. Cached like [`Map::dijkstra_map`].
+    pub fn flee_map(
+        &self,
+        threats: &[CellKey],
+        profile: &MovementProfile,
+        factor: f32,
+    ) -> Arc<DijkstraMap> {
+        if let Some(map) =
+            self.flow_fields
+                .lock()
+                .unwrap()
+                .get(self.revision, profile, threats, Some(factor))
+        {
+            return map;
+        }
+        let towards = self.dijkstra_map(threats, profile);
+        let links = flow_field::reverse_links(self.topology.as_ref());
+        let map = Arc::new(towards.flee_with_climb(
+            factor,
+            |cell| links.get(cell).cloned().unwrap_or_default(),
+            |cell| profile.cost(self.get_cell_metadata(cell)),
+            |from, to| {
+                profile.climb_cost(
+                    from,
+                    to,
+                    self.get_cell_metadata(from),
+                    self.get_cell_metadata(to),
+                )
+            },
+        ));
+        self.flow_fields.lock().unwrap().insert(
+            self.revision,
+            profile,
+            threats,
+            Some(factor),
+            map.clone(),
+        );
+        map
+    }
+
+    /// Adds the neighbor links between z-levels implied by stairs, ramp and
+    /// ladder cells (see [`vertical`]), and removes the links an earlier call
+    /// added for connectors that are gone. Returns the number of links added;
+    /// province maps have no levels and get none.
+    pub fn connect_levels(&mut self) -> usize {
+        let cells = self.all_cells();
+        let current: HashSet<(CellKey, CellKey)> = vertical::vertical_links(
+            &cells,
+            |cell| self.contains(cell),
+            |cell| self.get_cell_metadata(cell),
         )
+        .into_iter()
+        .collect();
+        let stale: Vec<(CellKey, CellKey)> =
+            self.vertical_links.difference(&current).cloned().collect();
+        let links: Vec<(CellKey, CellKey)> = current
+            .iter()
+            .filter(|(from, to)| !self.neighbors(from).contains(to))
+            .cloned()
+            .collect();
+        self.vertical_links = current;
+        if links.is_empty() && stale.is_empty() {
+            return 0;
+        }
+        for (from, to) in &stale {
+            self.set_link(from, to, false);
+        }
+        for (from, to) in &links {
+            self.set_link(from, to, true);
+        }
+        self.mark_changed();
+        links.len()
+    }
+
+    /// Add or remove a neighbor link between two grid cells.
+    fn set_link(&mut self, from: &CellKey, to: &CellKey, linked: bool) {
+        match (from, to) {
+            (
+                CellKey::Square { x, y, z },
+                CellKey::Square {
+                    x: tx,
+                    y: ty,
+                    z: tz,
+                },
+            ) => {
+                if let Some(grid) = self.topology.as_any_mut().downcast_mut::<SquareGridMap>() {
+                    if linked {
+                        grid.add_neighbor((*x, *y, *z), (*tx, *ty, *tz));
+                    } else {
+                        grid.remove_neighbor((*x, *y, *z), (*tx, *ty, *tz));
+                    }
+                }
+            }
+            (
+                CellKey::Hex { q, r, z },
+                CellKey::Hex {
+                    q: tq,
+                    r: tr,
+                    z: tz,
+                },
+            ) => {
+                if let Some(grid) = self.topology.as_any_mut().downcast_mut::<HexGridMap>() {
+                    if linked {
+                        grid.add_neighbor((*q, *r, *z), (*tq, *tr, *tz));
+                    } else {
+                        grid.remove_neighbor((*q, *r, *z), (*tq, *tr, *tz));
+                    }
+                }
+            }
+            _ => {}
+        }
     }
 
     /// Merge another map (chunk) into this map.
     pub fn merge_chunk(&mut self, other: &Map) {
+        self.mark_changed();
         if self.topology_type() == other.topology_type() {
             if let Some(this_sq) = self.topology.as_any_mut().downcast_mut::<SquareGridMap>() {
                 if let Some(other_sq) = other.topology.as_any().downcast_ref::<SquareGridMap>() {
@@ -0,0 +1,212 @@
+//! Movement profiles.
+//!
+//! A movement profile decides how an agent reads cell metadata when
+//! pathfinding: which cells it can enter and what each step costs. Cells are
+//! described by the `walkable`, `cost` and `terrain` metadata fields, and steps
+//! between z-levels pay an extra climbing cost (see [`super::vertical`]).
+//! Agents pick a profile by name through the `movement_profile` field of their
+//! `Agent` component; agents without one use [`MovementProfile::WALKER`].
+
+use super::CellKey;
+use super::vertical::{DEFAULT_CLIMB_COST, VerticalKind, level};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// How an agent moves across cells.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MovementProfile {
+    /// Profile name, referenced by `Agent.movement_profile`
+    pub name: String,
+    /// Terrains the profile can enter even when the cell is not walkable
+    #[serde(default)]
+    pub passable_terrain: BTreeSet<String>,
+    /// Terrains the profile can never enter
+    #[serde(default)]
+    pub blocked_terrain: BTreeSet<String>,
+    /// Step cost per terrain, replacing the cell's `cost`
+    #[serde(default)]
+    pub terrain_costs: BTreeMap<String, f32>,
+    /// Ignore the cell's `cost` (every other step costs 1) and climb for free
+    #[serde(default)]
+    pub ignore_cost: bool,
+    /// Extra cost of a level change per connector kind (`stairs`, `ramp`,
+    /// `ladder`), replacing its default
+    #[serde(default)]
+    pub climb_costs: BTreeMap<String, f32>,
+}
+
+impl MovementProfile {
+    /// Name of the default profile.
+    pub const WALKER: &'static str = "walker";
+
+    /// A profile that reads metadata like [`default_cost_fn`](super::pathfinding::default_cost_fn).
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            passable_terrain: BTreeSet::new(),
+            blocked_terrain: BTreeSet::new(),
+            terrain_costs: BTreeMap::new(),
+            ignore_cost: false,
+            climb_costs: BTreeMap::new(),
+        }
+    }
+
+    /// The default profile: blocked by unwalkable cells, pays each cell's `cost`.
+    pub fn walker() -> Self {
+        Self::new(Self::WALKER)
+    }
+
+    /// Crosses water, where it moves at normal cost.
+    pub fn swimmer() -> Self {
+        let mut profile = Self::new("swimmer");
+        profile.passable_terrain.insert("water".to_string());
+        profile.terrain_costs.insert("water".to_string(), 1.0);
+        profile
+    }
+
+    /// Flies over water, lava and chasms and ignores terrain costs.
+    pub fn flyer() -> Self {
+        let mut profile = Self::new("flyer");
+        for terrain in ["water", "lava", "chasm"] {
+            profile.passable_terrain.insert(terrain.to_string());
+        }
+        profile.ignore_cost = true;
+        profile
+    }
+
+    /// Tunnels through rock and walls, slowly.
+    pub fn digger() -> Self {
+        let mut profile = Self::new("digger");
+        for terrain in ["rock", "wall"] {
+            profile.passable_terrain.insert(terrain.to_string());
+            profile.terrain_costs.insert(terrain.to_string(), 5.0);
+        }
+        profile
+    }
+
+    /// Step cost of entering a cell with the given metadata;
+    /// `f32::INFINITY` if the profile cannot enter it.
+    pub fn cost(&self, meta: Option<&Value>) -> f32 {
+        let Some(meta) = meta else {
+            return 1.0;
+        };
+        let terrain = meta.get("terrain").and_then(|t| t.as_str());
+        if terrain.is_some_and(|t| self.blocked_terrain.contains(t)) {
+            return f32::INFINITY;
+        }
+        let walkable = meta.get("walkable") != Some(&Value::Bool(false))
+            && VerticalKind::from_meta(Some(meta)) != Some(VerticalKind::Open);
+        if !walkable && !terrain.is_some_and(|t| self.passable_terrain.contains(t)) {
+            return f32::INFINITY;
+        }
+        if let Some(cost) = terrain.and_then(|t| self.terrain_costs.get(t)) {
+            return *cost;
+        }
+        if self.ignore_cost {
+            return 1.0;
+        }
+        meta.get("cost")
+            .and_then(|c| c.as_f64())
+            .map_or(1.0, |c| c as f32)
+    }
+
+    /// Extra cost of stepping from `from` to `to` on another z-level, set by
+    /// the connector on the lower of the two cells; 0 on the same level.
+    pub fn climb_cost(
+        &self,
+        from: &CellKey,
+        to: &CellKey,
+        from_meta: Option<&Value>,
+        to_meta: Option<&Value>,
+    ) -> f32 {
+        let (Some(from_z), Some(to_z)) = (level(from), level(to)) else {
+            return 0.0;
+        };
+        if from_z == to_z || self.ignore_cost {
+            return 0.0;
+        }
+        let lower = if from_z < to_z { from_meta } else { to_meta };
+        match VerticalKind::from_meta(lower) {
+            Some(kind) => self
+                .climb_costs
+                .get(kind.name())
+                .copied()
+                .unwrap_or_else(|| kind.default_climb_cost()),
+            None => DEFAULT_CLIMB_COST,
+        }
+    }
+
+    /// Cost of stepping from `from` into `to`: the cost of entering `to` plus
+    /// any [climbing cost](Self::climb_cost).
+    pub fn step_cost(
+        &self,
+        from: &CellKey,
+        to: &CellKey,
+        from_meta: Option<&Value>,
+        to_meta: Option<&Value>,
+    ) -> f32 {
+        self.cost(to_meta) + self.climb_cost(from, to, from_meta, to_meta)
+    }
+}
+
+impl Default for MovementProfile {
+    fn default() -> Self {
+        Self::walker()
+    }
+}
+
+/// Movement profiles by name, starting with the built-in walker, swimmer,
+/// flyer and digger.
+#[derive(Debug, Clone)]
+pub struct MovementProfiles {
+    profiles: BTreeMap<String, MovementProfile>,
+}
+
+impl MovementProfiles {
+    /// Adds a profile, replacing any with the same name.
+    pub fn register(&mut self, profile: MovementProfile) {
+        self.profiles.insert(profile.name.clone(), profile);
+    }
+
+    /// Removes a profile. The walker cannot be removed.
+    pub fn unregister(&mut self, name: &str) -> Option<MovementProfile> {
+        if name == MovementProfile::WALKER {
+            return None;
+        }
+        self.profiles.remove(name)
+    }
+
+    /// The profile called `name`, if registered.
+    pub fn get(&self, name: &str) -> Option<&MovementProfile> {
+        self.profiles.get(name)
+    }
+
+    /// The profile called `name`, falling back to the walker.
+    pub fn get_or_walker(&self, name: Option<&str>) -> &MovementProfile {
+        name.and_then(|n| self.profiles.get(n))
+            .unwrap_or_else(|| &self.profiles[MovementProfile::WALKER])
+    }
+
+    /// Names of all registered profiles.
+    pub fn names(&self) -> Vec<String> {
+        self.profiles.keys().cloned().collect()
+    }
+}
+
+impl Default for MovementProfiles {
+    fn default() -> Self {
+        let mut profiles = Self {
+            profiles: BTreeMap::new(),
+        };
+        for profile in [
+            MovementProfile::walker(),
+            MovementProfile::swimmer(),
+            MovementProfile::flyer(),
+            MovementProfile::digger(),
+        ] {
+            profiles.register(profile);
+        }
+        profiles
+    }
+}
@@ -1,3 +1,5 @@
+use crate::map::hex::hex_distance;
+use crate::map::movement::MovementProfile;
 use crate::map::{CellKey, MapTopology};
 use serde_json::Value;
 use std::collections::{BinaryHeap, HashMap, HashSet};
@@ -59,7 +61,9 @@ pub fn default_cost_fn(meta: Option<&Value>) -> f32 {
     1.0
 }
 
-/// Default heuristic: Manhattan distance for Square, 0 for others (Dijkstra fallback).
+/// Default heuristic: Manhattan distance for Square and hex distance for Hex
+/// (both plus the z difference), 0 for others (Dijkstra fallback; see
+/// [`ProvinceCentroids`] for provinces).
 pub fn default_heuristic(a: &CellKey, b: &CellKey) -> f32 {
     match (a, b) {
         (
@@ -74,11 +78,162 @@ pub fn default_heuristic(a: &CellKey, b: &CellKey) -> f32 {
                 z: bz,
             },
         ) => ((ax - bx).abs() + (ay - by).abs() + (az - bz).abs()) as f32,
-        // For hex or province, fallback to 0 (Dijkstra)
+        (
+            CellKey::Hex {
+                q: aq,
+                r: ar,
+                z: az,
+            },
+            CellKey::Hex {
+                q: bq,
+                r: br,
+                z: bz,
+            },
+        ) => hex_distance((*aq, *ar), (*bq, *br)) as f32 + (az - bz).abs() as f32,
         _ => 0.0,
     }
 }
 
+/// Straight-line heuristic for province maps, read from each province's
+/// `centroid` metadata (`{"x": .., "y": ..}` or `[x, y]`).
+///
+/// Distances are divided by the longest centroid distance between neighboring
+/// provinces, so the estimate never exceeds the number of borders left to cross.
+/// Provinces without a centroid are estimated at 0.
+#[derive(Debug, Clone, Default)]
+pub struct ProvinceCentroids {
+    centroids: HashMap<String, (f64, f64)>,
+    longest_border: f64,
+}
+
+impl ProvinceCentroids {
+    /// Reads the centroids of all provinces in `map`.
+    pub fn from_map(map: &dyn MapTopology) -> Self {
+        let mut centroids = HashMap::new();
+        for cell in map.all_cells() {
+            if let CellKey::Province { id } = &cell
+                && let Some(centroid) = map
+                    .get_cell_metadata(&cell)
+                    .and_then(|meta| meta.get("centroid"))
+                    .and_then(parse_centroid)
+            {
+                centroids.insert(id.clone(), centroid);
+            }
+        }
+        let mut this = Self {
+            centroids,
+            longest_border: 0.0,
+        };
+        for cell in map.all_cells() {
+            for neighbor in map.neighbors(&cell) {
+                if let Some(d) = this.distance(&cell, &neighbor) {
+                    this.longest_border = this.longest_border.max(d);
+                }
+            }
+        }
+        this
+    }
+
+    /// Estimated number of borders between two provinces.
+    pub fn estimate(&self, a: &CellKey, b: &CellKey) -> f32 {
+        match self.distance(a, b) {
+            Some(d) if self.longest_border > 0.0 => (d / self.longest_border) as f32,
+            _ => 0.0,
+        }
+    }
+
+    fn distance(&self, a: &CellKey, b: &CellKey) -> Option<f64> {
+        let (CellKey::Province { id: a }, CellKey::Province { id: b }) = (a, b) else {
+            return None;
+        };
+        let (ax, ay) = self.centroids.get(a)?;
+        let (bx, by) = self.centroids.get(b)?;
+        Some(((ax - bx).powi(2) + (ay - by).powi(2)).sqrt())
+    }
+}
+
+fn parse_centroid(value: &Value) -> Option<(f64, f64)> {
+    match value {
+        Value::Array(xy) if xy.len() == 2 => Some((xy[0].as_f64()?, xy[1].as_f64()?)),
+        _ => Some((value.get("x")?.as_f64()?, value.get("y")?.as_f64()?)),
+    }
+}
+
+/// Maximum number of results a [`PathCache`] holds before it is emptied.
+pub const PATH_CACHE_CAPACITY: usize = 4096;
+
+/// Cache of pathfinding results (including failed searches) per movement
+/// profile, start and goal.
+///
+/// Results are tagged with the map's topology revision; a lookup with a newer
+/// revision empties the cache, as does a profile whose rules changed.
+#[derive(Debug, Default)]
+pub struct PathCache {
+    revision: u64,
+    profiles: HashMap<String, MovementProfile>,
+    paths: HashMap<(String, CellKey, CellKey), Option<PathfindingResult>>,
+}
+
+impl PathCache {
+    /// The cached result of a search, if any. The outer `None` is a cache miss.
+    pub fn get(
+        &mut self,
+        revision: u64,
+        profile: &MovementProfile,
+        start: &CellKey,
+        goal: &CellKey,
+    ) -> Option<Option<PathfindingResult>> {
+        self.sync(revision, profile);
+        self.paths
+            .get(&(profile.name.clone(), start.clone(), goal.clone()))
+            .cloned()
+    }
+
+    /// Stores the result of a search.
+    pub fn insert(
+        &mut self,
+        revision: u64,
+        profile: &MovementProfile,
+        start: &CellKey,
+        goal: &CellKey,
+        result: Option<PathfindingResult>,
+    ) {
+        self.sync(revision, profile);
+        if self.paths.len() >= PATH_CACHE_CAPACITY {
+            self.paths.clear();
+        }
+        self.paths
+            .insert((profile.name.clone(), start.clone(), goal.clone()), result);
+    }
+
+    /// Number of cached results.
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    /// Returns true if nothing is cached.
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// Drops all cached results.
+    pub fn clear(&mut self) {
+        self.paths.clear();
+        self.profiles.clear();
+    }
+
+    fn sync(&mut self, revision: u64, profile: &MovementProfile) {
+        if revision != self.revision {
+            self.clear();
+            self.revision = revision;
+        }
+        if self.profiles.get(&profile.name) != Some(profile) {
+            self.paths.retain(|(name, _, _), _| name != &profile.name);
+            self.profiles.insert(profile.name.clone(), profile.clone());
+        }
+    }
+}
+
 /// Generic A* pathfinding for any MapTopology.
 /// - `cost_fn` is called with cell metadata (or None).
 /// - `heuristic` is called with (current, goal) cell.
@@ -89,6 +244,19 @@ pub fn find_path<'a>(
     cost_fn: &dyn Fn(Option<&Value>) -> f32,
     heuristic: &dyn Fn(&CellKey, &CellKey) -> f32,
     get_meta: &'a dyn Fn(&CellKey) -> Option<&'a Value>,
+) -> Option<PathfindingResult> {
+    find_path_by_step(map, start, goal, &|_, to| cost_fn(get_meta(to)), heuristic)
+}
+
+/// A* pathfinding with a cost per step.
+/// - `step_cost` is called with (from, to) for each step considered.
+/// - `heuristic` is called with (current, goal) cell.
+pub fn find_path_by_step(
+    map: &dyn MapTopology,
+    start: &CellKey,
+    goal: &CellKey,
+    step_cost: &dyn Fn(&CellKey, &CellKey) -> f32,
+    heuristic: &dyn Fn(&CellKey, &CellKey) -> f32,
 ) -> Option<PathfindingResult> {
     if !map.contains(start) || !map.contains(goal) {
         return None;
@@ -128,8 +296,7 @@ pub fn find_path<'a>(
             if closed.contains(&neighbor) {
                 continue;
             }
-            let meta = get_meta(&neighbor);
-            let step_cost = cost_fn(meta);
+            let step_cost = step_cost(&cell, &neighbor);
             if !step_cost.is_finite() {
                 continue; // Impassable
             }
@@ -0,0 +1,74 @@
+//! Map serialization module.
+//!
+//! Converts a [`Map`] back into the JSON shape accepted by
+//! [`map_from_json`](super::deserialize::map_from_json), with explicit neighbors and
+//! cell metadata, so any topology round-trips through save files.
+
+use super::Map;
+use super::cell_key::CellKey;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map as JsonMap, Value, json};
+
+/// Coordinate fields identifying a cell in map JSON.
+fn cell_fields(cell: &CellKey) -> JsonMap<String, Value> {
+    let value = match cell {
+        CellKey::Square { x, y, z } => json!({ "x": x, "y": y, "z": z }),
+        CellKey::Hex { q, r, z } => json!({ "q": q, "r": r, "z": z }),
+        CellKey::Province { id } => json!({ "id": id }),
+    };
+    match value {
+        Value::Object(obj) => obj,
+        _ => JsonMap::new(),
+    }
+}
+
+/// Neighbor reference: coordinates for grids, a bare ID for provinces.
+fn neighbor_to_json(cell: &CellKey) -> Value {
+    match cell {
+        CellKey::Province { id } => json!(id),
+        _ => Value::Object(cell_fields(cell)),
+    }
+}
+
+/// Convert a Map to JSON. Cells and neighbors are sorted so the output is stable.
+pub fn map_to_json(map: &Map) -> Value {
+    let mut cells = map.all_cells();
+    cells.sort();
+
+    let cells_json: Vec<Value> = cells
+        .iter()
+        .map(|cell| {
+            let mut obj = cell_fields(cell);
+            let mut neighbors = map.neighbors(cell);
+            neighbors.sort();
+            obj.insert(
+                "neighbors".to_string(),
+                Value::Array(neighbors.iter().map(neighbor_to_json).collect()),
+            );
+            if let Some(meta) = map.get_cell_metadata(cell) {
+                obj.insert("metadata".to_string(), meta.clone());
+            }
+            Value::Object(obj)
+        })
+        .collect();
+
+    json!({
+        "topology": map.topology_type(),
+        "cells": cells_json,
+    })
+}
+
+impl Serialize for Map {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        map_to_json(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Map {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        super::deserialize::parse_map_json(&value)
+            .ok_or_else(|| D::Error::custom("could not parse map from JSON"))
+    }
+}
@@ -0,0 +1,290 @@
+//! Spatial index of entity positions.
+//!
+//! Maps each occupied [`CellKey`] to the entities in it, so cell, z-level,
+//! rectangle and radius queries look up cells instead of scanning every entity.
+//! Square, hex and province cells are all indexed.
+
+use super::CellKey;
+use super::hex::hex_distance;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+/// Index from cells to the entities positioned in them.
+#[derive(Debug, Clone, Default)]
+pub struct SpatialIndex {
+    cells: HashMap<CellKey, BTreeSet<u32>>,
+    positions: HashMap<u32, CellKey>,
+}
+
+impl SpatialIndex {
+    /// Create an empty index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Place `entity` in `cell`, moving it out of its previous cell.
+    pub fn insert(&mut self, entity: u32, cell: CellKey) {
+        if self.positions.get(&entity) == Some(&cell) {
+            return;
+        }
+        self.remove(entity);
+        self.cells.entry(cell.clone()).or_default().insert(entity);
+        self.positions.insert(entity, cell);
+    }
+
+    /// Remove `entity` from the index, returning the cell it was in.
+    pub fn remove(&mut self, entity: u32) -> Option<CellKey> {
+        let cell = self.positions.remove(&entity)?;
+        if let Some(entities) = self.cells.get_mut(&cell) {
+            entities.remove(&entity);
+            if entities.is_empty() {
+                self.cells.remove(&cell);
+            }
+        }
+        Some(cell)
+    }
+
+    /// Remove all entities.
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.positions.clear();
+    }
+
+    /// Number of indexed entities.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Returns true if no entity is indexed.
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// The cell `entity` is in, if indexed.
+    pub fn cell_of(&self, entity: u32) -> Option<&CellKey> {
+        self.positions.get(&entity)
+    }
+
+    /// Entities in `cell`, in ascending ID order.
+    pub fn entities_in_cell(&self, cell: &CellKey) -> Vec<u32> {
+        self.cells
+            .get(cell)
+            .map(|entities| entities.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Entities on z-level `z` of a square or hex map, in ascending ID order.
+    pub fn entities_in_zlevel(&self, z: i32) -> Vec<u32> {
+        self.scan(|cell| grid_coords(cell).is_some_and(|(_, _, cz)| cz == z))
+    }
+
+    /// Entities in the box spanned by two corner cells (inclusive on every axis,
+    /// z included), in ascending ID order.
+    ///
+    /// Both corners must be square cells or both hex cells (using axial `q`/`r`).
+    /// Provinces have no coordinates, so a province corner matches nothing.
+    pub fn entities_in_rect(&self, a: &CellKey, b: &CellKey) -> Vec<u32> {
+        let (Some(a_coords), Some(b_coords)) = (grid_coords(a), grid_coords(b)) else {
+            return vec![];
+        };
+        if std::mem::discriminant(a) != std::mem::discriminant(b) {
+            return vec![];
+        }
+        let min = (
+            a_coords.0.min(b_coords.0),
+            a_coords.1.min(b_coords.1),
+            a_coords.2.min(b_coords.2),
+        );
+        let max = (
+            a_coords.0.max(b_coords.0),
+            a_coords.1.max(b_coords.1),
+            a_coords.2.max(b_coords.2),
+        );
+        let in_box = |(x, y, z): (i32, i32, i32)| {
+            (min.0..=max.0).contains(&x)
+                && (min.1..=max.1).contains(&y)
+                && (min.2..=max.2).contains(&z)
+        };
+        // Spans reach 2^32, so the volume of a huge box does not fit in a u64
+        let volume = span(min.0, max.0)
+            .saturating_mul(span(min.1, max.1))
+            .saturating_mul(span(min.2, max.2));
+        if volume > self.cells.len() as u64 {
+            return self.scan(|cell| {
+                std::mem::discriminant(cell) == std::mem::discriminant(a)
+                    && grid_coords(cell).is_some_and(in_box)
+            });
+        }
+        let mut cells = Vec::new();
+        for z in min.2..=max.2 {
+            for y in min.1..=max.1 {
+                for x in min.0..=max.0 {
+                    cells.push(with_coords(a, x, y, z));
+                }
+            }
+        }
+        self.gather(cells.iter())
+    }
+
+    /// Entities within `radius` of `center` on its z-level, in ascending ID order.
+    ///
+    /// Distance is measured as in [`cell_distance`]. Province distance is the
+    /// number of borders crossed, found by walking `neighbors`.
+    pub fn entities_in_radius<F>(&self, center: &CellKey, radius: f64, neighbors: F) -> Vec<u32>
+    where
+        F: Fn(&CellKey) -> Vec<CellKey>,
+    {
+        if radius.is_nan() || radius < 0.0 {
+            return vec![];
+        }
+        let Some((cx, cy, cz)) = grid_coords(center) else {
+            let hops = province_distances(center, Some(radius as u32), neighbors);
+            return self.gather(hops.keys());
+        };
+        let reach = radius.floor().min(i32::MAX as f64) as i32;
+        let within = |cell: &CellKey| cell_distance(center, cell).is_some_and(|d| d <= radius);
+        let side = span(cx.saturating_sub(reach), cx.saturating_add(reach));
+        if side.saturating_mul(side) > self.cells.len() as u64 {
+            return self.scan(within);
+        }
+        let mut cells = Vec::new();
+        for y in cy.saturating_sub(reach)..=cy.saturating_add(reach) {
+            for x in cx.saturating_sub(reach)..=cx.saturating_add(reach) {
+                let cell = with_coords(center, x, y, cz);
+                if within(&cell) {
+                    cells.push(cell);
+                }
+            }
+        }
+        self.gather(cells.iter())
+    }
+
+    /// The entity among `candidates` closest to `center`, optionally no further
+    /// than `max_radius`. Ties go to the lowest ID; unindexed candidates and
+    /// candidates on other z-levels are skipped.
+    pub fn nearest<I, F>(
+        &self,
+        center: &CellKey,
+        candidates: I,
+        max_radius: Option<f64>,
+        neighbors: F,
+    ) -> Option<u32>
+    where
+        I: IntoIterator<Item = u32>,
+        F: Fn(&CellKey) -> Vec<CellKey>,
+    {
+        let hops = matches!(center, CellKey::Province { .. })
+            .then(|| province_distances(center, max_radius.map(|r| r as u32), neighbors));
+        candidates
+            .into_iter()
+            .filter_map(|entity| {
+                let cell = self.positions.get(&entity)?;
+                let distance = match &hops {
+                    Some(hops) => *hops.get(cell)? as f64,
+                    None => cell_distance(center, cell)?,
+                };
+                max_radius
+                    .is_none_or(|max| distance <= max)
+                    .then_some((distance, entity))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)))
+            .map(|(_, entity)| entity)
+    }
+
+    /// Entities in any of `cells`, in ascending ID order.
+    fn gather<'a>(&self, cells: impl IntoIterator<Item = &'a CellKey>) -> Vec<u32> {
+        let mut ids: Vec<u32> = cells
+            .into_iter()
+            .filter_map(|cell| self.cells.get(cell))
+            .flatten()
+            .copied()
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Entities in occupied cells matching `keep`, in ascending ID order.
+    fn scan(&self, keep: impl Fn(&CellKey) -> bool) -> Vec<u32> {
+        self.gather(self.cells.keys().filter(|cell| keep(cell)))
+    }
+}
+
+/// Distance between two cells on the same z-level of the same topology.
+///
+/// Square cells use Euclidean distance and hex cells the number of hex steps.
+/// Provinces are at distance 0 from themselves; other province distances depend
+/// on the map's borders, so they are `None`, as are cells on different
+/// z-levels or topologies.
+pub fn cell_distance(a: &CellKey, b: &CellKey) -> Option<f64> {
+    match (a, b) {
+        (
+            CellKey::Square { x, y, z },
+            CellKey::Square {
+                x: bx,
+                y: by,
+                z: bz,
+            },
+        ) if z == bz => {
+            let dx = (*x as f64) - (*bx as f64);
+            let dy = (*y as f64) - (*by as f64);
+            Some((dx * dx + dy * dy).sqrt())
+        }
+        (
+            CellKey::Hex { q, r, z },
+            CellKey::Hex {
+                q: bq,
+                r: br,
+                z: bz,
+            },
+        ) if z == bz => Some(hex_distance((*q, *r), (*bq, *br)) as f64),
+        (CellKey::Province { id }, CellKey::Province { id: other }) if id == other => Some(0.0),
+        _ => None,
+    }
+}
+
+/// Breadth-first border distances from `start`, up to `max_hops` if given.
+fn province_distances<F>(
+    start: &CellKey,
+    max_hops: Option<u32>,
+    neighbors: F,
+) -> HashMap<CellKey, u32>
+where
+    F: Fn(&CellKey) -> Vec<CellKey>,
+{
+    let mut distances = HashMap::from([(start.clone(), 0)]);
+    let mut queue = VecDeque::from([start.clone()]);
+    while let Some(cell) = queue.pop_front() {
+        let hops = distances[&cell];
+        if max_hops.is_some_and(|max| hops >= max) {
+            continue;
+        }
+        for next in neighbors(&cell) {
+            if !distances.contains_key(&next) {
+                distances.insert(next.clone(), hops + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+    distances
+}
+
+/// `(x, y, z)` of a square cell or `(q, r, z)` of a hex cell.
+fn grid_coords(cell: &CellKey) -> Option<(i32, i32, i32)> {
+    match cell {
+        CellKey::Square { x, y, z } => Some((*x, *y, *z)),
+        CellKey::Hex { q, r, z } => Some((*q, *r, *z)),
+        CellKey::Province { .. } => None,
+    }
+}
+
+/// A cell of the same topology as `template` at the given coordinates.
+fn with_coords(template: &CellKey, x: i32, y: i32, z: i32) -> CellKey {
+    match template {
+        CellKey::Hex { .. } => CellKey::Hex { q: x, r: y, z },
+        _ => CellKey::Square { x, y, z },
+    }
+}
+
+/// Number of integers in `min..=max`.
+fn span(min: i32, max: i32) -> u64 {
+    (max as i64 - min as i64 + 1) as u64
+}
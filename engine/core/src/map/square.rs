@@ -46,6 +46,22 @@ impl SquareGridMap {
             });
     }
 
+    /// Remove a neighbor from a cell
+    pub fn remove_neighbor(&mut self, from: (i32, i32, i32), to: (i32, i32, i32)) {
+        let from = CellKey::Square {
+            x: from.0,
+            y: from.1,
+            z: from.2,
+        };
+        if let Some(neighbors) = self.cells.get_mut(&from) {
+            neighbors.remove(&CellKey::Square {
+                x: to.0,
+                y: to.1,
+                z: to.2,
+            });
+        }
+    }
+
     /// Merge another SquareGridMap into this one
     pub fn merge_from(&mut self, other: &SquareGridMap) {
         for (cell, neighbors) in &other.cells {
@@ -0,0 +1,178 @@
+//! Vertical connectivity between z-levels.
+//!
+//! Cells join the level above through the `vertical` metadata field:
+//! - `"stairs"` and `"ladder"` lead to the cell directly above,
+//! - `"ramp"` leads to the cells above next to it (4 on square grids, 6 on
+//!   hex grids), so agents walk off the top of the ramp,
+//! - `"open"` cells have no floor: movement profiles treat them as not
+//!   walkable (flyers still cross them if their `terrain` is `chasm`), and
+//!   sight carries down to the level below.
+//!
+//! [`vertical_links`] lists the neighbor links these cells imply; maps add
+//! them with [`Map::connect_levels`](super::Map::connect_levels). Climbing
+//! costs come from [`MovementProfile::climb_cost`](super::MovementProfile::climb_cost).
+
+use super::CellKey;
+use serde_json::Value;
+
+/// Metadata field naming a cell's vertical connector.
+pub const VERTICAL_FIELD: &str = "vertical";
+
+/// Extra cost of a level change through a cell that is not a connector, such
+/// as one linked by hand with `add_neighbor`.
+pub const DEFAULT_CLIMB_COST: f32 = 1.0;
+
+/// How a cell connects to the level above it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VerticalKind {
+    /// Stairs up to the cell above.
+    Stairs,
+    /// A ramp up to the cells above next to it.
+    Ramp,
+    /// A ladder up to the cell above.
+    Ladder,
+    /// No floor; the level below shows through.
+    Open,
+}
+
+impl VerticalKind {
+    /// Parses a `vertical` metadata value.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "stairs" => Some(Self::Stairs),
+            "ramp" => Some(Self::Ramp),
+            "ladder" => Some(Self::Ladder),
+            "open" => Some(Self::Open),
+            _ => None,
+        }
+    }
+
+    /// The kind named by a cell's metadata, if any.
+    pub fn from_meta(meta: Option<&Value>) -> Option<Self> {
+        meta?
+            .get(VERTICAL_FIELD)
+            .and_then(|v| v.as_str())
+            .and_then(Self::from_name)
+    }
+
+    /// The `vertical` metadata value for this kind.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Stairs => "stairs",
+            Self::Ramp => "ramp",
+            Self::Ladder => "ladder",
+            Self::Open => "open",
+        }
+    }
+
+    /// Extra cost of climbing through this kind of cell, unless a movement
+    /// profile overrides it.
+    pub fn default_climb_cost(&self) -> f32 {
+        match self {
+            Self::Stairs | Self::Ramp => 1.0,
+            Self::Ladder => 2.0,
+            Self::Open => DEFAULT_CLIMB_COST,
+        }
+    }
+}
+
+/// The cell `dz` levels above `cell` (below for negative `dz`).
+pub fn shifted(cell: &CellKey, dz: i32) -> Option<CellKey> {
+    match cell {
+        CellKey::Square { x, y, z } => Some(CellKey::Square {
+            x: *x,
+            y: *y,
+            z: z + dz,
+        }),
+        CellKey::Hex { q, r, z } => Some(CellKey::Hex {
+            q: *q,
+            r: *r,
+            z: z + dz,
+        }),
+        CellKey::Province { .. } => None,
+    }
+}
+
+/// The z-level of a grid cell; `None` for provinces.
+pub fn level(cell: &CellKey) -> Option<i32> {
+    match cell {
+        CellKey::Square { z, .. } | CellKey::Hex { z, .. } => Some(*z),
+        CellKey::Province { .. } => None,
+    }
+}
+
+/// Cells a ramp at `cell` leads up to, whether or not they exist.
+fn ramp_tops(cell: &CellKey) -> Vec<CellKey> {
+    match cell {
+        CellKey::Square { x, y, z } => [(1, 0), (0, 1), (-1, 0), (0, -1)]
+            .into_iter()
+            .map(|(dx, dy)| CellKey::Square {
+                x: x + dx,
+                y: y + dy,
+                z: z + 1,
+            })
+            .collect(),
+        CellKey::Hex { q, r, z } => [(1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1), (0, 1)]
+            .into_iter()
+            .map(|(dq, dr)| CellKey::Hex {
+                q: q + dq,
+                r: r + dr,
+                z: z + 1,
+            })
+            .collect(),
+        CellKey::Province { .. } => Vec::new(),
+    }
+}
+
+/// Neighbor links implied by the connectors among `cells`, in both directions
+/// and in ascending order. Links only lead to cells for which `contains`
+/// holds, and never into open cells.
+pub fn vertical_links<'a, C, M>(cells: &[CellKey], contains: C, meta: M) -> Vec<(CellKey, CellKey)>
+where
+    C: Fn(&CellKey) -> bool,
+    M: Fn(&CellKey) -> Option<&'a Value>,
+{
+    let mut links = Vec::new();
+    for cell in cells {
+        let tops = match VerticalKind::from_meta(meta(cell)) {
+            Some(VerticalKind::Stairs | VerticalKind::Ladder) => {
+                shifted(cell, 1).into_iter().collect()
+            }
+            Some(VerticalKind::Ramp) => ramp_tops(cell),
+            Some(VerticalKind::Open) | None => continue,
+        };
+        for top in tops {
+            if contains(&top) && VerticalKind::from_meta(meta(&top)) != Some(VerticalKind::Open) {
+                links.push((cell.clone(), top.clone()));
+                links.push((top, cell.clone()));
+            }
+        }
+    }
+    links.sort();
+    links.dedup();
+    links
+}
+
+/// Cells seen looking down the open shaft at `cell`: each cell below an open
+/// cell, down to the first one with a floor or `depth` levels, whichever
+/// comes first.
+pub fn shaft_below<'a, C, M>(cell: &CellKey, depth: u32, contains: C, meta: M) -> Vec<CellKey>
+where
+    C: Fn(&CellKey) -> bool,
+    M: Fn(&CellKey) -> Option<&'a Value>,
+{
+    let mut seen = Vec::new();
+    let mut current = cell.clone();
+    while seen.len() < depth as usize
+        && VerticalKind::from_meta(meta(&current)) == Some(VerticalKind::Open)
+    {
+        match shifted(&current, -1) {
+            Some(below) if contains(&below) => {
+                seen.push(below.clone());
+                current = below;
+            }
+            _ => break,
+        }
+    }
+    seen
+}
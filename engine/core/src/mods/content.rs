@@ -0,0 +1,226 @@
+//! Game data shipped by mods.
+//!
+//! Besides schemas and scripts, a mod can contribute data files in
+//! conventional subdirectories: `recipes/`, `materials/`, `jobs/`,
+//! `loot_tables/` and `tech/`. Each JSON file holds one definition or an array
+//! of them. A definition with the same ID as an existing one replaces it, so
+//! mods later in the load order override earlier mods and the base game.
+
+use crate::ecs::world::World;
+use crate::loot::LootTable;
+use crate::systems::economic::Recipe;
+use crate::systems::job::types::JobTypeData;
+use crate::tech_tree::TechNode;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Kind of data a mod can contribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+pub enum ContentKind {
+    /// Production recipes (`recipes/`), keyed by `name`
+    Recipe,
+    /// Material definitions (`materials/`), keyed by `name`
+    Material,
+    /// Job types (`jobs/`), keyed by `name`
+    Job,
+    /// Loot tables (`loot_tables/`), keyed by `name`
+    LootTable,
+    /// Tech tree nodes (`tech/`), keyed by `id`
+    TechNode,
+}
+
+impl ContentKind {
+    /// All content kinds, in the order they are read.
+    pub const ALL: [ContentKind; 5] = [
+        ContentKind::Recipe,
+        ContentKind::Material,
+        ContentKind::Job,
+        ContentKind::LootTable,
+        ContentKind::TechNode,
+    ];
+
+    /// Subdirectory of the mod holding this kind of data.
+    pub fn dir_name(self) -> &'static str {
+        match self {
+            ContentKind::Recipe => "recipes",
+            ContentKind::Material => "materials",
+            ContentKind::Job => "jobs",
+            ContentKind::LootTable => "loot_tables",
+            ContentKind::TechNode => "tech",
+        }
+    }
+
+    /// Field holding the definition's ID.
+    pub fn key_field(self) -> &'static str {
+        match self {
+            ContentKind::TechNode => "id",
+            _ => "name",
+        }
+    }
+
+    /// Checks that a definition has the shape the engine expects.
+    fn validate(self, value: &Value) -> Result<(), String> {
+        let result = match self {
+            ContentKind::Recipe => serde_json::from_value::<Recipe>(value.clone()).map(drop),
+            ContentKind::Material => Ok(()),
+            ContentKind::Job => serde_json::from_value::<JobTypeData>(value.clone()).map(drop),
+            ContentKind::LootTable => serde_json::from_value::<LootTable>(value.clone()).map(drop),
+            ContentKind::TechNode => serde_json::from_value::<TechNode>(value.clone()).map(drop),
+        };
+        result.map_err(|e| e.to_string())
+    }
+}
+
+impl std::fmt::Display for ContentKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ContentKind::Recipe => "recipe",
+            ContentKind::Material => "material",
+            ContentKind::Job => "job",
+            ContentKind::LootTable => "loot table",
+            ContentKind::TechNode => "tech node",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A definition read from a mod's data files.
+#[derive(Debug, Clone)]
+pub struct ContentEntry {
+    /// Kind of definition
+    pub kind: ContentKind,
+    /// Definition ID (its `name`, or `id` for tech nodes)
+    pub id: String,
+    /// The definition
+    pub value: Value,
+    /// File it was read from
+    pub source: PathBuf,
+}
+
+/// Reads and validates all data files of a mod, in kind order and then file
+/// name order.
+pub fn read_mod_content<P: AsRef<Path>>(mod_dir: P) -> anyhow::Result<Vec<ContentEntry>> {
+    let mut entries = Vec::new();
+    for kind in ContentKind::ALL {
+        let dir = mod_dir.as_ref().join(kind.dir_name());
+        if !dir.is_dir() {
+            continue;
+        }
+        let mut files: Vec<PathBuf> = std::fs::read_dir(&dir)?
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == "json"))
+            .collect();
+        files.sort();
+        for file in files {
+            let data = std::fs::read_to_string(&file)
+                .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", file.display()))?;
+            let value: Value = serde_json::from_str(&data)
+                .map_err(|e| anyhow::anyhow!("Failed to parse {}: {e}", file.display()))?;
+            let values = match value {
+                Value::Array(values) => values,
+                value => vec![value],
+            };
+            for value in values {
+                let id = value
+                    .get(kind.key_field())
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "{kind} in {} has no '{}' field",
+                            file.display(),
+                            kind.key_field()
+                        )
+                    })?
+                    .to_string();
+                kind.validate(&value).map_err(|e| {
+                    anyhow::anyhow!("Invalid {kind} '{id}' in {}: {e}", file.display())
+                })?;
+                entries.push(ContentEntry {
+                    kind,
+                    id,
+                    value,
+                    source: file.clone(),
+                });
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// Adds a definition to the world's data, replacing any with the same ID.
+pub fn apply_content(world: &mut World, entry: &ContentEntry) -> anyhow::Result<()> {
+    set_content(world, entry.kind, &entry.id, &entry.value)
+}
+
+/// Sets the definition of `id`; see [`apply_content`].
+pub(crate) fn set_content(
+    world: &mut World,
+    kind: ContentKind,
+    id: &str,
+    value: &Value,
+) -> anyhow::Result<()> {
+    match kind {
+        ContentKind::Recipe => {
+            world.recipes.insert(id.to_string(), value.clone());
+        }
+        ContentKind::Material => {
+            world
+                .material_definitions
+                .insert(id.to_string(), value.clone());
+        }
+        ContentKind::Job => {
+            let data: JobTypeData = serde_json::from_value(value.clone())?;
+            world.job_types.register_data(data);
+            world.jobs.insert(id.to_string(), value.clone());
+        }
+        ContentKind::LootTable => {
+            let table: LootTable = serde_json::from_value(value.clone())?;
+            world
+                .loot_tables
+                .define_table(id, table.entries)
+                .map_err(|e| anyhow::anyhow!("{e}"))?;
+        }
+        ContentKind::TechNode => {
+            let node: TechNode = serde_json::from_value(value.clone())?;
+            world.tech_tree.insert(node);
+        }
+    }
+    Ok(())
+}
+
+/// The world's current definition of `id`, if any.
+pub(crate) fn current_content(world: &World, kind: ContentKind, id: &str) -> Option<Value> {
+    match kind {
+        ContentKind::Recipe => world.recipes.get(id).cloned(),
+        ContentKind::Material => world.material_definitions.get(id).cloned(),
+        ContentKind::Job => world
+            .jobs
+            .get(id)
+            .cloned()
+            .or_else(|| serde_json::to_value(world.job_types.get_data(id)?).ok()),
+        ContentKind::LootTable => serde_json::to_value(world.loot_tables.get_table(id)?).ok(),
+        ContentKind::TechNode => serde_json::to_value(world.tech_tree.get(id)?).ok(),
+    }
+}
+
+/// Removes a definition from the world's data.
+pub(crate) fn remove_content(world: &mut World, kind: ContentKind, id: &str) {
+    match kind {
+        ContentKind::Recipe => {
+            world.recipes.remove(id);
+        }
+        ContentKind::Material => {
+            world.material_definitions.remove(id);
+        }
+        ContentKind::Job => {
+            world.jobs.remove(id);
+            world.job_types.unregister(id);
+        }
+        ContentKind::LootTable => world.loot_tables.remove_table(id),
+        ContentKind::TechNode => {
+            world.tech_tree.remove(id);
+        }
+    }
+}
@@ -1,54 +1,157 @@
+use crate::ecs::save_migration::{SaveMigrationRegistry, for_each_component_value, mod_scope};
 use crate::ecs::schema::load_allowed_modes;
 use crate::ecs::schema::load_schemas_from_dir_with_modes;
 use crate::ecs::world::World;
-use crate::mods::manifest::ModManifest;
+use crate::mods::content::{apply_content, read_mod_content};
+use crate::mods::manifest::{ModManifest, ModMigration};
+use crate::mods::ownership::ModResource;
 use std::cell::RefCell;
+use std::path::Path;
 use std::rc::Rc;
 
-/// Loads a mod, registers schemas, and runs the main script via a scripting engine passed in.
+/// Loads a mod, registers schemas, adds its data files to the world and runs
+/// its scripts via a scripting engine passed in.
+///
+/// Dependencies aren't checked; use [`ModManager`](super::ModManager) to load
+/// a set of mods in dependency order.
 pub fn load_mod<S: ModScriptEngine>(
     mod_dir: &str,
     world: Rc<RefCell<World>>,
     engine: &mut S,
 ) -> anyhow::Result<()> {
-    // Load the mod manifest (mod.json)
-    let manifest_path = format!("{mod_dir}/mod.json");
+    let mod_dir = Path::new(mod_dir);
+    let manifest = read_manifest(mod_dir)?;
+    let content = read_mod_content(mod_dir)?;
+    register_mod_schemas(&manifest, mod_dir, &world)?;
+    for entry in &content {
+        apply_content(&mut world.borrow_mut(), entry)?;
+    }
+    run_mod_scripts(&manifest, mod_dir, engine)
+}
+
+/// Reads and validates a mod's manifest (`mod.json`).
+pub fn read_manifest(mod_dir: &Path) -> anyhow::Result<ModManifest> {
+    let manifest_path = mod_dir.join("mod.json");
     let manifest_data = std::fs::read_to_string(&manifest_path)
         .map_err(|e| anyhow::anyhow!("Failed to read mod manifest: {}", e))?;
     let manifest: ModManifest = serde_json::from_str(&manifest_data)
         .map_err(|e| anyhow::anyhow!("Failed to parse mod manifest: {}", e))?;
-
-    // Validate manifest
     manifest
         .validate()
         .map_err(|errors| anyhow::anyhow!("{}", errors.join(", ")))?;
+    Ok(manifest)
+}
 
-    // Load schemas using the unified loader
-    let schema_dir = format!("{mod_dir}/schemas");
-    let allowed_modes = load_allowed_modes()?;
-    let schemas = load_schemas_from_dir_with_modes(&schema_dir, &allowed_modes)
-        .map_err(|e| anyhow::anyhow!("Failed to load schemas: {}", e))?;
-
-    // Register schemas with the world's registry
+/// Registers a mod's schemas and save migrations with the world's registry.
+///
+/// Inside a mod scope the schemas are recorded as the mod's resources.
+pub fn register_mod_schemas(
+    manifest: &ModManifest,
+    mod_dir: &Path,
+    world: &Rc<RefCell<World>>,
+) -> anyhow::Result<()> {
     let registry = world.borrow().registry.clone();
-    for (_name, schema) in schemas {
-        registry.lock().unwrap().register_external_schema(schema);
+
+    // Load schemas using the unified loader; data-only mods may have none
+    let schema_dir = mod_dir.join("schemas");
+    if schema_dir.is_dir() {
+        let allowed_modes = load_allowed_modes()?;
+        let schemas = load_schemas_from_dir_with_modes(&schema_dir, &allowed_modes)
+            .map_err(|e| anyhow::anyhow!("Failed to load schemas: {}", e))?;
+
+        // Register schemas with the world's registry
+        for (name, schema) in schemas {
+            registry.lock().unwrap().register_external_schema(schema);
+            world
+                .borrow_mut()
+                .record_mod_resource(ModResource::Schema(name));
+        }
     }
 
-    // Optionally: Load assets, jobs, recipes, etc. as needed here
+    // Register save migrations so older saves are upgraded on load
+    register_mod_migrations(manifest, registry.lock().unwrap().save_migrations_mut());
+    Ok(())
+}
 
-    // Load and run the main system script
-    let main_script = manifest.main_script.unwrap();
-    let script_path = format!("{mod_dir}/{main_script}");
-    let script = std::fs::read_to_string(&script_path)
-        .map_err(|e| anyhow::anyhow!("Failed to read main script: {}", e))?;
-    if let Err(e) = engine.run_script(&script) {
-        return Err(anyhow::anyhow!("Error running main script: {:?}", e));
+/// Runs a mod's helper scripts, then its system files, then its main script.
+pub fn run_mod_scripts<S: ModScriptEngine>(
+    manifest: &ModManifest,
+    mod_dir: &Path,
+    engine: &mut S,
+) -> anyhow::Result<()> {
+    let main_script = manifest
+        .main_script
+        .as_deref()
+        .ok_or_else(|| anyhow::anyhow!("No main_script field found in mod manifest"))?;
+    let mut files: Vec<&str> = Vec::new();
+    for file in manifest
+        .scripts
+        .iter()
+        .chain(manifest.systems.iter().map(|s| &s.file))
+    {
+        if file != main_script && !files.contains(&file.as_str()) {
+            files.push(file);
+        }
     }
+    files.push(main_script);
 
+    for file in files {
+        let script_path = mod_dir.join(file);
+        let script = std::fs::read_to_string(&script_path)
+            .map_err(|e| anyhow::anyhow!("Failed to read script {file}: {}", e))?;
+        if let Err(e) = engine.run_script(&script) {
+            return Err(anyhow::anyhow!("Error running script {file}: {:?}", e));
+        }
+    }
     Ok(())
 }
 
+/// Registers a mod's declarative save migrations.
+///
+/// Their versions are numbered in the mod's own [`mod_scope`], independently of
+/// the engine's save format and of other mods.
+pub fn register_mod_migrations(manifest: &ModManifest, registry: &mut SaveMigrationRegistry) {
+    let scope = mod_scope(&manifest.name);
+    for migration in &manifest.migrations {
+        let name = format!(
+            "{}: {} migration v{}",
+            manifest.name, migration.component, migration.version
+        );
+        let migration = migration.clone();
+        registry.register_scoped(&scope, migration.version, &name, move |save, report| {
+            let component = migration.component.clone();
+            for_each_component_value(save, &component, |eid, value| {
+                if migration.drop {
+                    report.record_dropped(&component, Some(eid), "dropped by mod migration");
+                    return false;
+                }
+                apply_mod_migration(&migration, value);
+                report.record_migrated(&component, Some(eid), "migrated by mod migration");
+                true
+            });
+            Ok(())
+        });
+    }
+}
+
+/// Applies a declarative mod migration to a single component value.
+fn apply_mod_migration(migration: &ModMigration, value: &mut serde_json::Value) {
+    let Some(obj) = value.as_object_mut() else {
+        return;
+    };
+    for (old, new) in &migration.rename {
+        if let Some(v) = obj.remove(old) {
+            obj.insert(new.clone(), v);
+        }
+    }
+    for field in &migration.remove {
+        obj.remove(field);
+    }
+    for (field, default) in &migration.defaults {
+        obj.entry(field.clone()).or_insert_with(|| default.clone());
+    }
+}
+
 /// Trait for scripting engines that can run mod scripts.
 pub trait ModScriptEngine {
     /// Runs a mod script.
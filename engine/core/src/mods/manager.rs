@@ -0,0 +1,654 @@
+//! Discovery, dependency resolution and ordered loading of mods.
+
+use crate::ecs::world::World;
+use crate::mods::content::{
+    ContentEntry, ContentKind, apply_content, current_content, read_mod_content, remove_content,
+    set_content,
+};
+use crate::mods::loader::{ModScriptEngine, read_manifest, register_mod_schemas, run_mod_scripts};
+use crate::mods::manifest::ModManifest;
+use indexmap::IndexMap;
+use semver::{Version, VersionReq};
+use serde::Serialize;
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use thiserror::Error;
+
+/// Problems that prevent a set of mods from being loaded.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ModError {
+    /// The manifest has an unparsable version or dependency.
+    #[error("Mod '{mod_name}': {message}")]
+    InvalidManifest {
+        /// Mod with the bad manifest
+        mod_name: String,
+        /// What is wrong with it
+        message: String,
+    },
+    /// A dependency isn't installed.
+    #[error("Mod '{mod_name}' depends on missing mod '{dependency}'")]
+    MissingDependency {
+        /// Mod declaring the dependency
+        mod_name: String,
+        /// Missing mod
+        dependency: String,
+    },
+    /// A dependency is installed but disabled.
+    #[error("Mod '{mod_name}' depends on disabled mod '{dependency}'")]
+    DisabledDependency {
+        /// Mod declaring the dependency
+        mod_name: String,
+        /// Disabled mod
+        dependency: String,
+    },
+    /// A dependency is installed in a version outside the required range.
+    #[error("Mod '{mod_name}' requires '{dependency}' {required}, found {found}")]
+    VersionMismatch {
+        /// Mod declaring the dependency
+        mod_name: String,
+        /// Required mod
+        dependency: String,
+        /// Accepted versions
+        required: VersionReq,
+        /// Installed version
+        found: Version,
+    },
+    /// Mods depend on each other in a loop.
+    #[error("Mod dependency cycle: {}", .0.join(" -> "))]
+    Cycle(Vec<String>),
+}
+
+/// A definition provided by more than one mod.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModConflict {
+    /// Kind of definition
+    pub kind: ContentKind,
+    /// Definition ID
+    pub id: String,
+    /// Mods providing it, in load order; the last one wins
+    pub mods: Vec<String>,
+}
+
+/// A mod found on disk.
+#[derive(Debug, Clone)]
+pub struct DiscoveredMod {
+    /// The mod's manifest
+    pub manifest: ModManifest,
+    /// Directory holding `mod.json`
+    pub dir: PathBuf,
+}
+
+/// Finds mods, orders them by their dependencies and loads them into a world.
+///
+/// Each mod's data files (see [`content`](super::content)) are applied in load
+/// order, so a mod overrides definitions of the mods it loads after; overlaps
+/// between mods are reported by [`ModManager::conflicts`].
+///
+/// While a mod loads, the world's mod scope is set to it, so the schemas,
+/// systems, job types, subscriptions and widgets its scripts register are
+/// recorded as its own (see [`ownership`](super::ownership)).
+/// [`ModManager::unload`] removes them together with its data, restoring the
+/// definitions it replaced.
+///
+/// The manager is a shared handle: clones refer to the same mods, and no
+/// borrow is held while mod scripts run, so scripts can unload or reload
+/// other mods through a clone.
+#[derive(Debug, Default, Clone)]
+pub struct ModManager {
+    state: Rc<RefCell<ModState>>,
+}
+
+#[derive(Debug, Default)]
+struct ModState {
+    mods: IndexMap<String, DiscoveredMod>,
+    disabled: HashSet<String>,
+    loaded: Vec<String>,
+    content: IndexMap<(ContentKind, String), ContentSlot>,
+}
+
+/// The definitions of one ID: the world's own one, if any, and the ones of
+/// the loaded mods providing it, in load order.
+#[derive(Debug, Default)]
+struct ContentSlot {
+    base: Option<Value>,
+    mods: Vec<(String, Value)>,
+}
+
+impl ModManager {
+    /// Create an empty mod manager
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a mod manager with every mod in `mods_dir` (each subdirectory
+    /// holding a `mod.json`).
+    pub fn discover<P: AsRef<Path>>(mods_dir: P) -> anyhow::Result<Self> {
+        let mods_dir = mods_dir.as_ref();
+        let mut dirs: Vec<PathBuf> = std::fs::read_dir(mods_dir)
+            .map_err(|e| anyhow::anyhow!("Failed to read mods directory {mods_dir:?}: {e}"))?
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.join("mod.json").is_file())
+            .collect();
+        dirs.sort();
+        let manager = Self::new();
+        for dir in dirs {
+            manager.add_mod(dir)?;
+        }
+        Ok(manager)
+    }
+
+    /// Add the mod in `mod_dir`, returning its name.
+    pub fn add_mod<P: AsRef<Path>>(&self, mod_dir: P) -> anyhow::Result<String> {
+        let dir = mod_dir.as_ref().to_path_buf();
+        let manifest =
+            read_manifest(&dir).map_err(|e| anyhow::anyhow!("Mod in {}: {e}", dir.display()))?;
+        let name = manifest.name.clone();
+        let mut state = self.state.borrow_mut();
+        if let Some(existing) = state.mods.get(&name) {
+            anyhow::bail!(
+                "Mod '{name}' found in both {} and {}",
+                existing.dir.display(),
+                dir.display()
+            );
+        }
+        state
+            .mods
+            .insert(name.clone(), DiscoveredMod { manifest, dir });
+        Ok(name)
+    }
+
+    /// All known mods, in the order they were added
+    pub fn mods(&self) -> Vec<DiscoveredMod> {
+        self.state.borrow().mods.values().cloned().collect()
+    }
+
+    /// A known mod by name
+    pub fn get(&self, name: &str) -> Option<DiscoveredMod> {
+        self.state.borrow().mods.get(name).cloned()
+    }
+
+    /// Names of the loaded mods, in load order
+    pub fn loaded(&self) -> Vec<String> {
+        self.state.borrow().loaded.clone()
+    }
+
+    /// Whether a mod is loaded
+    pub fn is_loaded(&self, name: &str) -> bool {
+        self.state.borrow().loaded.iter().any(|m| m == name)
+    }
+
+    /// Whether a mod is enabled (mods are enabled when discovered)
+    pub fn is_enabled(&self, name: &str) -> bool {
+        let state = self.state.borrow();
+        state.mods.contains_key(name) && !state.disabled.contains(name)
+    }
+
+    /// Allow a disabled mod to be loaded again. Doesn't load it.
+    pub fn enable(&self, name: &str) -> anyhow::Result<()> {
+        let mut state = self.state.borrow_mut();
+        if !state.mods.contains_key(name) {
+            anyhow::bail!("Mod '{name}' not found");
+        }
+        state.disabled.remove(name);
+        Ok(())
+    }
+
+    /// Unload a mod if it is loaded, and skip it in later loads.
+    pub fn disable(&self, name: &str, world: &Rc<RefCell<World>>) -> anyhow::Result<()> {
+        if !self.state.borrow().mods.contains_key(name) {
+            anyhow::bail!("Mod '{name}' not found");
+        }
+        if self.is_loaded(name) {
+            self.unload(name, world)?;
+        }
+        self.state.borrow_mut().disabled.insert(name.to_string());
+        Ok(())
+    }
+
+    /// Order in which the enabled mods must be loaded so each comes after its
+    /// dependencies. Independent mods keep their discovery order.
+    ///
+    /// Returns every missing, disabled or mismatched dependency, and any
+    /// cycle, found.
+    pub fn resolve_load_order(&self) -> Result<Vec<String>, Vec<ModError>> {
+        let state = self.state.borrow();
+        let roots: Vec<&str> = state
+            .mods
+            .keys()
+            .filter(|name| !state.disabled.contains(*name))
+            .map(String::as_str)
+            .collect();
+        state.resolve(roots)
+    }
+
+    /// Load all enabled mods in dependency order, returning the order.
+    ///
+    /// Stops at the first mod failing to load; the mods before it stay loaded.
+    pub fn load_all<S: ModScriptEngine>(
+        &self,
+        world: Rc<RefCell<World>>,
+        engine: &mut S,
+    ) -> anyhow::Result<Vec<String>> {
+        let order = self.resolve_load_order().map_err(join_errors)?;
+        self.load_in_order(&order, &world, engine)?;
+        Ok(order)
+    }
+
+    /// Load a mod after its (transitive) dependencies, returning the order.
+    /// Mods already loaded are skipped.
+    pub fn load<S: ModScriptEngine>(
+        &self,
+        name: &str,
+        world: Rc<RefCell<World>>,
+        engine: &mut S,
+    ) -> anyhow::Result<Vec<String>> {
+        let order = {
+            let state = self.state.borrow();
+            if !state.mods.contains_key(name) {
+                anyhow::bail!("Mod '{name}' not found");
+            }
+            if state.disabled.contains(name) {
+                anyhow::bail!("Mod '{name}' is disabled");
+            }
+            state.resolve(vec![name]).map_err(join_errors)?
+        };
+        self.load_in_order(&order, &world, engine)?;
+        Ok(order)
+    }
+
+    /// Unload a mod: remove everything it registered and its data, restoring
+    /// the definitions it replaced.
+    ///
+    /// Fails if another loaded mod depends on it.
+    pub fn unload(&self, name: &str, world: &Rc<RefCell<World>>) -> anyhow::Result<()> {
+        if !self.is_loaded(name) {
+            anyhow::bail!("Mod '{name}' is not loaded");
+        }
+        let dependents = self.loaded_dependents(name);
+        if !dependents.is_empty() {
+            anyhow::bail!(
+                "Mod '{name}' is required by loaded mods: {}",
+                dependents.join(", ")
+            );
+        }
+        self.release(name, world);
+        Ok(())
+    }
+
+    /// Unload a mod and load it again from disk, re-reading its manifest.
+    ///
+    /// The mod keeps its place in the load order, so its definitions still
+    /// yield to those of the mods loaded after it. Mods depending on it stay
+    /// loaded; the reload fails, leaving the old version loaded, if the new
+    /// version is outside the range one of them requires. If the new version
+    /// fails to load, the mod is left unloaded.
+    pub fn reload<S: ModScriptEngine>(
+        &self,
+        name: &str,
+        world: Rc<RefCell<World>>,
+        engine: &mut S,
+    ) -> anyhow::Result<()> {
+        let dir = self
+            .get(name)
+            .ok_or_else(|| anyhow::anyhow!("Mod '{name}' not found"))?
+            .dir;
+        let manifest =
+            read_manifest(&dir).map_err(|e| anyhow::anyhow!("Mod in {}: {e}", dir.display()))?;
+        if manifest.name != name {
+            anyhow::bail!(
+                "Mod in {} was renamed from '{name}' to '{}'",
+                dir.display(),
+                manifest.name
+            );
+        }
+        let version = manifest
+            .parsed_version()
+            .map_err(|message| ModError::InvalidManifest {
+                mod_name: name.to_string(),
+                message,
+            })?;
+        let mismatches = self.rejecting_dependents(name, &version);
+        if !mismatches.is_empty() {
+            return Err(join_errors(mismatches));
+        }
+        let position = self.state.borrow().loaded.iter().position(|m| m == name);
+        if position.is_some() {
+            self.release(name, &world);
+        }
+        let order = {
+            let mut state = self.state.borrow_mut();
+            state
+                .mods
+                .insert(name.to_string(), DiscoveredMod { manifest, dir });
+            if state.disabled.contains(name) {
+                anyhow::bail!("Mod '{name}' is disabled");
+            }
+            state.resolve(vec![name]).map_err(join_errors)?
+        };
+        for dependency in &order {
+            if self.is_loaded(dependency) {
+                continue;
+            }
+            let position = if dependency == name { position } else { None };
+            self.load_one(dependency, position, &world, engine)
+                .map_err(|e| anyhow::anyhow!("Failed to load mod '{dependency}': {e}"))?;
+        }
+        Ok(())
+    }
+
+    /// Version mismatches between `version` of `name` and the ranges its loaded
+    /// dependents require.
+    fn rejecting_dependents(&self, name: &str, version: &Version) -> Vec<ModError> {
+        let state = self.state.borrow();
+        let mut errors = Vec::new();
+        for loaded in &state.loaded {
+            let Ok(dependencies) = state.mods[loaded.as_str()].manifest.parsed_dependencies()
+            else {
+                continue;
+            };
+            for dependency in dependencies {
+                if dependency.name == name && !dependency.version.matches(version) {
+                    errors.push(ModError::VersionMismatch {
+                        mod_name: loaded.clone(),
+                        dependency: dependency.name,
+                        required: dependency.version,
+                        found: version.clone(),
+                    });
+                }
+            }
+        }
+        errors
+    }
+
+    /// Loaded mods that declare a dependency on `name`
+    fn loaded_dependents(&self, name: &str) -> Vec<String> {
+        let state = self.state.borrow();
+        state
+            .loaded
+            .iter()
+            .filter(|loaded| {
+                state.mods[loaded.as_str()]
+                    .manifest
+                    .parsed_dependencies()
+                    .is_ok_and(|deps| deps.iter().any(|d| d.name == name))
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn load_in_order<S: ModScriptEngine>(
+        &self,
+        order: &[String],
+        world: &Rc<RefCell<World>>,
+        engine: &mut S,
+    ) -> anyhow::Result<()> {
+        for name in order {
+            if !self.is_loaded(name) {
+                self.load_one(name, None, world, engine)
+                    .map_err(|e| anyhow::anyhow!("Failed to load mod '{name}': {e}"))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Load one mod inside its mod scope, rolling it back if any step fails.
+    ///
+    /// The mod goes at `position` in the load order (the end if `None`), but
+    /// never before one of its dependencies.
+    fn load_one<S: ModScriptEngine>(
+        &self,
+        name: &str,
+        position: Option<usize>,
+        world: &Rc<RefCell<World>>,
+        engine: &mut S,
+    ) -> anyhow::Result<()> {
+        let discovered = self.state.borrow().mods[name].clone();
+        let content = read_mod_content(&discovered.dir)?;
+        {
+            let mut state = self.state.borrow_mut();
+            let dependencies = discovered
+                .manifest
+                .parsed_dependencies()
+                .unwrap_or_default();
+            let after_dependencies = state
+                .loaded
+                .iter()
+                .rposition(|loaded| dependencies.iter().any(|d| &d.name == loaded))
+                .map_or(0, |pos| pos + 1);
+            let position = position
+                .unwrap_or(state.loaded.len())
+                .clamp(after_dependencies, state.loaded.len());
+            state.loaded.insert(position, name.to_string());
+        }
+        world.borrow_mut().begin_mod_scope(name);
+        let result = self
+            .apply(name, &discovered, &content, world)
+            .and_then(|()| {
+                // Scripts may use the manager and the world themselves
+                run_mod_scripts(&discovered.manifest, &discovered.dir, engine)
+            });
+        world.borrow_mut().end_mod_scope();
+        if result.is_err() {
+            self.release(name, world);
+        }
+        result
+    }
+
+    fn apply(
+        &self,
+        name: &str,
+        discovered: &DiscoveredMod,
+        content: &[ContentEntry],
+        world: &Rc<RefCell<World>>,
+    ) -> anyhow::Result<()> {
+        register_mod_schemas(&discovered.manifest, &discovered.dir, world)?;
+        let mut state = self.state.borrow_mut();
+        let mut world = world.borrow_mut();
+        let load_order = state.loaded.clone();
+        let rank = |provider: &str| load_order.iter().position(|loaded| loaded == provider);
+        for entry in content {
+            let slot = state
+                .content
+                .entry((entry.kind, entry.id.clone()))
+                .or_insert_with(|| ContentSlot {
+                    base: current_content(&world, entry.kind, &entry.id),
+                    mods: Vec::new(),
+                });
+            slot.mods.retain(|(provider, _)| provider != name);
+            // Slots follow the load order; only the last provider's definition is in effect
+            let at = slot
+                .mods
+                .iter()
+                .position(|(provider, _)| rank(provider) > rank(name))
+                .unwrap_or(slot.mods.len());
+            slot.mods
+                .insert(at, (name.to_string(), entry.value.clone()));
+            if at + 1 == slot.mods.len() {
+                apply_content(&mut world, entry)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove a mod's resources and data and mark it unloaded.
+    fn release(&self, name: &str, world: &Rc<RefCell<World>>) {
+        let mut world = world.borrow_mut();
+        world.release_mod_resources(name);
+        let mut state = self.state.borrow_mut();
+        for ((kind, id), slot) in state.content.iter_mut() {
+            let Some(pos) = slot.mods.iter().position(|(provider, _)| provider == name) else {
+                continue;
+            };
+            let was_active = pos + 1 == slot.mods.len();
+            slot.mods.remove(pos);
+            if !was_active {
+                continue;
+            }
+            match slot
+                .mods
+                .last()
+                .map(|(_, value)| value)
+                .or(slot.base.as_ref())
+            {
+                Some(value) => {
+                    if let Err(e) = set_content(&mut world, *kind, id, value) {
+                        log::warn!("Failed to restore {kind} '{id}': {e}");
+                    }
+                }
+                None => remove_content(&mut world, *kind, id),
+            }
+        }
+        state.content.retain(|_, slot| !slot.mods.is_empty());
+        state.loaded.retain(|loaded| loaded != name);
+    }
+
+    /// Definitions provided by more than one loaded mod.
+    pub fn conflicts(&self) -> Vec<ModConflict> {
+        self.state
+            .borrow()
+            .content
+            .iter()
+            .filter(|(_, slot)| slot.mods.len() > 1)
+            .map(|((kind, id), slot)| ModConflict {
+                kind: *kind,
+                id: id.clone(),
+                mods: slot.mods.iter().map(|(m, _)| m.clone()).collect(),
+            })
+            .collect()
+    }
+
+    /// The mod whose definition of `id` is in effect, if a loaded mod provides it
+    pub fn provider(&self, kind: ContentKind, id: &str) -> Option<String> {
+        self.state
+            .borrow()
+            .content
+            .get(&(kind, id.to_string()))
+            .and_then(|slot| slot.mods.last())
+            .map(|(provider, _)| provider.clone())
+    }
+}
+
+impl ModState {
+    fn resolve(&self, roots: Vec<&str>) -> Result<Vec<String>, Vec<ModError>> {
+        let mut resolver = Resolver {
+            mods: &self.mods,
+            disabled: &self.disabled,
+            state: HashMap::new(),
+            stack: Vec::new(),
+            order: Vec::new(),
+            errors: Vec::new(),
+        };
+        for name in roots {
+            resolver.visit(name);
+        }
+        if resolver.errors.is_empty() {
+            Ok(resolver.order)
+        } else {
+            Err(resolver.errors)
+        }
+    }
+}
+
+fn join_errors(errors: Vec<ModError>) -> anyhow::Error {
+    anyhow::anyhow!(
+        "{}",
+        errors
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; ")
+    )
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum VisitState {
+    Visiting,
+    Done,
+}
+
+/// Depth-first post-order walk of the dependency graph: dependencies land in
+/// `order` before their dependents, and a dependency already on the stack
+/// closes a cycle.
+struct Resolver<'a> {
+    mods: &'a IndexMap<String, DiscoveredMod>,
+    disabled: &'a HashSet<String>,
+    state: HashMap<&'a str, VisitState>,
+    stack: Vec<&'a str>,
+    order: Vec<String>,
+    errors: Vec<ModError>,
+}
+
+impl<'a> Resolver<'a> {
+    fn visit(&mut self, name: &'a str) {
+        match self.state.get(name) {
+            Some(VisitState::Done) => return,
+            Some(VisitState::Visiting) => {
+                let start = self.stack.iter().position(|n| *n == name).unwrap_or(0);
+                let mut cycle: Vec<String> =
+                    self.stack[start..].iter().map(|n| n.to_string()).collect();
+                cycle.push(name.to_string());
+                self.errors.push(ModError::Cycle(cycle));
+                return;
+            }
+            None => {}
+        }
+        self.state.insert(name, VisitState::Visiting);
+        self.stack.push(name);
+        for dependency in self.dependencies(name) {
+            self.visit(dependency);
+        }
+        self.stack.pop();
+        self.state.insert(name, VisitState::Done);
+        self.order.push(name.to_string());
+    }
+
+    /// Installed dependencies of a mod, recording the missing and mismatched
+    /// ones.
+    fn dependencies(&mut self, name: &'a str) -> Vec<&'a str> {
+        let manifest = &self.mods[name].manifest;
+        let dependencies = match manifest.parsed_dependencies() {
+            Ok(dependencies) => dependencies,
+            Err(message) => {
+                self.errors.push(ModError::InvalidManifest {
+                    mod_name: name.to_string(),
+                    message,
+                });
+                return Vec::new();
+            }
+        };
+        let mut installed = Vec::new();
+        for dependency in dependencies {
+            let Some((dep_name, dep)) = self.mods.get_key_value(&dependency.name) else {
+                self.errors.push(ModError::MissingDependency {
+                    mod_name: name.to_string(),
+                    dependency: dependency.name,
+                });
+                continue;
+            };
+            if self.disabled.contains(dep_name) {
+                self.errors.push(ModError::DisabledDependency {
+                    mod_name: name.to_string(),
+                    dependency: dependency.name,
+                });
+                continue;
+            }
+            // Manifests are validated when added, so the version parses
+            if let Ok(found) = dep.manifest.parsed_version()
+                && !dependency.version.matches(&found)
+            {
+                self.errors.push(ModError::VersionMismatch {
+                    mod_name: name.to_string(),
+                    dependency: dependency.name,
+                    required: dependency.version,
+                    found,
+                });
+            }
+            installed.push(dep_name.as_str());
+        }
+        installed
+    }
+}
@@ -1,4 +1,7 @@
+use semver::{Version, VersionReq};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
 
 /// Mod system
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,6 +15,31 @@ pub struct ModSystem {
     pub dependencies: Vec<String>,
 }
 
+/// Declarative save migration shipped by a mod.
+///
+/// Applied to every saved value of `component` when loading saves older than `version`.
+/// Each mod numbers its migrations from 1, independently of the engine's save
+/// format and of other mods.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModMigration {
+    /// Version of the mod's save data this migration upgrades to
+    pub version: u32,
+    /// Component the migration applies to
+    pub component: String,
+    /// Fields to rename (old name to new name)
+    #[serde(default)]
+    pub rename: HashMap<String, String>,
+    /// Default values for missing fields
+    #[serde(default)]
+    pub defaults: Map<String, Value>,
+    /// Fields to remove
+    #[serde(default)]
+    pub remove: Vec<String>,
+    /// Drop the component from all entities
+    #[serde(default)]
+    pub drop: bool,
+}
+
 /// Mod manifest
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModManifest {
@@ -24,7 +52,8 @@ pub struct ModManifest {
     pub description: String,
     /// Main script file path
     pub main_script: Option<String>,
-    /// Mod dependencies
+    /// Mod dependencies: a mod name, optionally followed by a semver range
+    /// (`"base_game"`, `"base_game ^1.2"`, `"base_game >=1.0, <2.0"`)
     #[serde(default)]
     pub dependencies: Vec<String>,
     /// Mod schemas
@@ -36,6 +65,9 @@ pub struct ModManifest {
     /// Mod scripts
     #[serde(default)]
     pub scripts: Vec<String>,
+    /// Mod save migrations
+    #[serde(default)]
+    pub migrations: Vec<ModMigration>,
 }
 
 impl ModManifest {
@@ -45,10 +77,63 @@ impl ModManifest {
         if self.main_script.is_none() {
             errors.push("No main_script field found in mod manifest".to_string());
         }
+        if let Err(e) = self.parsed_version() {
+            errors.push(e);
+        }
+        if let Err(e) = self.parsed_dependencies() {
+            errors.push(e);
+        }
         if errors.is_empty() {
             Ok(())
         } else {
             Err(errors)
         }
     }
+
+    /// The mod version as semver.
+    pub fn parsed_version(&self) -> Result<Version, String> {
+        Version::parse(&self.version)
+            .map_err(|e| format!("Invalid mod version '{}': {e}", self.version))
+    }
+
+    /// The dependencies with their version requirements.
+    pub fn parsed_dependencies(&self) -> Result<Vec<ModDependency>, String> {
+        self.dependencies
+            .iter()
+            .map(|dep| ModDependency::parse(dep))
+            .collect()
+    }
+}
+
+/// A dependency on another mod.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModDependency {
+    /// Name of the required mod
+    pub name: String,
+    /// Accepted versions (`*` when the manifest gives no range)
+    pub version: VersionReq,
+}
+
+impl ModDependency {
+    /// Parse a manifest dependency entry: a mod name, optionally followed by
+    /// whitespace and a semver range.
+    pub fn parse(entry: &str) -> Result<Self, String> {
+        let entry = entry.trim();
+        let (name, range) = entry.split_once(char::is_whitespace).unwrap_or((entry, ""));
+        if name.is_empty() {
+            return Err("Empty mod dependency".to_string());
+        }
+        let range = range.trim();
+        let version = if range.is_empty() {
+            VersionReq::STAR
+        } else {
+            VersionReq::parse(range).map_err(|e| {
+                format!("Invalid version range '{range}' for dependency '{name}': {e}")
+            })?
+        };
+        Ok(Self {
+            name: name.to_string(),
+            version,
+        })
+    }
 }
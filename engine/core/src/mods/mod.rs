@@ -2,7 +2,17 @@
 //!
 //! This module contains the API for mods.
 
+/// Data files contributed by mods.
+pub mod content;
 /// Mod loader.
 pub mod loader;
+/// Mod manager.
+pub mod manager;
 /// Mod Manifests.
 pub mod manifest;
+/// Per-mod ownership of registered resources.
+pub mod ownership;
+
+pub use content::ContentKind;
+pub use manager::{ModConflict, ModError, ModManager};
+pub use ownership::ModResource;
@@ -0,0 +1,76 @@
+//! Tracking of what each mod registered, so it can be unloaded.
+//!
+//! While a mod loads, the world has a mod scope open (see
+//! [`World::begin_mod_scope`](crate::ecs::world::World::begin_mod_scope)).
+//! Schemas, dynamic systems, job types, event subscriptions and UI widgets
+//! registered in that time are recorded as the mod's resources, and
+//! [`World::release_mod_resources`](crate::ecs::world::World::release_mod_resources)
+//! removes them again.
+
+use crate::ecs::event::SubscriberId;
+use crate::ecs::world::World;
+use crate::presentation::ui::widget::WidgetId;
+use indexmap::IndexMap;
+
+/// Something a mod registered with the world.
+#[derive(Debug, Clone)]
+pub enum ModResource {
+    /// A component schema (unregistering it drops its component data)
+    Schema(String),
+    /// A dynamic system
+    System(String),
+    /// A job type and its handler
+    JobType(String),
+    /// An event bus subscription
+    Subscription {
+        /// Event bus name
+        bus: String,
+        /// Subscriber on that bus
+        id: SubscriberId,
+        /// Removes the subscription (the bus's event type is erased here)
+        unsubscribe: fn(&World, &str, SubscriberId) -> bool,
+    },
+    /// A UI widget in the global widget registry
+    Widget(WidgetId),
+}
+
+/// Resources registered by each mod, and the mod currently loading.
+#[derive(Debug, Default)]
+pub struct ModOwnership {
+    scopes: Vec<String>,
+    owned: IndexMap<String, Vec<ModResource>>,
+}
+
+impl ModOwnership {
+    /// The mod whose code is running, if any (the innermost scope)
+    pub fn current(&self) -> Option<&str> {
+        self.scopes.last().map(String::as_str)
+    }
+
+    pub(crate) fn push_scope(&mut self, mod_name: &str) {
+        self.scopes.push(mod_name.to_string());
+    }
+
+    pub(crate) fn pop_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    /// Attributes a resource to the current mod; does nothing outside a mod scope.
+    pub fn record(&mut self, resource: ModResource) {
+        if let Some(current) = self.scopes.last() {
+            self.owned
+                .entry(current.clone())
+                .or_default()
+                .push(resource);
+        }
+    }
+
+    /// Resources registered by a mod, in registration order
+    pub fn resources(&self, mod_name: &str) -> &[ModResource] {
+        self.owned.get(mod_name).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    pub(crate) fn take(&mut self, mod_name: &str) -> Vec<ModResource> {
+        self.owned.shift_remove(mod_name).unwrap_or_default()
+    }
+}
@@ -0,0 +1,212 @@
+//! C ABI description of the plugin types.
+//!
+//! The `#[repr(C)]` structs shared with plugins are declared through
+//! [`c_abi_struct!`], which also records their C declarations. The C header
+//! `engine/engine_plugin_abi.h` is generated from those by [`c_header`], so the
+//! header can't drift from the Rust definitions (regenerate it with
+//! `cargo run -p xtask -- gen-plugin-header`).
+
+use std::os::raw::{c_char, c_void};
+
+/// A Rust type with a C spelling.
+pub trait CType {
+    /// The C type name, e.g. `const char *`.
+    fn c_name() -> String;
+
+    /// A C declaration of `name` with this type, e.g. `const char *name`.
+    fn c_decl(name: &str) -> String {
+        let ty = Self::c_name();
+        if ty.ends_with('*') {
+            format!("{ty}{name}")
+        } else {
+            format!("{ty} {name}")
+        }
+    }
+}
+
+macro_rules! c_scalar {
+    ($($ty:ty => $c:literal),* $(,)?) => {
+        $(impl CType for $ty {
+            fn c_name() -> String {
+                $c.to_string()
+            }
+        })*
+    };
+}
+
+c_scalar! {
+    () => "void",
+    c_void => "void",
+    c_char => "char",
+    i32 => "int32_t",
+    u32 => "uint32_t",
+    i64 => "int64_t",
+    u64 => "uint64_t",
+    f32 => "float",
+    f64 => "double",
+}
+
+/// Appends a pointer star, without a space between consecutive stars.
+fn pointer_to(inner: String) -> String {
+    if inner.ends_with('*') {
+        format!("{inner}*")
+    } else {
+        format!("{inner} *")
+    }
+}
+
+impl<T: CType> CType for *mut T {
+    fn c_name() -> String {
+        pointer_to(T::c_name())
+    }
+}
+
+impl<T: CType> CType for *const T {
+    fn c_name() -> String {
+        pointer_to(format!("const {}", T::c_name()))
+    }
+}
+
+/// Nullable function pointers are spelled like plain ones in C.
+impl<T: CType> CType for Option<T> {
+    fn c_name() -> String {
+        T::c_name()
+    }
+
+    fn c_decl(name: &str) -> String {
+        T::c_decl(name)
+    }
+}
+
+macro_rules! c_fn_pointer {
+    ($($arg:ident),*) => {
+        impl<R: CType $(, $arg: CType)*> CType for unsafe extern "C" fn($($arg),*) -> R {
+            fn c_name() -> String {
+                Self::c_decl("")
+            }
+
+            fn c_decl(name: &str) -> String {
+                let args: Vec<String> = vec![$($arg::c_name()),*];
+                let args = if args.is_empty() {
+                    "void".to_string()
+                } else {
+                    args.join(", ")
+                };
+                let ret = R::c_name();
+                let sep = if ret.ends_with('*') { "" } else { " " };
+                format!("{ret}{sep}(*{name})({args})")
+            }
+        }
+    };
+}
+
+c_fn_pointer!();
+c_fn_pointer!(A);
+c_fn_pointer!(A, B);
+c_fn_pointer!(A, B, C);
+c_fn_pointer!(A, B, C, D);
+c_fn_pointer!(A, B, C, D, E);
+
+/// Declares a `#[repr(C)]` struct shared with plugins, implementing [`CType`] for
+/// it and a `c_declaration()` that spells it as a C typedef. Field doc comments
+/// become C comments.
+macro_rules! c_abi_struct {
+    (
+        $(#[$meta:meta])*
+        pub struct $name:ident {
+            $(
+                $(#[doc = $doc:literal])*
+                pub $field:ident: $ty:ty,
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        #[repr(C)]
+        pub struct $name {
+            $(
+                $(#[doc = $doc])*
+                pub $field: $ty,
+            )*
+        }
+
+        impl $crate::plugins::abi::CType for $name {
+            fn c_name() -> String {
+                format!("struct {}", stringify!($name))
+            }
+        }
+
+        impl $name {
+            /// C typedef of this struct, for the generated plugin header.
+            pub fn c_declaration() -> String {
+                let mut out = format!("typedef struct {} {{\n", stringify!($name));
+                $(
+                    let docs: &[&str] = &[$($doc),*];
+                    for line in docs {
+                        out.push_str(&format!("  //{}\n", line.trim_end()));
+                    }
+                    out.push_str(&format!(
+                        "  {};\n",
+                        <$ty as $crate::plugins::abi::CType>::c_decl(stringify!($field))
+                    ));
+                )*
+                out.push_str(&format!("}} {};\n", stringify!($name)));
+                out
+            }
+        }
+    };
+}
+
+pub(crate) use c_abi_struct;
+
+/// The C plugin header (`engine/engine_plugin_abi.h`) for the current ABI.
+pub fn c_header() -> String {
+    use super::types::{
+        ENGINE_API_VERSION, EngineApi, PLUGIN_ABI_VERSION, PluginVTable, SystemPlugin,
+    };
+
+    let mut out = String::new();
+    out.push_str(
+        "// Generated from engine/core/src/plugins/types.rs; do not edit.\n\
+         // Regenerate with `cargo run -p xtask -- gen-plugin-header`.\n\
+         #ifndef ENGINE_PLUGIN_ABI_H\n\
+         #define ENGINE_PLUGIN_ABI_H\n\n\
+         #include <stddef.h>\n\
+         #include <stdint.h>\n\n",
+    );
+    out.push_str(&format!(
+        "// Current ABI version. Increment on any breaking change to PluginVTable layout.\n\
+         #define PLUGIN_ABI_VERSION {PLUGIN_ABI_VERSION}\n\n\
+         // EngineApi revision. Incremented when functions are appended to EngineApi.\n\
+         #define ENGINE_API_VERSION {ENGINE_API_VERSION}\n\n"
+    ));
+    out.push_str(
+        "// True if the engine that filled `api` provides `field` (older engines pass a\n\
+         // shorter EngineApi).\n\
+         #define ENGINE_API_HAS(api, field) \\\n  \
+         ((api)->struct_size >= offsetof(EngineApi, field) + sizeof((api)->field))\n\n\
+         #ifdef __cplusplus\n\
+         extern \"C\" {\n\
+         #endif\n\n\
+         typedef void *WorldPtr;\n\n\
+         typedef void (*SystemRunFn)(WorldPtr, float delta_time);\n\n",
+    );
+    for declaration in [
+        SystemPlugin::c_declaration(),
+        EngineApi::c_declaration(),
+        PluginVTable::c_declaration(),
+    ] {
+        out.push_str(&declaration);
+        out.push('\n');
+    }
+    out.push_str(
+        "extern PluginVTable *PLUGIN_VTABLE;\n\n\
+         const char *worldgen_name(void);\n\
+         int generate_world(const char *params_json, char **out_result_json);\n\
+         void free_result_json(char *result_json);\n\n\
+         #ifdef __cplusplus\n\
+         }\n\
+         #endif\n\n\
+         #endif // ENGINE_PLUGIN_ABI_H\n",
+    );
+    out
+}
@@ -8,10 +8,13 @@ use topo_sort::{SortResults, TopoSort};
 /// A function that can be run on a world
 pub type DynSystemFn = Box<dyn Fn(Rc<RefCell<World>>, f32) + 'static>;
 
+/// A registered dynamic system, shared so it can run without borrowing the registry.
+pub type SharedDynSystemFn = Rc<dyn Fn(Rc<RefCell<World>>, f32) + 'static>;
+
 /// A registry of dynamic systems
 #[derive(Default)]
 pub struct DynamicSystemRegistry {
-    systems: IndexMap<String, DynSystemFn>,
+    systems: IndexMap<String, SharedDynSystemFn>,
     dependencies: HashMap<String, Vec<String>>,
 }
 
@@ -31,7 +34,7 @@ impl DynamicSystemRegistry {
 
     /// Register a system
     pub fn register_system(&mut self, name: String, run: DynSystemFn) {
-        self.systems.insert(name.clone(), run);
+        self.systems.insert(name.clone(), Rc::from(run));
         self.dependencies.entry(name).or_default();
     }
 
@@ -42,7 +45,7 @@ impl DynamicSystemRegistry {
         dependencies: Vec<String>,
         run: DynSystemFn,
     ) {
-        self.systems.insert(name.clone(), run);
+        self.systems.insert(name.clone(), Rc::from(run));
         self.dependencies.insert(name, dependencies);
     }
 
@@ -116,6 +119,11 @@ impl DynamicSystemRegistry {
         }
     }
 
+    /// Get a handle to a system, to run it after releasing borrows of the world
+    pub fn get_system(&self, name: &str) -> Option<SharedDynSystemFn> {
+        self.systems.get(name).cloned()
+    }
+
     /// List all systems
     pub fn list_systems(&self) -> Vec<String> {
         self.systems.keys().cloned().collect()
@@ -1,5 +1,7 @@
 use crate::ecs::World;
-use std::ffi::CStr;
+use crate::ecs::query::Query;
+use crate::map::CellKey;
+use std::ffi::{CStr, CString};
 use std::os::raw::{c_char, c_uint, c_void};
 
 /// # Safety
@@ -43,3 +45,233 @@ pub unsafe extern "C" fn ffi_set_component(
         Err(_) => -1,
     }
 }
+
+/// Reads a C string argument, or `None` if it is null or not UTF-8.
+unsafe fn str_arg<'a>(ptr: *const c_char) -> Option<&'a str> {
+    if ptr.is_null() {
+        return None;
+    }
+    unsafe { CStr::from_ptr(ptr) }.to_str().ok()
+}
+
+/// Reads a JSON C string argument.
+unsafe fn json_arg<T: serde::de::DeserializeOwned>(ptr: *const c_char) -> Option<T> {
+    serde_json::from_str(unsafe { str_arg(ptr) }?).ok()
+}
+
+/// Serializes a value to an engine-allocated C string, released with [`ffi_free_string`].
+fn json_out<T: serde::Serialize>(value: &T) -> *mut c_char {
+    serde_json::to_string(value)
+        .ok()
+        .and_then(|json| CString::new(json).ok())
+        .map_or(std::ptr::null_mut(), CString::into_raw)
+}
+
+/// # Safety
+///
+/// - `world` must be a valid pointer to a `World`.
+/// - Caller must ensure exclusive access to the pointed `World`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ffi_despawn_entity(world: *mut c_void, entity: c_uint) -> i32 {
+    let world = unsafe { &mut *(world as *mut World) };
+    if !world.entity_exists(entity) {
+        return -1;
+    }
+    world.despawn_entity(entity);
+    0
+}
+
+/// # Safety
+///
+/// - `world` must be a valid pointer to a `World`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ffi_entity_exists(world: *mut c_void, entity: c_uint) -> i32 {
+    let world = unsafe { &*(world as *const World) };
+    world.entity_exists(entity) as i32
+}
+
+/// # Safety
+///
+/// - `world` must be a valid pointer to a `World`.
+/// - `name` must be a valid null-terminated C string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ffi_get_component(
+    world: *mut c_void,
+    entity: c_uint,
+    name: *const c_char,
+) -> *mut c_char {
+    let world = unsafe { &*(world as *const World) };
+    let Some(name) = (unsafe { str_arg(name) }) else {
+        return std::ptr::null_mut();
+    };
+    world
+        .get_component(entity, name)
+        .map_or(std::ptr::null_mut(), json_out)
+}
+
+/// # Safety
+///
+/// - `world` must be a valid pointer to a `World`.
+/// - `name` must be a valid null-terminated C string.
+/// - Caller must ensure exclusive access to the pointed `World`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ffi_remove_component(
+    world: *mut c_void,
+    entity: c_uint,
+    name: *const c_char,
+) -> i32 {
+    let world = unsafe { &mut *(world as *mut World) };
+    let Some(name) = (unsafe { str_arg(name) }) else {
+        return -1;
+    };
+    match world.remove_component(entity, name) {
+        Ok(_) => 0,
+        Err(_) => -1,
+    }
+}
+
+/// # Safety
+///
+/// - `world` must be a valid pointer to a `World`.
+/// - `name` must be a valid null-terminated C string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ffi_get_entities_with_component(
+    world: *mut c_void,
+    name: *const c_char,
+) -> *mut c_char {
+    let world = unsafe { &*(world as *const World) };
+    let Some(name) = (unsafe { str_arg(name) }) else {
+        return std::ptr::null_mut();
+    };
+    json_out(&world.get_entities_with_component(name))
+}
+
+/// # Safety
+///
+/// - `world` must be a valid pointer to a `World`.
+/// - `spec_json` must be a valid null-terminated C string holding a query spec.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ffi_query_entities(
+    world: *mut c_void,
+    spec_json: *const c_char,
+) -> *mut c_char {
+    let world = unsafe { &*(world as *const World) };
+    let Some(query) = (unsafe { json_arg::<Query>(spec_json) }) else {
+        return std::ptr::null_mut();
+    };
+    json_out(&query.entities(world))
+}
+
+/// # Safety
+///
+/// - `world` must be a valid pointer to a `World`.
+/// - `bus` and `json_payload` must be valid null-terminated C strings.
+/// - Caller must ensure exclusive access to the pointed `World`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ffi_send_event(
+    world: *mut c_void,
+    bus: *const c_char,
+    json_payload: *const c_char,
+) -> i32 {
+    let world = unsafe { &mut *(world as *mut World) };
+    let (Some(bus), Some(payload)) = (unsafe { str_arg(bus) }, unsafe {
+        json_arg::<serde_json::Value>(json_payload)
+    }) else {
+        return -1;
+    };
+    match world.send_event(bus, payload) {
+        Ok(_) => 0,
+        Err(_) => -1,
+    }
+}
+
+/// # Safety
+///
+/// - `world` must be a valid pointer to a `World`.
+/// - `bus` must be a valid null-terminated C string.
+/// - Caller must ensure exclusive access to the pointed `World`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ffi_poll_events(world: *mut c_void, bus: *const c_char) -> *mut c_char {
+    let world = unsafe { &mut *(world as *mut World) };
+    let Some(bus) = (unsafe { str_arg(bus) }) else {
+        return std::ptr::null_mut();
+    };
+    json_out(&world.take_events(bus))
+}
+
+/// # Safety
+///
+/// - `world` must be a valid pointer to a `World`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ffi_get_turn(world: *mut c_void) -> c_uint {
+    let world = unsafe { &*(world as *const World) };
+    world.turn
+}
+
+/// # Safety
+///
+/// - `world` must be a valid pointer to a `World`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ffi_get_map_topology(world: *mut c_void) -> *mut c_char {
+    let world = unsafe { &*(world as *const World) };
+    world
+        .map
+        .as_ref()
+        .and_then(|map| CString::new(map.topology_type()).ok())
+        .map_or(std::ptr::null_mut(), CString::into_raw)
+}
+
+/// # Safety
+///
+/// - `world` must be a valid pointer to a `World`.
+/// - `cell_json` must be a valid null-terminated C string holding a cell.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ffi_get_neighbors(
+    world: *mut c_void,
+    cell_json: *const c_char,
+) -> *mut c_char {
+    let world = unsafe { &*(world as *const World) };
+    let (Some(map), Some(cell)) = (world.map.as_ref(), unsafe {
+        json_arg::<CellKey>(cell_json)
+    }) else {
+        return std::ptr::null_mut();
+    };
+    json_out(&map.neighbors(&cell))
+}
+
+/// # Safety
+///
+/// - `world` must be a valid pointer to a `World`.
+/// - `start_json` and `goal_json` must be valid null-terminated C strings holding cells.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ffi_find_path(
+    world: *mut c_void,
+    start_json: *const c_char,
+    goal_json: *const c_char,
+) -> *mut c_char {
+    let world = unsafe { &*(world as *const World) };
+    let (Some(start), Some(goal)) = (unsafe { json_arg::<CellKey>(start_json) }, unsafe {
+        json_arg::<CellKey>(goal_json)
+    }) else {
+        return std::ptr::null_mut();
+    };
+    world
+        .find_path(&start, &goal)
+        .map_or(std::ptr::null_mut(), |result| {
+            json_out(&serde_json::json!({
+                "path": result.path,
+                "total_cost": result.total_cost,
+            }))
+        })
+}
+
+/// # Safety
+///
+/// - `ptr` must be null or a string returned by one of the engine API functions,
+///   and must not be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ffi_free_string(ptr: *mut c_char) {
+    if !ptr.is_null() {
+        drop(unsafe { CString::from_raw(ptr) });
+    }
+}
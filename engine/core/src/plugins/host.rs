@@ -0,0 +1,555 @@
+//! Hot-reloading host for native plugins.
+//!
+//! [`NativePluginHost`] owns the libraries of the plugins it loads and remembers
+//! what each one registered (dynamic systems and worldgen entries), so a plugin
+//! can be unloaded, or swapped for a rebuilt version while the game runs:
+//!
+//! 1. [`NativePluginHost::poll_changes`] compares the modification time of every
+//!    plugin library with the one it was loaded from.
+//! 2. [`NativePluginHost::reload`] opens the new build from a private copy, so the
+//!    old version stays mapped. It calls `init` and collects the new version's
+//!    systems and worldgen entry.
+//! 3. Only then is the old version's state handed to the new version through
+//!    `hot_reload`, the old version's registrations replaced and its library
+//!    closed. If anything before that fails, the old version stays in place with
+//!    its state, and the new version is shut down if it passed `init`.
+//!
+//! Only registrations are rolled back: entities or components a failed `init`
+//! created through the [`EngineApi`] stay in the world.
+//!
+//! [`NativePluginHost::watch`] runs these steps at the start of every tick.
+
+use crate::config::GameConfig;
+use crate::ecs::World;
+use crate::ecs::schedule::SystemStage;
+use crate::plugins::PLUGIN_ABI_VERSION;
+use crate::plugins::loader::native_plugin_paths;
+use crate::plugins::types::{EngineApi, PluginVTable, SystemPlugin};
+use crate::worldgen::{WorldgenPlugin, WorldgenRegistry};
+use indexmap::IndexMap;
+use libloading::{Library, Symbol};
+use serde_json::Value;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::fs;
+use std::os::raw::{c_char, c_int, c_void};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// Name of the dynamic system registered by [`NativePluginHost::watch`].
+pub const HOT_RELOAD_SYSTEM: &str = "native_plugin_hot_reload";
+
+/// System run function exported by a plugin.
+type PluginRunFn = unsafe extern "C" fn(*mut c_void, f32);
+
+/// Worldgen function wrapping a plugin's `generate_world`.
+type PluginGenerateFn = Arc<dyn Fn(&Value) -> Value + Send + Sync>;
+
+/// A plugin version that passed `init` but isn't registered yet.
+struct StagedPlugin {
+    copy: PathBuf,
+    vtable: *mut PluginVTable,
+    systems: Vec<(String, PluginRunFn)>,
+    worldgen: Option<(String, PluginGenerateFn)>,
+    lib: Library,
+}
+
+impl StagedPlugin {
+    /// Hand the plugin its state through `hot_reload`, returning the state it keeps.
+    unsafe fn hand_over(&self, state: *mut c_void) -> *mut c_void {
+        match unsafe { (*self.vtable).hot_reload } {
+            Some(hot_reload) => unsafe { hot_reload(state) },
+            None => std::ptr::null_mut(),
+        }
+    }
+}
+
+/// A plugin version registered with the world.
+struct HostedPlugin {
+    /// Library the plugin was loaded from, watched for changes.
+    source: PathBuf,
+    /// Modification time of `source` when it was last (re)loaded.
+    modified: Option<SystemTime>,
+    /// Private copy the library was actually opened from.
+    copy: PathBuf,
+    vtable: *mut PluginVTable,
+    /// Value returned by the plugin's `hot_reload`, handed to the next version.
+    state: *mut c_void,
+    systems: Vec<String>,
+    worldgen: Vec<String>,
+    // Declared last so everything pointing into the library is dropped first
+    lib: Library,
+}
+
+impl HostedPlugin {
+    fn install(
+        staged: StagedPlugin,
+        state: *mut c_void,
+        source: PathBuf,
+        modified: Option<SystemTime>,
+        world: &mut World,
+        worldgen: &mut WorldgenRegistry,
+    ) -> Self {
+        let mut systems = Vec::new();
+        for (name, run) in staged.systems {
+            world.register_dynamic_system(&name, move |world_rc, delta_time| {
+                let mut world = world_rc.borrow_mut();
+                unsafe { run(&mut *world as *mut World as *mut c_void, delta_time) };
+            });
+            systems.push(name);
+        }
+        let mut worldgen_names = Vec::new();
+        if let Some((name, generate)) = staged.worldgen {
+            worldgen.register(WorldgenPlugin::CAbi {
+                name: name.clone(),
+                generate,
+                _lib: None, // The host owns the library
+            });
+            worldgen_names.push(name);
+        }
+        Self {
+            source,
+            modified,
+            copy: staged.copy,
+            vtable: staged.vtable,
+            state,
+            systems,
+            worldgen: worldgen_names,
+            lib: staged.lib,
+        }
+    }
+
+    fn unregister(&self, world: &mut World, worldgen: &mut WorldgenRegistry) {
+        for name in &self.systems {
+            world.unregister_dynamic_system(name);
+        }
+        for name in &self.worldgen {
+            worldgen.unregister(name);
+        }
+    }
+
+    /// Close the library and delete its private copy.
+    fn close(self) {
+        let copy = self.copy.clone();
+        drop(self);
+        let _ = fs::remove_file(copy);
+    }
+}
+
+/// Loads native plugins and hot-reloads them when their library changes.
+///
+/// Plugins are keyed by the file stem of their library without the `lib`
+/// prefix (`plugins/foo/libfoo.so` is `"foo"`).
+pub struct NativePluginHost {
+    plugins: IndexMap<String, HostedPlugin>,
+    copy_dir: PathBuf,
+    loads: u64,
+}
+
+impl NativePluginHost {
+    /// Create a host keeping its library copies in a per-process temp directory.
+    pub fn new() -> Self {
+        Self::with_copy_dir(
+            std::env::temp_dir().join(format!("mge_plugins_{}", std::process::id())),
+        )
+    }
+
+    /// Create a host keeping its library copies in `copy_dir`.
+    pub fn with_copy_dir<P: AsRef<Path>>(copy_dir: P) -> Self {
+        Self {
+            plugins: IndexMap::new(),
+            copy_dir: copy_dir.as_ref().to_path_buf(),
+            loads: 0,
+        }
+    }
+
+    /// The name a plugin library is hosted under.
+    pub fn plugin_name<P: AsRef<Path>>(path: P) -> String {
+        let stem = path
+            .as_ref()
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        stem.strip_prefix("lib").map(str::to_string).unwrap_or(stem)
+    }
+
+    /// Load a plugin, call its `init` and `hot_reload` (with NULL), and register its
+    /// systems and worldgen entry. Returns the plugin's name.
+    ///
+    /// # Safety
+    ///
+    /// - `path` must point to a valid dynamic library exposing a compatible plugin vtable.
+    /// - `engine_api` must stay valid as long as the plugin is loaded.
+    /// - `world` and `worldgen` must be the same for every call on this host, since
+    ///   reloading and unloading remove the plugin's registrations from them.
+    pub unsafe fn load<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        engine_api: &mut EngineApi,
+        world: &mut World,
+        worldgen: &mut WorldgenRegistry,
+    ) -> Result<String, String> {
+        let source = path.as_ref().to_path_buf();
+        let name = Self::plugin_name(&source);
+        if self.plugins.contains_key(&name) {
+            return Err(format!("Plugin '{name}' is already loaded"));
+        }
+        let modified = modified_time(&source);
+        let staged = unsafe { self.stage(&name, &source, engine_api, world)? };
+        let state = unsafe { staged.hand_over(std::ptr::null_mut()) };
+        let plugin = HostedPlugin::install(staged, state, source, modified, world, worldgen);
+        self.plugins.insert(name.clone(), plugin);
+        Ok(name)
+    }
+
+    /// Reload a plugin from its library, handing the new version the old one's
+    /// state once it has started. On error the old version stays loaded and
+    /// registered, and keeps its state.
+    ///
+    /// # Safety
+    ///
+    /// Same requirements as [`NativePluginHost::load`].
+    pub unsafe fn reload(
+        &mut self,
+        name: &str,
+        engine_api: &mut EngineApi,
+        world: &mut World,
+        worldgen: &mut WorldgenRegistry,
+    ) -> Result<(), String> {
+        let Some(plugin) = self.plugins.get(name) else {
+            return Err(format!("Plugin '{name}' is not loaded"));
+        };
+        let source = plugin.source.clone();
+        let modified = modified_time(&source);
+        let staged = match unsafe { self.stage(name, &source, engine_api, world) } {
+            Ok(staged) => staged,
+            Err(e) => {
+                // Don't retry this build until it changes again
+                if let Some(plugin) = self.plugins.get_mut(name) {
+                    plugin.modified = modified;
+                }
+                return Err(format!("Reloading plugin '{name}' failed: {e}"));
+            }
+        };
+
+        let plugin = self.plugins.get_mut(name).expect("plugin checked above");
+        let state = unsafe { staged.hand_over(plugin.state) };
+        plugin.unregister(world, worldgen);
+        let new = HostedPlugin::install(staged, state, source, modified, world, worldgen);
+        std::mem::replace(plugin, new).close();
+        Ok(())
+    }
+
+    /// Unregister a plugin's systems and worldgen entry, call its `shutdown` and
+    /// close its library.
+    pub fn unload(
+        &mut self,
+        name: &str,
+        world: &mut World,
+        worldgen: &mut WorldgenRegistry,
+    ) -> Result<(), String> {
+        let plugin = self
+            .plugins
+            .shift_remove(name)
+            .ok_or_else(|| format!("Plugin '{name}' is not loaded"))?;
+        plugin.unregister(world, worldgen);
+        unsafe { ((*plugin.vtable).shutdown)() };
+        plugin.close();
+        Ok(())
+    }
+
+    /// Names of loaded plugins whose library changed since it was (re)loaded.
+    pub fn poll_changes(&self) -> Vec<String> {
+        self.plugins
+            .iter()
+            .filter(|(_, plugin)| {
+                let modified = modified_time(&plugin.source);
+                modified.is_some() && modified != plugin.modified
+            })
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// Reload every plugin whose library changed, returning each one's result.
+    ///
+    /// # Safety
+    ///
+    /// Same requirements as [`NativePluginHost::load`].
+    pub unsafe fn reload_changed(
+        &mut self,
+        engine_api: &mut EngineApi,
+        world: &mut World,
+        worldgen: &mut WorldgenRegistry,
+    ) -> Vec<(String, Result<(), String>)> {
+        self.poll_changes()
+            .into_iter()
+            .map(|name| {
+                let result = unsafe { self.reload(&name, engine_api, world, worldgen) };
+                (name, result)
+            })
+            .collect()
+    }
+
+    /// Load the native plugins listed in `config`, in order, returning their names.
+    ///
+    /// # Safety
+    ///
+    /// Same requirements as [`NativePluginHost::load`].
+    pub unsafe fn load_config(
+        &mut self,
+        config: &GameConfig,
+        engine_api: &mut EngineApi,
+        world: &mut World,
+        worldgen: &mut WorldgenRegistry,
+    ) -> Result<Vec<String>, String> {
+        native_plugin_paths(config)?
+            .into_iter()
+            .map(|path| unsafe { self.load(path, engine_api, world, worldgen) })
+            .collect()
+    }
+
+    /// Hand the host over to `world` as the [`HOT_RELOAD_SYSTEM`], which runs
+    /// [`NativePluginHost::reload_changed`] at the start of every tick and passes
+    /// each result to `on_reload`.
+    ///
+    /// # Safety
+    ///
+    /// `engine_api` must be the API the plugins were loaded with, and `world` and
+    /// `worldgen` the ones they were registered with.
+    pub unsafe fn watch<F>(
+        self,
+        world: &mut World,
+        engine_api: Box<EngineApi>,
+        worldgen: Rc<RefCell<WorldgenRegistry>>,
+        on_reload: F,
+    ) where
+        F: Fn(&str, &Result<(), String>) + 'static,
+    {
+        let watched = RefCell::new((self, engine_api));
+        world.register_dynamic_system(HOT_RELOAD_SYSTEM, move |world_rc, _| {
+            let (host, engine_api) = &mut *watched.borrow_mut();
+            let results = unsafe {
+                host.reload_changed(
+                    engine_api,
+                    &mut world_rc.borrow_mut(),
+                    &mut worldgen.borrow_mut(),
+                )
+            };
+            for (name, result) in &results {
+                on_reload(name, result);
+            }
+        });
+        world.set_system_stage(HOT_RELOAD_SYSTEM, SystemStage::PreUpdate);
+    }
+
+    /// Names of the loaded plugins, in load order.
+    pub fn loaded(&self) -> Vec<String> {
+        self.plugins.keys().cloned().collect()
+    }
+
+    /// Returns true if a plugin is loaded under `name`.
+    pub fn is_loaded(&self, name: &str) -> bool {
+        self.plugins.contains_key(name)
+    }
+
+    /// Dynamic systems registered by a plugin.
+    pub fn systems(&self, name: &str) -> Vec<String> {
+        self.plugins
+            .get(name)
+            .map(|plugin| plugin.systems.clone())
+            .unwrap_or_default()
+    }
+
+    /// Worldgen entries registered by a plugin.
+    pub fn worldgen(&self, name: &str) -> Vec<String> {
+        self.plugins
+            .get(name)
+            .map(|plugin| plugin.worldgen.clone())
+            .unwrap_or_default()
+    }
+
+    /// Copy a plugin's library, open the copy and run the plugin up to the point
+    /// where its registrations are known.
+    unsafe fn stage(
+        &mut self,
+        name: &str,
+        source: &Path,
+        engine_api: &mut EngineApi,
+        world: &mut World,
+    ) -> Result<StagedPlugin, String> {
+        self.loads += 1;
+        let extension = source
+            .extension()
+            .map(|ext| format!(".{}", ext.to_string_lossy()))
+            .unwrap_or_default();
+        let copy = self
+            .copy_dir
+            .join(format!("{name}-{}{extension}", self.loads));
+        fs::create_dir_all(&self.copy_dir)
+            .and_then(|_| fs::copy(source, &copy))
+            .map_err(|e| format!("Failed to copy '{}': {e}", source.display()))?;
+        let staged = unsafe { stage_copy(source, copy.clone(), engine_api, world) };
+        if staged.is_err() {
+            let _ = fs::remove_file(&copy);
+        }
+        staged
+    }
+}
+
+/// Open a copied plugin library and run `init` and the registration functions,
+/// calling `shutdown` if registration fails after `init` succeeded.
+unsafe fn stage_copy(
+    source: &Path,
+    copy: PathBuf,
+    engine_api: &mut EngineApi,
+    world: &mut World,
+) -> Result<StagedPlugin, String> {
+    let lib = unsafe { Library::new(&copy) }.map_err(|e| e.to_string())?;
+    let vtable: Symbol<*mut *mut PluginVTable> =
+        unsafe { lib.get(b"PLUGIN_VTABLE\0") }.map_err(|e| e.to_string())?;
+    let plugin_vtable: *mut PluginVTable = unsafe { **vtable };
+    if plugin_vtable.is_null() {
+        return Err("PLUGIN_VTABLE symbol is null".to_string());
+    }
+    let vtable_ref: &PluginVTable = unsafe { &*plugin_vtable };
+
+    // Check ABI version before calling any plugin code
+    let plugin_version = vtable_ref.abi_version;
+    if plugin_version != PLUGIN_ABI_VERSION {
+        return Err(format!(
+            "Plugin '{}' ABI version mismatch: expected {}, got {}",
+            source.display(),
+            PLUGIN_ABI_VERSION,
+            plugin_version,
+        ));
+    }
+
+    let world_ptr = world as *mut World as *mut c_void;
+    let init_result = unsafe { (vtable_ref.init)(engine_api as *mut _, world_ptr) };
+    if init_result != 0 {
+        return Err(format!("Plugin init failed with code {init_result}"));
+    }
+
+    let registrations = unsafe { collect_registrations(vtable_ref, engine_api, world_ptr) };
+    let (systems, worldgen) = match registrations {
+        Ok(registrations) => registrations,
+        Err(e) => {
+            unsafe { (vtable_ref.shutdown)() };
+            return Err(e);
+        }
+    };
+
+    Ok(StagedPlugin {
+        copy,
+        vtable: plugin_vtable,
+        systems,
+        worldgen,
+        lib,
+    })
+}
+
+/// Systems and worldgen entry of a plugin that passed `init`.
+type Registrations = (
+    Vec<(String, PluginRunFn)>,
+    Option<(String, PluginGenerateFn)>,
+);
+
+/// Run a plugin's registration functions.
+unsafe fn collect_registrations(
+    vtable_ref: &PluginVTable,
+    engine_api: &mut EngineApi,
+    world_ptr: *mut c_void,
+) -> Result<Registrations, String> {
+    let mut systems = Vec::new();
+    if let Some(register_systems_fn) = vtable_ref.register_systems {
+        let mut systems_ptr: *mut SystemPlugin = std::ptr::null_mut();
+        let mut count: c_int = 0;
+        let res = unsafe {
+            register_systems_fn(
+                engine_api as *mut _,
+                world_ptr,
+                &mut systems_ptr,
+                &mut count,
+            )
+        };
+        if res != 0 {
+            return Err(format!("Plugin register_systems failed with code {res}"));
+        }
+        if !systems_ptr.is_null() && count > 0 {
+            let systems_slice = unsafe { std::slice::from_raw_parts(systems_ptr, count as usize) };
+            for sys in systems_slice {
+                systems.push((unsafe { sys.name_str() }, sys.run));
+            }
+            if let Some(free_systems_fn) = vtable_ref.free_systems {
+                unsafe { free_systems_fn(systems_ptr, count) };
+            }
+        }
+    }
+
+    let worldgen = match vtable_ref.worldgen_name {
+        Some(worldgen_name_fn) => {
+            let name = unsafe { CStr::from_ptr(worldgen_name_fn()) }
+                .to_str()
+                .map_err(|e| e.to_string())?
+                .to_owned();
+            Some((
+                name,
+                generate_fn(vtable_ref.generate_world, vtable_ref.free_result_json),
+            ))
+        }
+        None => None,
+    };
+
+    Ok((systems, worldgen))
+}
+
+/// Wrap a plugin's `generate_world` into a worldgen function.
+fn generate_fn(
+    generate_world_fn: Option<unsafe extern "C" fn(*const c_char, *mut *mut c_char) -> c_int>,
+    free_result_json_fn: Option<unsafe extern "C" fn(*mut c_char)>,
+) -> PluginGenerateFn {
+    Arc::new(move |params: &Value| -> Value {
+        let Some(generate_world_fn) = generate_world_fn else {
+            return Value::Null;
+        };
+        let Some(c_params) = serde_json::to_string(params)
+            .ok()
+            .and_then(|json| CString::new(json).ok())
+        else {
+            return Value::Null;
+        };
+        let mut out_ptr: *mut c_char = std::ptr::null_mut();
+        let res = unsafe { generate_world_fn(c_params.as_ptr(), &mut out_ptr) };
+        if res != 0 || out_ptr.is_null() {
+            return Value::Null;
+        }
+        let result = unsafe { CStr::from_ptr(out_ptr).to_string_lossy().into_owned() };
+        if let Some(free_result_json_fn) = free_result_json_fn {
+            unsafe { free_result_json_fn(out_ptr) };
+        }
+        serde_json::from_str(&result).unwrap_or(Value::Null)
+    })
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+impl Default for NativePluginHost {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for NativePluginHost {
+    fn drop(&mut self) {
+        // Plugins that weren't unloaded may still have systems and worldgen entries
+        // registered elsewhere, so their code has to stay mapped.
+        for (_, plugin) in self.plugins.drain(..) {
+            let HostedPlugin { lib, .. } = plugin;
+            std::mem::forget(lib);
+        }
+    }
+}
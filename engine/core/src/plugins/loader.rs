@@ -411,6 +411,45 @@ pub fn resolve_plugin_load_order(
     Ok(order)
 }
 
+/// Absolute paths of the native plugins listed in `config`.
+///
+/// Paths are resolved against `MGE_WORKSPACE_ROOT`, or the nearest ancestor of
+/// this crate containing a `plugins` directory. Errors if a plugin is missing.
+pub fn native_plugin_paths(config: &GameConfig) -> Result<Vec<PathBuf>, String> {
+    let Some(plugins) = &config.plugins else {
+        return Ok(Vec::new());
+    };
+    // Determine workspace root robustly
+    let workspace_root = std::env::var("MGE_WORKSPACE_ROOT")
+        .ok()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            // Fallback: go up from CARGO_MANIFEST_DIR until "plugins" dir exists
+            let mut dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+            while !dir.join("plugins").exists() {
+                if !dir.pop() {
+                    panic!("Could not find workspace root containing 'plugins' directory");
+                }
+            }
+            dir
+        });
+
+    plugins
+        .native
+        .iter()
+        .map(|plugin_path| {
+            let abs_path = workspace_root.join(plugin_path);
+            if abs_path.exists() {
+                Ok(abs_path)
+            } else {
+                Err(format!(
+                    "Plugin not found: {plugin_path} (resolved as {abs_path:?})"
+                ))
+            }
+        })
+        .collect()
+}
+
 /// # Safety
 ///
 /// - `world_ptr` must be valid for the duration of plugin loading.
@@ -421,37 +460,14 @@ pub unsafe fn load_native_plugins_from_config_threadsafe(
     world_ptr: *mut std::os::raw::c_void,
     worldgen_registry: &mut ThreadSafeWorldgenRegistry,
 ) -> Result<(), String> {
-    if let Some(plugins) = &config.plugins {
-        // Determine workspace root robustly
-        let workspace_root = std::env::var("MGE_WORKSPACE_ROOT")
-            .ok()
-            .map(PathBuf::from)
-            .unwrap_or_else(|| {
-                // Fallback: go up from CARGO_MANIFEST_DIR until "plugins" dir exists
-                let mut dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-                while !dir.join("plugins").exists() {
-                    if !dir.pop() {
-                        panic!("Could not find workspace root containing 'plugins' directory");
-                    }
-                }
-                dir
-            });
-
-        for plugin_path in &plugins.native {
-            let abs_path = workspace_root.join(plugin_path);
-            if !abs_path.exists() {
-                return Err(format!(
-                    "Plugin not found: {plugin_path} (resolved as {abs_path:?})"
-                ));
-            }
-            unsafe {
-                load_plugin_and_register_worldgen_threadsafe(
-                    &abs_path,
-                    engine_api,
-                    world_ptr,
-                    worldgen_registry,
-                )?;
-            }
+    for abs_path in native_plugin_paths(config)? {
+        unsafe {
+            load_plugin_and_register_worldgen_threadsafe(
+                &abs_path,
+                engine_api,
+                world_ptr,
+                worldgen_registry,
+            )?;
         }
     }
     Ok(())
@@ -467,37 +483,9 @@ pub unsafe fn load_native_plugins_from_config(
     world_ptr: *mut std::os::raw::c_void,
     worldgen_registry: &mut WorldgenRegistry,
 ) -> Result<(), String> {
-    if let Some(plugins) = &config.plugins {
-        // Determine workspace root robustly
-        let workspace_root = std::env::var("MGE_WORKSPACE_ROOT")
-            .ok()
-            .map(PathBuf::from)
-            .unwrap_or_else(|| {
-                // Fallback: go up from CARGO_MANIFEST_DIR until "plugins" dir exists
-                let mut dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-                while !dir.join("plugins").exists() {
-                    if !dir.pop() {
-                        panic!("Could not find workspace root containing 'plugins' directory");
-                    }
-                }
-                dir
-            });
-
-        for plugin_path in &plugins.native {
-            let abs_path = workspace_root.join(plugin_path);
-            if !abs_path.exists() {
-                return Err(format!(
-                    "Plugin not found: {plugin_path} (resolved as {abs_path:?})"
-                ));
-            }
-            unsafe {
-                load_plugin_and_register_worldgen(
-                    &abs_path,
-                    engine_api,
-                    world_ptr,
-                    worldgen_registry,
-                )?;
-            }
+    for abs_path in native_plugin_paths(config)? {
+        unsafe {
+            load_plugin_and_register_worldgen(&abs_path, engine_api, world_ptr, worldgen_registry)?;
         }
     }
     Ok(())
@@ -1,10 +1,23 @@
-use crate::plugins::subprocess::{PluginRequest, PluginResponse, PluginSubprocess};
+use crate::ecs::World;
+use crate::plugins::subprocess::{
+    DEFAULT_REQUEST_TIMEOUT, PluginRequest, PluginResponse, PluginSubprocess,
+};
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::path::Path;
+use std::rc::Rc;
+use std::time::Duration;
 
 /// Manages plugins
+///
+/// Each plugin runs in its own subprocess. Systems registered with
+/// [`PluginManager::register_systems`] are registered again from the new
+/// binary by [`PluginManager::reload_plugin`], removed by
+/// [`PluginManager::shutdown_plugin`], and turn into no-ops if the plugin fails.
 pub struct PluginManager {
-    plugins: HashMap<String, PluginSubprocess>,
+    plugins: HashMap<String, Rc<RefCell<PluginSubprocess>>>,
+    systems: HashMap<String, Vec<String>>,
+    timeout: Duration,
 }
 
 impl PluginManager {
@@ -12,6 +25,16 @@ impl PluginManager {
     pub fn new() -> Self {
         Self {
             plugins: HashMap::new(),
+            systems: HashMap::new(),
+            timeout: DEFAULT_REQUEST_TIMEOUT,
+        }
+    }
+
+    /// Set the time plugins get to answer a request, for running and future plugins
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+        for plugin in self.plugins.values() {
+            plugin.borrow_mut().set_timeout(timeout);
         }
     }
 
@@ -25,31 +48,104 @@ impl PluginManager {
         if self.plugins.contains_key(&name) {
             return Err(format!("Plugin '{name}' already running"));
         }
-        let subprocess = PluginSubprocess::spawn(bin_path, socket_path)?;
-        self.plugins.insert(name, subprocess);
+        let subprocess = self.spawn(bin_path, socket_path)?;
+        self.plugins.insert(name, Rc::new(RefCell::new(subprocess)));
         Ok(())
     }
 
     /// Send a request to a plugin
     pub fn send(&mut self, name: &str, request: &PluginRequest) -> Result<PluginResponse, String> {
-        let plugin = self.plugins.get_mut(name).ok_or("Plugin not found")?;
-        plugin.send_request(request)
+        let plugin = self.plugins.get(name).ok_or("Plugin not found")?;
+        plugin.borrow_mut().send_request(request)
+    }
+
+    /// Send a request to a plugin, giving it access to the world while it handles it
+    pub fn send_with_world(
+        &mut self,
+        name: &str,
+        request: &PluginRequest,
+        world: &mut World,
+    ) -> Result<PluginResponse, String> {
+        let plugin = self.plugins.get(name).ok_or("Plugin not found")?;
+        plugin.borrow_mut().send_request_with_world(request, world)
+    }
+
+    /// Register the plugin's systems as dynamic systems of the world, returning
+    /// their names. Each tick they ask the plugin to run the system.
+    pub fn register_systems(
+        &mut self,
+        name: &str,
+        world: &mut World,
+    ) -> Result<Vec<String>, String> {
+        let plugin = Rc::clone(self.plugins.get(name).ok_or("Plugin not found")?);
+        let systems = plugin.borrow_mut().list_systems()?;
+        for system in &systems {
+            let plugin = Rc::clone(&plugin);
+            let plugin_name = name.to_string();
+            let system_name = system.clone();
+            world.register_dynamic_system(system, move |world_rc, delta_time| {
+                let mut plugin = plugin.borrow_mut();
+                if plugin.failure().is_some() {
+                    return;
+                }
+                let mut world = world_rc.borrow_mut();
+                if let Err(e) = plugin.run_system(&system_name, delta_time, &mut world) {
+                    log::warn!("Plugin '{plugin_name}' system '{system_name}' failed: {e}");
+                }
+            });
+        }
+        self.systems.insert(name.to_string(), systems.clone());
+        Ok(systems)
+    }
+
+    /// Unregister the systems registered for a plugin
+    pub fn unregister_systems(&mut self, name: &str, world: &mut World) {
+        for system in self.systems.remove(name).unwrap_or_default() {
+            world.unregister_dynamic_system(&system);
+        }
+    }
+
+    /// Why a plugin stopped (crash, timeout, protocol error or shutdown), if it did
+    pub fn plugin_failure(&self, name: &str) -> Option<String> {
+        self.plugins
+            .get(name)
+            .and_then(|plugin| plugin.borrow().failure().map(str::to_string))
     }
 
     /// Reload a plugin
+    ///
+    /// Starts a fresh subprocess and only then stops the old one, so a failed
+    /// spawn leaves the running plugin in place. If the plugin has registered
+    /// systems, they are unregistered and the new binary's systems are
+    /// registered in their place.
     pub fn reload_plugin<P: AsRef<Path>>(
         &mut self,
         name: &str,
         bin_path: P,
         socket_path: &str,
+        world: &mut World,
     ) -> Result<(), String> {
-        self.shutdown_plugin(name)?;
-        self.launch_plugin(name.to_string(), bin_path, socket_path)
+        let Some(plugin) = self.plugins.get(name).cloned() else {
+            return self.launch_plugin(name.to_string(), bin_path, socket_path);
+        };
+        let subprocess = self.spawn(bin_path, socket_path)?;
+        {
+            let mut old = std::mem::replace(&mut *plugin.borrow_mut(), subprocess);
+            old.send_request(&PluginRequest::Shutdown).ok();
+            old.terminate();
+        }
+        if self.systems.contains_key(name) {
+            self.unregister_systems(name, world);
+            self.register_systems(name, world)?;
+        }
+        Ok(())
     }
 
-    /// Shutdown a plugin
-    pub fn shutdown_plugin(&mut self, name: &str) -> Result<(), String> {
-        if let Some(mut plugin) = self.plugins.remove(name) {
+    /// Shutdown a plugin and unregister its systems
+    pub fn shutdown_plugin(&mut self, name: &str, world: &mut World) -> Result<(), String> {
+        self.unregister_systems(name, world);
+        if let Some(plugin) = self.plugins.remove(name) {
+            let mut plugin = plugin.borrow_mut();
             plugin.send_request(&PluginRequest::Shutdown).ok();
             plugin.terminate();
         }
@@ -58,11 +154,22 @@ impl PluginManager {
 
     /// Shutdown all plugins
     pub fn shutdown_all(&mut self) {
-        for (_name, mut plugin) in self.plugins.drain() {
+        for (_name, plugin) in self.plugins.drain() {
+            let mut plugin = plugin.borrow_mut();
             plugin.send_request(&PluginRequest::Shutdown).ok();
             plugin.terminate();
         }
     }
+
+    fn spawn<P: AsRef<Path>>(
+        &self,
+        bin_path: P,
+        socket_path: &str,
+    ) -> Result<PluginSubprocess, String> {
+        let mut subprocess = PluginSubprocess::spawn(bin_path, socket_path)?;
+        subprocess.set_timeout(self.timeout);
+        Ok(subprocess)
+    }
 }
 
 impl Drop for PluginManager {
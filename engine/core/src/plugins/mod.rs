@@ -2,10 +2,14 @@
 //!
 //! The plugin system allows for loading and running plugins.
 
+/// C ABI description and header generation
+pub mod abi;
 /// Dynamic systems
 pub mod dynamic_systems;
 /// FFI
 pub mod ffi;
+/// Native plugin hot-reload host
+pub mod host;
 /// Plugin loader
 pub mod loader;
 /// Plugin manager
@@ -19,7 +23,8 @@ pub mod types;
 
 pub use dynamic_systems::*;
 pub use ffi::*;
+pub use host::{HOT_RELOAD_SYSTEM, NativePluginHost};
 pub use loader::*;
 pub use registry::*;
-pub use subprocess::{PluginRequest, PluginResponse};
+pub use subprocess::{PluginRequest, PluginResponse, WorldOp, WorldOpResult};
 pub use types::*;
@@ -1,10 +1,34 @@
+//! Out-of-process plugins.
+//!
+//! A subprocess plugin is a separate executable that connects to a Unix socket
+//! passed as its only argument and exchanges newline-delimited JSON messages
+//! with the engine. The engine sends [`PluginRequest`]s and waits for one final
+//! [`PluginResponse`] per request. While handling `Initialize`, `RunSystem` or
+//! `RunCommand`, the plugin may instead answer with any number of
+//! [`PluginResponse::WorldRequest`] batches; the engine applies each batch of
+//! [`WorldOp`]s in order and replies with a [`PluginRequest::WorldResult`].
+//!
+//! The plugin never shares memory with the engine, and runs with an empty
+//! environment. A plugin that crashes, closes the socket, sends malformed JSON
+//! or doesn't answer within its timeout is killed and marked as failed; the
+//! engine keeps running and later requests to it return an error.
+
+use crate::ecs::World;
+use crate::ecs::query::Query;
 use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
 use std::fs;
-use std::io::{BufRead, BufReader, Write};
+use std::io::{BufRead, BufReader, ErrorKind, Write};
 use std::os::unix::net::{UnixListener, UnixStream};
 use std::process::{Child, Command, Stdio};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+/// Default time a plugin gets to answer a request, world round-trips included.
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Time a freshly spawned plugin gets to connect to its socket.
+pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// Plugin requests
 #[derive(Debug, Serialize, Deserialize)]
@@ -22,6 +46,20 @@ pub enum PluginRequest {
         /// Arguments
         data: serde_json::Value,
     },
+    /// Ask for the systems the plugin wants run every tick
+    ListSystems,
+    /// Run one of the plugin's systems
+    RunSystem {
+        /// System name, as listed by the plugin
+        system: String,
+        /// Tick length in seconds
+        delta_time: f32,
+    },
+    /// Results of a [`PluginResponse::WorldRequest`] batch, one per operation
+    WorldResult {
+        /// Results
+        results: Vec<WorldOpResult>,
+    },
 }
 
 /// Plugin responses
@@ -43,13 +81,143 @@ pub enum PluginResponse {
         /// Error message
         message: String,
     },
+    /// Systems the plugin wants run every tick
+    Systems {
+        /// System names
+        systems: Vec<String>,
+    },
+    /// A system finished running
+    SystemDone,
+    /// Batch of world operations to apply before the plugin answers the
+    /// pending request
+    WorldRequest {
+        /// Operations, applied in order
+        ops: Vec<WorldOp>,
+    },
+}
+
+/// World operation requested by a plugin.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum WorldOp {
+    /// Spawn an entity; returns its ID
+    Spawn,
+    /// Despawn an entity
+    Despawn {
+        /// Entity
+        entity: u32,
+    },
+    /// Get a component; returns its value, or null if absent
+    GetComponent {
+        /// Entity
+        entity: u32,
+        /// Component name
+        name: String,
+    },
+    /// Set a component
+    SetComponent {
+        /// Entity
+        entity: u32,
+        /// Component name
+        name: String,
+        /// Component value
+        value: Value,
+    },
+    /// Remove a component
+    RemoveComponent {
+        /// Entity
+        entity: u32,
+        /// Component name
+        name: String,
+    },
+    /// Run a query spec (`{"with": [...], "where": [...], ...}`); returns the
+    /// matching rows (`[{"entity": 1, "components": {...}}]`)
+    Query {
+        /// Query spec
+        query: Value,
+    },
+    /// Send an event to a named event bus
+    SendEvent {
+        /// Event bus
+        bus: String,
+        /// Event payload
+        payload: Value,
+    },
+    /// Get the current turn
+    GetTurn,
+}
+
+/// Result of a [`WorldOp`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum WorldOpResult {
+    /// The operation succeeded
+    Ok {
+        /// Returned value (null for writes)
+        value: Value,
+    },
+    /// The operation failed
+    Err {
+        /// Error message
+        message: String,
+    },
+}
+
+impl WorldOp {
+    /// Apply the operation to the world.
+    pub fn apply(self, world: &mut World) -> Result<Value, String> {
+        match self {
+            WorldOp::Spawn => Ok(json!(world.spawn_entity())),
+            WorldOp::Despawn { entity } => {
+                if !world.entity_exists(entity) {
+                    return Err(format!("Entity {entity} does not exist"));
+                }
+                world.despawn_entity(entity);
+                Ok(Value::Null)
+            }
+            WorldOp::GetComponent { entity, name } => Ok(world
+                .get_component(entity, &name)
+                .cloned()
+                .unwrap_or(Value::Null)),
+            WorldOp::SetComponent {
+                entity,
+                name,
+                value,
+            } => world
+                .set_component(entity, &name, value)
+                .map(|_| Value::Null),
+            WorldOp::RemoveComponent { entity, name } => {
+                world.remove_component(entity, &name).map(|_| Value::Null)
+            }
+            WorldOp::Query { query } => {
+                let query: Query =
+                    serde_json::from_value(query).map_err(|e| format!("Invalid query: {e}"))?;
+                serde_json::to_value(query.rows(world)).map_err(|e| e.to_string())
+            }
+            WorldOp::SendEvent { bus, payload } => {
+                world.send_event(&bus, payload).map(|_| Value::Null)
+            }
+            WorldOp::GetTurn => Ok(json!(world.turn)),
+        }
+    }
+}
+
+impl From<Result<Value, String>> for WorldOpResult {
+    fn from(result: Result<Value, String>) -> Self {
+        match result {
+            Ok(value) => WorldOpResult::Ok { value },
+            Err(message) => WorldOpResult::Err { message },
+        }
+    }
 }
 
 /// Plugin subprocess
 pub struct PluginSubprocess {
     child: Child,
     stream: UnixStream,
+    reader: BufReader<UnixStream>,
     socket_path: String,
+    timeout: Duration,
+    failure: Option<String>,
+    terminated: bool,
 }
 
 impl PluginSubprocess {
@@ -64,55 +232,222 @@ impl PluginSubprocess {
         // Start listener first so plugin can connect
         let listener =
             UnixListener::bind(socket_path).map_err(|e| format!("Failed to bind socket: {e}"))?;
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| format!("Failed to configure socket: {e}"))?;
 
-        let child = Command::new(bin_path.as_ref())
+        let mut child = Command::new(bin_path.as_ref())
             .arg(socket_path)
+            .env_clear()
             .stdin(Stdio::null())
             .stdout(Stdio::null())
             .stderr(Stdio::null())
             .spawn()
-            .map_err(|e| format!("Failed to spawn plugin: {e}"))?;
+            .map_err(|e| {
+                let _ = fs::remove_file(socket_path);
+                format!("Failed to spawn plugin: {e}")
+            })?;
 
         // Accept connection (with timeout)
+        let deadline = Instant::now() + CONNECT_TIMEOUT;
         let stream = loop {
             match listener.accept() {
                 Ok((stream, _addr)) => break stream,
-                Err(e) => {
-                    if e.kind() == std::io::ErrorKind::WouldBlock {
-                        thread::sleep(Duration::from_millis(10));
-                        continue;
-                    } else {
-                        return Err(format!("Socket accept error: {e}"));
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                    if let Ok(Some(status)) = child.try_wait() {
+                        let _ = fs::remove_file(socket_path);
+                        return Err(format!("Plugin exited before connecting ({status})"));
                     }
+                    if Instant::now() >= deadline {
+                        let _ = child.kill();
+                        let _ = fs::remove_file(socket_path);
+                        return Err(format!("Plugin did not connect within {CONNECT_TIMEOUT:?}"));
+                    }
+                    thread::sleep(Duration::from_millis(10));
+                }
+                Err(e) => {
+                    let _ = child.kill();
+                    let _ = fs::remove_file(socket_path);
+                    return Err(format!("Socket accept error: {e}"));
                 }
             }
         };
+        stream
+            .set_nonblocking(false)
+            .and_then(|_| stream.try_clone())
+            .map(|reader| Self {
+                child,
+                reader: BufReader::new(reader),
+                stream,
+                socket_path: socket_path.to_string(),
+                timeout: DEFAULT_REQUEST_TIMEOUT,
+                failure: None,
+                terminated: false,
+            })
+            .map_err(|e| format!("Failed to configure socket: {e}"))
+    }
 
-        Ok(Self {
-            child,
-            stream,
-            socket_path: socket_path.to_string(),
-        })
+    /// Set the time the plugin gets to answer a request.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Time the plugin gets to answer a request.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Why the plugin stopped (crash, timeout, protocol error or shutdown), if it did.
+    pub fn failure(&self) -> Option<&str> {
+        self.failure.as_deref()
     }
 
     /// Send request
+    ///
+    /// World operations the plugin requests while handling it are answered with
+    /// errors; use [`PluginSubprocess::send_request_with_world`] to grant access.
     pub fn send_request(&mut self, request: &PluginRequest) -> Result<PluginResponse, String> {
-        let msg = serde_json::to_string(request).map_err(|e| e.to_string())? + "\n";
-        self.stream
-            .write_all(msg.as_bytes())
-            .map_err(|e| e.to_string())?;
-        self.stream.flush().map_err(|e| e.to_string())?;
+        self.exchange(request, None)
+    }
 
-        let mut reader = BufReader::new(&self.stream);
-        let mut line = String::new();
-        reader.read_line(&mut line).map_err(|e| e.to_string())?;
-        serde_json::from_str(&line).map_err(|e| e.to_string())
+    /// Send a request, applying the world operations the plugin issues while
+    /// handling it.
+    pub fn send_request_with_world(
+        &mut self,
+        request: &PluginRequest,
+        world: &mut World,
+    ) -> Result<PluginResponse, String> {
+        self.exchange(request, Some(world))
+    }
+
+    /// Systems the plugin wants run every tick. Plugins that don't know the
+    /// request have none.
+    pub fn list_systems(&mut self) -> Result<Vec<String>, String> {
+        match self.send_request(&PluginRequest::ListSystems)? {
+            PluginResponse::Systems { systems } => Ok(systems),
+            PluginResponse::Error { .. } => Ok(Vec::new()),
+            other => Err(format!("Unexpected response to ListSystems: {other:?}")),
+        }
+    }
+
+    /// Run one of the plugin's systems against the world.
+    pub fn run_system(
+        &mut self,
+        system: &str,
+        delta_time: f32,
+        world: &mut World,
+    ) -> Result<(), String> {
+        let request = PluginRequest::RunSystem {
+            system: system.to_string(),
+            delta_time,
+        };
+        match self.send_request_with_world(&request, world)? {
+            PluginResponse::SystemDone => Ok(()),
+            PluginResponse::Error { message } => Err(message),
+            other => Err(format!("Unexpected response to RunSystem: {other:?}")),
+        }
     }
 
     /// Terminate
     pub fn terminate(&mut self) {
+        if self.terminated {
+            return;
+        }
+        self.terminated = true;
         let _ = self.child.kill();
+        let _ = self.child.wait();
         let _ = fs::remove_file(&self.socket_path);
+        self.failure
+            .get_or_insert_with(|| "Plugin was shut down".to_string());
+    }
+
+    fn exchange(
+        &mut self,
+        request: &PluginRequest,
+        world: Option<&mut World>,
+    ) -> Result<PluginResponse, String> {
+        if let Some(failure) = &self.failure {
+            return Err(format!("Plugin is not running: {failure}"));
+        }
+        let result = self.converse(request, world);
+        if let Err(e) = &result {
+            // The conversation is out of sync; don't trust the plugin any further
+            log::warn!("Stopping plugin subprocess: {e}");
+            self.failure = Some(e.clone());
+            self.terminate();
+        }
+        result
+    }
+
+    /// Send a request and answer world batches until the plugin sends its final
+    /// response.
+    fn converse(
+        &mut self,
+        request: &PluginRequest,
+        mut world: Option<&mut World>,
+    ) -> Result<PluginResponse, String> {
+        let deadline = Instant::now() + self.timeout;
+        self.write_message(request, deadline)?;
+        loop {
+            let ops = match self.read_response(deadline)? {
+                PluginResponse::WorldRequest { ops } => ops,
+                response => return Ok(response),
+            };
+            let results = ops
+                .into_iter()
+                .map(|op| match world.as_deref_mut() {
+                    Some(world) => op.apply(world).into(),
+                    None => WorldOpResult::Err {
+                        message: "World access is not available for this request".to_string(),
+                    },
+                })
+                .collect();
+            self.write_message(&PluginRequest::WorldResult { results }, deadline)?;
+        }
+    }
+
+    fn write_message(&mut self, request: &PluginRequest, deadline: Instant) -> Result<(), String> {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(format!("Plugin timed out after {:?}", self.timeout));
+        }
+        self.stream
+            .set_write_timeout(Some(remaining))
+            .map_err(|e| e.to_string())?;
+        let msg = serde_json::to_string(request).map_err(|e| e.to_string())? + "\n";
+        self.stream
+            .write_all(msg.as_bytes())
+            .and_then(|_| self.stream.flush())
+            .map_err(|e| match e.kind() {
+                ErrorKind::WouldBlock | ErrorKind::TimedOut => {
+                    format!("Plugin timed out after {:?}", self.timeout)
+                }
+                _ => format!("Failed to write to plugin: {e}"),
+            })
+    }
+
+    fn read_response(&mut self, deadline: Instant) -> Result<PluginResponse, String> {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(format!("Plugin timed out after {:?}", self.timeout));
+        }
+        self.reader
+            .get_ref()
+            .set_read_timeout(Some(remaining))
+            .map_err(|e| e.to_string())?;
+        let mut line = String::new();
+        match self.reader.read_line(&mut line) {
+            Ok(0) => Err(match self.child.try_wait() {
+                Ok(Some(status)) => format!("Plugin exited ({status})"),
+                _ => "Plugin closed the connection".to_string(),
+            }),
+            Ok(_) => serde_json::from_str(&line)
+                .map_err(|e| format!("Malformed response from plugin: {e}")),
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                Err(format!("Plugin timed out after {:?}", self.timeout))
+            }
+            Err(e) => Err(format!("Failed to read from plugin: {e}")),
+        }
     }
 }
 
@@ -1,3 +1,4 @@
+use super::abi::c_abi_struct;
 use std::os::raw::{c_char, c_float, c_int, c_void};
 
 /// A plugin manifest
@@ -29,57 +30,134 @@ pub struct PluginMetadata {
     pub path: std::path::PathBuf,
 }
 
-/// The engine API
-#[repr(C)]
-pub struct EngineApi {
-    /// Spawns a new entity
-    pub spawn_entity: unsafe extern "C" fn(*mut c_void) -> u32,
-    /// Sets a component
-    pub set_component: unsafe extern "C" fn(*mut c_void, u32, *const c_char, *const c_char) -> i32,
+c_abi_struct! {
+    /// A system plugin
+    pub struct SystemPlugin {
+        /// The system's name
+        pub name: *const c_char,
+        /// The system's run function
+        pub run: unsafe extern "C" fn(*mut c_void, f32),
+    }
 }
 
-/// A system plugin
-#[repr(C)]
-pub struct SystemPlugin {
-    /// The system's name
-    pub name: *const c_char,
-    /// The system's run function
-    pub run: unsafe extern "C" fn(*mut c_void, f32),
+/// The current ABI version that the engine expects plugins to match.
+pub const PLUGIN_ABI_VERSION: u32 = 2;
+
+/// Revision of [`EngineApi`]. Bumped whenever functions are appended; plugins
+/// check `struct_size` (`ENGINE_API_HAS` in C) before calling newer functions.
+pub const ENGINE_API_VERSION: u32 = 1;
+
+c_abi_struct! {
+    /// Engine functions handed to plugins.
+    ///
+    /// The struct only ever grows at the end, so appending functions doesn't
+    /// change [`PLUGIN_ABI_VERSION`]. All functions take the world pointer passed
+    /// to the plugin. Functions returning `char *` return engine-allocated JSON
+    /// (or NULL on error) that the plugin releases with `free_string`.
+    pub struct EngineApi {
+        /// Size of this struct as filled by the engine, in bytes
+        pub struct_size: u32,
+        /// ENGINE_API_VERSION of the engine
+        pub version: u32,
+        /// Spawns a new entity
+        pub spawn_entity: unsafe extern "C" fn(*mut c_void) -> u32,
+        /// Sets a component from JSON; returns 0 on success
+        pub set_component: unsafe extern "C" fn(*mut c_void, u32, *const c_char, *const c_char) -> i32,
+        /// Despawns an entity; returns 0 on success
+        pub despawn_entity: unsafe extern "C" fn(*mut c_void, u32) -> i32,
+        /// Returns 1 if the entity exists, 0 otherwise
+        pub entity_exists: unsafe extern "C" fn(*mut c_void, u32) -> i32,
+        /// Gets a component as JSON, or NULL if absent
+        pub get_component: unsafe extern "C" fn(*mut c_void, u32, *const c_char) -> *mut c_char,
+        /// Removes a component; returns 0 on success
+        pub remove_component: unsafe extern "C" fn(*mut c_void, u32, *const c_char) -> i32,
+        /// Entities with a component, as a JSON array of IDs
+        pub get_entities_with_component: unsafe extern "C" fn(*mut c_void, *const c_char) -> *mut c_char,
+        /// Entities matching a JSON query spec ({"with": [...], "without": [...], ...}), as a JSON array of IDs
+        pub query_entities: unsafe extern "C" fn(*mut c_void, *const c_char) -> *mut c_char,
+        /// Sends a JSON event to a named event bus; returns 0 on success
+        pub send_event: unsafe extern "C" fn(*mut c_void, *const c_char, *const c_char) -> i32,
+        /// Takes the events delivered to a bus by the last update, as a JSON array
+        pub poll_events: unsafe extern "C" fn(*mut c_void, *const c_char) -> *mut c_char,
+        /// Current turn
+        pub get_turn: unsafe extern "C" fn(*mut c_void) -> u32,
+        /// Map topology type ("square", "hex", "province"), or NULL without a map
+        pub get_map_topology: unsafe extern "C" fn(*mut c_void) -> *mut c_char,
+        /// Neighbors of a JSON cell, as a JSON array of cells
+        pub get_neighbors: unsafe extern "C" fn(*mut c_void, *const c_char) -> *mut c_char,
+        /// Path between two JSON cells as {"path": [...], "total_cost": n}, or NULL if none
+        pub find_path: unsafe extern "C" fn(*mut c_void, *const c_char, *const c_char) -> *mut c_char,
+        /// Frees a string returned by the engine
+        pub free_string: unsafe extern "C" fn(*mut c_char),
+    }
 }
 
-/// The current ABI version that the engine expects plugins to match.
-pub const PLUGIN_ABI_VERSION: u32 = 1;
+impl EngineApi {
+    /// The engine API backed by the `ffi_*` functions, for a `*mut World` world pointer.
+    pub fn new() -> Self {
+        use super::ffi::*;
+        Self {
+            struct_size: std::mem::size_of::<EngineApi>() as u32,
+            version: ENGINE_API_VERSION,
+            spawn_entity: ffi_spawn_entity,
+            set_component: ffi_set_component,
+            despawn_entity: ffi_despawn_entity,
+            entity_exists: ffi_entity_exists,
+            get_component: ffi_get_component,
+            remove_component: ffi_remove_component,
+            get_entities_with_component: ffi_get_entities_with_component,
+            query_entities: ffi_query_entities,
+            send_event: ffi_send_event,
+            poll_events: ffi_poll_events,
+            get_turn: ffi_get_turn,
+            get_map_topology: ffi_get_map_topology,
+            get_neighbors: ffi_get_neighbors,
+            find_path: ffi_find_path,
+            free_string: ffi_free_string,
+        }
+    }
+}
 
-/// A plugin's vtable
-#[repr(C)]
-pub struct PluginVTable {
-    /// MUST equal PLUGIN_ABI_VERSION
-    pub abi_version: u32,
-    /// The plugin's init function
-    pub init: unsafe extern "C" fn(*mut EngineApi, *mut c_void) -> c_int,
-    /// The plugin's shutdown function
-    pub shutdown: unsafe extern "C" fn(),
-    /// The plugin's update function
-    pub update: unsafe extern "C" fn(c_float),
-    /// The plugin's worldgen name
-    pub worldgen_name: Option<unsafe extern "C" fn() -> *const c_char>,
-    /// The plugin's worldgen function
-    pub generate_world: Option<unsafe extern "C" fn(*const c_char, *mut *mut c_char) -> c_int>,
-    /// The plugin's result parser
-    pub free_result_json: Option<unsafe extern "C" fn(*mut c_char)>,
-    /// The plugin's system registration function
-    pub register_systems: Option<
-        unsafe extern "C" fn(
-            *mut EngineApi,
-            *mut c_void,
-            *mut *mut SystemPlugin,
-            *mut c_int,
-        ) -> c_int,
-    >,
-    /// The plugin's system unregistration function
-    pub free_systems: Option<unsafe extern "C" fn(*mut SystemPlugin, c_int)>,
-    /// The plugin's hot-reload function
-    pub hot_reload: Option<unsafe extern "C" fn(old_state: *mut c_void) -> *mut c_void>,
+impl Default for EngineApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+c_abi_struct! {
+    /// A plugin's vtable
+    pub struct PluginVTable {
+        /// MUST equal PLUGIN_ABI_VERSION
+        pub abi_version: u32,
+        /// The plugin's init function
+        pub init: unsafe extern "C" fn(*mut EngineApi, *mut c_void) -> c_int,
+        /// The plugin's shutdown function
+        pub shutdown: unsafe extern "C" fn(),
+        /// The plugin's update function
+        pub update: unsafe extern "C" fn(c_float),
+        /// The plugin's worldgen name
+        pub worldgen_name: Option<unsafe extern "C" fn() -> *const c_char>,
+        /// The plugin's worldgen function
+        pub generate_world: Option<unsafe extern "C" fn(*const c_char, *mut *mut c_char) -> c_int>,
+        /// The plugin's result parser
+        pub free_result_json: Option<unsafe extern "C" fn(*mut c_char)>,
+        /// The plugin's system registration function
+        pub register_systems: Option<
+            unsafe extern "C" fn(
+                *mut EngineApi,
+                *mut c_void,
+                *mut *mut SystemPlugin,
+                *mut c_int,
+            ) -> c_int,
+        >,
+        /// The plugin's system unregistration function
+        pub free_systems: Option<unsafe extern "C" fn(*mut SystemPlugin, c_int)>,
+        /// Called once init and system registration succeed, with NULL on the first
+        /// load and the state returned by the previous version on a hot reload; returns
+        /// the state to keep. The state must live outside the library's own memory
+        /// (e.g. be malloc'd).
+        pub hot_reload: Option<unsafe extern "C" fn(*mut c_void) -> *mut c_void>,
+    }
 }
 
 impl SystemPlugin {
@@ -1,7 +1,10 @@
 //! Input abstraction for the presentation layer.
 
+use serde::{Deserialize, Serialize};
+
 /// An input event.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "key", rename_all = "snake_case")]
 pub enum InputEvent {
     /// A key was pressed.
     KeyPress(char),
@@ -17,6 +17,7 @@ use crate::presentation::renderer::{
     COLOR_BLACK, COLOR_DIM_GRAY, COLOR_GRAY, COLOR_VERY_DIM, PresentationRenderer, RenderColor,
     RenderCommand,
 };
+use serde_json::Value;
 use std::collections::HashSet;
 
 /// Presentation system for ECS worlds with schema-driven components.
@@ -88,6 +89,7 @@ impl<R: PresentationRenderer> PresentationSystem<R> {
     }
 
     /// Render the map without visibility filtering.
+    /// Only the viewport's z-level is drawn if it has one (see [`Viewport::with_z`]).
     /// Delegates to [`render_map_with_visibility`](Self::render_map_with_visibility)
     /// with `None` for visible/explored cells.
     pub fn render_map(&mut self, world: &crate::ecs::world::World, viewport: &Viewport) {
@@ -128,6 +130,9 @@ impl<R: PresentationRenderer> PresentationSystem<R> {
 
         // Draw terrain/background
         for cell in map.all_cells() {
+            if !viewport.shows_level(&cell) {
+                continue;
+            }
             let (sx, sy) = layout.cell_to_screen(&cell);
             if viewport.contains(sx, sy) {
                 let in_visible = visible_cells.map(|vis| vis.contains(&cell)).unwrap_or(true);
@@ -150,21 +155,7 @@ impl<R: PresentationRenderer> PresentationSystem<R> {
                         ('.', COLOR_VERY_DIM)
                     }
                     // Visible: render terrain normally (also handles explored_cells=None + visible)
-                    (true, _) => {
-                        if let Some(meta) = meta {
-                            if let Some(terrain) = meta.get("terrain").and_then(|v| v.as_str()) {
-                                match terrain {
-                                    "wall" => ('#', COLOR_GRAY),
-                                    "floor" => ('.', COLOR_DIM_GRAY),
-                                    _ => ('.', COLOR_DIM_GRAY),
-                                }
-                            } else {
-                                ('.', COLOR_DIM_GRAY)
-                            }
-                        } else {
-                            ('.', COLOR_DIM_GRAY)
-                        }
-                    }
+                    (true, _) => cell_glyph(map, &cell, meta),
                 };
                 self.renderer.queue_draw(RenderCommand {
                     glyph,
@@ -215,6 +206,13 @@ impl<R: PresentationRenderer> PresentationSystem<R> {
                     (0, 0, None)
                 };
 
+                if entity_cell
+                    .as_ref()
+                    .is_some_and(|cell| !viewport.shows_level(cell))
+                {
+                    continue;
+                }
+
                 // Skip entities in non-visible cells
                 let in_visible = match (&visible_cells, &entity_cell) {
                     (Some(vis), Some(cell)) => vis.contains(cell),
@@ -257,6 +255,40 @@ impl<R: PresentationRenderer> PresentationSystem<R> {
     }
 }
 
+/// Glyph and color of a visible cell. Stairs, ladders and ramps show as `<`,
+/// `H` and `^`, the cell above stairs or a ladder as `>`, and open cells as
+/// the dim floor below them, if any.
+fn cell_glyph(map: &crate::map::Map, cell: &CellKey, meta: Option<&Value>) -> (char, RenderColor) {
+    use crate::map::VerticalKind;
+    use crate::map::vertical::shifted;
+
+    let below = shifted(cell, -1).filter(|below| map.contains(below));
+    match VerticalKind::from_meta(meta) {
+        Some(VerticalKind::Stairs) => return ('<', COLOR_GRAY),
+        Some(VerticalKind::Ladder) => return ('H', COLOR_GRAY),
+        Some(VerticalKind::Ramp) => return ('^', COLOR_GRAY),
+        Some(VerticalKind::Open) => {
+            return match below {
+                Some(_) => ('.', COLOR_VERY_DIM),
+                None => (' ', COLOR_BLACK),
+            };
+        }
+        None => {}
+    }
+    let terrain = meta.and_then(|m| m.get("terrain")).and_then(|v| v.as_str());
+    if terrain == Some("wall") {
+        return ('#', COLOR_GRAY);
+    }
+    let below_kind = below.and_then(|below| VerticalKind::from_meta(map.get_cell_metadata(&below)));
+    if matches!(
+        below_kind,
+        Some(VerticalKind::Stairs | VerticalKind::Ladder)
+    ) {
+        return ('>', COLOR_GRAY);
+    }
+    ('.', COLOR_DIM_GRAY)
+}
+
 /// Calculate the centroid of a province for rendering.
 /// Returns (x, y) as i32 grid coordinates.
 /// This function assumes the map contains provinces as collections of cell positions.
@@ -302,6 +334,8 @@ pub struct Viewport {
     pub width: i32,
     /// The height
     pub height: i32,
+    /// The z-level shown, or `None` to draw every level (set with `with_z`)
+    z: Option<i32>,
 }
 
 impl Viewport {
@@ -312,6 +346,26 @@ impl Viewport {
             y,
             width,
             height,
+            z: None,
+        }
+    }
+
+    /// Show only the cells and entities on z-level `z`
+    pub fn with_z(mut self, z: i32) -> Self {
+        self.z = Some(z);
+        self
+    }
+
+    /// The z-level shown, or `None` if every level is drawn
+    pub fn z(&self) -> Option<i32> {
+        self.z
+    }
+
+    /// Check if a cell is on the level shown; province cells always are
+    pub fn shows_level(&self, cell: &CellKey) -> bool {
+        match (self.z, crate::map::vertical::level(cell)) {
+            (Some(z), Some(level)) => z == level,
+            _ => true,
         }
     }
 
@@ -0,0 +1,153 @@
+//! Deterministic, world-owned random number generation.
+//!
+//! A [`WorldRng`] holds a single master seed and any number of named sub-streams
+//! (loot, jobs, AI, worldgen, ...). Each stream is an independent ChaCha8 generator
+//! whose seed is derived from the master seed and the stream name, so drawing from
+//! one stream never perturbs another. The RNG state is saved with the world, which
+//! makes two runs from the same save produce the same results.
+
+use crate::config::GameConfig;
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Stream used by loot table rolls.
+pub const LOOT: &str = "loot";
+/// Stream used by the job system (XP rolls, job outcomes).
+pub const JOBS: &str = "jobs";
+/// Stream used by AI decision making.
+pub const AI: &str = "ai";
+/// Stream used by world and map generation.
+pub const WORLDGEN: &str = "worldgen";
+
+/// A single named random stream.
+pub type RngStream = ChaCha8Rng;
+
+/// Seeded random number service owned by the world.
+#[derive(Debug, Clone)]
+pub struct WorldRng {
+    seed: u64,
+    streams: BTreeMap<String, RngStream>,
+}
+
+impl Default for WorldRng {
+    fn default() -> Self {
+        Self::from_entropy()
+    }
+}
+
+impl WorldRng {
+    /// Create an RNG service from a master seed.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            streams: BTreeMap::new(),
+        }
+    }
+
+    /// Create an RNG service seeded from the system clock.
+    pub fn from_entropy() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        Self::new(nanos as u64 ^ (nanos >> 64) as u64)
+    }
+
+    /// Create an RNG service from the game config `seed`, falling back to entropy.
+    pub fn from_config(config: &GameConfig) -> Self {
+        config
+            .seed
+            .map(Self::new)
+            .unwrap_or_else(Self::from_entropy)
+    }
+
+    /// Master seed.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Replace the master seed and restart every stream.
+    pub fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.streams.clear();
+    }
+
+    /// Get a named stream, creating it on first use.
+    pub fn stream(&mut self, name: &str) -> &mut RngStream {
+        let seed = self.seed;
+        self.streams
+            .entry(name.to_string())
+            .or_insert_with(|| RngStream::seed_from_u64(stream_seed(seed, name)))
+    }
+
+    /// Names of the streams drawn from so far.
+    pub fn stream_names(&self) -> Vec<String> {
+        self.streams.keys().cloned().collect()
+    }
+
+    /// Draw a random `u64` from a stream.
+    pub fn next_u64(&mut self, stream: &str) -> u64 {
+        self.stream(stream).next_u64()
+    }
+
+    /// Draw a random float in `[0, 1)` from a stream.
+    pub fn next_f64(&mut self, stream: &str) -> f64 {
+        self.stream(stream).random::<f64>()
+    }
+
+    /// Draw a random integer in `[min, max]` from a stream.
+    /// Returns `min` if the range is empty.
+    pub fn range_i64(&mut self, stream: &str, min: i64, max: i64) -> i64 {
+        if max <= min {
+            return min;
+        }
+        self.stream(stream).random_range(min..=max)
+    }
+}
+
+/// Derive a stream seed from the master seed and the stream name (FNV-1a),
+/// so stream seeds are stable across platforms and releases.
+fn stream_seed(seed: u64, name: &str) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in name.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    seed ^ hash
+}
+
+/// Saved form of a [`WorldRng`]: the master seed and each stream's position.
+#[derive(Serialize, Deserialize)]
+struct WorldRngState {
+    seed: u64,
+    #[serde(default)]
+    streams: BTreeMap<String, u64>,
+}
+
+impl Serialize for WorldRng {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        WorldRngState {
+            seed: self.seed,
+            streams: self
+                .streams
+                .iter()
+                .map(|(name, rng)| (name.clone(), rng.get_word_pos() as u64))
+                .collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for WorldRng {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let state = WorldRngState::deserialize(deserializer)?;
+        let mut rng = WorldRng::new(state.seed);
+        for (name, word_pos) in state.streams {
+            rng.stream(&name).set_word_pos(word_pos as u128);
+        }
+        Ok(rng)
+    }
+}
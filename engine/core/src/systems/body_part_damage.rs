@@ -1,3 +1,4 @@
+use crate::ecs::Health;
 use crate::ecs::system::System;
 use crate::ecs::world::World;
 use serde_json::{Value as JsonValue, json};
@@ -211,13 +212,17 @@ impl System for BodyPartDamageSystem {
 
             let _ = world.set_component(entity, "Body", body);
 
-            // Update Health.current
-            if let Some(healths) = world.components.get_mut("Health")
-                && let Some(value) = healths.get_mut(&entity)
-                && let Some(obj) = value.as_object_mut()
-                && let Some(current) = obj.get_mut("current")
-            {
-                *current = json!(total_hp);
+            // Update Health.current, in place if Health has a typed column
+            if world.is_typed_component("Health") {
+                if let Some(health) = world.typed_value_mut::<Health>(entity, "Health") {
+                    health.current = total_hp as f32;
+                }
+            } else {
+                world.modify_component_raw(entity, "Health", |value| {
+                    if let Some(current) = value.get_mut("current") {
+                        *current = json!(total_hp);
+                    }
+                });
             }
 
             let _ = world.remove_component(entity, "PendingDamage");
@@ -1,3 +1,4 @@
+use crate::ecs::Health;
 use crate::ecs::system::System;
 use crate::ecs::world::World;
 use serde_json::json;
@@ -12,10 +13,19 @@ impl System for ProcessDeaths {
     fn run(&mut self, world: &mut World) {
         let mut to_process = Vec::new();
 
-        // Collect entities with Health <= 0
-        if let Some(healths) = world.components.get("Health") {
-            for (&entity, value) in healths.iter() {
-                if let Some(obj) = value.as_object()
+        // Collect entities with Health <= 0, reading the typed column if Health has one
+        if let Some(healths) = world.typed_component::<Health>("Health") {
+            to_process.extend(
+                healths
+                    .iter()
+                    .filter(|(_, health)| health.current <= 0.0)
+                    .map(|(entity, _)| entity),
+            );
+        } else {
+            for entity in world.get_entities_with_component("Health") {
+                if let Some(obj) = world
+                    .get_component(entity, "Health")
+                    .and_then(|v| v.as_object())
                     && let Some(current) = obj.get("current")
                     && current.as_f64().unwrap_or(1.0) <= 0.0
                 {
@@ -26,9 +36,7 @@ impl System for ProcessDeaths {
 
         for entity in to_process {
             // Remove Health component
-            if let Some(healths) = world.components.get_mut("Health") {
-                healths.remove(&entity);
-            }
+            let _ = world.remove_component(entity, "Health");
 
             // Add Corpse component
             let _ = world.set_component(entity, "Corpse", json!({}));
@@ -47,8 +55,8 @@ impl System for ProcessDecay {
     }
     fn run(&mut self, world: &mut World) {
         let mut to_despawn_entities = Vec::new();
-        if let Some(decays) = world.components.get_mut("Decay") {
-            for (&entity, value) in decays.iter_mut() {
+        for entity in world.get_entities_with_component("Decay") {
+            world.modify_component_raw(entity, "Decay", |value| {
                 if let Some(obj) = value.as_object_mut()
                     && let Some(time_remaining) = obj.get_mut("time_remaining")
                     && let Some(t) = time_remaining.as_u64()
@@ -59,7 +67,7 @@ impl System for ProcessDecay {
                         *time_remaining = json!(t - 1);
                     }
                 }
-            }
+            });
         }
         for entity in to_despawn_entities {
             world.despawn_entity(entity);
@@ -1,11 +1,20 @@
 use crate::World;
 use crate::ecs::system::System;
+use crate::systems::research::{RESEARCH_RESOURCE, credit_research};
+use crate::tech_effects;
 use serde_json::{Value as JsonValue, json};
 use std::collections::HashMap;
 
 use super::recipe::{Recipe, ResourceAmount};
 
 /// Economic system
+///
+/// Runs the `ProductionJob` of every entity with a `Stockpile`. Jobs for
+/// recipes a tech unlocks wait in the `locked` state until the tech's effects
+/// apply to the entity, then go back to `in_progress` (if assigned) or
+/// `pending`. Tech production multipliers scale the outputs
+/// (see [`crate::tech_effects`]). Outputs of the [`RESEARCH_RESOURCE`] kind are
+/// credited as research points (see [`credit_research`]) instead of stockpiled.
 #[derive(Default)]
 pub struct EconomicSystem {
     recipes: HashMap<String, Recipe>,
@@ -55,6 +64,9 @@ impl EconomicSystem {
         outputs: &[ResourceAmount],
     ) {
         for output in outputs {
+            if output.kind == RESEARCH_RESOURCE {
+                continue;
+            }
             let current = stockpile
                 .get(&output.kind)
                 .and_then(|v| v.as_i64())
@@ -101,7 +113,9 @@ impl System for EconomicSystem {
             }
 
             // Transition pending → in_progress when assigned to a worker
-            if state == "pending" && job.get("assigned_to").and_then(|v| v.as_u64()).is_some() {
+            let assigned = job.get("assigned_to").and_then(|v| v.as_u64()).is_some();
+            let was_locked = state == "locked";
+            if state == "pending" && assigned {
                 job["state"] = json!("in_progress");
             }
 
@@ -109,13 +123,30 @@ impl System for EconomicSystem {
                 Some(r) => r.to_string(),
                 None => continue,
             };
-            let recipe = match self.recipes.get(&recipe_name) {
+            // Recipes in the world's data (e.g. contributed by mods) take
+            // precedence over the ones the system was built with
+            let recipe = match world
+                .recipes
+                .get(&recipe_name)
+                .and_then(|r| serde_json::from_value::<Recipe>(r.clone()).ok())
+                .or_else(|| self.recipes.get(&recipe_name).cloned())
+            {
                 Some(r) => r,
                 None => continue,
             };
+            if !tech_effects::is_recipe_unlocked(world, eid, &recipe_name) {
+                job["state"] = json!("locked");
+                let _ = world.set_component(eid, "ProductionJob", job);
+                continue;
+            }
+            // Resume a job whose recipe was locked until now
+            if was_locked {
+                job["state"] = json!(if assigned { "in_progress" } else { "pending" });
+            }
             let Some(mut stockpile) = world.get_component(eid, "Stockpile").cloned() else {
                 continue;
             };
+            let mut research = 0;
             let Some(stock_map) = stockpile
                 .get_mut("resources")
                 .and_then(|v| v.as_object_mut())
@@ -137,26 +168,36 @@ impl System for EconomicSystem {
                         .filter(|&v| v >= 1)
                         .unwrap_or(1);
 
-                    // Produce outputs multiplied by batch_size
+                    // Produce outputs multiplied by batch_size and the tech
+                    // production multiplier of each resource
                     let multiplied_outputs: Vec<super::recipe::ResourceAmount> = recipe
                         .outputs
                         .iter()
-                        .map(|o| super::recipe::ResourceAmount {
-                            kind: o.kind.clone(),
-                            amount: o.amount * batch_size,
+                        .map(|o| {
+                            let multiplier =
+                                tech_effects::get_production_multiplier(world, eid, &o.kind);
+                            super::recipe::ResourceAmount {
+                                kind: o.kind.clone(),
+                                amount: ((o.amount * batch_size) as f64 * multiplier).round()
+                                    as i64,
+                            }
                         })
                         .collect();
                     Self::produce_outputs(stock_map, &multiplied_outputs);
+                    research += multiplied_outputs
+                        .iter()
+                        .filter(|o| o.kind == RESEARCH_RESOURCE)
+                        .map(|o| o.amount)
+                        .sum::<i64>();
                     job["state"] = json!("complete");
 
                     // Emit production_completed event
-                    let outputs_payload: Vec<serde_json::Value> = recipe
-                        .outputs
+                    let outputs_payload: Vec<serde_json::Value> = multiplied_outputs
                         .iter()
                         .map(|o| {
                             json!({
                                 "kind": o.kind,
-                                "amount": o.amount * batch_size,
+                                "amount": o.amount,
                             })
                         })
                         .collect();
@@ -176,6 +217,9 @@ impl System for EconomicSystem {
 
             let _ = world.set_component(eid, "ProductionJob", job);
             let _ = world.set_component(eid, "Stockpile", stockpile);
+            if research > 0 {
+                credit_research(world, eid, research as f64);
+            }
         }
     }
 }
@@ -1,13 +1,16 @@
-use crate::ecs::system::System;
+use crate::ecs::schedule::{SystemView, run_deferred};
+use crate::ecs::system::{System, SystemAccess, SystemCommands};
 use crate::ecs::world::World;
 use crate::map::cell_key::CellKey;
-use crate::map::fov::{BfsFovAlgorithm, RecursiveShadowcasting};
+use crate::map::fov::{BfsFovAlgorithm, FovAlgorithm, RecursiveShadowcasting};
 use std::collections::HashSet;
 
 /// System: Computes field-of-view for all entities with a Sight component.
 ///
 /// Each tick, this system iterates entities with Sight, reads their Position,
-/// and stores visible cell sets in `world.visible_cells`.
+/// and stores visible cell sets in `world.visible_cells`. It reads `Sight` and
+/// `Position` and writes the FOV algorithm and visible cells resources, so it
+/// only shares a parallel batch with systems that use none of them.
 ///
 /// The FOV algorithm is auto-selected based on the map's topology type:
 /// - `"square"` → [`RecursiveShadowcasting`]
@@ -26,11 +29,22 @@ impl System for FovUpdateSystem {
         &[]
     }
 
+    fn access(&self) -> Option<SystemAccess> {
+        Some(
+            SystemAccess::new()
+                .read(&["Sight", "Position"])
+                .write_resources(&[SystemAccess::FOV_ALGORITHM, SystemAccess::VISIBLE_CELLS]),
+        )
+    }
+
     fn run(&mut self, world: &mut World) {
+        run_deferred(self, world);
+    }
+
+    fn run_parallel(&mut self, view: &SystemView<'_>) -> Option<SystemCommands> {
         // Guard against missing map
-        let map = match &world.map {
-            Some(m) => m,
-            None => return,
+        let Some(map) = view.map() else {
+            return Some(Box::new(|_| {}));
         };
 
         // Auto-select the FOV algorithm based on map topology
@@ -38,42 +52,47 @@ impl System for FovUpdateSystem {
             "hex" | "province" => "bfs_flood_fill",
             _ => "recursive_shadowcasting",
         };
-        if world.fov_algorithm.name() != desired {
+        let replacement: Option<Box<dyn FovAlgorithm>> = if view.fov_algorithm().name() != desired {
             match desired {
-                "bfs_flood_fill" => world.fov_algorithm = Box::new(BfsFovAlgorithm),
-                _ => world.fov_algorithm = Box::new(RecursiveShadowcasting),
+                "bfs_flood_fill" => Some(Box::new(BfsFovAlgorithm)),
+                _ => Some(Box::new(RecursiveShadowcasting)),
             }
-        }
+        } else {
+            None
+        };
+        let algorithm = replacement.as_deref().unwrap_or(view.fov_algorithm());
 
-        // Collect-then-apply pattern to avoid borrow conflicts with world.components
+        // Compute now, store the visible cells in the apply phase
         let mut results: Vec<(u32, HashSet<CellKey>)> = Vec::new();
 
-        if let Some(sight_components) = world.components.get("Sight") {
-            for (&entity, data) in sight_components.iter() {
-                let range = data.get("range").and_then(|v| v.as_u64()).unwrap_or(8) as u32;
+        for entity in view.get_entities_with_component("Sight") {
+            let Some(data) = view.get_component(entity, "Sight") else {
+                continue;
+            };
+            let range = data.get("range").and_then(|v| v.as_u64()).unwrap_or(8) as u32;
 
-                if let Some(pos) = world
-                    .get_component(entity, "Position")
-                    .and_then(CellKey::from_position)
-                {
-                    let visible =
-                        world
-                            .fov_algorithm()
-                            .compute_fov(&pos, range, map.topology.as_ref());
+            if let Some(pos) = view
+                .get_component(entity, "Position")
+                .and_then(CellKey::from_position)
+            {
+                let visible = algorithm.compute_fov(&pos, range, map.topology.as_ref());
 
-                    let visible: HashSet<CellKey> = visible
-                        .into_iter()
-                        .filter(|cell| map.contains(cell))
-                        .collect();
+                let visible: HashSet<CellKey> = visible
+                    .into_iter()
+                    .filter(|cell| map.contains(cell))
+                    .collect();
 
-                    results.push((entity, visible));
-                }
+                results.push((entity, visible));
             }
         }
 
-        // Apply collected results
-        for (entity, visible) in results {
-            world.set_visible_cells(entity, visible);
-        }
+        Some(Box::new(move |world: &mut World| {
+            if let Some(algorithm) = replacement {
+                world.fov_algorithm = algorithm;
+            }
+            for (entity, visible) in results {
+                world.set_visible_cells(entity, visible);
+            }
+        }))
     }
 }
@@ -15,36 +15,29 @@ impl System for AiEventReactionSystem {
             if let Some(kind) = intent.get("kind").and_then(|v| v.as_str()) {
                 // For every agent, enqueue a production job for the scarce resource if not already queued
                 let production_jobs: Vec<u32> = world
-                    .components
-                    .get("Job")
-                    .map(|map| {
-                        map.iter()
-                            .filter_map(|(&eid, job)| {
-                                if job.get("job_type").and_then(|v| v.as_str())
-                                    == Some("production")
-                                    && job
-                                        .get("resource_outputs")
-                                        .and_then(|v| v.as_array())
-                                        .is_some_and(|outputs| {
-                                            outputs.iter().any(|output| {
-                                                output.get("kind").and_then(|v| v.as_str())
-                                                    == Some(kind)
-                                            })
+                    .get_entities_with_component("Job")
+                    .into_iter()
+                    .filter(|&eid| {
+                        world.get_component(eid, "Job").is_some_and(|job| {
+                            job.get("job_type").and_then(|v| v.as_str()) == Some("production")
+                                && job
+                                    .get("resource_outputs")
+                                    .and_then(|v| v.as_array())
+                                    .is_some_and(|outputs| {
+                                        outputs.iter().any(|output| {
+                                            output.get("kind").and_then(|v| v.as_str())
+                                                == Some(kind)
                                         })
-                                {
-                                    Some(eid)
-                                } else {
-                                    None
-                                }
-                            })
-                            .collect()
+                                    })
+                        })
                     })
-                    .unwrap_or_default();
+                    .collect();
 
                 let mut updates = Vec::new();
-                for (&agent_id, agent) in
-                    world.components.get("Agent").unwrap_or(&Default::default())
-                {
+                for agent_id in world.get_entities_with_component("Agent") {
+                    let Some(agent) = world.get_component(agent_id, "Agent") else {
+                        continue;
+                    };
                     let mut queue = agent
                         .get("job_queue")
                         .and_then(|v| v.as_array())
@@ -59,13 +52,9 @@ impl System for AiEventReactionSystem {
                 }
                 // --- Now apply updates mutably ---
                 for (agent_id, queue) in updates {
-                    if let Some(agent_entry) = world
-                        .components
-                        .get_mut("Agent")
-                        .and_then(|m| m.get_mut(&agent_id))
-                    {
+                    world.modify_component_raw(agent_id, "Agent", |agent_entry| {
                         agent_entry["job_queue"] = JsonValue::from(queue);
-                    }
+                    });
                 }
             }
         }
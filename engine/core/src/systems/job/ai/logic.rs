@@ -1,5 +1,6 @@
 use crate::ecs::world::World;
 use crate::systems::job::job_board::JobBoard;
+use crate::tech_effects;
 use serde_json::Value as JsonValue;
 use std::collections::VecDeque;
 use std::sync::{Arc, LazyLock, Mutex};
@@ -57,7 +58,15 @@ fn compute_job_utility(agent: &JsonValue, job: &JsonValue, world: &World) -> f64
     skill + pref + resource_bonus + specialization_bonus
 }
 
+/// Whether the job's type is unlocked for the agent (see [`tech_effects`]).
+fn job_type_unlocked(world: &World, agent_id: u32, job: &JsonValue) -> bool {
+    job.get("job_type")
+        .and_then(|v| v.as_str())
+        .is_none_or(|job_type| tech_effects::is_job_type_unlocked(world, agent_id, job_type))
+}
+
 /// Assigns jobs to agents based on utility, priority, job queue, and specialization.
+/// Jobs whose type a tech unlocks are only assigned to agents the tech's effects apply to.
 /// Prefers assigning jobs to agents whose specializations match the job's category.
 /// If no such agent is available, will assign jobs to any idle agent as a fallback.
 /// Handles job preemption, agent job queues, and blocked jobs.
@@ -72,12 +81,7 @@ pub fn assign_jobs(
 ) {
     use std::collections::{HashMap, HashSet};
 
-    let mut agent_ids: Vec<u32> = world
-        .components
-        .get("Agent")
-        .map(|map| map.keys().copied().collect())
-        .unwrap_or_default();
-    agent_ids.sort();
+    let agent_ids = world.get_entities_with_component("Agent");
 
     job_board.update(world, current_tick, shortage_kinds);
 
@@ -87,7 +91,7 @@ pub fn assign_jobs(
 
     // Always clean up any agent still holding a blocked job
     for &agent_id in &agent_ids {
-        let agent_opt = world.components.get("Agent").and_then(|m| m.get(&agent_id));
+        let agent_opt = world.get_component(agent_id, "Agent");
         let current_job_eid = agent_opt.and_then(|agent| {
             agent.get("current_job").and_then(|v| {
                 if v.is_null() {
@@ -120,7 +124,7 @@ pub fn assign_jobs(
     // === First pass: assign jobs to agents with matching specialization ===
     for agent_id in &agent_ids {
         let (mut agent_state, agent_queue, mut has_current_job, mut current_job_eid) = {
-            let agent = match world.components.get("Agent").and_then(|m| m.get(agent_id)) {
+            let agent = match world.get_component(*agent_id, "Agent") {
                 Some(agent) => agent,
                 None => {
                     continue;
@@ -171,7 +175,7 @@ pub fn assign_jobs(
             let current_priority = current_job
                 .and_then(|job| job.get("priority").and_then(|v| v.as_i64()))
                 .unwrap_or(0);
-            let Some(agent) = world.components.get("Agent").and_then(|m| m.get(agent_id)) else {
+            let Some(agent) = world.get_component(*agent_id, "Agent") else {
                 continue;
             };
 
@@ -184,11 +188,7 @@ pub fn assign_jobs(
                 .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect::<Vec<_>>())
                 .unwrap_or_default();
 
-            let all_job_ids: Vec<u32> = world
-                .components
-                .get("Job")
-                .map(|map| map.keys().copied().collect())
-                .unwrap_or_default();
+            let all_job_ids = world.get_entities_with_component("Job");
 
             for &job_eid in &all_job_ids {
                 if assigned_jobs.contains(&job_eid) {
@@ -213,6 +213,7 @@ pub fn assign_jobs(
 
                 if job_state != "pending"
                     || assigned == *agent_id as u64
+                    || !job_type_unlocked(world, *agent_id, job)
                     || job_state == "blocked"
                     || job_state == "failed"
                     || job_state == "complete"
@@ -251,9 +252,7 @@ pub fn assign_jobs(
                     job_clone["assigned_to"] = serde_json::json!(*agent_id);
                     let _ = world.set_component(new_job_eid, "Job", job_clone);
                 }
-                if let Some(agent_entry) =
-                    world.components.get("Agent").and_then(|m| m.get(agent_id))
-                {
+                if let Some(agent_entry) = world.get_component(*agent_id, "Agent") {
                     let mut agent_obj = agent_entry.clone();
                     agent_obj["current_job"] = serde_json::json!(new_job_eid);
                     agent_obj["state"] = serde_json::json!("working");
@@ -267,7 +266,7 @@ pub fn assign_jobs(
         }
 
         if became_idle_this_tick || preempted_this_tick {
-            let agent = match world.components.get("Agent").and_then(|m| m.get(agent_id)) {
+            let agent = match world.get_component(*agent_id, "Agent") {
                 Some(agent) => agent,
                 None => continue,
             };
@@ -311,6 +310,7 @@ pub fn assign_jobs(
                     let job_state = job.get("state").and_then(|v| v.as_str()).unwrap_or("");
                     if job_state == "pending"
                         && (!job_category.is_empty() && specializations.contains(&job_category))
+                        && job_type_unlocked(world, *agent_id, job)
                         && job_state != "blocked"
                         && job_state != "failed"
                         && job_state != "complete"
@@ -338,6 +338,7 @@ pub fn assign_jobs(
                     let job_state = job.get("state").and_then(|v| v.as_str()).unwrap_or("");
                     if job_state == "pending"
                         && (!job_category.is_empty() && specializations.contains(&job_category))
+                        && job_type_unlocked(world, *agent_id, job)
                         && job_state != "blocked"
                         && job_state != "failed"
                         && job_state != "complete"
@@ -364,7 +365,7 @@ pub fn assign_jobs(
                     None,
                 );
             }
-            if let Some(agent_entry) = world.components.get("Agent").and_then(|m| m.get(agent_id)) {
+            if let Some(agent_entry) = world.get_component(*agent_id, "Agent") {
                 let mut agent_obj = agent_entry.clone();
                 agent_obj["current_job"] = serde_json::json!(job_eid);
                 agent_obj["state"] = serde_json::json!("working");
@@ -379,7 +380,7 @@ pub fn assign_jobs(
 
     // === Second pass: fallback assignment for any idle agent and unassigned job ===
     for agent_id in &agent_ids {
-        let agent = match world.components.get("Agent").and_then(|m| m.get(agent_id)) {
+        let agent = match world.get_component(*agent_id, "Agent") {
             Some(agent) => agent,
             None => continue,
         };
@@ -405,6 +406,7 @@ pub fn assign_jobs(
             if let Some(job) = world.get_component(job_eid, "Job") {
                 let job_state = job.get("state").and_then(|v| v.as_str()).unwrap_or("");
                 if job_state == "pending"
+                    && job_type_unlocked(world, *agent_id, job)
                     && job_state != "blocked"
                     && job_state != "failed"
                     && job_state != "complete"
@@ -429,7 +431,7 @@ pub fn assign_jobs(
                     None,
                 );
             }
-            if let Some(agent_entry) = world.components.get("Agent").and_then(|m| m.get(agent_id)) {
+            if let Some(agent_entry) = world.get_component(*agent_id, "Agent") {
                 let mut agent_obj = agent_entry.clone();
                 agent_obj["current_job"] = serde_json::json!(job_eid);
                 agent_obj["state"] = serde_json::json!("working");
@@ -442,7 +444,7 @@ pub fn assign_jobs(
     }
 
     for agent_id in &agent_ids {
-        if let Some(agent_entry) = world.components.get("Agent").and_then(|m| m.get(agent_id)) {
+        if let Some(agent_entry) = world.get_component(*agent_id, "Agent") {
             let mut agent_obj = agent_entry.clone();
             let has_job = agent_obj
                 .get("current_job")
@@ -82,6 +82,46 @@ pub enum JobAssignmentResult {
     NoJobsAvailable,
 }
 
+/// Serialized form of a [`JobBoard`]; the policy is stored by name.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct JobBoardState {
+    jobs: Vec<u32>,
+    policy: String,
+    #[serde(default)]
+    current_tick: u64,
+    #[serde(default)]
+    shortage_kinds: Vec<String>,
+}
+
+impl serde::Serialize for JobBoard {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        JobBoardState {
+            jobs: self.jobs.clone(),
+            policy: self.policy.name().to_string(),
+            current_tick: self.current_tick,
+            shortage_kinds: self.shortage_kinds.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for JobBoard {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let state = JobBoardState::deserialize(deserializer)?;
+        let mut board = JobBoard::default();
+        if board.set_policy(&state.policy).is_err() {
+            log::warn!(
+                "Job board policy '{}' cannot be restored from a save; using priority",
+                state.policy
+            );
+        }
+        board.jobs = state.jobs;
+        board.current_tick = state.current_tick;
+        board.shortage_kinds = state.shortage_kinds;
+        Ok(board)
+    }
+}
+
 impl Default for JobBoard {
     fn default() -> Self {
         Self::with_policy(Box::new(PriorityPolicy))
@@ -6,7 +6,8 @@
 use crate::ecs::world::World;
 use serde_json::{Value as JsonValue, json};
 
-/// Assigns a move path to the agent from `from_cell` to `to_cell` using the map's pathfinding.
+/// Assigns a move path to the agent from `from_cell` to `to_cell` using the map's pathfinding
+/// and the agent's movement profile.
 /// If a valid path exists, updates the agent's `move_path` component.
 pub fn assign_move_path(
     world: &mut World,
@@ -14,41 +15,64 @@ pub fn assign_move_path(
     from_cell: &crate::map::CellKey,
     to_cell: &crate::map::CellKey,
 ) {
-    if let Some(map) = &world.map
-        && let Some(pathfinding) = map.find_path(from_cell, to_cell)
-    {
-        if pathfinding.path.len() <= 1 {
-            // Already at destination or path empty; clear move_path if any
-            if let Some(mut agent) = world.get_component(agent_id, "Agent").cloned() {
-                if let Some(obj) = agent.as_object_mut() {
-                    obj.remove("move_path");
-                }
-                let _ = world.set_component(agent_id, "Agent", agent);
+    if let Some(pathfinding) = world.find_path_for(agent_id, from_cell, to_cell) {
+        set_move_path(world, agent_id, &pathfinding.path);
+    }
+}
+
+/// Assigns a move path to the agent from `from_cell` to the nearest of `goals` by stepping
+/// down the map's cached Dijkstra map for the agent's movement profile.
+///
+/// Agents heading for the same goals share one map instead of searching separately.
+/// If no goal is reachable, the agent's `move_path` is left unchanged.
+pub fn assign_move_path_down_gradient(
+    world: &mut World,
+    agent_id: u32,
+    from_cell: &crate::map::CellKey,
+    goals: &[crate::map::CellKey],
+) {
+    let Some(map) = world.map.as_ref() else {
+        return;
+    };
+    let field = map.dijkstra_map(goals, world.movement_profile_of(agent_id));
+    if let Some(path) = field.descend(from_cell, |cell| map.neighbors(cell)) {
+        set_move_path(world, agent_id, &path);
+    }
+}
+
+/// Stores `path` (starting at the agent's cell) as the agent's `move_path`,
+/// clearing it if the agent is already at the end of the path.
+fn set_move_path(world: &mut World, agent_id: u32, path: &[crate::map::CellKey]) {
+    if path.len() <= 1 {
+        // Already at destination or path empty; clear move_path if any
+        if let Some(mut agent) = world.get_component(agent_id, "Agent").cloned() {
+            if let Some(obj) = agent.as_object_mut() {
+                obj.remove("move_path");
             }
-            return;
+            let _ = world.set_component(agent_id, "Agent", agent);
         }
-        let move_path: Vec<JsonValue> = pathfinding
-            .path
-            .iter()
-            .skip(1)
-            .map(|cell| match cell {
-                crate::map::CellKey::Square { x, y, z } => {
-                    json!({ "Square": { "x": x, "y": y, "z": z } })
-                }
-                crate::map::CellKey::Hex { q, r, z } => {
-                    json!({ "Hex": { "q": q, "r": r, "z": z } })
-                }
-                crate::map::CellKey::Province { id } => {
-                    json!({ "Province": { "id": id } })
-                }
-            })
-            .collect();
-        let Some(mut agent) = world.get_component(agent_id, "Agent").cloned() else {
-            return;
-        };
-        agent["move_path"] = json!(move_path);
-        let _ = world.set_component(agent_id, "Agent", agent);
+        return;
     }
+    let move_path: Vec<JsonValue> = path
+        .iter()
+        .skip(1)
+        .map(|cell| match cell {
+            crate::map::CellKey::Square { x, y, z } => {
+                json!({ "Square": { "x": x, "y": y, "z": z } })
+            }
+            crate::map::CellKey::Hex { q, r, z } => {
+                json!({ "Hex": { "q": q, "r": r, "z": z } })
+            }
+            crate::map::CellKey::Province { id } => {
+                json!({ "Province": { "id": id } })
+            }
+        })
+        .collect();
+    let Some(mut agent) = world.get_component(agent_id, "Agent").cloned() else {
+        return;
+    };
+    agent["move_path"] = json!(move_path);
+    let _ = world.set_component(agent_id, "Agent", agent);
 }
 
 /// Returns `true` if the agent is currently at the given cell.
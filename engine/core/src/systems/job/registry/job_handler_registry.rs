@@ -62,4 +62,10 @@ impl JobHandlerRegistry {
     pub fn keys(&self) -> Vec<String> {
         self.handlers.keys().cloned().collect()
     }
+
+    /// Removes the handler for the job type, returning whether there was one.
+    pub fn unregister(&mut self, job_type: &str) -> bool {
+        let key = normalize_key(job_type);
+        self.handlers.remove(&key).is_some()
+    }
 }
@@ -29,10 +29,8 @@ pub fn handle_going_to_site_state(world: &mut World, eid: u32, mut job: JsonValu
                 job["state"] = serde_json::json!("at_site");
                 let _ = world.set_component(eid, "Job", job.clone());
                 return job;
-            } else if movement_ops::is_move_path_empty(world, assigned_to)
-                && let Some(map) = &world.map
-            {
-                let path_result = map.find_path(&agent_cell, &target_cell);
+            } else if movement_ops::is_move_path_empty(world, assigned_to) && world.map.is_some() {
+                let path_result = world.find_path_for(assigned_to, &agent_cell, &target_cell);
                 if path_result.is_none() {
                     return handle_pathfinding_failure(world, eid, job);
                 }
@@ -50,11 +50,12 @@ pub fn handle_fetching_resources_state(
         if let (Some(agent_cell), Some(stockpile_cell)) = (agent_cell, stockpile_cell) {
             if agent_cell != stockpile_cell {
                 if movement_ops::is_move_path_empty(world, assigned_to) {
-                    movement_ops::assign_move_path(
+                    // Haulers share the stockpile's Dijkstra map.
+                    movement_ops::assign_move_path_down_gradient(
                         world,
                         assigned_to,
                         &agent_cell,
-                        &stockpile_cell,
+                        std::slice::from_ref(&stockpile_cell),
                     );
                 }
                 job["state"] = json!("fetching_resources");
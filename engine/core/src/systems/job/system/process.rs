@@ -1,6 +1,7 @@
 //! Core job processing logic for the job system.
 
 use crate::ecs::world::World;
+use crate::rng;
 use rand::Rng;
 use serde_json::{Map, Value as JsonValue};
 use std::collections::HashMap;
@@ -95,7 +96,7 @@ fn grant_xp_on_job_completion(
     let max_level = entry.map(|e| e.max_level).unwrap_or(100.0);
 
     // xp_gained = max(1, floor(base_xp * (1.0 + random_0_to_1)))
-    let random_factor: f64 = rand::rng().random::<f64>();
+    let random_factor: f64 = world.rng.stream(rng::JOBS).random::<f64>();
     let xp_gained = (base_xp * (1.0 + random_factor)).floor().max(1.0);
 
     // Get or create SkillLevels component
@@ -119,4 +119,19 @@ impl JobTypeRegistry {
     pub fn register_job_type(&mut self, data: JobTypeData) {
         self.register(data, JobLogicKind::Native(|_, _, _, job| job.clone()));
     }
+
+    /// Removes a job type and its logic, returning whether it was registered.
+    pub fn unregister(&mut self, name: &str) -> bool {
+        let had_logic = self.logic.remove(name).is_some();
+        self.data.remove(name).is_some() || had_logic
+    }
+
+    /// Registers or replaces a job type's data, keeping any logic already
+    /// registered for it (new job types get the default native handler).
+    pub fn register_data(&mut self, data: JobTypeData) {
+        self.logic
+            .entry(data.name.clone())
+            .or_insert(JobLogicKind::Native(|_, _, _, job| job.clone()));
+        self.data.insert(data.name.clone(), data);
+    }
 }
@@ -1,6 +1,7 @@
 use crate::ecs::system::System;
 use crate::ecs::world::World;
-use serde_json::Value as JsonValue;
+use crate::map::CellKey;
+use serde_json::{Value as JsonValue, json};
 
 /// System for movement
 #[derive(Default)]
@@ -19,10 +20,17 @@ impl System for MovementSystem {
                 None => continue,
             };
 
-            // Only process agents with a move_path
-            let move_path = match agent.get_mut("move_path") {
-                Some(JsonValue::Array(path)) if !path.is_empty() => path,
-                _ => continue,
+            // Agents without a move_path may follow a flow field instead
+            let has_path = agent
+                .get("move_path")
+                .and_then(|path| path.as_array())
+                .is_some_and(|path| !path.is_empty());
+            if !has_path {
+                step_down_flow_field(world, eid, agent);
+                continue;
+            }
+            let Some(JsonValue::Array(move_path)) = agent.get_mut("move_path") else {
+                continue;
             };
 
             // Next step in path as JSON
@@ -55,3 +63,29 @@ impl System for MovementSystem {
         }
     }
 }
+
+/// Moves an agent with `flow_goals` one step down the Dijkstra map towards
+/// the nearest goal, removing the goals once it arrives or none is reachable.
+fn step_down_flow_field(world: &mut World, eid: u32, mut agent: JsonValue) {
+    let Some(goals) = agent.get("flow_goals").and_then(|goals| goals.as_array()) else {
+        return;
+    };
+    let goals: Vec<CellKey> = goals.iter().filter_map(CellKey::from_position).collect();
+    let Some(cell) = world
+        .get_component(eid, "Position")
+        .and_then(CellKey::from_position)
+    else {
+        return;
+    };
+    match world.flow_step_for(eid, &cell, &goals) {
+        Some(next) => {
+            let _ = world.set_component(eid, "Position", json!({ "pos": next }));
+        }
+        None => {
+            if let Some(obj) = agent.as_object_mut() {
+                obj.remove("flow_goals");
+            }
+            let _ = world.set_component(eid, "Agent", agent);
+        }
+    }
+}
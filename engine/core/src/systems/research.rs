@@ -1,13 +1,127 @@
-//! Research system: allocates research points and completes techs.
+//! Research system: generates and allocates research points and completes techs.
+//!
+//! Research points come from the simulation:
+//!
+//! - Agents working an `in_progress` job of the [`RESEARCH_CATEGORY`] category
+//!   produce points equal to their [`RESEARCH_SKILL`] skill (1 if unskilled).
+//! - Recipes with [`RESEARCH_RESOURCE`] outputs produce points instead of a
+//!   stockpiled resource (see [`EconomicSystem`](crate::systems::economic::EconomicSystem)).
+//! - Each entity with a TechProgress component with a non-empty queue gains
+//!   its `base_rate` every tick. It defaults to 0, so passive research is
+//!   opt-in.
+//!
+//! Points produced by a member of a faction go to the faction's shared
+//! research pool ([`ResearchPools`]); the queues of the faction's members draw
+//! from it, in entity order. Points produced by an entity without a faction go
+//! to its own TechProgress. [`credit_research`] adds points the same way.
 //!
-//! Each tick, entities with a TechProgress component gain 1 research point.
 //! Points are allocated to the front of the research queue. When a tech's
-//! cost is met, it is marked as completed and a `tech_unlocked` event fires.
+//! cost is met, it is marked as completed, its effects are applied (see
+//! [`crate::tech_effects`]) and a `tech_unlocked` event fires.
 
 use crate::ecs::system::System;
 use crate::ecs::world::World;
+use crate::faction;
+use crate::tech_effects;
 use crate::tech_tree;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::collections::BTreeMap;
+
+/// Job category whose workers produce research points.
+pub const RESEARCH_CATEGORY: &str = "research";
+/// Agent skill scaling the research points produced by working research jobs.
+pub const RESEARCH_SKILL: &str = "research";
+/// Recipe output kind credited as research points.
+pub const RESEARCH_RESOURCE: &str = "research";
+
+/// Shared research points of each faction (`world.research_pools`), saved with
+/// the world.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ResearchPools {
+    points: BTreeMap<String, f64>,
+}
+
+impl ResearchPools {
+    /// Points in a faction's pool
+    pub fn get(&self, faction_id: &str) -> f64 {
+        self.points.get(faction_id).copied().unwrap_or(0.0)
+    }
+
+    /// Add points to a faction's pool
+    pub fn add(&mut self, faction_id: &str, points: f64) {
+        *self.points.entry(faction_id.to_string()).or_insert(0.0) += points;
+    }
+
+    /// Take up to `max` points from a faction's pool, returning the points taken.
+    pub fn take(&mut self, faction_id: &str, max: f64) -> f64 {
+        let Some(pool) = self.points.get_mut(faction_id) else {
+            return 0.0;
+        };
+        let taken = pool.min(max).max(0.0);
+        *pool -= taken;
+        taken
+    }
+
+    /// All pools, by faction ID
+    pub fn iter(&self) -> impl Iterator<Item = (&str, f64)> {
+        self.points.iter().map(|(k, v)| (k.as_str(), *v))
+    }
+}
+
+/// Credits research points produced by `entity` to its faction's research
+/// pool, or to its own TechProgress if it has no faction.
+///
+/// Returns false, dropping the points, if the entity has neither.
+pub fn credit_research(world: &mut World, entity: u32, points: f64) -> bool {
+    if let Some(faction_id) = faction::get_faction(world, entity) {
+        world.research_pools.add(&faction_id, points);
+        return true;
+    }
+    let Some(mut progress) = world.get_component(entity, "TechProgress").cloned() else {
+        return false;
+    };
+    let current = progress["research_points"].as_f64().unwrap_or(0.0);
+    progress["research_points"] = json!(current + points);
+    world
+        .set_component(entity, "TechProgress", progress)
+        .is_ok()
+}
+
+/// Credits the research points of the agents working research jobs.
+fn produce_agent_research(world: &mut World) {
+    let mut produced = Vec::new();
+    for job_eid in world.get_entities_with_component("Job") {
+        let Some(job) = world.get_component(job_eid, "Job") else {
+            continue;
+        };
+        if job.get("category").and_then(|v| v.as_str()) != Some(RESEARCH_CATEGORY)
+            || job.get("state").and_then(|v| v.as_str()) != Some("in_progress")
+        {
+            continue;
+        }
+        let Some(agent_id) = job
+            .get("assigned_to")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as u32)
+        else {
+            continue;
+        };
+        let Some(agent) = world.get_component(agent_id, "Agent") else {
+            continue;
+        };
+        let points = agent
+            .get("skills")
+            .and_then(|s| s.get(RESEARCH_SKILL))
+            .and_then(|v| v.as_f64())
+            .unwrap_or(1.0);
+        produced.push((agent_id, points));
+    }
+    for (agent_id, points) in produced {
+        credit_research(world, agent_id, points);
+    }
+}
 
 /// System that processes research each tick.
 ///
@@ -24,7 +138,9 @@ impl System for ResearchSystem {
     }
 
     fn run(&mut self, world: &mut World) {
-        // Step 1: Find all entities with TechProgress component
+        // Step 1: Credit research produced by agents, then find all entities
+        // with TechProgress component
+        produce_agent_research(world);
         let entities = world.get_entities_with_component("TechProgress");
 
         for entity in entities {
@@ -51,19 +167,38 @@ impl System for ResearchSystem {
                 .cloned()
                 .unwrap_or_default();
             let mut research_points = progress["research_points"].as_f64().unwrap_or(0.0);
+            let mut unlocked = Vec::new();
 
             if queue.is_empty() {
                 continue; // Nothing to research
             }
 
-            // Step 3: Add base research points (1.0 per tick)
-            research_points += 1.0;
+            // Step 3: Add base research points (base_rate per tick, default 0),
+            // then draw what the queue still needs from the faction's pool
+            research_points += progress["base_rate"].as_f64().unwrap_or(0.0);
+            if let Some(faction_id) = faction::get_faction(world, entity) {
+                let remaining: f64 = queue
+                    .iter()
+                    .filter_map(|id| {
+                        let cost = tech_tree::get_tech_node(world, id)?.cost;
+                        let done = queue_progress
+                            .get(id)
+                            .and_then(|v| v.as_f64())
+                            .unwrap_or(0.0);
+                        Some((cost - done).max(0.0))
+                    })
+                    .sum();
+                let needed = remaining - research_points;
+                if needed > 0.0 {
+                    research_points += world.research_pools.take(&faction_id, needed);
+                }
+            }
 
             // Step 4: Allocate to front of queue until points exhausted
             while research_points > 0.0 && !queue.is_empty() {
                 let front_id = queue[0].clone();
-                let node = match tech_tree::get_tech_node(&front_id) {
-                    Some(n) => n,
+                let node = match tech_tree::get_tech_node(world, &front_id) {
+                    Some(n) => n.clone(),
                     None => {
                         // Unknown tech in queue — remove it
                         queue.remove(0);
@@ -88,6 +223,7 @@ impl System for ResearchSystem {
                     completed.insert(front_id.clone(), json!(world.turn));
                     queue.remove(0);
                     queue_progress.remove(&front_id);
+                    unlocked.push(front_id.clone());
 
                     // Fire tech_unlocked event
                     let _ = world.send_event(
@@ -105,13 +241,17 @@ impl System for ResearchSystem {
             }
 
             // Step 5: Write updated TechProgress
-            let updated = json!({
-                "completed": serde_json::Value::Object(completed),
-                "queue": queue,
-                "queue_progress": serde_json::Value::Object(queue_progress),
-                "research_points": research_points,
-            });
+            let mut updated = progress;
+            updated["completed"] = serde_json::Value::Object(completed);
+            updated["queue"] = json!(queue);
+            updated["queue_progress"] = serde_json::Value::Object(queue_progress);
+            updated["research_points"] = json!(research_points);
             let _ = world.set_component(entity, "TechProgress", updated);
+
+            // Step 6: Apply the effects of completed techs
+            for tech_id in unlocked {
+                let _ = tech_effects::apply_tech_effects(world, entity, &tech_id);
+            }
         }
     }
 }
@@ -1,17 +1,25 @@
 use crate::ecs::system::System;
 use crate::ecs::world::World;
+use crate::tech_effects;
 use serde_json::{Map, Value as JsonValue};
 
-/// System for calculating stats from BaseStats and EquipmentEffects.
+/// System for calculating stats from BaseStats, EquipmentEffects and tech stat modifiers.
 ///
 /// Single source of truth for stat computation:
-///   Stats[k] = (BaseStats[k] || 0) + (EquipmentEffects[k] || 0)
+///   Stats[k] = (BaseStats[k] || 0) + (EquipmentEffects[k] || 0) + (tech modifiers[k] || 0)
+///
+/// Tech modifiers are the `stat_modifier` effects that apply to the entity (see
+/// [`crate::tech_effects`]).
 ///
 /// Edge cases handled:
 /// - No BaseStats component: entity is skipped (filtered by component query)
 /// - Null BaseStats values: treated as 0 via unwrap_or(0.0)
 /// - Key in EquipmentEffects but not in BaseStats: added to Stats with only the effect value
 /// - No EquipmentEffects component: treated as empty, only BaseStats contribute
+///
+/// Entities whose BaseStats, EquipmentEffects, Faction and Stats are unchanged
+/// since the system last ran are skipped (see [`World::system_last_run`]), unless
+/// tech effects were granted or reverted in between.
 pub struct StatCalculationSystem;
 
 impl System for StatCalculationSystem {
@@ -20,7 +28,17 @@ impl System for StatCalculationSystem {
     }
 
     fn run(&mut self, world: &mut World) {
+        let since = world
+            .system_last_run()
+            .filter(|&since| !world.tech_unlocks.changed_since(since));
         for eid in world.get_entities_with_component("BaseStats") {
+            if let Some(since) = since
+                && ["BaseStats", "EquipmentEffects", "Faction", "Stats"]
+                    .iter()
+                    .all(|name| !world.component_changed_since(eid, name, since))
+            {
+                continue;
+            }
             let Some(mut result) = world.get_component(eid, "BaseStats").cloned() else {
                 continue;
             };
@@ -38,6 +56,10 @@ impl System for StatCalculationSystem {
                     result[k] = JsonValue::from(base_val + delta);
                 }
             }
+            for (k, delta) in tech_effects::get_stat_modifiers(world, eid) {
+                let base_val = result.get(&k).and_then(|v| v.as_f64()).unwrap_or(0.0);
+                result[k] = JsonValue::from(base_val + delta);
+            }
             let _ = world.set_component(eid, "Stats", result);
         }
     }
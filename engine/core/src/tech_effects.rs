@@ -0,0 +1,392 @@
+//! Tech effects: what completing a tech does.
+//!
+//! A tech node's effects are `{action, data}` records. The engine handles the
+//! standard actions of [`TechEffect`]:
+//!
+//! - `unlock_recipe` (`{"recipe": name}`): the recipe can be produced by the
+//!   [`EconomicSystem`](crate::systems::economic::EconomicSystem).
+//! - `unlock_job` (`{"job_type": name}`): agents can be assigned jobs of the type.
+//! - `stat_modifier` (`{"stat": name, "amount": n}`): adds to the stat computed by the
+//!   [`StatCalculationSystem`](crate::systems::stat_calculation::StatCalculationSystem).
+//! - `production_multiplier` (`{"resource": kind, "multiplier": n}`): scales the
+//!   amount of the resource produced by recipes.
+//!
+//! Recipes and job types unlocked by some tech in the world's tree are locked
+//! until then; all others are available from the start. Other actions go to the
+//! handler registered with [`World::register_effect_handler`], and to the
+//! `Undo{action}` handler when the effects are reverted.
+//!
+//! Effects apply to the faction of the entity that researched the tech (its
+//! `Faction` component), or to the entity alone if it has none. They stay
+//! with the faction until no member has the tech completed. [`grant_tech`]
+//! and [`revoke_tech`] complete and un-complete techs directly, for cheats and
+//! debug tools.
+
+use crate::ecs::world::World;
+use crate::faction;
+use crate::systems::job::registry::EffectProcessorRegistry;
+use crate::tech_tree::{self, Effect};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value as JsonValue, json};
+use std::collections::BTreeMap;
+
+/// A tech effect the engine applies itself.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", content = "data", rename_all = "snake_case")]
+pub enum TechEffect {
+    /// Unlock a production recipe
+    UnlockRecipe {
+        /// Recipe name
+        recipe: String,
+    },
+    /// Unlock a job type
+    UnlockJob {
+        /// Job type name
+        job_type: String,
+    },
+    /// Permanently add to a stat
+    StatModifier {
+        /// Stat name
+        stat: String,
+        /// Amount added to the stat
+        amount: f64,
+    },
+    /// Multiply the amount of a resource produced by recipes
+    ProductionMultiplier {
+        /// Resource kind
+        resource: String,
+        /// Factor applied to produced amounts
+        multiplier: f64,
+    },
+}
+
+impl TechEffect {
+    /// Actions handled by the engine.
+    pub const ACTIONS: [&'static str; 4] = [
+        "unlock_recipe",
+        "unlock_job",
+        "stat_modifier",
+        "production_multiplier",
+    ];
+
+    /// Parse a tech node effect; `None` if the engine doesn't handle its action.
+    pub fn from_effect(effect: &Effect) -> Option<Result<TechEffect, serde_json::Error>> {
+        if !Self::ACTIONS.contains(&effect.action.as_str()) {
+            return None;
+        }
+        Some(serde_json::from_value(
+            json!({ "action": effect.action, "data": effect.data }),
+        ))
+    }
+}
+
+/// Who a tech's effects apply to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TechScope {
+    /// Every member of a faction
+    Faction(String),
+    /// A single entity
+    Entity(u32),
+}
+
+impl TechScope {
+    /// Scope of the techs researched by `entity`: its faction, or itself.
+    pub fn of(world: &World, entity: u32) -> Self {
+        match faction::get_faction(world, entity) {
+            Some(faction_id) => TechScope::Faction(faction_id),
+            None => TechScope::Entity(entity),
+        }
+    }
+}
+
+/// The effects of one tech applied to a scope.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TechGrant {
+    /// Entity whose research applied the effects
+    pub entity: u32,
+    /// Effects applied by the engine
+    #[serde(default)]
+    pub effects: Vec<TechEffect>,
+    /// Other effects, run by registered effect handlers
+    #[serde(default)]
+    pub custom: Vec<Effect>,
+}
+
+/// Tech effects applied to factions and entities, stored on the world
+/// (`world.tech_unlocks`) and saved with it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TechUnlocks {
+    #[serde(default)]
+    factions: BTreeMap<String, BTreeMap<String, TechGrant>>,
+    #[serde(default)]
+    entities: BTreeMap<u32, BTreeMap<String, TechGrant>>,
+    /// Change tick of the last grant or revert (runtime only)
+    #[serde(skip)]
+    changed: u64,
+}
+
+impl TechUnlocks {
+    /// Grants of a scope, by tech ID.
+    pub fn grants(&self, scope: &TechScope) -> Option<&BTreeMap<String, TechGrant>> {
+        match scope {
+            TechScope::Faction(faction_id) => self.factions.get(faction_id),
+            TechScope::Entity(entity) => self.entities.get(entity),
+        }
+    }
+
+    /// The grant of a tech to a scope.
+    pub fn get(&self, scope: &TechScope, tech_id: &str) -> Option<&TechGrant> {
+        self.grants(scope)?.get(tech_id)
+    }
+
+    /// Whether grants were added or reverted after change tick `since`
+    /// (see [`World::system_last_run`]).
+    pub fn changed_since(&self, since: u64) -> bool {
+        self.changed > since
+    }
+
+    fn insert(&mut self, scope: TechScope, tech_id: &str, grant: TechGrant) {
+        let grants = match scope {
+            TechScope::Faction(faction_id) => self.factions.entry(faction_id).or_default(),
+            TechScope::Entity(entity) => self.entities.entry(entity).or_default(),
+        };
+        grants.insert(tech_id.to_string(), grant);
+    }
+
+    fn remove(&mut self, scope: &TechScope, tech_id: &str) -> Option<TechGrant> {
+        match scope {
+            TechScope::Faction(faction_id) => {
+                let grants = self.factions.get_mut(faction_id)?;
+                let grant = grants.remove(tech_id);
+                if grants.is_empty() {
+                    self.factions.remove(faction_id);
+                }
+                grant
+            }
+            TechScope::Entity(entity) => {
+                let grants = self.entities.get_mut(entity)?;
+                let grant = grants.remove(tech_id);
+                if grants.is_empty() {
+                    self.entities.remove(entity);
+                }
+                grant
+            }
+        }
+    }
+}
+
+/// Scopes whose grants apply to an entity: its faction's, then its own.
+fn scopes(world: &World, entity: u32) -> Vec<TechScope> {
+    let mut scopes = Vec::new();
+    if let Some(faction_id) = faction::get_faction(world, entity) {
+        scopes.push(TechScope::Faction(faction_id));
+    }
+    scopes.push(TechScope::Entity(entity));
+    scopes
+}
+
+/// Standard effects that apply to an entity.
+fn entity_effects(world: &World, entity: u32) -> impl Iterator<Item = &TechEffect> {
+    scopes(world, entity)
+        .into_iter()
+        .filter_map(|scope| world.tech_unlocks.grants(&scope))
+        .flat_map(|grants| grants.values())
+        .flat_map(|grant| &grant.effects)
+}
+
+/// Run (or undo) effects with the world's effect handlers.
+fn run_custom_effects(world: &mut World, entity: u32, effects: &[Effect], undo: bool) {
+    let Some(registry) = world.effect_processor_registry.clone() else {
+        return;
+    };
+    let effects: Vec<JsonValue> = effects
+        .iter()
+        .filter_map(|effect| serde_json::to_value(effect).ok())
+        .collect();
+    if undo {
+        EffectProcessorRegistry::rollback_effects_arc(&registry, world, entity, &effects);
+    } else {
+        EffectProcessorRegistry::process_effects_arc(&registry, world, entity, &effects);
+    }
+}
+
+// ── Applying and reverting ───────────────────────────────────────────────
+
+/// Applies the effects of a tech completed by `entity` to its scope.
+///
+/// Does nothing if they already apply to the scope (e.g. another member of the
+/// faction researched the tech). Called by the research system when a tech
+/// completes.
+pub fn apply_tech_effects(world: &mut World, entity: u32, tech_id: &str) -> Result<(), String> {
+    let node = tech_tree::get_tech_node(world, tech_id)
+        .cloned()
+        .ok_or_else(|| format!("Unknown tech '{}'", tech_id))?;
+    let scope = TechScope::of(world, entity);
+    if world.tech_unlocks.get(&scope, tech_id).is_some() {
+        return Ok(());
+    }
+
+    let mut grant = TechGrant {
+        entity,
+        effects: Vec::new(),
+        custom: Vec::new(),
+    };
+    for effect in &node.effects {
+        match TechEffect::from_effect(effect) {
+            Some(Ok(parsed)) => grant.effects.push(parsed),
+            Some(Err(e)) => {
+                return Err(format!(
+                    "Invalid '{}' effect of tech '{}': {e}",
+                    effect.action, tech_id
+                ));
+            }
+            None => grant.custom.push(effect.clone()),
+        }
+    }
+    let custom = grant.custom.clone();
+    world.tech_unlocks.insert(scope, tech_id, grant);
+    world.tech_unlocks.changed = world.advance_change_tick();
+    run_custom_effects(world, entity, &custom, false);
+    Ok(())
+}
+
+/// Reverts the effects of a tech that apply to `entity` (through its faction
+/// or itself). A faction keeps the effects while another member still has the
+/// tech completed. Returns false if no effects were reverted.
+pub fn revert_tech_effects(world: &mut World, entity: u32, tech_id: &str) -> bool {
+    let scopes: Vec<TechScope> = scopes(world, entity)
+        .into_iter()
+        .filter(|scope| !still_researched(world, scope, entity, tech_id))
+        .collect();
+    let Some(grant) = scopes
+        .iter()
+        .find_map(|scope| world.tech_unlocks.remove(scope, tech_id))
+    else {
+        return false;
+    };
+    world.tech_unlocks.changed = world.advance_change_tick();
+    run_custom_effects(world, grant.entity, &grant.custom, true);
+    true
+}
+
+/// Whether a faction scope has a member other than `entity` with the tech
+/// completed.
+fn still_researched(world: &World, scope: &TechScope, entity: u32, tech_id: &str) -> bool {
+    let TechScope::Faction(faction_id) = scope else {
+        return false;
+    };
+    world
+        .get_entities_with_component("TechProgress")
+        .into_iter()
+        .filter(|&member| member != entity)
+        .any(|member| {
+            faction::get_faction(world, member).as_deref() == Some(faction_id)
+                && tech_tree::is_tech_completed(world, member, tech_id)
+        })
+}
+
+/// Completes a tech for an entity without research and applies its effects.
+/// Fires `tech_unlocked` unless the tech was already completed.
+pub fn grant_tech(world: &mut World, entity: u32, tech_id: &str) -> Result<(), String> {
+    let tech_name = tech_tree::get_tech_node(world, tech_id)
+        .map(|n| n.name.clone())
+        .ok_or_else(|| format!("Unknown tech '{}'", tech_id))?;
+
+    if !tech_tree::is_tech_completed(world, entity, tech_id) {
+        let mut progress = tech_tree::get_or_create_tech_progress(world, entity);
+        progress["completed"][tech_id] = json!(world.turn);
+        if let Some(queue) = progress["queue"].as_array_mut() {
+            queue.retain(|v| v.as_str() != Some(tech_id));
+        }
+        if let Some(queue_progress) = progress["queue_progress"].as_object_mut() {
+            queue_progress.remove(tech_id);
+        }
+        world.set_component(entity, "TechProgress", progress)?;
+        world.send_event(
+            "tech_unlocked",
+            json!({
+                "entity": entity,
+                "tech_id": tech_id,
+                "tech_name": tech_name,
+                "tick": world.turn,
+            }),
+        )?;
+    }
+
+    apply_tech_effects(world, entity, tech_id)
+}
+
+/// Un-completes a tech for an entity and reverts its effects. Fires `tech_revoked`.
+pub fn revoke_tech(world: &mut World, entity: u32, tech_id: &str) -> Result<(), String> {
+    if !tech_tree::is_tech_completed(world, entity, tech_id) {
+        return Err(format!("Tech '{}' is not completed", tech_id));
+    }
+    let mut progress = tech_tree::get_or_create_tech_progress(world, entity);
+    if let Some(completed) = progress["completed"].as_object_mut() {
+        completed.remove(tech_id);
+    }
+    world.set_component(entity, "TechProgress", progress)?;
+    revert_tech_effects(world, entity, tech_id);
+
+    let tech_name = tech_tree::get_tech_node(world, tech_id)
+        .map(|n| n.name.clone())
+        .unwrap_or_else(|| tech_id.to_string());
+    world.send_event(
+        "tech_revoked",
+        json!({
+            "entity": entity,
+            "tech_id": tech_id,
+            "tech_name": tech_name,
+            "tick": world.turn,
+        }),
+    )?;
+    Ok(())
+}
+
+// ── Queries ──────────────────────────────────────────────────────────────
+
+/// Standard effects that apply to an entity, through its faction or itself.
+pub fn get_tech_effects(world: &World, entity: u32) -> Vec<TechEffect> {
+    entity_effects(world, entity).cloned().collect()
+}
+
+/// Whether an entity may produce a recipe: true unless a tech in the world's
+/// tree unlocks the recipe and that tech's effects don't apply to the entity.
+pub fn is_recipe_unlocked(world: &World, entity: u32, recipe: &str) -> bool {
+    let unlocks = |effect: &TechEffect| matches!(effect, TechEffect::UnlockRecipe { recipe: r } if r == recipe);
+    !world.tech_tree.gates().recipes.contains(recipe) || entity_effects(world, entity).any(unlocks)
+}
+
+/// Whether an entity may be assigned jobs of a type: true unless a tech in the
+/// world's tree unlocks the job type and that tech's effects don't apply to
+/// the entity.
+pub fn is_job_type_unlocked(world: &World, entity: u32, job_type: &str) -> bool {
+    let unlocks = |effect: &TechEffect| matches!(effect, TechEffect::UnlockJob { job_type: j } if j == job_type);
+    !world.tech_tree.gates().job_types.contains(job_type)
+        || entity_effects(world, entity).any(unlocks)
+}
+
+/// Sum of the tech stat modifiers that apply to an entity, by stat.
+pub fn get_stat_modifiers(world: &World, entity: u32) -> BTreeMap<String, f64> {
+    let mut modifiers = BTreeMap::new();
+    for effect in entity_effects(world, entity) {
+        if let TechEffect::StatModifier { stat, amount } = effect {
+            *modifiers.entry(stat.clone()).or_insert(0.0) += amount;
+        }
+    }
+    modifiers
+}
+
+/// Product of the tech production multipliers of a resource that apply to an
+/// entity (1.0 if none do).
+pub fn get_production_multiplier(world: &World, entity: u32, resource: &str) -> f64 {
+    entity_effects(world, entity)
+        .filter_map(|effect| match effect {
+            TechEffect::ProductionMultiplier {
+                resource: r,
+                multiplier,
+            } if r == resource => Some(*multiplier),
+            _ => None,
+        })
+        .product()
+}
@@ -1,11 +1,17 @@
 //! Tech tree and research system definitions.
 //!
-//! Provides loading of tech tree definitions from JSON, per-entity tech progress
-//! management, prerequisite checking, and research queue manipulation.
+//! Provides per-world tech trees loaded from JSON assets and mods, per-entity
+//! tech progress management, prerequisite checking, and research queue
+//! manipulation.
 
 use crate::ecs::world::World;
+use crate::systems::job::reservation::resource_reservation_ops::ResourceReservationOps;
+use crate::tech_effects::TechEffect;
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use serde_json::{Value as JsonValue, json};
+use std::collections::{BTreeMap, HashSet};
+use std::ops::{Deref, DerefMut};
 use std::path::Path;
 use std::sync::OnceLock;
 
@@ -23,6 +29,8 @@ pub struct Prerequisite {
 }
 
 /// An effect that fires when a tech is unlocked.
+///
+/// See [`crate::tech_effects`] for the actions the engine handles.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Effect {
     /// Action identifier
@@ -45,6 +53,10 @@ pub struct TechNode {
     /// Research point cost to complete
     #[serde(default = "default_cost")]
     pub cost: f64,
+    /// Resources (kind → amount) taken from the researcher's `Stockpile` when
+    /// the tech is queued, and refunded if it is cancelled
+    #[serde(default)]
+    pub resource_cost: BTreeMap<String, i64>,
     /// Tech tier (for display / sorting)
     #[serde(default = "default_tier")]
     pub tier: u32,
@@ -74,61 +86,277 @@ fn default_category() -> String {
     "general".to_string()
 }
 
-/// Loaded tech tree data: map of tech ID → TechNode.
-type TechTreeMap = Vec<TechNode>;
-
-/// Loads the tech tree from tech_tree.json on first access.
-fn get_tech_tree_inner() -> &'static TechTreeMap {
-    static TECH_TREE: OnceLock<TechTreeMap> = OnceLock::new();
-    TECH_TREE.get_or_init(|| {
-        let paths = [
-            "engine/assets/schemas/tech_tree.json", // from workspace root
-            "../engine/assets/schemas/tech_tree.json", // from engine/ subdir
-            "../../engine/assets/schemas/tech_tree.json", // from engine/core/ subdir (tests)
-        ];
-        for path_str in &paths {
-            let path = Path::new(path_str);
-            if path.exists() {
-                match std::fs::read_to_string(path) {
-                    Ok(content) => {
-                        if let Ok(json) = serde_json::from_str::<JsonValue>(&content)
-                            && let Some(techs) = json.get("techs").and_then(|v| v.as_array())
-                        {
-                            let nodes: Vec<TechNode> = techs
-                                .iter()
-                                .filter_map(|t| {
-                                    let mut node: TechNode =
-                                        serde_json::from_value(t.clone()).ok()?;
-                                    // Ensure cost is at least 1 to avoid division by zero
-                                    if node.cost < 1.0 {
-                                        node.cost = 1.0;
-                                    }
-                                    Some(node)
-                                })
-                                .collect();
-                            if !nodes.is_empty() {
-                                return nodes;
-                            }
+/// A problem found by [`TechTree::validate`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TechTreeError {
+    /// A tech prerequisite names a tech that isn't in the tree
+    #[error("Tech '{tech}' requires unknown tech '{prerequisite}'")]
+    UnknownPrerequisite {
+        /// Tech declaring the prerequisite
+        tech: String,
+        /// Unknown tech ID
+        prerequisite: String,
+    },
+    /// Techs require each other; the first ID is repeated at the end
+    #[error("Tech prerequisite cycle: {}", .0.join(" -> "))]
+    Cycle(Vec<String>),
+    /// A standard effect (see [`TechEffect`]) has malformed data
+    #[error("Invalid '{action}' effect of tech '{tech}': {reason}")]
+    InvalidEffect {
+        /// Tech declaring the effect
+        tech: String,
+        /// Effect action
+        action: String,
+        /// Why the data doesn't parse
+        reason: String,
+    },
+}
+
+/// A set of tech nodes keyed by ID, in the order they were added.
+///
+/// Each [`World`] has its own tree (`world.tech_tree`). Trees are loaded from
+/// JSON files holding `{ "techs": [...] }`, an array of nodes or a single node,
+/// and from the `tech/` data of mods. A node with the ID of an existing one
+/// replaces it, so later sources override earlier ones.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(from = "Vec<TechNode>", into = "Vec<TechNode>")]
+pub struct TechTree {
+    nodes: IndexMap<String, TechNode>,
+    /// Derived from the nodes; cleared whenever they change.
+    gates: OnceLock<TechGates>,
+}
+
+/// Recipes and job types that some tech in a tree unlocks, and so are locked
+/// until it applies.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TechGates {
+    pub recipes: HashSet<String>,
+    pub job_types: HashSet<String>,
+}
+
+impl TechGates {
+    fn of<'a>(nodes: impl Iterator<Item = &'a TechNode>) -> Self {
+        let mut gates = TechGates::default();
+        let effects = nodes
+            .flat_map(|node| &node.effects)
+            .filter_map(|effect| TechEffect::from_effect(effect)?.ok());
+        for effect in effects {
+            match effect {
+                TechEffect::UnlockRecipe { recipe } => {
+                    gates.recipes.insert(recipe);
+                }
+                TechEffect::UnlockJob { job_type } => {
+                    gates.job_types.insert(job_type);
+                }
+                _ => {}
+            }
+        }
+        gates
+    }
+}
+
+impl From<Vec<TechNode>> for TechTree {
+    fn from(nodes: Vec<TechNode>) -> Self {
+        let mut tree = TechTree::new();
+        for node in nodes {
+            tree.insert(node);
+        }
+        tree.gates();
+        tree
+    }
+}
+
+impl From<TechTree> for Vec<TechNode> {
+    fn from(tree: TechTree) -> Self {
+        tree.nodes.into_values().collect()
+    }
+}
+
+impl TechTree {
+    /// Create an empty tech tree
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a node, replacing any node with the same ID (in place).
+    ///
+    /// Costs below 1 are raised to 1 so research always takes time.
+    pub fn insert(&mut self, mut node: TechNode) {
+        if node.cost < 1.0 {
+            node.cost = 1.0;
+        }
+        self.nodes.insert(node.id.clone(), node);
+        self.gates.take();
+    }
+
+    /// Remove a node, returning it.
+    pub fn remove(&mut self, id: &str) -> Option<TechNode> {
+        self.gates.take();
+        self.nodes.shift_remove(id)
+    }
+
+    /// Add every node of `other`, replacing nodes with the same ID.
+    pub fn merge(&mut self, other: TechTree) {
+        for node in other.nodes.into_values() {
+            self.insert(node);
+        }
+        self.gates();
+    }
+
+    /// Recipes and job types gated behind techs, computed once per change to the tree.
+    pub(crate) fn gates(&self) -> &TechGates {
+        self.gates
+            .get_or_init(|| TechGates::of(self.nodes.values()))
+    }
+
+    /// A node by ID
+    pub fn get(&self, id: &str) -> Option<&TechNode> {
+        self.nodes.get(id)
+    }
+
+    /// Whether the tree has a node with this ID
+    pub fn contains(&self, id: &str) -> bool {
+        self.nodes.contains_key(id)
+    }
+
+    /// All nodes, in the order they were added
+    pub fn iter(&self) -> impl Iterator<Item = &TechNode> {
+        self.nodes.values()
+    }
+
+    /// Number of nodes
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether the tree has no nodes
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Parse a tree from JSON: `{ "techs": [...] }`, an array of nodes or one node.
+    pub fn from_json(value: JsonValue) -> anyhow::Result<Self> {
+        let value = match value {
+            JsonValue::Object(mut object) if object.contains_key("techs") => {
+                object.remove("techs").unwrap_or_default()
+            }
+            value => value,
+        };
+        let nodes: Vec<TechNode> = match value {
+            JsonValue::Array(_) => serde_json::from_value(value)?,
+            value => vec![serde_json::from_value(value)?],
+        };
+        Ok(nodes.into())
+    }
+
+    /// Merge the nodes from a JSON file, or from every JSON file in a directory
+    /// (in file name order), into the tree.
+    pub fn load<P: AsRef<Path>>(&mut self, path: P) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let mut files = Vec::new();
+        if path.is_dir() {
+            files = std::fs::read_dir(path)?
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == "json"))
+                .collect();
+            files.sort();
+        } else {
+            files.push(path.to_path_buf());
+        }
+        for file in files {
+            let data = std::fs::read_to_string(&file)
+                .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", file.display()))?;
+            let value: JsonValue = serde_json::from_str(&data)
+                .map_err(|e| anyhow::anyhow!("Failed to parse {}: {e}", file.display()))?;
+            let tree = TechTree::from_json(value)
+                .map_err(|e| anyhow::anyhow!("Invalid tech tree {}: {e}", file.display()))?;
+            self.merge(tree);
+        }
+        Ok(())
+    }
+
+    /// Check that tech prerequisites name known techs and don't form cycles, and
+    /// that standard effects are well-formed.
+    ///
+    /// Returns every unknown prerequisite, cycle and invalid effect found.
+    pub fn validate(&self) -> Result<(), Vec<TechTreeError>> {
+        let mut errors = Vec::new();
+        for node in self.iter() {
+            for effect in &node.effects {
+                if let Some(Err(e)) = TechEffect::from_effect(effect) {
+                    errors.push(TechTreeError::InvalidEffect {
+                        tech: node.id.clone(),
+                        action: effect.action.clone(),
+                        reason: e.to_string(),
+                    });
+                }
+            }
+            for prereq in tech_prerequisites(node) {
+                if !self.contains(prereq) {
+                    errors.push(TechTreeError::UnknownPrerequisite {
+                        tech: node.id.clone(),
+                        prerequisite: prereq.to_string(),
+                    });
+                }
+            }
+        }
+
+        // Depth-first search; a tech reached again while on the stack closes a cycle
+        let mut done = HashSet::new();
+        for root in self.nodes.keys() {
+            let mut stack: Vec<(&str, usize)> = vec![(root, 0)];
+            let mut on_stack = vec![root.as_str()];
+            while let Some(top) = stack.last_mut() {
+                let id = top.0;
+                let prereq = self.get(id).and_then(|n| tech_prerequisites(n).nth(top.1));
+                top.1 += 1;
+                match prereq {
+                    Some(prereq) if !self.contains(prereq) || done.contains(prereq) => {}
+                    Some(prereq) => {
+                        if let Some(start) = on_stack.iter().position(|s| *s == prereq) {
+                            let mut cycle: Vec<String> =
+                                on_stack[start..].iter().map(|s| s.to_string()).collect();
+                            cycle.push(prereq.to_string());
+                            errors.push(TechTreeError::Cycle(cycle));
+                        } else {
+                            stack.push((prereq, 0));
+                            on_stack.push(prereq);
                         }
                     }
-                    Err(_) => continue,
+                    None => {
+                        done.insert(id);
+                        stack.pop();
+                        on_stack.pop();
+                    }
                 }
-                break;
             }
         }
-        // Return empty vec if file not found or parse error
-        Vec::new()
-    })
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
 }
 
-/// Returns all tech tree nodes.
-pub fn get_tech_tree() -> &'static Vec<TechNode> {
-    get_tech_tree_inner()
+/// IDs of the techs a node requires
+fn tech_prerequisites(node: &TechNode) -> impl Iterator<Item = &str> {
+    node.prerequisites
+        .iter()
+        .filter(|p| p.prereq_type == "tech")
+        .map(|p| p.id.as_str())
+}
+
+/// Returns the world's tech tree.
+pub fn get_tech_tree(world: &World) -> &TechTree {
+    &world.tech_tree
 }
 
 /// Returns a specific tech node by ID, or None if not found.
-pub fn get_tech_node(id: &str) -> Option<&'static TechNode> {
-    get_tech_tree_inner().iter().find(|n| n.id == id)
+pub fn get_tech_node<'a>(world: &'a World, id: &str) -> Option<&'a TechNode> {
+    world.tech_tree.get(id)
 }
 
 /// Reads the TechProgress component for an entity, returning the raw JSON or None.
@@ -149,26 +377,12 @@ pub fn get_completed_techs(world: &World, entity: u32) -> Vec<String> {
 
 /// Checks if a specific tech has been completed by an entity.
 pub fn is_tech_completed(world: &World, entity: u32, tech_id: &str) -> bool {
-    get_tech_progress(world, entity)
-        .and_then(|p| {
-            p.get("completed")
-                .and_then(|c| c.as_object())
-                .map(|m| m.contains_key(tech_id))
-        })
-        .unwrap_or(false)
+    progress_has_completed(get_tech_progress(world, entity).as_ref(), tech_id)
 }
 
 /// Returns the current research queue for an entity.
 pub fn get_research_queue(world: &World, entity: u32) -> Vec<String> {
-    get_tech_progress(world, entity)
-        .and_then(|p| {
-            p.get("queue").and_then(|q| q.as_array()).map(|a| {
-                a.iter()
-                    .filter_map(|v| v.as_str().map(String::from))
-                    .collect()
-            })
-        })
-        .unwrap_or_default()
+    progress_queue(get_tech_progress(world, entity).as_ref())
 }
 
 /// Returns the queue progress map for an entity (tech_id → accumulated points).
@@ -178,18 +392,81 @@ pub fn get_research_queue_progress(world: &World, entity: u32) -> JsonValue {
         .unwrap_or(json!({}))
 }
 
+// ── Research backends ───────────────────────────────────────────────────
+
+/// World operations needed by the research actions. Both World and WasmWorld
+/// implement this, so every bridge queues, charges and refunds research the
+/// same way.
+pub trait ResearchOps: ResourceReservationOps {
+    /// Returns the world's tech tree.
+    fn research_tech_tree(&self) -> &TechTree;
+    /// Fires an event with a JSON payload.
+    fn send_research_event(&mut self, event_type: &str, payload: JsonValue) -> Result<(), String>;
+}
+
+impl ResearchOps for World {
+    fn research_tech_tree(&self) -> &TechTree {
+        &self.tech_tree
+    }
+
+    fn send_research_event(&mut self, event_type: &str, payload: JsonValue) -> Result<(), String> {
+        self.send_event(event_type, payload)
+    }
+}
+
+/// Blanket impl so that smart pointers like `RefMut<'_, World>` work with the trait.
+impl<W: Deref<Target = World> + DerefMut> ResearchOps for W {
+    fn research_tech_tree(&self) -> &TechTree {
+        let w: &World = self;
+        &w.tech_tree
+    }
+
+    fn send_research_event(&mut self, event_type: &str, payload: JsonValue) -> Result<(), String> {
+        let w: &mut World = self;
+        w.send_event(event_type, payload)
+    }
+}
+
+/// Whether a TechProgress value lists `tech_id` as completed.
+fn progress_has_completed(progress: Option<&JsonValue>, tech_id: &str) -> bool {
+    progress
+        .and_then(|p| p.get("completed"))
+        .and_then(|c| c.as_object())
+        .is_some_and(|m| m.contains_key(tech_id))
+}
+
+/// The research queue of a TechProgress value.
+fn progress_queue(progress: Option<&JsonValue>) -> Vec<String> {
+    progress
+        .and_then(|p| p.get("queue"))
+        .and_then(|q| q.as_array())
+        .map(|a| {
+            a.iter()
+                .filter_map(|v| v.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 // ── Prerequisite checking ────────────────────────────────────────────────
 
 /// Checks if all prerequisites for a tech are satisfied by the given entity.
 ///
 /// Returns `Ok(true)` if all prerequisites are met. Returns `Err(reason)` with
 /// a human-readable reason if any prerequisite is not satisfied.
-fn check_prerequisites(world: &World, entity: u32, node: &TechNode) -> Result<bool, String> {
+fn check_prerequisites<W: ResearchOps + ?Sized>(
+    world: &W,
+    entity: u32,
+    node: &TechNode,
+) -> Result<bool, String> {
+    let progress = world.get_component_value(entity, "TechProgress");
     for prereq in &node.prerequisites {
         match prereq.prereq_type.as_str() {
             "tech" => {
-                if !is_tech_completed(world, entity, &prereq.id) {
-                    let name = get_tech_node(&prereq.id)
+                if !progress_has_completed(progress.as_ref(), &prereq.id) {
+                    let name = world
+                        .research_tech_tree()
+                        .get(&prereq.id)
                         .map(|n| n.name.as_str())
                         .unwrap_or(&prereq.id);
                     return Err(format!("Requires tech '{}' ({})", prereq.id, name));
@@ -198,7 +475,7 @@ fn check_prerequisites(world: &World, entity: u32, node: &TechNode) -> Result<bo
             "skill" => {
                 let required_level = prereq.level.unwrap_or(1.0);
                 let current_level = world
-                    .get_component(entity, "SkillLevels")
+                    .get_component_value(entity, "SkillLevels")
                     .and_then(|sl| {
                         sl.get("skill_levels")
                             .and_then(|slm| slm.as_object())
@@ -228,7 +505,11 @@ fn check_prerequisites(world: &World, entity: u32, node: &TechNode) -> Result<bo
 /// A cycle occurs when the target tech depends (directly or transitively)
 /// on a tech already in the queue, and that queued tech transitively depends
 /// back on the target tech. We walk forward from the target's prerequisites.
-fn would_create_cycle(node: &TechNode, queue: &[String]) -> bool {
+fn would_create_cycle<W: ResearchOps + ?Sized>(
+    world: &W,
+    node: &TechNode,
+    queue: &[String],
+) -> bool {
     // Collect all tech IDs in the queue as Strings for HashSet lookup
     let queued_ids: std::collections::HashSet<String> = queue.iter().cloned().collect();
 
@@ -244,7 +525,7 @@ fn would_create_cycle(node: &TechNode, queue: &[String]) -> bool {
         if queued_ids.contains(&current) {
             return true;
         }
-        if let Some(current_node) = get_tech_node(&current) {
+        if let Some(current_node) = world.research_tech_tree().get(&current) {
             for prereq in &current_node.prerequisites {
                 if prereq.prereq_type == "tech" {
                     stack.push(prereq.id.clone());
@@ -262,16 +543,24 @@ fn would_create_cycle(node: &TechNode, queue: &[String]) -> bool {
 ///
 /// Returns `Ok(true)` if the tech can be researched, or `Err(reason)` with a
 /// human-readable reason if it cannot.
-pub fn can_research_tech(world: &World, entity: u32, tech_id: &str) -> Result<bool, String> {
-    let node = get_tech_node(tech_id).ok_or_else(|| format!("Unknown tech '{}'", tech_id))?;
+pub fn can_research_tech<W: ResearchOps + ?Sized>(
+    world: &W,
+    entity: u32,
+    tech_id: &str,
+) -> Result<bool, String> {
+    let node = world
+        .research_tech_tree()
+        .get(tech_id)
+        .ok_or_else(|| format!("Unknown tech '{}'", tech_id))?;
+    let progress = world.get_component_value(entity, "TechProgress");
 
     // Already completed?
-    if is_tech_completed(world, entity, tech_id) {
+    if progress_has_completed(progress.as_ref(), tech_id) {
         return Err(format!("Tech '{}' already completed", tech_id));
     }
 
     // Already in queue?
-    let queue = get_research_queue(world, entity);
+    let queue = progress_queue(progress.as_ref());
     if queue.iter().any(|t| t == tech_id) {
         return Err(format!("Tech '{}' already in research queue", tech_id));
     }
@@ -279,64 +568,62 @@ pub fn can_research_tech(world: &World, entity: u32, tech_id: &str) -> Result<bo
     // Check prerequisites
     check_prerequisites(world, entity, node)?;
 
+    // Check the resource cost
+    for (kind, amount) in &node.resource_cost {
+        let available = stockpile_amount(world, entity, kind);
+        if available < *amount {
+            return Err(format!("Requires {} {} (have {})", amount, kind, available));
+        }
+    }
+
     Ok(true)
 }
 
 /// Adds a tech to the research queue if prerequisites are met and it's not
 /// already completed or queued. Fires a `research_started` event on success.
-pub fn research_tech(world: &mut World, entity: u32, tech_id: &str) -> Result<(), String> {
-    let node = get_tech_node(tech_id).ok_or_else(|| format!("Unknown tech '{}'", tech_id))?;
+pub fn research_tech<W: ResearchOps + ?Sized>(
+    world: &mut W,
+    entity: u32,
+    tech_id: &str,
+) -> Result<(), String> {
+    let node = world
+        .research_tech_tree()
+        .get(tech_id)
+        .cloned()
+        .ok_or_else(|| format!("Unknown tech '{}'", tech_id))?;
 
     // Validate via can_research_tech
     can_research_tech(world, entity, tech_id)?;
 
     // Cycle detection: check that queuing this tech does not create a cycle
-    let queue = get_research_queue(world, entity);
-    if would_create_cycle(node, &queue) {
+    let queue = progress_queue(world.get_component_value(entity, "TechProgress").as_ref());
+    if would_create_cycle(world, &node, &queue) {
         return Err(format!(
             "Researching '{}' would create a dependency cycle",
             tech_id
         ));
     }
 
-    // Read or create TechProgress component
-    let progress = get_or_create_tech_progress(world, entity);
+    // Pay the resource cost
+    transfer_resource_cost(world, entity, &node, false)?;
 
-    let completed = progress
-        .get("completed")
-        .and_then(|c| c.as_object())
-        .cloned()
-        .unwrap_or_default();
-    let mut queue: Vec<JsonValue> = progress
-        .get("queue")
-        .and_then(|q| q.as_array())
-        .cloned()
-        .unwrap_or_default();
-    let mut queue_progress = progress
-        .get("queue_progress")
-        .and_then(|qp| qp.as_object())
-        .cloned()
-        .unwrap_or_default();
-    let research_points = progress
-        .get("research_points")
-        .and_then(|rp| rp.as_f64())
-        .unwrap_or(0.0);
-
-    // Add to queue
-    queue.push(json!(tech_id));
-    queue_progress.insert(tech_id.to_string(), json!(0.0));
-
-    let updated = json!({
-        "completed": JsonValue::Object(completed),
-        "queue": JsonValue::Array(queue),
-        "queue_progress": JsonValue::Object(queue_progress),
-        "research_points": research_points,
-    });
+    // Read or create TechProgress component, then add to queue
+    let mut progress = get_or_create_tech_progress(world, entity);
+    match progress["queue"].as_array_mut() {
+        Some(queue) => queue.push(json!(tech_id)),
+        None => progress["queue"] = json!([tech_id]),
+    }
+    match progress["queue_progress"].as_object_mut() {
+        Some(queue_progress) => {
+            queue_progress.insert(tech_id.to_string(), json!(0.0));
+        }
+        None => progress["queue_progress"] = json!({ tech_id: 0.0 }),
+    }
 
-    world.set_component(entity, "TechProgress", updated)?;
+    world.set_component_value(entity, "TechProgress", progress)?;
 
     // Fire research_started event
-    world.send_event(
+    world.send_research_event(
         "research_started",
         json!({
             "entity": entity,
@@ -348,87 +635,74 @@ pub fn research_tech(world: &mut World, entity: u32, tech_id: &str) -> Result<()
     Ok(())
 }
 
-/// Removes a tech from the research queue. Fires a `research_cancelled` event.
-pub fn cancel_research(world: &mut World, entity: u32, tech_id: &str) -> Result<(), String> {
-    let progress = get_or_create_tech_progress(world, entity);
-
-    let completed = progress
-        .get("completed")
-        .and_then(|c| c.as_object())
-        .cloned()
-        .unwrap_or_default();
-    let mut queue: Vec<JsonValue> = progress
-        .get("queue")
-        .and_then(|q| q.as_array())
-        .cloned()
-        .unwrap_or_default();
-    let mut queue_progress = progress
-        .get("queue_progress")
-        .and_then(|qp| qp.as_object())
-        .cloned()
-        .unwrap_or_default();
-    let research_points = progress
-        .get("research_points")
-        .and_then(|rp| rp.as_f64())
-        .unwrap_or(0.0);
+/// Removes a tech from the research queue and refunds its resource cost.
+/// Fires a `research_cancelled` event.
+pub fn cancel_research<W: ResearchOps + ?Sized>(
+    world: &mut W,
+    entity: u32,
+    tech_id: &str,
+) -> Result<(), String> {
+    let mut progress = get_or_create_tech_progress(world, entity);
 
     // Find and remove from queue
-    let pos = queue.iter().position(|v| v.as_str() == Some(tech_id));
+    let pos = progress["queue"]
+        .as_array()
+        .and_then(|q| q.iter().position(|v| v.as_str() == Some(tech_id)));
     match pos {
         Some(idx) => {
-            queue.remove(idx);
-            queue_progress.remove(tech_id);
+            if let Some(queue) = progress["queue"].as_array_mut() {
+                queue.remove(idx);
+            }
+            if let Some(queue_progress) = progress["queue_progress"].as_object_mut() {
+                queue_progress.remove(tech_id);
+            }
         }
         None => {
             return Err(format!("Tech '{}' is not in the research queue", tech_id));
         }
     }
 
-    let updated = json!({
-        "completed": JsonValue::Object(completed),
-        "queue": JsonValue::Array(queue),
-        "queue_progress": JsonValue::Object(queue_progress),
-        "research_points": research_points,
-    });
+    world.set_component_value(entity, "TechProgress", progress)?;
 
-    world.set_component(entity, "TechProgress", updated)?;
+    // Refund the resource cost
+    let node = world.research_tech_tree().get(tech_id).cloned();
+    if let Some(node) = &node {
+        transfer_resource_cost(world, entity, node, true)?;
+    }
 
     // Fire research_cancelled event
-    let node = get_tech_node(tech_id);
-    world.send_event(
+    let tech_name = node.map_or_else(|| tech_id.to_string(), |n| n.name);
+    world.send_research_event(
         "research_cancelled",
         json!({
             "entity": entity,
             "tech_id": tech_id,
-            "tech_name": node.map(|n| n.name.as_str()).unwrap_or(tech_id),
+            "tech_name": tech_name,
         }),
     )?;
 
     Ok(())
 }
 
-/// Empties the research queue and clears queue progress.
-pub fn clear_research_queue(world: &mut World, entity: u32) -> Result<(), String> {
-    let progress = get_or_create_tech_progress(world, entity);
+/// Empties the research queue, clears queue progress and refunds the resource
+/// costs of the queued techs.
+pub fn clear_research_queue<W: ResearchOps + ?Sized>(
+    world: &mut W,
+    entity: u32,
+) -> Result<(), String> {
+    let mut progress = get_or_create_tech_progress(world, entity);
+    let queue = progress_queue(Some(&progress));
 
-    let completed = progress
-        .get("completed")
-        .and_then(|c| c.as_object())
-        .cloned()
-        .unwrap_or_default();
-    let research_points = progress
-        .get("research_points")
-        .and_then(|rp| rp.as_f64())
-        .unwrap_or(0.0);
+    progress["queue"] = json!([]);
+    progress["queue_progress"] = json!({});
 
-    let updated = json!({
-        "completed": JsonValue::Object(completed),
-        "queue": JsonValue::Array(vec![]),
-        "queue_progress": json!({}),
-        "research_points": research_points,
-    });
+    world.set_component_value(entity, "TechProgress", progress)?;
 
-    world.set_component(entity, "TechProgress", updated)?;
+    for tech_id in queue {
+        if let Some(node) = world.research_tech_tree().get(&tech_id).cloned() {
+            transfer_resource_cost(world, entity, &node, true)?;
+        }
+    }
 
     Ok(())
 }
@@ -437,10 +711,12 @@ pub fn clear_research_queue(world: &mut World, entity: u32) -> Result<(), String
 
 /// Returns the TechProgress component value for an entity, creating a default
 /// one with empty fields if it doesn't exist yet.
-fn get_or_create_tech_progress(world: &World, entity: u32) -> JsonValue {
+pub(crate) fn get_or_create_tech_progress<W: ResearchOps + ?Sized>(
+    world: &W,
+    entity: u32,
+) -> JsonValue {
     world
-        .get_component(entity, "TechProgress")
-        .cloned()
+        .get_component_value(entity, "TechProgress")
         .unwrap_or_else(|| {
             json!({
                 "completed": {},
@@ -450,3 +726,39 @@ fn get_or_create_tech_progress(world: &World, entity: u32) -> JsonValue {
             })
         })
 }
+
+/// Amount of a resource in an entity's `Stockpile` (0 if it has none).
+fn stockpile_amount<W: ResearchOps + ?Sized>(world: &W, entity: u32, kind: &str) -> i64 {
+    world
+        .get_component_value(entity, "Stockpile")
+        .as_ref()
+        .and_then(|s| s.get("resources"))
+        .and_then(|r| r.get(kind))
+        .and_then(|v| v.as_i64())
+        .unwrap_or(0)
+}
+
+/// Takes a tech's resource cost from the entity's `Stockpile`, or gives it
+/// back if `refund` is set.
+fn transfer_resource_cost<W: ResearchOps + ?Sized>(
+    world: &mut W,
+    entity: u32,
+    node: &TechNode,
+    refund: bool,
+) -> Result<(), String> {
+    if node.resource_cost.is_empty() {
+        return Ok(());
+    }
+    let mut stockpile = world
+        .get_component_value(entity, "Stockpile")
+        .unwrap_or_else(|| json!({ "resources": {} }));
+    if !stockpile["resources"].is_object() {
+        stockpile["resources"] = json!({});
+    }
+    for (kind, amount) in &node.resource_cost {
+        let current = stockpile["resources"][kind].as_i64().unwrap_or(0);
+        let delta = if refund { *amount } else { -amount };
+        stockpile["resources"][kind] = json!(current + delta);
+    }
+    world.set_component_value(entity, "Stockpile", stockpile)
+}
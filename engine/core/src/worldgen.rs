@@ -139,6 +139,16 @@ impl ThreadSafeWorldgenRegistry {
             .collect()
     }
 
+    /// Remove every plugin registered under `name`. Returns true if any was removed.
+    pub fn unregister(&mut self, name: &str) -> bool {
+        let before = self.plugins.len();
+        self.plugins.retain(|p| match p {
+            ThreadSafeWorldgenPlugin::CAbi { name: n, .. } => n != name,
+            ThreadSafeWorldgenPlugin::ThreadSafeScripting { name: n, .. } => n != name,
+        });
+        self.plugins.len() != before
+    }
+
     /// Register a validator
     pub fn register_validator<F>(&mut self, f: F)
     where
@@ -240,6 +250,17 @@ impl WorldgenRegistry {
             .collect()
     }
 
+    /// Remove every plugin registered under `name`. Returns true if any was removed.
+    pub fn unregister(&mut self, name: &str) -> bool {
+        let before = self.plugins.len();
+        self.plugins.retain(|p| match p {
+            WorldgenPlugin::CAbi { name: n, .. } => n != name,
+            WorldgenPlugin::ThreadSafeScripting { name: n, .. } => n != name,
+            WorldgenPlugin::Scripting { name: n, .. } => n != name,
+        });
+        self.plugins.len() != before
+    }
+
     /// Registers a validator
     pub fn register_validator<F>(&mut self, f: F)
     where
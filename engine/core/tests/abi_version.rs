@@ -33,10 +33,7 @@ fn plugin_path(name: &str) -> PathBuf {
 }
 
 fn make_engine_api() -> engine_core::plugins::EngineApi {
-    engine_core::plugins::EngineApi {
-        spawn_entity: engine_core::plugins::ffi_spawn_entity,
-        set_component: engine_core::plugins::ffi_set_component,
-    }
+    engine_core::plugins::EngineApi::new()
 }
 
 fn make_world() -> *mut c_void {
@@ -137,14 +134,14 @@ fn test_mismatched_version_rejected() {
         engine_core::plugins::load_plugin(&path, &mut api, world_ptr)
             .expect_err("test_abi_mismatch should be rejected")
     };
-    // AC007: Error message contains path, expected=1, actual=999
+    // AC007: Error message contains path, expected=2, actual=999
     assert!(
         err.contains("ABI version mismatch"),
         "Error should mention 'ABI version mismatch', got: {err}"
     );
     assert!(
-        err.contains("expected 1"),
-        "Error should contain 'expected 1', got: {err}"
+        err.contains("expected 2"),
+        "Error should contain 'expected 2', got: {err}"
     );
     assert!(
         err.contains("got 999"),
@@ -167,7 +164,7 @@ fn test_zero_version_rejected() {
             .expect_err("test_abi_zero should be rejected")
     };
     assert!(err.contains("ABI version mismatch"), "Error: {err}");
-    assert!(err.contains("expected 1"), "Error: {err}");
+    assert!(err.contains("expected 2"), "Error: {err}");
     assert!(err.contains("got 0"), "Error: {err}");
 }
 
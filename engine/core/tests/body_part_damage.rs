@@ -1,6 +1,7 @@
 #[path = "helpers/world.rs"]
 mod world_helper;
 
+use engine_core::ecs::Health;
 use engine_core::systems::body_equipment_sync::BodyEquipmentSyncSystem;
 use engine_core::systems::body_part_damage::BodyPartDamageSystem;
 use serde_json::json;
@@ -366,3 +367,28 @@ fn test_empty_damages_array_noop() {
     let body = world.get_component(eid, "Body").unwrap();
     assert_eq!(body["parts"][0]["hp"], json!(50.0));
 }
+
+#[test]
+fn test_damage_updates_typed_health() {
+    let mut world = world_helper::make_test_world();
+    world.current_mode = "roguelike".to_string();
+    world.register_typed_component::<Health>("Health").unwrap();
+    register_system(&mut world);
+
+    let eid = world.spawn_entity();
+    world.set_component(eid, "Body", humanoid_body()).unwrap();
+    world
+        .set_component(eid, "Health", json!({"current": 85.0, "max": 85.0}))
+        .unwrap();
+    let before = world.change_tick();
+
+    world.damage_entity_part(eid, "left hand", 5.0);
+    world.run_system("BodyPartDamageSystem").unwrap();
+
+    let health = world
+        .typed_component::<Health>("Health")
+        .and_then(|healths| healths.get(eid))
+        .unwrap();
+    assert_eq!(health.current, 80.0);
+    assert!(world.component_changed_since(eid, "Health", before));
+}
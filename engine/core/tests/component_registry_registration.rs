@@ -377,7 +377,7 @@ fn test_update_external_schema_with_data_migration() {
         modes: vec!["colony".to_string()],
     };
     registry
-        .update_external_schema_with_migration(schema_v2, &mut component_data, migration)
+        .update_external_schema_with_migration(schema_v2, 1, &mut component_data, migration)
         .unwrap();
 
     // Data should be migrated
@@ -1,6 +1,7 @@
 #[path = "helpers/world.rs"]
 mod world_helper;
 
+use engine_core::ecs::Health;
 use engine_core::systems::death_decay::{ProcessDeaths, ProcessDecay};
 use serde_json::json;
 
@@ -65,3 +66,27 @@ fn test_decay_removes_entity_after_time() {
     world.run_system("ProcessDecay").unwrap();
     assert!(world.get_component(id, "Decay").is_none());
 }
+
+#[test]
+fn test_death_processes_typed_health() {
+    let mut world = world_helper::make_test_world();
+    world.current_mode = "colony".to_string();
+    world.register_typed_component::<Health>("Health").unwrap();
+
+    let dead = world.spawn_entity();
+    let alive = world.spawn_entity();
+    world
+        .set_component(dead, "Health", json!({ "current": 0.0, "max": 10.0 }))
+        .unwrap();
+    world
+        .set_component(alive, "Health", json!({ "current": 3.0, "max": 10.0 }))
+        .unwrap();
+
+    world.register_system(ProcessDeaths);
+    world.run_system("ProcessDeaths").unwrap();
+
+    assert!(world.get_component(dead, "Health").is_none());
+    assert!(world.get_component(dead, "Corpse").is_some());
+    assert!(world.get_component(alive, "Health").is_some());
+    assert!(world.get_component(alive, "Corpse").is_none());
+}
@@ -324,3 +324,38 @@ fn test_negative_priority_sorted_lowest() {
     assert_eq!(job_high["state"], "complete");
     assert_eq!(job_low["state"], "complete");
 }
+
+#[test]
+fn test_world_recipes_override_system_recipes() {
+    let mut world = world_helper::make_test_world();
+    world.current_mode = "colony".to_string();
+
+    // A mod-provided recipe replaces the one the system was built with
+    world.recipes.insert(
+        "wood_plank".into(),
+        json!({
+            "name": "wood_plank",
+            "inputs": [{"kind": "wood", "amount": 1}],
+            "outputs": [{"kind": "plank", "amount": 10}],
+            "duration": 1
+        }),
+    );
+    let workshop = world.spawn_entity();
+    world
+        .set_component(workshop, "Stockpile", json!({"resources": { "wood": 3 }}))
+        .unwrap();
+    world
+        .set_component(
+            workshop,
+            "ProductionJob",
+            json!({"recipe": "wood_plank", "progress": 0, "state": "pending"}),
+        )
+        .unwrap();
+
+    let recipe_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap() + "/../assets/recipes";
+    let mut econ_system = EconomicSystem::with_recipes(load_recipes_from_dir(&recipe_dir));
+    econ_system.run(&mut world);
+
+    let stockpile = world.get_component(workshop, "Stockpile").unwrap();
+    assert_eq!(stockpile["resources"]["plank"], 10);
+}
@@ -227,7 +227,7 @@ fn test_update_external_schema_with_migration() {
             .into_iter()
             .collect();
 
-    reg.update_external_schema_with_migration(schema, &mut data, |v| {
+    reg.update_external_schema_with_migration(schema, 1, &mut data, |v| {
         let mut map = v.as_object().unwrap().clone();
         map.insert("migrated".into(), json!(true));
         serde_json::Value::Object(map)
@@ -0,0 +1,180 @@
+#[path = "helpers/world.rs"]
+mod world_helper;
+use world_helper::make_test_world;
+
+#[path = "helpers/world_io.rs"]
+mod world_io_helper;
+use world_io_helper::save_and_load_roundtrip;
+
+use engine_core::ecs::entity::{Entity, EntityAllocator, MAX_GENERATION, MAX_INDEX};
+use engine_core::ecs::world::World;
+use engine_core::ecs::world::wasm::WasmWorld;
+use serde_json::json;
+
+#[test]
+fn test_fresh_ids_are_sequential() {
+    let mut world = make_test_world();
+    assert_eq!(world.spawn_entity(), 1);
+    assert_eq!(world.spawn_entity(), 2);
+    assert_eq!(Entity::from_id(2), Entity::new(2, 0));
+}
+
+#[test]
+fn test_reused_slot_gets_new_generation() {
+    let mut world = make_test_world();
+    let old = world.spawn_entity();
+    world.despawn_entity(old);
+    let new = world.spawn_entity();
+
+    assert_ne!(old, new);
+    assert_eq!(Entity::from_id(new).index, Entity::from_id(old).index);
+    assert_eq!(Entity::from_id(new).generation, 1);
+    assert!(world.is_stale_entity(old));
+    assert!(!world.is_stale_entity(new));
+}
+
+#[test]
+fn test_stale_handles_are_rejected() {
+    let mut world = make_test_world();
+    let old = world.spawn_entity();
+    world
+        .set_component(old, "Health", json!({ "current": 5, "max": 10 }))
+        .unwrap();
+    world.despawn_entity(old);
+
+    assert!(world.get_component(old, "Health").is_none());
+    let err = world
+        .set_component(old, "Health", json!({ "current": 5, "max": 10 }))
+        .unwrap_err();
+    assert!(err.contains("stale"), "{err}");
+    assert!(world.remove_component(old, "Health").is_err());
+
+    // Despawning through a stale handle must not touch the slot's new occupant.
+    let new = world.spawn_entity();
+    world
+        .set_component(new, "Health", json!({ "current": 7, "max": 10 }))
+        .unwrap();
+    world.despawn_entity(old);
+    assert!(world.get_entities().contains(&new));
+    assert!(world.has_component(new, "Health"));
+    assert!(!world.has_component(old, "Health"));
+}
+
+#[test]
+fn test_stale_handles_cannot_move_or_damage() {
+    let mut world = make_test_world();
+    let old = world.spawn_entity();
+    world.despawn_entity(old);
+    let new = world.spawn_entity();
+    world
+        .set_component(
+            new,
+            "Position",
+            json!({ "pos": { "Square": { "x": 1, "y": 1, "z": 0 } } }),
+        )
+        .unwrap();
+    world
+        .set_component(new, "Health", json!({ "current": 5, "max": 10 }))
+        .unwrap();
+    let position = world.get_component(new, "Position").cloned();
+
+    world.move_entity(old, 3.0, 3.0);
+    world.damage_entity(old, 4.0);
+    world.damage_entity_part(old, "head", 4.0);
+
+    assert_eq!(world.get_component(new, "Position").cloned(), position);
+    assert_eq!(
+        world.get_component(new, "Health").unwrap()["current"],
+        json!(5)
+    );
+}
+
+#[test]
+fn test_ids_not_from_the_allocator_are_not_stale() {
+    let mut world = make_test_world();
+    world
+        .set_component(42, "Health", json!({ "current": 1, "max": 1 }))
+        .unwrap();
+    assert!(!world.is_stale_entity(42));
+    assert!(world.get_component(42, "Health").is_some());
+}
+
+#[test]
+fn test_generations_survive_save_and_load() {
+    let mut world = make_test_world();
+    let old = world.spawn_entity();
+    world.despawn_entity(old);
+    let registry = world.registry.clone();
+    let mut loaded = save_and_load_roundtrip(&world, registry);
+
+    assert!(loaded.is_stale_entity(old));
+    let new = loaded.spawn_entity();
+    assert_eq!(new, Entity::new(1, 1).id());
+}
+
+#[test]
+fn test_legacy_saves_get_an_allocator() {
+    let mut world = make_test_world();
+    let a = world.spawn_entity();
+    let b = world.spawn_entity();
+    world.despawn_entity(a);
+    let registry = world.registry.clone();
+
+    let mut save = world.save_to_value().unwrap();
+    let obj = save.as_object_mut().unwrap();
+    obj.remove("save_header");
+    obj.remove("entity_allocator");
+    obj.insert("next_id".to_string(), json!(3));
+    let (mut loaded, report) = World::load_from_value_with_report(save, registry).unwrap();
+
+    assert!(
+        report
+            .applied
+            .contains(&"generational_entity_ids".to_string())
+    );
+    assert!(loaded.is_stale_entity(a));
+    assert!(!loaded.is_stale_entity(b));
+    assert_eq!(loaded.spawn_entity(), 3);
+}
+
+#[test]
+fn test_legacy_ids_must_fit_the_index_bits() {
+    let allocator = EntityAllocator::from_legacy(MAX_INDEX + 1, &[MAX_INDEX]).unwrap();
+    assert!(!allocator.is_stale(MAX_INDEX));
+    assert!(EntityAllocator::from_legacy(MAX_INDEX + 2, &[]).is_err());
+    assert!(EntityAllocator::from_legacy(2, &[MAX_INDEX + 1]).is_err());
+}
+
+#[test]
+fn test_exhausted_slot_is_retired() {
+    let mut allocator = EntityAllocator::new();
+    let mut id = allocator.allocate();
+    for _ in 0..MAX_GENERATION {
+        assert!(allocator.free(id));
+        id = allocator.allocate();
+        assert_eq!(Entity::from_id(id).index, 1);
+    }
+    assert_eq!(Entity::from_id(id).generation, MAX_GENERATION);
+    allocator.free(id);
+    assert!(allocator.is_stale(id));
+    assert_eq!(Entity::from_id(allocator.allocate()).index, 2);
+}
+
+#[test]
+fn test_wasm_world_rejects_stale_handles() {
+    let mut world = WasmWorld::new();
+    let old = world.spawn_entity();
+    world.despawn_entity(old);
+    let new = world.spawn_entity();
+    assert_ne!(old, new);
+    assert!(world.is_stale_entity(old));
+    assert!(
+        world
+            .set_component(old, "Health", r#"{"current":1}"#)
+            .is_err()
+    );
+    world
+        .set_component(new, "Health", r#"{"current":1}"#)
+        .unwrap();
+    assert!(world.get_component(old, "Health").is_none());
+}
@@ -0,0 +1,258 @@
+#[path = "helpers/world.rs"]
+mod world_helper;
+
+use engine_core::ecs::system::System;
+use engine_core::ecs::world::wasm::WasmWorld;
+use engine_core::map::flow_field::{DEFAULT_FLEE_FACTOR, FlowFieldCache};
+use engine_core::map::{CellKey, DijkstraMap, HexGridMap, Map, MovementProfile, SquareGridMap};
+use engine_core::systems::job::movement_ops::assign_move_path_down_gradient;
+use engine_core::systems::movement_system::MovementSystem;
+use serde_json::json;
+use std::sync::Arc;
+
+fn square(x: i32, y: i32) -> CellKey {
+    CellKey::Square { x, y, z: 0 }
+}
+
+/// A 4-connected 10x10 grid with a wall down x = 5 open at y = 9, and mud
+/// (cost 3) at (2, 2).
+fn walled_grid() -> Map {
+    let mut grid = SquareGridMap::new();
+    for x in 0..10 {
+        for y in 0..10 {
+            grid.add_cell(x, y, 0);
+            for (nx, ny) in [(x + 1, y), (x, y + 1), (x - 1, y), (x, y - 1)] {
+                if (0..10).contains(&nx) && (0..10).contains(&ny) {
+                    grid.add_neighbor((x, y, 0), (nx, ny, 0));
+                }
+            }
+        }
+    }
+    let mut map = Map::new(Box::new(grid));
+    for y in 0..9 {
+        map.set_cell_metadata(&square(5, y), json!({ "walkable": false }));
+    }
+    map.set_cell_metadata(&square(2, 2), json!({ "cost": 3.0 }));
+    map
+}
+
+#[test]
+fn test_distances_match_shortest_paths_to_nearest_goal() {
+    let map = walled_grid();
+    let walker = MovementProfile::walker();
+    let goals = [square(0, 0), square(9, 0)];
+    let field = DijkstraMap::build(map.topology.as_ref(), &goals, &walker);
+
+    assert_eq!(field.distance(&square(0, 0)), Some(0.0));
+    assert_eq!(field.distance(&square(5, 0)), None, "Walls get no value");
+    for cell in [square(3, 3), square(2, 3), square(6, 8), square(4, 9)] {
+        let nearest = goals
+            .iter()
+            .filter_map(|goal| map.find_path(&cell, goal))
+            .map(|result| result.total_cost)
+            .fold(f32::INFINITY, f32::min);
+        assert_eq!(field.distance(&cell), Some(nearest), "{cell:?}");
+    }
+    assert_eq!(field.len(), 91);
+
+    let path = field
+        .descend(&square(4, 5), |cell| map.neighbors(cell))
+        .unwrap();
+    assert_eq!(path.first(), Some(&square(4, 5)));
+    assert_eq!(path.last(), Some(&square(0, 0)));
+    assert!(!path.contains(&square(2, 2)), "Mud is avoided");
+    assert_eq!(path.len(), 10);
+    assert_eq!(
+        field.next_step(&square(0, 0), |cell| map.neighbors(cell)),
+        None
+    );
+}
+
+#[test]
+fn test_flee_map_leads_away_from_threats() {
+    let map = walled_grid();
+    let walker = MovementProfile::walker();
+    let towards = DijkstraMap::build(map.topology.as_ref(), &[square(0, 0)], &walker);
+    let flee = towards.flee(
+        DEFAULT_FLEE_FACTOR,
+        |cell| map.neighbors(cell),
+        |cell| walker.cost(map.get_cell_metadata(cell)),
+    );
+    assert_eq!(flee.len(), towards.len());
+
+    let mut cell = square(1, 1);
+    for _ in 0..20 {
+        let Some(next) = flee.next_step(&cell, |c| map.neighbors(c)) else {
+            break;
+        };
+        assert!(flee.distance(&next) < flee.distance(&cell));
+        cell = next;
+    }
+    assert!(
+        towards.distance(&cell).unwrap() > 10.0,
+        "Fled to {cell:?}, which is still close"
+    );
+}
+
+#[test]
+fn test_seeded_desire_maps_prefer_lower_seeds() {
+    let map = walled_grid();
+    let walker = MovementProfile::walker();
+    let field = DijkstraMap::from_seeds(
+        [(square(0, 4), 0.0), (square(4, 4), -6.0)],
+        |cell| map.neighbors(cell),
+        |cell| walker.cost(map.get_cell_metadata(cell)),
+    );
+    assert_eq!(
+        field.next_step(&square(1, 4), |cell| map.neighbors(cell)),
+        Some(square(2, 4)),
+        "The stronger desire wins despite being further away"
+    );
+}
+
+#[test]
+fn test_maps_are_cached_until_the_map_changes() {
+    let mut map = walled_grid();
+    let walker = MovementProfile::walker();
+    let first = map.dijkstra_map(&[square(0, 0), square(9, 0)], &walker);
+    let again = map.dijkstra_map(&[square(9, 0), square(0, 0), square(9, 0)], &walker);
+    assert!(Arc::ptr_eq(&first, &again), "Goal order does not matter");
+    assert!(!Arc::ptr_eq(
+        &first,
+        &map.dijkstra_map(&[square(0, 0)], &MovementProfile::flyer())
+    ));
+    let flee = map.flee_map(&[square(0, 0)], &walker, 1.5);
+    assert!(Arc::ptr_eq(
+        &flee,
+        &map.flee_map(&[square(0, 0)], &walker, 1.5)
+    ));
+
+    map.set_cell_metadata(&square(5, 0), json!({ "walkable": true }));
+    let updated = map.dijkstra_map(&[square(0, 0), square(9, 0)], &walker);
+    assert!(!Arc::ptr_eq(&first, &updated));
+    assert_eq!(updated.distance(&square(5, 0)), Some(4.0));
+
+    let mut cache = FlowFieldCache::default();
+    let mut profile = MovementProfile::new("mule");
+    cache.insert(1, &profile, &[square(0, 0)], None, first.clone());
+    assert!(cache.get(1, &profile, &[square(0, 0)], None).is_some());
+    profile.ignore_cost = true;
+    assert!(cache.get(1, &profile, &[square(0, 0)], None).is_none());
+    cache.insert(1, &profile, &[square(0, 0)], None, first);
+    assert!(cache.get(2, &profile, &[square(0, 0)], None).is_none());
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn test_hex_maps_have_flow_fields() {
+    let mut hexes = HexGridMap::new();
+    for q in 0..4 {
+        hexes.add_cell(q, 0, 0);
+        if q > 0 {
+            hexes.add_neighbor((q - 1, 0, 0), (q, 0, 0));
+        }
+    }
+    let map = Map::new(Box::new(hexes));
+    let (start, end) = (
+        CellKey::Hex { q: 0, r: 0, z: 0 },
+        CellKey::Hex { q: 3, r: 0, z: 0 },
+    );
+    let field = map.dijkstra_map(std::slice::from_ref(&end), &MovementProfile::walker());
+    assert_eq!(field.distance(&start), Some(3.0));
+    let field = map.dijkstra_map(std::slice::from_ref(&start), &MovementProfile::walker());
+    assert_eq!(
+        field.distance(&end),
+        None,
+        "Links only lead away from the start"
+    );
+    assert!(map.dijkstra_map(&[], &MovementProfile::walker()).is_empty());
+}
+
+#[test]
+fn test_agents_step_down_shared_flow_fields() {
+    let mut world = world_helper::make_test_world();
+    world.map = Some(walled_grid());
+    let goals = [square(9, 0)];
+    let hauler = world.spawn_entity();
+    world
+        .set_component(
+            hauler,
+            "Agent",
+            json!({ "entity_id": hauler, "flow_goals": [{ "Square": { "x": 9, "y": 0, "z": 0 } }] }),
+        )
+        .unwrap();
+    world
+        .set_component(
+            hauler,
+            "Position",
+            json!({ "pos": { "Square": { "x": 4, "y": 8, "z": 0 } } }),
+        )
+        .unwrap();
+    assert_eq!(
+        world.flow_step_for(hauler, &square(4, 8), &goals),
+        Some(square(4, 9))
+    );
+
+    let mut movement = MovementSystem;
+    for _ in 0..15 {
+        movement.run(&mut world);
+    }
+    let position = world.get_component(hauler, "Position").unwrap();
+    assert_eq!(CellKey::from_position(position), Some(square(9, 0)));
+    assert!(
+        world.get_component(hauler, "Agent").unwrap()["flow_goals"].is_array(),
+        "Goals are kept until the agent has arrived"
+    );
+    movement.run(&mut world);
+    assert!(world.get_component(hauler, "Agent").unwrap()["flow_goals"].is_null());
+
+    let runner = world.spawn_entity();
+    world
+        .set_component(runner, "Agent", json!({ "entity_id": runner }))
+        .unwrap();
+    assign_move_path_down_gradient(&mut world, runner, &square(6, 9), &goals);
+    let agent = world.get_component(runner, "Agent").unwrap();
+    assert_eq!(agent["move_path"].as_array().unwrap().len(), 12);
+    assert_eq!(
+        world.flee_step(
+            &square(1, 0),
+            &[square(0, 0)],
+            "walker",
+            DEFAULT_FLEE_FACTOR
+        ),
+        world
+            .flee_map(&[square(0, 0)], "walker", DEFAULT_FLEE_FACTOR)
+            .unwrap()
+            .next_step(&square(1, 0), |cell| world
+                .map
+                .as_ref()
+                .unwrap()
+                .neighbors(cell))
+    );
+}
+
+#[test]
+fn test_wasm_world_flow_fields_follow_map_changes() {
+    let mut world = WasmWorld::new();
+    let key = |x: i32| serde_json::to_string(&square(x, 0)).unwrap();
+    for x in 0..3 {
+        world.add_cell(x, 0, 0);
+    }
+    world.add_neighbor(&key(0), &key(1)).unwrap();
+    world.add_neighbor(&key(1), &key(2)).unwrap();
+    let to_end = serde_json::to_string(&[square(2, 0)]).unwrap();
+
+    assert_eq!(world.flow_step(&to_end, &key(0)), Some(key(1)));
+    assert_eq!(
+        world.flee_step(&to_end, &key(1), DEFAULT_FLEE_FACTOR),
+        Some(key(0))
+    );
+
+    // Blocking the corridor cuts the way off
+    world.set_cell_metadata(&key(1), r#"{"walkable": false}"#);
+    assert_eq!(world.flow_step(&to_end, &key(0)), None);
+
+    // A shortcut is taken once added
+    world.add_neighbor(&key(0), &key(2)).unwrap();
+    assert_eq!(world.flow_step(&to_end, &key(0)), Some(key(2)));
+}
@@ -23,6 +23,7 @@ pub fn setup_registry_with_c_plugin() -> WorldgenRegistry {
     let mut engine_api = EngineApi {
         spawn_entity: test_spawn_entity,
         set_component: test_set_component,
+        ..EngineApi::new()
     };
     let world_ptr = std::ptr::null_mut();
 
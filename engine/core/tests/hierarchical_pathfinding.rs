@@ -0,0 +1,200 @@
+#[path = "helpers/world.rs"]
+mod world_helper;
+
+use engine_core::ecs::world::World;
+use engine_core::map::{
+    CellKey, HexGridMap, HierarchicalGraph, Map, MovementProfile, PathfindingResult,
+    PathfindingStrategy, SquareGridMap,
+};
+use serde_json::json;
+
+const SIZE: i32 = 48;
+
+fn square(x: i32, y: i32) -> CellKey {
+    CellKey::Square { x, y, z: 0 }
+}
+
+fn wall() -> serde_json::Value {
+    json!({ "walkable": false })
+}
+
+/// A 4-connected 48x48 grid with two walls: one at x = 20 open at the bottom
+/// (y >= 45) and one at x = 32 open at the top (y < 4).
+fn maze() -> Map {
+    let mut grid = SquareGridMap::new();
+    for x in 0..SIZE {
+        for y in 0..SIZE {
+            grid.add_cell(x, y, 0);
+            for (nx, ny) in [(x + 1, y), (x, y + 1), (x - 1, y), (x, y - 1)] {
+                if (0..SIZE).contains(&nx) && (0..SIZE).contains(&ny) {
+                    grid.add_neighbor((x, y, 0), (nx, ny, 0));
+                }
+            }
+        }
+    }
+    let mut map = Map::new(Box::new(grid));
+    for y in 0..45 {
+        map.set_cell_metadata(&square(20, y), wall());
+    }
+    for y in 4..SIZE {
+        map.set_cell_metadata(&square(32, y), wall());
+    }
+    map
+}
+
+fn hierarchical(cluster_size: u32) -> PathfindingStrategy {
+    PathfindingStrategy::Hierarchical { cluster_size }
+}
+
+fn assert_walkable_path(map: &Map, result: &PathfindingResult, start: &CellKey, goal: &CellKey) {
+    assert_eq!(result.path.first(), Some(start));
+    assert_eq!(result.path.last(), Some(goal));
+    for step in result.path.windows(2) {
+        assert!(
+            map.neighbors(&step[0]).contains(&step[1]),
+            "{:?} -> {:?} is not a neighbor step",
+            step[0],
+            step[1]
+        );
+        assert_ne!(map.get_cell_metadata(&step[1]), Some(&wall()));
+    }
+    assert_eq!(result.total_cost, (result.path.len() - 1) as f32);
+}
+
+#[test]
+fn test_hierarchical_paths_are_walkable_and_near_optimal() {
+    let map = maze();
+    let walker = MovementProfile::walker();
+    let (start, goal) = (square(2, 2), square(45, 45));
+
+    let exact = map.find_path(&start, &goal).expect("Path should exist");
+    let result = map
+        .find_path_using(&start, &goal, &walker, hierarchical(8))
+        .expect("Hierarchical path should exist");
+    assert_walkable_path(&map, &result, &start, &goal);
+    assert!(result.total_cost >= exact.total_cost);
+    assert!(
+        result.total_cost <= exact.total_cost * 1.25,
+        "{} is far from the optimal {}",
+        result.total_cost,
+        exact.total_cost
+    );
+
+    let graph = HierarchicalGraph::build(map.topology.as_ref(), &walker, 8);
+    assert_eq!(graph.cluster_count(), 36);
+    assert!(graph.entrance_count() < (SIZE * SIZE / 8) as usize);
+}
+
+#[test]
+fn test_short_and_unreachable_paths() {
+    let mut map = maze();
+    let walker = MovementProfile::walker();
+    let (start, near) = (square(1, 1), square(3, 2));
+    let result = map
+        .find_path_using(&start, &near, &walker, hierarchical(8))
+        .expect("Same-cluster path should exist");
+    assert_walkable_path(&map, &result, &start, &near);
+    assert_eq!(result.path.len(), 4);
+
+    for (x, y) in [(40, 39), (40, 41), (39, 40), (41, 40)] {
+        map.set_cell_metadata(&square(x, y), wall());
+    }
+    assert!(
+        map.find_path_using(&start, &square(40, 40), &walker, hierarchical(8))
+            .is_none()
+    );
+    assert!(
+        map.find_path_using(&start, &square(99, 99), &walker, hierarchical(8))
+            .is_none()
+    );
+}
+
+#[test]
+fn test_metadata_changes_update_clusters_incrementally() {
+    let mut map = maze();
+    let walker = MovementProfile::walker();
+    let (start, goal) = (square(2, 2), square(45, 45));
+    assert!(
+        map.find_path_using(&start, &goal, &walker, hierarchical(8))
+            .is_some()
+    );
+
+    for y in 45..SIZE {
+        map.set_cell_metadata(&square(20, y), wall());
+    }
+    assert!(
+        map.find_path_using(&start, &goal, &walker, hierarchical(8))
+            .is_none(),
+        "Closing the gap cuts the map in two"
+    );
+    map.set_cell_metadata(&square(20, 46), json!({ "walkable": true }));
+    let reopened = map
+        .find_path_using(&start, &goal, &walker, hierarchical(8))
+        .expect("Reopened gap");
+    assert!(reopened.path.contains(&square(20, 46)));
+
+    let mut graph = HierarchicalGraph::build(map.topology.as_ref(), &walker, 8);
+    graph.mark_dirty(&square(20, 46));
+    assert_eq!(graph.dirty_count(), 1);
+    map.topology.set_cell_metadata(&square(20, 46), wall());
+    assert!(
+        graph
+            .find_path(map.topology.as_ref(), &start, &goal)
+            .is_none()
+    );
+    assert_eq!(graph.dirty_count(), 0);
+}
+
+#[test]
+fn test_world_find_path_uses_selected_strategy() {
+    let mut world = world_helper::make_test_world();
+    world.map = Some(maze());
+    let (start, goal) = (square(2, 2), square(45, 45));
+    let exact = world.find_path(&start, &goal).unwrap();
+
+    world
+        .set_pathfinding_strategy_by_name("hierarchical", Some(8))
+        .unwrap();
+    assert_eq!(world.pathfinding_strategy, hierarchical(8));
+    let result = world.find_path(&start, &goal).unwrap();
+    assert_walkable_path(world.map.as_ref().unwrap(), &result, &start, &goal);
+    assert!(result.total_cost <= exact.total_cost * 1.25);
+    assert!(
+        world
+            .set_pathfinding_strategy_by_name("dijkstra", None)
+            .is_err()
+    );
+    assert!(
+        world
+            .set_pathfinding_strategy_by_name("hierarchical", Some(0))
+            .is_err()
+    );
+
+    let snapshot = world.snapshot().unwrap();
+    world.pathfinding_strategy = PathfindingStrategy::AStar;
+    world.restore(&snapshot).unwrap();
+    assert_eq!(world.pathfinding_strategy, hierarchical(8));
+    let saved = world.save_to_value().unwrap();
+    let loaded = World::load_from_value(saved, world.registry.clone()).unwrap();
+    assert_eq!(loaded.pathfinding_strategy, hierarchical(8));
+}
+
+#[test]
+fn test_other_topologies_fall_back_to_astar() {
+    let mut hexes = HexGridMap::new();
+    for q in 0..4 {
+        hexes.add_cell(q, 0, 0);
+        if q > 0 {
+            hexes.add_neighbor((q - 1, 0, 0), (q, 0, 0));
+        }
+    }
+    let map = Map::new(Box::new(hexes));
+    let (start, goal) = (
+        CellKey::Hex { q: 0, r: 0, z: 0 },
+        CellKey::Hex { q: 3, r: 0, z: 0 },
+    );
+    assert_eq!(
+        map.find_path_using(&start, &goal, &MovementProfile::walker(), hierarchical(2)),
+        map.find_path(&start, &goal)
+    );
+}
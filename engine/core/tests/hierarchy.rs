@@ -0,0 +1,135 @@
+#[path = "helpers/world.rs"]
+mod world_helper;
+use world_helper::make_test_world;
+
+#[path = "helpers/world_io.rs"]
+mod world_io_helper;
+use world_io_helper::save_and_load_roundtrip;
+
+use engine_core::ecs::world::wasm::WasmWorld;
+use engine_core::ecs::world::{DespawnPolicy, ReplayCommand, World};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Spawns a chain `root -> mid -> leaf`.
+fn chain(world: &mut World) -> (u32, u32, u32) {
+    let root = world.spawn_entity();
+    let mid = world.spawn_entity();
+    let leaf = world.spawn_entity();
+    world.set_parent(mid, root).unwrap();
+    world.set_parent(leaf, mid).unwrap();
+    (root, mid, leaf)
+}
+
+#[test]
+fn test_parent_links_are_queryable() {
+    let mut world = make_test_world();
+    let (root, mid, leaf) = chain(&mut world);
+    let sibling = world.spawn_entity();
+    world.set_parent(sibling, root).unwrap();
+
+    assert_eq!(world.parent_of(leaf), Some(mid));
+    assert_eq!(world.parent_of(root), None);
+    assert_eq!(world.children_of(root), vec![mid, sibling]);
+    assert_eq!(world.ancestors(leaf), vec![mid, root]);
+    assert_eq!(world.descendants(root), vec![mid, leaf, sibling]);
+
+    // Reparenting moves the child out of its old parent's children.
+    world.set_parent(leaf, root).unwrap();
+    assert!(world.children_of(mid).is_empty());
+    assert_eq!(world.remove_parent(leaf), Some(root));
+    assert_eq!(world.parent_of(leaf), None);
+}
+
+#[test]
+fn test_invalid_links_are_rejected() {
+    let mut world = make_test_world();
+    let (root, _, leaf) = chain(&mut world);
+    assert!(world.set_parent(root, root).is_err());
+    assert!(world.set_parent(root, leaf).is_err(), "cycle");
+    assert!(world.set_parent(leaf, 999).is_err(), "missing parent");
+    assert_eq!(world.ancestors(leaf).len(), 2);
+}
+
+#[test]
+fn test_despawn_cascades_to_children() {
+    let mut world = make_test_world();
+    let (root, mid, leaf) = chain(&mut world);
+    world.despawn_entity(root);
+    assert!(world.get_entities().is_empty());
+    for e in [root, mid, leaf] {
+        assert!(world.is_stale_entity(e));
+        assert!(world.children_of(e).is_empty());
+    }
+}
+
+#[test]
+fn test_reparent_policy_moves_children_up() {
+    let mut world = make_test_world();
+    let holder = world.spawn_entity();
+    let bag = world.spawn_entity();
+    let item = world.spawn_entity();
+    world.set_parent(bag, holder).unwrap();
+    world
+        .set_parent_with_policy(item, bag, DespawnPolicy::Reparent)
+        .unwrap();
+
+    world.despawn_entity(bag);
+    assert_eq!(world.parent_of(item), Some(holder));
+    assert_eq!(world.children_of(holder), vec![item]);
+
+    // Without a grandparent the child becomes a root.
+    world.despawn_entity(holder);
+    assert!(world.get_entities().contains(&item));
+    assert_eq!(world.parent_of(item), None);
+}
+
+#[test]
+fn test_hierarchy_survives_save_load_and_replay() {
+    let mut world = make_test_world();
+    let (root, mid, leaf) = chain(&mut world);
+    let registry = world.registry.clone();
+    let loaded = save_and_load_roundtrip(&world, registry);
+    assert_eq!(loaded.ancestors(leaf), vec![mid, root]);
+    assert_eq!(loaded.state_hash(), world.state_hash());
+
+    let world_rc = Rc::new(RefCell::new(world));
+    world_rc.borrow_mut().start_recording().unwrap();
+    world_rc
+        .borrow_mut()
+        .set_parent_with_policy(leaf, root, DespawnPolicy::Reparent)
+        .unwrap();
+    World::tick(Rc::clone(&world_rc));
+    let recording = world_rc.borrow_mut().stop_recording().unwrap();
+    assert_eq!(
+        recording.ticks[0].commands,
+        vec![ReplayCommand::SetParent {
+            entity: leaf,
+            parent: Some(root),
+            policy: DespawnPolicy::Reparent,
+        }]
+    );
+    let replay = Rc::new(RefCell::new(make_test_world()));
+    assert_eq!(World::replay(Rc::clone(&replay), &recording).unwrap(), 1);
+    assert_eq!(replay.borrow().parent_of(leaf), Some(root));
+}
+
+#[test]
+fn test_wasm_world_hierarchy() {
+    let mut world = WasmWorld::new();
+    let root = world.spawn_entity();
+    let child = world.spawn_entity();
+    let item = world.spawn_entity();
+    world
+        .set_parent(child, root, DespawnPolicy::Cascade)
+        .unwrap();
+    world
+        .set_parent(item, child, DespawnPolicy::Reparent)
+        .unwrap();
+    assert_eq!(world.ancestors(item), vec![child, root]);
+
+    world.despawn_entity(child);
+    assert_eq!(world.parent_of(item), Some(root));
+    world.despawn_entity(root);
+    assert_eq!(world.get_entities(), &[item]);
+}
@@ -824,3 +824,18 @@ fn test_lifo_job_assignment_policy() {
     // Should get job2 (the last-added), not job1 (the highest priority)
     assert_eq!(agent_obj["current_job"], job2);
 }
+
+#[test]
+fn test_job_board_unknown_saved_policy_falls_back_to_priority() {
+    let board: JobBoard = serde_json::from_value(json!({
+        "jobs": [3, 1],
+        "policy": "round_robin",
+        "current_tick": 7
+    }))
+    .expect("An unknown policy should not fail the load");
+    assert_eq!(board.get_policy_name(), "priority");
+    assert_eq!(board.jobs, vec![3, 1]);
+
+    let board: JobBoard = serde_json::from_value(json!({"jobs": [], "policy": "fifo"})).unwrap();
+    assert_eq!(board.get_policy_name(), "fifo");
+}
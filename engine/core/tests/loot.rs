@@ -15,7 +15,7 @@ fn test_define_and_roll() {
         )
         .unwrap();
 
-    let result = registry.roll("test").unwrap();
+    let result = registry.roll("test", &mut rand::rng()).unwrap();
     assert_eq!(result.len(), 1);
     assert_eq!(result[0].0, "item1");
     assert_eq!(result[0].1, 1);
@@ -25,14 +25,14 @@ fn test_define_and_roll() {
 fn test_empty_table_returns_error() {
     let mut registry = LootTableRegistry::new();
     registry.define_table("empty", vec![]).unwrap();
-    let result = registry.roll("empty");
+    let result = registry.roll("empty", &mut rand::rng());
     assert!(result.is_err());
 }
 
 #[test]
 fn test_undefined_table_returns_error() {
     let registry = LootTableRegistry::new();
-    let result = registry.roll("nonexistent");
+    let result = registry.roll("nonexistent", &mut rand::rng());
     assert!(result.is_err());
 }
 
@@ -67,7 +67,7 @@ fn test_min_max_count_range() {
         .unwrap();
 
     for _ in 0..20 {
-        let result = registry.roll("multi").unwrap();
+        let result = registry.roll("multi", &mut rand::rng()).unwrap();
         assert_eq!(result.len(), 1);
         assert!(result[0].1 >= 2 && result[0].1 <= 5);
     }
@@ -100,7 +100,7 @@ fn test_weighted_distribution() {
     let mut rare_count = 0u32;
     let total_rolls = 100;
     for _ in 0..total_rolls {
-        let result = registry.roll("weighted").unwrap();
+        let result = registry.roll("weighted", &mut rand::rng()).unwrap();
         assert_eq!(result.len(), 1, "weighted-sum should return exactly 1 item");
         if result[0].0 == "common" {
             common_count += 1;
@@ -167,7 +167,7 @@ fn test_remove_table() {
     assert!(registry.has_table("temp"));
     registry.remove_table("temp");
     assert!(!registry.has_table("temp"));
-    assert!(registry.roll("temp").is_err());
+    assert!(registry.roll("temp", &mut rand::rng()).is_err());
 }
 
 #[test]
@@ -210,6 +210,6 @@ fn test_define_overwrites() {
             }],
         )
         .unwrap();
-    let result = registry.roll("dupe").unwrap();
+    let result = registry.roll("dupe", &mut rand::rng()).unwrap();
     assert_eq!(result[0].0, "new");
 }
@@ -51,12 +51,10 @@ fn test_map_rendering_renders_terrain_and_entities_in_viewport() {
             cell_metadata.insert(cell, json!({ "terrain": terrain }));
         }
     }
-    let map = Map {
-        topology: Box::new(SquareGridMap {
-            cells,
-            cell_metadata,
-        }),
-    };
+    let map = Map::new(Box::new(SquareGridMap {
+        cells,
+        cell_metadata,
+    }));
     world.map = Some(map);
 
     // Spawn an entity at (1, 1) with a Renderable component
@@ -153,12 +151,10 @@ fn test_map_rendering_with_visibility_filter() {
         CellKey::Square { x: 1, y: 1, z: 0 },
         json!({ "terrain": "wall" }),
     );
-    let map = Map {
-        topology: Box::new(SquareGridMap {
-            cells,
-            cell_metadata,
-        }),
-    };
+    let map = Map::new(Box::new(SquareGridMap {
+        cells,
+        cell_metadata,
+    }));
     world.map = Some(map);
 
     // Spawn entity at (2, 2) with Renderable
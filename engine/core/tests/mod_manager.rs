@@ -0,0 +1,581 @@
+use engine_core::ecs::registry::ComponentRegistry;
+use engine_core::ecs::world::World;
+use engine_core::mods::loader::ModScriptEngine;
+use engine_core::mods::{ContentKind, ModConflict, ModError, ModManager};
+use engine_core::tech_tree::TechTree;
+use serde_json::{Value as JsonValue, json};
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+/// Records the scripts it is asked to run.
+#[derive(Default)]
+struct RecordingEngine {
+    scripts: Vec<String>,
+}
+
+impl ModScriptEngine for RecordingEngine {
+    fn run_script(&mut self, script: &str) -> Result<(), String> {
+        self.scripts.push(script.to_string());
+        Ok(())
+    }
+}
+
+/// Writes a mod whose main script is `-- <name>`.
+fn write_mod(
+    mods_dir: &Path,
+    name: &str,
+    version: &str,
+    dependencies: &[&str],
+) -> std::path::PathBuf {
+    let dir = mods_dir.join(name);
+    std::fs::create_dir_all(&dir).unwrap();
+    let manifest = json!({
+        "name": name,
+        "version": version,
+        "dependencies": dependencies,
+        "main_script": "main.lua",
+    });
+    std::fs::write(dir.join("mod.json"), manifest.to_string()).unwrap();
+    std::fs::write(dir.join("main.lua"), format!("-- {name}")).unwrap();
+    dir
+}
+
+fn write_data(mod_dir: &Path, kind: ContentKind, file: &str, value: serde_json::Value) {
+    let dir = mod_dir.join(kind.dir_name());
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join(file), value.to_string()).unwrap();
+}
+
+/// Registers a system, a job handler and a subscription named after each mod
+/// whose main script it runs.
+struct RegisteringEngine {
+    world: Rc<RefCell<World>>,
+}
+
+impl ModScriptEngine for RegisteringEngine {
+    fn run_script(&mut self, script: &str) -> Result<(), String> {
+        let name = script.trim_start_matches("-- ");
+        let mut world = self.world.borrow_mut();
+        world.register_dynamic_system(&format!("{name}_tick"), |_, _| {});
+        world.register_job_handler(&format!("{name}_job"), |_, _, _, job| job.clone());
+        world
+            .subscribe::<JsonValue, _>("alerts", |_| {})
+            .ok_or("no alerts bus")?;
+        Ok(())
+    }
+}
+
+fn write_schema(mod_dir: &Path, title: &str) {
+    let dir = mod_dir.join("schemas");
+    std::fs::create_dir_all(&dir).unwrap();
+    let schema = json!({
+        "title": title,
+        "type": "object",
+        "properties": {"value": {"type": "number"}},
+        "modes": ["colony"],
+    });
+    std::fs::write(dir.join(format!("{title}.json")), schema.to_string()).unwrap();
+}
+
+fn plank_recipe(amount: u32) -> JsonValue {
+    json!({"name": "plank", "inputs": [{"kind": "wood", "amount": 1}],
+           "outputs": [{"kind": "plank", "amount": amount}], "duration": 1})
+}
+
+fn new_world() -> Rc<RefCell<World>> {
+    let registry = Arc::new(Mutex::new(ComponentRegistry::new()));
+    Rc::new(RefCell::new(World::new(registry)))
+}
+
+#[test]
+fn test_load_order_follows_dependencies() {
+    let mods = tempfile::tempdir().unwrap();
+    write_mod(mods.path(), "addon", "0.1.0", &["core ^1.2", "extras"]);
+    write_mod(mods.path(), "core", "1.4.0", &[]);
+    write_mod(mods.path(), "extras", "2.0.0", &["core >=1.0, <2.0"]);
+    write_mod(mods.path(), "standalone", "1.0.0", &[]);
+
+    let manager = ModManager::discover(mods.path()).unwrap();
+    assert_eq!(
+        manager.resolve_load_order().unwrap(),
+        vec!["core", "extras", "addon", "standalone"]
+    );
+
+    let mut engine = RecordingEngine::default();
+    let order = manager.load_all(new_world(), &mut engine).unwrap();
+    assert_eq!(manager.loaded(), order);
+    assert_eq!(
+        engine.scripts,
+        vec!["-- core", "-- extras", "-- addon", "-- standalone"]
+    );
+}
+
+#[test]
+fn test_missing_mismatched_and_cyclic_dependencies_are_reported() {
+    let mods = tempfile::tempdir().unwrap();
+    write_mod(mods.path(), "a", "1.0.0", &["b"]);
+    write_mod(mods.path(), "b", "1.0.0", &["c"]);
+    write_mod(mods.path(), "c", "1.0.0", &["a"]);
+    write_mod(mods.path(), "d", "1.0.0", &["ghost", "c ^2"]);
+
+    let manager = ModManager::discover(mods.path()).unwrap();
+    let errors = manager.resolve_load_order().unwrap_err();
+    assert!(errors.contains(&ModError::MissingDependency {
+        mod_name: "d".into(),
+        dependency: "ghost".into(),
+    }));
+    assert!(errors.iter().any(|e| matches!(
+        e,
+        ModError::VersionMismatch { mod_name, dependency, .. } if mod_name == "d" && dependency == "c"
+    )));
+    assert!(errors.contains(&ModError::Cycle(vec![
+        "a".into(),
+        "b".into(),
+        "c".into(),
+        "a".into()
+    ])));
+    assert_eq!(
+        ModError::Cycle(vec!["a".into(), "b".into(), "a".into()]).to_string(),
+        "Mod dependency cycle: a -> b -> a"
+    );
+
+    let mut engine = RecordingEngine::default();
+    assert!(manager.load_all(new_world(), &mut engine).is_err());
+    assert!(engine.scripts.is_empty());
+    assert!(manager.loaded().is_empty());
+}
+
+#[test]
+fn test_loading_one_mod_loads_its_dependencies_first() {
+    let mods = tempfile::tempdir().unwrap();
+    write_mod(mods.path(), "core", "1.0.0", &[]);
+    write_mod(mods.path(), "game", "1.0.0", &["core"]);
+    write_mod(mods.path(), "unrelated", "1.0.0", &["ghost"]);
+
+    let manager = ModManager::discover(mods.path()).unwrap();
+    let mut engine = RecordingEngine::default();
+    let world = new_world();
+    assert_eq!(
+        manager.load("game", world.clone(), &mut engine).unwrap(),
+        vec!["core", "game"]
+    );
+    assert_eq!(engine.scripts, vec!["-- core", "-- game"]);
+
+    // Already loaded mods are not loaded again
+    manager.load("core", world.clone(), &mut engine).unwrap();
+    assert_eq!(engine.scripts.len(), 2);
+    assert!(
+        manager
+            .load("unrelated", world.clone(), &mut engine)
+            .is_err()
+    );
+    assert!(manager.load("nope", world, &mut engine).is_err());
+}
+
+#[test]
+fn test_invalid_manifests_are_rejected() {
+    let mods = tempfile::tempdir().unwrap();
+    write_mod(mods.path(), "bad_version", "one", &[]);
+    assert!(ModManager::discover(mods.path()).is_err());
+
+    let mods = tempfile::tempdir().unwrap();
+    write_mod(mods.path(), "bad_range", "1.0.0", &["core not-a-range"]);
+    let err = ModManager::discover(mods.path()).unwrap_err().to_string();
+    assert!(err.contains("Invalid version range"), "{err}");
+}
+
+#[test]
+fn test_mods_overlay_data_and_report_conflicts() {
+    let mods = tempfile::tempdir().unwrap();
+    let core = write_mod(mods.path(), "core", "1.0.0", &[]);
+    let tweaks = write_mod(mods.path(), "tweaks", "1.0.0", &["core"]);
+
+    write_data(
+        &core,
+        ContentKind::Recipe,
+        "recipes.json",
+        json!([
+            {"name": "plank", "inputs": [{"kind": "wood", "amount": 1}],
+             "outputs": [{"kind": "plank", "amount": 2}], "duration": 2},
+            {"name": "brick", "inputs": [{"kind": "clay", "amount": 1}],
+             "outputs": [{"kind": "brick", "amount": 1}], "duration": 3}
+        ]),
+    );
+    write_data(
+        &core,
+        ContentKind::Material,
+        "oak.json",
+        json!({"name": "oak", "density": 0.7}),
+    );
+    write_data(
+        &core,
+        ContentKind::Job,
+        "chop.json",
+        json!({"name": "chop", "duration": 5.0}),
+    );
+    write_data(
+        &core,
+        ContentKind::LootTable,
+        "chest.json",
+        json!({"name": "chest", "entries": [{"item_id": "gold", "weight": 1}]}),
+    );
+    write_data(
+        &core,
+        ContentKind::TechNode,
+        "tech.json",
+        json!([{"id": "carpentry", "name": "Carpentry", "cost": 50.0}]),
+    );
+    write_data(
+        &tweaks,
+        ContentKind::Recipe,
+        "plank.json",
+        json!({"name": "plank", "inputs": [{"kind": "wood", "amount": 1}],
+               "outputs": [{"kind": "plank", "amount": 4}], "duration": 1}),
+    );
+    write_data(
+        &tweaks,
+        ContentKind::TechNode,
+        "carpentry.json",
+        json!({"id": "carpentry", "name": "Carpentry", "cost": 25.0}),
+    );
+
+    let world = new_world();
+    world
+        .borrow_mut()
+        .material_definitions
+        .insert("granite".into(), json!({"name": "granite"}));
+    let manager = ModManager::discover(mods.path()).unwrap();
+    manager
+        .load_all(world.clone(), &mut RecordingEngine::default())
+        .unwrap();
+
+    let world = world.borrow();
+    assert_eq!(world.recipes["plank"]["outputs"][0]["amount"], 4);
+    assert_eq!(world.recipes["brick"]["duration"], 3);
+    assert_eq!(world.material_definitions.len(), 2);
+    assert_eq!(
+        world.job_types.get_data("chop").unwrap().duration,
+        Some(5.0)
+    );
+    assert!(world.loot_tables.has_table("chest"));
+    assert_eq!(world.tech_tree.len(), 1);
+    assert_eq!(world.tech_tree.get("carpentry").unwrap().cost, 25.0);
+
+    assert_eq!(
+        manager.conflicts(),
+        vec![
+            ModConflict {
+                kind: ContentKind::Recipe,
+                id: "plank".into(),
+                mods: vec!["core".into(), "tweaks".into()],
+            },
+            ModConflict {
+                kind: ContentKind::TechNode,
+                id: "carpentry".into(),
+                mods: vec!["core".into(), "tweaks".into()],
+            },
+        ]
+    );
+    assert_eq!(
+        manager.provider(ContentKind::Recipe, "plank"),
+        Some("tweaks".to_string())
+    );
+    assert_eq!(
+        manager.provider(ContentKind::Recipe, "brick"),
+        Some("core".to_string())
+    );
+    assert_eq!(manager.provider(ContentKind::Material, "granite"), None);
+}
+
+#[test]
+fn test_invalid_data_file_fails_the_mod() {
+    let mods = tempfile::tempdir().unwrap();
+    let broken = write_mod(mods.path(), "broken", "1.0.0", &[]);
+    write_data(
+        &broken,
+        ContentKind::LootTable,
+        "bad.json",
+        json!({"name": "bad", "entries": "nope"}),
+    );
+
+    let manager = ModManager::discover(mods.path()).unwrap();
+    let err = manager
+        .load_all(new_world(), &mut RecordingEngine::default())
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("Failed to load mod 'broken'"), "{err}");
+    assert!(err.contains("Invalid loot table 'bad'"), "{err}");
+    assert!(manager.loaded().is_empty());
+}
+
+fn world_with_alerts_bus() -> Rc<RefCell<World>> {
+    let world = new_world();
+    world.borrow_mut().register_event_bus::<JsonValue>("alerts");
+    world
+}
+
+#[test]
+fn test_unload_removes_what_the_mod_registered() {
+    let mods = tempfile::tempdir().unwrap();
+    let core = write_mod(mods.path(), "core", "1.0.0", &[]);
+    let extras = write_mod(mods.path(), "extras", "1.0.0", &[]);
+    write_schema(&core, "Sturdy");
+    write_schema(&extras, "Shiny");
+
+    let world = world_with_alerts_bus();
+    let manager = ModManager::discover(mods.path()).unwrap();
+    let mut engine = RegisteringEngine {
+        world: world.clone(),
+    };
+    manager.load_all(world.clone(), &mut engine).unwrap();
+    assert_eq!(world.borrow().mod_resources("extras").len(), 4);
+    assert_eq!(
+        world
+            .borrow()
+            .event_bus_subscriber_count::<JsonValue>("alerts"),
+        Some(2)
+    );
+
+    manager.unload("extras", &world).unwrap();
+    assert_eq!(manager.loaded(), vec!["core"]);
+    let w = world.borrow();
+    assert!(w.mod_resources("extras").is_empty());
+    assert!(!w.dynamic_systems.is_registered("extras_tick"));
+    assert!(w.dynamic_systems.is_registered("core_tick"));
+    let handlers = w.job_handler_registry.lock().unwrap().keys();
+    assert!(!handlers.contains(&"extras_job".to_string()));
+    assert!(handlers.contains(&"core_job".to_string()));
+    assert_eq!(w.event_bus_subscriber_count::<JsonValue>("alerts"), Some(1));
+    let registry = w.registry.lock().unwrap();
+    assert!(!registry.is_registered("Shiny"));
+    assert!(registry.is_registered("Sturdy"));
+    drop(registry);
+    drop(w);
+
+    assert!(manager.unload("extras", &world).is_err());
+}
+
+#[test]
+fn test_unload_restores_replaced_data() {
+    let mods = tempfile::tempdir().unwrap();
+    let core = write_mod(mods.path(), "core", "1.0.0", &[]);
+    let tweaks = write_mod(mods.path(), "tweaks", "1.0.0", &[]);
+    write_data(&core, ContentKind::Recipe, "plank.json", plank_recipe(2));
+    write_data(
+        &core,
+        ContentKind::Material,
+        "oak.json",
+        json!({"name": "oak", "density": 0.7}),
+    );
+    write_data(&tweaks, ContentKind::Recipe, "plank.json", plank_recipe(4));
+    write_data(
+        &tweaks,
+        ContentKind::Material,
+        "granite.json",
+        json!({"name": "granite", "density": 2.6}),
+    );
+
+    let world = new_world();
+    world
+        .borrow_mut()
+        .material_definitions
+        .insert("granite".into(), json!({"name": "granite", "density": 2.7}));
+    let manager = ModManager::discover(mods.path()).unwrap();
+    manager
+        .load_all(world.clone(), &mut RecordingEngine::default())
+        .unwrap();
+    assert_eq!(world.borrow().recipes["plank"]["outputs"][0]["amount"], 4);
+    assert_eq!(
+        world.borrow().material_definitions["granite"]["density"],
+        2.6
+    );
+
+    // The earlier mod's and the world's own definitions come back
+    manager.unload("tweaks", &world).unwrap();
+    assert_eq!(world.borrow().recipes["plank"]["outputs"][0]["amount"], 2);
+    assert_eq!(
+        world.borrow().material_definitions["granite"]["density"],
+        2.7
+    );
+    assert_eq!(
+        manager.provider(ContentKind::Recipe, "plank"),
+        Some("core".to_string())
+    );
+    assert!(manager.conflicts().is_empty());
+
+    // Definitions only a mod provided go away
+    manager.unload("core", &world).unwrap();
+    assert!(world.borrow().recipes.is_empty());
+    assert!(!world.borrow().material_definitions.contains_key("oak"));
+    assert!(world.borrow().material_definitions.contains_key("granite"));
+}
+
+#[test]
+fn test_unload_refuses_while_dependents_are_loaded() {
+    let mods = tempfile::tempdir().unwrap();
+    write_mod(mods.path(), "core", "1.0.0", &[]);
+    write_mod(mods.path(), "game", "1.0.0", &["core"]);
+
+    let world = new_world();
+    let manager = ModManager::discover(mods.path()).unwrap();
+    manager
+        .load("game", world.clone(), &mut RecordingEngine::default())
+        .unwrap();
+    let err = manager.unload("core", &world).unwrap_err().to_string();
+    assert!(err.contains("required by loaded mods: game"), "{err}");
+
+    manager.unload("game", &world).unwrap();
+    manager.unload("core", &world).unwrap();
+    assert!(manager.loaded().is_empty());
+}
+
+#[test]
+fn test_reload_picks_up_changes() {
+    let mods = tempfile::tempdir().unwrap();
+    let core = write_mod(mods.path(), "core", "1.0.0", &[]);
+    write_data(&core, ContentKind::Recipe, "plank.json", plank_recipe(2));
+
+    let world = world_with_alerts_bus();
+    let manager = ModManager::discover(mods.path()).unwrap();
+    let mut engine = RegisteringEngine {
+        world: world.clone(),
+    };
+    manager.load("core", world.clone(), &mut engine).unwrap();
+
+    write_data(&core, ContentKind::Recipe, "plank.json", plank_recipe(3));
+    std::fs::write(
+        core.join("mod.json"),
+        json!({"name": "core", "version": "1.1.0", "main_script": "main.lua"}).to_string(),
+    )
+    .unwrap();
+    manager.reload("core", world.clone(), &mut engine).unwrap();
+
+    assert_eq!(world.borrow().recipes["plank"]["outputs"][0]["amount"], 3);
+    assert_eq!(manager.get("core").unwrap().manifest.version, "1.1.0");
+    assert_eq!(manager.loaded(), vec!["core"]);
+    // Registrations are replaced, not duplicated
+    assert_eq!(world.borrow().mod_resources("core").len(), 3);
+    assert_eq!(
+        world
+            .borrow()
+            .event_bus_subscriber_count::<JsonValue>("alerts"),
+        Some(1)
+    );
+}
+
+#[test]
+fn test_reload_keeps_load_order_and_checks_dependents() {
+    let mods = tempfile::tempdir().unwrap();
+    let core = write_mod(mods.path(), "core", "1.0.0", &[]);
+    let game = write_mod(mods.path(), "game", "1.0.0", &["core ^1"]);
+    write_data(&core, ContentKind::Recipe, "plank.json", plank_recipe(2));
+    write_data(&game, ContentKind::Recipe, "plank.json", plank_recipe(5));
+
+    let world = new_world();
+    let manager = ModManager::discover(mods.path()).unwrap();
+    let mut engine = RecordingEngine::default();
+    manager.load("game", world.clone(), &mut engine).unwrap();
+
+    write_data(&core, ContentKind::Recipe, "plank.json", plank_recipe(3));
+    manager.reload("core", world.clone(), &mut engine).unwrap();
+    assert_eq!(manager.loaded(), vec!["core", "game"]);
+    assert_eq!(
+        manager.provider(ContentKind::Recipe, "plank").as_deref(),
+        Some("game")
+    );
+    assert_eq!(world.borrow().recipes["plank"]["outputs"][0]["amount"], 5);
+
+    // Unloading the dependent reveals the reloaded definition
+    manager.unload("game", &world).unwrap();
+    assert_eq!(world.borrow().recipes["plank"]["outputs"][0]["amount"], 3);
+    manager.load("game", world.clone(), &mut engine).unwrap();
+
+    std::fs::write(
+        core.join("mod.json"),
+        json!({"name": "core", "version": "2.0.0", "main_script": "main.lua"}).to_string(),
+    )
+    .unwrap();
+    let err = manager
+        .reload("core", world.clone(), &mut engine)
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.contains("'game' requires 'core' ^1, found 2.0.0"),
+        "{err}"
+    );
+    assert_eq!(manager.loaded(), vec!["core", "game"]);
+    assert_eq!(manager.get("core").unwrap().manifest.version, "1.0.0");
+}
+
+#[test]
+fn test_disabled_mods_are_skipped() {
+    let mods = tempfile::tempdir().unwrap();
+    write_mod(mods.path(), "core", "1.0.0", &[]);
+    write_mod(mods.path(), "game", "1.0.0", &["core"]);
+    write_mod(mods.path(), "extras", "1.0.0", &[]);
+
+    let world = new_world();
+    let manager = ModManager::discover(mods.path()).unwrap();
+    let mut engine = RecordingEngine::default();
+    manager.load("extras", world.clone(), &mut engine).unwrap();
+
+    manager.disable("extras", &world).unwrap();
+    assert!(!manager.is_enabled("extras"));
+    assert!(!manager.is_loaded("extras"));
+    assert_eq!(manager.resolve_load_order().unwrap(), vec!["core", "game"]);
+    assert!(manager.load("extras", world.clone(), &mut engine).is_err());
+
+    manager.disable("core", &world).unwrap();
+    assert_eq!(
+        manager.resolve_load_order().unwrap_err(),
+        vec![ModError::DisabledDependency {
+            mod_name: "game".into(),
+            dependency: "core".into(),
+        }]
+    );
+
+    manager.enable("core").unwrap();
+    manager.enable("extras").unwrap();
+    assert_eq!(
+        manager.load_all(world, &mut engine).unwrap(),
+        vec!["core", "extras", "game"]
+    );
+}
+
+#[test]
+fn test_mod_tech_nodes_extend_the_world_tech_tree() {
+    let mods = tempfile::tempdir().unwrap();
+    let sailing = write_mod(mods.path(), "sailing", "1.0.0", &[]);
+    write_data(
+        &sailing,
+        ContentKind::TechNode,
+        "tech.json",
+        json!([
+            {"id": "boats", "name": "Boats", "prerequisites": [{"type": "tech", "id": "carpentry"}]},
+            {"id": "carpentry", "name": "Carpentry", "cost": 20.0}
+        ]),
+    );
+
+    let world = new_world();
+    world.borrow_mut().tech_tree = TechTree::from_json(json!({"techs": [
+        {"id": "carpentry", "name": "Carpentry", "cost": 80.0}
+    ]}))
+    .unwrap();
+    let manager = ModManager::discover(mods.path()).unwrap();
+    manager
+        .load_all(world.clone(), &mut RecordingEngine::default())
+        .unwrap();
+    {
+        let world = world.borrow();
+        assert_eq!(world.tech_tree.len(), 2);
+        assert_eq!(world.tech_tree.get("carpentry").unwrap().cost, 20.0);
+        assert!(world.tech_tree.validate().is_ok());
+    }
+
+    manager.unload("sailing", &world).unwrap();
+    let world = world.borrow();
+    assert!(world.tech_tree.get("boats").is_none());
+    assert_eq!(world.tech_tree.get("carpentry").unwrap().cost, 80.0);
+}
@@ -11,6 +11,7 @@ fn test_validate_valid_manifest() {
         schemas: vec![],
         systems: vec![],
         scripts: vec![],
+        migrations: vec![],
     };
     assert!(manifest.validate().is_ok());
 }
@@ -26,6 +27,7 @@ fn test_validate_missing_main_script() {
         schemas: vec![],
         systems: vec![],
         scripts: vec![],
+        migrations: vec![],
     };
     let result = manifest.validate();
     assert!(result.is_err());
@@ -44,6 +46,25 @@ fn test_validate_with_empty_schemas_systems() {
         schemas: vec![],
         systems: vec![],
         scripts: vec![],
+        migrations: vec![],
     };
     assert!(manifest.validate().is_ok());
 }
+
+#[test]
+fn test_validate_rejects_bad_version_and_dependency() {
+    let manifest = ModManifest {
+        name: "bad".into(),
+        version: "latest".into(),
+        description: "".into(),
+        main_script: Some("main.lua".into()),
+        dependencies: vec!["core ~~1".into()],
+        schemas: vec![],
+        systems: vec![],
+        scripts: vec![],
+        migrations: vec![],
+    };
+    let errors = manifest.validate().unwrap_err();
+    assert!(errors.iter().any(|e| e.contains("Invalid mod version")));
+    assert!(errors.iter().any(|e| e.contains("Invalid version range")));
+}
@@ -1,4 +1,4 @@
-use engine_core::mods::manifest::{ModManifest, ModSystem};
+use engine_core::mods::manifest::{ModDependency, ModManifest, ModSystem};
 
 #[test]
 fn test_serde_roundtrip_all_fields() {
@@ -15,6 +15,7 @@ fn test_serde_roundtrip_all_fields() {
             dependencies: vec!["baz".into()],
         }],
         scripts: vec!["util.lua".into()],
+        migrations: vec![],
     };
     let json = serde_json::to_string(&manifest).unwrap();
     let deserialized: ModManifest = serde_json::from_str(&json).unwrap();
@@ -46,6 +47,7 @@ fn test_missing_optionals_get_defaults() {
     assert!(manifest.schemas.is_empty());
     assert!(manifest.systems.is_empty());
     assert!(manifest.scripts.is_empty());
+    assert!(manifest.migrations.is_empty());
 }
 
 #[test]
@@ -113,3 +115,35 @@ fn test_empty_systems_list_valid() {
     let manifest: ModManifest = serde_json::from_str(json).unwrap();
     assert!(manifest.systems.is_empty());
 }
+
+#[test]
+fn test_migrations_parse_with_defaults() {
+    let json = r#"{
+        "name": "migrating",
+        "version": "1.0.0",
+        "migrations": [
+            { "version": 3, "component": "Tag", "rename": { "label": "name" } },
+            { "version": 4, "component": "Legacy", "drop": true }
+        ]
+    }"#;
+    let manifest: ModManifest = serde_json::from_str(json).unwrap();
+    assert_eq!(manifest.migrations.len(), 2);
+    assert_eq!(manifest.migrations[0].rename["label"], "name");
+    assert!(manifest.migrations[0].defaults.is_empty());
+    assert!(!manifest.migrations[0].drop);
+    assert!(manifest.migrations[1].drop);
+}
+
+#[test]
+fn test_parse_dependency_with_version_range() {
+    let any = ModDependency::parse("core").unwrap();
+    assert_eq!(any.name, "core");
+    assert!(any.version.matches(&semver::Version::new(9, 0, 0)));
+
+    let ranged = ModDependency::parse("core >=1.2, <2.0").unwrap();
+    assert_eq!(ranged.name, "core");
+    assert!(ranged.version.matches(&semver::Version::new(1, 5, 0)));
+    assert!(!ranged.version.matches(&semver::Version::new(2, 0, 0)));
+
+    assert!(ModDependency::parse("").is_err());
+}
@@ -0,0 +1,192 @@
+#[path = "helpers/world.rs"]
+mod world_helper;
+
+use engine_core::map::pathfinding::{PathCache, ProvinceCentroids, default_heuristic};
+use engine_core::map::{CellKey, Map, MapTopology, MovementProfile, ProvinceMap, SquareGridMap};
+use engine_core::systems::job::movement_ops::assign_move_path;
+use serde_json::json;
+
+fn square(x: i32, y: i32) -> CellKey {
+    CellKey::Square { x, y, z: 0 }
+}
+
+/// A 4-connected `width` x 3 grid with a river of water down column 1,
+/// except for a ford at (1, 2) when `ford` is set.
+fn river_map(width: i32, ford: bool) -> Map {
+    let mut grid = SquareGridMap::new();
+    for x in 0..width {
+        for y in 0..3 {
+            grid.add_cell(x, y, 0);
+        }
+    }
+    for x in 0..width {
+        for y in 0..3 {
+            for (nx, ny) in [(x + 1, y), (x, y + 1), (x - 1, y), (x, y - 1)] {
+                if (0..width).contains(&nx) && (0..3).contains(&ny) {
+                    grid.add_neighbor((x, y, 0), (nx, ny, 0));
+                }
+            }
+        }
+    }
+    let mut map = Map::new(Box::new(grid));
+    for y in 0..3 {
+        if !(ford && y == 2) {
+            map.set_cell_metadata(
+                &square(1, y),
+                json!({ "terrain": "water", "walkable": false }),
+            );
+        }
+    }
+    map
+}
+
+#[test]
+fn test_hex_heuristic_counts_hex_steps() {
+    let a = CellKey::Hex { q: 0, r: 0, z: 0 };
+    let b = CellKey::Hex { q: 2, r: -1, z: 1 };
+    assert_eq!(default_heuristic(&a, &b), 3.0);
+    assert_eq!(default_heuristic(&b, &b), 0.0);
+}
+
+#[test]
+fn test_province_centroids_estimate_borders() {
+    let mut provinces = ProvinceMap::new();
+    for id in ["west", "middle", "east"] {
+        provinces.add_cell(id);
+    }
+    provinces.add_neighbor("west", "middle");
+    provinces.add_neighbor("middle", "east");
+    let mut map = Map::new(Box::new(provinces));
+    let west = CellKey::Province {
+        id: "west".to_string(),
+    };
+    let middle = CellKey::Province {
+        id: "middle".to_string(),
+    };
+    let east = CellKey::Province {
+        id: "east".to_string(),
+    };
+    map.set_cell_metadata(&west, json!({ "centroid": { "x": 0.0, "y": 0.0 } }));
+    map.set_cell_metadata(&middle, json!({ "centroid": [10.0, 0.0] }));
+    map.set_cell_metadata(&east, json!({ "centroid": { "x": 15.0, "y": 0.0 } }));
+
+    let centroids = ProvinceCentroids::from_map(map.topology.as_ref());
+    assert_eq!(centroids.estimate(&west, &east), 1.5);
+    assert_eq!(
+        centroids.estimate(&west, &CellKey::Province { id: "x".into() }),
+        0.0
+    );
+
+    let result = map.find_path(&west, &east).expect("Path should exist");
+    assert_eq!(result.path, vec![west, middle, east]);
+}
+
+#[test]
+fn test_profiles_read_terrain_differently() {
+    let map = river_map(3, false);
+    let (start, goal) = (square(0, 0), square(2, 0));
+    assert!(map.find_path(&start, &goal).is_none());
+
+    let swim = map
+        .find_path_with_profile(&start, &goal, &MovementProfile::swimmer())
+        .expect("Swimmers cross water");
+    assert_eq!(swim.path, vec![start.clone(), square(1, 0), goal.clone()]);
+
+    let mut waders = MovementProfile::new("wader");
+    waders.passable_terrain.insert("water".to_string());
+    waders.terrain_costs.insert("water".to_string(), 10.0);
+    let wade = map
+        .find_path_with_profile(&start, &goal, &waders)
+        .expect("Waders cross water");
+    assert_eq!(wade.total_cost, 11.0);
+
+    let mut landlubber = MovementProfile::swimmer();
+    landlubber.blocked_terrain.insert("water".to_string());
+    assert!(
+        map.find_path_with_profile(&start, &goal, &landlubber)
+            .is_none()
+    );
+}
+
+#[test]
+fn test_cached_paths_follow_topology_revision() {
+    let mut map = river_map(3, true);
+    let (start, goal) = (square(0, 0), square(2, 0));
+    let around = map.find_path(&start, &goal).expect("Path via the ford");
+    assert_eq!(around.path.len(), 7);
+
+    let revision = map.revision();
+    map.set_cell_metadata(
+        &square(1, 2),
+        json!({ "terrain": "water", "walkable": false }),
+    );
+    assert!(map.revision() > revision);
+    assert!(
+        map.find_path(&start, &goal).is_none(),
+        "Metadata changes invalidate cached paths"
+    );
+
+    if let Some(grid) = map.topology.as_any_mut().downcast_mut::<SquareGridMap>() {
+        grid.set_cell_metadata(&square(1, 0), json!({ "terrain": "grass" }));
+    }
+    assert!(map.find_path(&start, &goal).is_none(), "Stale until marked");
+    map.mark_changed();
+    assert_eq!(map.find_path(&start, &goal).unwrap().path.len(), 3);
+}
+
+#[test]
+fn test_path_cache_drops_results_of_changed_profiles() {
+    let mut cache = PathCache::default();
+    let (start, goal) = (square(0, 0), square(1, 0));
+    let mut profile = MovementProfile::new("mule");
+    cache.insert(1, &profile, &start, &goal, None);
+    cache.insert(1, &MovementProfile::walker(), &start, &goal, None);
+    assert_eq!(cache.get(1, &profile, &start, &goal), Some(None));
+
+    profile.ignore_cost = true;
+    assert_eq!(cache.get(1, &profile, &start, &goal), None);
+    assert_eq!(cache.len(), 1);
+    assert_eq!(
+        cache.get(2, &MovementProfile::walker(), &start, &goal),
+        None
+    );
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn test_agents_path_with_their_movement_profile() {
+    let mut world = world_helper::make_test_world();
+    world.map = Some(river_map(3, false));
+    let (start, goal) = (square(0, 0), square(2, 0));
+    let fish = world.spawn_entity();
+    world
+        .set_component(
+            fish,
+            "Agent",
+            json!({ "entity_id": fish, "movement_profile": "swimmer" }),
+        )
+        .unwrap();
+    let cat = world.spawn_entity();
+    world
+        .set_component(cat, "Agent", json!({ "entity_id": cat }))
+        .unwrap();
+
+    assert_eq!(world.movement_profile_of(fish).name, "swimmer");
+    assert_eq!(world.movement_profile_of(cat).name, MovementProfile::WALKER);
+    assert!(world.find_path_for(cat, &start, &goal).is_none());
+
+    assign_move_path(&mut world, fish, &start, &goal);
+    let agent = world.get_component(fish, "Agent").unwrap();
+    assert_eq!(agent["move_path"].as_array().unwrap().len(), 2);
+
+    world.movement_profiles.unregister("swimmer");
+    assert_eq!(
+        world.movement_profile_of(fish).name,
+        MovementProfile::WALKER
+    );
+    assert!(
+        world
+            .find_path_with_profile(&start, &goal, "flyer")
+            .is_some()
+    );
+}
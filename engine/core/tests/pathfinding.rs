@@ -35,3 +35,38 @@ fn test_pathfinding_simple() {
     // Path length should be 5 (around the block)
     assert_eq!(result.path.len(), 5);
 }
+
+#[test]
+fn test_concurrent_searches_share_the_path_cache() {
+    let mut grid = SquareGridMap::new();
+    for x in 0..16 {
+        for y in 0..16 {
+            grid.add_cell(x, y, 0);
+            if x > 0 {
+                grid.add_neighbor((x, y, 0), (x - 1, y, 0));
+                grid.add_neighbor((x - 1, y, 0), (x, y, 0));
+            }
+            if y > 0 {
+                grid.add_neighbor((x, y, 0), (x, y - 1, 0));
+                grid.add_neighbor((x, y - 1, 0), (x, y, 0));
+            }
+        }
+    }
+    let map = Map::new(Box::new(grid));
+    let start = CellKey::Square { x: 0, y: 0, z: 0 };
+    let goals: Vec<CellKey> = (0..16)
+        .map(|y| CellKey::Square { x: 15, y, z: 0 })
+        .collect();
+
+    let lengths: Vec<usize> = std::thread::scope(|scope| {
+        let handles: Vec<_> = goals
+            .iter()
+            .map(|goal| scope.spawn(|| map.find_path(&start, goal).unwrap().path.len()))
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    for (y, len) in lengths.into_iter().enumerate() {
+        assert_eq!(len, 16 + y);
+        assert_eq!(map.find_path(&start, &goals[y]).unwrap().path.len(), len);
+    }
+}
@@ -82,3 +82,145 @@ fn test_ffi_spawn_entity_and_set_component_via_ffi() {
     assert_eq!(comp["x"], 42.0);
     assert_eq!(comp["y"], 99.0);
 }
+
+fn position_world() -> engine_core::ecs::World {
+    let mut registry = engine_core::ecs::registry::ComponentRegistry::new();
+    registry
+        .register_external_schema_from_json(
+            r#"{
+                "title": "Position",
+                "type": "object",
+                "properties": { "x": { "type": "number" }, "y": { "type": "number" } },
+                "required": ["x", "y"],
+                "modes": ["colony", "roguelike"]
+            }"#,
+        )
+        .unwrap();
+    engine_core::ecs::World::new(Arc::new(Mutex::new(registry)))
+}
+
+/// Reads and frees a string returned by the engine API.
+fn take_json(api: &engine_core::plugins::EngineApi, ptr: *mut std::os::raw::c_char) -> String {
+    assert!(!ptr.is_null());
+    let json = unsafe { std::ffi::CStr::from_ptr(ptr) }
+        .to_str()
+        .unwrap()
+        .to_string();
+    unsafe { (api.free_string)(ptr) };
+    json
+}
+
+#[test]
+fn test_engine_api_header_is_up_to_date() {
+    let header_path =
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../engine_plugin_abi.h");
+    let header = std::fs::read_to_string(header_path).unwrap();
+    assert_eq!(
+        header,
+        engine_core::plugins::abi::c_header(),
+        "engine/engine_plugin_abi.h is stale, run `cargo run -p xtask -- gen-plugin-header`"
+    );
+}
+
+#[test]
+fn test_engine_api_components_and_queries() {
+    use engine_core::plugins::{ENGINE_API_VERSION, EngineApi};
+    use std::ffi::CString;
+    use std::os::raw::c_void;
+
+    let api = EngineApi::new();
+    assert_eq!(api.struct_size as usize, std::mem::size_of::<EngineApi>());
+    assert_eq!(api.version, ENGINE_API_VERSION);
+
+    let mut world = position_world();
+    let world_ptr = &mut world as *mut _ as *mut c_void;
+    let position = CString::new("Position").unwrap();
+    let a = unsafe { (api.spawn_entity)(world_ptr) };
+    let b = unsafe { (api.spawn_entity)(world_ptr) };
+    for (entity, json) in [(a, r#"{"x": 1, "y": 2}"#), (b, r#"{"x": 5, "y": 2}"#)] {
+        let json = CString::new(json).unwrap();
+        let result =
+            unsafe { (api.set_component)(world_ptr, entity, position.as_ptr(), json.as_ptr()) };
+        assert_eq!(result, 0);
+    }
+
+    let component = take_json(&api, unsafe {
+        (api.get_component)(world_ptr, a, position.as_ptr())
+    });
+    let component: serde_json::Value = serde_json::from_str(&component).unwrap();
+    assert_eq!(component["x"], 1.0);
+
+    let spec = CString::new(
+        r#"{"with": ["Position"], "where": [{"component": "Position", "path": "x", "op": ">", "value": 3}]}"#,
+    )
+    .unwrap();
+    let matches = take_json(&api, unsafe {
+        (api.query_entities)(world_ptr, spec.as_ptr())
+    });
+    assert_eq!(matches, format!("[{b}]"));
+
+    assert_eq!(
+        unsafe { (api.remove_component)(world_ptr, a, position.as_ptr()) },
+        0
+    );
+    assert!(unsafe { (api.get_component)(world_ptr, a, position.as_ptr()) }.is_null());
+    let holders = take_json(&api, unsafe {
+        (api.get_entities_with_component)(world_ptr, position.as_ptr())
+    });
+    assert_eq!(holders, format!("[{b}]"));
+
+    assert_eq!(unsafe { (api.entity_exists)(world_ptr, b) }, 1);
+    assert_eq!(unsafe { (api.despawn_entity)(world_ptr, b) }, 0);
+    assert_eq!(unsafe { (api.entity_exists)(world_ptr, b) }, 0);
+    assert_eq!(unsafe { (api.despawn_entity)(world_ptr, b) }, -1);
+}
+
+#[test]
+fn test_engine_api_events_and_map() {
+    use engine_core::map::{Map, SquareGridMap};
+    use engine_core::plugins::EngineApi;
+    use std::ffi::CString;
+    use std::os::raw::c_void;
+
+    let api = EngineApi::new();
+    let mut world = position_world();
+    let mut grid = SquareGridMap::new();
+    for x in 0..3 {
+        grid.add_cell(x, 0, 0);
+    }
+    grid.add_neighbor((0, 0, 0), (1, 0, 0));
+    grid.add_neighbor((1, 0, 0), (2, 0, 0));
+    world.map = Some(Map::new(Box::new(grid)));
+    let world_ptr = &mut world as *mut _ as *mut c_void;
+
+    let bus = CString::new("PluginEvent").unwrap();
+    let payload = CString::new(r#"{"value": 7}"#).unwrap();
+    assert_eq!(
+        unsafe { (api.send_event)(world_ptr, bus.as_ptr(), payload.as_ptr()) },
+        0
+    );
+    // Events are delivered by the next update, and taken once
+    world.update_event_buses::<serde_json::Value>();
+    let events = take_json(&api, unsafe { (api.poll_events)(world_ptr, bus.as_ptr()) });
+    assert_eq!(events, r#"[{"value":7}]"#);
+    let events = take_json(&api, unsafe { (api.poll_events)(world_ptr, bus.as_ptr()) });
+    assert_eq!(events, "[]");
+    assert_eq!(unsafe { (api.get_turn)(world_ptr) }, 0);
+
+    let topology = take_json(&api, unsafe { (api.get_map_topology)(world_ptr) });
+    assert_eq!(topology, "square");
+    let start = CString::new(r#"{"Square": {"x": 0, "y": 0, "z": 0}}"#).unwrap();
+    let goal = CString::new(r#"{"Square": {"x": 2, "y": 0, "z": 0}}"#).unwrap();
+    let neighbors = take_json(&api, unsafe {
+        (api.get_neighbors)(world_ptr, start.as_ptr())
+    });
+    assert_eq!(neighbors, r#"[{"Square":{"x":1,"y":0,"z":0}}]"#);
+    let path = take_json(&api, unsafe {
+        (api.find_path)(world_ptr, start.as_ptr(), goal.as_ptr())
+    });
+    let path: serde_json::Value = serde_json::from_str(&path).unwrap();
+    assert_eq!(path["path"].as_array().unwrap().len(), 3);
+
+    let bad_cell = CString::new("not json").unwrap();
+    assert!(unsafe { (api.get_neighbors)(world_ptr, bad_cell.as_ptr()) }.is_null());
+}
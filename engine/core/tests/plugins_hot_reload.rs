@@ -0,0 +1,307 @@
+#[path = "helpers/world.rs"]
+mod world_helper;
+use world_helper::make_test_world;
+
+use engine_core::config::{GameConfig, PluginConfig};
+use engine_core::ecs::{SystemStage, World};
+use engine_core::plugins::{EngineApi, HOT_RELOAD_SYSTEM, NativePluginHost};
+use engine_core::worldgen::WorldgenRegistry;
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::rc::Rc;
+
+/// A plugin whose `counter` system adds VERSION to a tick count kept in its
+/// hot-reload state, and reports both, and the version that took the state over
+/// last, on the `HotReload` event bus. `shutdown` appends VERSION to SHUTDOWN_LOG.
+const PLUGIN_SOURCE: &str = r#"
+#include "engine_plugin_abi.h"
+#include <stdio.h>
+#include <stdlib.h>
+
+typedef struct { int ticks; int owner; } State;
+
+static State *state;
+static EngineApi *engine;
+static struct PluginVTable vtable;
+
+static void *hot_reload(void *old_state) {
+  state = old_state ? old_state : calloc(1, sizeof(State));
+  state->owner = VERSION;
+  return state;
+}
+
+static int init(EngineApi *api, void *world) {
+  (void)world;
+  engine = api;
+  return INIT_RESULT;
+}
+
+static void shutdown(void) {
+  FILE *log = fopen(SHUTDOWN_LOG, "a");
+  fprintf(log, "%d\n", VERSION);
+  fclose(log);
+}
+static void update(float dt) { (void)dt; }
+
+static void counter(WorldPtr world, float dt) {
+  (void)dt;
+  char payload[96];
+  state->ticks += VERSION;
+  snprintf(payload, sizeof payload,
+           "{\"version\": %d, \"ticks\": %d, \"owner\": %d}", VERSION,
+           state->ticks, state->owner);
+  engine->send_event(world, "HotReload", payload);
+}
+
+static SystemPlugin systems[] = {{"counter", counter}};
+
+static int register_systems(EngineApi *api, void *world, SystemPlugin **out,
+                            int *count) {
+  (void)api;
+  (void)world;
+  *out = systems;
+  *count = 1;
+  return REGISTER_RESULT;
+}
+
+static const char *plugin_worldgen_name(void) { return WORLDGEN_NAME; }
+
+__attribute__((constructor)) static void init_vtable(void) {
+  vtable.abi_version = PLUGIN_ABI_VERSION;
+  vtable.init = init;
+  vtable.shutdown = shutdown;
+  vtable.update = update;
+  vtable.worldgen_name = plugin_worldgen_name;
+  vtable.register_systems = register_systems;
+  vtable.hot_reload = hot_reload;
+}
+
+__attribute__((visibility("default"))) struct PluginVTable *PLUGIN_VTABLE = &vtable;
+"#;
+
+/// Build a version of the test plugin into `dir`, returning the library path.
+fn build_plugin(dir: &Path, version: u32, init_result: i32, register_result: i32) -> PathBuf {
+    let header_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+    let source = dir.join(format!("hot_v{version}.c"));
+    std::fs::write(&source, PLUGIN_SOURCE).unwrap();
+    let out = dir.join(format!("libhot_v{version}.so"));
+    let status = Command::new("cc")
+        .args(["-shared", "-fPIC"])
+        .arg(format!("-I{}", header_dir.display()))
+        .arg(format!("-DVERSION={version}"))
+        .arg(format!("-DINIT_RESULT={init_result}"))
+        .arg(format!("-DREGISTER_RESULT={register_result}"))
+        .arg(format!(
+            "-DSHUTDOWN_LOG=\"{}\"",
+            shutdown_log(dir).display()
+        ))
+        .arg(format!("-DWORLDGEN_NAME=\"hot_gen_v{version}\""))
+        .arg(&source)
+        .arg("-o")
+        .arg(&out)
+        .status()
+        .expect("a C compiler (cc) is needed to build the hot-reload test plugin");
+    assert!(status.success(), "failed to build {source:?}");
+    out
+}
+
+/// File the test plugin's `shutdown` writes to.
+fn shutdown_log(dir: &Path) -> PathBuf {
+    dir.join("shutdown.log")
+}
+
+/// Versions whose `shutdown` ran, in order.
+fn shutdowns(dir: &Path) -> Vec<u64> {
+    std::fs::read_to_string(shutdown_log(dir))
+        .unwrap_or_default()
+        .lines()
+        .map(|line| line.parse().unwrap())
+        .collect()
+}
+
+/// Replace the watched library with another build, making sure its
+/// modification time changes.
+fn install(build: &Path, target: &Path) {
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    std::fs::copy(build, target).unwrap();
+}
+
+/// Run one tick and return the `(version, ticks)` the plugin reported.
+fn tick(world_rc: &Rc<RefCell<World>>) -> Vec<(u64, u64)> {
+    tick_with_owner(world_rc)
+        .into_iter()
+        .map(|(version, ticks, _)| (version, ticks))
+        .collect()
+}
+
+/// Run one tick and return the `(version, ticks, owner)` the plugin reported.
+fn tick_with_owner(world_rc: &Rc<RefCell<World>>) -> Vec<(u64, u64, u64)> {
+    World::tick(Rc::clone(world_rc));
+    let mut world = world_rc.borrow_mut();
+    world
+        .take_events("HotReload")
+        .iter()
+        .map(|e| {
+            let field = |name: &str| e[name].as_u64().unwrap();
+            (field("version"), field("ticks"), field("owner"))
+        })
+        .collect()
+}
+
+#[test]
+fn test_hot_reload_keeps_state_and_rolls_back_failed_init() {
+    let dir = tempfile::tempdir().unwrap();
+    let v1 = build_plugin(dir.path(), 1, 0, 0);
+    let v2 = build_plugin(dir.path(), 2, 0, 0);
+    let broken = build_plugin(dir.path(), 3, -1, 0);
+    let unregistrable = build_plugin(dir.path(), 4, 0, -1);
+    let watched = dir.path().join("libhot.so");
+    std::fs::copy(&v1, &watched).unwrap();
+
+    let mut api = EngineApi::new();
+    let mut worldgen = WorldgenRegistry::new();
+    let world_rc = Rc::new(RefCell::new(make_test_world()));
+    let mut host = NativePluginHost::with_copy_dir(dir.path().join("copies"));
+
+    let name = unsafe {
+        host.load(
+            &watched,
+            &mut api,
+            &mut world_rc.borrow_mut(),
+            &mut worldgen,
+        )
+        .unwrap()
+    };
+    assert_eq!(name, "hot");
+    assert_eq!(host.systems("hot"), vec!["counter"]);
+    assert_eq!(worldgen.list_names(), vec!["hot_gen_v1"]);
+    assert!(host.poll_changes().is_empty());
+    assert_eq!(tick(&world_rc), vec![(1, 1)]);
+    assert_eq!(tick(&world_rc), vec![(1, 2)]);
+
+    // A new build replaces the systems and worldgen entry and keeps the state
+    install(&v2, &watched);
+    assert_eq!(host.poll_changes(), vec!["hot"]);
+    let results =
+        unsafe { host.reload_changed(&mut api, &mut world_rc.borrow_mut(), &mut worldgen) };
+    assert_eq!(results, vec![("hot".to_string(), Ok(()))]);
+    assert!(host.poll_changes().is_empty());
+    assert_eq!(worldgen.list_names(), vec!["hot_gen_v2"]);
+    assert_eq!(tick(&world_rc), vec![(2, 4)]);
+
+    // A build failing init is rejected and the previous version keeps running
+    install(&broken, &watched);
+    let err = unsafe { host.reload("hot", &mut api, &mut world_rc.borrow_mut(), &mut worldgen) }
+        .unwrap_err();
+    assert!(err.contains("init failed"), "{err}");
+    assert!(host.poll_changes().is_empty());
+    assert_eq!(worldgen.list_names(), vec!["hot_gen_v2"]);
+    assert_eq!(
+        tick_with_owner(&world_rc),
+        vec![(2, 6, 2)],
+        "The failed build never got the state"
+    );
+    assert!(shutdowns(dir.path()).is_empty());
+
+    // A build failing registration after init is shut down and never gets the state
+    install(&unregistrable, &watched);
+    let err = unsafe { host.reload("hot", &mut api, &mut world_rc.borrow_mut(), &mut worldgen) }
+        .unwrap_err();
+    assert!(err.contains("register_systems failed"), "{err}");
+    assert_eq!(shutdowns(dir.path()), vec![4]);
+    assert_eq!(tick_with_owner(&world_rc), vec![(2, 8, 2)]);
+
+    host.unload("hot", &mut world_rc.borrow_mut(), &mut worldgen)
+        .unwrap();
+    assert_eq!(shutdowns(dir.path()), vec![4, 2]);
+    assert!(!host.is_loaded("hot"));
+    assert!(worldgen.list_names().is_empty());
+    assert!(tick(&world_rc).is_empty());
+    assert_eq!(
+        std::fs::read_dir(dir.path().join("copies"))
+            .unwrap()
+            .count(),
+        0
+    );
+}
+
+#[test]
+fn test_watched_plugins_reload_on_tick() {
+    let dir = tempfile::tempdir().unwrap();
+    let v1 = build_plugin(dir.path(), 1, 0, 0);
+    let v2 = build_plugin(dir.path(), 2, 0, 0);
+    let broken = build_plugin(dir.path(), 3, -1, 0);
+    let watched = dir.path().join("libhot.so");
+    std::fs::copy(&v1, &watched).unwrap();
+    let config = GameConfig {
+        title: "Hot".to_string(),
+        version: "0.1".to_string(),
+        allowed_modes: vec!["colony".to_string()],
+        plugins: Some(PluginConfig {
+            native: vec![watched.to_string_lossy().into_owned()],
+        }),
+        seed: None,
+    };
+
+    let mut api = Box::new(EngineApi::new());
+    let worldgen = Rc::new(RefCell::new(WorldgenRegistry::new()));
+    let world_rc = Rc::new(RefCell::new(make_test_world()));
+    let mut host = NativePluginHost::with_copy_dir(dir.path().join("copies"));
+    let reloads = Rc::new(RefCell::new(Vec::new()));
+    {
+        let mut world = world_rc.borrow_mut();
+        let names = unsafe {
+            host.load_config(&config, &mut api, &mut world, &mut worldgen.borrow_mut())
+                .unwrap()
+        };
+        assert_eq!(names, vec!["hot"]);
+        let reloads = Rc::clone(&reloads);
+        unsafe {
+            host.watch(
+                &mut world,
+                api,
+                Rc::clone(&worldgen),
+                move |name, result| {
+                    reloads
+                        .borrow_mut()
+                        .push((name.to_string(), result.is_ok()));
+                },
+            )
+        };
+        assert_eq!(
+            world.system_stage(HOT_RELOAD_SYSTEM),
+            SystemStage::PreUpdate
+        );
+    }
+    assert_eq!(tick(&world_rc), vec![(1, 1)]);
+
+    // The rebuilt plugin is picked up before the tick's systems run
+    install(&v2, &watched);
+    assert_eq!(tick(&world_rc), vec![(2, 3)]);
+    assert_eq!(worldgen.borrow().list_names(), vec!["hot_gen_v2"]);
+
+    // A broken build is reported once and the running version keeps going
+    install(&broken, &watched);
+    assert_eq!(tick(&world_rc), vec![(2, 5)]);
+    assert_eq!(tick(&world_rc), vec![(2, 7)]);
+    assert_eq!(
+        *reloads.borrow(),
+        vec![("hot".to_string(), true), ("hot".to_string(), false)]
+    );
+}
+
+#[test]
+fn test_reload_unknown_plugin_fails() {
+    let mut host = NativePluginHost::new();
+    let mut world = make_test_world();
+    let mut worldgen = WorldgenRegistry::new();
+    let err = unsafe { host.reload("missing", &mut EngineApi::new(), &mut world, &mut worldgen) }
+        .unwrap_err();
+    assert_eq!(err, "Plugin 'missing' is not loaded");
+    assert!(host.unload("missing", &mut world, &mut worldgen).is_err());
+    assert_eq!(
+        NativePluginHost::plugin_name("plugins/foo/libfoo.so"),
+        "foo"
+    );
+}
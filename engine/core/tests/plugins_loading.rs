@@ -24,10 +24,7 @@ fn test_loads_and_initializes_plugin() {
     let mut world = engine_core::ecs::World::new(registry.clone());
     let world_ptr = &mut world as *mut _ as *mut c_void;
 
-    let mut engine_api = engine_core::plugins::EngineApi {
-        spawn_entity: engine_core::plugins::ffi_spawn_entity,
-        set_component: engine_core::plugins::ffi_set_component,
-    };
+    let mut engine_api = engine_core::plugins::EngineApi::new();
 
     let plugin_path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
         .parent()
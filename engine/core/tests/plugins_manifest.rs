@@ -76,10 +76,7 @@ fn test_load_plugin_with_manifest_and_metadata() {
     "#;
     std::fs::write(&manifest_path, manifest_content).unwrap();
 
-    let mut engine_api = engine_core::plugins::EngineApi {
-        spawn_entity: engine_core::plugins::ffi_spawn_entity,
-        set_component: engine_core::plugins::ffi_set_component,
-    };
+    let mut engine_api = engine_core::plugins::EngineApi::new();
 
     // This should not panic or return an error
     let result = unsafe { load_plugin_with_manifest(&manifest_path, &mut engine_api, world_ptr) };
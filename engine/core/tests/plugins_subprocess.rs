@@ -1,10 +1,17 @@
 #[path = "helpers/plugins.rs"]
 mod plugins_helper;
 use plugins_helper::{plugin_bin_path, test_socket_path};
+#[path = "helpers/world.rs"]
+mod world_helper;
+use world_helper::make_test_world;
 
+use engine_core::ecs::World;
 use engine_core::plugins::manager::PluginManager;
-use engine_core::plugins::subprocess::{PluginRequest, PluginResponse};
+use engine_core::plugins::subprocess::{PluginRequest, PluginResponse, WorldOp};
+use serde_json::json;
+use std::cell::RefCell;
 use std::fs;
+use std::rc::Rc;
 use std::thread;
 use std::time::Duration;
 
@@ -16,6 +23,7 @@ fn test_subprocess_plugin_lifecycle() {
     let socket_path = test_socket_path("lifecycle");
     let _ = fs::remove_file(&socket_path);
     let mut manager = PluginManager::new();
+    let mut world = make_test_world();
 
     manager
         .launch_plugin(TEST_PLUGIN_NAME.to_string(), &bin, &socket_path)
@@ -50,7 +58,7 @@ fn test_subprocess_plugin_lifecycle() {
     assert!(matches!(resp, PluginResponse::Reloaded));
 
     manager
-        .reload_plugin(TEST_PLUGIN_NAME, &bin, &socket_path)
+        .reload_plugin(TEST_PLUGIN_NAME, &bin, &socket_path, &mut world)
         .expect("Failed to hot reload plugin");
     thread::sleep(Duration::from_millis(100));
     let resp = manager
@@ -59,11 +67,39 @@ fn test_subprocess_plugin_lifecycle() {
     assert!(matches!(resp, PluginResponse::Initialized));
 
     manager
-        .shutdown_plugin(TEST_PLUGIN_NAME)
+        .shutdown_plugin(TEST_PLUGIN_NAME, &mut world)
         .expect("Failed to shutdown plugin");
     let _ = fs::remove_file(&socket_path);
 }
 
+#[test]
+fn test_failed_reload_keeps_old_plugin() {
+    let bin = plugin_bin_path();
+    let socket_path = test_socket_path("failed_reload");
+    let mut manager = PluginManager::new();
+    let mut world = make_test_world();
+    manager
+        .launch_plugin(TEST_PLUGIN_NAME.to_string(), &bin, &socket_path)
+        .expect("Failed to launch plugin");
+
+    let err = manager
+        .reload_plugin(
+            TEST_PLUGIN_NAME,
+            "/nonexistent/plugin_binary",
+            &socket_path,
+            &mut world,
+        )
+        .unwrap_err();
+    assert!(err.contains("Failed to spawn"), "{err}");
+    assert!(!std::path::Path::new(&socket_path).exists());
+
+    let resp = manager
+        .send(TEST_PLUGIN_NAME, &PluginRequest::Initialize)
+        .expect("Old plugin should still be running");
+    assert!(matches!(resp, PluginResponse::Initialized));
+    manager.shutdown_all();
+}
+
 #[test]
 fn test_plugin_shutdown_all() {
     let bin = plugin_bin_path();
@@ -114,3 +150,224 @@ fn test_plugin_error_on_send_to_missing() {
         .unwrap_err();
     assert!(err.contains("Plugin not found"));
 }
+
+fn run_command(command: &str, data: serde_json::Value) -> PluginRequest {
+    PluginRequest::RunCommand {
+        command: command.to_string(),
+        data,
+    }
+}
+
+#[test]
+fn test_plugin_systems_read_and_write_world() {
+    let bin = plugin_bin_path();
+    let socket_path = test_socket_path("systems");
+    let mut manager = PluginManager::new();
+    manager
+        .launch_plugin(TEST_PLUGIN_NAME.to_string(), &bin, &socket_path)
+        .expect("Failed to launch plugin");
+    let world_rc = Rc::new(RefCell::new(make_test_world()));
+
+    let resp = manager
+        .send_with_world(
+            TEST_PLUGIN_NAME,
+            &run_command("spawn", json!({ "current": 10.0, "max": 10.0 })),
+            &mut world_rc.borrow_mut(),
+        )
+        .expect("Failed to send spawn command");
+    let PluginResponse::CommandResult { result } = resp else {
+        panic!("Unexpected response: {resp:?}");
+    };
+    assert_eq!(result["set"], json!(true));
+    let entity = result["entity"].as_u64().unwrap() as u32;
+
+    let systems = manager
+        .register_systems(TEST_PLUGIN_NAME, &mut world_rc.borrow_mut())
+        .expect("Failed to register systems");
+    assert_eq!(systems, vec!["bleed"]);
+    for _ in 0..3 {
+        World::tick(Rc::clone(&world_rc));
+    }
+    assert_eq!(
+        world_rc.borrow().get_component(entity, "Health").unwrap()["current"],
+        json!(7.0)
+    );
+
+    // Systems keep running against the relaunched subprocess
+    manager
+        .reload_plugin(
+            TEST_PLUGIN_NAME,
+            &bin,
+            &socket_path,
+            &mut world_rc.borrow_mut(),
+        )
+        .expect("Failed to reload plugin");
+    World::tick(Rc::clone(&world_rc));
+    assert_eq!(
+        world_rc.borrow().get_component(entity, "Health").unwrap()["current"],
+        json!(6.0)
+    );
+
+    manager.unregister_systems(TEST_PLUGIN_NAME, &mut world_rc.borrow_mut());
+    World::tick(Rc::clone(&world_rc));
+    assert_eq!(
+        world_rc.borrow().get_component(entity, "Health").unwrap()["current"],
+        json!(6.0)
+    );
+    manager.shutdown_all();
+}
+
+#[test]
+fn test_plugin_reload_and_shutdown_update_systems() {
+    let bin = plugin_bin_path();
+    let socket_path = test_socket_path("reload_systems");
+    let _ = fs::remove_file(&socket_path);
+    // A copy named `*_heal` offers `heal` instead of `bleed`
+    let heal_bin = std::env::temp_dir().join("rust_test_plugin_heal");
+    fs::copy(&bin, &heal_bin).expect("Failed to copy plugin binary");
+    let mut manager = PluginManager::new();
+    manager
+        .launch_plugin(TEST_PLUGIN_NAME.to_string(), &bin, &socket_path)
+        .expect("Failed to launch plugin");
+    let world_rc = Rc::new(RefCell::new(make_test_world()));
+
+    let resp = manager
+        .send_with_world(
+            TEST_PLUGIN_NAME,
+            &run_command("spawn", json!({ "current": 5.0, "max": 10.0 })),
+            &mut world_rc.borrow_mut(),
+        )
+        .expect("Failed to send spawn command");
+    let PluginResponse::CommandResult { result } = resp else {
+        panic!("Unexpected response: {resp:?}");
+    };
+    let entity = result["entity"].as_u64().unwrap() as u32;
+    let health = || world_rc.borrow().get_component(entity, "Health").unwrap()["current"].clone();
+
+    manager
+        .register_systems(TEST_PLUGIN_NAME, &mut world_rc.borrow_mut())
+        .expect("Failed to register systems");
+    World::tick(Rc::clone(&world_rc));
+    assert_eq!(health(), json!(4.0));
+
+    // The new binary's systems replace the old ones
+    manager
+        .reload_plugin(
+            TEST_PLUGIN_NAME,
+            &heal_bin,
+            &socket_path,
+            &mut world_rc.borrow_mut(),
+        )
+        .expect("Failed to reload plugin");
+    World::tick(Rc::clone(&world_rc));
+    assert_eq!(health(), json!(5.0), "Only heal runs after the reload");
+
+    manager
+        .shutdown_plugin(TEST_PLUGIN_NAME, &mut world_rc.borrow_mut())
+        .expect("Failed to shutdown plugin");
+    World::tick(Rc::clone(&world_rc));
+    assert_eq!(health(), json!(5.0), "No systems run after shutdown");
+    let _ = fs::remove_file(&heal_bin);
+    let _ = fs::remove_file(&socket_path);
+}
+
+#[test]
+fn test_world_requests_without_world_access_fail_per_op() {
+    let bin = plugin_bin_path();
+    let socket_path = test_socket_path("no_world");
+    let mut manager = PluginManager::new();
+    manager
+        .launch_plugin(TEST_PLUGIN_NAME.to_string(), &bin, &socket_path)
+        .expect("Failed to launch plugin");
+
+    let resp = manager
+        .send(TEST_PLUGIN_NAME, &run_command("spawn", json!({})))
+        .expect("Failed to send spawn command");
+    let PluginResponse::CommandResult { result } = resp else {
+        panic!("Unexpected response: {resp:?}");
+    };
+    assert_eq!(result["entity"], json!(null));
+    assert!(manager.plugin_failure(TEST_PLUGIN_NAME).is_none());
+    manager.shutdown_all();
+}
+
+#[test]
+fn test_crashing_plugin_is_isolated() {
+    let bin = plugin_bin_path();
+    let socket_path = test_socket_path("crash");
+    let mut manager = PluginManager::new();
+    manager
+        .launch_plugin(TEST_PLUGIN_NAME.to_string(), &bin, &socket_path)
+        .expect("Failed to launch plugin");
+    let world_rc = Rc::new(RefCell::new(make_test_world()));
+    manager
+        .register_systems(TEST_PLUGIN_NAME, &mut world_rc.borrow_mut())
+        .unwrap();
+
+    let err = manager
+        .send(TEST_PLUGIN_NAME, &run_command("crash", json!(null)))
+        .unwrap_err();
+    assert!(err.contains("exited") || err.contains("closed"), "{err}");
+    assert!(manager.plugin_failure(TEST_PLUGIN_NAME).is_some());
+
+    // Its systems are skipped and later requests fail fast
+    World::tick(Rc::clone(&world_rc));
+    let err = manager
+        .send(TEST_PLUGIN_NAME, &PluginRequest::Initialize)
+        .unwrap_err();
+    assert!(err.contains("not running"), "{err}");
+}
+
+#[test]
+fn test_hanging_and_garbled_plugins_are_stopped() {
+    let bin = plugin_bin_path();
+    let mut manager = PluginManager::new();
+    manager.set_timeout(Duration::from_millis(200));
+    manager
+        .launch_plugin("hang".to_string(), &bin, &test_socket_path("hang"))
+        .expect("Failed to launch plugin");
+    manager
+        .launch_plugin("garbage".to_string(), &bin, &test_socket_path("garbage"))
+        .expect("Failed to launch plugin");
+
+    let err = manager
+        .send("hang", &run_command("hang", json!(null)))
+        .unwrap_err();
+    assert!(err.contains("timed out"), "{err}");
+    let err = manager
+        .send("garbage", &run_command("garbage", json!(null)))
+        .unwrap_err();
+    assert!(err.contains("Malformed"), "{err}");
+    assert!(manager.plugin_failure("garbage").is_some());
+    manager.shutdown_all();
+}
+
+#[test]
+fn test_world_op_apply() {
+    let mut world = make_test_world();
+    let entity = WorldOp::Spawn.apply(&mut world).unwrap().as_u64().unwrap() as u32;
+    WorldOp::SetComponent {
+        entity,
+        name: "Health".to_string(),
+        value: json!({ "current": 3.0, "max": 5.0 }),
+    }
+    .apply(&mut world)
+    .unwrap();
+    let rows = WorldOp::Query {
+        query: json!({ "with": ["Health"] }),
+    }
+    .apply(&mut world)
+    .unwrap();
+    assert_eq!(rows[0]["entity"], json!(entity));
+    assert_eq!(rows[0]["components"]["Health"]["current"], json!(3.0));
+    assert!(
+        WorldOp::Query {
+            query: json!({ "with": 5 })
+        }
+        .apply(&mut world)
+        .unwrap_err()
+        .starts_with("Invalid query")
+    );
+    WorldOp::Despawn { entity }.apply(&mut world).unwrap();
+    assert!(WorldOp::Despawn { entity }.apply(&mut world).is_err());
+}
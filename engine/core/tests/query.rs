@@ -0,0 +1,333 @@
+#[path = "helpers/world.rs"]
+mod world_helper;
+use world_helper::make_test_world;
+
+use engine_core::ecs::system::System;
+use engine_core::ecs::world::World;
+use engine_core::ecs::world::wasm::WasmWorld;
+use engine_core::ecs::{Component, FieldOp, MigrationError, Query};
+use engine_core::systems::stat_calculation::StatCalculationSystem;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+
+fn spawn_with_health(world: &mut World, current: f64) -> u32 {
+    let eid = world.spawn_entity();
+    world
+        .set_component(eid, "Health", json!({ "current": current, "max": 10.0 }))
+        .unwrap();
+    eid
+}
+
+#[test]
+fn test_with_without_optional() {
+    let mut world = make_test_world();
+    let alive = spawn_with_health(&mut world, 5.0);
+    let dead = spawn_with_health(&mut world, 0.0);
+    world.set_component(dead, "Corpse", json!({})).unwrap();
+    let tagged = spawn_with_health(&mut world, 8.0);
+    world
+        .set_component(tagged, "Type", json!({ "kind": "dwarf" }))
+        .unwrap();
+    world.spawn_entity();
+
+    let query = Query::new()
+        .with("Health")
+        .without("Corpse")
+        .optional("Type");
+    assert_eq!(query.entities(&world), vec![alive, tagged]);
+
+    let rows = query.rows(&world);
+    assert_eq!(rows.len(), 2);
+    assert!(!rows[0].components.contains_key("Type"));
+    assert_eq!(rows[1].components["Type"], json!({ "kind": "dwarf" }));
+    assert_eq!(rows[1].components["Health"]["current"], json!(8.0));
+
+    assert_eq!(Query::new().entities(&world).len(), 4);
+}
+
+#[test]
+fn test_field_predicates_and_filters() {
+    let mut world = make_test_world();
+    let low = spawn_with_health(&mut world, 2.0);
+    let high = spawn_with_health(&mut world, 9.0);
+    world
+        .set_component(high, "Type", json!({ "kind": "goblin" }))
+        .unwrap();
+
+    let wounded = Query::new().where_field("Health", "current", FieldOp::Lt, json!(5));
+    assert_eq!(wounded.entities(&world), vec![low]);
+    let goblins = Query::new().where_field("Type", "kind", FieldOp::Eq, json!("goblin"));
+    assert_eq!(goblins.entities(&world), vec![high]);
+    let missing_path = Query::new().where_field("Health", "nope", FieldOp::Ne, json!(1));
+    assert!(missing_path.entities(&world).is_empty());
+
+    let healthy = Query::new().filter("Health", |h| h["current"].as_f64() > Some(5.0));
+    assert_eq!(healthy.entities(&world), vec![high]);
+}
+
+#[test]
+fn test_changed_and_added_since_tick() {
+    let mut world = make_test_world();
+    let a = spawn_with_health(&mut world, 5.0);
+    let b = spawn_with_health(&mut world, 5.0);
+    let tick = world.change_tick();
+
+    // Rewriting the same value is not a change.
+    world
+        .set_component(a, "Health", json!({ "current": 5.0, "max": 10.0 }))
+        .unwrap();
+    world
+        .set_component(b, "Health", json!({ "current": 4.0, "max": 10.0 }))
+        .unwrap();
+    let c = spawn_with_health(&mut world, 7.0);
+
+    let changed = Query::new().changed("Health").since(tick);
+    assert_eq!(changed.entities(&world), vec![b, c]);
+    let added = Query::new().added("Health").since(tick);
+    assert_eq!(added.entities(&world), vec![c]);
+
+    let ticks = world.component_ticks(b, "Health").unwrap();
+    assert!(ticks.changed > ticks.added);
+    world.remove_component(a, "Health").unwrap();
+    assert!(world.component_changed_since(a, "Health", tick));
+}
+
+#[test]
+fn test_failed_resource_edits_are_not_changes() {
+    let mut world = make_test_world();
+    let eid = world.spawn_entity();
+    world
+        .set_component(eid, "Resource", json!({ "kind": "wood", "amount": 2.0 }))
+        .unwrap();
+    world
+        .set_component(eid, "Stockpile", json!({ "resources": { "wood": 2.0 } }))
+        .unwrap();
+    let resource = world.component_ticks(eid, "Resource");
+    let stockpile = world.component_ticks(eid, "Stockpile");
+
+    assert!(world.modify_resource_amount(eid, "wood", -5.0).is_err());
+    assert!(world.modify_resource_amount(eid, "stone", 1.0).is_err());
+    assert!(world.modify_stockpile_resource(eid, "wood", -5.0).is_err());
+    world.damage_entity(eid, 0.0);
+    assert_eq!(world.component_ticks(eid, "Resource"), resource);
+    assert_eq!(world.component_ticks(eid, "Stockpile"), stockpile);
+
+    world.modify_resource_amount(eid, "wood", 1.0).unwrap();
+    assert_ne!(world.component_ticks(eid, "Resource"), resource);
+}
+
+/// Records which entities it sees as having changed Health.
+struct HealthWatcher(Arc<Mutex<Vec<Vec<u32>>>>);
+
+impl System for HealthWatcher {
+    fn name(&self) -> &'static str {
+        "HealthWatcher"
+    }
+
+    fn run(&mut self, world: &mut World) {
+        let seen = Query::new().changed("Health").entities(world);
+        self.0.lock().unwrap().push(seen);
+    }
+}
+
+#[test]
+fn test_systems_see_changes_since_their_last_run() {
+    let mut world = make_test_world();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    world.register_system(HealthWatcher(Arc::clone(&seen)));
+    let a = spawn_with_health(&mut world, 5.0);
+    let b = spawn_with_health(&mut world, 5.0);
+
+    world.run_system("HealthWatcher").unwrap();
+    world.run_system("HealthWatcher").unwrap();
+    world
+        .set_component(b, "Health", json!({ "current": 1.0, "max": 10.0 }))
+        .unwrap();
+    world.run_system("HealthWatcher").unwrap();
+    world.reset_change_detection();
+    world.run_system("HealthWatcher").unwrap();
+
+    assert_eq!(
+        *seen.lock().unwrap(),
+        vec![vec![a, b], vec![], vec![b], vec![a, b]]
+    );
+    assert_eq!(world.system_last_run(), None, "outside of systems");
+}
+
+#[test]
+fn test_stat_calculation_skips_unchanged_entities() {
+    let mut world = make_test_world();
+    world.current_mode = "roguelike".to_string();
+    world.register_system(StatCalculationSystem);
+    let eid = world.spawn_entity();
+    world
+        .set_component(eid, "BaseStats", json!({ "strength": 10.0 }))
+        .unwrap();
+    world.run_system("StatCalculationSystem").unwrap();
+    assert_eq!(
+        world.get_component(eid, "Stats").unwrap()["strength"],
+        json!(10.0)
+    );
+
+    // An unchanged entity is skipped, so its Stats tick stays put.
+    let stats_tick = world.component_ticks(eid, "Stats").unwrap().changed;
+    world.run_system("StatCalculationSystem").unwrap();
+    assert_eq!(
+        world.component_ticks(eid, "Stats").unwrap().changed,
+        stats_tick
+    );
+
+    world
+        .set_component(eid, "BaseStats", json!({ "strength": 12.0 }))
+        .unwrap();
+    world.run_system("StatCalculationSystem").unwrap();
+    assert_eq!(
+        world.get_component(eid, "Stats").unwrap()["strength"],
+        json!(12.0)
+    );
+}
+
+/// Typed stand-in for the `BaseStats` component.
+#[derive(Serialize, Deserialize)]
+struct BaseStats {
+    strength: f64,
+}
+
+impl Component for BaseStats {
+    fn generate_schema() -> Option<schemars::Schema> {
+        None
+    }
+
+    fn migrate(_from_version: semver::Version, _data: &[u8]) -> Result<Self, MigrationError> {
+        Err(MigrationError::DataFormatError)
+    }
+}
+
+fn stat_world() -> (World, u32) {
+    let mut world = make_test_world();
+    world.current_mode = "roguelike".to_string();
+    world.register_system(StatCalculationSystem);
+    let eid = world.spawn_entity();
+    world
+        .set_component(eid, "BaseStats", json!({ "strength": 10.0 }))
+        .unwrap();
+    world.run_system("StatCalculationSystem").unwrap();
+    (world, eid)
+}
+
+#[test]
+fn test_stat_calculation_sees_typed_writes() {
+    let (mut world, eid) = stat_world();
+    world
+        .register_typed_component::<BaseStats>("BaseStats")
+        .unwrap();
+    world.run_system("StatCalculationSystem").unwrap();
+
+    for (_, stats) in world
+        .typed_component_mut::<BaseStats>("BaseStats")
+        .unwrap()
+        .iter_mut()
+    {
+        stats.strength = 14.0;
+    }
+    world.run_system("StatCalculationSystem").unwrap();
+    assert_eq!(
+        world.get_component(eid, "Stats").unwrap()["strength"],
+        json!(14.0)
+    );
+
+    world
+        .typed_value_mut::<BaseStats>(eid, "BaseStats")
+        .unwrap()
+        .strength = 16.0;
+    world.run_system("StatCalculationSystem").unwrap();
+    assert_eq!(
+        world.get_component(eid, "Stats").unwrap()["strength"],
+        json!(16.0)
+    );
+}
+
+#[test]
+fn test_stat_calculation_sees_restored_and_direct_writes() {
+    let (mut world, eid) = stat_world();
+    let snapshot = world.snapshot().unwrap();
+    world
+        .set_component(eid, "BaseStats", json!({ "strength": 12.0 }))
+        .unwrap();
+    world.run_system("StatCalculationSystem").unwrap();
+
+    // Restoring forgets system runs, so the next run recomputes every entity,
+    // even one whose Stats were overwritten without a change tick
+    world.restore(&snapshot).unwrap();
+    world
+        .components
+        .get_mut("Stats")
+        .unwrap()
+        .get_mut(&eid)
+        .unwrap()["strength"] = json!(3.0);
+    world.run_system("StatCalculationSystem").unwrap();
+    assert_eq!(
+        world.get_component(eid, "Stats").unwrap()["strength"],
+        json!(10.0)
+    );
+
+    world
+        .components
+        .get_mut("BaseStats")
+        .unwrap()
+        .get_mut(&eid)
+        .unwrap()["strength"] = json!(11.0);
+    world.mark_component_changed(eid, "BaseStats");
+    world.run_system("StatCalculationSystem").unwrap();
+    assert_eq!(
+        world.get_component(eid, "Stats").unwrap()["strength"],
+        json!(11.0)
+    );
+}
+
+#[test]
+fn test_query_deserializes_from_json() {
+    let mut world = make_test_world();
+    let low = spawn_with_health(&mut world, 1.0);
+    let dead = spawn_with_health(&mut world, 0.0);
+    world.set_component(dead, "Corpse", json!({})).unwrap();
+
+    let query: Query = serde_json::from_value(json!({
+        "with": ["Health"],
+        "without": ["Corpse"],
+        "where": [{ "component": "Health", "path": "current", "op": "<=", "value": 1 }],
+        "changed": ["Health"],
+        "since": 0
+    }))
+    .unwrap();
+    assert_eq!(query.entities(&world), vec![low]);
+}
+
+#[test]
+fn test_wasm_world_query() {
+    let mut world = WasmWorld::new();
+    let a = world.spawn_entity();
+    let b = world.spawn_entity();
+    world
+        .set_component(a, "Health", r#"{"current": 3, "max": 10}"#)
+        .unwrap();
+    world
+        .set_component(b, "Health", r#"{"current": 8, "max": 10}"#)
+        .unwrap();
+    let tick = world.change_tick();
+    world
+        .set_component(b, "Health", r#"{"current": 7, "max": 10}"#)
+        .unwrap();
+
+    let query = Query::new()
+        .with("Health")
+        .where_field("Health", "current", FieldOp::Gt, json!(5));
+    assert_eq!(query.entities(&world), vec![b]);
+    assert_eq!(
+        Query::new().changed("Health").since(tick).entities(&world),
+        vec![b]
+    );
+    world.despawn_entity(b);
+    assert_eq!(Query::new().with("Health").entities(&world), vec![a]);
+}
@@ -0,0 +1,172 @@
+#[path = "helpers/world.rs"]
+mod world_helper;
+use world_helper::make_test_world;
+
+use engine_core::ecs::ReplayError;
+use engine_core::ecs::system::System;
+use engine_core::ecs::world::{INPUT_EVENT_BUS, Recording, ReplayCommand, SaveFormat, World};
+use engine_core::presentation::input::InputEvent;
+use engine_core::rng;
+use serde_json::{Value as JsonValue, json};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Drains a random amount of health every tick; pressing `h` heals to full.
+struct DecaySystem {
+    max_roll: i64,
+}
+
+impl System for DecaySystem {
+    fn name(&self) -> &'static str {
+        "DecaySystem"
+    }
+
+    fn run(&mut self, world: &mut World) {
+        let heal = world
+            .drain_events::<JsonValue>(INPUT_EVENT_BUS)
+            .iter()
+            .any(|e| e["key"] == "h");
+        for eid in world.get_entities_with_component("Health") {
+            let mut health = world.get_component(eid, "Health").unwrap().clone();
+            let max = health["max"].as_f64().unwrap();
+            let current = if heal {
+                max
+            } else {
+                health["current"].as_f64().unwrap()
+                    - world.random_range(rng::AI, 0, self.max_roll) as f64
+            };
+            health["current"] = json!(current.max(0.0));
+            world.set_component(eid, "Health", health).unwrap();
+        }
+    }
+}
+
+fn make_world(max_roll: i64) -> Rc<RefCell<World>> {
+    let mut world = make_test_world();
+    world.register_system(DecaySystem { max_roll });
+    world.seed_rng(99);
+    Rc::new(RefCell::new(world))
+}
+
+fn record_session(world_rc: &Rc<RefCell<World>>) -> Recording {
+    {
+        let mut world = world_rc.borrow_mut();
+        let e = world.spawn_entity();
+        world
+            .set_component(e, "Health", json!({ "current": 100, "max": 100 }))
+            .unwrap();
+        world.start_recording().unwrap();
+    }
+    for tick in 0..6 {
+        match tick {
+            1 => {
+                let mut world = world_rc.borrow_mut();
+                let e = world.spawn_entity();
+                world
+                    .set_component(e, "Health", json!({ "current": 40, "max": 80 }))
+                    .unwrap();
+            }
+            3 => world_rc.borrow_mut().push_input(InputEvent::KeyPress('h')),
+            4 => world_rc
+                .borrow_mut()
+                .send_event("weather", json!({ "rain": true }))
+                .unwrap(),
+            _ => {}
+        }
+        World::tick(Rc::clone(world_rc));
+    }
+    world_rc.borrow_mut().stop_recording().unwrap()
+}
+
+#[test]
+fn test_replay_reproduces_recorded_session() {
+    let recorded = make_world(5);
+    let recording = record_session(&recorded);
+    assert_eq!(recording.len(), 6);
+    assert_eq!(recording.seed, 99);
+    assert!(!recorded.borrow().is_recording());
+
+    // Replay in a fresh world that only shares the setup (systems), not the state.
+    let replay = make_world(5);
+    assert_eq!(World::replay(Rc::clone(&replay), &recording).unwrap(), 6);
+
+    let a = recorded.borrow();
+    let b = replay.borrow();
+    assert_eq!(a.turn, b.turn);
+    assert_eq!(a.get_entities(), b.get_entities());
+    for eid in a.get_entities() {
+        assert_eq!(
+            a.get_component(eid, "Health"),
+            b.get_component(eid, "Health")
+        );
+    }
+}
+
+#[test]
+fn test_only_external_commands_are_recorded() {
+    let recording = record_session(&make_world(5));
+    assert!(recording.ticks[0].commands.is_empty());
+    assert_eq!(
+        recording.ticks[1].commands,
+        vec![
+            ReplayCommand::SpawnEntity { entity: 2 },
+            ReplayCommand::SetComponent {
+                entity: 2,
+                component: "Health".to_string(),
+                value: json!({ "current": 40, "max": 80 }),
+            },
+        ]
+    );
+    assert_eq!(
+        recording.ticks[3].commands,
+        vec![ReplayCommand::Input {
+            event: InputEvent::KeyPress('h')
+        }]
+    );
+    assert!(matches!(
+        &recording.ticks[4].commands[..],
+        [ReplayCommand::SendEvent { event_type, .. }] if event_type == "weather"
+    ));
+}
+
+#[test]
+fn test_replay_reports_first_diverging_tick() {
+    let recording = record_session(&make_world(5));
+    // A system that behaves differently makes the replay diverge on the first tick.
+    let replay = make_world(50);
+    match World::replay(Rc::clone(&replay), &recording) {
+        Err(ReplayError::Desync { tick, turn, .. }) => {
+            assert_eq!(tick, 0);
+            assert_eq!(turn, 1);
+        }
+        other => panic!("expected desync, got {other:?}"),
+    }
+
+    // Replaying a prefix stops after the requested tick count.
+    let replay = make_world(5);
+    assert_eq!(
+        World::replay_ticks(Rc::clone(&replay), &recording, 2).unwrap(),
+        2
+    );
+    assert_eq!(replay.borrow().turn, 2);
+}
+
+#[test]
+fn test_recording_round_trips_through_files() {
+    let recording = record_session(&make_world(5));
+    let dir = tempfile::tempdir().unwrap();
+    for (name, format) in [
+        ("session.json", SaveFormat::Json),
+        ("session.bin", SaveFormat::CompressedBinary),
+    ] {
+        let path = dir.path().join(name);
+        recording.save_to_file(&path, format).unwrap();
+        let loaded = Recording::load_from_file(&path).unwrap();
+        assert_eq!(loaded.ticks, recording.ticks);
+        assert_eq!(loaded.seed, recording.seed);
+        assert_eq!(loaded.initial.turn(), recording.initial.turn());
+
+        let replay = make_world(5);
+        assert_eq!(World::replay(replay, &loaded).unwrap(), recording.len());
+    }
+}
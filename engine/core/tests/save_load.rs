@@ -7,6 +7,10 @@ mod world_io_helper;
 use world_io_helper::save_and_load_roundtrip;
 
 use engine_core::ecs::components::position::{Position, PositionComponent};
+use engine_core::ecs::world::{SAVE_FORMAT_VERSION, SaveFormat, World};
+use engine_core::loot::LootEntry;
+use engine_core::map::{CellKey, HexGridMap, Map, ProvinceMap, SquareGridMap};
+use serde_json::json;
 
 #[test]
 fn test_save_and_load_world_roundtrip() {
@@ -45,3 +49,262 @@ fn test_save_and_load_world_roundtrip() {
         loaded_world.get_component(e2, "Position")
     );
 }
+
+#[test]
+fn test_save_round_trips_square_map_with_metadata() {
+    let mut world = make_test_world();
+    let registry = world.registry.clone();
+
+    let mut grid = SquareGridMap::new();
+    grid.add_cell(0, 0, 0);
+    grid.add_cell(1, 0, 0);
+    grid.add_cell(5, 5, 0);
+    grid.add_neighbor((0, 0, 0), (1, 0, 0));
+    grid.add_neighbor((1, 0, 0), (0, 0, 0));
+    // One-way link between non-adjacent cells must survive (no re-inference).
+    grid.add_neighbor((1, 0, 0), (5, 5, 0));
+    world.map = Some(Map::new(Box::new(grid)));
+    world.set_cell_metadata(
+        &CellKey::Square { x: 0, y: 0, z: 0 },
+        json!({ "walkable": false, "terrain": "rock" }),
+    );
+
+    let loaded = save_and_load_roundtrip(&world, registry);
+    let map = loaded.get_map().expect("map should be restored");
+    assert_eq!(map.topology_type(), "square");
+    assert_eq!(map.all_cells().len(), 3);
+
+    let mut neighbors = map.neighbors(&CellKey::Square { x: 1, y: 0, z: 0 });
+    neighbors.sort();
+    assert_eq!(
+        neighbors,
+        vec![
+            CellKey::Square { x: 0, y: 0, z: 0 },
+            CellKey::Square { x: 5, y: 5, z: 0 },
+        ]
+    );
+    assert!(
+        map.neighbors(&CellKey::Square { x: 5, y: 5, z: 0 })
+            .is_empty()
+    );
+    assert_eq!(
+        map.get_cell_metadata(&CellKey::Square { x: 0, y: 0, z: 0 }),
+        Some(&json!({ "walkable": false, "terrain": "rock" }))
+    );
+}
+
+#[test]
+fn test_save_round_trips_hex_and_province_maps() {
+    let mut world = make_test_world();
+    let registry = world.registry.clone();
+
+    let mut hex = HexGridMap::new();
+    hex.add_cell(0, 0, 0);
+    hex.add_cell(1, -1, 0);
+    hex.add_neighbor((0, 0, 0), (1, -1, 0));
+    world.map = Some(Map::new(Box::new(hex)));
+    world.set_cell_metadata(&CellKey::Hex { q: 1, r: -1, z: 0 }, json!(7));
+
+    let loaded = save_and_load_roundtrip(&world, registry.clone());
+    let map = loaded.get_map().unwrap();
+    assert_eq!(map.topology_type(), "hex");
+    assert_eq!(
+        map.neighbors(&CellKey::Hex { q: 0, r: 0, z: 0 }),
+        vec![CellKey::Hex { q: 1, r: -1, z: 0 }]
+    );
+    assert_eq!(
+        map.get_cell_metadata(&CellKey::Hex { q: 1, r: -1, z: 0 }),
+        Some(&json!(7))
+    );
+
+    let mut provinces = ProvinceMap::new();
+    provinces.add_cell("north");
+    provinces.add_cell("south");
+    provinces.add_neighbor("north", "south");
+    world.map = Some(Map::new(Box::new(provinces)));
+    world.set_cell_metadata(
+        &CellKey::Province {
+            id: "north".to_string(),
+        },
+        json!({ "owner": "red" }),
+    );
+
+    let loaded = save_and_load_roundtrip(&world, registry);
+    let map = loaded.get_map().unwrap();
+    assert_eq!(map.topology_type(), "province");
+    assert_eq!(
+        map.neighbors(&CellKey::Province {
+            id: "north".to_string()
+        }),
+        vec![CellKey::Province {
+            id: "south".to_string()
+        }]
+    );
+    assert_eq!(
+        map.get_cell_metadata(&CellKey::Province {
+            id: "north".to_string()
+        }),
+        Some(&json!({ "owner": "red" }))
+    );
+}
+
+#[test]
+fn test_save_round_trips_registries_job_board_and_fov_choice() {
+    let mut world = make_test_world();
+    let registry = world.registry.clone();
+
+    world
+        .loot_tables
+        .define_table(
+            "chest",
+            vec![LootEntry {
+                item_id: "gold".to_string(),
+                weight: 1,
+                min_count: 2,
+                max_count: 2,
+            }],
+        )
+        .unwrap();
+    world
+        .resource_definitions
+        .insert("wood".to_string(), json!({ "kind": "wood" }));
+    world
+        .recipes
+        .insert("plank".to_string(), json!({ "name": "plank" }));
+    world.job_board.set_policy("fifo").unwrap();
+    world.set_fov_algorithm_by_name("bfs_flood_fill").unwrap();
+    world.emit_event("custom_event", json!({ "n": 1 }));
+    world.update_event_queues();
+
+    let mut loaded = save_and_load_roundtrip(&world, registry);
+    assert_eq!(
+        loaded.roll_loot("chest").unwrap(),
+        vec![("gold".to_string(), 2)]
+    );
+    assert_eq!(
+        loaded.resource_definitions["wood"],
+        json!({ "kind": "wood" })
+    );
+    assert!(loaded.recipes.contains_key("plank"));
+    assert_eq!(loaded.job_board.get_policy_name(), "fifo");
+    assert_eq!(loaded.fov_algorithm().name(), "bfs_flood_fill");
+    let mut delivered = Vec::new();
+    loaded.process_events("custom_event", |e| delivered.push(e.clone()));
+    assert_eq!(delivered, vec![json!({ "n": 1 })]);
+}
+
+#[test]
+fn test_save_files_carry_a_versioned_header() {
+    let world = make_test_world();
+    let value = world.save_to_value().unwrap();
+    assert_eq!(
+        value["save_header"]["format_version"],
+        json!(SAVE_FORMAT_VERSION)
+    );
+}
+
+#[test]
+fn test_headerless_legacy_save_still_loads() {
+    let mut world = make_test_world();
+    let registry = world.registry.clone();
+    let e = world.spawn_entity();
+    world
+        .set_component(e, "Health", json!({ "current": 5, "max": 10 }))
+        .unwrap();
+
+    // Strip everything a version 1 save did not contain.
+    let mut value = world.save_to_value().unwrap();
+    let obj = value.as_object_mut().unwrap();
+    for key in [
+        "save_header",
+        "map",
+        "loot_tables",
+        "job_board",
+        "resource_definitions",
+        "material_definitions",
+        "recipes",
+        "jobs",
+        "fov_algorithm",
+    ] {
+        obj.remove(key);
+    }
+
+    let loaded = World::load_from_value(value, registry).unwrap();
+    assert_eq!(loaded.get_component(e, "Health").unwrap()["current"], 5);
+    assert!(loaded.get_map().is_none());
+    assert_eq!(loaded.job_board.get_policy_name(), "priority");
+    assert_eq!(loaded.fov_algorithm().name(), "recursive_shadowcasting");
+}
+
+#[test]
+fn test_save_from_newer_format_is_rejected() {
+    let world = make_test_world();
+    let registry = world.registry.clone();
+    let mut value = world.save_to_value().unwrap();
+    value["save_header"]["format_version"] = json!(SAVE_FORMAT_VERSION + 1);
+    assert!(World::load_from_value(value, registry).is_err());
+}
+
+fn populated_world() -> World {
+    let mut world = make_test_world();
+    let mut grid = SquareGridMap::new();
+    for i in 0..50 {
+        let e = world.spawn_entity();
+        world
+            .set_component(e, "Health", json!({ "current": i, "max": 100 }))
+            .unwrap();
+        grid.add_cell(i, 0, 0);
+    }
+    world.map = Some(Map::new(Box::new(grid)));
+    world
+}
+
+#[test]
+fn test_binary_and_compressed_saves_round_trip() {
+    let world = populated_world();
+    let registry = world.registry.clone();
+    let json_bytes = world.save_to_bytes(SaveFormat::Json).unwrap();
+
+    for format in [SaveFormat::Binary, SaveFormat::CompressedBinary] {
+        let bytes = world.save_to_bytes(format).unwrap();
+        assert!(bytes.len() < json_bytes.len());
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        world.save_to_file_as(file.path(), format).unwrap();
+        let loaded = World::load_from_file(file.path(), registry.clone()).unwrap();
+        assert_eq!(loaded.entities, world.entities);
+        assert_eq!(loaded.components, world.components);
+        assert_eq!(loaded.get_map().unwrap().all_cells().len(), 50);
+    }
+}
+
+#[test]
+fn test_compressed_save_is_smaller_than_binary() {
+    let world = populated_world();
+    let binary = world.save_to_bytes(SaveFormat::Binary).unwrap();
+    let compressed = world.save_to_bytes(SaveFormat::CompressedBinary).unwrap();
+    assert!(compressed.len() < binary.len());
+}
+
+#[test]
+fn test_truncated_binary_save_is_rejected() {
+    let world = make_test_world();
+    let registry = world.registry.clone();
+    let mut bytes = world.save_to_bytes(SaveFormat::CompressedBinary).unwrap();
+    bytes.truncate(bytes.len() / 2);
+    assert!(World::load_from_bytes(&bytes, registry.clone()).is_err());
+    assert!(World::load_from_bytes(b"MGEB", registry).is_err());
+}
+
+#[test]
+fn test_save_format_names() {
+    assert_eq!(SaveFormat::from_name("json"), Some(SaveFormat::Json));
+    assert_eq!(SaveFormat::from_name("binary"), Some(SaveFormat::Binary));
+    assert_eq!(
+        SaveFormat::from_name("compressed"),
+        Some(SaveFormat::CompressedBinary)
+    );
+    assert_eq!(SaveFormat::from_name("yaml"), None);
+    assert_eq!(SaveFormat::from_name("msgpack"), None);
+    assert_eq!(SaveFormat::from_name("zstd"), None);
+}
@@ -0,0 +1,292 @@
+#[path = "helpers/world.rs"]
+mod world_helper;
+use world_helper::make_test_world;
+
+use engine_core::ecs::schema::ComponentSchema;
+use engine_core::ecs::world::{SAVE_FORMAT_VERSION, World};
+use engine_core::mods::loader::register_mod_migrations;
+use engine_core::mods::manifest::ModManifest;
+use serde_json::json;
+
+fn health_schema() -> ComponentSchema {
+    let world = make_test_world();
+    let registry = world.registry.lock().unwrap();
+    registry.get_schema_by_name("Health").unwrap().clone()
+}
+
+#[test]
+fn test_registered_migrations_run_in_version_order() {
+    let mut world = make_test_world();
+    let registry = world.registry.clone();
+    let e = world.spawn_entity();
+    world
+        .set_component(e, "Health", json!({ "current": 10, "max": 50 }))
+        .unwrap();
+    let save = world.save_to_value().unwrap();
+
+    // Registered out of order on purpose.
+    world.register_save_migration(SAVE_FORMAT_VERSION + 2, "double", |save, _| {
+        let current = save["components"]["Health"]["1"]["current"]
+            .as_f64()
+            .unwrap();
+        save["components"]["Health"]["1"]["current"] = json!(current * 2.0);
+        Ok(())
+    });
+    world.register_save_migration(SAVE_FORMAT_VERSION + 1, "add_five", |save, _| {
+        let current = save["components"]["Health"]["1"]["current"]
+            .as_f64()
+            .unwrap();
+        save["components"]["Health"]["1"]["current"] = json!(current + 5.0);
+        Ok(())
+    });
+
+    let (loaded, report) = World::load_from_value_with_report(save, registry).unwrap();
+    assert_eq!(report.applied, vec!["add_five", "double"]);
+    assert_eq!(report.from_version, SAVE_FORMAT_VERSION);
+    assert_eq!(report.to_version, SAVE_FORMAT_VERSION + 2);
+    assert_eq!(loaded.get_component(e, "Health").unwrap()["current"], 30.0);
+
+    // New saves are stamped with the latest version, so migrations do not rerun.
+    let resaved = loaded.save_to_value().unwrap();
+    assert_eq!(
+        resaved["save_header"]["format_version"],
+        json!(SAVE_FORMAT_VERSION + 2)
+    );
+    let (_, report) = World::load_from_value_with_report(resaved, loaded.registry.clone()).unwrap();
+    assert!(report.is_empty());
+}
+
+#[test]
+fn test_schema_hot_reload_migration_applies_to_older_saves() {
+    let mut world = make_test_world();
+    let registry = world.registry.clone();
+    let e = world.spawn_entity();
+    world
+        .set_component(e, "Health", json!({ "current": 10, "max": 20 }))
+        .unwrap();
+    let old_save = world.save_to_value().unwrap();
+
+    world
+        .hotreload_schema_with_migration(health_schema(), 1, |v| {
+            let mut v = v.clone();
+            v["max"] = json!(40);
+            v
+        })
+        .unwrap();
+    assert_eq!(world.get_component(e, "Health").unwrap()["max"], 40);
+
+    let (loaded, report) = World::load_from_value_with_report(old_save, registry).unwrap();
+    assert_eq!(loaded.get_component(e, "Health").unwrap()["max"], 40);
+    assert_eq!(report.migrated.len(), 1);
+    assert_eq!(report.migrated[0].component, "Health");
+    assert_eq!(report.migrated[0].entity, Some(e));
+}
+
+#[test]
+fn test_values_invalid_after_migration_are_dropped_and_reported() {
+    let mut world = make_test_world();
+    let registry = world.registry.clone();
+    let a = world.spawn_entity();
+    let b = world.spawn_entity();
+    world
+        .set_component(a, "Health", json!({ "current": 10, "max": 20 }))
+        .unwrap();
+    world
+        .set_component(b, "Health", json!({ "current": 90, "max": 95 }))
+        .unwrap();
+    let old_save = world.save_to_value().unwrap();
+
+    // Pushes `max` past the schema maximum (100) for entity b only.
+    world
+        .hotreload_schema_with_migration(health_schema(), 1, |v| {
+            let mut v = v.clone();
+            v["max"] = json!(v["max"].as_f64().unwrap() + 10.0);
+            v
+        })
+        .unwrap();
+
+    let (loaded, report) = World::load_from_value_with_report(old_save, registry).unwrap();
+    assert_eq!(loaded.get_component(a, "Health").unwrap()["max"], 30.0);
+    assert!(loaded.get_component(b, "Health").is_none());
+    assert_eq!(report.dropped.len(), 1);
+    assert_eq!(report.dropped[0].entity, Some(b));
+}
+
+#[test]
+fn test_legacy_agent_skills_are_migrated_by_builtin_migration() {
+    let mut world = make_test_world();
+    let registry = world.registry.clone();
+    let e = world.spawn_entity();
+    world
+        .components
+        .entry("Agent".to_string())
+        .or_default()
+        .insert(e, json!({ "entity_id": e, "skills": { "mining": 2.0 } }));
+    let mut save = world.save_to_value().unwrap();
+    save.as_object_mut().unwrap().remove("save_header");
+
+    let (loaded, report) = World::load_from_value_with_report(save, registry).unwrap();
+    assert_eq!(report.from_version, 1);
+    assert!(
+        report
+            .applied
+            .contains(&"agent_skills_to_skill_levels".to_string())
+    );
+    assert_eq!(
+        loaded.components["SkillLevels"][&e]["skills"],
+        json!({ "mining": 2.0 })
+    );
+}
+
+#[test]
+fn test_mod_migrations_rename_and_drop_components() {
+    let mut world = make_test_world();
+    let registry = world.registry.clone();
+    let e = world.spawn_entity();
+    world
+        .components
+        .entry("Nickname".to_string())
+        .or_default()
+        .insert(e, json!({ "label": "Bob" }));
+    world
+        .components
+        .entry("Obsolete".to_string())
+        .or_default()
+        .insert(e, json!({}));
+    let save = world.save_to_value().unwrap();
+
+    let manifest: ModManifest = serde_json::from_value(json!({
+        "name": "renamer",
+        "version": "1.0.0",
+        "migrations": [
+            {
+                "version": 1,
+                "component": "Nickname",
+                "rename": { "label": "name" },
+                "defaults": { "title": "" }
+            },
+            { "version": 1, "component": "Obsolete", "drop": true }
+        ]
+    }))
+    .unwrap();
+    register_mod_migrations(&manifest, registry.lock().unwrap().save_migrations_mut());
+
+    let (loaded, report) = World::load_from_value_with_report(save, registry).unwrap();
+    assert_eq!(
+        loaded.components["Nickname"][&e],
+        json!({ "name": "Bob", "title": "" })
+    );
+    assert!(
+        loaded
+            .components
+            .get("Obsolete")
+            .is_none_or(|m| m.is_empty())
+    );
+    assert_eq!(report.dropped.len(), 1);
+    assert_eq!(report.dropped[0].component, "Obsolete");
+    assert_eq!(report.applied.len(), 2);
+}
+
+#[test]
+fn test_failing_migration_aborts_load() {
+    let mut world = make_test_world();
+    let registry = world.registry.clone();
+    let save = world.save_to_value().unwrap();
+    world.register_save_migration(SAVE_FORMAT_VERSION + 1, "broken", |_, _| {
+        Err("unsupported data".to_string())
+    });
+    let err = World::load_from_value(save, registry)
+        .err()
+        .expect("load should fail");
+    assert!(err.to_string().contains("broken"));
+}
+
+#[test]
+fn test_schema_migrations_are_versioned_per_schema() {
+    let mut world = make_test_world();
+    let e = world.spawn_entity();
+    world
+        .set_component(e, "Health", json!({ "current": 10, "max": 20 }))
+        .unwrap();
+    world
+        .hotreload_schema_with_migration(health_schema(), 2, |v| {
+            let mut v = v.clone();
+            v["max"] = json!(40);
+            v
+        })
+        .unwrap();
+    let save = world.save_to_value().unwrap();
+    assert_eq!(
+        save["save_header"]["format_version"],
+        json!(SAVE_FORMAT_VERSION)
+    );
+    assert_eq!(
+        save["save_header"]["scoped_versions"],
+        json!({ "schema:Health": 2 })
+    );
+
+    // A process that never registered the migration still loads the save, and
+    // keeps the schema version when saving it again.
+    let fresh = make_test_world();
+    let (loaded, report) =
+        World::load_from_value_with_report(save, fresh.registry.clone()).unwrap();
+    assert!(report.is_empty());
+    assert_eq!(loaded.get_component(e, "Health").unwrap()["max"], 40);
+    let resaved = loaded.save_to_value().unwrap();
+    assert_eq!(
+        resaved["save_header"]["scoped_versions"],
+        json!({ "schema:Health": 2 })
+    );
+
+    // Once registered there, the migration does not rerun on the resaved file.
+    let mut later = make_test_world();
+    later
+        .hotreload_schema_with_migration(health_schema(), 2, |v| {
+            let mut v = v.clone();
+            v["max"] = json!(40);
+            v
+        })
+        .unwrap();
+    let (_, report) = World::load_from_value_with_report(resaved, later.registry.clone()).unwrap();
+    assert!(report.is_empty());
+}
+
+#[test]
+fn test_mod_migrations_are_versioned_per_mod() {
+    let mut world = make_test_world();
+    let registry = world.registry.clone();
+    let e = world.spawn_entity();
+    for component in ["Nickname", "Title"] {
+        world
+            .components
+            .entry(component.to_string())
+            .or_default()
+            .insert(e, json!({ "label": "x" }));
+    }
+    let save = world.save_to_value().unwrap();
+
+    for (name, component) in [("first", "Nickname"), ("second", "Title")] {
+        let manifest: ModManifest = serde_json::from_value(json!({
+            "name": name,
+            "version": "1.0.0",
+            "migrations": [
+                { "version": 1, "component": component, "rename": { "label": "name" } }
+            ]
+        }))
+        .unwrap();
+        register_mod_migrations(&manifest, registry.lock().unwrap().save_migrations_mut());
+        // Reloading a mod registers its migrations again without duplicating them.
+        register_mod_migrations(&manifest, registry.lock().unwrap().save_migrations_mut());
+    }
+
+    let (loaded, report) = World::load_from_value_with_report(save, registry).unwrap();
+    assert_eq!(report.applied.len(), 2);
+    assert_eq!(report.to_version, SAVE_FORMAT_VERSION);
+    assert_eq!(loaded.components["Nickname"][&e], json!({ "name": "x" }));
+    assert_eq!(loaded.components["Title"][&e], json!({ "name": "x" }));
+    let resaved = loaded.save_to_value().unwrap();
+    assert_eq!(
+        resaved["save_header"]["scoped_versions"],
+        json!({ "mod:first": 1, "mod:second": 1 })
+    );
+}
@@ -0,0 +1,103 @@
+#[path = "helpers/world.rs"]
+mod world_helper;
+use world_helper::make_test_world;
+
+use engine_core::ecs::Health;
+use engine_core::ecs::system::System;
+use engine_core::ecs::world::{World, WorldSnapshot};
+use engine_core::rng;
+use serde_json::json;
+
+struct NoopSystem;
+
+impl System for NoopSystem {
+    fn name(&self) -> &'static str {
+        "NoopSystem"
+    }
+    fn run(&mut self, _world: &mut World) {}
+}
+
+#[test]
+fn test_restore_rolls_back_state_and_keeps_systems() {
+    let mut world = make_test_world();
+    world.register_system(NoopSystem);
+    let e = world.spawn_entity();
+    world
+        .set_component(e, "Health", json!({ "current": 50, "max": 100 }))
+        .unwrap();
+    world.turn = 3;
+    world.seed_rng(11);
+
+    let snapshot = world.snapshot().unwrap();
+    assert_eq!(snapshot.turn(), 3);
+    let expected_draw = world.rng.clone().next_f64(rng::AI);
+
+    world
+        .set_component(e, "Health", json!({ "current": 1, "max": 100 }))
+        .unwrap();
+    let extra = world.spawn_entity();
+    world.turn = 9;
+    world.random_f64(rng::AI);
+
+    world.restore(&snapshot).unwrap();
+    assert_eq!(world.turn, 3);
+    assert_eq!(world.get_component(e, "Health").unwrap()["current"], 50);
+    assert!(!world.entity_exists(extra));
+    assert!(world.has_system("NoopSystem"));
+    assert_eq!(world.random_f64(rng::AI), expected_draw);
+
+    // Entity IDs continue from the snapshot, not from the discarded future.
+    assert_eq!(world.spawn_entity(), extra);
+}
+
+#[test]
+fn test_restore_refills_typed_columns() {
+    let mut world = make_test_world();
+    world.register_typed_component::<Health>("Health").unwrap();
+    let e = world.spawn_entity();
+    world
+        .set_component(e, "Health", json!({ "current": 20.0, "max": 40.0 }))
+        .unwrap();
+    let snapshot = world.snapshot().unwrap();
+
+    world.remove_component(e, "Health").unwrap();
+    world.restore(&snapshot).unwrap();
+
+    assert!(!world.components.contains_key("Health"));
+    let column = world.typed_component::<Health>("Health").unwrap();
+    assert_eq!(column.get(e).unwrap().current, 20.0);
+}
+
+#[test]
+fn test_snapshot_bytes_round_trip() {
+    let mut world = make_test_world();
+    world.turn = 7;
+    let snapshot = world.snapshot().unwrap();
+    assert!(!snapshot.is_empty());
+
+    let copy = WorldSnapshot::from_bytes(snapshot.as_bytes().to_vec()).unwrap();
+    assert_eq!(copy, snapshot);
+    assert_eq!(copy.turn(), 7);
+    assert!(WorldSnapshot::from_bytes(b"not a snapshot".to_vec()).is_err());
+}
+
+#[test]
+fn test_failed_restore_leaves_the_world_unchanged() {
+    let mut world = make_test_world();
+    world.register_typed_component::<Health>("Health").unwrap();
+    let e = world.spawn_entity();
+    world
+        .set_component(e, "Health", json!({ "current": 20.0, "max": 40.0 }))
+        .unwrap();
+    world.turn = 2;
+    let mut save = serde_json::to_value(world.snapshot().unwrap()).unwrap();
+    save["components"]["Health"][e.to_string()] = json!({ "current": "full" });
+    let snapshot: WorldSnapshot = serde_json::from_value(save).unwrap();
+
+    world.turn = 5;
+    let err = world.restore(&snapshot).unwrap_err();
+    assert!(err.to_string().contains("Typed component"), "{err}");
+    assert_eq!(world.turn, 5);
+    let column = world.typed_component::<Health>("Health").unwrap();
+    assert_eq!(column.get(e).unwrap().current, 20.0);
+}
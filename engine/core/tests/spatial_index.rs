@@ -0,0 +1,240 @@
+#[path = "helpers/world.rs"]
+mod world_helper;
+
+use engine_core::ecs::world::World;
+use engine_core::ecs::world::wasm::WasmWorld;
+use engine_core::map::{CellKey, Map, ProvinceMap, SpatialIndex, cell_distance};
+use serde_json::json;
+
+fn square(x: i32, y: i32, z: i32) -> CellKey {
+    CellKey::Square { x, y, z }
+}
+
+fn hex(q: i32, r: i32, z: i32) -> CellKey {
+    CellKey::Hex { q, r, z }
+}
+
+fn province(id: &str) -> CellKey {
+    CellKey::Province { id: id.to_string() }
+}
+
+fn spawn_at(world: &mut World, cell: &CellKey) -> u32 {
+    let eid = world.spawn_entity();
+    world
+        .set_component(eid, "Position", json!({ "pos": cell }))
+        .unwrap();
+    eid
+}
+
+#[test]
+fn test_index_follows_position_writes() {
+    let mut world = world_helper::make_test_world();
+    let a = spawn_at(&mut world, &square(1, 1, 0));
+    let b = spawn_at(&mut world, &square(1, 1, 0));
+    assert_eq!(world.entities_in_cell(&square(1, 1, 0)), vec![a, b]);
+
+    world.move_entity(a, 2.0, 0.0);
+    assert_eq!(world.entities_in_cell(&square(1, 1, 0)), vec![b]);
+    assert_eq!(world.entity_cell(a), Some(&square(3, 1, 0)));
+
+    world.remove_component(b, "Position").unwrap();
+    assert!(world.entities_in_cell(&square(1, 1, 0)).is_empty());
+    world.despawn_entity(a);
+    assert!(world.entities_in_zlevel(0).is_empty());
+}
+
+#[test]
+fn test_hex_and_province_cells_are_indexed() {
+    let mut world = world_helper::make_test_world();
+    let hexling = spawn_at(&mut world, &hex(2, -1, 1));
+    let duke = spawn_at(&mut world, &province("north"));
+
+    assert_eq!(world.entities_in_cell(&hex(2, -1, 1)), vec![hexling]);
+    assert_eq!(world.entities_in_cell(&province("north")), vec![duke]);
+    assert_eq!(world.entities_in_zlevel(1), vec![hexling]);
+    assert!(world.entities_in_cell(&square(2, -1, 1)).is_empty());
+}
+
+#[test]
+fn test_radius_and_rect_queries() {
+    let mut world = world_helper::make_test_world();
+    let center = spawn_at(&mut world, &square(0, 0, 0));
+    let near = spawn_at(&mut world, &square(1, 1, 0));
+    let edge = spawn_at(&mut world, &square(0, 2, 0));
+    let corner = spawn_at(&mut world, &square(2, 2, 0));
+    let below = spawn_at(&mut world, &square(0, 0, -1));
+
+    assert_eq!(
+        world.entities_in_radius(&square(0, 0, 0), 2.0),
+        vec![center, near, edge]
+    );
+    assert_eq!(
+        world.entities_in_rect(&square(2, 2, 0), &square(1, 0, 0)),
+        vec![near, corner]
+    );
+    assert_eq!(
+        world.entities_in_rect(&square(0, 0, -1), &square(0, 0, 0)),
+        vec![center, below]
+    );
+    assert_eq!(
+        world.entities_in_radius(&square(0, 0, 0), 1000.0).len(),
+        4,
+        "Large radii scan occupied cells instead of the whole box"
+    );
+
+    let h = spawn_at(&mut world, &hex(0, 0, 0));
+    let h2 = spawn_at(&mut world, &hex(2, -2, 0));
+    assert_eq!(world.entities_in_radius(&hex(1, -1, 0), 1.0), vec![h, h2]);
+    assert_eq!(cell_distance(&hex(0, 0, 0), &hex(2, -1, 0)), Some(2.0));
+}
+
+#[test]
+fn test_province_radius_walks_borders() {
+    let mut world = world_helper::make_test_world();
+    let mut provinces = ProvinceMap::new();
+    for id in ["a", "b", "c"] {
+        provinces.add_cell(id);
+    }
+    provinces.add_neighbor("a", "b");
+    provinces.add_neighbor("b", "c");
+    world.map = Some(Map::new(Box::new(provinces)));
+    let in_a = spawn_at(&mut world, &province("a"));
+    let in_b = spawn_at(&mut world, &province("b"));
+    let in_c = spawn_at(&mut world, &province("c"));
+
+    assert_eq!(
+        world.entities_in_radius(&province("a"), 1.0),
+        vec![in_a, in_b]
+    );
+    assert_eq!(
+        world.entities_in_radius(&province("a"), 2.0),
+        vec![in_a, in_b, in_c]
+    );
+    world
+        .set_component(in_c, "Health", json!({ "current": 5.0, "max": 5.0 }))
+        .unwrap();
+    assert_eq!(
+        world.nearest_entity_with_component(&province("a"), "Health", None),
+        Some(in_c)
+    );
+    assert_eq!(
+        world.nearest_entity_with_component(&province("a"), "Health", Some(1.0)),
+        None
+    );
+}
+
+#[test]
+fn test_nearest_entity_with_component() {
+    let mut world = world_helper::make_test_world();
+    let seeker = spawn_at(&mut world, &square(0, 0, 0));
+    let far = spawn_at(&mut world, &square(5, 0, 0));
+    let close = spawn_at(&mut world, &square(0, 3, 0));
+    let upstairs = spawn_at(&mut world, &square(0, 0, 1));
+    for eid in [seeker, far, close, upstairs] {
+        world
+            .set_component(eid, "Health", json!({ "current": 5.0, "max": 5.0 }))
+            .unwrap();
+    }
+    world.remove_component(seeker, "Health").unwrap();
+
+    let origin = square(0, 0, 0);
+    assert_eq!(
+        world.nearest_entity_with_component(&origin, "Health", None),
+        Some(close)
+    );
+    assert_eq!(
+        world.nearest_entity_with_component(&origin, "Health", Some(2.0)),
+        None
+    );
+    assert_eq!(
+        world.nearest_entity_with_component(&origin, "Inventory", None),
+        None
+    );
+}
+
+#[test]
+fn test_index_is_rebuilt_on_restore_and_load() {
+    let mut world = world_helper::make_test_world();
+    let eid = spawn_at(&mut world, &hex(0, 1, 0));
+    let snapshot = world.snapshot().unwrap();
+
+    world
+        .set_component(eid, "Position", json!({ "pos": hex(4, 4, 0) }))
+        .unwrap();
+    world.restore(&snapshot).unwrap();
+    assert_eq!(world.entities_in_cell(&hex(0, 1, 0)), vec![eid]);
+    assert!(world.entities_in_cell(&hex(4, 4, 0)).is_empty());
+
+    let saved = world.save_to_value().unwrap();
+    let loaded = World::load_from_value(saved, world.registry.clone()).unwrap();
+    assert_eq!(loaded.entity_cell(eid), Some(&hex(0, 1, 0)));
+}
+
+#[test]
+fn test_spatial_index_moves_entities_between_cells() {
+    let mut index = SpatialIndex::new();
+    index.insert(7, square(0, 0, 0));
+    index.insert(7, square(1, 0, 0));
+    assert_eq!(index.len(), 1);
+    assert!(index.entities_in_cell(&square(0, 0, 0)).is_empty());
+    assert_eq!(index.remove(7), Some(square(1, 0, 0)));
+    assert!(index.is_empty());
+}
+
+#[test]
+fn test_queries_over_extreme_bounds() {
+    let mut index = SpatialIndex::new();
+    let low = square(i32::MIN, i32::MIN, i32::MIN);
+    let high = square(i32::MAX, i32::MAX, i32::MAX);
+    index.insert(1, low.clone());
+    index.insert(2, high.clone());
+    index.insert(3, square(0, 0, 0));
+
+    assert_eq!(index.entities_in_rect(&low, &high), vec![1, 2, 3]);
+    assert_eq!(index.entities_in_rect(&high, &square(0, 0, 0)), vec![2, 3]);
+    assert_eq!(
+        index.entities_in_radius(&square(0, 0, 0), f64::MAX, |_| Vec::new()),
+        vec![3]
+    );
+}
+
+#[test]
+fn test_wasm_world_spatial_queries() {
+    let mut world = WasmWorld::new();
+    let flat = world.spawn_entity();
+    let nested = world.spawn_entity();
+    let target = world.spawn_entity();
+    world
+        .set_component(flat, "Position", r#"{"x": 1.0, "y": 0.0}"#)
+        .unwrap();
+    world
+        .set_component(
+            nested,
+            "Position",
+            r#"{"pos": {"Square": {"x": 0, "y": 1, "z": 0}}}"#,
+        )
+        .unwrap();
+    world
+        .set_component(target, "Position", r#"{"x": 6, "y": 0, "z": 0}"#)
+        .unwrap();
+    world
+        .set_component(target, "Health", r#"{"current": 5, "max": 5}"#)
+        .unwrap();
+
+    let origin = r#"{"Square": {"x": 0, "y": 0, "z": 0}}"#;
+    assert_eq!(world.entities_in_radius(origin, 1.0), vec![flat, nested]);
+    assert_eq!(
+        world.entities_in_rect(origin, r#"{"Square": {"x": 6, "y": 0, "z": 0}}"#),
+        vec![flat, target]
+    );
+    assert_eq!(
+        world.nearest_entity_with_component(origin, "Health", None),
+        Some(target)
+    );
+
+    world.move_entity(flat, -1.0, 0.0);
+    assert_eq!(world.entities_in_cell(origin), vec![flat]);
+    world.remove_component(nested, "Position").unwrap();
+    world.despawn_entity(target);
+    assert_eq!(world.entities_in_radius(origin, 10.0), vec![flat]);
+}
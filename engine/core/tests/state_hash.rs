@@ -0,0 +1,218 @@
+#[path = "helpers/world.rs"]
+mod world_helper;
+use world_helper::make_test_world;
+
+#[path = "helpers/world_io.rs"]
+mod world_io_helper;
+use world_io_helper::save_and_load_roundtrip;
+
+use engine_core::ecs::system::System;
+use engine_core::ecs::world::wasm::WasmWorld;
+use engine_core::ecs::world::{TICK_START, World, first_divergence};
+use engine_core::map::{CellKey, Map, SquareGridMap};
+use engine_core::rng;
+use serde_json::json;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+fn populate(world: &mut World, reversed: bool) {
+    let a = world.spawn_entity();
+    let b = world.spawn_entity();
+    let mut writes = vec![
+        (a, "Health", json!({ "current": 5, "max": 10 })),
+        (b, "Health", json!({ "max": 20, "current": 15 })),
+        (a, "Type", json!({ "kind": "colonist" })),
+    ];
+    if reversed {
+        writes.reverse();
+    }
+    for (eid, name, value) in writes {
+        world.set_component(eid, name, value).unwrap();
+    }
+}
+
+#[test]
+fn test_state_hash_ignores_insertion_order() {
+    let mut a = make_test_world();
+    let mut b = make_test_world();
+    a.seed_rng(1);
+    b.seed_rng(1);
+    populate(&mut a, false);
+    populate(&mut b, true);
+    assert_eq!(a.state_hash(), b.state_hash());
+}
+
+#[test]
+fn test_state_hash_tracks_simulated_state() {
+    let mut world = make_test_world();
+    world.seed_rng(1);
+    populate(&mut world, false);
+    let mut seen = vec![world.state_hash()];
+    let mut changed = |world: &World| {
+        let hash = world.state_hash();
+        assert!(
+            !seen.contains(&hash),
+            "state change did not change the hash"
+        );
+        seen.push(hash);
+    };
+
+    world
+        .set_component(1, "Health", json!({ "current": 4, "max": 10 }))
+        .unwrap();
+    changed(&world);
+    world.turn += 1;
+    changed(&world);
+    world.time_of_day.minute += 1;
+    changed(&world);
+    world.random_f64(rng::AI);
+    changed(&world);
+
+    let mut grid = SquareGridMap::new();
+    grid.add_cell(0, 0, 0);
+    world.map = Some(Map::new(Box::new(grid)));
+    changed(&world);
+    let cell = CellKey::Square { x: 0, y: 0, z: 0 };
+    world
+        .map
+        .as_mut()
+        .unwrap()
+        .set_cell_metadata(&cell, json!({ "biome": "Forest" }));
+    changed(&world);
+}
+
+#[test]
+fn test_state_hash_survives_save_and_load() {
+    let mut world = make_test_world();
+    populate(&mut world, false);
+    world.random_f64(rng::LOOT);
+    let registry = world.registry.clone();
+    let loaded = save_and_load_roundtrip(&world, registry);
+    assert_eq!(loaded.state_hash(), world.state_hash());
+}
+
+/// Heals every entity by a fixed amount.
+struct HealSystem;
+
+impl System for HealSystem {
+    fn name(&self) -> &'static str {
+        "HealSystem"
+    }
+    fn run(&mut self, world: &mut World) {
+        for eid in world.get_entities_with_component("Health") {
+            let mut health = world.get_component(eid, "Health").unwrap().clone();
+            health["current"] = json!(health["current"].as_f64().unwrap() + 1.0);
+            world.set_component(eid, "Health", health).unwrap();
+        }
+    }
+}
+
+/// Sets a marker component, optionally using the wall clock (nondeterministic).
+struct MarkSystem {
+    use_clock: bool,
+}
+
+impl System for MarkSystem {
+    fn name(&self) -> &'static str {
+        "MarkSystem"
+    }
+    fn dependencies(&self) -> &'static [&'static str] {
+        &["HealSystem"]
+    }
+    fn run(&mut self, world: &mut World) {
+        let stamp = if self.use_clock {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos() as u64
+        } else {
+            world.turn as u64
+        };
+        world
+            .set_component(1, "Type", json!({ "kind": format!("mark-{stamp}") }))
+            .unwrap();
+    }
+}
+
+fn run_ticks(use_clock: bool) -> Vec<engine_core::ecs::world::SystemHash> {
+    let mut world = make_test_world();
+    world.seed_rng(3);
+    populate(&mut world, false);
+    world.register_system(HealSystem);
+    world.register_system(MarkSystem { use_clock });
+    world.set_system_hash_debug(true);
+    let world_rc = Rc::new(RefCell::new(world));
+    for _ in 0..3 {
+        World::tick(Rc::clone(&world_rc));
+    }
+    let log = world_rc.borrow_mut().take_system_hashes();
+    assert!(world_rc.borrow().system_hashes().is_empty());
+    log
+}
+
+#[test]
+fn test_system_hash_log_names_the_diverging_system() {
+    let a = run_ticks(false);
+    let b = run_ticks(false);
+    assert_eq!(a.len(), 9);
+    assert_eq!(a[0].system, TICK_START);
+    assert_eq!(a[1].system, "HealSystem");
+    assert_eq!(a[2].system, "MarkSystem");
+    assert_eq!(first_divergence(&a, &b), None);
+
+    let c = run_ticks(true);
+    let index = first_divergence(&a, &c).unwrap();
+    assert_eq!(c[index].system, "MarkSystem");
+    assert_eq!(c[index].turn, 0);
+    assert_eq!(first_divergence(&a, &a[..4]), Some(4));
+}
+
+#[test]
+fn test_system_hash_debug_is_off_by_default() {
+    let mut world = make_test_world();
+    world.register_system(HealSystem);
+    assert!(!world.system_hash_debug());
+    let world_rc = Rc::new(RefCell::new(world));
+    World::tick(Rc::clone(&world_rc));
+    assert!(world_rc.borrow().system_hashes().is_empty());
+}
+
+#[test]
+fn test_wasm_world_state_hash_ignores_insertion_order() {
+    let mut a = WasmWorld::new();
+    let mut b = WasmWorld::new();
+    let ids: Vec<u32> = (0..3).map(|_| a.spawn_entity()).collect();
+    for _ in 0..3 {
+        b.spawn_entity();
+    }
+    for &id in &ids {
+        a.set_component(id, "Health", r#"{"current":1,"max":2}"#)
+            .unwrap();
+    }
+    for &id in ids.iter().rev() {
+        b.set_component(id, "Health", r#"{"max":2,"current":1}"#)
+            .unwrap();
+    }
+    b.seed_rng(a.rng_seed());
+    assert_eq!(a.state_hash(), b.state_hash());
+    b.tick();
+    assert_ne!(a.state_hash(), b.state_hash());
+}
+
+#[test]
+fn test_wasm_world_system_hash_log() {
+    let mut world = WasmWorld::new();
+    world.register_system("Growth", "script");
+    world.tick();
+    assert!(world.take_system_hashes().is_empty());
+
+    world.set_system_hash_debug(true);
+    world.tick();
+    world.run_system("Growth").unwrap();
+    let log = world.take_system_hashes();
+    let systems: Vec<&str> = log.iter().map(|entry| entry.system.as_str()).collect();
+    assert_eq!(systems, [TICK_START, "Growth"]);
+    assert_eq!(log[1].hash, world.state_hash());
+    assert!(world.system_hash_debug());
+    assert!(world.take_system_hashes().is_empty());
+}
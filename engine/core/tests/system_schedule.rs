@@ -0,0 +1,427 @@
+#[path = "helpers/world.rs"]
+mod world_helper;
+use world_helper::make_test_world;
+
+use engine_core::ecs::schedule::{RunCondition, SystemStage, SystemView, plan_batches};
+use engine_core::ecs::system::{System, SystemAccess, SystemCommands};
+use engine_core::ecs::world::World;
+use engine_core::map::{Map, SquareGridMap};
+use engine_core::systems::faction_reputation::FactionReputationSystem;
+use engine_core::systems::fov::FovUpdateSystem;
+use serde_json::json;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+fn access(reads: &[&str], writes: &[&str]) -> Option<SystemAccess> {
+    Some(SystemAccess::new().read(reads).write(writes))
+}
+
+#[test]
+fn test_plan_batches_splits_on_conflicts() {
+    let systems = vec![
+        ("A".to_string(), access(&["Position"], &["Health"])),
+        ("B".to_string(), access(&["Position"], &["Stats"])),
+        ("C".to_string(), access(&["Health"], &[])),
+        ("D".to_string(), None),
+        ("E".to_string(), access(&[], &["Reputation"])),
+        ("F".to_string(), access(&["Sight"], &[])),
+    ];
+    assert_eq!(
+        plan_batches(&systems),
+        vec![
+            vec!["A".to_string(), "B".to_string()],
+            vec!["C".to_string()],
+            vec!["D".to_string()],
+            vec!["E".to_string(), "F".to_string()],
+        ]
+    );
+}
+
+#[test]
+fn test_plan_batches_splits_on_resource_conflicts() {
+    let fov = SystemAccess::new()
+        .read(&["Sight"])
+        .write_resources(&[SystemAccess::FOV_ALGORITHM]);
+    let systems = vec![
+        ("Fov".to_string(), Some(fov.clone())),
+        ("Reputation".to_string(), access(&[], &["Reputation"])),
+        (
+            "Reader".to_string(),
+            Some(SystemAccess::new().read_resources(&[SystemAccess::FOV_ALGORITHM])),
+        ),
+        ("Fov2".to_string(), Some(fov)),
+    ];
+    assert_eq!(
+        plan_batches(&systems),
+        vec![
+            vec!["Fov".to_string(), "Reputation".to_string()],
+            vec!["Reader".to_string()],
+            vec!["Fov2".to_string()],
+        ]
+    );
+}
+
+/// Writes `value` into `Stats.<key>` from its parallel phase, after checking that
+/// undeclared components are invisible to it.
+struct StatWriter {
+    name: &'static str,
+    key: &'static str,
+    value: f64,
+}
+
+impl System for StatWriter {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn run(&mut self, _world: &mut World) {
+        panic!("{} should run through run_parallel", self.name);
+    }
+
+    fn access(&self) -> Option<SystemAccess> {
+        Some(SystemAccess::new().read(&["Type"]))
+    }
+
+    fn run_parallel(&mut self, view: &SystemView<'_>) -> Option<SystemCommands> {
+        let entities = view.get_entities_with_component("Type");
+        assert!(view.get_entities_with_component("Health").is_empty());
+        let key = self.key;
+        let value = self.value;
+        Some(Box::new(move |world: &mut World| {
+            for eid in entities {
+                let mut stats = world
+                    .get_component(eid, "Stats")
+                    .cloned()
+                    .unwrap_or(json!({}));
+                stats[key] = json!(value);
+                world.set_component(eid, "Stats", stats).unwrap();
+            }
+        }))
+    }
+}
+
+#[test]
+fn test_parallel_batch_applies_commands_in_order() {
+    let mut world = make_test_world();
+    world.current_mode = "roguelike".to_string();
+    let eid = world.spawn_entity();
+    world
+        .set_component(eid, "Type", json!({ "kind": "dwarf" }))
+        .unwrap();
+    world
+        .set_component(eid, "Health", json!({ "current": 5.0, "max": 10.0 }))
+        .unwrap();
+    world.register_system(StatWriter {
+        name: "WriterA",
+        key: "strength",
+        value: 1.0,
+    });
+    world.register_system(StatWriter {
+        name: "WriterB",
+        key: "dexterity",
+        value: 2.0,
+    });
+    let batches = world.system_batches();
+    assert_eq!(batches.len(), 1);
+    assert_eq!(batches[0].len(), 2);
+
+    let world_rc = Rc::new(RefCell::new(world));
+    World::simulation_tick(Rc::clone(&world_rc));
+    let world = world_rc.borrow();
+    let stats = world.get_component(eid, "Stats").unwrap();
+    assert_eq!(stats["strength"], json!(1.0));
+    assert_eq!(stats["dexterity"], json!(2.0));
+}
+
+#[test]
+fn test_system_view_queries_follow_the_mode() {
+    let mut world = make_test_world();
+    world.current_mode = "colony".to_string();
+    let eid = world.spawn_entity();
+    world
+        .set_component(
+            eid,
+            "Hazard",
+            json!({ "active": true, "slowdown_factor": 0.5 }),
+        )
+        .unwrap();
+    let access = SystemAccess::new().read(&["Hazard"]);
+
+    assert_eq!(
+        world
+            .system_view("HazardReader", &access)
+            .get_entities_with_component("Hazard"),
+        vec![eid]
+    );
+    world.current_mode = "roguelike".to_string();
+    let view = world.system_view("HazardReader", &access);
+    assert!(view.get_entities_with_component("Hazard").is_empty());
+    assert!(view.get_component(eid, "Hazard").is_none());
+}
+
+/// A world with observers and reputation decay on a small open map.
+fn sim_world() -> World {
+    let mut world = make_test_world();
+    world.seed_rng(7);
+    let mut grid = SquareGridMap::new();
+    for x in 0..8 {
+        for y in 0..8 {
+            grid.add_cell(x, y, 0);
+        }
+    }
+    for x in 0..8 {
+        for y in 0..8 {
+            if x + 1 < 8 {
+                grid.add_neighbor((x, y, 0), (x + 1, y, 0));
+            }
+            if y + 1 < 8 {
+                grid.add_neighbor((x, y, 0), (x, y + 1, 0));
+            }
+        }
+    }
+    world.map = Some(Map::new(Box::new(grid)));
+    for i in 0..4 {
+        let eid = world.spawn_entity();
+        world
+            .set_component(eid, "Sight", json!({ "range": 3 + i }))
+            .unwrap();
+        world
+            .set_component(
+                eid,
+                "Position",
+                json!({ "pos": { "Square": { "x": i * 2, "y": i, "z": 0 } } }),
+            )
+            .unwrap();
+        world
+            .set_component(
+                eid,
+                "Reputation",
+                json!({ "values": { "guild": 10 * i, "bandits": -5 * i }, "decay_rate": 1.0 }),
+            )
+            .unwrap();
+    }
+    world.register_system(FactionReputationSystem);
+    world.register_system(FovUpdateSystem);
+    world.set_system_hash_debug(true);
+    world
+}
+
+#[test]
+fn test_parallel_ticks_match_serial_ticks() {
+    let parallel = sim_world();
+    assert_eq!(
+        parallel.system_batches(),
+        vec![vec![
+            "FactionReputationSystem".to_string(),
+            "FovUpdateSystem".to_string()
+        ]]
+    );
+    let mut serial = sim_world();
+    serial.set_parallel_systems(false);
+    assert_eq!(serial.system_batches().len(), 2);
+
+    let parallel = Rc::new(RefCell::new(parallel));
+    let serial = Rc::new(RefCell::new(serial));
+    for _ in 0..5 {
+        World::tick(Rc::clone(&parallel));
+        World::tick(Rc::clone(&serial));
+    }
+
+    let parallel = parallel.borrow();
+    let serial = serial.borrow();
+    assert_eq!(parallel.system_hashes(), serial.system_hashes());
+    assert_eq!(parallel.state_hash(), serial.state_hash());
+    assert_eq!(parallel.visible_cells, serial.visible_cells);
+    assert!(!parallel.visible_cells.is_empty());
+    assert_eq!(
+        parallel.get_component(4, "Reputation").unwrap()["values"]["guild"],
+        json!(25)
+    );
+}
+
+/// Appends its name to the `log` event queue when it runs.
+struct Logger {
+    name: &'static str,
+    stage: SystemStage,
+}
+
+impl System for Logger {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn stage(&self) -> SystemStage {
+        self.stage
+    }
+
+    fn run(&mut self, world: &mut World) {
+        world.emit_event("log", json!(self.name));
+    }
+}
+
+fn logged(world: &mut World) -> Vec<String> {
+    world.update_event_queues();
+    let mut names = Vec::new();
+    world.process_events("log", |event| {
+        names.push(event.as_str().unwrap().to_string())
+    });
+    names
+}
+
+#[test]
+fn test_stages_run_in_order() {
+    let mut world = make_test_world();
+    world.register_system(Logger {
+        name: "Draw",
+        stage: SystemStage::Render,
+    });
+    world.register_system(Logger {
+        name: "Cleanup",
+        stage: SystemStage::PostUpdate,
+    });
+    world.register_system(Logger {
+        name: "Simulate",
+        stage: SystemStage::Update,
+    });
+    world.register_system(Logger {
+        name: "Input",
+        stage: SystemStage::Update,
+    });
+    world.set_system_stage("Input", SystemStage::PreUpdate);
+    assert_eq!(world.system_stage("Input"), SystemStage::PreUpdate);
+    assert_eq!(world.system_stage("Unknown"), SystemStage::Update);
+
+    let world_rc = Rc::new(RefCell::new(world));
+    World::tick(Rc::clone(&world_rc));
+    assert_eq!(
+        logged(&mut world_rc.borrow_mut()),
+        vec!["Input", "Simulate", "Cleanup", "Draw"]
+    );
+}
+
+#[test]
+fn test_dynamic_systems_run_after_native_systems_of_their_stage() {
+    let mut world = make_test_world();
+    world.register_system(Logger {
+        name: "Simulate",
+        stage: SystemStage::Update,
+    });
+    world.register_system(Logger {
+        name: "Draw",
+        stage: SystemStage::Render,
+    });
+    world.register_dynamic_system("Script", |world_rc, _dt| {
+        world_rc.borrow_mut().emit_event("log", json!("Script"));
+    });
+    world.register_dynamic_system("Setup", |world_rc, _dt| {
+        world_rc.borrow_mut().emit_event("log", json!("Setup"));
+    });
+    world.set_system_stage("Setup", SystemStage::PreUpdate);
+
+    let world_rc = Rc::new(RefCell::new(world));
+    World::tick(Rc::clone(&world_rc));
+    assert_eq!(
+        logged(&mut world_rc.borrow_mut()),
+        vec!["Setup", "Simulate", "Script", "Draw"]
+    );
+}
+
+#[test]
+fn test_run_conditions() {
+    let mut world = make_test_world();
+    world.current_mode = "roguelike".to_string();
+    for name in ["Always", "ColonyOnly", "EveryThird", "OnAlarm"] {
+        world.register_system(Logger {
+            name,
+            stage: SystemStage::Update,
+        });
+    }
+    world.add_run_condition(
+        "ColonyOnly",
+        RunCondition::InMode {
+            mode: "colony".to_string(),
+        },
+    );
+    world.add_run_condition("EveryThird", RunCondition::EveryNTicks { n: 3, offset: 1 });
+    world.add_run_condition(
+        "OnAlarm",
+        RunCondition::EventsPending {
+            bus: "Alarm".to_string(),
+        },
+    );
+
+    let world_rc = Rc::new(RefCell::new(world));
+    let mut runs = Vec::new();
+    for turn in 0..4 {
+        if turn == 2 {
+            world_rc
+                .borrow_mut()
+                .send_event("Alarm", json!({}))
+                .unwrap();
+        }
+        World::tick(Rc::clone(&world_rc));
+        let mut names = logged(&mut world_rc.borrow_mut());
+        names.sort();
+        runs.push(names);
+    }
+    assert_eq!(
+        runs,
+        vec![
+            vec!["Always"],
+            vec!["Always", "EveryThird"],
+            vec!["Always", "OnAlarm"],
+            vec!["Always", "OnAlarm"],
+        ]
+    );
+
+    let mut world = world_rc.borrow_mut();
+    world.current_mode = "colony".to_string();
+    assert!(world.should_run_system("ColonyOnly"));
+    world.clear_run_conditions("OnAlarm");
+    assert!(world.run_conditions("OnAlarm").is_empty());
+}
+
+#[test]
+fn test_run_condition_json() {
+    let condition: RunCondition =
+        serde_json::from_value(json!({ "type": "every_n_ticks", "n": 5 })).unwrap();
+    assert_eq!(condition, RunCondition::EveryNTicks { n: 5, offset: 0 });
+    assert_eq!(
+        serde_json::to_value(RunCondition::InMode {
+            mode: "colony".to_string()
+        })
+        .unwrap(),
+        json!({ "type": "in_mode", "mode": "colony" })
+    );
+    assert_eq!(
+        SystemStage::from_name("post_update"),
+        Some(SystemStage::PostUpdate)
+    );
+}
+
+#[test]
+fn test_advance_runs_fixed_ticks_and_passes_dt() {
+    let mut world = make_test_world();
+    world.set_fixed_timestep(0.1);
+    world.register_dynamic_system("Clock", |world_rc, dt| {
+        world_rc.borrow_mut().emit_event("log", json!(dt));
+    });
+    let world_rc = Rc::new(RefCell::new(world));
+
+    assert_eq!(World::advance(Rc::clone(&world_rc), 0.05), 0);
+    assert_eq!(World::advance(Rc::clone(&world_rc), 0.26), 3);
+    assert_eq!(world_rc.borrow().turn, 3);
+    assert!((world_rc.borrow().timestep_alpha() - 0.1).abs() < 1e-3);
+    {
+        let mut world = world_rc.borrow_mut();
+        world.update_event_queues();
+        let mut dts = Vec::new();
+        world.process_events("log", |event| dts.push(event.as_f64().unwrap() as f32));
+        assert_eq!(dts, vec![0.1; 3]);
+    }
+
+    // Long stalls are capped instead of replayed tick by tick
+    world_rc.borrow_mut().set_max_ticks_per_advance(4);
+    assert_eq!(World::advance(Rc::clone(&world_rc), 10.0), 4);
+    assert!(world_rc.borrow().timestep_alpha() < 1.0);
+}
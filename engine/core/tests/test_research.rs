@@ -0,0 +1,226 @@
+#[path = "helpers/world.rs"]
+mod world_helper;
+
+use engine_core::ecs::system::System;
+use engine_core::ecs::world::World;
+use engine_core::faction::set_faction;
+use engine_core::systems::economic::{EconomicSystem, Recipe};
+use engine_core::systems::research::{RESEARCH_RESOURCE, ResearchSystem, credit_research};
+use engine_core::tech_tree::{
+    TechTree, can_research_tech, cancel_research, clear_research_queue,
+    get_research_queue_progress, get_tech_progress, is_tech_completed, research_tech,
+};
+use serde_json::json;
+
+fn setup_world() -> World {
+    let mut world = world_helper::make_test_world();
+    world.current_mode = "colony".to_string();
+    world.tech_tree.merge(
+        TechTree::from_json(json!([
+            { "id": "writing", "name": "Writing", "cost": 10 },
+            {
+                "id": "smelting",
+                "name": "Smelting",
+                "cost": 10,
+                "resource_cost": { "ore": 3, "wood": 1 }
+            }
+        ]))
+        .unwrap(),
+    );
+    world
+}
+
+/// A TechProgress that gains no points by itself.
+fn passive_progress(world: &mut World, entity: u32) {
+    world
+        .set_component(
+            entity,
+            "TechProgress",
+            json!({"completed": {}, "queue": [], "queue_progress": {}, "research_points": 0.0, "base_rate": 0.0}),
+        )
+        .unwrap();
+}
+
+fn progress_of(world: &World, entity: u32, tech_id: &str) -> f64 {
+    get_research_queue_progress(world, entity)[tech_id]
+        .as_f64()
+        .unwrap_or(0.0)
+}
+
+#[test]
+fn test_base_rate_sets_passive_research() {
+    let mut world = setup_world();
+    let scholar = world.spawn_entity();
+    let idle = world.spawn_entity();
+    research_tech(&mut world, scholar, "writing").unwrap();
+    research_tech(&mut world, idle, "writing").unwrap();
+    let mut progress = get_tech_progress(&world, scholar).unwrap();
+    progress["base_rate"] = json!(1.0);
+    world
+        .set_component(scholar, "TechProgress", progress)
+        .unwrap();
+
+    ResearchSystem.run(&mut world);
+    assert_eq!(progress_of(&world, scholar, "writing"), 1.0);
+    assert_eq!(
+        progress_of(&world, idle, "writing"),
+        0.0,
+        "Passive research is off by default"
+    );
+    assert_eq!(
+        get_tech_progress(&world, scholar).unwrap()["base_rate"],
+        1.0,
+        "base_rate is kept when the system writes TechProgress"
+    );
+}
+
+#[test]
+fn test_agents_working_research_jobs_produce_points() {
+    let mut world = setup_world();
+    let researcher = world.spawn_entity();
+    passive_progress(&mut world, researcher);
+    world
+        .set_component(
+            researcher,
+            "Agent",
+            json!({ "entity_id": researcher, "skills": { "research": 3.0 }, "state": "working" }),
+        )
+        .unwrap();
+    let job = world.spawn_entity();
+    world
+        .set_component(
+            job,
+            "Job",
+            json!({
+                "job_type": "study",
+                "category": "research",
+                "state": "in_progress",
+                "assigned_to": researcher
+            }),
+        )
+        .unwrap();
+    research_tech(&mut world, researcher, "writing").unwrap();
+
+    ResearchSystem.run(&mut world);
+    assert_eq!(progress_of(&world, researcher, "writing"), 3.0);
+
+    let mut job_value = world.get_component(job, "Job").unwrap().clone();
+    job_value["state"] = json!("paused");
+    world.set_component(job, "Job", job_value).unwrap();
+    ResearchSystem.run(&mut world);
+    assert_eq!(
+        progress_of(&world, researcher, "writing"),
+        3.0,
+        "Only in-progress research jobs produce points"
+    );
+}
+
+#[test]
+fn test_faction_members_share_a_research_pool() {
+    let mut world = setup_world();
+    let lab = world.spawn_entity();
+    let capital = world.spawn_entity();
+    let outsider = world.spawn_entity();
+    set_faction(&mut world, lab, "empire", "member").unwrap();
+    set_faction(&mut world, capital, "empire", "leader").unwrap();
+    passive_progress(&mut world, capital);
+    passive_progress(&mut world, outsider);
+    research_tech(&mut world, capital, "writing").unwrap();
+
+    assert!(credit_research(&mut world, lab, 25.0));
+    assert!(credit_research(&mut world, outsider, 2.0));
+    let nobody = world.spawn_entity();
+    assert!(!credit_research(&mut world, nobody, 2.0));
+    assert_eq!(world.research_pools.get("empire"), 25.0);
+    assert_eq!(
+        get_tech_progress(&world, outsider).unwrap()["research_points"],
+        2.0
+    );
+
+    ResearchSystem.run(&mut world);
+    assert!(is_tech_completed(&world, capital, "writing"));
+    assert_eq!(
+        world.research_pools.get("empire"),
+        15.0,
+        "Members only draw what their queue needs"
+    );
+
+    let snapshot = world.snapshot().unwrap();
+    world.research_pools.take("empire", 100.0);
+    world.restore(&snapshot).unwrap();
+    assert_eq!(world.research_pools.get("empire"), 15.0);
+}
+
+#[test]
+fn test_research_recipes_credit_research_points() {
+    let mut world = setup_world();
+    let library = world.spawn_entity();
+    passive_progress(&mut world, library);
+    world
+        .set_component(library, "Stockpile", json!({ "resources": { "paper": 2 } }))
+        .unwrap();
+    world
+        .set_component(
+            library,
+            "ProductionJob",
+            json!({ "recipe": "scribe", "progress": 0, "state": "pending" }),
+        )
+        .unwrap();
+    let recipe: Recipe = serde_json::from_value(json!({
+        "name": "scribe",
+        "inputs": [{ "kind": "paper", "amount": 1 }],
+        "outputs": [{ "kind": RESEARCH_RESOURCE, "amount": 5 }],
+        "duration": 1
+    }))
+    .unwrap();
+    EconomicSystem::with_recipes(vec![recipe]).run(&mut world);
+
+    assert_eq!(
+        get_tech_progress(&world, library).unwrap()["research_points"],
+        5.0
+    );
+    let resources = &world.get_component(library, "Stockpile").unwrap()["resources"];
+    assert_eq!(resources["paper"], 1);
+    assert!(resources.get(RESEARCH_RESOURCE).is_none());
+}
+
+#[test]
+fn test_tech_resource_costs_are_paid_and_refunded() {
+    let mut world = setup_world();
+    let smith = world.spawn_entity();
+    world
+        .set_component(
+            smith,
+            "Stockpile",
+            json!({ "resources": { "ore": 2, "wood": 5 } }),
+        )
+        .unwrap();
+    let err = can_research_tech(&world, smith, "smelting").unwrap_err();
+    assert!(err.contains("ore"), "unexpected reason: {err}");
+    assert!(research_tech(&mut world, smith, "smelting").is_err());
+
+    world
+        .set_component(
+            smith,
+            "Stockpile",
+            json!({ "resources": { "ore": 4, "wood": 5 } }),
+        )
+        .unwrap();
+    research_tech(&mut world, smith, "smelting").unwrap();
+    let resources = world.get_component(smith, "Stockpile").unwrap()["resources"].clone();
+    assert_eq!(resources["ore"], 1);
+    assert_eq!(resources["wood"], 4);
+
+    cancel_research(&mut world, smith, "smelting").unwrap();
+    let resources = world.get_component(smith, "Stockpile").unwrap()["resources"].clone();
+    assert_eq!(resources["ore"], 4);
+    assert_eq!(resources["wood"], 5);
+
+    research_tech(&mut world, smith, "smelting").unwrap();
+    research_tech(&mut world, smith, "writing").unwrap();
+    clear_research_queue(&mut world, smith).unwrap();
+    assert_eq!(
+        world.get_component(smith, "Stockpile").unwrap()["resources"]["ore"],
+        4
+    );
+}
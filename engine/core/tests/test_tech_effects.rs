@@ -0,0 +1,352 @@
+#[path = "helpers/world.rs"]
+mod world_helper;
+
+use engine_core::ecs::system::System;
+use engine_core::ecs::world::World;
+use engine_core::faction::set_faction;
+use engine_core::systems::economic::{EconomicSystem, load_recipes_from_dir};
+use engine_core::systems::job::assign_jobs;
+use engine_core::systems::job::job_board::JobBoard;
+use engine_core::systems::research::{ResearchSystem, credit_research};
+use engine_core::systems::stat_calculation::StatCalculationSystem;
+use engine_core::tech_effects::{
+    TechEffect, get_production_multiplier, get_stat_modifiers, get_tech_effects, grant_tech,
+    is_job_type_unlocked, is_recipe_unlocked, revoke_tech,
+};
+use engine_core::tech_tree::{TechTree, TechTreeError, is_tech_completed, research_tech};
+use serde_json::json;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicI64, Ordering};
+
+fn setup_world() -> World {
+    let mut world = world_helper::make_test_world();
+    world.current_mode = "colony".to_string();
+    let tree = TechTree::from_json(json!([
+        {
+            "id": "carpentry",
+            "name": "Carpentry",
+            "cost": 1,
+            "effects": [
+                { "action": "unlock_recipe", "data": { "recipe": "wood_plank" } },
+                { "action": "production_multiplier", "data": { "resource": "plank", "multiplier": 1.5 } },
+                { "action": "stat_modifier", "data": { "stat": "strength", "amount": 2 } }
+            ]
+        },
+        {
+            "id": "mining",
+            "name": "Mining",
+            "cost": 1,
+            "effects": [
+                { "action": "unlock_job", "data": { "job_type": "dig" } },
+                { "action": "log_message", "data": { "message": "Mining unlocked" } }
+            ]
+        },
+        {
+            "id": "joinery",
+            "name": "Joinery",
+            "cost": 1,
+            "effects": [
+                { "action": "unlock_recipe", "data": { "recipe": "plank_to_furniture" } }
+            ]
+        }
+    ]))
+    .unwrap();
+    world.tech_tree.merge(tree);
+    world
+}
+
+#[test]
+fn test_research_applies_effects_to_the_researchers_faction() {
+    let mut world = setup_world();
+    let researcher = world.spawn_entity();
+    let ally = world.spawn_entity();
+    let outsider = world.spawn_entity();
+    set_faction(&mut world, researcher, "guild", "member").unwrap();
+    set_faction(&mut world, ally, "guild", "member").unwrap();
+
+    assert!(!is_recipe_unlocked(&world, ally, "wood_plank"));
+    assert!(is_recipe_unlocked(&world, ally, "smelt_iron"));
+
+    research_tech(&mut world, researcher, "carpentry").unwrap();
+    credit_research(&mut world, researcher, 1.0);
+    ResearchSystem.run(&mut world);
+    assert!(is_tech_completed(&world, researcher, "carpentry"));
+
+    for entity in [researcher, ally] {
+        assert!(is_recipe_unlocked(&world, entity, "wood_plank"));
+        assert_eq!(get_production_multiplier(&world, entity, "plank"), 1.5);
+        assert_eq!(get_stat_modifiers(&world, entity)["strength"], 2.0);
+    }
+    assert!(!is_recipe_unlocked(&world, outsider, "wood_plank"));
+    assert_eq!(get_production_multiplier(&world, outsider, "plank"), 1.0);
+    assert!(get_tech_effects(&world, outsider).is_empty());
+}
+
+#[test]
+fn test_factions_keep_effects_while_a_member_has_the_tech() {
+    let mut world = setup_world();
+    let researcher = world.spawn_entity();
+    let ally = world.spawn_entity();
+    set_faction(&mut world, researcher, "guild", "member").unwrap();
+    set_faction(&mut world, ally, "guild", "member").unwrap();
+    grant_tech(&mut world, researcher, "carpentry").unwrap();
+    grant_tech(&mut world, ally, "carpentry").unwrap();
+
+    revoke_tech(&mut world, researcher, "carpentry").unwrap();
+    assert!(
+        is_recipe_unlocked(&world, researcher, "wood_plank"),
+        "The ally still has carpentry"
+    );
+    revoke_tech(&mut world, ally, "carpentry").unwrap();
+    assert!(!is_recipe_unlocked(&world, researcher, "wood_plank"));
+    assert!(!is_recipe_unlocked(&world, ally, "wood_plank"));
+}
+
+#[test]
+fn test_locked_recipes_wait_until_unlocked_and_use_multipliers() {
+    let mut world = setup_world();
+    let workshop = world.spawn_entity();
+    world
+        .set_component(workshop, "Stockpile", json!({ "resources": { "wood": 3 } }))
+        .unwrap();
+    world
+        .set_component(
+            workshop,
+            "ProductionJob",
+            json!({ "recipe": "wood_plank", "progress": 0, "state": "pending" }),
+        )
+        .unwrap();
+    let recipe_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap() + "/../assets/recipes";
+    let mut econ_system = EconomicSystem::with_recipes(load_recipes_from_dir(&recipe_dir));
+
+    econ_system.run(&mut world);
+    let job = world.get_component(workshop, "ProductionJob").unwrap();
+    assert_eq!(job["state"], "locked");
+    assert_eq!(
+        world.get_component(workshop, "Stockpile").unwrap()["resources"]["wood"],
+        3
+    );
+
+    grant_tech(&mut world, workshop, "carpentry").unwrap();
+    econ_system.run(&mut world);
+    let resources = &world.get_component(workshop, "Stockpile").unwrap()["resources"];
+    assert_eq!(resources["wood"], 2);
+    assert_eq!(
+        resources["plank"], 6,
+        "4 planks scaled by the 1.5 multiplier"
+    );
+}
+
+#[test]
+fn test_jobs_resume_their_state_when_unlocked_again() {
+    let mut world = setup_world();
+    let worker = world.spawn_entity();
+    let recipe_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap() + "/../assets/recipes";
+    let mut econ_system = EconomicSystem::with_recipes(load_recipes_from_dir(&recipe_dir));
+    // plank_to_furniture takes 3 ticks
+    let mut workshop = |assigned_to: Option<u32>| {
+        let workshop = world.spawn_entity();
+        set_faction(&mut world, workshop, "guild", "member").unwrap();
+        world
+            .set_component(
+                workshop,
+                "Stockpile",
+                json!({ "resources": { "plank": 10 } }),
+            )
+            .unwrap();
+        world
+            .set_component(
+                workshop,
+                "ProductionJob",
+                json!({
+                    "recipe": "plank_to_furniture",
+                    "progress": 0,
+                    "state": "pending",
+                    "assigned_to": assigned_to,
+                }),
+            )
+            .unwrap();
+        workshop
+    };
+    let assigned = workshop(Some(worker));
+    let unassigned = workshop(None);
+    let job = |world: &World, workshop| {
+        world
+            .get_component(workshop, "ProductionJob")
+            .unwrap()
+            .clone()
+    };
+
+    econ_system.run(&mut world);
+    assert_eq!(job(&world, assigned)["state"], "locked");
+    assert_eq!(job(&world, unassigned)["state"], "locked");
+
+    grant_tech(&mut world, assigned, "joinery").unwrap();
+    econ_system.run(&mut world);
+    assert_eq!(job(&world, assigned)["state"], "in_progress");
+    assert_eq!(job(&world, assigned)["progress"], 1);
+    assert_eq!(job(&world, unassigned)["state"], "pending");
+    assert_eq!(job(&world, unassigned)["progress"], 1);
+
+    revoke_tech(&mut world, assigned, "joinery").unwrap();
+    econ_system.run(&mut world);
+    assert_eq!(job(&world, assigned)["state"], "locked");
+    assert_eq!(job(&world, assigned)["progress"], 1);
+
+    grant_tech(&mut world, assigned, "joinery").unwrap();
+    econ_system.run(&mut world);
+    assert_eq!(job(&world, assigned)["state"], "in_progress");
+    assert_eq!(job(&world, assigned)["progress"], 2);
+    econ_system.run(&mut world);
+    assert_eq!(job(&world, assigned)["state"], "complete");
+}
+
+#[test]
+fn test_stat_modifiers_feed_stat_calculation_and_revert() {
+    let mut world = setup_world();
+    world.register_system(StatCalculationSystem);
+    let eid = world.spawn_entity();
+    world
+        .set_component(eid, "BaseStats", json!({ "strength": 10.0 }))
+        .unwrap();
+
+    world.run_system("StatCalculationSystem").unwrap();
+    assert_eq!(world.get_component(eid, "Stats").unwrap()["strength"], 10.0);
+
+    grant_tech(&mut world, eid, "carpentry").unwrap();
+    world.run_system("StatCalculationSystem").unwrap();
+    assert_eq!(world.get_component(eid, "Stats").unwrap()["strength"], 12.0);
+
+    revoke_tech(&mut world, eid, "carpentry").unwrap();
+    world.run_system("StatCalculationSystem").unwrap();
+    assert_eq!(world.get_component(eid, "Stats").unwrap()["strength"], 10.0);
+    assert!(!is_tech_completed(&world, eid, "carpentry"));
+    assert!(revoke_tech(&mut world, eid, "carpentry").is_err());
+}
+
+#[test]
+fn test_locked_job_types_are_only_assigned_to_unlocked_agents() {
+    engine_core::systems::job::system::events::init_job_event_logger();
+    let mut world = setup_world();
+    let agent = world.spawn_entity();
+    world
+        .set_component(
+            agent,
+            "Agent",
+            json!({ "entity_id": agent, "skills": { "dig": 5.0 }, "state": "idle" }),
+        )
+        .unwrap();
+    let job = world.spawn_entity();
+    world
+        .set_component(
+            job,
+            "Job",
+            json!({ "job_type": "dig", "state": "pending", "priority": 1, "category": "mining" }),
+        )
+        .unwrap();
+
+    let mut job_board = JobBoard::default();
+    assign_jobs(&mut world, &mut job_board, 0, &[]);
+    assert!(!is_job_type_unlocked(&world, agent, "dig"));
+    assert!(
+        world.get_component(job, "Job").unwrap()["assigned_to"].is_null(),
+        "Locked job type should not be assigned"
+    );
+
+    grant_tech(&mut world, agent, "mining").unwrap();
+    assign_jobs(&mut world, &mut job_board, 1, &[]);
+    assert_eq!(
+        world.get_component(job, "Job").unwrap()["assigned_to"],
+        agent
+    );
+    assert_eq!(
+        get_tech_effects(&world, agent),
+        vec![TechEffect::UnlockJob {
+            job_type: "dig".to_string()
+        }]
+    );
+}
+
+#[test]
+fn test_gates_follow_changes_to_the_tree() {
+    let mut world = setup_world();
+    let agent = world.spawn_entity();
+    assert!(!is_job_type_unlocked(&world, agent, "dig"));
+
+    let mining = world.tech_tree.remove("mining").unwrap();
+    assert!(is_job_type_unlocked(&world, agent, "dig"));
+
+    world.tech_tree.insert(mining);
+    assert!(!is_job_type_unlocked(&world, agent, "dig"));
+
+    let mut tree = TechTree::new();
+    tree.merge(world.tech_tree.clone());
+    tree.insert(
+        serde_json::from_value(json!({ "id": "carpentry", "name": "Carpentry", "cost": 1 }))
+            .unwrap(),
+    );
+    world.tech_tree = tree;
+    assert!(is_recipe_unlocked(&world, agent, "wood_plank"));
+    assert!(!is_recipe_unlocked(&world, agent, "plank_to_furniture"));
+}
+
+#[test]
+fn test_custom_effects_run_registered_handlers_and_undo() {
+    let mut world = setup_world();
+    let messages = Arc::new(AtomicI64::new(0));
+    let counter = messages.clone();
+    world.register_effect_handler("log_message", move |_, _, _| {
+        counter.fetch_add(1, Ordering::SeqCst);
+    });
+    let counter = messages.clone();
+    world
+        .effect_processor_registry
+        .as_ref()
+        .unwrap()
+        .lock()
+        .unwrap()
+        .register_undo_handler("Undolog_message", move |_, _, _| {
+            counter.fetch_sub(1, Ordering::SeqCst);
+        });
+
+    let eid = world.spawn_entity();
+    grant_tech(&mut world, eid, "mining").unwrap();
+    grant_tech(&mut world, eid, "mining").unwrap();
+    assert_eq!(messages.load(Ordering::SeqCst), 1, "Effects apply once");
+    revoke_tech(&mut world, eid, "mining").unwrap();
+    assert_eq!(messages.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn test_tech_unlocks_are_saved_with_the_world() {
+    let mut world = setup_world();
+    let eid = world.spawn_entity();
+    set_faction(&mut world, eid, "guild", "leader").unwrap();
+    grant_tech(&mut world, eid, "carpentry").unwrap();
+
+    let snapshot = world.snapshot().unwrap();
+    revoke_tech(&mut world, eid, "carpentry").unwrap();
+    assert!(!is_recipe_unlocked(&world, eid, "wood_plank"));
+    world.restore(&snapshot).unwrap();
+    assert!(is_recipe_unlocked(&world, eid, "wood_plank"));
+}
+
+#[test]
+fn test_validation_reports_malformed_standard_effects() {
+    let mut world = setup_world();
+    world.tech_tree.merge(
+        TechTree::from_json(json!({
+            "id": "broken",
+            "name": "Broken",
+            "effects": [{ "action": "stat_modifier", "data": { "stat": "strength" } }]
+        }))
+        .unwrap(),
+    );
+    let errors = world.tech_tree.validate().unwrap_err();
+    assert!(matches!(
+        &errors[..],
+        [TechTreeError::InvalidEffect { tech, action, .. }] if tech == "broken" && action == "stat_modifier"
+    ));
+    let eid = world.spawn_entity();
+    assert!(grant_tech(&mut world, eid, "broken").is_err());
+}
@@ -4,9 +4,9 @@ use engine_core::ecs::system::System;
 use engine_core::ecs::world::World;
 use engine_core::systems::research::ResearchSystem;
 use engine_core::tech_tree::{
-    self, can_research_tech, cancel_research, clear_research_queue, get_completed_techs,
-    get_research_queue, get_research_queue_progress, get_tech_node, get_tech_progress,
-    is_tech_completed, research_tech,
+    self, TechTree, TechTreeError, can_research_tech, cancel_research, clear_research_queue,
+    get_completed_techs, get_research_queue, get_research_queue_progress, get_tech_node,
+    get_tech_progress, is_tech_completed, research_tech,
 };
 use serde_json::{Value as JsonValue, json};
 use std::sync::{Arc, Mutex};
@@ -35,12 +35,16 @@ fn setup_world() -> World {
     let mut world = World::new(registry);
     world.current_mode = "colony".to_string();
     world
+        .tech_tree
+        .load(concat!(env!("CARGO_MANIFEST_DIR"), "/../assets/tech"))
+        .unwrap();
+    world
 }
 
 #[test]
 fn test_get_tech_tree_returns_nodes() {
-    let _world = setup_world();
-    let tree = tech_tree::get_tech_tree();
+    let world = setup_world();
+    let tree = tech_tree::get_tech_tree(&world);
     assert!(!tree.is_empty(), "Tech tree should have nodes");
     let ids: Vec<&str> = tree.iter().map(|n| n.id.as_str()).collect();
     assert!(
@@ -52,16 +56,16 @@ fn test_get_tech_tree_returns_nodes() {
 
 #[test]
 fn test_get_tech_node_exists() {
-    let _world = setup_world();
-    let node = get_tech_node("bronze_working");
+    let world = setup_world();
+    let node = get_tech_node(&world, "bronze_working");
     assert!(node.is_some(), "bronze_working should exist");
     assert_eq!(node.unwrap().name, "Bronze Working");
 }
 
 #[test]
 fn test_get_tech_node_missing() {
-    let _world = setup_world();
-    let node = get_tech_node("nonexistent_tech");
+    let world = setup_world();
+    let node = get_tech_node(&world, "nonexistent_tech");
     assert!(node.is_none(), "Nonexistent tech should return None");
 }
 
@@ -268,11 +272,21 @@ fn test_get_research_queue_progress() {
     assert_eq!(progress, 0.0, "Initial progress should be 0");
 }
 
+/// Opt an entity into passive research.
+fn set_base_rate(world: &mut World, entity: u32, rate: f64) {
+    let mut progress = get_tech_progress(world, entity).unwrap();
+    progress["base_rate"] = json!(rate);
+    world
+        .set_component(entity, "TechProgress", progress)
+        .unwrap();
+}
+
 #[test]
 fn test_research_system_allocates_points() {
     let mut world = setup_world();
     let entity = world.spawn_entity();
     research_tech(&mut world, entity, "bronze_working").unwrap();
+    set_base_rate(&mut world, entity, 1.0);
     let mut system = ResearchSystem;
     system.run(&mut world);
     let progress = get_tech_progress(&world, entity).unwrap();
@@ -372,6 +386,8 @@ fn test_research_system_multiple_entities() {
     let e2 = world.spawn_entity();
     research_tech(&mut world, e1, "bronze_working").unwrap();
     research_tech(&mut world, e2, "bronze_working").unwrap();
+    set_base_rate(&mut world, e1, 1.0);
+    set_base_rate(&mut world, e2, 1.0);
     let mut system = ResearchSystem;
     system.run(&mut world);
     let p1 = get_research_queue_progress(&world, e1);
@@ -385,3 +401,99 @@ fn test_research_system_multiple_entities() {
         "e2 should have progress"
     );
 }
+
+fn node(id: &str, requires: &[&str]) -> JsonValue {
+    let prerequisites: Vec<JsonValue> = requires
+        .iter()
+        .map(|p| json!({"type": "tech", "id": p}))
+        .collect();
+    json!({"id": id, "name": id, "prerequisites": prerequisites})
+}
+
+#[test]
+fn test_worlds_have_independent_tech_trees() {
+    let mut world = setup_world();
+    let mut other = setup_world();
+    other.tech_tree = TechTree::from_json(json!([node("sailing", &[])])).unwrap();
+
+    assert!(get_tech_node(&world, "bronze_working").is_some());
+    assert!(get_tech_node(&other, "bronze_working").is_none());
+    let entity = other.spawn_entity();
+    assert!(research_tech(&mut other, entity, "sailing").is_ok());
+    let entity = world.spawn_entity();
+    assert!(research_tech(&mut world, entity, "sailing").is_err());
+}
+
+#[test]
+fn test_tech_tree_load_merges_and_overrides() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("a_base.json"),
+        json!({"techs": [node("farming", &[]), node("pottery", &["farming"])]}).to_string(),
+    )
+    .unwrap();
+    let mut pottery = node("pottery", &[]);
+    pottery["cost"] = json!(0.0);
+    std::fs::write(dir.path().join("b_override.json"), pottery.to_string()).unwrap();
+    std::fs::write(
+        dir.path().join("c_extra.json"),
+        json!([node("writing", &["pottery"])]).to_string(),
+    )
+    .unwrap();
+
+    let mut tree = TechTree::new();
+    tree.load(dir.path()).unwrap();
+    let ids: Vec<&str> = tree.iter().map(|n| n.id.as_str()).collect();
+    assert_eq!(ids, vec!["farming", "pottery", "writing"]);
+    // The later file replaced the node; costs are at least 1
+    let pottery = tree.get("pottery").unwrap();
+    assert!(pottery.prerequisites.is_empty());
+    assert_eq!(pottery.cost, 1.0);
+
+    let mut base = TechTree::from_json(json!([node("farming", &[]), node("mining", &[])])).unwrap();
+    base.merge(tree);
+    assert_eq!(base.len(), 4);
+    assert!(base.validate().is_ok());
+}
+
+#[test]
+fn test_tech_tree_validation_reports_unknown_ids_and_cycles() {
+    let tree = TechTree::from_json(json!([
+        node("a", &["b"]),
+        node("b", &["c"]),
+        node("c", &["a"]),
+        node("d", &["ghost", "a"]),
+    ]))
+    .unwrap();
+    let errors = tree.validate().unwrap_err();
+    assert_eq!(
+        errors,
+        vec![
+            TechTreeError::UnknownPrerequisite {
+                tech: "d".into(),
+                prerequisite: "ghost".into(),
+            },
+            TechTreeError::Cycle(vec!["a".into(), "b".into(), "c".into(), "a".into()]),
+        ]
+    );
+    assert_eq!(
+        errors[1].to_string(),
+        "Tech prerequisite cycle: a -> b -> c -> a"
+    );
+
+    let world = setup_world();
+    assert!(world.tech_tree.validate().is_ok());
+}
+
+#[test]
+fn test_tech_tree_is_saved_with_the_world() {
+    let mut world = setup_world();
+    world
+        .tech_tree
+        .insert(serde_json::from_value(node("sailing", &["bronze_working"])).unwrap());
+    let snapshot = world.snapshot().unwrap();
+    world.tech_tree = TechTree::new();
+    world.restore(&snapshot).unwrap();
+    assert_eq!(world.tech_tree.len(), 5);
+    assert!(get_tech_node(&world, "sailing").is_some());
+}
@@ -0,0 +1,242 @@
+#[path = "helpers/world.rs"]
+mod world_helper;
+use world_helper::make_test_world;
+
+#[path = "helpers/world_io.rs"]
+mod world_io_helper;
+use world_io_helper::save_and_load_roundtrip;
+
+use engine_core::ecs::storage::SparseSet;
+use engine_core::ecs::{Entity, Health};
+use serde_json::json;
+
+#[test]
+fn test_sparse_set_insert_remove_keeps_dense_packing() {
+    let mut set = SparseSet::new();
+    set.insert(3, "a");
+    set.insert(7, "b");
+    set.insert(1, "c");
+    assert_eq!(set.len(), 3);
+
+    assert_eq!(set.remove(3), Some("a"));
+    assert!(!set.contains(3));
+    assert_eq!(set.get(7), Some(&"b"));
+    assert_eq!(set.get(1), Some(&"c"));
+    assert_eq!(set.entities().len(), 2);
+
+    assert_eq!(set.insert(1, "d"), Some("c"));
+    assert_eq!(set.get(1), Some(&"d"));
+    assert_eq!(set.len(), 2);
+}
+
+#[test]
+fn test_sparse_set_indexes_reused_slots_by_index() {
+    let old = Entity::new(5, 0).id();
+    let reused = Entity::new(5, 3).id();
+    let mut set = SparseSet::new();
+    set.insert(old, "old");
+    assert!(
+        !set.contains(reused),
+        "A newer generation is not the same entity"
+    );
+
+    set.insert(reused, "new");
+    assert_eq!(set.get(reused), Some(&"new"));
+    assert!(!set.contains(old), "The stale value is replaced");
+    assert_eq!(set.len(), 1);
+    assert_eq!(set.remove(old), None);
+    assert_eq!(set.remove(reused), Some("new"));
+}
+
+#[test]
+fn test_typed_insert_after_slot_reuse() {
+    let mut world = make_test_world();
+    world.register_typed_component::<Health>("Health").unwrap();
+    let first = world.spawn_entity();
+    world
+        .set_component(first, "Health", json!({ "current": 1.0, "max": 2.0 }))
+        .unwrap();
+    world.despawn_entity(first);
+
+    let second = world.spawn_entity();
+    assert_eq!(Entity::from_id(second).index, Entity::from_id(first).index);
+    assert!(Entity::from_id(second).generation > 0);
+    world
+        .set_component(second, "Health", json!({ "current": 3.0, "max": 4.0 }))
+        .unwrap();
+    assert!(!world.has_component(first, "Health"));
+    let column = world.typed_component::<Health>("Health").unwrap();
+    assert_eq!(column.get(second).unwrap().current, 3.0);
+    assert!(column.get(first).is_none());
+    assert_eq!(column.len(), 1);
+}
+
+#[test]
+fn test_json_api_is_a_view_over_typed_storage() {
+    let mut world = make_test_world();
+    world.register_typed_component::<Health>("Health").unwrap();
+    assert!(world.is_typed_component("Health"));
+
+    let e = world.spawn_entity();
+    world
+        .set_component(e, "Health", json!({ "current": 10.0, "max": 20.0 }))
+        .unwrap();
+
+    assert!(!world.components.contains_key("Health"));
+    assert!(world.has_component(e, "Health"));
+    assert_eq!(world.get_entities_with_component("Health"), vec![e]);
+    assert_eq!(
+        world.get_component(e, "Health"),
+        Some(&json!({ "current": 10.0, "max": 20.0 }))
+    );
+
+    let column = world.typed_component::<Health>("Health").unwrap();
+    assert_eq!(column.get(e).unwrap().current, 10.0);
+
+    world.remove_component(e, "Health").unwrap();
+    assert!(!world.has_component(e, "Health"));
+}
+
+#[test]
+fn test_native_mutation_is_visible_through_json_api() {
+    let mut world = make_test_world();
+    world.register_typed_component::<Health>("Health").unwrap();
+
+    let a = world.spawn_entity();
+    let b = world.spawn_entity();
+    for e in [a, b] {
+        world
+            .set_component(e, "Health", json!({ "current": 50.0, "max": 100.0 }))
+            .unwrap();
+    }
+    // Populate the cached JSON view before mutating natively.
+    assert_eq!(world.get_component(a, "Health").unwrap()["current"], 50.0);
+
+    for (_, health) in world
+        .typed_component_mut::<Health>("Health")
+        .unwrap()
+        .iter_mut()
+    {
+        health.current -= 5.0;
+    }
+
+    assert_eq!(world.get_component(a, "Health").unwrap()["current"], 45.0);
+    assert_eq!(world.get_component(b, "Health").unwrap()["current"], 45.0);
+
+    world.damage_entity(a, 15.0);
+    assert_eq!(
+        world
+            .typed_component::<Health>("Health")
+            .unwrap()
+            .get(a)
+            .unwrap()
+            .current,
+        30.0
+    );
+}
+
+#[test]
+fn test_registering_typed_storage_migrates_existing_data() {
+    let mut world = make_test_world();
+    let e = world.spawn_entity();
+    world
+        .set_component(e, "Health", json!({ "current": 7.0, "max": 9.0 }))
+        .unwrap();
+
+    world.register_typed_component::<Health>("Health").unwrap();
+    assert!(!world.components.contains_key("Health"));
+    assert_eq!(
+        world
+            .typed_component::<Health>("Health")
+            .unwrap()
+            .get(e)
+            .unwrap()
+            .max,
+        9.0
+    );
+
+    world.unregister_typed_component("Health");
+    assert!(!world.is_typed_component("Health"));
+    assert_eq!(world.components["Health"][&e]["max"], 9.0);
+}
+
+#[test]
+fn test_registry_components_get_typed_storage() {
+    let mut world = make_test_world();
+    let e = world.spawn_entity();
+    world
+        .set_component(e, "Stats", json!({ "strength": 3.0 }))
+        .unwrap();
+    world
+        .registry
+        .lock()
+        .unwrap()
+        .register_component::<Health>()
+        .unwrap();
+
+    world
+        .set_component(e, "Health", json!({ "current": 4.0, "max": 8.0 }))
+        .unwrap();
+    assert!(world.is_typed_component("Health"));
+    assert!(!world.components.contains_key("Health"));
+    let health = world.typed_component::<Health>("Health").unwrap().get(e);
+    assert_eq!(health.unwrap().current, 4.0);
+    assert!(!world.is_typed_component("Stats"));
+}
+
+#[test]
+fn test_typed_storage_rejects_data_that_does_not_fit_the_type() {
+    let mut world = make_test_world();
+    world.register_typed_component::<Health>("Health").unwrap();
+    let e = world.spawn_entity();
+
+    let result = world.set_component(e, "Health", json!({ "current": 5.0 }));
+    assert!(result.is_err());
+    assert!(!world.has_component(e, "Health"));
+}
+
+#[test]
+fn test_despawn_and_mode_change_clear_typed_data() {
+    let mut world = make_test_world();
+    world.register_typed_component::<Health>("Health").unwrap();
+    let e = world.spawn_entity();
+    world
+        .set_component(e, "Health", json!({ "current": 5.0, "max": 10.0 }))
+        .unwrap();
+
+    world.despawn_entity(e);
+    assert!(!world.entity_exists(e));
+
+    let e = world.spawn_entity();
+    world
+        .set_component(e, "Health", json!({ "current": 5.0, "max": 10.0 }))
+        .unwrap();
+    world.set_mode("editor");
+    world.set_mode("colony");
+    assert!(!world.has_component(e, "Health"));
+}
+
+#[test]
+fn test_typed_components_survive_save_and_load() {
+    let mut world = make_test_world();
+    let registry = world.registry.clone();
+    world.register_typed_component::<Health>("Health").unwrap();
+    let e = world.spawn_entity();
+    world
+        .set_component(e, "Health", json!({ "current": 33.0, "max": 40.0 }))
+        .unwrap();
+
+    let mut loaded = save_and_load_roundtrip(&world, registry);
+    assert_eq!(loaded.get_component(e, "Health").unwrap()["current"], 33.0);
+
+    loaded.register_typed_component::<Health>("Health").unwrap();
+    assert_eq!(
+        loaded
+            .typed_component::<Health>("Health")
+            .unwrap()
+            .get(e)
+            .unwrap()
+            .current,
+        33.0
+    );
+}
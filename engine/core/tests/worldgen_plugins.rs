@@ -50,6 +50,7 @@ fn test_simple_square_plugin() {
     let mut engine_api = EngineApi {
         spawn_entity: dummy_spawn_entity,
         set_component: dummy_set_component,
+        ..EngineApi::new()
     };
     let component_registry = Arc::new(Mutex::new(ComponentRegistry::new()));
     let mut dummy_world = World::new(component_registry);
@@ -122,6 +123,7 @@ fn test_simple_hex_plugin() {
     let mut engine_api = EngineApi {
         spawn_entity: dummy_spawn_entity,
         set_component: dummy_set_component,
+        ..EngineApi::new()
     };
     let component_registry = Arc::new(Mutex::new(ComponentRegistry::new()));
     let mut dummy_world = World::new(component_registry);
@@ -192,6 +194,7 @@ fn test_province_plugin() {
     let mut engine_api = EngineApi {
         spawn_entity: dummy_spawn_entity,
         set_component: dummy_set_component,
+        ..EngineApi::new()
     };
     let component_registry = Arc::new(Mutex::new(ComponentRegistry::new()));
     let mut dummy_world = World::new(component_registry);
@@ -112,6 +112,7 @@ fn test_register_and_invoke_cabi_worldgen_plugin() {
     let mut engine_api = EngineApi {
         spawn_entity: dummy_spawn_entity,
         set_component: dummy_set_component,
+        ..EngineApi::new()
     };
 
     let component_registry = Arc::new(Mutex::new(ComponentRegistry::new()));
@@ -0,0 +1,287 @@
+#[path = "helpers/world.rs"]
+mod world_helper;
+
+use engine_core::ecs::world::wasm::WasmWorld;
+use engine_core::map::{
+    BfsFovAlgorithm, CellKey, FovAlgorithm, Map, MovementProfile, PathfindingStrategy,
+    SquareGridMap, compute_fov,
+};
+use engine_core::presentation::renderer::{
+    COLOR_GRAY, COLOR_VERY_DIM, RenderCommand, TestRenderer,
+};
+use engine_core::presentation::{PresentationSystem, Viewport};
+use serde_json::json;
+
+fn cell(x: i32, y: i32, z: i32) -> CellKey {
+    CellKey::Square { x, y, z }
+}
+
+/// Two 5x5 levels, 4-connected within each level. Stairs at (0, 0, 0), a
+/// ladder at (4, 0, 0), a ramp at (2, 4, 0) and an open shaft at (2, 2, 1).
+/// The levels are not linked until `connect_levels` runs.
+fn two_levels() -> Map {
+    let mut grid = SquareGridMap::new();
+    for z in 0..2 {
+        for x in 0..5 {
+            for y in 0..5 {
+                grid.add_cell(x, y, z);
+                for (nx, ny) in [(x + 1, y), (x, y + 1), (x - 1, y), (x, y - 1)] {
+                    if (0..5).contains(&nx) && (0..5).contains(&ny) {
+                        grid.add_neighbor((x, y, z), (nx, ny, z));
+                    }
+                }
+            }
+        }
+    }
+    let mut map = Map::new(Box::new(grid));
+    map.set_cell_metadata(&cell(0, 0, 0), json!({ "vertical": "stairs" }));
+    map.set_cell_metadata(&cell(4, 0, 0), json!({ "vertical": "ladder" }));
+    map.set_cell_metadata(&cell(2, 4, 0), json!({ "vertical": "ramp" }));
+    map.set_cell_metadata(&cell(2, 2, 1), json!({ "vertical": "open" }));
+    map
+}
+
+#[test]
+fn test_connectors_link_levels_both_ways() {
+    let mut map = two_levels();
+    assert!(!map.neighbors(&cell(0, 0, 0)).contains(&cell(0, 0, 1)));
+    // Stairs and ladder: one pair each. Ramp: (1, 4, 1), (3, 4, 1), (2, 3, 1).
+    assert_eq!(map.connect_levels(), 10);
+    assert!(map.neighbors(&cell(0, 0, 0)).contains(&cell(0, 0, 1)));
+    assert!(map.neighbors(&cell(0, 0, 1)).contains(&cell(0, 0, 0)));
+    assert!(map.neighbors(&cell(4, 0, 1)).contains(&cell(4, 0, 0)));
+    for top in [cell(1, 4, 1), cell(3, 4, 1), cell(2, 3, 1)] {
+        assert!(map.neighbors(&cell(2, 4, 0)).contains(&top));
+        assert!(map.neighbors(&top).contains(&cell(2, 4, 0)));
+    }
+    assert!(!map.neighbors(&cell(2, 4, 0)).contains(&cell(2, 4, 1)));
+    assert_eq!(
+        map.connect_levels(),
+        0,
+        "Existing links are not added twice"
+    );
+}
+
+#[test]
+fn test_removed_connectors_lose_their_links() {
+    let mut map = two_levels();
+    map.connect_levels();
+    if let Some(grid) = map.topology.as_any_mut().downcast_mut::<SquareGridMap>() {
+        grid.add_neighbor((3, 3, 0), (3, 3, 1));
+    }
+    map.set_cell_metadata(&cell(0, 0, 0), json!({}));
+    map.set_cell_metadata(&cell(2, 4, 0), json!({ "vertical": "stairs" }));
+    let revision = map.revision();
+
+    assert_eq!(map.connect_levels(), 2, "The new stairs link both ways");
+    assert!(map.revision() > revision);
+    assert!(!map.neighbors(&cell(0, 0, 0)).contains(&cell(0, 0, 1)));
+    assert!(!map.neighbors(&cell(0, 0, 1)).contains(&cell(0, 0, 0)));
+    assert!(!map.neighbors(&cell(2, 4, 0)).contains(&cell(2, 3, 1)));
+    assert!(map.neighbors(&cell(2, 4, 0)).contains(&cell(2, 4, 1)));
+    assert!(map.neighbors(&cell(4, 0, 0)).contains(&cell(4, 0, 1)));
+    assert!(
+        map.neighbors(&cell(3, 3, 0)).contains(&cell(3, 3, 1)),
+        "Links added by hand stay"
+    );
+    let path = map
+        .find_path(&cell(0, 1, 0), &cell(0, 1, 1))
+        .expect("The ladder still leads up");
+    assert!(!path.path.contains(&cell(0, 0, 1)));
+}
+
+#[test]
+fn test_paths_pay_climbing_costs() {
+    let mut map = two_levels();
+    map.connect_levels();
+    let (start, goal) = (cell(0, 1, 0), cell(0, 1, 1));
+
+    let walker = map.find_path(&start, &goal).expect("Stairs lead up");
+    assert_eq!(
+        walker.path,
+        vec![cell(0, 1, 0), cell(0, 0, 0), cell(0, 0, 1), cell(0, 1, 1)]
+    );
+    assert_eq!(
+        walker.total_cost, 4.0,
+        "Three steps plus one for the stairs"
+    );
+
+    let mut careful = MovementProfile::new("careful");
+    careful.climb_costs.insert("stairs".to_string(), 10.0);
+    let result = map
+        .find_path_with_profile(&start, &goal, &careful)
+        .expect("The ramp leads up too");
+    assert!(result.path.contains(&cell(2, 4, 0)), "{:?}", result.path);
+    assert_eq!(result.total_cost, 11.0);
+    assert!(
+        !result.path.contains(&cell(2, 2, 1)),
+        "Open cells cannot be walked"
+    );
+
+    let flyer = map
+        .find_path_with_profile(&start, &goal, &MovementProfile::flyer())
+        .unwrap();
+    assert_eq!(flyer.total_cost, 3.0, "Flyers climb for free");
+
+    let hierarchical = map
+        .find_path_using(
+            &start,
+            &goal,
+            &MovementProfile::walker(),
+            PathfindingStrategy::Hierarchical { cluster_size: 2 },
+        )
+        .expect("Hierarchical path between levels");
+    assert_eq!(hierarchical.path.first(), Some(&start));
+    assert_eq!(hierarchical.path.last(), Some(&goal));
+    assert_eq!(hierarchical.total_cost, walker.total_cost);
+
+    let field = map.dijkstra_map(std::slice::from_ref(&goal), &MovementProfile::walker());
+    assert_eq!(field.distance(&start), Some(4.0));
+    let field = map.dijkstra_map(std::slice::from_ref(&goal), &careful);
+    assert_eq!(field.distance(&start), Some(11.0));
+}
+
+#[test]
+fn test_fov_looks_down_open_shafts() {
+    let mut map = two_levels();
+    map.connect_levels();
+    let visible = compute_fov(&map, &cell(2, 1, 1), 4);
+    assert!(visible.contains(&cell(2, 2, 1)));
+    assert!(visible.contains(&cell(2, 2, 0)), "Seen down the shaft");
+    assert!(
+        !visible.contains(&cell(2, 1, 0)),
+        "Floors block the view down"
+    );
+
+    let visible = BfsFovAlgorithm.compute_fov(&cell(2, 1, 1), 1, map.topology.as_ref());
+    assert!(visible.contains(&cell(2, 2, 0)));
+}
+
+#[test]
+fn test_generated_maps_connect_their_levels() {
+    let mut world = world_helper::make_test_world();
+    world
+        .apply_generated_map(&json!({
+            "topology": "square",
+            "cells": [
+                { "x": 0, "y": 0, "z": 0, "metadata": { "vertical": "ladder" } },
+                { "x": 1, "y": 0, "z": 0 },
+                { "x": 0, "y": 0, "z": 1 }
+            ]
+        }))
+        .unwrap();
+    let result = world.find_path(&cell(1, 0, 0), &cell(0, 0, 1)).unwrap();
+    assert_eq!(result.total_cost, 4.0, "Two steps plus two for the ladder");
+    assert_eq!(world.connect_levels(), 0);
+}
+
+#[test]
+fn test_render_map_draws_one_z_slice() {
+    let mut world = world_helper::make_test_world();
+    world.current_mode = "colony".to_string();
+    let mut map = two_levels();
+    map.connect_levels();
+    world.map = Some(map);
+    for (glyph, z) in [("d", 0), ("b", 1)] {
+        let entity = world.spawn_entity();
+        world
+            .set_component(
+                entity,
+                "Position",
+                json!({ "pos": { "Square": { "x": 3, "y": 3, "z": z } } }),
+            )
+            .unwrap();
+        world
+            .set_component(
+                entity,
+                "Renderable",
+                json!({ "glyph": glyph, "color": [255, 255, 255] }),
+            )
+            .unwrap();
+    }
+
+    let mut lower = PresentationSystem::new(TestRenderer::new());
+    lower.render_map(&world, &Viewport::new(0, 0, 5, 5).with_z(0));
+    let glyph_at = |draws: &[RenderCommand], pos| {
+        draws
+            .iter()
+            .filter(|cmd| cmd.pos == pos)
+            .map(|cmd| (cmd.glyph, cmd.color))
+            .collect::<Vec<_>>()
+    };
+    let draws = &lower.renderer.draws;
+    assert_eq!(draws.len(), 26, "25 cells and one entity");
+    assert_eq!(glyph_at(draws, (0, 0)), vec![('<', COLOR_GRAY)]);
+    assert_eq!(glyph_at(draws, (4, 0)), vec![('H', COLOR_GRAY)]);
+    assert_eq!(glyph_at(draws, (2, 4)), vec![('^', COLOR_GRAY)]);
+    assert_eq!(glyph_at(draws, (3, 3)).last().unwrap().0, 'd');
+
+    let mut upper = PresentationSystem::new(TestRenderer::new());
+    let viewport = Viewport::new(0, 0, 5, 5).with_z(1);
+    assert_eq!(viewport.z(), Some(1));
+    upper.render_map(&world, &viewport);
+    let draws = &upper.renderer.draws;
+    assert_eq!(draws.len(), 26);
+    assert_eq!(glyph_at(draws, (0, 0)), vec![('>', COLOR_GRAY)]);
+    assert_eq!(glyph_at(draws, (4, 0)), vec![('>', COLOR_GRAY)]);
+    assert_eq!(glyph_at(draws, (2, 2)), vec![('.', COLOR_VERY_DIM)]);
+    assert_eq!(glyph_at(draws, (3, 3)).last().unwrap().0, 'b');
+
+    let mut all = PresentationSystem::new(TestRenderer::new());
+    all.render_map(&world, &Viewport::new(0, 0, 5, 5));
+    assert_eq!(all.renderer.draws.len(), 52, "No z-level draws every level");
+}
+
+#[test]
+fn test_wasm_worlds_connect_levels() {
+    let mut world = WasmWorld::new();
+    world.add_cell(0, 0, 0);
+    world.add_cell(1, 0, 0);
+    world.add_cell(0, 0, 1);
+    world
+        .add_neighbor(
+            r#"{"Square": {"x": 0, "y": 0, "z": 0}}"#,
+            r#"{"Square": {"x": 1, "y": 0, "z": 0}}"#,
+        )
+        .unwrap();
+    world.set_cell_metadata(
+        r#"{"Square": {"x": 0, "y": 0, "z": 0}}"#,
+        r#"{"vertical": "ladder"}"#,
+    );
+    assert_eq!(world.connect_levels(), 2);
+    assert_eq!(world.connect_levels(), 0);
+    world.set_cell_metadata(r#"{"Square": {"x": 0, "y": 0, "z": 0}}"#, "{}");
+    assert_eq!(world.connect_levels(), 0);
+    assert!(
+        world
+            .flow_step(
+                r#"[{"Square": {"x": 0, "y": 0, "z": 1}}]"#,
+                r#"{"Square": {"x": 1, "y": 0, "z": 0}}"#
+            )
+            .is_none(),
+        "The ladder is gone"
+    );
+    world.set_cell_metadata(
+        r#"{"Square": {"x": 0, "y": 0, "z": 0}}"#,
+        r#"{"vertical": "ladder"}"#,
+    );
+    assert_eq!(world.connect_levels(), 2);
+
+    let goals = r#"[{"Square": {"x": 0, "y": 0, "z": 1}}]"#;
+    let step = world
+        .flow_step(goals, r#"{"Square": {"x": 1, "y": 0, "z": 0}}"#)
+        .unwrap();
+    assert_eq!(
+        serde_json::from_str::<CellKey>(&step).unwrap(),
+        cell(0, 0, 0)
+    );
+    let field: serde_json::Value =
+        serde_json::from_str(&world.dijkstra_map(goals).unwrap()).unwrap();
+    let distance = field
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|entry| entry["cell"] == json!({ "Square": { "x": 1, "y": 0, "z": 0 } }))
+        .map(|entry| entry["distance"].as_f64().unwrap());
+    assert_eq!(distance, Some(4.0), "Two steps plus two for the ladder");
+}
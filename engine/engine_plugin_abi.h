@@ -1,8 +1,21 @@
+// Generated from engine/core/src/plugins/types.rs; do not edit.
+// Regenerate with `cargo run -p xtask -- gen-plugin-header`.
 #ifndef ENGINE_PLUGIN_ABI_H
 #define ENGINE_PLUGIN_ABI_H
 
+#include <stddef.h>
+#include <stdint.h>
+
 // Current ABI version. Increment on any breaking change to PluginVTable layout.
-#define PLUGIN_ABI_VERSION 1
+#define PLUGIN_ABI_VERSION 2
+
+// EngineApi revision. Incremented when functions are appended to EngineApi.
+#define ENGINE_API_VERSION 1
+
+// True if the engine that filled `api` provides `field` (older engines pass a
+// shorter EngineApi).
+#define ENGINE_API_HAS(api, field) \
+  ((api)->struct_size >= offsetof(EngineApi, field) + sizeof((api)->field))
 
 #ifdef __cplusplus
 extern "C" {
@@ -13,29 +26,73 @@ typedef void *WorldPtr;
 typedef void (*SystemRunFn)(WorldPtr, float delta_time);
 
 typedef struct SystemPlugin {
+  // The system's name
   const char *name;
-  SystemRunFn run;
-  // Optional: add metadata fields here (e.g., required components)
+  // The system's run function
+  void (*run)(void *, float);
 } SystemPlugin;
 
 typedef struct EngineApi {
-  unsigned int (*spawn_entity)(WorldPtr);
-  int (*set_component)(WorldPtr, unsigned int, const char *name,
-                       const char *json_value);
+  // Size of this struct as filled by the engine, in bytes
+  uint32_t struct_size;
+  // ENGINE_API_VERSION of the engine
+  uint32_t version;
+  // Spawns a new entity
+  uint32_t (*spawn_entity)(void *);
+  // Sets a component from JSON; returns 0 on success
+  int32_t (*set_component)(void *, uint32_t, const char *, const char *);
+  // Despawns an entity; returns 0 on success
+  int32_t (*despawn_entity)(void *, uint32_t);
+  // Returns 1 if the entity exists, 0 otherwise
+  int32_t (*entity_exists)(void *, uint32_t);
+  // Gets a component as JSON, or NULL if absent
+  char *(*get_component)(void *, uint32_t, const char *);
+  // Removes a component; returns 0 on success
+  int32_t (*remove_component)(void *, uint32_t, const char *);
+  // Entities with a component, as a JSON array of IDs
+  char *(*get_entities_with_component)(void *, const char *);
+  // Entities matching a JSON query spec ({"with": [...], "without": [...], ...}), as a JSON array of IDs
+  char *(*query_entities)(void *, const char *);
+  // Sends a JSON event to a named event bus; returns 0 on success
+  int32_t (*send_event)(void *, const char *, const char *);
+  // Takes the events delivered to a bus by the last update, as a JSON array
+  char *(*poll_events)(void *, const char *);
+  // Current turn
+  uint32_t (*get_turn)(void *);
+  // Map topology type ("square", "hex", "province"), or NULL without a map
+  char *(*get_map_topology)(void *);
+  // Neighbors of a JSON cell, as a JSON array of cells
+  char *(*get_neighbors)(void *, const char *);
+  // Path between two JSON cells as {"path": [...], "total_cost": n}, or NULL if none
+  char *(*find_path)(void *, const char *, const char *);
+  // Frees a string returned by the engine
+  void (*free_string)(char *);
 } EngineApi;
 
 typedef struct PluginVTable {
-  unsigned int abi_version;  // MUST equal PLUGIN_ABI_VERSION
-  int (*init)(struct EngineApi *api, void *world);
-  void (*shutdown)();
-  void (*update)(float delta_time);
-  const char *(*worldgen_name)();
-  int (*generate_world)(const char *params_json, char **out_result_json);
-  void (*free_result_json)(char *result_json);
-  int (*register_systems)(struct EngineApi *api, void *world,
-                          SystemPlugin **systems, int *count);
-  void (*free_systems)(SystemPlugin *systems, int count);
-  void *(*hot_reload)(void *old_state);
+  // MUST equal PLUGIN_ABI_VERSION
+  uint32_t abi_version;
+  // The plugin's init function
+  int32_t (*init)(struct EngineApi *, void *);
+  // The plugin's shutdown function
+  void (*shutdown)(void);
+  // The plugin's update function
+  void (*update)(float);
+  // The plugin's worldgen name
+  const char *(*worldgen_name)(void);
+  // The plugin's worldgen function
+  int32_t (*generate_world)(const char *, char **);
+  // The plugin's result parser
+  void (*free_result_json)(char *);
+  // The plugin's system registration function
+  int32_t (*register_systems)(struct EngineApi *, void *, struct SystemPlugin **, int32_t *);
+  // The plugin's system unregistration function
+  void (*free_systems)(struct SystemPlugin *, int32_t);
+  // Called once init and system registration succeed, with NULL on the first
+  // load and the state returned by the previous version on a hot reload; returns
+  // the state to keep. The state must live outside the library's own memory
+  // (e.g. be malloc'd).
+  void *(*hot_reload)(void *);
 } PluginVTable;
 
 extern PluginVTable *PLUGIN_VTABLE;
@@ -8,6 +8,29 @@ local function test_entities_in_cell()
 	assert.equals(#entities, 1, "Should find one entity in cell")
 end
 
+local function test_spatial_queries()
+	local function spawn_at(q, r)
+		local eid = spawn_entity()
+		set_component(eid, "Position", { pos = { Hex = { q = q, r = r, z = 0 } } })
+		return eid
+	end
+	local origin = spawn_at(0, 0)
+	local near = spawn_at(1, -1)
+	local far = spawn_at(3, 0)
+	set_component(far, "Health", { current = 5, max = 10 })
+
+	local center = { Hex = { q = 0, r = 0, z = 0 } }
+	assert.equals(#entities_in_cell(center), 1, "Hex cells should be indexed")
+	local nearby = entities_in_radius(center, 1)
+	assert.equals(#nearby, 2, "Radius 1 should find two entities")
+	assert.equals(nearby[1], origin)
+	local rect = entities_in_rect({ Hex = { q = 0, r = -1, z = 0 } }, { Hex = { q = 3, r = -1, z = 0 } })
+	assert.equals(#rect, 1)
+	assert.equals(rect[1], near)
+	assert.equals(nearest_entity_with_component(center, "Health"), far)
+	assert.is_nil(nearest_entity_with_component(center, "Health", 2))
+end
+
 local function test_count_entities_with_type()
 	local id1 = spawn_entity()
 	set_component(id1, "Type", { kind = "player" })
@@ -31,8 +54,24 @@ local function test_count_entities_with_type()
 	assert.equals(count_enemy, 2)
 end
 
+local function test_stale_entity_handles()
+	local old = spawn_entity()
+	set_component(old, "Health", { current = 5, max = 10 })
+	despawn_entity(old)
+	local new = spawn_entity()
+
+	assert.is_true(old ~= new, "Reused slot should get a new ID")
+	assert.is_true(is_stale_entity(old), "Old handle should be stale")
+	assert.is_false(is_stale_entity(new), "New handle should be valid")
+	assert.is_nil(get_component(old, "Health"))
+	local ok = pcall(set_component, old, "Health", { current = 5, max = 10 })
+	assert.is_false(ok, "Writing through a stale handle should fail")
+end
+
 return {
 
 	test_entities_in_cell = test_entities_in_cell,
+	test_spatial_queries = test_spatial_queries,
 	test_count_entities_with_type = test_count_entities_with_type,
+	test_stale_entity_handles = test_stale_entity_handles,
 }
@@ -0,0 +1,39 @@
+local assert = require("assert")
+
+local function test_parent_links()
+	local root = spawn_entity()
+	local mid = spawn_entity()
+	local leaf = spawn_entity()
+	set_parent(mid, root)
+	set_parent(leaf, mid)
+
+	assert.equals(parent_of(leaf), mid)
+	assert.is_nil(parent_of(root))
+	assert.table_equals(children_of(root), { mid })
+	assert.table_equals(ancestors(leaf), { mid, root })
+	assert.table_equals(descendants(root), { mid, leaf })
+
+	local ok = pcall(set_parent, root, leaf)
+	assert.is_false(ok, "Cycles should be rejected")
+	assert.equals(remove_parent(leaf), mid)
+	assert.is_nil(parent_of(leaf))
+end
+
+local function test_despawn_policies()
+	local holder = spawn_entity()
+	local bag = spawn_entity()
+	local strap = spawn_entity()
+	local item = spawn_entity()
+	set_parent(bag, holder)
+	set_parent(strap, bag)
+	set_parent(item, bag, "reparent")
+
+	despawn_entity(bag)
+	assert.is_true(is_stale_entity(strap), "Cascading child should be despawned")
+	assert.equals(parent_of(item), holder)
+end
+
+return {
+	test_parent_links = test_parent_links,
+	test_despawn_policies = test_despawn_policies,
+}
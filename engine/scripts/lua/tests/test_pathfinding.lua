@@ -28,4 +28,114 @@ local function test_pathfinding()
 	assert.equals(#result.path, 5)
 end
 
-return { test_pathfinding = test_pathfinding }
+local function test_movement_profiles()
+	-- A row of three cells with water in the middle
+	for x = 0, 2 do
+		add_cell(x, 10, 0)
+	end
+	add_neighbor({ x = 0, y = 10, z = 0 }, { x = 1, y = 10, z = 0 })
+	add_neighbor({ x = 1, y = 10, z = 0 }, { x = 2, y = 10, z = 0 })
+	set_cell_metadata({ x = 1, y = 10, z = 0 }, { terrain = "water", walkable = false })
+	local start, goal = { x = 0, y = 10, z = 0 }, { x = 2, y = 10, z = 0 }
+
+	assert.is_nil(find_path(start, goal))
+	assert.equals(#find_path(start, goal, "swimmer").path, 3)
+
+	register_movement_profile({ name = "wader", passable_terrain = { "water" }, terrain_costs = { water = 4 } })
+	assert.equals(find_path(start, goal, "wader").total_cost, 5)
+
+	local fish = spawn_entity()
+	set_component(fish, "Agent", { entity_id = fish, movement_profile = "swimmer" })
+	assert.is_table(find_path_for(fish, start, goal))
+end
+
+local function test_hierarchical_strategy()
+	-- A 12x12 grid with a wall at x = 25 open at y = 11
+	for x = 20, 31 do
+		for y = 0, 11 do
+			add_cell(x, y, 0)
+		end
+	end
+	for x = 20, 31 do
+		for y = 0, 11 do
+			for _, d in ipairs({ { 1, 0 }, { 0, 1 }, { -1, 0 }, { 0, -1 } }) do
+				local nx, ny = x + d[1], y + d[2]
+				if nx >= 20 and nx <= 31 and ny >= 0 and ny <= 11 then
+					add_neighbor({ x = x, y = y, z = 0 }, { x = nx, y = ny, z = 0 })
+				end
+			end
+		end
+	end
+	for y = 0, 10 do
+		set_cell_metadata({ x = 25, y = y, z = 0 }, { walkable = false })
+	end
+	local start, goal = { x = 20, y = 0, z = 0 }, { x = 31, y = 0, z = 0 }
+	local exact = find_path(start, goal)
+
+	set_pathfinding_strategy("hierarchical", 4)
+	assert.equals(get_pathfinding_strategy(), "hierarchical")
+	local result = find_path(start, goal)
+	assert.is_table(result)
+	assert.equals(result.path[#result.path].Square.x, 31)
+	assert.is_true(result.total_cost >= exact.total_cost)
+	assert.is_false(pcall(set_pathfinding_strategy, "teleport"))
+	set_pathfinding_strategy("astar")
+end
+
+local function test_flow_fields()
+	-- A corridor from x = 40 to x = 45
+	for x = 40, 45 do
+		add_cell(x, 0, 0)
+		if x > 40 then
+			add_neighbor({ x = x - 1, y = 0, z = 0 }, { x = x, y = 0, z = 0 })
+			add_neighbor({ x = x, y = 0, z = 0 }, { x = x - 1, y = 0, z = 0 })
+		end
+	end
+	local goal = { x = 40, y = 0, z = 0 }
+	local field = dijkstra_map({ goal })
+	assert.is_table(field)
+	assert.equals(#field, 6)
+	for _, entry in ipairs(field) do
+		assert.equals(entry.distance, entry.cell.Square.x - 40)
+	end
+
+	local step = flow_step({ x = 43, y = 0, z = 0 }, { goal })
+	assert.equals(step.Square.x, 42)
+	assert.is_nil(flow_step(goal, { goal }))
+
+	local away = flee_step({ x = 41, y = 0, z = 0 }, { goal })
+	assert.equals(away.Square.x, 42)
+	local flee = flee_map({ goal }, "walker", 2.0)
+	assert.is_table(flee)
+	assert.equals(#flee, 6)
+end
+
+local function test_z_levels()
+	-- Two cells at z = 0 with a ladder up to a third at z = 1
+	add_cell(50, 0, 0)
+	add_cell(51, 0, 0)
+	add_cell(50, 0, 1)
+	add_neighbor({ x = 50, y = 0, z = 0 }, { x = 51, y = 0, z = 0 })
+	add_neighbor({ x = 51, y = 0, z = 0 }, { x = 50, y = 0, z = 0 })
+	local start, goal = { x = 51, y = 0, z = 0 }, { x = 50, y = 0, z = 1 }
+	assert.is_nil(find_path(start, goal))
+
+	set_cell_metadata({ x = 50, y = 0, z = 0 }, { vertical = "ladder" })
+	assert.equals(connect_levels(), 2)
+	assert.equals(connect_levels(), 0)
+	local result = find_path(start, goal)
+	assert.equals(#result.path, 3)
+	assert.equals(result.total_cost, 4)
+
+	register_movement_profile({ name = "climber", climb_costs = { ladder = 0 } })
+	assert.equals(find_path(start, goal, "climber").total_cost, 2)
+	assert.equals(find_path(goal, start, "climber").total_cost, 2)
+end
+
+return {
+	test_pathfinding = test_pathfinding,
+	test_movement_profiles = test_movement_profiles,
+	test_hierarchical_strategy = test_hierarchical_strategy,
+	test_flow_fields = test_flow_fields,
+	test_z_levels = test_z_levels,
+}
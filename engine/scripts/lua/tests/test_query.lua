@@ -0,0 +1,49 @@
+local assert = require("assert")
+
+local function spawn_with_health(current)
+	local e = spawn_entity()
+	set_component(e, "Health", { current = current, max = 10 })
+	return e
+end
+
+local function test_query_filters()
+	local tick = change_tick()
+	local low = spawn_with_health(2)
+	local high = spawn_with_health(9)
+	local dead = spawn_with_health(0)
+	set_component(dead, "Corpse", {})
+	set_component(high, "Type", { kind = "goblin" })
+
+	local fresh = query():with("Health"):added("Health"):since(tick)
+	assert.table_equals(fresh:without("Corpse"):entities(), { low, high })
+
+	local wounded = query():added("Health"):since(tick):where("Health", "current", "<", 5):entities()
+	assert.table_equals(wounded, { low, dead })
+
+	local rows = query():added("Health"):since(tick):optional("Type"):where("Type", "kind", "==", "goblin"):rows()
+	assert.equals(#rows, 1)
+	assert.equals(rows[1].entity, high)
+	assert.equals(rows[1].components.Type.kind, "goblin")
+	assert.equals(rows[1].components.Health.current, 9)
+
+	local ok = pcall(function()
+		query():where("Health", "current", "~", 1)
+	end)
+	assert.is_false(ok, "Unknown operators should be rejected")
+end
+
+local function test_query_changed_since_tick()
+	local a = spawn_with_health(5)
+	local b = spawn_with_health(5)
+	local tick = change_tick()
+	set_component(a, "Health", { current = 5, max = 10 })
+	set_component(b, "Health", { current = 3, max = 10 })
+
+	assert.table_equals(query():changed("Health"):since(tick):entities(), { b })
+	assert.table_equals(query():added("Health"):since(tick):entities(), {})
+end
+
+return {
+	test_query_filters = test_query_filters,
+	test_query_changed_since_tick = test_query_changed_since_tick,
+}
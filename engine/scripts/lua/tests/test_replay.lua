@@ -0,0 +1,48 @@
+-- test_replay.lua: Tests for session recording and headless replay.
+-- Global functions: start_recording(), is_recording(), stop_recording(), replay_recording(), push_input()
+
+local assert = require("assert")
+
+-- 1. A recorded session replays with matching state hashes
+local function test_record_and_replay()
+	seed_rng(5)
+	local e = spawn_entity()
+	set_component(e, "Health", { current = 100, max = 100 })
+
+	start_recording()
+	assert.is_true(is_recording())
+	tick()
+	set_component(e, "Health", { current = 50, max = 100 })
+	push_input("h")
+	tick()
+	local added = spawn_entity()
+	set_component(added, "Health", { current = 5, max = 10 })
+	tick()
+	local turn = get_turn()
+	assert.equals(stop_recording("test_replay.json"), 3)
+	assert.is_true(not is_recording())
+
+	-- Wreck the state, then replay it back from the recording
+	set_component(e, "Health", { current = 1, max = 100 })
+	despawn_entity(added)
+	assert.equals(replay_recording("test_replay.json"), 3)
+	assert.equals(get_component(e, "Health").current, 50)
+	assert.equals(get_component(added, "Health").current, 5)
+	assert.equals(get_turn(), turn)
+
+	-- Replaying a prefix stops early
+	assert.equals(replay_recording("test_replay.json", 1), 1)
+	assert.equals(get_component(e, "Health").current, 100)
+end
+
+-- 2. Invalid input is rejected
+local function test_push_input_rejects_words()
+	local ok = pcall(push_input, "jump")
+	assert.is_true(not ok, "multi-character input should be rejected")
+	push_input("quit")
+end
+
+return {
+	test_record_and_replay = test_record_and_replay,
+	test_push_input_rejects_words = test_push_input_rejects_words,
+}
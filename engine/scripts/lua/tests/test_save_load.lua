@@ -24,6 +24,34 @@ local function test_save_and_load()
 	assert.is_true(#entities_after >= 2, "Entities should exist after loading")
 end
 
+local function test_compressed_save_and_load()
+	local e = spawn_entity()
+	set_component(e, "Health", { current = 7, max = 10 })
+
+	save_to_file("test_save.bin", "compressed")
+	despawn_entity(e)
+
+	load_from_file("test_save.bin")
+	assert.equals(get_component(e, "Health").current, 7)
+end
+
+local function test_snapshot_and_restore()
+	local e = spawn_entity()
+	set_component(e, "Health", { current = 10, max = 10 })
+	local snap = snapshot()
+
+	set_component(e, "Health", { current = 1, max = 10 })
+	local extra = spawn_entity()
+
+	restore(snap)
+	assert.equals(get_component(e, "Health").current, 10)
+	for _, eid in ipairs(get_entities()) do
+		assert.is_true(eid ~= extra, "Entity spawned after the snapshot should be gone")
+	end
+end
+
 return {
 	test_save_and_load = test_save_and_load,
+	test_compressed_save_and_load = test_compressed_save_and_load,
+	test_snapshot_and_restore = test_snapshot_and_restore,
 }
@@ -0,0 +1,40 @@
+-- test_state_hash.lua: Tests for world state hashing.
+-- Global functions: state_hash(), set_system_hash_debug(), take_system_hashes()
+
+local assert = require("assert")
+
+-- 1. The hash follows the simulated state
+local function test_state_hash_changes_with_state()
+	local e = spawn_entity()
+	set_component(e, "Health", { current = 10, max = 10 })
+	local before = state_hash()
+	assert.equals(#before, 16)
+	assert.equals(state_hash(), before)
+
+	set_component(e, "Health", { current = 9, max = 10 })
+	local after = state_hash()
+	assert.is_true(after ~= before, "hash should change with a component")
+
+	set_component(e, "Health", { max = 10, current = 10 })
+	assert.equals(state_hash(), before)
+end
+
+-- 2. Per-system hashes are logged while debugging is on
+local function test_system_hash_log()
+	set_system_hash_debug(true)
+	tick()
+	local log = take_system_hashes()
+	assert.is_true(#log >= 1, "tick start should be logged")
+	assert.equals(log[1].system, "tick_start")
+	assert.equals(#log[1].hash, 16)
+	assert.equals(#take_system_hashes(), 0)
+
+	set_system_hash_debug(false)
+	tick()
+	assert.equals(#take_system_hashes(), 0)
+end
+
+return {
+	test_state_hash_changes_with_state = test_state_hash_changes_with_state,
+	test_system_hash_log = test_system_hash_log,
+}
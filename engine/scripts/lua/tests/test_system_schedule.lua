@@ -0,0 +1,63 @@
+local assert = require("assert")
+
+local function test_system_stages()
+	local order = {}
+	register_system("late", function()
+		table.insert(order, "late")
+	end, { stage = "post_update" })
+	register_system("early", function()
+		table.insert(order, "early")
+	end)
+	set_system_stage("early", "pre_update")
+
+	assert.equals(get_system_stage("early"), "pre_update")
+	assert.equals(get_system_stage("late"), "post_update")
+	tick()
+	assert.table_equals(order, { "early", "late" })
+end
+
+local function test_run_conditions()
+	local runs = 0
+	local alarms = 0
+	register_system("every_other", function()
+		runs = runs + 1
+	end, { run_if = { { type = "every_n_ticks", n = 2 } } })
+	register_system("on_alarm", function()
+		alarms = alarms + 1
+	end)
+	add_run_condition("on_alarm", { type = "events_pending", bus = "Alarm" })
+
+	for _ = 1, 4 do
+		tick()
+	end
+	assert.equals(runs, 2)
+	assert.equals(alarms, 0)
+
+	send_event("Alarm", "{}")
+	tick()
+	assert.equals(alarms, 1)
+
+	clear_run_conditions("every_other")
+	tick()
+	assert.equals(runs, 4)
+end
+
+local function test_fixed_timestep_advance()
+	local dts = {}
+	set_fixed_timestep(0.25)
+	assert.equals(get_fixed_timestep(), 0.25)
+	register_system("clock", function(dt)
+		table.insert(dts, dt)
+	end)
+
+	local turn = get_turn()
+	assert.equals(advance(0.6), 2)
+	assert.equals(get_turn(), turn + 2)
+	assert.table_equals(dts, { 0.25, 0.25 })
+end
+
+return {
+	test_system_stages = test_system_stages,
+	test_run_conditions = test_run_conditions,
+	test_fixed_timestep_advance = test_fixed_timestep_advance,
+}
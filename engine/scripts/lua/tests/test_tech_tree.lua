@@ -142,6 +142,35 @@ local function test_cannot_research_already_queued()
     assert.is_false(ok, "Should error when already in queue")
 end
 
+-- 16. grant_tech completes a tech and revoke_tech un-completes it
+local function test_grant_and_revoke_tech()
+    local id = spawn_entity()
+    research_tech(id, "bronze_working")
+    grant_tech(id, "bronze_working")
+    assert.is_true(is_tech_completed(id, "bronze_working"), "bronze_working should be completed")
+    assert.equals(#get_research_queue(id), 0, "Granted tech should leave the queue")
+    assert.is_table(get_tech_effects(id), "Should return a table")
+    revoke_tech(id, "bronze_working")
+    assert.is_false(is_tech_completed(id, "bronze_working"), "bronze_working should be revoked")
+    local ok = pcall(revoke_tech, id, "bronze_working")
+    assert.is_false(ok, "Should error when revoking a tech that isn't completed")
+    assert.is_true(is_recipe_unlocked(id, "no_such_recipe"), "Recipes no tech unlocks are available")
+end
+
+-- 17. Research points credit the faction pool, or the entity's own progress
+local function test_add_research_points()
+    local member = spawn_entity()
+    set_faction(member, "scholars", "member")
+    assert.is_true(add_research_points(member, 4), "Faction members credit the pool")
+    assert.equals(get_research_pool("scholars"), 4, "Pool should hold the points")
+
+    local loner = spawn_entity()
+    assert.is_false(add_research_points(loner, 4), "No faction and no TechProgress")
+    research_tech(loner, "bronze_working")
+    assert.is_true(add_research_points(loner, 4), "Credits the entity's TechProgress")
+    assert.equals(get_tech_progress(loner).research_points, 4, "Points go to TechProgress")
+end
+
 return {
     test_get_tech_tree = test_get_tech_tree,
     test_get_tech_node = test_get_tech_node,
@@ -158,4 +187,6 @@ return {
     test_get_completed_techs = test_get_completed_techs,
     test_get_research_queue_progress = test_get_research_queue_progress,
     test_cannot_research_already_queued = test_cannot_research_already_queued,
+    test_grant_and_revoke_tech = test_grant_and_revoke_tech,
+    test_add_research_points = test_add_research_points,
 }
@@ -4,8 +4,8 @@ use engine_core::config::GameConfig;
 use engine_core::ecs::assets::load_material_definitions;
 use engine_core::ecs::registry::ComponentRegistry;
 use engine_core::ecs::world::World;
-use engine_core::mods::loader::load_mod;
-use engine_core::plugins::loader::load_native_plugins_from_config;
+use engine_core::mods::ModManager;
+use engine_core::plugins::NativePluginHost;
 use engine_core::plugins::types::EngineApi;
 use engine_core::systems::body_part_damage::BodyPartDamageSystem;
 use engine_core::systems::economic::{EconomicSystem, load_recipes_from_dir};
@@ -18,6 +18,33 @@ use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::{Arc, Mutex};
 
+/// Load the native plugins listed in the config, and reload each one at the
+/// start of a tick once its library has been rebuilt.
+fn load_native_plugins(config: &GameConfig, world_rc: &Rc<RefCell<World>>) {
+    let mut engine_api = Box::new(EngineApi::new());
+    let worldgen_registry = Rc::new(RefCell::new(WorldgenRegistry::new()));
+    let mut host = NativePluginHost::new();
+    let mut world = world_rc.borrow_mut();
+    unsafe {
+        host.load_config(
+            config,
+            &mut engine_api,
+            &mut world,
+            &mut worldgen_registry.borrow_mut(),
+        )
+        .expect("Failed to load native plugins from config");
+        host.watch(
+            &mut world,
+            engine_api,
+            worldgen_registry,
+            |name, result| match result {
+                Ok(()) => println!("Reloaded plugin '{name}'"),
+                Err(e) => eprintln!("{e}"),
+            },
+        );
+    }
+}
+
 /// Returns the absolute path to the engine's schema directory,
 /// robust to workspace layout and usable for both dev and test.
 fn find_schema_dir() -> PathBuf {
@@ -41,6 +68,30 @@ fn find_materials_dir() -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../engine/assets/materials")
 }
 
+/// Returns the directory holding the base tech tree.
+fn find_tech_dir() -> PathBuf {
+    if let Ok(dir) = env::var("MGE_TECH_DIR") {
+        return PathBuf::from(dir);
+    }
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../engine/assets/tech")
+}
+
+/// Loads the base tech tree into the world, reporting problems in it.
+fn load_tech_tree(world: &mut World) {
+    if let Err(e) = world.tech_tree.load(find_tech_dir()) {
+        eprintln!("Failed to load tech tree: {e}");
+    }
+}
+
+/// Reports unknown prerequisites and cycles in the world's tech tree.
+fn report_tech_tree_errors(world: &World) {
+    if let Err(errors) = world.tech_tree.validate() {
+        for error in errors {
+            eprintln!("Tech tree: {error}");
+        }
+    }
+}
+
 fn find_config_file() -> PathBuf {
     // Try env var override first
     if let Ok(path) = env::var("MGE_CONFIG_FILE") {
@@ -107,25 +158,6 @@ fn main() {
             registry.lock().unwrap().register_external_schema(schema);
         }
 
-        // --- Plugin registration ---
-        let mut engine_api = EngineApi {
-            // Fill with function pointers as needed for engine
-            spawn_entity: engine_core::plugins::ffi::ffi_spawn_entity,
-            set_component: engine_core::plugins::ffi::ffi_set_component,
-        };
-        let mut worldgen_registry = WorldgenRegistry::new();
-        let world_ptr: *mut std::os::raw::c_void = std::ptr::null_mut(); // Use actual pointer if needed
-
-        unsafe {
-            load_native_plugins_from_config(
-                &config,
-                &mut engine_api,
-                world_ptr,
-                &mut worldgen_registry,
-            )
-        }
-        .expect("Failed to load native plugins from config");
-
         // Read mod manifest and parse mode if present
         let manifest_path = format!("{mod_dir}/mod.json");
         let manifest: Option<serde_json::Value> = fs::read_to_string(&manifest_path)
@@ -153,7 +185,7 @@ fn main() {
         }
         let recipes = load_recipes_from_dir(&recipes_dir);
         let economic_system = EconomicSystem::with_recipes(recipes);
-        let mut world = World::new(registry.clone());
+        let mut world = World::with_config(registry.clone(), &config);
         world.register_system(BodyPartDamageSystem);
         world.register_system(economic_system);
         world.current_mode = mode.clone();
@@ -163,17 +195,37 @@ fn main() {
         if let Ok(mats) = load_material_definitions(&materials_dir) {
             world.material_definitions = mats;
         }
+        load_tech_tree(&mut world);
 
         let world_rc = Rc::new(RefCell::new(world));
+        load_native_plugins(&config, &world_rc);
         let mut engine = ScriptEngine::new();
         engine
             .register_world(world_rc.clone())
             .expect("Failed to register ECS API");
 
-        if let Err(e) = load_mod(&mod_dir, world_rc.clone(), &mut engine) {
+        // Load the mod after the mods it depends on
+        let mods = ModManager::discover("mods").unwrap_or_else(|e| {
+            eprintln!("Failed to read mods: {e}");
+            std::process::exit(1);
+        });
+        engine
+            .register_mod_manager(mods.clone(), world_rc.clone())
+            .expect("Failed to register mod API");
+        if let Err(e) = mods.load(&mod_name, world_rc.clone(), &mut engine) {
             eprintln!("Failed to load mod: {e}");
             std::process::exit(1);
         }
+        report_tech_tree_errors(&world_rc.borrow());
+        for conflict in mods.conflicts() {
+            eprintln!(
+                "Mod conflict: {} '{}' defined by {}; using {}",
+                conflict.kind,
+                conflict.id,
+                conflict.mods.join(", "),
+                conflict.mods.last().unwrap()
+            );
+        }
         return;
     }
 
@@ -205,24 +257,6 @@ fn main() {
             registry.lock().unwrap().register_external_schema(schema);
         }
 
-        // --- Plugin registration ---
-        let mut engine_api = EngineApi {
-            spawn_entity: engine_core::plugins::ffi::ffi_spawn_entity,
-            set_component: engine_core::plugins::ffi::ffi_set_component,
-        };
-        let mut worldgen_registry = WorldgenRegistry::new();
-        let world_ptr: *mut std::os::raw::c_void = std::ptr::null_mut();
-
-        unsafe {
-            load_native_plugins_from_config(
-                &config,
-                &mut engine_api,
-                world_ptr,
-                &mut worldgen_registry,
-            )
-        }
-        .expect("Failed to load native plugins from config");
-
         // Load recipes and register EconomicSystem
         let recipes_dir = find_recipes_dir();
         if !recipes_dir.exists() {
@@ -234,7 +268,7 @@ fn main() {
         }
         let recipes = load_recipes_from_dir(&recipes_dir);
         let economic_system = EconomicSystem::with_recipes(recipes);
-        let mut world = World::new(registry.clone());
+        let mut world = World::with_config(registry.clone(), &config);
         world.register_system(BodyPartDamageSystem);
         world.register_system(economic_system);
         if let Some(mode) = mode_arg {
@@ -246,13 +280,16 @@ fn main() {
         if let Ok(mats) = load_material_definitions(&materials_dir) {
             world.material_definitions = mats;
         }
+        load_tech_tree(&mut world);
 
         let world_rc = Rc::new(RefCell::new(world));
+        load_native_plugins(&config, &world_rc);
         let mut engine = ScriptEngine::new();
         engine
             .register_world(world_rc.clone())
             .expect("Failed to register ECS API");
         engine.set_lua_args(script_args);
+        report_tech_tree_errors(&world_rc.borrow());
 
         if let Err(e) = engine.run_script(&script) {
             eprintln!("Lua error: {e:?}");
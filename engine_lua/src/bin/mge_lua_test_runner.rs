@@ -85,6 +85,11 @@ fn materials_dir() -> PathBuf {
     workspace_root().join("engine/assets/materials")
 }
 
+/// Returns the absolute path to the engine tech tree directory
+fn tech_dir() -> PathBuf {
+    workspace_root().join("engine/assets/tech")
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args: Vec<String> = env::args().skip(1).collect();
     let filter_module = args.first().map(|s| s.as_str());
@@ -196,6 +201,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         let mut engine_api = EngineApi {
             spawn_entity: dummy_spawn_entity,
             set_component: dummy_set_component,
+            ..EngineApi::new()
         };
         let world_ptr = std::ptr::null_mut();
         let mut dir = workspace_root();
@@ -260,6 +266,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         if let Ok(mats) = load_material_definitions(materials_dir()) {
             world.borrow_mut().material_definitions = mats;
         }
+        world
+            .borrow_mut()
+            .tech_tree
+            .load(tech_dir())
+            .expect("Failed to load tech tree");
 
         let mut grid = SquareGridMap::new();
         grid.add_cell(0, 2, 0);
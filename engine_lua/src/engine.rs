@@ -7,10 +7,12 @@ use crate::lua_api::job_events::register_job_event_api;
 use crate::lua_api::job_mutation::register_job_mutation_api;
 use crate::lua_api::job_query::register_job_query_api;
 use crate::lua_api::job_system::register_job_system_api;
+use crate::lua_api::mods::register_mods_api;
 use crate::lua_api::system::register_system_functions;
 use crate::lua_api::world::register_world_api;
 use crate::lua_api::worldgen::register_worldgen_api;
 use engine_core::ecs::world::World;
+use engine_core::mods::ModManager;
 use engine_core::mods::loader::ModScriptEngine;
 use engine_core::worldgen::{GLOBAL_WORLDGEN_REGISTRY, WorldgenRegistry};
 use mlua::RegistryKey;
@@ -164,6 +166,17 @@ impl ScriptEngine {
         Ok(())
     }
 
+    /// Exposes a mod manager to Lua (`list_mods`, `load_mod`, `unload_mod`,
+    /// `reload_mod`, `enable_mod`, `disable_mod`)
+    pub fn register_mod_manager(
+        &self,
+        mods: ModManager,
+        world: Rc<RefCell<World>>,
+    ) -> mlua::Result<()> {
+        let globals = self.lua.globals();
+        register_mods_api(Rc::clone(&self.lua), &globals, world, mods)
+    }
+
     /// Sets the command line arguments
     pub fn set_lua_args(&self, args: Vec<String>) {
         let globals = self.lua.globals();
@@ -1,16 +1,28 @@
 //! Dungeon generation API: `generate_dungeon(config)` Lua global.
 //!
-//! Provides a stateless function that delegates to `DungeonGenerator::generate()`
-//! and returns the result as a Lua table compatible with `world:apply_generated_map()`.
+//! Delegates to `DungeonGenerator::generate()` and returns the result as a Lua table
+//! compatible with `world:apply_generated_map()`. When no seed is given, one is drawn
+//! from the world's worldgen RNG stream.
 
+use engine_core::ecs::world::World;
+use engine_core::rng;
 use engine_core::systems::dungeon::{DungeonConfig, DungeonGenerator};
 use mlua::{Lua, Result as LuaResult, Table};
+use std::cell::RefCell;
+use std::rc::Rc;
 
 /// Registers the dungeon generation API.
-pub fn register_dungeon_api(lua: &Lua, globals: &Table) -> LuaResult<()> {
+pub fn register_dungeon_api(
+    lua: &Lua,
+    globals: &Table,
+    world: Rc<RefCell<World>>,
+) -> LuaResult<()> {
     let generate_dungeon = lua.create_function_mut(move |lua, config: Option<Table>| {
         // Parse config from Lua table or use defaults
-        let mut dungeon_config = DungeonConfig::default();
+        let mut dungeon_config = DungeonConfig {
+            seed: world.borrow_mut().rng.next_u64(rng::WORLDGEN),
+            ..DungeonConfig::default()
+        };
 
         if let Some(table) = config {
             // Only apply user-provided values; zero or non-numeric → error or default
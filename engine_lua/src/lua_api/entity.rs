@@ -76,6 +76,14 @@ pub fn register_entity_api(lua: &Lua, globals: &Table, world: Rc<RefCell<World>>
     })?;
     globals.set("is_entity_alive", is_entity_alive)?;
 
+    // is_stale_entity(entity)
+    let world_is_stale = world.clone();
+    let is_stale_entity = lua.create_function_mut(move |_, entity: u32| {
+        let world = world_is_stale.borrow();
+        Ok(world.is_stale_entity(entity))
+    })?;
+    globals.set("is_stale_entity", is_stale_entity)?;
+
     // move_entity(entity, dx, dy)
     let world_move_entity = world.clone();
     let move_entity = lua.create_function_mut(move |_, (entity, dx, dy): (u32, f32, f32)| {
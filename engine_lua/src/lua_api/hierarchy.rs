@@ -0,0 +1,66 @@
+//! Hierarchy API: parent/child links between entities.
+
+use crate::helpers::{lua_error_from_any, lua_error_msg};
+use engine_core::ecs::world::{DespawnPolicy, World};
+use mlua::{Lua, Result as LuaResult, Table};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Register the hierarchy API.
+pub fn register_hierarchy_api(
+    lua: &Lua,
+    globals: &Table,
+    world: Rc<RefCell<World>>,
+) -> LuaResult<()> {
+    // set_parent(child, parent, policy?) where policy is "cascade" (default) or "reparent"
+    let world_set = world.clone();
+    let set_parent = lua.create_function_mut(
+        move |lua, (child, parent, policy): (u32, u32, Option<String>)| {
+            let policy = match policy {
+                Some(name) => DespawnPolicy::from_name(&name).ok_or_else(|| {
+                    lua_error_msg(lua, &format!("Unknown despawn policy '{name}'"))
+                })?,
+                None => DespawnPolicy::default(),
+            };
+            world_set
+                .borrow_mut()
+                .set_parent_with_policy(child, parent, policy)
+                .map_err(|e| lua_error_from_any(lua, e))
+        },
+    )?;
+    globals.set("set_parent", set_parent)?;
+
+    // remove_parent(child) -> former parent or nil
+    let world_remove = world.clone();
+    let remove_parent = lua.create_function_mut(move |_, child: u32| {
+        Ok(world_remove.borrow_mut().remove_parent(child))
+    })?;
+    globals.set("remove_parent", remove_parent)?;
+
+    // parent_of(entity) -> parent or nil
+    let world_parent = world.clone();
+    let parent_of =
+        lua.create_function(move |_, entity: u32| Ok(world_parent.borrow().parent_of(entity)))?;
+    globals.set("parent_of", parent_of)?;
+
+    // children_of(entity) -> { child, ... }
+    let world_children = world.clone();
+    let children_of =
+        lua.create_function(move |_, entity: u32| Ok(world_children.borrow().children_of(entity)))?;
+    globals.set("children_of", children_of)?;
+
+    // ancestors(entity) -> { parent, grandparent, ... }
+    let world_ancestors = world.clone();
+    let ancestors =
+        lua.create_function(move |_, entity: u32| Ok(world_ancestors.borrow().ancestors(entity)))?;
+    globals.set("ancestors", ancestors)?;
+
+    // descendants(entity) -> all entities below, depth first
+    let world_descendants = world.clone();
+    let descendants = lua.create_function(move |_, entity: u32| {
+        Ok(world_descendants.borrow().descendants(entity))
+    })?;
+    globals.set("descendants", descendants)?;
+
+    Ok(())
+}
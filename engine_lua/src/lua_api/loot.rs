@@ -51,8 +51,7 @@ pub fn register_loot_api(lua: &Lua, globals: &Table, world: Rc<RefCell<World>>)
     // roll_loot_table(name) — returns array of {item_id=, count=} tables
     let world_roll = world.clone();
     let roll_fn = lua.create_function_mut(move |lua: &Lua, name: String| -> LuaResult<Table> {
-        let world = world_roll.borrow();
-        let result = world.loot_tables.roll(&name);
+        let result = world_roll.borrow_mut().roll_loot(&name);
 
         let results_table = lua.create_table()?;
         if let Ok(items) = result {
@@ -1,6 +1,7 @@
 use crate::helpers::{json_to_lua_table, lua_table_to_json, lua_value_to_json};
 use engine_core::ecs::world::World;
-use engine_core::map::CellKey;
+use engine_core::map::flow_field::DEFAULT_FLEE_FACTOR;
+use engine_core::map::{CellKey, DijkstraMap, MovementProfile};
 use mlua::{Lua, Result as LuaResult, Table, Value as LuaValue};
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -72,7 +73,6 @@ pub fn register_map_api(lua: &Lua, globals: &Table, world: Rc<RefCell<World>>) -
         let mut world = world_add_cell.borrow_mut();
         if let Some(map) = &mut world.map
             && let Some(square) = map
-                .topology
                 .as_any_mut()
                 .downcast_mut::<engine_core::map::SquareGridMap>()
         {
@@ -128,7 +128,6 @@ pub fn register_map_api(lua: &Lua, globals: &Table, world: Rc<RefCell<World>>) -
         let to_xyz = table_to_xyz(lua, to)?;
         if let Some(map) = &mut world.map
             && let Some(square) = map
-                .topology
                 .as_any_mut()
                 .downcast_mut::<engine_core::map::SquareGridMap>()
         {
@@ -138,6 +137,12 @@ pub fn register_map_api(lua: &Lua, globals: &Table, world: Rc<RefCell<World>>) -
     })?;
     globals.set("add_neighbor", add_neighbor)?;
 
+    // connect_levels()
+    let world_connect_levels = world.clone();
+    let connect_levels = lua
+        .create_function_mut(move |_, ()| Ok(world_connect_levels.borrow_mut().connect_levels()))?;
+    globals.set("connect_levels", connect_levels)?;
+
     // entities_in_cell(cell)
     let world_entities_in_cell = world.clone();
     let entities_in_cell = lua.create_function_mut(move |lua, cell: LuaValue| {
@@ -153,6 +158,40 @@ pub fn register_map_api(lua: &Lua, globals: &Table, world: Rc<RefCell<World>>) -
     })?;
     globals.set("entities_in_cell", entities_in_cell)?;
 
+    // entities_in_radius(cell, radius)
+    let world_in_radius = world.clone();
+    let entities_in_radius =
+        lua.create_function_mut(move |lua, (cell, radius): (LuaValue, f64)| {
+            let world = world_in_radius.borrow();
+            let cell_key = parse_cell_key(lua_value_to_json(lua, cell, None)?)?;
+            lua.create_sequence_from(world.entities_in_radius(&cell_key, radius))
+        })?;
+    globals.set("entities_in_radius", entities_in_radius)?;
+
+    // entities_in_rect(corner_a, corner_b)
+    let world_in_rect = world.clone();
+    let entities_in_rect = lua.create_function_mut(move |lua, (a, b): (LuaValue, LuaValue)| {
+        let world = world_in_rect.borrow();
+        let a = parse_cell_key(lua_value_to_json(lua, a, None)?)?;
+        let b = parse_cell_key(lua_value_to_json(lua, b, None)?)?;
+        lua.create_sequence_from(world.entities_in_rect(&a, &b))
+    })?;
+    globals.set("entities_in_rect", entities_in_rect)?;
+
+    // nearest_entity_with_component(cell, component, max_radius?) -> entity or nil
+    let world_nearest = world.clone();
+    let nearest_entity_with_component = lua.create_function_mut(
+        move |lua, (cell, component, max_radius): (LuaValue, String, Option<f64>)| {
+            let world = world_nearest.borrow();
+            let cell_key = parse_cell_key(lua_value_to_json(lua, cell, None)?)?;
+            Ok(world.nearest_entity_with_component(&cell_key, &component, max_radius))
+        },
+    )?;
+    globals.set(
+        "nearest_entity_with_component",
+        nearest_entity_with_component,
+    )?;
+
     // --- get_cell_metadata(cell) ---
     let world_get_cell_meta = world.clone();
     let get_cell_metadata = lua.create_function_mut(move |lua, cell: LuaValue| {
@@ -180,32 +219,124 @@ pub fn register_map_api(lua: &Lua, globals: &Table, world: Rc<RefCell<World>>) -
         })?;
     globals.set("set_cell_metadata", set_cell_metadata)?;
 
-    // find_path(start_cell, goal_cell)
+    // find_path(start_cell, goal_cell, profile?)
     let world_find_path = world.clone();
-    let find_path = lua.create_function_mut(move |lua, (start, goal): (LuaValue, LuaValue)| {
-        let world = world_find_path.borrow();
-        let start_json = lua_value_to_json(lua, start, None)?;
-        let goal_json = lua_value_to_json(lua, goal, None)?;
-        let start_key = parse_cell_key(start_json)?;
-        let goal_key = parse_cell_key(goal_json)?;
-        if let Some(result) = world.find_path(&start_key, &goal_key) {
-            let arr = lua.create_table()?;
-            for (i, cell) in result.path.iter().enumerate() {
-                arr.set(
-                    i + 1,
-                    json_to_lua_table(lua, &serde_json::to_value(cell).unwrap())?,
-                )?;
-            }
-            let out = lua.create_table()?;
-            out.set("path", arr)?;
-            out.set("total_cost", result.total_cost)?;
-            Ok(LuaValue::Table(out))
-        } else {
-            Ok(LuaValue::Nil)
-        }
-    })?;
+    let find_path = lua.create_function_mut(
+        move |lua, (start, goal, profile): (LuaValue, LuaValue, Option<String>)| {
+            let world = world_find_path.borrow();
+            let start_key = parse_cell_key(lua_value_to_json(lua, start, None)?)?;
+            let goal_key = parse_cell_key(lua_value_to_json(lua, goal, None)?)?;
+            let result = match profile {
+                Some(profile) => world.find_path_with_profile(&start_key, &goal_key, &profile),
+                None => world.find_path(&start_key, &goal_key),
+            };
+            path_to_lua(lua, result)
+        },
+    )?;
     globals.set("find_path", find_path)?;
 
+    // find_path_for(entity, start_cell, goal_cell): uses the entity's movement profile
+    let world_find_path_for = world.clone();
+    let find_path_for = lua.create_function_mut(
+        move |lua, (entity, start, goal): (u32, LuaValue, LuaValue)| {
+            let world = world_find_path_for.borrow();
+            let start_key = parse_cell_key(lua_value_to_json(lua, start, None)?)?;
+            let goal_key = parse_cell_key(lua_value_to_json(lua, goal, None)?)?;
+            path_to_lua(lua, world.find_path_for(entity, &start_key, &goal_key))
+        },
+    )?;
+    globals.set("find_path_for", find_path_for)?;
+
+    // dijkstra_map(goal_cells, profile?) -> { {cell, distance}, ... }
+    let world_dijkstra_map = world.clone();
+    let dijkstra_map =
+        lua.create_function(move |lua, (goals, profile): (Table, Option<String>)| {
+            let goals = parse_cells(lua, &goals)?;
+            let world = world_dijkstra_map.borrow();
+            let profile = profile.as_deref().unwrap_or(MovementProfile::WALKER);
+            dijkstra_map_to_lua(lua, world.dijkstra_map(&goals, profile))
+        })?;
+    globals.set("dijkstra_map", dijkstra_map)?;
+
+    // flee_map(threat_cells, profile?, factor?) -> { {cell, distance}, ... }
+    let world_flee_map = world.clone();
+    let flee_map = lua.create_function(
+        move |lua, (threats, profile, factor): (Table, Option<String>, Option<f32>)| {
+            let threats = parse_cells(lua, &threats)?;
+            let world = world_flee_map.borrow();
+            let profile = profile.as_deref().unwrap_or(MovementProfile::WALKER);
+            let factor = factor.unwrap_or(DEFAULT_FLEE_FACTOR);
+            dijkstra_map_to_lua(lua, world.flee_map(&threats, profile, factor))
+        },
+    )?;
+    globals.set("flee_map", flee_map)?;
+
+    // flow_step(cell, goal_cells, profile?) -> next cell towards the nearest goal, or nil
+    let world_flow_step = world.clone();
+    let flow_step = lua.create_function(
+        move |lua, (cell, goals, profile): (LuaValue, Table, Option<String>)| {
+            let cell = parse_cell_key(lua_value_to_json(lua, cell, None)?)?;
+            let goals = parse_cells(lua, &goals)?;
+            let world = world_flow_step.borrow();
+            let profile = profile.as_deref().unwrap_or(MovementProfile::WALKER);
+            cell_to_lua(lua, world.flow_step(&cell, &goals, profile))
+        },
+    )?;
+    globals.set("flow_step", flow_step)?;
+
+    // flee_step(cell, threat_cells, profile?, factor?) -> next cell away from threats, or nil
+    let world_flee_step = world.clone();
+    let flee_step =
+        lua.create_function(
+            move |lua,
+                  (cell, threats, profile, factor): (
+                LuaValue,
+                Table,
+                Option<String>,
+                Option<f32>,
+            )| {
+                let cell = parse_cell_key(lua_value_to_json(lua, cell, None)?)?;
+                let threats = parse_cells(lua, &threats)?;
+                let world = world_flee_step.borrow();
+                let profile = profile.as_deref().unwrap_or(MovementProfile::WALKER);
+                let factor = factor.unwrap_or(DEFAULT_FLEE_FACTOR);
+                cell_to_lua(lua, world.flee_step(&cell, &threats, profile, factor))
+            },
+        )?;
+    globals.set("flee_step", flee_step)?;
+
+    // register_movement_profile(profile_table)
+    let world_register_profile = world.clone();
+    let register_movement_profile = lua.create_function_mut(move |lua, profile: Table| {
+        let profile_json = lua_table_to_json(lua, &profile, None)?;
+        let profile: MovementProfile =
+            serde_json::from_value(profile_json).map_err(mlua::Error::external)?;
+        world_register_profile
+            .borrow_mut()
+            .movement_profiles
+            .register(profile);
+        Ok(())
+    })?;
+    globals.set("register_movement_profile", register_movement_profile)?;
+
+    // set_pathfinding_strategy(name, cluster_size?): "astar" or "hierarchical"
+    let world_set_strategy = world.clone();
+    let set_pathfinding_strategy =
+        lua.create_function_mut(move |_, (name, cluster_size): (String, Option<u32>)| {
+            world_set_strategy
+                .borrow_mut()
+                .set_pathfinding_strategy_by_name(&name, cluster_size)
+                .map_err(mlua::Error::external)
+        })?;
+    globals.set("set_pathfinding_strategy", set_pathfinding_strategy)?;
+
+    // get_pathfinding_strategy() -> name
+    let world_get_strategy = world.clone();
+    let get_pathfinding_strategy = lua.create_function(move |_, ()| {
+        Ok(world_get_strategy.borrow().pathfinding_strategy.name())
+    })?;
+    globals.set("get_pathfinding_strategy", get_pathfinding_strategy)?;
+
     // apply_generated_map(map_table)
     let world_for_apply = world.clone();
     let apply_generated_map = lua.create_function_mut(move |lua, map_table: Table| {
@@ -239,3 +370,60 @@ pub fn register_map_api(lua: &Lua, globals: &Table, world: Rc<RefCell<World>>) -
 
     Ok(())
 }
+
+/// Converts a pathfinding result to a `{ path, total_cost }` table, or nil.
+fn path_to_lua(
+    lua: &Lua,
+    result: Option<engine_core::map::PathfindingResult>,
+) -> LuaResult<LuaValue> {
+    let Some(result) = result else {
+        return Ok(LuaValue::Nil);
+    };
+    let arr = lua.create_table()?;
+    for (i, cell) in result.path.iter().enumerate() {
+        arr.set(
+            i + 1,
+            json_to_lua_table(lua, &serde_json::to_value(cell).unwrap())?,
+        )?;
+    }
+    let out = lua.create_table()?;
+    out.set("path", arr)?;
+    out.set("total_cost", result.total_cost)?;
+    Ok(LuaValue::Table(out))
+}
+
+/// Parses an array of cells.
+fn parse_cells(lua: &Lua, cells: &Table) -> LuaResult<Vec<CellKey>> {
+    cells
+        .clone()
+        .sequence_values::<LuaValue>()
+        .map(|cell| parse_cell_key(lua_value_to_json(lua, cell?, None)?))
+        .collect()
+}
+
+/// Converts a cell to a table, or nil.
+fn cell_to_lua(lua: &Lua, cell: Option<CellKey>) -> LuaResult<LuaValue> {
+    match cell {
+        Some(cell) => json_to_lua_table(lua, &serde_json::to_value(cell).unwrap()),
+        None => Ok(LuaValue::Nil),
+    }
+}
+
+/// Converts a Dijkstra map to an array of `{ cell, distance }` tables, or nil
+/// without a map.
+fn dijkstra_map_to_lua(lua: &Lua, map: Option<std::sync::Arc<DijkstraMap>>) -> LuaResult<LuaValue> {
+    let Some(map) = map else {
+        return Ok(LuaValue::Nil);
+    };
+    let arr = lua.create_table()?;
+    for (i, (cell, distance)) in map.entries().into_iter().enumerate() {
+        let entry = lua.create_table()?;
+        entry.set(
+            "cell",
+            json_to_lua_table(lua, &serde_json::to_value(cell).unwrap())?,
+        )?;
+        entry.set("distance", distance)?;
+        arr.set(i + 1, entry)?;
+    }
+    Ok(LuaValue::Table(arr))
+}
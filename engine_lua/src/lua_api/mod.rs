@@ -25,6 +25,8 @@ pub mod event_bus;
 pub mod faction;
 /// Field-of-view API
 pub mod fov;
+/// Hierarchy API
+pub mod hierarchy;
 /// Input API
 pub mod input;
 /// Inventory API
@@ -51,12 +53,22 @@ pub mod map;
 pub mod material;
 /// Game mode API
 pub mod mode;
+/// Mod manager API
+pub mod mods;
 /// Movement API
 pub mod movement_ops;
+/// Query API
+pub mod query;
 /// Region API
 pub mod region;
+/// Replay API
+pub mod replay;
+/// Seeded RNG API
+pub mod rng;
 /// Save/Load API
 pub mod save_load;
+/// State hash API
+pub mod state_hash;
 /// System API
 pub mod system;
 /// Tech Tree and Research API
@@ -90,6 +102,8 @@ pub fn register_all_api_functions(
     worldgen_registry: Rc<RefCell<WorldgenRegistry>>,
 ) -> LuaResult<()> {
     entity::register_entity_api(lua, globals, world.clone())?;
+    hierarchy::register_hierarchy_api(lua, globals, world.clone())?;
+    query::register_query_api(lua, globals, world.clone())?;
     component::register_component_api(lua, globals, world.clone())?;
     input::register_input_api(lua, globals, input_provider)?;
     inventory::register_inventory_api(lua, globals, world.clone())?;
@@ -97,7 +111,7 @@ pub fn register_all_api_functions(
     body::register_body_api(lua, globals, world.clone())?;
     region::register_region_api(lua, globals, world.clone())?;
     camera::register_camera_api(lua, globals, world.clone())?;
-    ui::register_ui_api(lua, globals)?;
+    ui::register_ui_api(lua, globals, world.clone())?;
     worldgen::register_worldgen_api(lua, globals, worldgen_registry)?;
     mode::register_mode_api(lua, globals, world.clone())?;
     turn::register_turn_api(lua, globals, world.clone())?;
@@ -107,9 +121,12 @@ pub fn register_all_api_functions(
     map::register_map_api(lua, globals, world.clone())?;
     economic::register_economic_api(lua, globals, world.clone())?;
     movement_ops::register_movement_ops_api(lua, globals, world.clone())?;
-    dungeon::register_dungeon_api(lua, globals)?;
+    dungeon::register_dungeon_api(lua, globals, world.clone())?;
     job_ai::register_job_ai_api(lua, globals, world.clone())?;
     loot::register_loot_api(lua, globals, world.clone())?;
+    rng::register_rng_api(lua, globals, world.clone())?;
+    replay::register_replay_api(lua, globals, world.clone())?;
+    state_hash::register_state_hash_api(lua, globals, world.clone())?;
     faction::register_faction_api(lua, globals, world.clone())?;
     material::register_material_api(lua, globals, world.clone())?;
     tech_tree::register_tech_tree_api(lua, globals, world.clone())?;
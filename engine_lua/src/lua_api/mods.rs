@@ -0,0 +1,97 @@
+//! Mod manager API: list, load, unload, reload, enable and disable mods at runtime.
+
+use crate::helpers::lua_error_from_any;
+use engine_core::ecs::world::World;
+use engine_core::mods::ModManager;
+use engine_core::mods::loader::ModScriptEngine;
+use mlua::{Lua, Result as LuaResult, Table};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Runs mod scripts in the Lua VM calling into the mod manager.
+struct LuaModScripts(Rc<Lua>);
+
+impl ModScriptEngine for LuaModScripts {
+    fn run_script(&mut self, script: &str) -> Result<(), String> {
+        self.0.load(script).call(()).map_err(|e| e.to_string())
+    }
+}
+
+/// Register the mod manager API.
+pub fn register_mods_api(
+    lua: Rc<Lua>,
+    globals: &Table,
+    world: Rc<RefCell<World>>,
+    mods: ModManager,
+) -> LuaResult<()> {
+    // list_mods() -> { { name, version, description, enabled, loaded }, ... }
+    let mods_list = mods.clone();
+    let list_mods = lua.create_function(move |lua, ()| {
+        let list = lua.create_table()?;
+        for (i, discovered) in mods_list.mods().into_iter().enumerate() {
+            let name = discovered.manifest.name;
+            let entry = lua.create_table()?;
+            entry.set("version", discovered.manifest.version)?;
+            entry.set("description", discovered.manifest.description)?;
+            entry.set("enabled", mods_list.is_enabled(&name))?;
+            entry.set("loaded", mods_list.is_loaded(&name))?;
+            entry.set("name", name)?;
+            list.set(i + 1, entry)?;
+        }
+        Ok(list)
+    })?;
+    globals.set("list_mods", list_mods)?;
+
+    // load_mod(name) -> names of the mods loaded, dependencies first
+    let (mods_load, world_load, lua_load) = (mods.clone(), world.clone(), lua.clone());
+    let load_mod = lua.create_function(move |lua, name: String| {
+        mods_load
+            .load(
+                &name,
+                world_load.clone(),
+                &mut LuaModScripts(lua_load.clone()),
+            )
+            .map_err(|e| lua_error_from_any(lua, e))
+    })?;
+    globals.set("load_mod", load_mod)?;
+
+    // unload_mod(name)
+    let (mods_unload, world_unload) = (mods.clone(), world.clone());
+    let unload_mod = lua.create_function(move |lua, name: String| {
+        mods_unload
+            .unload(&name, &world_unload)
+            .map_err(|e| lua_error_from_any(lua, e))
+    })?;
+    globals.set("unload_mod", unload_mod)?;
+
+    // reload_mod(name)
+    let (mods_reload, world_reload, lua_reload) = (mods.clone(), world.clone(), lua.clone());
+    let reload_mod = lua.create_function(move |lua, name: String| {
+        mods_reload
+            .reload(
+                &name,
+                world_reload.clone(),
+                &mut LuaModScripts(lua_reload.clone()),
+            )
+            .map_err(|e| lua_error_from_any(lua, e))
+    })?;
+    globals.set("reload_mod", reload_mod)?;
+
+    // enable_mod(name)
+    let mods_enable = mods.clone();
+    let enable_mod = lua.create_function(move |lua, name: String| {
+        mods_enable
+            .enable(&name)
+            .map_err(|e| lua_error_from_any(lua, e))
+    })?;
+    globals.set("enable_mod", enable_mod)?;
+
+    // disable_mod(name) unloads the mod if loaded
+    let disable_mod = lua.create_function(move |lua, name: String| {
+        mods.disable(&name, &world)
+            .map_err(|e| lua_error_from_any(lua, e))
+    })?;
+    globals.set("disable_mod", disable_mod)?;
+
+    Ok(())
+}
@@ -69,8 +69,8 @@ pub fn register_movement_ops_api(
             let mut world = world_clone.borrow_mut();
 
             let move_path_vec = {
-                if let Some(map) = &world.map {
-                    if let Some(pathfinding) = map.find_path(&from_cell, &to_cell) {
+                if world.map.is_some() {
+                    if let Some(pathfinding) = world.find_path_for(agent_id, &from_cell, &to_cell) {
                         // skip the start cell and convert path cells to JSON
                         pathfinding
                             .path
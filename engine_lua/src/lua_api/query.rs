@@ -0,0 +1,108 @@
+//! Query API: chainable entity queries with filters and change detection.
+//!
+//! ```lua
+//! local wounded = query():with("Health"):without("Corpse")
+//!     :where("Health", "current", "<", 5):changed("Health"):entities()
+//! ```
+
+use crate::helpers::{json_to_lua_table, lua_error_msg, lua_value_to_json};
+use engine_core::ecs::query::{FieldOp, FieldPredicate, Query};
+use engine_core::ecs::world::World;
+use mlua::{AnyUserData, Lua, Result as LuaResult, Table, UserData, UserDataMethods, Value};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// A query under construction, bound to the world it runs against.
+pub struct LuaQuery {
+    world: Rc<RefCell<World>>,
+    query: Query,
+}
+
+/// Applies `edit` to the query and returns the same userdata for chaining.
+fn chain(ud: AnyUserData, edit: impl FnOnce(&mut Query)) -> LuaResult<AnyUserData> {
+    edit(&mut ud.borrow_mut::<LuaQuery>()?.query);
+    Ok(ud)
+}
+
+impl UserData for LuaQuery {
+    fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+        methods.add_function("with", |_, (ud, name): (AnyUserData, String)| {
+            chain(ud, |q| q.with.push(name))
+        });
+        methods.add_function("without", |_, (ud, name): (AnyUserData, String)| {
+            chain(ud, |q| q.without.push(name))
+        });
+        methods.add_function("optional", |_, (ud, name): (AnyUserData, String)| {
+            chain(ud, |q| q.optional.push(name))
+        });
+        methods.add_function("changed", |_, (ud, name): (AnyUserData, String)| {
+            chain(ud, |q| q.changed.push(name))
+        });
+        methods.add_function("added", |_, (ud, name): (AnyUserData, String)| {
+            chain(ud, |q| q.added.push(name))
+        });
+        methods.add_function("since", |_, (ud, tick): (AnyUserData, u64)| {
+            chain(ud, |q| q.since = Some(tick))
+        });
+
+        // where(component, path, op, value)
+        methods.add_function(
+            "where",
+            |lua, (ud, component, path, op, value): (AnyUserData, String, String, String, Value)| {
+                let op = FieldOp::from_name(&op)
+                    .ok_or_else(|| lua_error_msg(lua, &format!("Unknown operator '{op}'")))?;
+                let value = lua_value_to_json(lua, value, None)?;
+                chain(ud, |q| {
+                    q.predicates.push(FieldPredicate {
+                        component,
+                        path,
+                        op,
+                        value,
+                    })
+                })
+            },
+        );
+
+        // entities() -> { id, ... } in ascending order
+        methods.add_method("entities", |_, this, ()| {
+            Ok(this.query.entities(&*this.world.borrow()))
+        });
+
+        // rows() -> { { entity = id, components = { Name = value, ... } }, ... }
+        methods.add_method("rows", |lua, this, ()| {
+            let rows = this.query.rows(&*this.world.borrow());
+            let result = lua.create_table()?;
+            for (i, row) in rows.into_iter().enumerate() {
+                let components = lua.create_table()?;
+                for (name, value) in &row.components {
+                    components.set(name.as_str(), json_to_lua_table(lua, value)?)?;
+                }
+                let entry = lua.create_table()?;
+                entry.set("entity", row.entity)?;
+                entry.set("components", components)?;
+                result.set(i + 1, entry)?;
+            }
+            Ok(result)
+        });
+    }
+}
+
+/// Register the query API.
+pub fn register_query_api(lua: &Lua, globals: &Table, world: Rc<RefCell<World>>) -> LuaResult<()> {
+    // query() -> new query matching every entity
+    let world_query = world.clone();
+    let query = lua.create_function(move |_, ()| {
+        Ok(LuaQuery {
+            world: world_query.clone(),
+            query: Query::new(),
+        })
+    })?;
+    globals.set("query", query)?;
+
+    // change_tick() -> current change tick, for query():since(tick)
+    let world_tick = world.clone();
+    let change_tick = lua.create_function(move |_, ()| Ok(world_tick.borrow().change_tick()))?;
+    globals.set("change_tick", change_tick)?;
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+//! Replay API: record sessions and replay them headless with per-tick state checks.
+
+use crate::helpers::lua_error_from_any;
+use engine_core::ecs::world::{Recording, SaveFormat, World};
+use engine_core::presentation::input::InputEvent;
+use mlua::{Lua, Result as LuaResult, Table};
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+
+/// Register the replay API.
+pub fn register_replay_api(lua: &Lua, globals: &Table, world: Rc<RefCell<World>>) -> LuaResult<()> {
+    // start_recording()
+    let world_start = world.clone();
+    let start_recording = lua.create_function_mut(move |lua, ()| {
+        world_start
+            .borrow_mut()
+            .start_recording()
+            .map_err(|e| lua_error_from_any(lua, e))
+    })?;
+    globals.set("start_recording", start_recording)?;
+
+    // is_recording()
+    let world_is = world.clone();
+    let is_recording = lua.create_function(move |_, ()| Ok(world_is.borrow().is_recording()))?;
+    globals.set("is_recording", is_recording)?;
+
+    // stop_recording(filename, format?) -> number of recorded ticks
+    let world_stop = world.clone();
+    let stop_recording =
+        lua.create_function_mut(move |lua, (filename, format): (String, Option<String>)| {
+            let format = match format {
+                Some(name) => SaveFormat::from_name(&name).ok_or_else(|| {
+                    mlua::Error::external(format!("Unknown save format '{name}'"))
+                })?,
+                None => SaveFormat::Json,
+            };
+            let recording = world_stop
+                .borrow_mut()
+                .stop_recording()
+                .ok_or_else(|| mlua::Error::external("Not recording"))?;
+            recording
+                .save_to_file(Path::new(&filename), format)
+                .map_err(|e| lua_error_from_any(lua, e))?;
+            Ok(recording.len())
+        })?;
+    globals.set("stop_recording", stop_recording)?;
+
+    // replay_recording(filename, ticks?) -> number of replayed ticks; errors on desync
+    let world_replay = world.clone();
+    let replay_recording =
+        lua.create_function_mut(move |lua, (filename, ticks): (String, Option<usize>)| {
+            let recording = Recording::load_from_file(Path::new(&filename))
+                .map_err(|e| lua_error_from_any(lua, e))?;
+            let ticks = ticks.unwrap_or(recording.len());
+            World::replay_ticks(Rc::clone(&world_replay), &recording, ticks)
+                .map_err(|e| lua_error_from_any(lua, e))
+        })?;
+    globals.set("replay_recording", replay_recording)?;
+
+    // push_input(key) — a single character, or "quit"
+    let world_input = world.clone();
+    let push_input = lua.create_function_mut(move |_, key: String| {
+        let event = if key == "quit" {
+            InputEvent::Quit
+        } else {
+            let mut chars = key.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => InputEvent::KeyPress(c),
+                _ => {
+                    return Err(mlua::Error::external(format!(
+                        "Expected a single character or \"quit\", got '{key}'"
+                    )));
+                }
+            }
+        };
+        world_input.borrow_mut().push_input(event);
+        Ok(())
+    })?;
+    globals.set("push_input", push_input)?;
+
+    Ok(())
+}
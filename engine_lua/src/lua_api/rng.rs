@@ -0,0 +1,38 @@
+//! RNG API: seed and draw from the world's named random streams.
+
+use engine_core::ecs::world::World;
+use mlua::{Lua, Result as LuaResult, Table};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Register the RNG API.
+pub fn register_rng_api(lua: &Lua, globals: &Table, world: Rc<RefCell<World>>) -> LuaResult<()> {
+    // seed_rng(seed)
+    let world_seed = world.clone();
+    let seed_rng = lua.create_function_mut(move |_, seed: u64| {
+        world_seed.borrow_mut().seed_rng(seed);
+        Ok(())
+    })?;
+    globals.set("seed_rng", seed_rng)?;
+
+    // get_rng_seed()
+    let world_get = world.clone();
+    let get_rng_seed = lua.create_function(move |_, ()| Ok(world_get.borrow().rng_seed()))?;
+    globals.set("get_rng_seed", get_rng_seed)?;
+
+    // rng_random(stream) -> float in [0, 1)
+    let world_random = world.clone();
+    let rng_random = lua.create_function_mut(move |_, stream: String| {
+        Ok(world_random.borrow_mut().random_f64(&stream))
+    })?;
+    globals.set("rng_random", rng_random)?;
+
+    // rng_range(stream, min, max) -> integer in [min, max]
+    let world_range = world.clone();
+    let rng_range = lua.create_function_mut(move |_, (stream, min, max): (String, i64, i64)| {
+        Ok(world_range.borrow_mut().random_range(&stream, min, max))
+    })?;
+    globals.set("rng_range", rng_range)?;
+
+    Ok(())
+}
@@ -1,7 +1,7 @@
-//! Save/Load API: world serialization.
+//! Save/Load API: world serialization and in-memory snapshots.
 
 use crate::helpers::lua_error_from_any;
-use engine_core::ecs::world::World;
+use engine_core::ecs::world::{SaveFormat, World, WorldSnapshot};
 use mlua::{Lua, Result as LuaResult, Table};
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -12,14 +12,21 @@ pub fn register_save_load_api(
     globals: &Table,
     world: Rc<RefCell<World>>,
 ) -> LuaResult<()> {
-    // save_to_file(filename)
+    // save_to_file(filename, format?) — format is "json" (default), "binary" or "compressed"
     let world_save = world.clone();
-    let save_to_file = lua.create_function_mut(move |lua, filename: String| {
-        let world = world_save.borrow();
-        world
-            .save_to_file(std::path::Path::new(&filename))
-            .map_err(|e| lua_error_from_any(lua, e))
-    })?;
+    let save_to_file =
+        lua.create_function_mut(move |lua, (filename, format): (String, Option<String>)| {
+            let format = match format {
+                Some(name) => SaveFormat::from_name(&name).ok_or_else(|| {
+                    mlua::Error::external(format!("Unknown save format '{name}'"))
+                })?,
+                None => SaveFormat::Json,
+            };
+            let world = world_save.borrow();
+            world
+                .save_to_file_as(std::path::Path::new(&filename), format)
+                .map_err(|e| lua_error_from_any(lua, e))
+        })?;
     globals.set("save_to_file", save_to_file)?;
 
     // load_from_file(filename)
@@ -34,5 +41,28 @@ pub fn register_save_load_api(
     })?;
     globals.set("load_from_file", load_from_file)?;
 
+    // snapshot() — returns the world state as an opaque binary string
+    let world_snapshot = world.clone();
+    let snapshot = lua.create_function(move |lua, ()| {
+        let snapshot = world_snapshot
+            .borrow()
+            .snapshot()
+            .map_err(|e| lua_error_from_any(lua, e))?;
+        lua.create_string(snapshot.as_bytes())
+    })?;
+    globals.set("snapshot", snapshot)?;
+
+    // restore(snapshot) — roll the world back to a snapshot, keeping registered systems
+    let world_restore = world.clone();
+    let restore = lua.create_function_mut(move |lua, data: mlua::String| {
+        let snapshot = WorldSnapshot::from_bytes(data.as_bytes().to_vec())
+            .map_err(|e| lua_error_from_any(lua, e))?;
+        world_restore
+            .borrow_mut()
+            .restore(&snapshot)
+            .map_err(|e| lua_error_from_any(lua, e))
+    })?;
+    globals.set("restore", restore)?;
+
     Ok(())
 }
@@ -0,0 +1,47 @@
+//! State hash API: world state hashing and per-system hash logs for desync hunting.
+//!
+//! Hashes are returned as 16-digit hex strings, since Lua numbers cannot hold a u64.
+
+use engine_core::ecs::world::World;
+use mlua::{Lua, Result as LuaResult, Table};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Register the state hash API.
+pub fn register_state_hash_api(
+    lua: &Lua,
+    globals: &Table,
+    world: Rc<RefCell<World>>,
+) -> LuaResult<()> {
+    // state_hash() -> hex string
+    let world_hash = world.clone();
+    let state_hash =
+        lua.create_function(move |_, ()| Ok(format!("{:016x}", world_hash.borrow().state_hash())))?;
+    globals.set("state_hash", state_hash)?;
+
+    // set_system_hash_debug(enabled)
+    let world_debug = world.clone();
+    let set_system_hash_debug = lua.create_function_mut(move |_, enabled: bool| {
+        world_debug.borrow_mut().set_system_hash_debug(enabled);
+        Ok(())
+    })?;
+    globals.set("set_system_hash_debug", set_system_hash_debug)?;
+
+    // take_system_hashes() -> { { turn, system, hash }, ... }
+    let world_take = world.clone();
+    let take_system_hashes = lua.create_function_mut(move |lua, ()| {
+        let entries = world_take.borrow_mut().take_system_hashes();
+        let tbl = lua.create_table()?;
+        for (i, entry) in entries.into_iter().enumerate() {
+            let row = lua.create_table()?;
+            row.set("turn", entry.turn)?;
+            row.set("system", entry.system)?;
+            row.set("hash", format!("{:016x}", entry.hash))?;
+            tbl.set(i + 1, row)?;
+        }
+        Ok(tbl)
+    })?;
+    globals.set("take_system_hashes", take_system_hashes)?;
+
+    Ok(())
+}
@@ -1,10 +1,23 @@
-use crate::helpers::{lua_error_from_any, lua_error_msg};
+use crate::helpers::{lua_error_from_any, lua_error_msg, lua_table_to_json};
+use engine_core::ecs::schedule::{RunCondition, SystemStage};
 use engine_core::ecs::world::World;
 use mlua::{Function, Lua, RegistryKey, Result as LuaResult, Table};
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
 
+/// Parses a stage name ("pre_update", "update", "post_update", "render").
+fn parse_stage(lua: &Lua, name: &str) -> LuaResult<SystemStage> {
+    SystemStage::from_name(name)
+        .ok_or_else(|| lua_error_msg(lua, &format!("Unknown system stage '{name}'")))
+}
+
+/// Parses a run condition table, e.g. `{ type = "every_n_ticks", n = 10 }`.
+fn parse_run_condition(lua: &Lua, table: &Table) -> LuaResult<RunCondition> {
+    let value = lua_table_to_json(lua, table, None)?;
+    serde_json::from_value(value).map_err(|e| lua_error_from_any(lua, e))
+}
+
 /// Registers all system functions and Lua bridge APIs except job system APIs.
 pub fn register_system_functions(
     lua: Rc<Lua>,
@@ -21,11 +34,21 @@ pub fn register_system_functions(
             lua_systems_outer.borrow_mut().insert(name.clone(), key);
 
             let mut dependencies = Vec::new();
-            if let Some(opts) = opts
-                && let Ok(dep_table) = opts.get::<Table>("dependencies")
-            {
-                for dep in dep_table.sequence_values::<String>() {
-                    dependencies.push(dep?);
+            let mut stage = None;
+            let mut conditions = Vec::new();
+            if let Some(opts) = opts {
+                if let Ok(dep_table) = opts.get::<Table>("dependencies") {
+                    for dep in dep_table.sequence_values::<String>() {
+                        dependencies.push(dep?);
+                    }
+                }
+                if let Some(name) = opts.get::<Option<String>>("stage")? {
+                    stage = Some(parse_stage(&lua_outer, &name)?);
+                }
+                if let Some(run_if) = opts.get::<Option<Table>>("run_if")? {
+                    for condition in run_if.sequence_values::<Table>() {
+                        conditions.push(parse_run_condition(&lua_outer, &condition?)?);
+                    }
                 }
             }
 
@@ -34,7 +57,15 @@ pub fn register_system_functions(
             let lua_systems_inner = Rc::clone(&lua_systems_outer);
             let lua_inner = lua_outer.clone();
 
-            world_rc.borrow_mut().register_dynamic_system_with_deps(
+            let mut world = world_rc.borrow_mut();
+            if let Some(stage) = stage {
+                world.set_system_stage(&name, stage);
+            }
+            world.clear_run_conditions(&name);
+            for condition in conditions {
+                world.add_run_condition(&name, condition);
+            }
+            world.register_dynamic_system_with_deps(
                 &system_name_for_closure,
                 dependencies,
                 move |_world, dt| {
@@ -77,5 +108,40 @@ pub fn register_system_functions(
     })?;
     globals.set("run_native_system", run_native_system)?;
 
+    // set_system_stage(name, stage)
+    let world_stage = world.clone();
+    let set_system_stage = lua.create_function(move |lua, (name, stage): (String, String)| {
+        let stage = parse_stage(lua, &stage)?;
+        world_stage.borrow_mut().set_system_stage(&name, stage);
+        Ok(())
+    })?;
+    globals.set("set_system_stage", set_system_stage)?;
+
+    // get_system_stage(name)
+    let world_get_stage = world.clone();
+    let get_system_stage = lua.create_function(move |_, name: String| {
+        Ok(world_get_stage.borrow().system_stage(&name).name())
+    })?;
+    globals.set("get_system_stage", get_system_stage)?;
+
+    // add_run_condition(name, { type = "in_mode", mode = "colony" })
+    let world_condition = world.clone();
+    let add_run_condition = lua.create_function(move |lua, (name, table): (String, Table)| {
+        let condition = parse_run_condition(lua, &table)?;
+        world_condition
+            .borrow_mut()
+            .add_run_condition(&name, condition);
+        Ok(())
+    })?;
+    globals.set("add_run_condition", add_run_condition)?;
+
+    // clear_run_conditions(name)
+    let world_clear = world.clone();
+    let clear_run_conditions = lua.create_function(move |_, name: String| {
+        world_clear.borrow_mut().clear_run_conditions(&name);
+        Ok(())
+    })?;
+    globals.set("clear_run_conditions", clear_run_conditions)?;
+
     Ok(())
 }
@@ -1,11 +1,15 @@
-//! Tech Tree and Research API: get_tech_tree, get_tech_node, get_tech_progress,
+//! Tech Tree and Research API: get_tech_tree, get_tech_node, load_tech_tree,
+//! validate_tech_tree, get_tech_progress,
 //! get_completed_techs, is_tech_completed, get_research_queue,
 //! get_research_queue_progress, research_tech, cancel_research,
-//! clear_research_queue, can_research_tech.
+//! clear_research_queue, can_research_tech, grant_tech, revoke_tech,
+//! get_tech_effects, is_recipe_unlocked, is_job_type_unlocked,
+//! get_research_pool, add_research_points.
 
-use crate::helpers::json_to_lua_table;
+use crate::helpers::{json_to_lua_table, lua_error_from_any};
 use engine_core::ecs::world::World;
-use engine_core::tech_tree;
+use engine_core::systems::research;
+use engine_core::{tech_effects, tech_tree};
 use mlua::{Lua, Result as LuaResult, Table, Value as LuaValue};
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -17,25 +21,49 @@ pub fn register_tech_tree_api(
     world: Rc<RefCell<World>>,
 ) -> LuaResult<()> {
     // get_tech_tree() -> table array of tech nodes
-    let get_tech_tree_fn = lua.create_function(|lua, ()| {
-        let nodes = tech_tree::get_tech_tree();
-        let json_value = serde_json::to_value(nodes).unwrap_or_default();
+    let w = world.clone();
+    let get_tech_tree_fn = lua.create_function(move |lua, ()| {
+        let world = w.borrow();
+        let json_value = serde_json::to_value(tech_tree::get_tech_tree(&world)).unwrap_or_default();
         json_to_lua_table(lua, &json_value)
     })?;
     globals.set("get_tech_tree", get_tech_tree_fn)?;
 
     // get_tech_node(tech_id) -> table or nil
-    let get_tech_node_fn = lua.create_function(|lua, tech_id: String| -> LuaResult<LuaValue> {
-        match tech_tree::get_tech_node(&tech_id) {
-            Some(node) => {
-                let json_value = serde_json::to_value(node).unwrap_or_default();
-                json_to_lua_table(lua, &json_value)
+    let w = world.clone();
+    let get_tech_node_fn =
+        lua.create_function(move |lua, tech_id: String| -> LuaResult<LuaValue> {
+            let world = w.borrow();
+            match tech_tree::get_tech_node(&world, &tech_id) {
+                Some(node) => {
+                    let json_value = serde_json::to_value(node).unwrap_or_default();
+                    json_to_lua_table(lua, &json_value)
+                }
+                None => Ok(LuaValue::Nil),
             }
-            None => Ok(LuaValue::Nil),
-        }
-    })?;
+        })?;
     globals.set("get_tech_node", get_tech_node_fn)?;
 
+    // load_tech_tree(path) — merges nodes from a JSON file or directory, replacing same IDs
+    let w = world.clone();
+    let load_tech_tree_fn = lua.create_function(move |lua, path: String| {
+        w.borrow_mut()
+            .tech_tree
+            .load(&path)
+            .map_err(|e| lua_error_from_any(lua, e))
+    })?;
+    globals.set("load_tech_tree", load_tech_tree_fn)?;
+
+    // validate_tech_tree() -> array of problems (unknown prerequisites, cycles)
+    let w = world.clone();
+    let validate_tech_tree_fn = lua.create_function(move |_, ()| {
+        Ok(match w.borrow().tech_tree.validate() {
+            Ok(()) => Vec::new(),
+            Err(errors) => errors.iter().map(|e| e.to_string()).collect(),
+        })
+    })?;
+    globals.set("validate_tech_tree", validate_tech_tree_fn)?;
+
     // get_tech_progress(entity) -> table or nil
     let w = world.clone();
     let get_tech_progress_fn =
@@ -124,11 +152,11 @@ pub fn register_tech_tree_api(
     globals.set("clear_research_queue", clear_research_queue_fn)?;
 
     // can_research_tech(entity, tech_id) -> boolean, reason_string
-    let w = world;
+    let w = world.clone();
     let can_research_tech_fn = lua.create_function_mut(
         move |_, (entity, tech_id): (u32, String)| -> LuaResult<(bool, String)> {
             let world = w.borrow();
-            match tech_tree::can_research_tech(&world, entity, &tech_id) {
+            match tech_tree::can_research_tech(&*world, entity, &tech_id) {
                 Ok(true) => Ok((true, String::new())),
                 Ok(false) => Ok((false, "Unknown reason".to_string())),
                 Err(reason) => Ok((false, reason)),
@@ -137,5 +165,85 @@ pub fn register_tech_tree_api(
     )?;
     globals.set("can_research_tech", can_research_tech_fn)?;
 
+    // grant_tech(entity, tech_id) — completes the tech and applies its effects (cheat/debug)
+    let w = world.clone();
+    let grant_tech_fn = lua.create_function_mut(
+        move |_, (entity, tech_id): (u32, String)| -> LuaResult<()> {
+            let mut world = w.borrow_mut();
+            tech_effects::grant_tech(&mut world, entity, &tech_id)
+                .map_err(mlua::Error::external)?;
+            Ok(())
+        },
+    )?;
+    globals.set("grant_tech", grant_tech_fn)?;
+
+    // revoke_tech(entity, tech_id) — un-completes the tech and reverts its effects
+    let w = world.clone();
+    let revoke_tech_fn = lua.create_function_mut(
+        move |_, (entity, tech_id): (u32, String)| -> LuaResult<()> {
+            let mut world = w.borrow_mut();
+            tech_effects::revoke_tech(&mut world, entity, &tech_id)
+                .map_err(mlua::Error::external)?;
+            Ok(())
+        },
+    )?;
+    globals.set("revoke_tech", revoke_tech_fn)?;
+
+    // get_tech_effects(entity) -> array of { action, data } applying to the entity
+    let w = world.clone();
+    let get_tech_effects_fn = lua.create_function(move |lua, entity: u32| {
+        let world = w.borrow();
+        let json_value = serde_json::to_value(tech_effects::get_tech_effects(&world, entity))
+            .unwrap_or_default();
+        json_to_lua_table(lua, &json_value)
+    })?;
+    globals.set("get_tech_effects", get_tech_effects_fn)?;
+
+    // is_recipe_unlocked(entity, recipe) -> boolean
+    let w = world.clone();
+    let is_recipe_unlocked_fn = lua.create_function(
+        move |_, (entity, recipe): (u32, String)| -> LuaResult<bool> {
+            Ok(tech_effects::is_recipe_unlocked(
+                &w.borrow(),
+                entity,
+                &recipe,
+            ))
+        },
+    )?;
+    globals.set("is_recipe_unlocked", is_recipe_unlocked_fn)?;
+
+    // is_job_type_unlocked(entity, job_type) -> boolean
+    let w = world.clone();
+    let is_job_type_unlocked_fn = lua.create_function(
+        move |_, (entity, job_type): (u32, String)| -> LuaResult<bool> {
+            Ok(tech_effects::is_job_type_unlocked(
+                &w.borrow(),
+                entity,
+                &job_type,
+            ))
+        },
+    )?;
+    globals.set("is_job_type_unlocked", is_job_type_unlocked_fn)?;
+
+    // get_research_pool(faction_id) -> number of shared research points
+    let w = world.clone();
+    let get_research_pool_fn = lua.create_function(move |_, faction_id: String| {
+        Ok(w.borrow().research_pools.get(&faction_id))
+    })?;
+    globals.set("get_research_pool", get_research_pool_fn)?;
+
+    // add_research_points(entity, points) -> boolean; credits the entity's faction pool
+    // or its own TechProgress
+    let w = world;
+    let add_research_points_fn =
+        lua.create_function(move |_, (entity, points): (u32, f64)| -> LuaResult<bool> {
+            Ok(research::credit_research(
+                &mut w.borrow_mut(),
+                entity,
+                points,
+            ))
+        })?;
+    globals.set("add_research_points", add_research_points_fn)?;
+
     Ok(())
 }
@@ -1,4 +1,4 @@
-//! Turn API: tick simulation, get current turn.
+//! Turn API: tick simulation, get current turn, fixed-timestep advance.
 
 use crate::lua_api::job_system::process_lua_job_calls;
 use engine_core::ecs::world::World;
@@ -27,5 +27,29 @@ pub fn register_turn_api(lua: &Lua, globals: &Table, world: Rc<RefCell<World>>)
     })?;
     globals.set("get_turn", get_turn)?;
 
+    // advance(elapsed_seconds) -> ticks run
+    let world_advance = world.clone();
+    let lua_advance = lua.clone();
+    let advance = lua.create_function_mut(move |_, elapsed: f32| {
+        let ticks = World::advance(Rc::clone(&world_advance), elapsed);
+        process_lua_job_calls(&lua_advance, &world_advance)?;
+        Ok(ticks)
+    })?;
+    globals.set("advance", advance)?;
+
+    // set_fixed_timestep(seconds)
+    let world_set_step = world.clone();
+    let set_fixed_timestep = lua.create_function(move |_, step: f32| {
+        world_set_step.borrow_mut().set_fixed_timestep(step);
+        Ok(())
+    })?;
+    globals.set("set_fixed_timestep", set_fixed_timestep)?;
+
+    // get_fixed_timestep()
+    let world_get_step = world.clone();
+    let get_fixed_timestep =
+        lua.create_function(move |_, ()| Ok(world_get_step.borrow().fixed_timestep()))?;
+    globals.set("get_fixed_timestep", get_fixed_timestep)?;
+
     Ok(())
 }
@@ -1,3 +1,5 @@
+use engine_core::ecs::world::World;
+use engine_core::mods::ModResource;
 use engine_core::presentation::ui::factory::{UI_FACTORY, WIDGET_REGISTRY, WidgetProps};
 use engine_core::presentation::ui::schema_loader::load_ui_from_json;
 use engine_core::presentation::ui::widget::UiWidget;
@@ -5,9 +7,14 @@ use engine_core::presentation::ui::widget::dynamic::DynamicWidget;
 use mlua::{
     Function as LuaFunction, Lua, LuaSerdeExt, Result as LuaResult, Table, Value as LuaValue,
 };
+use std::cell::RefCell;
+use std::rc::Rc;
 
 /// Register UI API
-pub fn register_ui_api(lua: &Lua, globals: &Table) -> LuaResult<()> {
+///
+/// Widgets created while a mod loads are recorded as the mod's, so unloading
+/// the mod removes them.
+pub fn register_ui_api(lua: &Lua, globals: &Table, world: Rc<RefCell<World>>) -> LuaResult<()> {
     engine_core::presentation::ui::register_all_widgets();
 
     let globals_table = lua.globals();
@@ -21,9 +28,10 @@ pub fn register_ui_api(lua: &Lua, globals: &Table) -> LuaResult<()> {
 
     let ui = lua.create_table()?;
 
+    let world_create = world.clone();
     ui.set(
         "create_widget",
-        lua.create_function(|lua, (type_name, props): (String, Table)| {
+        lua.create_function(move |lua, (type_name, props): (String, Table)| {
             // Check for custom widget type registered via register_widget
             let callbacks: Table = lua.globals().get::<Table>("_ui_callbacks")?;
             let ctor_key = format!("_custom_widget_ctor_{type_name}");
@@ -35,6 +43,9 @@ pub fn register_ui_api(lua: &Lua, globals: &Table) -> LuaResult<()> {
                     let dynamic_widget = DynamicWidget::new(type_name, widget);
                     registry.insert(widget_id, Box::new(dynamic_widget));
                 }
+                world_create
+                    .borrow_mut()
+                    .record_mod_resource(ModResource::Widget(widget_id));
                 return Ok(widget_id);
             }
 
@@ -52,6 +63,9 @@ pub fn register_ui_api(lua: &Lua, globals: &Table) -> LuaResult<()> {
                 let binding = WIDGET_REGISTRY.lock();
                 let mut registry = binding.borrow_mut();
                 registry.insert(id, widget);
+                world_create
+                    .borrow_mut()
+                    .record_mod_resource(ModResource::Widget(id));
                 Ok(id)
             } else {
                 Ok(0)
@@ -59,9 +73,10 @@ pub fn register_ui_api(lua: &Lua, globals: &Table) -> LuaResult<()> {
         })?,
     )?;
 
+    let world_load = world.clone();
     ui.set(
         "load_json",
-        lua.create_function(|_, json_str: String| {
+        lua.create_function(move |_, json_str: String| {
             let mut ids = Vec::new();
             if let Some(root_widget) = load_ui_from_json(&json_str) {
                 fn collect_ids(widget: &(dyn UiWidget + Send), ids: &mut Vec<u64>) {
@@ -70,6 +85,9 @@ pub fn register_ui_api(lua: &Lua, globals: &Table) -> LuaResult<()> {
                 collect_ids(root_widget.as_ref(), &mut ids);
                 let binding = WIDGET_REGISTRY.lock();
                 let mut registry = binding.borrow_mut();
+                world_load
+                    .borrow_mut()
+                    .record_mod_resource(ModResource::Widget(root_widget.id()));
                 registry.insert(root_widget.id(), root_widget);
             }
             Ok(ids)
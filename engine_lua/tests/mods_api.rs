@@ -0,0 +1,82 @@
+//! Tests for managing mods from Lua: loading, unloading and reloading mods at
+//! runtime removes and re-creates what their scripts registered.
+
+use engine_core::ecs::registry::ComponentRegistry;
+use engine_core::ecs::world::World;
+use engine_core::mods::ModManager;
+use engine_lua::ScriptEngine;
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+fn write_mod(mods_dir: &Path, name: &str, main: &str) {
+    let dir = mods_dir.join(name);
+    std::fs::create_dir_all(&dir).unwrap();
+    let manifest = serde_json::json!({
+        "name": name,
+        "version": "1.0.0",
+        "main_script": "main.lua",
+    });
+    std::fs::write(dir.join("mod.json"), manifest.to_string()).unwrap();
+    std::fs::write(dir.join("main.lua"), main).unwrap();
+}
+
+#[test]
+fn test_mods_can_be_unloaded_and_reloaded_from_lua() {
+    let mods_dir = tempfile::tempdir().unwrap();
+    write_mod(
+        mods_dir.path(),
+        "hud",
+        r#"
+        hud_widget = ui.create_widget("Button", { label = "HUD", pos = { 0, 0 }, color = { 1, 2, 3 } })
+        register_system("hud_tick", function(dt) end)
+        "#,
+    );
+
+    let registry = Arc::new(Mutex::new(ComponentRegistry::new()));
+    let world = Rc::new(RefCell::new(World::new(registry)));
+    let mut engine = ScriptEngine::new();
+    engine.register_world(world.clone()).unwrap();
+    let mods = ModManager::discover(mods_dir.path()).unwrap();
+    engine
+        .register_mod_manager(mods.clone(), world.clone())
+        .unwrap();
+
+    engine
+        .run_script(
+            r#"
+            local loaded = load_mod("hud")
+            assert(#loaded == 1 and loaded[1] == "hud")
+            local listed = list_mods()
+            assert(#listed == 1)
+            assert(listed[1].name == "hud" and listed[1].loaded and listed[1].enabled)
+            assert(ui.get_widget_type(hud_widget) ~= nil)
+            first_widget = hud_widget
+
+            unload_mod("hud")
+            assert(ui.get_widget_type(first_widget) == nil)
+            assert(not list_mods()[1].loaded)
+            "#,
+        )
+        .unwrap();
+    assert!(!world.borrow().dynamic_systems.is_registered("hud_tick"));
+
+    engine
+        .run_script(
+            r#"
+            load_mod("hud")
+            reload_mod("hud")
+            assert(ui.get_widget_type(hud_widget) ~= nil)
+            disable_mod("hud")
+            assert(ui.get_widget_type(hud_widget) == nil)
+            assert(not pcall(load_mod, "hud"))
+            enable_mod("hud")
+            load_mod("hud")
+            "#,
+        )
+        .unwrap();
+    assert!(world.borrow().dynamic_systems.is_registered("hud_tick"));
+    assert_eq!(world.borrow().mod_resources("hud").len(), 2);
+    assert_eq!(mods.loaded(), vec!["hud"]);
+}
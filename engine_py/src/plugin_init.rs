@@ -33,10 +33,7 @@ pub fn register_plugins() {
         panic!("Failed to load config for plugin registration: {config_path:?}")
     });
 
-    let mut engine_api = EngineApi {
-        spawn_entity: engine_core::plugins::ffi::ffi_spawn_entity,
-        set_component: engine_core::plugins::ffi::ffi_set_component,
-    };
+    let mut engine_api = EngineApi::new();
     let world_ptr: *mut std::os::raw::c_void = std::ptr::null_mut();
 
     let mut registry = GLOBAL_WORLDGEN_REGISTRY.lock().unwrap();
@@ -2,6 +2,7 @@
 
 use crate::PyObject;
 use crate::python_api::PyWorld;
+use engine_core::rng;
 use engine_core::systems::dungeon::{DungeonConfig, DungeonGenerator};
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
@@ -16,8 +17,11 @@ pub trait DungeonApi {
 impl DungeonApi for PyWorld {
     fn generate_dungeon(&self, config: HashMap<String, PyObject>) -> PyResult<PyObject> {
         Python::attach(|py| {
-            // Build DungeonConfig from Python dict
-            let mut cfg = DungeonConfig::default();
+            // Build DungeonConfig from Python dict, seeded from the worldgen stream by default
+            let mut cfg = DungeonConfig {
+                seed: self.inner.borrow_mut().rng.next_u64(rng::WORLDGEN),
+                ..DungeonConfig::default()
+            };
 
             for (key, value) in &config {
                 match key.as_str() {
@@ -13,6 +13,8 @@ pub trait EntityApi {
     fn count_entities_with_type(&self, type_str: String) -> usize;
     /// Check if an entity is alive
     fn is_entity_alive(&self, entity_id: u32) -> bool;
+    /// Check if an entity ID refers to a despawned or replaced entity
+    fn is_stale_entity(&self, entity_id: u32) -> bool;
     /// Move an entity
     fn move_entity(&self, entity_id: u32, dx: f32, dy: f32);
     /// Damage an entity
@@ -53,6 +55,12 @@ impl EntityApi for PyWorld {
         world.is_entity_alive(entity_id)
     }
 
+    /// Check if an entity ID refers to a despawned or replaced entity
+    fn is_stale_entity(&self, entity_id: u32) -> bool {
+        let world = self.inner.borrow();
+        world.is_stale_entity(entity_id)
+    }
+
     /// Move an entity
     fn move_entity(&self, entity_id: u32, dx: f32, dy: f32) {
         let mut world = self.inner.borrow_mut();
@@ -0,0 +1,54 @@
+use super::PyWorld;
+use engine_core::ecs::world::DespawnPolicy;
+use pyo3::prelude::*;
+
+/// Entity hierarchy (parent/child links)
+pub trait HierarchyApi {
+    /// Set the parent of an entity; policy is "cascade" (default) or "reparent"
+    fn set_parent(&self, child: u32, parent: u32, policy: Option<String>) -> PyResult<()>;
+    /// Detach an entity from its parent, returning the former parent
+    fn remove_parent(&self, child: u32) -> Option<u32>;
+    /// Parent of an entity
+    fn parent_of(&self, entity: u32) -> Option<u32>;
+    /// Direct children of an entity
+    fn children_of(&self, entity: u32) -> Vec<u32>;
+    /// Ancestors of an entity, nearest first
+    fn ancestors(&self, entity: u32) -> Vec<u32>;
+    /// All entities below an entity, depth first
+    fn descendants(&self, entity: u32) -> Vec<u32>;
+}
+
+impl HierarchyApi for PyWorld {
+    fn set_parent(&self, child: u32, parent: u32, policy: Option<String>) -> PyResult<()> {
+        let policy = match policy {
+            Some(name) => DespawnPolicy::from_name(&name).ok_or_else(|| {
+                pyo3::exceptions::PyValueError::new_err(format!("Unknown despawn policy '{name}'"))
+            })?,
+            None => DespawnPolicy::default(),
+        };
+        self.inner
+            .borrow_mut()
+            .set_parent_with_policy(child, parent, policy)
+            .map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    fn remove_parent(&self, child: u32) -> Option<u32> {
+        self.inner.borrow_mut().remove_parent(child)
+    }
+
+    fn parent_of(&self, entity: u32) -> Option<u32> {
+        self.inner.borrow().parent_of(entity)
+    }
+
+    fn children_of(&self, entity: u32) -> Vec<u32> {
+        self.inner.borrow().children_of(entity)
+    }
+
+    fn ancestors(&self, entity: u32) -> Vec<u32> {
+        self.inner.borrow().ancestors(entity)
+    }
+
+    fn descendants(&self, entity: u32) -> Vec<u32> {
+        self.inner.borrow().descendants(entity)
+    }
+}
@@ -1,5 +1,7 @@
 use crate::PyObject;
 use crate::python_api::world::PyWorld;
+use engine_core::map::flow_field::DEFAULT_FLEE_FACTOR;
+use engine_core::map::{CellKey, DijkstraMap, MovementProfile};
 use pyo3::Py;
 use pyo3::prelude::*;
 use pyo3::types::{PyAny, PyDict};
@@ -57,7 +59,6 @@ pub fn add_neighbor(pyworld: &PyWorld, from: (i32, i32, i32), to: (i32, i32, i32
     let mut world = pyworld.inner.borrow_mut();
     if let Some(map) = &mut world.map
         && let Some(square) = map
-            .topology
             .as_any_mut()
             .downcast_mut::<engine_core::map::SquareGridMap>()
     {
@@ -65,6 +66,13 @@ pub fn add_neighbor(pyworld: &PyWorld, from: (i32, i32, i32), to: (i32, i32, i32
     }
 }
 
+/// Link stairs, ramps and ladders to the level above.
+///
+/// Returns the number of neighbor links added.
+pub fn connect_levels(pyworld: &PyWorld) -> usize {
+    pyworld.inner.borrow_mut().connect_levels()
+}
+
 /// Get a list of entity IDs located in the given cell.
 ///
 /// `cell` is a Python object representing a cell key.
@@ -79,6 +87,56 @@ pub fn entities_in_cell(pyworld: &PyWorld, py: Python, cell: &Bound<'_, PyAny>)
     serde_pyobject::to_pyobject(py, &entities).unwrap().into()
 }
 
+/// Get entity IDs within `radius` of `cell` on its z-level.
+///
+/// Returns `None` if `cell` is not a valid cell key.
+pub fn entities_in_radius(
+    pyworld: &PyWorld,
+    py: Python,
+    cell: &Bound<'_, PyAny>,
+    radius: f64,
+) -> PyObject {
+    let world = pyworld.inner.borrow();
+    let cell_key: engine_core::map::CellKey = match pythonize::depythonize(cell) {
+        Ok(val) => val,
+        Err(_) => return py.None(),
+    };
+    let entities = world.entities_in_radius(&cell_key, radius);
+    serde_pyobject::to_pyobject(py, &entities).unwrap().into()
+}
+
+/// Get entity IDs in the box spanned by two square or hex corner cells.
+///
+/// Returns `None` if either corner is not a valid cell key.
+pub fn entities_in_rect(
+    pyworld: &PyWorld,
+    py: Python,
+    a: &Bound<'_, PyAny>,
+    b: &Bound<'_, PyAny>,
+) -> PyObject {
+    let world = pyworld.inner.borrow();
+    let (Ok(a), Ok(b)) = (
+        pythonize::depythonize::<engine_core::map::CellKey>(a),
+        pythonize::depythonize::<engine_core::map::CellKey>(b),
+    ) else {
+        return py.None();
+    };
+    let entities = world.entities_in_rect(&a, &b);
+    serde_pyobject::to_pyobject(py, &entities).unwrap().into()
+}
+
+/// Get the entity with `component` nearest to `cell`, or `None`.
+pub fn nearest_entity_with_component(
+    pyworld: &PyWorld,
+    cell: &Bound<'_, PyAny>,
+    component: &str,
+    max_radius: Option<f64>,
+) -> Option<u32> {
+    let world = pyworld.inner.borrow();
+    let cell_key: engine_core::map::CellKey = pythonize::depythonize(cell).ok()?;
+    world.nearest_entity_with_component(&cell_key, component, max_radius)
+}
+
 /// Get metadata associated with a given cell.
 ///
 /// `cell` is a Python object representing a cell key.
@@ -114,6 +172,7 @@ pub fn set_cell_metadata(
 /// Find a path between two cells using the map's pathfinding system.
 ///
 /// `start` and `goal` are Python objects representing cell keys (e.g., coordinates).
+/// `profile` names a movement profile; the walker is used if it is `None`.
 ///
 /// Returns a Python dictionary with keys:
 /// - `"path"`: list of cells representing the path
@@ -124,6 +183,7 @@ pub fn find_path(
     py: Python,
     start: &Bound<'_, PyAny>,
     goal: &Bound<'_, PyAny>,
+    profile: Option<&str>,
 ) -> PyObject {
     let world = pyworld.inner.borrow();
 
@@ -137,19 +197,166 @@ pub fn find_path(
         Err(_) => return py.None(),
     };
 
-    if let Some(result) = world.find_path(&start_key, &goal_key) {
-        let dict = PyDict::new(py);
-        let _ = dict.set_item(
-            "path",
-            serde_pyobject::to_pyobject(py, &result.path).unwrap(),
-        );
-        let _ = dict.set_item("total_cost", result.total_cost);
-        dict.into()
-    } else {
-        py.None()
+    let result = match profile {
+        Some(profile) => world.find_path_with_profile(&start_key, &goal_key, profile),
+        None => world.find_path(&start_key, &goal_key),
+    };
+    path_to_py(py, result)
+}
+
+/// Find a path between two cells using the movement profile of `entity`.
+pub fn find_path_for(
+    pyworld: &PyWorld,
+    py: Python,
+    entity: u32,
+    start: &Bound<'_, PyAny>,
+    goal: &Bound<'_, PyAny>,
+) -> PyObject {
+    let world = pyworld.inner.borrow();
+    let (Ok(start_key), Ok(goal_key)) = (
+        pythonize::depythonize::<engine_core::map::CellKey>(start),
+        pythonize::depythonize::<engine_core::map::CellKey>(goal),
+    ) else {
+        return py.None();
+    };
+    path_to_py(py, world.find_path_for(entity, &start_key, &goal_key))
+}
+
+/// The Dijkstra map towards `goals` as a list of `{"cell", "distance"}` dicts,
+/// or None without a map or with invalid cells.
+pub fn dijkstra_map(
+    pyworld: &PyWorld,
+    py: Python,
+    goals: &Bound<'_, PyAny>,
+    profile: Option<&str>,
+) -> PyObject {
+    let world = pyworld.inner.borrow();
+    let Ok(goals) = depythonize::<Vec<CellKey>>(goals) else {
+        return py.None();
+    };
+    let profile = profile.unwrap_or(MovementProfile::WALKER);
+    dijkstra_map_to_py(py, world.dijkstra_map(&goals, profile))
+}
+
+/// The map leading away from `threats`, in the format of [`dijkstra_map`].
+pub fn flee_map(
+    pyworld: &PyWorld,
+    py: Python,
+    threats: &Bound<'_, PyAny>,
+    profile: Option<&str>,
+    factor: Option<f32>,
+) -> PyObject {
+    let world = pyworld.inner.borrow();
+    let Ok(threats) = depythonize::<Vec<CellKey>>(threats) else {
+        return py.None();
+    };
+    let profile = profile.unwrap_or(MovementProfile::WALKER);
+    let factor = factor.unwrap_or(DEFAULT_FLEE_FACTOR);
+    dijkstra_map_to_py(py, world.flee_map(&threats, profile, factor))
+}
+
+/// The next cell from `cell` towards the nearest of `goals`, or None.
+pub fn flow_step(
+    pyworld: &PyWorld,
+    py: Python,
+    cell: &Bound<'_, PyAny>,
+    goals: &Bound<'_, PyAny>,
+    profile: Option<&str>,
+) -> PyObject {
+    let world = pyworld.inner.borrow();
+    let (Ok(cell), Ok(goals)) = (
+        depythonize::<CellKey>(cell),
+        depythonize::<Vec<CellKey>>(goals),
+    ) else {
+        return py.None();
+    };
+    let profile = profile.unwrap_or(MovementProfile::WALKER);
+    match world.flow_step(&cell, &goals, profile) {
+        Some(next) => serde_pyobject::to_pyobject(py, &next).unwrap().into(),
+        None => py.None(),
+    }
+}
+
+/// The next cell from `cell` away from `threats`, or None.
+pub fn flee_step(
+    pyworld: &PyWorld,
+    py: Python,
+    cell: &Bound<'_, PyAny>,
+    threats: &Bound<'_, PyAny>,
+    profile: Option<&str>,
+    factor: Option<f32>,
+) -> PyObject {
+    let world = pyworld.inner.borrow();
+    let (Ok(cell), Ok(threats)) = (
+        depythonize::<CellKey>(cell),
+        depythonize::<Vec<CellKey>>(threats),
+    ) else {
+        return py.None();
+    };
+    let profile = profile.unwrap_or(MovementProfile::WALKER);
+    let factor = factor.unwrap_or(DEFAULT_FLEE_FACTOR);
+    match world.flee_step(&cell, &threats, profile, factor) {
+        Some(next) => serde_pyobject::to_pyobject(py, &next).unwrap().into(),
+        None => py.None(),
     }
 }
 
+/// Register (or replace) a movement profile from a dict.
+pub fn register_movement_profile(pyworld: &PyWorld, profile: &Bound<'_, PyAny>) -> PyResult<()> {
+    let profile: engine_core::map::MovementProfile = depythonize(profile)?;
+    pyworld
+        .inner
+        .borrow_mut()
+        .movement_profiles
+        .register(profile);
+    Ok(())
+}
+
+/// Select the pathfinding strategy (`"astar"` or `"hierarchical"`).
+pub fn set_pathfinding_strategy(
+    pyworld: &PyWorld,
+    name: &str,
+    cluster_size: Option<u32>,
+) -> PyResult<()> {
+    pyworld
+        .inner
+        .borrow_mut()
+        .set_pathfinding_strategy_by_name(name, cluster_size)
+        .map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+/// Name of the active pathfinding strategy.
+pub fn get_pathfinding_strategy(pyworld: &PyWorld) -> &'static str {
+    pyworld.inner.borrow().pathfinding_strategy.name()
+}
+
+/// Converts a pathfinding result to a `{"path", "total_cost"}` dict, or None.
+fn path_to_py(py: Python, result: Option<engine_core::map::PathfindingResult>) -> PyObject {
+    let Some(result) = result else {
+        return py.None();
+    };
+    let dict = PyDict::new(py);
+    let _ = dict.set_item(
+        "path",
+        serde_pyobject::to_pyobject(py, &result.path).unwrap(),
+    );
+    let _ = dict.set_item("total_cost", result.total_cost);
+    dict.into()
+}
+
+/// Converts a Dijkstra map to a list of `{"cell", "distance"}` dicts, or None.
+fn dijkstra_map_to_py(py: Python, map: Option<std::sync::Arc<DijkstraMap>>) -> PyObject {
+    let Some(map) = map else {
+        return py.None();
+    };
+    let entries: Vec<serde_json::Value> = map
+        .entries()
+        .into_iter()
+        .map(|(cell, distance)| serde_json::json!({ "cell": cell, "distance": distance }))
+        .collect();
+    serde_pyobject::to_pyobject(py, &entries).unwrap().into()
+}
+
 /// Register a Python callback as a map validator.
 pub fn register_map_validator(pyworld: &PyWorld, py: Python, callback: Py<PyAny>) {
     pyworld
@@ -233,7 +440,6 @@ pub fn add_cell(pyworld: &PyWorld, x: i32, y: i32, z: i32) {
     let mut world = pyworld.inner.borrow_mut();
     if let Some(map) = &mut world.map
         && let Some(square) = map
-            .topology
             .as_any_mut()
             .downcast_mut::<engine_core::map::SquareGridMap>()
     {
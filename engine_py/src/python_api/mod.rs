@@ -23,6 +23,8 @@ pub mod equipment;
 pub mod faction;
 /// Field-of-view API
 pub mod fov;
+/// Hierarchy API
+pub mod hierarchy;
 /// Inventory API
 pub mod inventory;
 /// Job AI API
@@ -51,10 +53,20 @@ pub mod material;
 pub mod mode;
 /// Movement API
 pub mod movement;
+/// Query API
+pub mod query;
 /// Region API
 pub mod region;
+/// Replay API
+pub mod replay;
+/// Seeded RNG API
+pub mod rng;
 /// Save/Load API
 pub mod save_load;
+/// System schedule API
+pub mod schedule;
+/// State hash API
+pub mod state_hash;
 /// Tech Tree and Research API
 pub mod tech_tree;
 /// Time API
@@ -66,5 +78,6 @@ pub mod ui;
 /// World API
 pub mod world;
 
+pub use query::PyQuery;
 pub use ui::UiApi;
 pub use world::PyWorld;
@@ -0,0 +1,117 @@
+use super::PyWorld;
+use crate::PyObject;
+use engine_core::ecs::query::{FieldOp, FieldPredicate, Query};
+use engine_core::ecs::world::World;
+use pyo3::prelude::*;
+use pyo3::types::PyAny;
+use serde_json::Value;
+use serde_pyobject::{from_pyobject, to_pyobject};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Chainable entity query returned by `world.query()`.
+///
+/// ```python
+/// wounded = world.query().with_("Health").without("Corpse") \
+///     .where("Health", "current", "<", 5).changed("Health").entities()
+/// ```
+#[pyclass(unsendable)]
+pub struct PyQuery {
+    world: Rc<RefCell<World>>,
+    query: Query,
+}
+
+#[pymethods]
+impl PyQuery {
+    /// Require a component (`with` is a Python keyword).
+    fn with_(mut slf: PyRefMut<'_, Self>, name: String) -> PyRefMut<'_, Self> {
+        slf.query.with.push(name);
+        slf
+    }
+
+    /// Exclude entities having a component.
+    fn without(mut slf: PyRefMut<'_, Self>, name: String) -> PyRefMut<'_, Self> {
+        slf.query.without.push(name);
+        slf
+    }
+
+    /// Include a component in rows() when present.
+    fn optional(mut slf: PyRefMut<'_, Self>, name: String) -> PyRefMut<'_, Self> {
+        slf.query.optional.push(name);
+        slf
+    }
+
+    /// Require a component field to compare to a value ("==", "!=", "<", "<=", ">", ">=").
+    /// The path is dotted ("stats.strength"); numeric segments index lists.
+    #[pyo3(name = "where")]
+    fn where_field<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        component: String,
+        path: String,
+        op: String,
+        value: Bound<'py, PyAny>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let op = FieldOp::from_name(&op).ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err(format!("Unknown operator '{op}'"))
+        })?;
+        let value: Value = from_pyobject(value)?;
+        slf.query.predicates.push(FieldPredicate {
+            component,
+            path,
+            op,
+            value,
+        });
+        Ok(slf)
+    }
+
+    /// Require a component added or changed after the change tick.
+    fn changed(mut slf: PyRefMut<'_, Self>, name: String) -> PyRefMut<'_, Self> {
+        slf.query.changed.push(name);
+        slf
+    }
+
+    /// Require a component added after the change tick.
+    fn added(mut slf: PyRefMut<'_, Self>, name: String) -> PyRefMut<'_, Self> {
+        slf.query.added.push(name);
+        slf
+    }
+
+    /// Set the change tick for changed()/added(); inside a system it defaults to
+    /// the system's previous run.
+    fn since(mut slf: PyRefMut<'_, Self>, tick: u64) -> PyRefMut<'_, Self> {
+        slf.query.since = Some(tick);
+        slf
+    }
+
+    /// Matching entity IDs in ascending order.
+    fn entities(&self) -> Vec<u32> {
+        self.query.entities(&*self.world.borrow())
+    }
+
+    /// Matching entities as [{"entity": id, "components": {name: value}}].
+    fn rows(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let rows = self.query.rows(&*self.world.borrow());
+        Ok(to_pyobject(py, &rows)?.into())
+    }
+}
+
+/// Query API
+pub trait QueryApi {
+    /// Start a new query matching every entity
+    fn query(&self) -> PyQuery;
+    /// Current change tick, for query().since(tick)
+    fn change_tick(&self) -> u64;
+}
+
+impl QueryApi for PyWorld {
+    fn query(&self) -> PyQuery {
+        PyQuery {
+            world: self.inner.clone(),
+            query: Query::new(),
+        }
+    }
+
+    fn change_tick(&self) -> u64 {
+        self.inner.borrow().change_tick()
+    }
+}
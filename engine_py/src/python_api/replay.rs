@@ -0,0 +1,77 @@
+use super::PyWorld;
+use engine_core::ecs::world::{Recording, SaveFormat, World};
+use engine_core::presentation::input::InputEvent;
+use pyo3::prelude::*;
+use std::path::Path;
+use std::rc::Rc;
+
+/// Session recording and headless replay
+pub trait ReplayApi {
+    /// Start recording a session from the current state
+    fn start_recording(&self) -> PyResult<()>;
+    /// Returns true while recording
+    fn is_recording(&self) -> bool;
+    /// Stop recording and write the recording to a file; returns the recorded tick count
+    fn stop_recording(&self, path: String, format: Option<String>) -> PyResult<usize>;
+    /// Replay a recording file, checking the state hash after every tick
+    fn replay_recording(&self, path: String, ticks: Option<usize>) -> PyResult<usize>;
+    /// Send an input event (a single character, or "quit")
+    fn push_input(&self, key: String) -> PyResult<()>;
+}
+
+impl ReplayApi for PyWorld {
+    fn start_recording(&self) -> PyResult<()> {
+        self.inner
+            .borrow_mut()
+            .start_recording()
+            .map_err(|e| pyo3::exceptions::PyIOError::new_err(e.to_string()))
+    }
+
+    fn is_recording(&self) -> bool {
+        self.inner.borrow().is_recording()
+    }
+
+    fn stop_recording(&self, path: String, format: Option<String>) -> PyResult<usize> {
+        let format = match format {
+            Some(name) => SaveFormat::from_name(&name).ok_or_else(|| {
+                pyo3::exceptions::PyValueError::new_err(format!("Unknown save format '{name}'"))
+            })?,
+            None => SaveFormat::Json,
+        };
+        let recording = self
+            .inner
+            .borrow_mut()
+            .stop_recording()
+            .ok_or_else(|| pyo3::exceptions::PyRuntimeError::new_err("Not recording"))?;
+        recording
+            .save_to_file(Path::new(&path), format)
+            .map_err(|e| pyo3::exceptions::PyIOError::new_err(e.to_string()))?;
+        Ok(recording.len())
+    }
+
+    fn replay_recording(&self, path: String, ticks: Option<usize>) -> PyResult<usize> {
+        let recording = Recording::load_from_file(Path::new(&path))
+            .map_err(|e| pyo3::exceptions::PyIOError::new_err(e.to_string()))?;
+        let ticks = ticks.unwrap_or(recording.len());
+        World::replay_ticks(Rc::clone(&self.inner), &recording, ticks)
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))
+    }
+
+    fn push_input(&self, key: String) -> PyResult<()> {
+        let event = if key == "quit" {
+            InputEvent::Quit
+        } else {
+            let mut chars = key.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => InputEvent::KeyPress(c),
+                _ => {
+                    return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                        "Expected a single character or \"quit\", got '{key}'"
+                    )));
+                }
+            }
+        };
+        self.inner.borrow_mut().push_input(event);
+        Ok(())
+    }
+}
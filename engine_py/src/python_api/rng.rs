@@ -0,0 +1,31 @@
+use super::PyWorld;
+
+/// Seeded RNG streams
+pub trait RngApi {
+    /// Reseed the world RNG
+    fn seed_rng(&self, seed: u64);
+    /// Get the master seed
+    fn get_rng_seed(&self) -> u64;
+    /// Draw a float in [0, 1) from a named stream
+    fn rng_random(&self, stream: &str) -> f64;
+    /// Draw an integer in [min, max] from a named stream
+    fn rng_range(&self, stream: &str, min: i64, max: i64) -> i64;
+}
+
+impl RngApi for PyWorld {
+    fn seed_rng(&self, seed: u64) {
+        self.inner.borrow_mut().seed_rng(seed);
+    }
+
+    fn get_rng_seed(&self) -> u64 {
+        self.inner.borrow().rng_seed()
+    }
+
+    fn rng_random(&self, stream: &str) -> f64 {
+        self.inner.borrow_mut().random_f64(stream)
+    }
+
+    fn rng_range(&self, stream: &str, min: i64, max: i64) -> i64 {
+        self.inner.borrow_mut().random_range(stream, min, max)
+    }
+}
@@ -1,19 +1,31 @@
 use super::PyWorld;
+use engine_core::ecs::world::{SaveFormat, WorldSnapshot};
 use pyo3::prelude::*;
+use pyo3::types::PyBytes;
 
 /// API for saving and loading a world
 pub trait SaveLoadApi {
-    /// Save a world to a file
-    fn save_to_file(&self, path: String) -> PyResult<()>;
+    /// Save a world to a file ("json", "binary" or "compressed")
+    fn save_to_file(&self, path: String, format: Option<String>) -> PyResult<()>;
     /// Load a world from a file
     fn load_from_file(&mut self, path: String) -> PyResult<()>;
+    /// Take an in-memory snapshot of the world
+    fn snapshot<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>>;
+    /// Restore the world from a snapshot
+    fn restore(&self, data: &[u8]) -> PyResult<()>;
 }
 
 impl SaveLoadApi for PyWorld {
-    fn save_to_file(&self, path: String) -> PyResult<()> {
+    fn save_to_file(&self, path: String, format: Option<String>) -> PyResult<()> {
+        let format = match format {
+            Some(name) => SaveFormat::from_name(&name).ok_or_else(|| {
+                pyo3::exceptions::PyValueError::new_err(format!("Unknown save format '{name}'"))
+            })?,
+            None => SaveFormat::Json,
+        };
         let world = self.inner.borrow_mut();
         world
-            .save_to_file(std::path::Path::new(&path))
+            .save_to_file_as(std::path::Path::new(&path), format)
             .map_err(|e| pyo3::exceptions::PyIOError::new_err(e.to_string()))
     }
 
@@ -29,4 +41,21 @@ impl SaveLoadApi for PyWorld {
         *world = loaded;
         Ok(())
     }
+
+    fn snapshot<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        let world = self.inner.borrow();
+        world
+            .snapshot()
+            .map(|snapshot| PyBytes::new(py, snapshot.as_bytes()))
+            .map_err(|e| pyo3::exceptions::PyIOError::new_err(e.to_string()))
+    }
+
+    fn restore(&self, data: &[u8]) -> PyResult<()> {
+        let snapshot = WorldSnapshot::from_bytes(data.to_vec())
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        self.inner
+            .borrow_mut()
+            .restore(&snapshot)
+            .map_err(|e| pyo3::exceptions::PyIOError::new_err(e.to_string()))
+    }
 }
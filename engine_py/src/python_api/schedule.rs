@@ -0,0 +1,79 @@
+use super::PyWorld;
+use engine_core::World;
+use engine_core::ecs::schedule::{RunCondition, SystemStage};
+use pyo3::prelude::*;
+use pyo3::types::PyAny;
+use serde_pyobject::from_pyobject;
+use std::rc::Rc;
+
+/// Parse a stage name ("pre_update", "update", "post_update", "render").
+pub fn parse_stage(name: &str) -> PyResult<SystemStage> {
+    SystemStage::from_name(name).ok_or_else(|| {
+        pyo3::exceptions::PyValueError::new_err(format!("Unknown system stage '{name}'"))
+    })
+}
+
+/// Parse a run condition dict, e.g. `{"type": "every_n_ticks", "n": 10}`.
+pub fn parse_run_condition(condition: &Bound<'_, PyAny>) -> PyResult<RunCondition> {
+    let value: serde_json::Value = from_pyobject(condition.clone())?;
+    serde_json::from_value(value)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Invalid run condition: {e}")))
+}
+
+/// System stages, run conditions and fixed-timestep ticking
+pub trait ScheduleApi {
+    /// Run a system in another stage
+    fn set_system_stage(&self, name: &str, stage: &str) -> PyResult<()>;
+    /// Get the stage a system runs in
+    fn get_system_stage(&self, name: &str) -> String;
+    /// Add a condition a system needs to run during ticks
+    fn add_run_condition(&self, name: &str, condition: &Bound<'_, PyAny>) -> PyResult<()>;
+    /// Remove all run conditions of a system
+    fn clear_run_conditions(&self, name: &str);
+    /// Set the length of a fixed tick in seconds
+    fn set_fixed_timestep(&self, step: f32);
+    /// Get the length of a fixed tick in seconds
+    fn get_fixed_timestep(&self) -> f32;
+    /// Advance by real elapsed seconds; returns the number of ticks run
+    fn advance(&self, elapsed: f32) -> u32;
+}
+
+impl ScheduleApi for PyWorld {
+    fn set_system_stage(&self, name: &str, stage: &str) -> PyResult<()> {
+        let stage = parse_stage(stage)?;
+        self.inner.borrow_mut().set_system_stage(name, stage);
+        Ok(())
+    }
+
+    fn get_system_stage(&self, name: &str) -> String {
+        self.inner.borrow().system_stage(name).name().to_string()
+    }
+
+    fn add_run_condition(&self, name: &str, condition: &Bound<'_, PyAny>) -> PyResult<()> {
+        let condition = parse_run_condition(condition)?;
+        self.inner.borrow_mut().add_run_condition(name, condition);
+        Ok(())
+    }
+
+    fn clear_run_conditions(&self, name: &str) {
+        self.inner.borrow_mut().clear_run_conditions(name);
+    }
+
+    fn set_fixed_timestep(&self, step: f32) {
+        self.inner.borrow_mut().set_fixed_timestep(step);
+    }
+
+    fn get_fixed_timestep(&self) -> f32 {
+        self.inner.borrow().fixed_timestep()
+    }
+
+    fn advance(&self, elapsed: f32) -> u32 {
+        let ticks = World::advance(Rc::clone(&self.inner), elapsed);
+        // Deliver job event bus callbacks after the ticks, as tick() does
+        Python::attach(|py| {
+            let mut world = self.inner.borrow_mut();
+            crate::python_api::job_events::deliver_job_event_bus_callbacks(py, &mut world).unwrap();
+        });
+        ticks
+    }
+}
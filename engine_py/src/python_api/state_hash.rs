@@ -0,0 +1,37 @@
+use super::PyWorld;
+use crate::PyObject;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+
+/// World state hashing
+pub trait StateHashApi {
+    /// Stable hash of the simulated state
+    fn state_hash(&self) -> u64;
+    /// Enable or disable per-system hash logging during ticks
+    fn set_system_hash_debug(&self, enabled: bool);
+    /// Take the logged per-system hashes as dicts (turn, system, hash)
+    fn take_system_hashes(&self, py: Python) -> PyResult<PyObject>;
+}
+
+impl StateHashApi for PyWorld {
+    fn state_hash(&self) -> u64 {
+        self.inner.borrow().state_hash()
+    }
+
+    fn set_system_hash_debug(&self, enabled: bool) {
+        self.inner.borrow_mut().set_system_hash_debug(enabled);
+    }
+
+    fn take_system_hashes(&self, py: Python) -> PyResult<PyObject> {
+        let entries = self.inner.borrow_mut().take_system_hashes();
+        let py_entries = PyList::empty(py);
+        for entry in entries {
+            let dict = PyDict::new(py);
+            dict.set_item("turn", entry.turn)?;
+            dict.set_item("system", entry.system)?;
+            dict.set_item("hash", entry.hash)?;
+            py_entries.append(dict)?;
+        }
+        Ok(py_entries.into())
+    }
+}
@@ -31,12 +31,13 @@ pub trait TechTreeApi {
 
 impl TechTreeApi for PyWorld {
     fn get_tech_tree(&self) -> JsonValue {
-        let nodes = tech_tree::get_tech_tree();
-        serde_json::to_value(nodes).unwrap_or_default()
+        let world = self.inner.borrow();
+        serde_json::to_value(tech_tree::get_tech_tree(&world)).unwrap_or_default()
     }
 
     fn get_tech_node(&self, tech_id: &str) -> JsonValue {
-        tech_tree::get_tech_node(tech_id)
+        let world = self.inner.borrow();
+        tech_tree::get_tech_node(&world, tech_id)
             .and_then(|node| serde_json::to_value(node).ok())
             .unwrap_or(JsonValue::Null)
     }
@@ -86,7 +87,7 @@ impl TechTreeApi for PyWorld {
 
     fn can_research_tech(&self, entity: u32, tech_id: &str) -> (bool, String) {
         let world = self.inner.borrow();
-        match tech_tree::can_research_tech(&world, entity, tech_id) {
+        match tech_tree::can_research_tech(&*world, entity, tech_id) {
             Ok(true) => (true, String::new()),
             Ok(false) => (false, "Unknown reason".to_string()),
             Err(reason) => (false, reason),
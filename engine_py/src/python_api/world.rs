@@ -7,13 +7,19 @@ use crate::python_api::entity::EntityApi;
 use crate::python_api::equipment::EquipmentApi;
 use crate::python_api::faction::FactionApi;
 use crate::python_api::fov::FovApi;
+use crate::python_api::hierarchy::HierarchyApi;
 use crate::python_api::inventory::InventoryApi;
 use crate::python_api::job_query::JobQueryApi;
 use crate::python_api::material::MaterialApi;
 use crate::python_api::mode::ModeApi;
 use crate::python_api::movement::MovementApi;
+use crate::python_api::query::{PyQuery, QueryApi};
 use crate::python_api::region::RegionApi;
+use crate::python_api::replay::ReplayApi;
+use crate::python_api::rng::RngApi;
 use crate::python_api::save_load::SaveLoadApi;
+use crate::python_api::schedule::ScheduleApi;
+use crate::python_api::state_hash::StateHashApi;
 use crate::python_api::time_of_day::TimeOfDayApi;
 use crate::python_api::turn::TurnApi;
 use crate::system_bridge::SystemBridge;
@@ -26,7 +32,8 @@ use engine_core::systems::fog::FogUpdateSystem;
 use engine_core::systems::fov::FovUpdateSystem;
 use engine_core::systems::job::job_board::JobBoard;
 use engine_core::systems::job::types::loader::load_job_types_from_dir;
-use engine_core::tech_tree;
+use engine_core::systems::research;
+use engine_core::{tech_effects, tech_tree};
 use pyo3::Python;
 use pyo3::prelude::*;
 use pyo3::types::{PyAny, PyAnyMethods, PyDict};
@@ -70,12 +77,14 @@ impl Clone for PyWorld {
 
 #[pymethods]
 impl PyWorld {
-    /// Create a new world, optionally loading schemas from a custom directory.
+    /// Create a new world, optionally loading schemas from a custom directory
+    /// and the game config (allowed modes, RNG seed) from a custom file.
     #[new]
-    #[pyo3(signature = (schema_dir=None))]
-    fn new(schema_dir: Option<String>) -> PyResult<Self> {
+    #[pyo3(signature = (schema_dir=None, config_file=None))]
+    fn new(schema_dir: Option<String>, config_file: Option<String>) -> PyResult<Self> {
+        use engine_core::config::GameConfig;
         use engine_core::ecs::registry::ComponentRegistry;
-        use engine_core::ecs::schema::{load_allowed_modes, load_schemas_from_dir_with_modes};
+        use engine_core::ecs::schema::load_schemas_from_dir_with_modes;
         use std::path::PathBuf;
 
         let schema_path = match schema_dir {
@@ -83,11 +92,15 @@ impl PyWorld {
             None => PathBuf::from("engine/assets/schemas"),
         };
 
-        let allowed_modes = load_allowed_modes().map_err(|e| {
-            pyo3::exceptions::PyValueError::new_err(format!("Failed to load allowed modes: {e}"))
+        let config = match config_file {
+            Some(path) => GameConfig::load_from_file(path),
+            None => GameConfig::load_default(),
+        }
+        .map_err(|e| {
+            pyo3::exceptions::PyValueError::new_err(format!("Failed to load game config: {e}"))
         })?;
-        let schemas =
-            load_schemas_from_dir_with_modes(&schema_path, &allowed_modes).map_err(|e| {
+        let schemas = load_schemas_from_dir_with_modes(&schema_path, &config.allowed_modes)
+            .map_err(|e| {
                 pyo3::exceptions::PyValueError::new_err(format!(
                     "Failed to load schemas from {schema_path:?}: {e}"
                 ))
@@ -98,7 +111,10 @@ impl PyWorld {
             registry.register_external_schema(schema);
         }
 
-        let mut world = World::new(std::sync::Arc::new(std::sync::Mutex::new(registry)));
+        let mut world = World::with_config(
+            std::sync::Arc::new(std::sync::Mutex::new(registry)),
+            &config,
+        );
 
         // Load material definitions
         let materials_dir = schema_path.parent().unwrap().join("materials");
@@ -106,6 +122,15 @@ impl PyWorld {
             world.material_definitions = mats;
         }
 
+        // Load the tech tree
+        let tech_dir = schema_path.parent().unwrap().join("tech");
+        if tech_dir.is_dir() {
+            world
+                .tech_tree
+                .load(&tech_dir)
+                .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        }
+
         // Load and register job types from assets
         let jobs_dir = schema_path.parent().unwrap().join("jobs");
         let job_types = load_job_types_from_dir(jobs_dir);
@@ -179,6 +204,12 @@ impl PyWorld {
         EntityApi::is_entity_alive(self, entity_id)
     }
 
+    /// Check if an entity ID refers to a despawned entity, or to an older
+    /// generation of a reused entity slot.
+    fn is_stale_entity(&self, entity_id: u32) -> bool {
+        EntityApi::is_stale_entity(self, entity_id)
+    }
+
     /// Move an entity by delta x and y.
     fn move_entity(&self, entity_id: u32, dx: f32, dy: f32) {
         EntityApi::move_entity(self, entity_id, dx, dy)
@@ -194,6 +225,40 @@ impl PyWorld {
         EntityApi::damage_entity_part(self, entity_id, part_name, amount)
     }
 
+    // ---- HIERARCHY ----
+
+    /// Set the parent of an entity. policy is "cascade" (default: despawned with
+    /// the parent) or "reparent" (moved to the grandparent on parent despawn).
+    #[pyo3(signature = (child, parent, policy=None))]
+    fn set_parent(&self, child: u32, parent: u32, policy: Option<String>) -> PyResult<()> {
+        HierarchyApi::set_parent(self, child, parent, policy)
+    }
+
+    /// Detach an entity from its parent. Returns the former parent or None.
+    fn remove_parent(&self, child: u32) -> Option<u32> {
+        HierarchyApi::remove_parent(self, child)
+    }
+
+    /// Parent of an entity, or None.
+    fn parent_of(&self, entity: u32) -> Option<u32> {
+        HierarchyApi::parent_of(self, entity)
+    }
+
+    /// Direct children of an entity, in ascending ID order.
+    fn children_of(&self, entity: u32) -> Vec<u32> {
+        HierarchyApi::children_of(self, entity)
+    }
+
+    /// Ancestors of an entity, nearest first.
+    fn ancestors(&self, entity: u32) -> Vec<u32> {
+        HierarchyApi::ancestors(self, entity)
+    }
+
+    /// All entities below an entity, depth first.
+    fn descendants(&self, entity: u32) -> Vec<u32> {
+        HierarchyApi::descendants(self, entity)
+    }
+
     // ---- COMPONENT ----
 
     /// Set component
@@ -236,6 +301,18 @@ impl PyWorld {
         ComponentApi::get_component_schema(self, name)
     }
 
+    // ---- QUERY ----
+
+    /// Start a query: world.query().with_("Health").without("Corpse").entities()
+    fn query(&self) -> PyQuery {
+        QueryApi::query(self)
+    }
+
+    /// Current change tick (advances with every component write).
+    fn change_tick(&self) -> u64 {
+        QueryApi::change_tick(self)
+    }
+
     // ---- MATERIAL ----
 
     /// Get material properties by name. Returns a dict or None.
@@ -466,9 +543,10 @@ impl PyWorld {
         FovApi::get_visibility_state(self, entity_id, x, y, z)
     }
 
-    /// Save
-    fn save_to_file(&self, path: String) -> PyResult<()> {
-        SaveLoadApi::save_to_file(self, path)
+    /// Save to a file as "json" (default), "binary" or "compressed"
+    #[pyo3(signature = (path, format=None))]
+    fn save_to_file(&self, path: String, format: Option<String>) -> PyResult<()> {
+        SaveLoadApi::save_to_file(self, path, format)
     }
 
     /// Load
@@ -476,11 +554,89 @@ impl PyWorld {
         SaveLoadApi::load_from_file(self, path)
     }
 
+    /// Take an in-memory snapshot of the world state (opaque bytes)
+    fn snapshot<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyBytes>> {
+        SaveLoadApi::snapshot(self, py)
+    }
+
+    /// Restore the world state from a snapshot, keeping registered systems
+    fn restore(&self, data: &[u8]) -> PyResult<()> {
+        SaveLoadApi::restore(self, data)
+    }
+
     /// Get the time of day
     fn get_time_of_day(&self, py: Python) -> PyObject {
         TimeOfDayApi::get_time_of_day(self, py)
     }
 
+    // ---- RNG ----
+
+    /// Reseed the world RNG, restarting every stream
+    fn seed_rng(&self, seed: u64) {
+        RngApi::seed_rng(self, seed)
+    }
+
+    /// Get the master seed of the world RNG
+    fn get_rng_seed(&self) -> u64 {
+        RngApi::get_rng_seed(self)
+    }
+
+    /// Draw a float in [0, 1) from a named stream ("loot", "jobs", "ai", "worldgen", ...)
+    fn rng_random(&self, stream: &str) -> f64 {
+        RngApi::rng_random(self, stream)
+    }
+
+    /// Draw an integer in [min, max] from a named stream
+    fn rng_range(&self, stream: &str, min: i64, max: i64) -> i64 {
+        RngApi::rng_range(self, stream, min, max)
+    }
+
+    // ---- Replay ----
+
+    /// Start recording a session (snapshot, seed and injected commands per tick)
+    fn start_recording(&self) -> PyResult<()> {
+        ReplayApi::start_recording(self)
+    }
+
+    /// Returns true while a session is being recorded
+    fn is_recording(&self) -> bool {
+        ReplayApi::is_recording(self)
+    }
+
+    /// Stop recording and save it as "json" (default), "binary" or "compressed"
+    #[pyo3(signature = (path, format=None))]
+    fn stop_recording(&self, path: String, format: Option<String>) -> PyResult<usize> {
+        ReplayApi::stop_recording(self, path, format)
+    }
+
+    /// Replay a recording headless; raises RuntimeError on the first desync
+    #[pyo3(signature = (path, ticks=None))]
+    fn replay_recording(&self, path: String, ticks: Option<usize>) -> PyResult<usize> {
+        ReplayApi::replay_recording(self, path, ticks)
+    }
+
+    /// Send an input event to the "input" event bus (a single character, or "quit")
+    fn push_input(&self, key: String) -> PyResult<()> {
+        ReplayApi::push_input(self, key)
+    }
+
+    // ---- State hash ----
+
+    /// Stable hash of the simulated state (entities, components, turn, time, RNG, map)
+    fn state_hash(&self) -> u64 {
+        StateHashApi::state_hash(self)
+    }
+
+    /// Enable or disable logging a state hash after every system during ticks
+    fn set_system_hash_debug(&self, enabled: bool) {
+        StateHashApi::set_system_hash_debug(self, enabled)
+    }
+
+    /// Take the per-system hash log as a list of dicts (turn, system, hash)
+    fn take_system_hashes(&self, py: Python) -> PyResult<PyObject> {
+        StateHashApi::take_system_hashes(self, py)
+    }
+
     /// Add a cell to the map
     fn add_cell(&self, x: i32, y: i32, z: i32) {
         crate::python_api::map_api::add_cell(self, x, y, z)
@@ -489,7 +645,8 @@ impl PyWorld {
     // ---- SYSTEM REGISTRATION/BRIDGE ----
 
     /// Register a system with optional dependency configuration.
-    /// `opts` is an optional dict with optional key "dependencies" (list of system name strings).
+    /// `opts` is an optional dict with optional keys "dependencies" (list of system name strings),
+    /// "stage" (stage name) and "run_if" (list of run condition dicts).
     #[pyo3(signature = (name, callback, opts=None))]
     fn register_system(
         &self,
@@ -515,6 +672,44 @@ impl PyWorld {
             .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
     }
 
+    // ---- SCHEDULE ----
+
+    /// Run a system in another stage ("pre_update", "update", "post_update", "render")
+    fn set_system_stage(&self, name: String, stage: String) -> PyResult<()> {
+        ScheduleApi::set_system_stage(self, &name, &stage)
+    }
+
+    /// Get the stage a system runs in
+    fn get_system_stage(&self, name: String) -> String {
+        ScheduleApi::get_system_stage(self, &name)
+    }
+
+    /// Add a run condition: {"type": "in_mode", "mode": ...},
+    /// {"type": "every_n_ticks", "n": ..., "offset": ...} or {"type": "events_pending", "bus": ...}
+    fn add_run_condition(&self, name: String, condition: &Bound<'_, PyAny>) -> PyResult<()> {
+        ScheduleApi::add_run_condition(self, &name, condition)
+    }
+
+    /// Remove all run conditions of a system
+    fn clear_run_conditions(&self, name: String) {
+        ScheduleApi::clear_run_conditions(self, &name)
+    }
+
+    /// Set the length of a fixed tick in seconds (the dt systems receive)
+    fn set_fixed_timestep(&self, step: f32) {
+        ScheduleApi::set_fixed_timestep(self, step)
+    }
+
+    /// Get the length of a fixed tick in seconds
+    fn get_fixed_timestep(&self) -> f32 {
+        ScheduleApi::get_fixed_timestep(self)
+    }
+
+    /// Advance by real elapsed seconds, running whole fixed ticks; returns the tick count
+    fn advance(&self, elapsed: f32) -> u32 {
+        ScheduleApi::advance(self, elapsed)
+    }
+
     // ---- EVENT BUS ----
 
     /// Send event
@@ -898,11 +1093,37 @@ impl PyWorld {
         crate::python_api::map_api::add_neighbor(self, from, to)
     }
 
+    /// Link stairs, ramps and ladders to the level above; returns the links added.
+    fn connect_levels(&self) -> usize {
+        crate::python_api::map_api::connect_levels(self)
+    }
+
     /// Get a list of entity IDs located in the given cell.
     fn entities_in_cell(&self, py: Python, cell: &Bound<'_, PyAny>) -> PyObject {
         crate::python_api::map_api::entities_in_cell(self, py, cell)
     }
 
+    /// Get a list of entity IDs within `radius` of the given cell.
+    fn entities_in_radius(&self, py: Python, cell: &Bound<'_, PyAny>, radius: f64) -> PyObject {
+        crate::python_api::map_api::entities_in_radius(self, py, cell, radius)
+    }
+
+    /// Get a list of entity IDs in the box spanned by two corner cells.
+    fn entities_in_rect(&self, py: Python, a: &Bound<'_, PyAny>, b: &Bound<'_, PyAny>) -> PyObject {
+        crate::python_api::map_api::entities_in_rect(self, py, a, b)
+    }
+
+    /// Get the entity with the given component nearest to a cell.
+    #[pyo3(signature = (cell, component, max_radius=None))]
+    fn nearest_entity_with_component(
+        &self,
+        cell: &Bound<'_, PyAny>,
+        component: &str,
+        max_radius: Option<f64>,
+    ) -> Option<u32> {
+        crate::python_api::map_api::nearest_entity_with_component(self, cell, component, max_radius)
+    }
+
     /// Get metadata associated with a given cell.
     fn get_cell_metadata(&self, py: Python, cell: &Bound<'_, PyAny>) -> PyObject {
         crate::python_api::map_api::get_cell_metadata(self, py, cell)
@@ -918,8 +1139,90 @@ impl PyWorld {
     }
 
     /// Find a path between two cells using the map's pathfinding system.
-    fn find_path(&self, py: Python, start: &Bound<'_, PyAny>, goal: &Bound<'_, PyAny>) -> PyObject {
-        crate::python_api::map_api::find_path(self, py, start, goal)
+    #[pyo3(signature = (start, goal, profile=None))]
+    fn find_path(
+        &self,
+        py: Python,
+        start: &Bound<'_, PyAny>,
+        goal: &Bound<'_, PyAny>,
+        profile: Option<&str>,
+    ) -> PyObject {
+        crate::python_api::map_api::find_path(self, py, start, goal, profile)
+    }
+
+    /// Find a path between two cells using an entity's movement profile.
+    fn find_path_for(
+        &self,
+        py: Python,
+        entity: u32,
+        start: &Bound<'_, PyAny>,
+        goal: &Bound<'_, PyAny>,
+    ) -> PyObject {
+        crate::python_api::map_api::find_path_for(self, py, entity, start, goal)
+    }
+
+    /// Dijkstra map towards the goal cells: a list of `{"cell", "distance"}` dicts.
+    #[pyo3(signature = (goals, profile=None))]
+    fn dijkstra_map(
+        &self,
+        py: Python,
+        goals: &Bound<'_, PyAny>,
+        profile: Option<&str>,
+    ) -> PyObject {
+        crate::python_api::map_api::dijkstra_map(self, py, goals, profile)
+    }
+
+    /// Map leading away from the threat cells, in the format of `dijkstra_map`.
+    #[pyo3(signature = (threats, profile=None, factor=None))]
+    fn flee_map(
+        &self,
+        py: Python,
+        threats: &Bound<'_, PyAny>,
+        profile: Option<&str>,
+        factor: Option<f32>,
+    ) -> PyObject {
+        crate::python_api::map_api::flee_map(self, py, threats, profile, factor)
+    }
+
+    /// Next cell from `cell` towards the nearest goal, or None.
+    #[pyo3(signature = (cell, goals, profile=None))]
+    fn flow_step(
+        &self,
+        py: Python,
+        cell: &Bound<'_, PyAny>,
+        goals: &Bound<'_, PyAny>,
+        profile: Option<&str>,
+    ) -> PyObject {
+        crate::python_api::map_api::flow_step(self, py, cell, goals, profile)
+    }
+
+    /// Next cell from `cell` away from the threats, or None.
+    #[pyo3(signature = (cell, threats, profile=None, factor=None))]
+    fn flee_step(
+        &self,
+        py: Python,
+        cell: &Bound<'_, PyAny>,
+        threats: &Bound<'_, PyAny>,
+        profile: Option<&str>,
+        factor: Option<f32>,
+    ) -> PyObject {
+        crate::python_api::map_api::flee_step(self, py, cell, threats, profile, factor)
+    }
+
+    /// Register (or replace) a movement profile.
+    fn register_movement_profile(&self, profile: &Bound<'_, PyAny>) -> PyResult<()> {
+        crate::python_api::map_api::register_movement_profile(self, profile)
+    }
+
+    /// Select the pathfinding strategy: "astar" or "hierarchical".
+    #[pyo3(signature = (name, cluster_size=None))]
+    fn set_pathfinding_strategy(&self, name: &str, cluster_size: Option<u32>) -> PyResult<()> {
+        crate::python_api::map_api::set_pathfinding_strategy(self, name, cluster_size)
+    }
+
+    /// Name of the active pathfinding strategy.
+    fn get_pathfinding_strategy(&self) -> &'static str {
+        crate::python_api::map_api::get_pathfinding_strategy(self)
     }
 
     /// Register a Python callback as a map validator.
@@ -1000,10 +1303,10 @@ impl PyWorld {
     /// Returns a list of dicts, each with `item_id` (str) and `count` (int).
     /// Raises `ValueError` if the table is not found or has no entries.
     fn roll_loot_table(&self, py: Python<'_>, name: &str) -> PyResult<Vec<PyObject>> {
-        let world = self.inner.borrow();
-        let results = world
-            .loot_tables
-            .roll(name)
+        let results = self
+            .inner
+            .borrow_mut()
+            .roll_loot(name)
             .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
 
         results
@@ -1042,17 +1345,36 @@ impl PyWorld {
 
     /// Returns all tech tree nodes as a list of dicts.
     fn get_tech_tree(&self, py: Python<'_>) -> PyResult<PyObject> {
-        let nodes = tech_tree::get_tech_tree();
-        let value = serde_json::to_value(nodes)
+        let world = self.inner.borrow();
+        let value = serde_json::to_value(tech_tree::get_tech_tree(&world))
             .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
         to_pyobject(py, &value)
             .map(|b| b.into())
             .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
     }
 
+    /// Merges tech nodes from a JSON file or directory into the tech tree,
+    /// replacing nodes with the same ID.
+    fn load_tech_tree(&self, path: &str) -> PyResult<()> {
+        self.inner
+            .borrow_mut()
+            .tech_tree
+            .load(path)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    /// Returns the tech tree's unknown prerequisites and cycles as messages.
+    fn validate_tech_tree(&self) -> Vec<String> {
+        match self.inner.borrow().tech_tree.validate() {
+            Ok(()) => Vec::new(),
+            Err(errors) => errors.iter().map(|e| e.to_string()).collect(),
+        }
+    }
+
     /// Returns a specific tech node by ID, or None.
     fn get_tech_node(&self, py: Python<'_>, tech_id: &str) -> PyResult<PyObject> {
-        match tech_tree::get_tech_node(tech_id) {
+        let world = self.inner.borrow();
+        match tech_tree::get_tech_node(&world, tech_id) {
             Some(node) => {
                 let value = serde_json::to_value(node)
                     .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
@@ -1126,10 +1448,55 @@ impl PyWorld {
     /// Checks if an entity can research a tech, returns (can_research, reason).
     fn can_research_tech(&self, entity: u32, tech_id: &str) -> (bool, String) {
         let world = self.inner.borrow();
-        match tech_tree::can_research_tech(&world, entity, tech_id) {
+        match tech_tree::can_research_tech(&*world, entity, tech_id) {
             Ok(true) => (true, String::new()),
             Ok(false) => (false, "Unknown reason".to_string()),
             Err(reason) => (false, reason),
         }
     }
+
+    /// Completes a tech for an entity without research and applies its effects.
+    fn grant_tech(&self, entity: u32, tech_id: &str) -> PyResult<()> {
+        let mut world = self.inner.borrow_mut();
+        tech_effects::grant_tech(&mut world, entity, tech_id)
+            .map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    /// Un-completes a tech for an entity and reverts its effects.
+    fn revoke_tech(&self, entity: u32, tech_id: &str) -> PyResult<()> {
+        let mut world = self.inner.borrow_mut();
+        tech_effects::revoke_tech(&mut world, entity, tech_id)
+            .map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    /// Returns the tech effects that apply to an entity as a list of dicts.
+    fn get_tech_effects(&self, py: Python<'_>, entity: u32) -> PyResult<PyObject> {
+        let world = self.inner.borrow();
+        let value = serde_json::to_value(tech_effects::get_tech_effects(&world, entity))
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        to_pyobject(py, &value)
+            .map(|b| b.into())
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    /// Checks if an entity may produce a recipe.
+    fn is_recipe_unlocked(&self, entity: u32, recipe: &str) -> bool {
+        tech_effects::is_recipe_unlocked(&self.inner.borrow(), entity, recipe)
+    }
+
+    /// Checks if an entity may be assigned jobs of a type.
+    fn is_job_type_unlocked(&self, entity: u32, job_type: &str) -> bool {
+        tech_effects::is_job_type_unlocked(&self.inner.borrow(), entity, job_type)
+    }
+
+    /// Returns the shared research points of a faction.
+    fn get_research_pool(&self, faction_id: &str) -> f64 {
+        self.inner.borrow().research_pools.get(faction_id)
+    }
+
+    /// Credits research points to an entity's faction pool, or to its own
+    /// TechProgress if it has no faction. Returns False if it has neither.
+    fn add_research_points(&self, entity: u32, points: f64) -> bool {
+        research::credit_research(&mut self.inner.borrow_mut(), entity, points)
+    }
 }
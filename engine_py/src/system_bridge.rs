@@ -1,3 +1,4 @@
+use crate::python_api::schedule::{parse_run_condition, parse_stage};
 use engine_core::ecs::world::World;
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
@@ -37,15 +38,33 @@ impl SystemBridge {
             }
         }
 
+        // Extract stage and run conditions from opts if provided
+        let mut stage = None;
+        let mut conditions = Vec::new();
+        if let Some(opts_dict) = opts {
+            if let Some(stage_item) = opts_dict.get_item("stage")? {
+                stage = Some(parse_stage(&stage_item.extract::<String>()?)?);
+            }
+            if let Some(run_if) = opts_dict.get_item("run_if")? {
+                for condition in run_if.cast::<PyList>()?.iter() {
+                    conditions.push(parse_run_condition(&condition)?);
+                }
+            }
+        }
+
         // Register with engine core for dependency-ordered execution
         let cb = callback.clone_ref(py);
-        world.borrow_mut().register_dynamic_system_with_deps(
-            &name,
-            dependencies,
-            move |_world_rc, _dt| {
-                let _ = Python::try_attach(|py| cb.call1(py, (0.0,)));
-            },
-        );
+        let mut world = world.borrow_mut();
+        if let Some(stage) = stage {
+            world.set_system_stage(&name, stage);
+        }
+        world.clear_run_conditions(&name);
+        for condition in conditions {
+            world.add_run_condition(&name, condition);
+        }
+        world.register_dynamic_system_with_deps(&name, dependencies, move |_world_rc, dt| {
+            let _ = Python::try_attach(|py| cb.call1(py, (dt,)));
+        });
 
         Ok(())
     }
@@ -9,3 +9,27 @@ def test_entities_in_cell(make_world):
     entities = world.entities_in_cell(cell)
     assert len(entities) == 1
     assert entities[0] == eid
+
+
+def test_spatial_queries(make_world):
+    world = make_world()
+
+    def spawn_at(x, y):
+        eid = world.spawn_entity()
+        world.set_component(
+            eid, "Position", {"pos": {"Square": {"x": x, "y": y, "z": 0}}}
+        )
+        return eid
+
+    origin = spawn_at(0, 0)
+    near = spawn_at(1, 1)
+    far = spawn_at(4, 0)
+    world.set_component(far, "Health", {"current": 5, "max": 10})
+
+    center = {"Square": {"x": 0, "y": 0, "z": 0}}
+    assert world.entities_in_radius(center, 1.5) == [origin, near]
+    assert world.entities_in_rect(
+        {"Square": {"x": 1, "y": 0, "z": 0}}, {"Square": {"x": 4, "y": 1, "z": 0}}
+    ) == [near, far]
+    assert world.nearest_entity_with_component(center, "Health") == far
+    assert world.nearest_entity_with_component(center, "Health", 3.0) is None
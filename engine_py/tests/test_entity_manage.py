@@ -1,3 +1,6 @@
+import pytest
+
+
 def test_despawn_and_remove_component(make_world):
     world = make_world()
     eid = world.spawn_entity()
@@ -15,3 +18,17 @@ def test_is_entity_alive(make_world):
     assert world.is_entity_alive(eid)
     world.set_component(eid, "Health", {"current": 0, "max": 10})
     assert not world.is_entity_alive(eid)
+
+
+def test_stale_entity_handles(make_world):
+    world = make_world()
+    old = world.spawn_entity()
+    world.set_component(old, "Health", {"current": 5, "max": 10})
+    world.despawn_entity(old)
+    new = world.spawn_entity()
+    assert old != new
+    assert world.is_stale_entity(old)
+    assert not world.is_stale_entity(new)
+    assert world.get_component(old, "Health") is None
+    with pytest.raises(ValueError, match="stale"):
+        world.set_component(old, "Health", {"current": 5, "max": 10})
@@ -0,0 +1,35 @@
+import pytest
+
+
+def test_parent_links(make_world):
+    world = make_world()
+    root = world.spawn_entity()
+    mid = world.spawn_entity()
+    leaf = world.spawn_entity()
+    world.set_parent(mid, root)
+    world.set_parent(leaf, mid)
+
+    assert world.parent_of(leaf) == mid
+    assert world.parent_of(root) is None
+    assert world.children_of(root) == [mid]
+    assert world.ancestors(leaf) == [mid, root]
+    assert world.descendants(root) == [mid, leaf]
+
+    with pytest.raises(ValueError, match="cycle"):
+        world.set_parent(root, leaf)
+    assert world.remove_parent(leaf) == mid
+
+
+def test_despawn_policies(make_world):
+    world = make_world()
+    holder = world.spawn_entity()
+    bag = world.spawn_entity()
+    strap = world.spawn_entity()
+    item = world.spawn_entity()
+    world.set_parent(bag, holder)
+    world.set_parent(strap, bag)
+    world.set_parent(item, bag, "reparent")
+
+    world.despawn_entity(bag)
+    assert world.is_stale_entity(strap)
+    assert world.parent_of(item) == holder
@@ -1,3 +1,6 @@
+import pytest
+
+
 def test_pathfinding(make_world):
     world = make_world()
     for x in range(3):
@@ -17,3 +20,93 @@ def test_pathfinding(make_world):
         if "Square" in cell:
             assert not (cell["Square"]["x"]==1 and cell["Square"]["y"]==1)
     assert len(path) == 5
+
+
+def test_movement_profiles(make_world):
+    world = make_world()
+    for x in range(3):
+        world.add_cell(x, 0, 0)
+    world.add_neighbor((0, 0, 0), (1, 0, 0))
+    world.add_neighbor((1, 0, 0), (2, 0, 0))
+    world.set_cell_metadata(
+        {"Square": {"x": 1, "y": 0, "z": 0}}, {"terrain": "water", "walkable": False}
+    )
+    start = {"Square": {"x": 0, "y": 0, "z": 0}}
+    goal = {"Square": {"x": 2, "y": 0, "z": 0}}
+
+    assert world.find_path(start, goal) is None
+    assert len(world.find_path(start, goal, profile="swimmer")["path"]) == 3
+
+    world.register_movement_profile(
+        {"name": "wader", "passable_terrain": ["water"], "terrain_costs": {"water": 4}}
+    )
+    assert world.find_path(start, goal, "wader")["total_cost"] == 5
+
+    fish = world.spawn_entity()
+    world.set_component(fish, "Agent", {"entity_id": fish, "movement_profile": "swimmer"})
+    assert world.find_path_for(fish, start, goal) is not None
+
+
+def test_hierarchical_strategy(make_world):
+    world = make_world()
+    for x in range(12):
+        for y in range(12):
+            world.add_cell(x, y, 0)
+            for dx, dy in [(1, 0), (0, 1), (-1, 0), (0, -1)]:
+                if 0 <= x + dx < 12 and 0 <= y + dy < 12:
+                    world.add_neighbor((x, y, 0), (x + dx, y + dy, 0))
+    for y in range(11):
+        world.set_cell_metadata({"Square": {"x": 5, "y": y, "z": 0}}, {"walkable": False})
+    start = {"Square": {"x": 0, "y": 0, "z": 0}}
+    goal = {"Square": {"x": 11, "y": 0, "z": 0}}
+    exact = world.find_path(start, goal)
+
+    world.set_pathfinding_strategy("hierarchical", cluster_size=4)
+    assert world.get_pathfinding_strategy() == "hierarchical"
+    result = world.find_path(start, goal)
+    assert result["path"][-1] == goal
+    assert result["total_cost"] >= exact["total_cost"]
+    with pytest.raises(ValueError):
+        world.set_pathfinding_strategy("teleport")
+
+
+def test_flow_fields(make_world):
+    world = make_world()
+    for x in range(6):
+        world.add_cell(x, 0, 0)
+        if x > 0:
+            world.add_neighbor((x - 1, 0, 0), (x, 0, 0))
+            world.add_neighbor((x, 0, 0), (x - 1, 0, 0))
+    goal = {"Square": {"x": 0, "y": 0, "z": 0}}
+    field = world.dijkstra_map([goal])
+    assert len(field) == 6
+    assert all(entry["distance"] == entry["cell"]["Square"]["x"] for entry in field)
+
+    step = world.flow_step({"Square": {"x": 3, "y": 0, "z": 0}}, [goal])
+    assert step == {"Square": {"x": 2, "y": 0, "z": 0}}
+    assert world.flow_step(goal, [goal]) is None
+    away = world.flee_step({"Square": {"x": 1, "y": 0, "z": 0}}, [goal], factor=2.0)
+    assert away == {"Square": {"x": 2, "y": 0, "z": 0}}
+    assert len(world.flee_map([goal], "walker")) == 6
+
+
+def test_z_levels(make_world):
+    world = make_world()
+    world.add_cell(0, 0, 0)
+    world.add_cell(1, 0, 0)
+    world.add_cell(0, 0, 1)
+    world.add_neighbor((0, 0, 0), (1, 0, 0))
+    world.add_neighbor((1, 0, 0), (0, 0, 0))
+    start = {"Square": {"x": 1, "y": 0, "z": 0}}
+    goal = {"Square": {"x": 0, "y": 0, "z": 1}}
+    assert world.find_path(start, goal) is None
+
+    world.set_cell_metadata({"Square": {"x": 0, "y": 0, "z": 0}}, {"vertical": "stairs"})
+    assert world.connect_levels() == 2
+    assert world.connect_levels() == 0
+    result = world.find_path(start, goal)
+    assert len(result["path"]) == 3
+    assert result["total_cost"] == 3
+
+    world.register_movement_profile({"name": "limper", "climb_costs": {"stairs": 5}})
+    assert world.find_path(start, goal, "limper")["total_cost"] == 7
@@ -0,0 +1,43 @@
+import pytest
+
+
+def spawn_with_health(world, current):
+    eid = world.spawn_entity()
+    world.set_component(eid, "Health", {"current": current, "max": 10})
+    return eid
+
+
+def test_query_filters(make_world):
+    world = make_world()
+    low = spawn_with_health(world, 2)
+    high = spawn_with_health(world, 9)
+    dead = spawn_with_health(world, 0)
+    world.set_component(dead, "Corpse", {})
+    world.set_component(high, "Type", {"kind": "goblin"})
+
+    assert world.query().with_("Health").without("Corpse").entities() == [low, high]
+    assert world.query().where("Health", "current", "<", 5).entities() == [low, dead]
+
+    rows = world.query().with_("Health").optional("Type").where("Type", "kind", "==", "goblin").rows()
+    assert rows == [
+        {
+            "entity": high,
+            "components": {"Health": {"current": 9, "max": 10}, "Type": {"kind": "goblin"}},
+        }
+    ]
+
+    with pytest.raises(ValueError, match="Unknown operator"):
+        world.query().where("Health", "current", "~", 1)
+
+
+def test_query_changed_since_tick(make_world):
+    world = make_world()
+    a = spawn_with_health(world, 5)
+    b = spawn_with_health(world, 5)
+    tick = world.change_tick()
+    world.set_component(a, "Health", {"current": 5, "max": 10})
+    world.set_component(b, "Health", {"current": 3, "max": 10})
+    c = spawn_with_health(world, 7)
+
+    assert world.query().changed("Health").since(tick).entities() == [b, c]
+    assert world.query().added("Health").since(tick).entities() == [c]
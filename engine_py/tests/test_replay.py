@@ -0,0 +1,30 @@
+import pytest
+
+
+def test_record_and_replay(make_world, tmp_path):
+    world = make_world()
+    e = world.spawn_entity()
+    world.set_component(e, "Health", {"current": 100, "max": 100})
+
+    world.start_recording()
+    assert world.is_recording()
+    world.tick()
+    world.set_component(e, "Health", {"current": 50, "max": 100})
+    world.push_input("h")
+    world.tick()
+    path = tmp_path / "session.bin"
+    assert world.stop_recording(str(path), "compressed") == 2
+    assert not world.is_recording()
+
+    world.set_component(e, "Health", {"current": 1, "max": 100})
+    assert world.replay_recording(str(path)) == 2
+    assert world.get_component(e, "Health")["current"] == 50
+
+    assert world.replay_recording(str(path), 1) == 1
+    assert world.get_component(e, "Health")["current"] == 100
+
+
+def test_push_input_rejects_words(make_world):
+    world = make_world()
+    with pytest.raises(ValueError):
+        world.push_input("jump")
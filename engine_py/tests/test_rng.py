@@ -0,0 +1,57 @@
+import os
+
+import mge as engine_py
+
+
+def test_same_seed_gives_same_sequence(make_world):
+    a = make_world()
+    b = make_world()
+    a.seed_rng(1234)
+    b.seed_rng(1234)
+    assert a.get_rng_seed() == 1234
+    assert [a.rng_random("ai") for _ in range(5)] == [b.rng_random("ai") for _ in range(5)]
+
+
+def test_streams_are_independent(make_world):
+    a = make_world()
+    b = make_world()
+    a.seed_rng(7)
+    b.seed_rng(7)
+    a.rng_random("worldgen")
+    assert a.rng_range("loot", 1, 100) == b.rng_range("loot", 1, 100)
+
+
+def test_range_is_inclusive(make_world):
+    world = make_world()
+    world.seed_rng(99)
+    values = {world.rng_range("ai", 1, 3) for _ in range(200)}
+    assert values == {1, 2, 3}
+
+
+def test_loot_rolls_are_reproducible(make_world):
+    rolls = []
+    for _ in range(2):
+        world = make_world()
+        world.seed_rng(42)
+        world.define_loot_table(
+            "chest",
+            [
+                {"item_id": "gold", "weight": 50, "min_count": 1, "max_count": 10},
+                {"item_id": "gem", "weight": 50},
+            ],
+        )
+        rolls.append([world.roll_loot_table("chest") for _ in range(10)])
+    assert rolls[0] == rolls[1]
+
+
+def test_config_seed_is_applied(tmp_path):
+    here = os.path.dirname(__file__)
+    schema_dir = os.path.abspath(os.path.join(here, "../../engine/assets/schemas"))
+    config = tmp_path / "game.toml"
+    config.write_text(
+        'title = "Seeded"\nversion = "0.1.0"\nallowed_modes = ["colony"]\nseed = 4242\n'
+    )
+    a = engine_py.PyWorld(schema_dir, str(config))
+    b = engine_py.PyWorld(schema_dir, str(config))
+    assert a.get_rng_seed() == 4242
+    assert [a.rng_random("ai") for _ in range(5)] == [b.rng_random("ai") for _ in range(5)]
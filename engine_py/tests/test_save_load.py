@@ -15,3 +15,31 @@ def test_save_and_load(make_world, tmp_path):
     assert len(entities) > 0
     h = world.get_component(entities[0], "Health")
     assert h["current"] == 99
+
+
+def test_compressed_save_and_load(make_world, tmp_path):
+    world = make_world()
+    e = world.spawn_entity()
+    world.set_component(e, "Health", {"current": 42, "max": 100})
+
+    save_file = tmp_path / "test_save.bin"
+    world.save_to_file(str(save_file), "compressed")
+    world.despawn_entity(e)
+
+    world.load_from_file(str(save_file))
+    assert world.get_component(e, "Health")["current"] == 42
+
+
+def test_snapshot_and_restore(make_world):
+    world = make_world()
+    e = world.spawn_entity()
+    world.set_component(e, "Health", {"current": 10, "max": 10})
+    snap = world.snapshot()
+    assert isinstance(snap, bytes)
+
+    world.set_component(e, "Health", {"current": 1, "max": 10})
+    extra = world.spawn_entity()
+
+    world.restore(snap)
+    assert world.get_component(e, "Health")["current"] == 10
+    assert extra not in world.get_entities()
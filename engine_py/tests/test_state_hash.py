@@ -0,0 +1,25 @@
+def test_state_hash_follows_state(make_world):
+    world = make_world()
+    e = world.spawn_entity()
+    world.set_component(e, "Health", {"current": 10, "max": 10})
+    before = world.state_hash()
+    assert world.state_hash() == before
+
+    world.set_component(e, "Health", {"current": 9, "max": 10})
+    assert world.state_hash() != before
+    world.set_component(e, "Health", {"max": 10, "current": 10})
+    assert world.state_hash() == before
+
+
+def test_system_hash_log(make_world):
+    world = make_world()
+    world.set_system_hash_debug(True)
+    world.tick()
+    log = world.take_system_hashes()
+    assert log[0]["system"] == "tick_start"
+    assert log[0]["turn"] == 0
+    assert world.take_system_hashes() == []
+
+    world.set_system_hash_debug(False)
+    world.tick()
+    assert world.take_system_hashes() == []
@@ -0,0 +1,60 @@
+import pytest
+
+
+def test_system_stages(make_world):
+    world = make_world()
+    order = []
+    world.register_system("late", lambda dt: order.append("late"), {"stage": "post_update"})
+    world.register_system("early", lambda dt: order.append("early"))
+    world.set_system_stage("early", "pre_update")
+
+    assert world.get_system_stage("early") == "pre_update"
+    assert world.get_system_stage("late") == "post_update"
+    world.tick()
+    assert order == ["early", "late"]
+
+    with pytest.raises(ValueError, match="Unknown system stage"):
+        world.set_system_stage("early", "sometime")
+
+
+def test_run_conditions(make_world):
+    world = make_world()
+    world.set_mode("colony")
+    runs = {"every_other": 0, "roguelike": 0}
+
+    def every_other(dt):
+        runs["every_other"] += 1
+
+    def roguelike(dt):
+        runs["roguelike"] += 1
+
+    world.register_system(
+        "every_other", every_other, {"run_if": [{"type": "every_n_ticks", "n": 2}]}
+    )
+    world.register_system("roguelike", roguelike)
+    world.add_run_condition("roguelike", {"type": "in_mode", "mode": "roguelike"})
+
+    for _ in range(4):
+        world.tick()
+    assert runs == {"every_other": 2, "roguelike": 0}
+
+    world.set_mode("roguelike")
+    world.clear_run_conditions("every_other")
+    world.tick()
+    assert runs == {"every_other": 3, "roguelike": 1}
+
+    with pytest.raises(ValueError, match="Invalid run condition"):
+        world.add_run_condition("roguelike", {"type": "sometimes"})
+
+
+def test_fixed_timestep_advance(make_world):
+    world = make_world()
+    world.set_fixed_timestep(0.25)
+    assert world.get_fixed_timestep() == 0.25
+    dts = []
+    world.register_system("clock", lambda dt: dts.append(dt))
+
+    turn = world.get_turn()
+    assert world.advance(0.6) == 2
+    assert world.get_turn() == turn + 2
+    assert dts == [0.25, 0.25]
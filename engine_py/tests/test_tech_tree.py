@@ -133,3 +133,30 @@ def test_cannot_research_with_prereq(make_world):
         assert False, "Should have raised an error"
     except Exception as e:
         assert "Requires tech" in str(e)
+
+
+def test_grant_and_revoke_tech(make_world):
+    """grant_tech() completes a tech; revoke_tech() un-completes it."""
+    world = make_world()
+    eid = world.spawn_entity()
+    world.grant_tech(eid, "bronze_working")
+    assert world.is_tech_completed(eid, "bronze_working")
+    assert world.get_tech_effects(eid) == []
+    world.revoke_tech(eid, "bronze_working")
+    assert not world.is_tech_completed(eid, "bronze_working")
+    try:
+        world.revoke_tech(eid, "bronze_working")
+        assert False, "Should have raised an error"
+    except Exception as e:
+        assert "not completed" in str(e)
+
+
+def test_add_research_points(make_world):
+    """add_research_points() credits the faction pool or the entity's TechProgress."""
+    world = make_world()
+    member = world.spawn_entity()
+    world.set_faction(member, "scholars", "member")
+    assert world.add_research_points(member, 4.0)
+    assert world.get_research_pool("scholars") == 4.0
+    loner = world.spawn_entity()
+    assert not world.add_research_points(loner, 4.0)
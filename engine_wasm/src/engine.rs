@@ -10,6 +10,7 @@ use crate::host_api::equipment::register_equipment_api;
 use crate::host_api::event_bus::register_event_bus_api;
 use crate::host_api::faction::register_faction_api;
 use crate::host_api::fov::register_fov_api;
+use crate::host_api::hierarchy::register_hierarchy_api;
 use crate::host_api::input::register_input_api;
 use crate::host_api::inventory::register_inventory_api;
 use crate::host_api::job_ai::register_job_ai_api;
@@ -24,8 +25,11 @@ use crate::host_api::map::register_map_api;
 use crate::host_api::material::register_material_api;
 use crate::host_api::mode::register_mode_api;
 use crate::host_api::movement_ops::register_movement_ops_api;
+use crate::host_api::query::register_query_api;
 use crate::host_api::region::register_region_api;
+use crate::host_api::rng::register_rng_api;
 use crate::host_api::save_load::register_save_load_api;
+use crate::host_api::state_hash::register_state_hash_api;
 use crate::host_api::system::register_system_api;
 use crate::host_api::tech_tree::register_tech_tree_api;
 use crate::host_api::time_of_day::register_time_of_day_api;
@@ -36,6 +40,7 @@ use crate::host_api::ui_tree::register_ui_tree_api;
 use crate::host_api::world_userdata::register_world_userdata_api;
 use crate::host_api::worldgen::register_worldgen_api;
 use anyhow::Result;
+use engine_core::config::GameConfig;
 use engine_core::ecs::assets::load_material_definitions;
 use engine_core::ecs::world::wasm::{InputSource, WasmWorld, load_schemas_from_dir};
 use engine_core::worldgen::ThreadSafeWorldgenRegistry;
@@ -125,6 +130,8 @@ pub struct WasmScriptEngineConfig {
     pub import_host_functions: Option<HostImportRegistrar>,
     /// Injectable input source for `get_user_input()`. Defaults to `Stdin`.
     pub input_source: Option<InputSource>,
+    /// Optional game config; its `seed` seeds the world RNG
+    pub game_config: Option<GameConfig>,
 }
 
 /// A WASM script engine
@@ -148,6 +155,8 @@ impl WasmScriptEngine {
 
         let mut linker = Linker::new(&engine);
         register_entity_api(&mut linker)?;
+        register_hierarchy_api(&mut linker)?;
+        register_query_api(&mut linker)?;
         register_component_api(&mut linker)?;
         register_turn_api(&mut linker)?;
         register_mode_api(&mut linker)?;
@@ -179,6 +188,8 @@ impl WasmScriptEngine {
         register_ui_tree_api(&mut linker)?;
         register_ui_events_api(&mut linker)?;
         register_loot_api(&mut linker)?;
+        register_rng_api(&mut linker)?;
+        register_state_hash_api(&mut linker)?;
         register_material_api(&mut linker)?;
         register_faction_api(&mut linker)?;
         register_fov_api(&mut linker)?;
@@ -201,14 +212,17 @@ impl WasmScriptEngine {
             imports(&mut linker);
         }
 
-        let mut world = WasmWorld::new();
+        let mut world = match &config.game_config {
+            Some(game_config) => WasmWorld::with_config(game_config),
+            None => WasmWorld::new(),
+        };
         world.component_schemas = schemas;
         if let Some(src) = config.input_source {
             world.input_source = src;
         }
 
-        // Auto-load material definitions from a "materials" sibling directory
-        // relative to the schema path (e.g., engine/assets/schemas → engine/assets/materials).
+        // Auto-load material definitions and the tech tree from "materials" and "tech"
+        // sibling directories of the schema path (e.g., engine/assets/schemas → engine/assets/materials).
         if let Some(ref schema_dir) = config.schema_path
             && let Some(parent) = schema_dir.parent()
         {
@@ -216,6 +230,12 @@ impl WasmScriptEngine {
             if let Ok(mats) = load_material_definitions(&materials_dir) {
                 world.material_definitions = mats;
             }
+            let tech_dir = parent.join("tech");
+            if tech_dir.is_dir()
+                && let Err(e) = world.tech_tree.load(&tech_dir)
+            {
+                eprintln!("Failed to load tech tree: {e}");
+            }
         }
 
         let world = Arc::new(Mutex::new(world));
@@ -5,6 +5,7 @@
 
 use crate::host_api::component::{read_wasm_string, write_string_to_wasm};
 use engine_core::ecs::world::wasm::WasmWorld;
+use engine_core::rng;
 use engine_core::systems::dungeon::{DungeonConfig, DungeonGenerator};
 use std::sync::{Arc, Mutex};
 use wasmtime::{Caller, Linker};
@@ -38,8 +39,12 @@ pub fn register_dungeon_api(linker: &mut Linker<Arc<Mutex<WasmWorld>>>) -> anyho
                 }
             };
 
-            // Build DungeonConfig from JSON (all fields optional)
-            let mut cfg = DungeonConfig::default();
+            // Build DungeonConfig from JSON (all fields optional; the seed defaults to a
+            // draw from the worldgen stream)
+            let mut cfg = DungeonConfig {
+                seed: caller.data().lock().unwrap().rng.next_u64(rng::WORLDGEN),
+                ..DungeonConfig::default()
+            };
             if let Some(w) = config_value.get("width").and_then(|v| v.as_u64()) {
                 if w == 0 {
                     let err = "Width must be positive".to_string();
@@ -101,6 +101,18 @@ pub fn register_entity_api(linker: &mut Linker<Arc<Mutex<WasmWorld>>>) -> anyhow
         },
     )?;
 
+    linker.func_wrap(
+        "entity",
+        "is_stale_entity",
+        |caller: Caller<'_, Arc<Mutex<WasmWorld>>>, entity_id: u32| -> i32 {
+            let stale = {
+                let world = caller.data().lock().unwrap();
+                world.is_stale_entity(entity_id)
+            };
+            stale as i32
+        },
+    )?;
+
     linker.func_wrap(
         "entity",
         "move_entity",
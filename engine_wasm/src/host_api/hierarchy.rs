@@ -0,0 +1,95 @@
+//! Entity hierarchy host API for WASM.
+//!
+//! Registers parent/child functions under the `"hierarchy"` namespace. Despawn
+//! policies are passed as `0` (cascade) or `1` (reparent); entity results that may
+//! be absent are returned as `i64`, with `-1` meaning none.
+
+use engine_core::ecs::world::DespawnPolicy;
+use engine_core::ecs::world::wasm::WasmWorld;
+use std::sync::{Arc, Mutex};
+use wasmtime::{Caller, Linker};
+
+/// Registers the hierarchy API (set_parent, remove_parent, parent_of, children_of, ancestors).
+pub fn register_hierarchy_api(linker: &mut Linker<Arc<Mutex<WasmWorld>>>) -> anyhow::Result<()> {
+    linker.func_wrap(
+        "hierarchy",
+        "set_parent",
+        |caller: Caller<'_, Arc<Mutex<WasmWorld>>>, child: u32, parent: u32, policy: i32| -> i32 {
+            let policy = match policy {
+                0 => DespawnPolicy::Cascade,
+                1 => DespawnPolicy::Reparent,
+                _ => return -1,
+            };
+            let mut world = caller.data().lock().unwrap();
+            match world.set_parent(child, parent, policy) {
+                Ok(()) => 0,
+                Err(_) => -1,
+            }
+        },
+    )?;
+
+    linker.func_wrap(
+        "hierarchy",
+        "remove_parent",
+        |caller: Caller<'_, Arc<Mutex<WasmWorld>>>, child: u32| -> i64 {
+            let mut world = caller.data().lock().unwrap();
+            world.remove_parent(child).map_or(-1, i64::from)
+        },
+    )?;
+
+    linker.func_wrap(
+        "hierarchy",
+        "parent_of",
+        |caller: Caller<'_, Arc<Mutex<WasmWorld>>>, entity: u32| -> i64 {
+            let world = caller.data().lock().unwrap();
+            world.parent_of(entity).map_or(-1, i64::from)
+        },
+    )?;
+
+    linker.func_wrap(
+        "hierarchy",
+        "children_of",
+        |mut caller: Caller<'_, Arc<Mutex<WasmWorld>>>,
+         entity: u32,
+         out_ptr: i32,
+         out_len: i32|
+         -> i32 {
+            let children = caller.data().lock().unwrap().children_of(entity);
+            write_u32_slice_to_wasm(&mut caller, out_ptr, &children, out_len)
+        },
+    )?;
+
+    linker.func_wrap(
+        "hierarchy",
+        "ancestors",
+        |mut caller: Caller<'_, Arc<Mutex<WasmWorld>>>,
+         entity: u32,
+         out_ptr: i32,
+         out_len: i32|
+         -> i32 {
+            let ancestors = caller.data().lock().unwrap().ancestors(entity);
+            write_u32_slice_to_wasm(&mut caller, out_ptr, &ancestors, out_len)
+        },
+    )?;
+
+    Ok(())
+}
+
+fn write_u32_slice_to_wasm<T>(
+    caller: &mut Caller<T>,
+    ptr: i32,
+    slice: &[u32],
+    max_len: i32,
+) -> i32 {
+    let mem = caller
+        .get_export("memory")
+        .and_then(|e| e.into_memory())
+        .expect("No memory export found");
+    let n = std::cmp::min(slice.len(), max_len as usize);
+    let bytes: &[u8] = unsafe {
+        std::slice::from_raw_parts(slice.as_ptr() as *const u8, n * std::mem::size_of::<u32>())
+    };
+    mem.write(caller, ptr as usize, bytes)
+        .expect("Failed to write to WASM memory");
+    n as i32
+}
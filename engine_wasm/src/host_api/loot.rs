@@ -40,8 +40,8 @@ pub fn register_loot_api(linker: &mut Linker<Arc<Mutex<WasmWorld>>>) -> anyhow::
             let name = read_wasm_string(&mut caller, name_ptr, name_len)
                 .expect("Failed to read table name from WASM memory");
             let result = {
-                let world = caller.data().lock().unwrap();
-                world.loot_tables.roll(&name)
+                let mut world = caller.data().lock().unwrap();
+                world.roll_loot(&name)
             };
             match result {
                 Ok(items) => {
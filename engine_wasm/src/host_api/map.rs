@@ -3,7 +3,7 @@ use engine_core::ecs::world::wasm::WasmWorld;
 use std::sync::{Arc, Mutex};
 use wasmtime::{Caller, Linker};
 
-/// Registers the map API (11 host functions).
+/// Registers the map API (19 host functions).
 pub fn register_map_api(linker: &mut Linker<Arc<Mutex<WasmWorld>>>) -> anyhow::Result<()> {
     linker.func_wrap(
         "wasm_map",
@@ -96,6 +96,72 @@ pub fn register_map_api(linker: &mut Linker<Arc<Mutex<WasmWorld>>>) -> anyhow::R
         },
     )?;
 
+    linker.func_wrap(
+        "wasm_map",
+        "entities_in_radius",
+        |mut caller: Caller<'_, Arc<Mutex<WasmWorld>>>,
+         cell_ptr: i32,
+         cell_len: i32,
+         radius: f64,
+         out_ptr: i32,
+         out_len: i32|
+         -> i32 {
+            let cell_json = read_wasm_string(&mut caller, cell_ptr, cell_len)
+                .expect("Failed to read cell JSON from WASM memory");
+            let entities = {
+                let world = caller.data().lock().unwrap();
+                world.entities_in_radius(&cell_json, radius)
+            };
+            write_u32_slice_to_wasm(&mut caller, out_ptr, &entities, out_len)
+        },
+    )?;
+
+    linker.func_wrap(
+        "wasm_map",
+        "entities_in_rect",
+        |mut caller: Caller<'_, Arc<Mutex<WasmWorld>>>,
+         a_ptr: i32,
+         a_len: i32,
+         b_ptr: i32,
+         b_len: i32,
+         out_ptr: i32,
+         out_len: i32|
+         -> i32 {
+            let a_json = read_wasm_string(&mut caller, a_ptr, a_len)
+                .expect("Failed to read corner cell from WASM memory");
+            let b_json = read_wasm_string(&mut caller, b_ptr, b_len)
+                .expect("Failed to read corner cell from WASM memory");
+            let entities = {
+                let world = caller.data().lock().unwrap();
+                world.entities_in_rect(&a_json, &b_json)
+            };
+            write_u32_slice_to_wasm(&mut caller, out_ptr, &entities, out_len)
+        },
+    )?;
+
+    // A negative `max_radius` means unbounded; returns -1 if nothing is found.
+    linker.func_wrap(
+        "wasm_map",
+        "nearest_entity_with_component",
+        |mut caller: Caller<'_, Arc<Mutex<WasmWorld>>>,
+         cell_ptr: i32,
+         cell_len: i32,
+         name_ptr: i32,
+         name_len: i32,
+         max_radius: f64|
+         -> i64 {
+            let cell_json = read_wasm_string(&mut caller, cell_ptr, cell_len)
+                .expect("Failed to read cell JSON from WASM memory");
+            let component = read_wasm_string(&mut caller, name_ptr, name_len)
+                .expect("Failed to read component name from WASM memory");
+            let world = caller.data().lock().unwrap();
+            let max_radius = (max_radius >= 0.0).then_some(max_radius);
+            world
+                .nearest_entity_with_component(&cell_json, &component, max_radius)
+                .map_or(-1, i64::from)
+        },
+    )?;
+
     linker.func_wrap(
         "wasm_map",
         "get_cell_metadata",
@@ -161,6 +227,113 @@ pub fn register_map_api(linker: &mut Linker<Arc<Mutex<WasmWorld>>>) -> anyhow::R
         },
     )?;
 
+    linker.func_wrap(
+        "wasm_map",
+        "dijkstra_map",
+        |mut caller: Caller<'_, Arc<Mutex<WasmWorld>>>,
+         goals_ptr: i32,
+         goals_len: i32,
+         out_ptr: i32,
+         out_len: i32|
+         -> i32 {
+            let goals_json = read_wasm_string(&mut caller, goals_ptr, goals_len)
+                .expect("Failed to read goal cells from WASM memory");
+            let result = {
+                let world = caller.data().lock().unwrap();
+                world.dijkstra_map(&goals_json)
+            };
+            match result {
+                Some(data) => write_string_to_wasm(&mut caller, out_ptr, out_len, &data) as i32,
+                None => -1,
+            }
+        },
+    )?;
+
+    linker.func_wrap(
+        "wasm_map",
+        "flee_map",
+        |mut caller: Caller<'_, Arc<Mutex<WasmWorld>>>,
+         threats_ptr: i32,
+         threats_len: i32,
+         factor: f32,
+         out_ptr: i32,
+         out_len: i32|
+         -> i32 {
+            let threats_json = read_wasm_string(&mut caller, threats_ptr, threats_len)
+                .expect("Failed to read threat cells from WASM memory");
+            let result = {
+                let world = caller.data().lock().unwrap();
+                world.flee_map(&threats_json, factor)
+            };
+            match result {
+                Some(data) => write_string_to_wasm(&mut caller, out_ptr, out_len, &data) as i32,
+                None => -1,
+            }
+        },
+    )?;
+
+    linker.func_wrap(
+        "wasm_map",
+        "flow_step",
+        |mut caller: Caller<'_, Arc<Mutex<WasmWorld>>>,
+         goals_ptr: i32,
+         goals_len: i32,
+         cell_ptr: i32,
+         cell_len: i32,
+         out_ptr: i32,
+         out_len: i32|
+         -> i32 {
+            let goals_json = read_wasm_string(&mut caller, goals_ptr, goals_len)
+                .expect("Failed to read goal cells from WASM memory");
+            let cell_json = read_wasm_string(&mut caller, cell_ptr, cell_len)
+                .expect("Failed to read cell JSON from WASM memory");
+            let result = {
+                let world = caller.data().lock().unwrap();
+                world.flow_step(&goals_json, &cell_json)
+            };
+            match result {
+                Some(data) => write_string_to_wasm(&mut caller, out_ptr, out_len, &data) as i32,
+                None => -1,
+            }
+        },
+    )?;
+
+    linker.func_wrap(
+        "wasm_map",
+        "flee_step",
+        |mut caller: Caller<'_, Arc<Mutex<WasmWorld>>>,
+         threats_ptr: i32,
+         threats_len: i32,
+         cell_ptr: i32,
+         cell_len: i32,
+         factor: f32,
+         out_ptr: i32,
+         out_len: i32|
+         -> i32 {
+            let threats_json = read_wasm_string(&mut caller, threats_ptr, threats_len)
+                .expect("Failed to read threat cells from WASM memory");
+            let cell_json = read_wasm_string(&mut caller, cell_ptr, cell_len)
+                .expect("Failed to read cell JSON from WASM memory");
+            let result = {
+                let world = caller.data().lock().unwrap();
+                world.flee_step(&threats_json, &cell_json, factor)
+            };
+            match result {
+                Some(data) => write_string_to_wasm(&mut caller, out_ptr, out_len, &data) as i32,
+                None => -1,
+            }
+        },
+    )?;
+
+    linker.func_wrap(
+        "wasm_map",
+        "connect_levels",
+        |caller: Caller<'_, Arc<Mutex<WasmWorld>>>| -> i32 {
+            let mut world = caller.data().lock().unwrap();
+            world.connect_levels() as i32
+        },
+    )?;
+
     linker.func_wrap(
         "wasm_map",
         "apply_generated_map",
@@ -97,6 +97,18 @@ pub mod job_ai;
 /// Loot table module (define_table, roll, has_table, table_names, remove_table)
 pub mod loot;
 
+/// Seeded RNG module (seed, get_seed, random, random_range)
+pub mod rng;
+
+/// World state hash module (state_hash)
+pub mod state_hash;
+
+/// Entity hierarchy module (set_parent, remove_parent, parent_of, children_of, ancestors)
+pub mod hierarchy;
+
+/// Entity query module (query, change_tick)
+pub mod query;
+
 /// Faction and reputation module (set_faction, get_faction, modify_reputation, get_reputation)
 pub mod faction;
 
@@ -0,0 +1,65 @@
+//! Entity query host API for WASM.
+//!
+//! Registers query functions under the `"query"` namespace. Queries are passed as
+//! JSON specs (see [`engine_core::ecs::query`]), e.g.
+//! `{"with":["Health"],"where":[{"component":"Health","path":"current","op":"<","value":5}]}`.
+
+use crate::host_api::component::read_wasm_string;
+use engine_core::ecs::query::Query;
+use engine_core::ecs::world::wasm::WasmWorld;
+use std::sync::{Arc, Mutex};
+use wasmtime::{Caller, Linker};
+
+/// Registers the query API (query, change_tick).
+pub fn register_query_api(linker: &mut Linker<Arc<Mutex<WasmWorld>>>) -> anyhow::Result<()> {
+    // query(spec_ptr, spec_len, out_ptr, out_len) -> number of entities written, or -1
+    // if the spec is invalid.
+    linker.func_wrap(
+        "query",
+        "query",
+        |mut caller: Caller<'_, Arc<Mutex<WasmWorld>>>,
+         spec_ptr: i32,
+         spec_len: i32,
+         out_ptr: i32,
+         out_len: i32|
+         -> i32 {
+            let Ok(spec) = read_wasm_string(&mut caller, spec_ptr, spec_len) else {
+                return -1;
+            };
+            let Ok(query) = serde_json::from_str::<Query>(&spec) else {
+                return -1;
+            };
+            let entities = query.entities(&*caller.data().lock().unwrap());
+            write_u32_slice_to_wasm(&mut caller, out_ptr, &entities, out_len)
+        },
+    )?;
+
+    linker.func_wrap(
+        "query",
+        "change_tick",
+        |caller: Caller<'_, Arc<Mutex<WasmWorld>>>| -> i64 {
+            caller.data().lock().unwrap().change_tick() as i64
+        },
+    )?;
+
+    Ok(())
+}
+
+fn write_u32_slice_to_wasm<T>(
+    caller: &mut Caller<T>,
+    ptr: i32,
+    slice: &[u32],
+    max_len: i32,
+) -> i32 {
+    let mem = caller
+        .get_export("memory")
+        .and_then(|e| e.into_memory())
+        .expect("No memory export found");
+    let n = std::cmp::min(slice.len(), max_len as usize);
+    let bytes: &[u8] = unsafe {
+        std::slice::from_raw_parts(slice.as_ptr() as *const u8, n * std::mem::size_of::<u32>())
+    };
+    mem.write(caller, ptr as usize, bytes)
+        .expect("Failed to write to WASM memory");
+    n as i32
+}
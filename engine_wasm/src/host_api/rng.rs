@@ -0,0 +1,59 @@
+//! Seeded RNG host API for WASM.
+//!
+//! Registers `seed`, `get_seed`, `random` and `random_range` under the `"rng"` namespace.
+//! Streams are named by string (e.g. `"loot"`, `"ai"`).
+
+use crate::host_api::component::read_wasm_string;
+use engine_core::ecs::world::wasm::WasmWorld;
+use std::sync::{Arc, Mutex};
+use wasmtime::{Caller, Linker};
+
+/// Registers the RNG API (seed, get_seed, random, random_range).
+pub fn register_rng_api(linker: &mut Linker<Arc<Mutex<WasmWorld>>>) -> anyhow::Result<()> {
+    linker.func_wrap(
+        "rng",
+        "seed",
+        |caller: Caller<'_, Arc<Mutex<WasmWorld>>>, seed: i64| {
+            let mut world = caller.data().lock().unwrap();
+            world.seed_rng(seed as u64);
+        },
+    )?;
+
+    linker.func_wrap(
+        "rng",
+        "get_seed",
+        |caller: Caller<'_, Arc<Mutex<WasmWorld>>>| -> i64 {
+            let world = caller.data().lock().unwrap();
+            world.rng_seed() as i64
+        },
+    )?;
+
+    linker.func_wrap(
+        "rng",
+        "random",
+        |mut caller: Caller<'_, Arc<Mutex<WasmWorld>>>, stream_ptr: i32, stream_len: i32| -> f64 {
+            let stream = read_wasm_string(&mut caller, stream_ptr, stream_len)
+                .expect("Failed to read stream name from WASM memory");
+            let mut world = caller.data().lock().unwrap();
+            world.random_f64(&stream)
+        },
+    )?;
+
+    linker.func_wrap(
+        "rng",
+        "random_range",
+        |mut caller: Caller<'_, Arc<Mutex<WasmWorld>>>,
+         stream_ptr: i32,
+         stream_len: i32,
+         min: i64,
+         max: i64|
+         -> i64 {
+            let stream = read_wasm_string(&mut caller, stream_ptr, stream_len)
+                .expect("Failed to read stream name from WASM memory");
+            let mut world = caller.data().lock().unwrap();
+            world.random_range(&stream, min, max)
+        },
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+//! State hash host API for WASM.
+//!
+//! Registers `state_hash`, `set_system_hash_debug` and `take_system_hashes` under the
+//! `"state"` namespace.
+
+use crate::host_api::component::write_string_to_wasm;
+use engine_core::ecs::world::wasm::WasmWorld;
+use std::sync::{Arc, Mutex};
+use wasmtime::{Caller, Linker};
+
+/// Registers the state hash API (state_hash, set_system_hash_debug, take_system_hashes).
+pub fn register_state_hash_api(linker: &mut Linker<Arc<Mutex<WasmWorld>>>) -> anyhow::Result<()> {
+    linker.func_wrap(
+        "state",
+        "state_hash",
+        |caller: Caller<'_, Arc<Mutex<WasmWorld>>>| -> i64 {
+            let world = caller.data().lock().unwrap();
+            world.state_hash() as i64
+        },
+    )?;
+
+    linker.func_wrap(
+        "state",
+        "set_system_hash_debug",
+        |caller: Caller<'_, Arc<Mutex<WasmWorld>>>, enabled: i32| {
+            let mut world = caller.data().lock().unwrap();
+            world.set_system_hash_debug(enabled != 0);
+        },
+    )?;
+
+    // Writes a JSON array of { turn, system, hash } and clears the log. Returns the
+    // byte length, or -1 (keeping the log) if it does not fit in the buffer.
+    linker.func_wrap(
+        "state",
+        "take_system_hashes",
+        |mut caller: Caller<'_, Arc<Mutex<WasmWorld>>>, out_ptr: i32, out_len: i32| -> i32 {
+            let json = {
+                let world = caller.data().lock().unwrap();
+                serde_json::to_string(world.system_hashes()).unwrap_or_else(|_| "[]".to_string())
+            };
+            if json.len() > out_len.max(0) as usize {
+                return -1;
+            }
+            caller.data().lock().unwrap().take_system_hashes();
+            write_string_to_wasm(&mut caller, out_ptr, out_len, &json) as i32
+        },
+    )?;
+
+    Ok(())
+}
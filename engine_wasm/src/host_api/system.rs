@@ -28,7 +28,7 @@ pub fn register_system_api(linker: &mut Linker<Arc<Mutex<WasmWorld>>>) -> anyhow
         |mut caller: Caller<'_, Arc<Mutex<WasmWorld>>>, name_ptr: i32, name_len: i32| {
             let name = read_wasm_string(&mut caller, name_ptr, name_len)
                 .expect("Failed to read system name from WASM memory");
-            let world = caller.data().lock().unwrap();
+            let mut world = caller.data().lock().unwrap();
             world.run_system(&name).expect("Failed to run system");
         },
     )?;
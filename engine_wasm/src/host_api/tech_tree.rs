@@ -26,67 +26,6 @@ fn read_tech_progress(world: &WasmWorld, entity: u32) -> serde_json::Value {
         })
 }
 
-/// Helper: write a TechProgress component to WasmWorld.
-fn write_tech_progress(
-    world: &mut WasmWorld,
-    entity: u32,
-    progress: &serde_json::Value,
-) -> Result<(), String> {
-    world.set_component(
-        entity,
-        "TechProgress",
-        &serde_json::to_string(progress).unwrap_or_else(|_| "{}".to_string()),
-    )
-}
-
-/// Helper: check prerequisites for a tech node against an entity.
-fn check_prerequisites(
-    world: &WasmWorld,
-    entity: u32,
-    node: &tech_tree::TechNode,
-) -> Result<(), String> {
-    for prereq in &node.prerequisites {
-        match prereq.prereq_type.as_str() {
-            "tech" => {
-                let progress = read_tech_progress(world, entity);
-                let completed = progress.get("completed").and_then(|c| c.as_object());
-                let is_done = completed
-                    .map(|m| m.contains_key(&prereq.id))
-                    .unwrap_or(false);
-                if !is_done {
-                    let name = tech_tree::get_tech_node(&prereq.id)
-                        .map(|n| n.name.as_str())
-                        .unwrap_or(&prereq.id);
-                    return Err(format!("Requires tech '{}' ({})", prereq.id, name));
-                }
-            }
-            "skill" => {
-                let required_level = prereq.level.unwrap_or(1.0);
-                let current_level = world
-                    .get_component(entity, "SkillLevels")
-                    .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
-                    .and_then(|sl| {
-                        sl.get("skill_levels")
-                            .and_then(|slm| slm.as_object())
-                            .and_then(|m| m.get(&prereq.id))
-                            .and_then(|v| v.as_f64())
-                    })
-                    .unwrap_or(0.0);
-                if current_level < required_level {
-                    return Err(format!(
-                        "Requires skill '{}' level {} (current: {})",
-                        prereq.id, required_level, current_level
-                    ));
-                }
-            }
-            other => {
-                return Err(format!("Unknown prerequisite type: {other}"));
-            }
-        }
-    }
-    Ok(())
-}
-
 /// Helper: write a boolean (i32) to WASM memory at the given pointer.
 fn write_bool_to_wasm<T>(caller: &mut Caller<T>, ptr: i32, val: bool) {
     let bytes = (if val { 1i32 } else { 0i32 }).to_le_bytes();
@@ -102,8 +41,10 @@ pub fn register_tech_tree_api(linker: &mut Linker<Arc<Mutex<WasmWorld>>>) -> any
         "tech_tree",
         "get_tech_tree",
         |mut caller: Caller<'_, Arc<Mutex<WasmWorld>>>, out_ptr: i32, out_len: i32| -> i32 {
-            let nodes = tech_tree::get_tech_tree();
-            let json_str = serde_json::to_string(nodes).unwrap_or_else(|_| "[]".to_string());
+            let json_str = {
+                let world = caller.data().lock().unwrap();
+                serde_json::to_string(&world.tech_tree).unwrap_or_else(|_| "[]".to_string())
+            };
             write_string_to_wasm(&mut caller, out_ptr, out_len, &json_str) as i32
         },
     )?;
@@ -122,9 +63,17 @@ pub fn register_tech_tree_api(linker: &mut Linker<Arc<Mutex<WasmWorld>>>) -> any
                 Ok(s) => s,
                 Err(_) => return -1,
             };
-            match tech_tree::get_tech_node(&tech_id) {
+            let node = caller
+                .data()
+                .lock()
+                .unwrap()
+                .tech_tree
+                .get(&tech_id)
+                .cloned();
+            match node {
                 Some(node) => {
-                    let json_str = serde_json::to_string(node).unwrap_or_else(|_| "{}".to_string());
+                    let json_str =
+                        serde_json::to_string(&node).unwrap_or_else(|_| "{}".to_string());
                     write_string_to_wasm(&mut caller, out_ptr, out_len, &json_str) as i32
                 }
                 None => -1,
@@ -132,6 +81,42 @@ pub fn register_tech_tree_api(linker: &mut Linker<Arc<Mutex<WasmWorld>>>) -> any
         },
     )?;
 
+    // load_tech_tree(path_ptr, path_len) -> 0 on success, -1 on error
+    // Merges nodes from a JSON file or directory, replacing nodes with the same IDs.
+    linker.func_wrap(
+        "tech_tree",
+        "load_tech_tree",
+        |mut caller: Caller<'_, Arc<Mutex<WasmWorld>>>, path_ptr: i32, path_len: i32| -> i32 {
+            let path = match read_wasm_string(&mut caller, path_ptr, path_len) {
+                Ok(s) => s,
+                Err(_) => return -1,
+            };
+            let mut world = caller.data().lock().unwrap();
+            match world.tech_tree.load(&path) {
+                Ok(()) => 0,
+                Err(e) => {
+                    eprintln!("Failed to load tech tree from {path}: {e}");
+                    -1
+                }
+            }
+        },
+    )?;
+
+    // validate_tech_tree(out_ptr, out_len) -> writes JSON array of problems
+    // (unknown prerequisites, cycles, invalid effects), empty if the tree is valid
+    linker.func_wrap(
+        "tech_tree",
+        "validate_tech_tree",
+        |mut caller: Caller<'_, Arc<Mutex<WasmWorld>>>, out_ptr: i32, out_len: i32| -> i32 {
+            let problems: Vec<String> = match caller.data().lock().unwrap().tech_tree.validate() {
+                Ok(()) => Vec::new(),
+                Err(errors) => errors.iter().map(|e| e.to_string()).collect(),
+            };
+            let json_str = serde_json::to_string(&problems).unwrap_or_else(|_| "[]".to_string());
+            write_string_to_wasm(&mut caller, out_ptr, out_len, &json_str) as i32
+        },
+    )?;
+
     // get_tech_progress(entity, out_ptr, out_len) -> 0 if found, -1 if not
     linker.func_wrap(
         "tech_tree",
@@ -257,96 +242,11 @@ pub fn register_tech_tree_api(linker: &mut Linker<Arc<Mutex<WasmWorld>>>) -> any
                 Ok(s) => s,
                 Err(_) => return -1,
             };
-
-            // Look up tech node
-            let node = match tech_tree::get_tech_node(&tech_id) {
-                Some(n) => n.clone(),
-                None => return -1,
-            };
-
-            // Perform the research operation inside a scoped block to drop the lock
-            let should_fire_event = {
-                let mut world = caller.data().lock().unwrap();
-                let progress = read_tech_progress(&world, entity);
-
-                // Already completed?
-                if progress
-                    .get("completed")
-                    .and_then(|c| c.as_object())
-                    .map(|m| m.contains_key(&tech_id))
-                    .unwrap_or(false)
-                {
-                    return -1;
-                }
-
-                // Already in queue?
-                let in_queue = progress
-                    .get("queue")
-                    .and_then(|q| q.as_array())
-                    .map(|a| a.iter().any(|v| v.as_str() == Some(&tech_id)))
-                    .unwrap_or(false);
-                if in_queue {
-                    return -1;
-                }
-
-                // Check prerequisites
-                if check_prerequisites(&world, entity, &node).is_err() {
-                    return -1;
-                }
-
-                // Build updated progress
-                let mut queue: Vec<serde_json::Value> = progress
-                    .get("queue")
-                    .and_then(|q| q.as_array())
-                    .cloned()
-                    .unwrap_or_default();
-                queue.push(json!(tech_id));
-
-                let mut queue_progress = progress
-                    .get("queue_progress")
-                    .and_then(|qp| qp.as_object())
-                    .cloned()
-                    .unwrap_or_default();
-                queue_progress.insert(tech_id.clone(), json!(0.0));
-
-                let completed = progress
-                    .get("completed")
-                    .and_then(|c| c.as_object())
-                    .cloned()
-                    .unwrap_or_default();
-                let research_points = progress
-                    .get("research_points")
-                    .and_then(|rp| rp.as_f64())
-                    .unwrap_or(0.0);
-
-                let updated = json!({
-                    "completed": serde_json::Value::Object(completed),
-                    "queue": serde_json::Value::Array(queue),
-                    "queue_progress": serde_json::Value::Object(queue_progress),
-                    "research_points": research_points,
-                });
-
-                if write_tech_progress(&mut world, entity, &updated).is_err() {
-                    return -1;
-                }
-
-                true
-            };
-
-            // Fire event outside the lock scope to avoid borrow issues
-            if should_fire_event {
-                let mut world = caller.data().lock().unwrap();
-                let event_data = json!({
-                    "entity": entity,
-                    "tech_id": tech_id,
-                    "tech_name": node.name,
-                });
-                world
-                    .send_event("research_started", &event_data.to_string())
-                    .ok();
+            let mut world = caller.data().lock().unwrap();
+            match tech_tree::research_tech(&mut *world, entity, &tech_id) {
+                Ok(()) => 0,
+                Err(_) => -1,
             }
-
-            0
         },
     )?;
 
@@ -363,70 +263,11 @@ pub fn register_tech_tree_api(linker: &mut Linker<Arc<Mutex<WasmWorld>>>) -> any
                 Ok(s) => s,
                 Err(_) => return -1,
             };
-
-            let should_fire_event = {
-                let mut world = caller.data().lock().unwrap();
-                let progress = read_tech_progress(&world, entity);
-
-                let mut queue: Vec<serde_json::Value> = progress
-                    .get("queue")
-                    .and_then(|q| q.as_array())
-                    .cloned()
-                    .unwrap_or_default();
-                let pos = queue.iter().position(|v| v.as_str() == Some(&tech_id));
-                match pos {
-                    Some(idx) => {
-                        queue.remove(idx);
-                    }
-                    None => return -1,
-                }
-
-                let mut queue_progress = progress
-                    .get("queue_progress")
-                    .and_then(|qp| qp.as_object())
-                    .cloned()
-                    .unwrap_or_default();
-                queue_progress.remove(&tech_id);
-
-                let completed = progress
-                    .get("completed")
-                    .and_then(|c| c.as_object())
-                    .cloned()
-                    .unwrap_or_default();
-                let research_points = progress
-                    .get("research_points")
-                    .and_then(|rp| rp.as_f64())
-                    .unwrap_or(0.0);
-
-                let updated = json!({
-                    "completed": serde_json::Value::Object(completed),
-                    "queue": serde_json::Value::Array(queue),
-                    "queue_progress": serde_json::Value::Object(queue_progress),
-                    "research_points": research_points,
-                });
-
-                if write_tech_progress(&mut world, entity, &updated).is_err() {
-                    return -1;
-                }
-
-                true
-            };
-
-            if should_fire_event {
-                let mut world = caller.data().lock().unwrap();
-                let event_data = json!({
-                    "entity": entity,
-                    "tech_id": tech_id,
-                    "tech_name": tech_tree::get_tech_node(&tech_id)
-                        .map(|n| n.name.as_str())
-                        .unwrap_or(&tech_id),
-                });
-                world
-                    .send_event("research_cancelled", &event_data.to_string())
-                    .ok();
+            let mut world = caller.data().lock().unwrap();
+            match tech_tree::cancel_research(&mut *world, entity, &tech_id) {
+                Ok(()) => 0,
+                Err(_) => -1,
             }
-
-            0
         },
     )?;
 
@@ -436,28 +277,10 @@ pub fn register_tech_tree_api(linker: &mut Linker<Arc<Mutex<WasmWorld>>>) -> any
         "clear_research_queue",
         |caller: Caller<'_, Arc<Mutex<WasmWorld>>>, entity: u32| -> i32 {
             let mut world = caller.data().lock().unwrap();
-            let progress = read_tech_progress(&world, entity);
-            let completed = progress
-                .get("completed")
-                .and_then(|c| c.as_object())
-                .cloned()
-                .unwrap_or_default();
-            let research_points = progress
-                .get("research_points")
-                .and_then(|rp| rp.as_f64())
-                .unwrap_or(0.0);
-
-            let updated = json!({
-                "completed": serde_json::Value::Object(completed),
-                "queue": [],
-                "queue_progress": {},
-                "research_points": research_points,
-            });
-
-            if write_tech_progress(&mut world, entity, &updated).is_err() {
-                return -1;
+            match tech_tree::clear_research_queue(&mut *world, entity) {
+                Ok(()) => 0,
+                Err(_) => -1,
             }
-            0
         },
     )?;
 
@@ -479,50 +302,13 @@ pub fn register_tech_tree_api(linker: &mut Linker<Arc<Mutex<WasmWorld>>>) -> any
                 Err(_) => return -1,
             };
 
-            // Look up tech node (static lookup, doesn't need lock)
-            let node = match tech_tree::get_tech_node(&tech_id) {
-                Some(n) => n,
-                None => {
-                    let reason = format!("Unknown tech '{}'", tech_id);
-                    write_bool_to_wasm(&mut caller, out_bool_ptr, false);
-                    write_string_to_wasm(&mut caller, out_reason_ptr, out_reason_len, &reason);
-                    return 0;
-                }
-            };
-
-            // Compute reason and can_research
-            let (can_research, reason) = {
+            let result = {
                 let world = caller.data().lock().unwrap();
-                let progress = read_tech_progress(&world, entity);
-
-                // Already completed?
-                if progress
-                    .get("completed")
-                    .and_then(|c| c.as_object())
-                    .map(|m| m.contains_key(&tech_id))
-                    .unwrap_or(false)
-                {
-                    (false, format!("Tech '{}' already completed", tech_id))
-                }
-                // Already in queue?
-                else if progress
-                    .get("queue")
-                    .and_then(|q| q.as_array())
-                    .map(|a| a.iter().any(|v| v.as_str() == Some(&tech_id)))
-                    .unwrap_or(false)
-                {
-                    (
-                        false,
-                        format!("Tech '{}' already in research queue", tech_id),
-                    )
-                }
-                // Check prerequisites
-                else {
-                    match check_prerequisites(&world, entity, node) {
-                        Ok(_) => (true, String::new()),
-                        Err(e) => (false, e),
-                    }
-                }
+                tech_tree::can_research_tech(&*world, entity, &tech_id)
+            };
+            let (can_research, reason) = match result {
+                Ok(_) => (true, String::new()),
+                Err(e) => (false, e),
             };
 
             // Write results (lock is dropped by now)
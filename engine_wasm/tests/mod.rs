@@ -12,7 +12,9 @@ mod wasm_entity_api;
 mod wasm_event_bus_ecs;
 mod wasm_export_discovery;
 mod wasm_faction_api;
+mod wasm_flow_field_api;
 mod wasm_fog_api;
+mod wasm_hierarchy_api;
 mod wasm_input_api;
 mod wasm_inventory_api;
 mod wasm_job_ai;
@@ -25,9 +27,13 @@ mod wasm_job_system;
 mod wasm_loot_api;
 mod wasm_map_api;
 mod wasm_mode_api;
+mod wasm_query_api;
 mod wasm_region_api;
+mod wasm_rng_api;
 mod wasm_save_load_api;
 mod wasm_skill_stat_api;
+mod wasm_state_hash_api;
+mod wasm_tech_tree_api;
 mod wasm_time_of_day_api;
 mod wasm_turn_api;
 mod wasm_ui_api;
@@ -33,6 +33,7 @@ fn test_wasm_body_part_damage_bridge() {
         worldgen_registry: None,
         import_host_functions: None,
         input_source: None,
+        game_config: None,
     };
 
     let engine = WasmScriptEngine::new(config).expect("Failed to create WasmScriptEngine");
@@ -33,6 +33,7 @@ fn test_wasm_camera_api_bridge() {
         worldgen_registry: None,
         import_host_functions: None,
         input_source: None,
+        game_config: None,
     };
 
     let engine = WasmScriptEngine::new(config).expect("Failed to create WasmScriptEngine");
@@ -32,6 +32,7 @@ fn test_wasm_reserve_only() {
         worldgen_registry: None,
         import_host_functions: None,
         input_source: None,
+        game_config: None,
     };
     let engine = WasmScriptEngine::new(config).expect("Failed to create WasmScriptEngine");
     let result = engine
@@ -49,6 +50,7 @@ fn test_wasm_get_reservations_before() {
         worldgen_registry: None,
         import_host_functions: None,
         input_source: None,
+        game_config: None,
     };
     let engine = WasmScriptEngine::new(config).expect("Failed to create WasmScriptEngine");
     let result = engine
@@ -66,6 +68,7 @@ fn test_wasm_reserve_then_query() {
         worldgen_registry: None,
         import_host_functions: None,
         input_source: None,
+        game_config: None,
     };
     let engine = WasmScriptEngine::new(config).expect("Failed to create WasmScriptEngine");
     let result = engine
@@ -83,6 +86,7 @@ fn test_wasm_reserve_and_release() {
         worldgen_registry: None,
         import_host_functions: None,
         input_source: None,
+        game_config: None,
     };
     let engine = WasmScriptEngine::new(config).expect("Failed to create WasmScriptEngine");
     let result = engine
@@ -29,6 +29,7 @@ fn test_wasm_script_engine_can_instantiate_and_run() {
         worldgen_registry: None,
         import_host_functions: None,
         input_source: None,
+        game_config: None,
     };
     let engine = WasmScriptEngine::new(config).expect("Failed to create WasmScriptEngine");
     let result = engine
@@ -68,6 +69,7 @@ fn test_wasm_script_engine_can_register_and_call_host_function() {
                 .unwrap();
         })),
         input_source: None,
+        game_config: None,
     };
     let engine = WasmScriptEngine::new(config).expect("Failed to create WasmScriptEngine");
     let result = engine
@@ -34,6 +34,7 @@ fn test_wasm_input_api_bridge() {
         worldgen_registry: None,
         import_host_functions: None,
         input_source: Some(InputSource::Mock(vec![])),
+        game_config: None,
     };
 
     let engine = WasmScriptEngine::new(config).expect("Failed to create WasmScriptEngine");
@@ -0,0 +1,89 @@
+use engine_core::config::GameConfig;
+use engine_wasm::{WasmScriptEngine, WasmScriptEngineConfig, WasmValue};
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+/// Loads a WASM test artifact from the wasm_tests directory at runtime.
+/// Panics if the file is missing.
+fn load_wasm_test_artifact(name: &str) -> Vec<u8> {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("wasm_tests")
+        .join(name);
+    std::fs::read(&path).unwrap_or_else(|e| {
+        panic!(
+            "Failed to load WASM test artifact '{}': {}",
+            path.display(),
+            e
+        )
+    })
+}
+
+/// Writes the loaded WASM bytes to a temporary file and returns the file handle.
+fn compile_test_wasm() -> NamedTempFile {
+    let wasm_bytes = load_wasm_test_artifact("test_rng_api.wasm");
+    let mut file = NamedTempFile::new().expect("Failed to create temp file");
+    file.write_all(&wasm_bytes)
+        .expect("Failed to write WASM module");
+    file
+}
+
+#[test]
+fn test_wasm_rng_api_bridge() {
+    let wasm_file = compile_test_wasm();
+
+    let config = WasmScriptEngineConfig {
+        module_path: wasm_file.path().to_path_buf(),
+        schema_path: None,
+        worldgen_registry: None,
+        import_host_functions: None,
+        input_source: None,
+        game_config: None,
+    };
+
+    let engine = WasmScriptEngine::new(config).expect("Failed to create WasmScriptEngine");
+
+    let result = engine
+        .invoke_exported_function("test_rng_api", &[])
+        .expect("Failed to call test_rng_api");
+    assert_eq!(result, Some(1i32.into()));
+}
+
+#[test]
+fn test_wasm_rng_uses_config_seed() {
+    let wasm_bytes = load_wasm_test_artifact("test_rng_config.wasm");
+    let mut wasm_file = NamedTempFile::new().expect("Failed to create temp file");
+    wasm_file
+        .write_all(&wasm_bytes)
+        .expect("Failed to write WASM module");
+
+    let mut config_file = NamedTempFile::new().expect("Failed to create temp file");
+    config_file
+        .write_all(
+            b"title = \"Seeded\"\nversion = \"0.1.0\"\nallowed_modes = [\"colony\"]\nseed = 4242\n",
+        )
+        .expect("Failed to write game config");
+    let game_config =
+        GameConfig::load_from_file(config_file.path()).expect("Failed to load game config");
+
+    let draws: Vec<_> = (0..2)
+        .map(|_| {
+            let config = WasmScriptEngineConfig {
+                module_path: wasm_file.path().to_path_buf(),
+                schema_path: None,
+                worldgen_registry: None,
+                import_host_functions: None,
+                input_source: None,
+                game_config: Some(game_config.clone()),
+            };
+            let engine = WasmScriptEngine::new(config).expect("Failed to create WasmScriptEngine");
+            let seed = engine
+                .invoke_exported_function("configured_seed", &[])
+                .expect("Failed to call configured_seed");
+            assert_eq!(seed, Some(WasmValue::I64(4242)));
+            engine
+                .invoke_exported_function("first_draw", &[])
+                .expect("Failed to call first_draw")
+        })
+        .collect();
+    assert_eq!(draws[0], draws[1]);
+}
@@ -0,0 +1,76 @@
+use engine_wasm::{WasmScriptEngine, WasmScriptEngineConfig};
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+/// Loads a WASM test artifact from the wasm_tests directory at runtime.
+fn load_wasm_test_artifact(name: &str) -> Vec<u8> {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("wasm_tests")
+        .join(name);
+    std::fs::read(&path).unwrap_or_else(|e| {
+        panic!(
+            "Failed to load WASM test artifact '{}': {}",
+            path.display(),
+            e
+        )
+    })
+}
+
+/// Writes the loaded WASM bytes to a temporary file and returns the file handle.
+fn compile_test_wasm() -> NamedTempFile {
+    let wasm_bytes = load_wasm_test_artifact("test_tech_tree_api.wasm");
+    let mut file = NamedTempFile::new().expect("Failed to create temp file");
+    file.write_all(&wasm_bytes)
+        .expect("Failed to write WASM module");
+    file
+}
+
+/// Creates an engine whose tech tree holds "masonry", auto-loaded from the "tech"
+/// sibling of schema_path.
+fn engine_with_tech_tree(wasm_file: &NamedTempFile) -> (WasmScriptEngine, tempfile::TempDir) {
+    let assets = tempfile::tempdir().expect("Failed to create temp dir");
+    let schema_dir = assets.path().join("schemas");
+    let tech_dir = assets.path().join("tech");
+    std::fs::create_dir_all(&schema_dir).unwrap();
+    std::fs::create_dir_all(&tech_dir).unwrap();
+    std::fs::write(
+        tech_dir.join("tree.json"),
+        r#"{"techs": [{"id": "masonry", "name": "Masonry", "cost": 10,
+            "resource_cost": {"stone": 20}}]}"#,
+    )
+    .unwrap();
+
+    let config = WasmScriptEngineConfig {
+        module_path: wasm_file.path().to_path_buf(),
+        schema_path: Some(schema_dir),
+        worldgen_registry: None,
+        import_host_functions: None,
+        input_source: None,
+        game_config: None,
+    };
+
+    let engine = WasmScriptEngine::new(config).expect("Failed to create WasmScriptEngine");
+    (engine, assets)
+}
+
+#[test]
+fn test_wasm_tech_tree_api_bridge() {
+    let wasm_file = compile_test_wasm();
+    let (engine, _assets) = engine_with_tech_tree(&wasm_file);
+
+    let result = engine
+        .invoke_exported_function("test_tech_tree_api", &[])
+        .expect("Failed to call test_tech_tree_api");
+    assert_eq!(result, Some(1i32.into()));
+}
+
+#[test]
+fn test_wasm_load_and_validate_tech_tree_bridge() {
+    let wasm_file = compile_test_wasm();
+    let (engine, _assets) = engine_with_tech_tree(&wasm_file);
+
+    let result = engine
+        .invoke_exported_function("test_load_and_validate_tech_tree", &[])
+        .expect("Failed to call test_load_and_validate_tech_tree");
+    assert_eq!(result, Some(1i32.into()));
+}
@@ -0,0 +1,105 @@
+// This file is compiled to WASM and loaded by the Rust host test harness.
+// Tests Dijkstra maps, flow steps and flee steps, and that they follow map changes.
+
+#[unsafe(no_mangle)]
+pub extern "C" fn test_flow_field_api() -> i32 {
+    #[link(wasm_import_module = "wasm_map")]
+    unsafe extern "C" {
+        fn add_cell(x: i32, y: i32, z: i32);
+        fn add_neighbor(from_ptr: *const u8, from_len: i32, to_ptr: *const u8, to_len: i32);
+        fn dijkstra_map(goals_ptr: *const u8, goals_len: i32, out_ptr: *mut u8, out_len: i32)
+        -> i32;
+        fn flow_step(
+            goals_ptr: *const u8,
+            goals_len: i32,
+            cell_ptr: *const u8,
+            cell_len: i32,
+            out_ptr: *mut u8,
+            out_len: i32,
+        ) -> i32;
+        fn flee_step(
+            threats_ptr: *const u8,
+            threats_len: i32,
+            cell_ptr: *const u8,
+            cell_len: i32,
+            factor: f32,
+            out_ptr: *mut u8,
+            out_len: i32,
+        ) -> i32;
+    }
+
+    unsafe fn link(from: &str, to: &str) {
+        unsafe { add_neighbor(from.as_ptr(), from.len() as i32, to.as_ptr(), to.len() as i32) }
+    }
+
+    unsafe fn step(goals: &str, cell: &str) -> String {
+        let mut buf = [0u8; 256];
+        let n = unsafe {
+            flow_step(
+                goals.as_ptr(),
+                goals.len() as i32,
+                cell.as_ptr(),
+                cell.len() as i32,
+                buf.as_mut_ptr(),
+                buf.len() as i32,
+            )
+        };
+        if n < 0 {
+            return String::new();
+        }
+        String::from_utf8_lossy(&buf[..n as usize]).into_owned()
+    }
+
+    let a = "{\"Square\":{\"x\":0,\"y\":0,\"z\":0}}";
+    let b = "{\"Square\":{\"x\":1,\"y\":0,\"z\":0}}";
+    let c = "{\"Square\":{\"x\":2,\"y\":0,\"z\":0}}";
+    let to_c = "[{\"Square\":{\"x\":2,\"y\":0,\"z\":0}}]";
+
+    unsafe {
+        // A corridor A - B - C
+        add_cell(0, 0, 0);
+        add_cell(1, 0, 0);
+        add_cell(2, 0, 0);
+        link(a, b);
+        link(b, c);
+
+        // Step 1: The Dijkstra map reaches every cell
+        let mut buf = [0u8; 1024];
+        let n = dijkstra_map(to_c.as_ptr(), to_c.len() as i32, buf.as_mut_ptr(), buf.len() as i32);
+        if n < 0 {
+            return 0;
+        }
+        let field = String::from_utf8_lossy(&buf[..n as usize]).into_owned();
+        if field.matches("\"distance\"").count() != 3 {
+            return 0;
+        }
+
+        // Step 2: From A the way to C leads through B
+        if step(to_c, a) != b {
+            return 0;
+        }
+
+        // Step 3: Fleeing from C leads from B to A
+        let mut out = [0u8; 256];
+        let n = flee_step(
+            to_c.as_ptr(),
+            to_c.len() as i32,
+            b.as_ptr(),
+            b.len() as i32,
+            1.2,
+            out.as_mut_ptr(),
+            out.len() as i32,
+        );
+        if n < 0 || &out[..n as usize] != a.as_bytes() {
+            return 0;
+        }
+
+        // Step 4: A new shortcut is used, so the cached map was refreshed
+        link(a, c);
+        if step(to_c, a) != c {
+            return 0;
+        }
+
+        1
+    }
+}
@@ -0,0 +1,52 @@
+// This file is compiled to WASM and loaded by the Rust host test harness.
+// Tests the entity hierarchy API (set_parent, parent_of, children_of, ancestors).
+
+#[unsafe(no_mangle)]
+pub extern "C" fn test_hierarchy_api() -> i32 {
+    #[link(wasm_import_module = "entity")]
+    unsafe extern "C" {
+        fn spawn_entity() -> u32;
+        fn despawn_entity(entity_id: u32);
+    }
+    #[link(wasm_import_module = "hierarchy")]
+    unsafe extern "C" {
+        fn set_parent(child: u32, parent: u32, policy: i32) -> i32;
+        fn parent_of(entity: u32) -> i64;
+        fn children_of(entity: u32, out_ptr: *mut u32, out_len: i32) -> i32;
+        fn ancestors(entity: u32, out_ptr: *mut u32, out_len: i32) -> i32;
+    }
+
+    unsafe {
+        let root = spawn_entity();
+        let bag = spawn_entity();
+        let item = spawn_entity();
+
+        // Step 1: Link root -> bag -> item and query the links
+        if set_parent(bag, root, 0) != 0 || set_parent(item, bag, 1) != 0 {
+            return 0;
+        }
+        if parent_of(item) != bag as i64 || parent_of(root) != -1 {
+            return 0;
+        }
+        let mut buf = [0u32; 4];
+        if children_of(root, buf.as_mut_ptr(), 4) != 1 || buf[0] != bag {
+            return 0;
+        }
+        if ancestors(item, buf.as_mut_ptr(), 4) != 2 || buf[0] != bag || buf[1] != root {
+            return 0;
+        }
+
+        // Step 2: Cycles are rejected
+        if set_parent(root, item, 0) != -1 {
+            return 0;
+        }
+
+        // Step 3: Despawning the bag moves the reparented item up to the root
+        despawn_entity(bag);
+        if parent_of(item) != root as i64 {
+            return 0;
+        }
+
+        1
+    }
+}
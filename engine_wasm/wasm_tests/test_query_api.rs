@@ -0,0 +1,71 @@
+// This file is compiled to WASM and loaded by the Rust host test harness.
+// Tests the entity query API (query with JSON specs, change_tick).
+
+#[unsafe(no_mangle)]
+pub extern "C" fn test_query_api() -> i32 {
+    #[link(wasm_import_module = "entity")]
+    unsafe extern "C" {
+        fn spawn_entity() -> u32;
+    }
+    #[link(wasm_import_module = "component")]
+    unsafe extern "C" {
+        fn set_component(
+            entity: u32,
+            name_ptr: *const u8,
+            name_len: i32,
+            json_ptr: *const u8,
+            json_len: i32,
+        );
+    }
+    #[link(wasm_import_module = "query")]
+    unsafe extern "C" {
+        fn query(spec_ptr: *const u8, spec_len: i32, out_ptr: *mut u32, out_len: i32) -> i32;
+        fn change_tick() -> i64;
+    }
+
+    unsafe fn set_health(entity: u32, json: &str) {
+        let name = "Health";
+        unsafe {
+            set_component(
+                entity,
+                name.as_ptr(),
+                name.len() as i32,
+                json.as_ptr(),
+                json.len() as i32,
+            );
+        }
+    }
+
+    unsafe fn run(spec: &str, buf: &mut [u32; 4]) -> i32 {
+        unsafe { query(spec.as_ptr(), spec.len() as i32, buf.as_mut_ptr(), 4) }
+    }
+
+    unsafe {
+        let low = spawn_entity();
+        let high = spawn_entity();
+        set_health(low, "{\"current\":2,\"max\":10}");
+        set_health(high, "{\"current\":9,\"max\":10}");
+        let mut buf = [0u32; 4];
+
+        // Step 1: Field predicates
+        let wounded = "{\"where\":[{\"component\":\"Health\",\"path\":\"current\",\"op\":\"<\",\"value\":5}]}";
+        if run(wounded, &mut buf) != 1 || buf[0] != low {
+            return 0;
+        }
+
+        // Step 2: Changed since a tick
+        let tick = change_tick();
+        set_health(high, "{\"current\":8,\"max\":10}");
+        let changed = format!("{{\"changed\":[\"Health\"],\"since\":{tick}}}");
+        if run(&changed, &mut buf) != 1 || buf[0] != high {
+            return 0;
+        }
+
+        // Step 3: Invalid specs are rejected
+        if run("{\"where\":[{\"op\":\"~\"}]}", &mut buf) != -1 {
+            return 0;
+        }
+
+        1
+    }
+}
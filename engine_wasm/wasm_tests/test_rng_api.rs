@@ -0,0 +1,52 @@
+// This file is compiled to WASM and loaded by the Rust host test harness.
+// Tests the seeded RNG API (seed, get_seed, random, random_range).
+
+#[unsafe(no_mangle)]
+pub extern "C" fn test_rng_api() -> i32 {
+    #[link(wasm_import_module = "rng")]
+    unsafe extern "C" {
+        fn seed(seed: i64);
+        fn get_seed() -> i64;
+        fn random(stream_ptr: *const u8, stream_len: i32) -> f64;
+        fn random_range(stream_ptr: *const u8, stream_len: i32, min: i64, max: i64) -> i64;
+    }
+
+    unsafe {
+        // Step 1: Seed and read back
+        seed(1234);
+        if get_seed() != 1234 {
+            return 0;
+        }
+
+        // Step 2: Reseeding repeats the sequence
+        let ai = "ai";
+        let first = random(ai.as_ptr(), ai.len() as i32);
+        if !(0.0..1.0).contains(&first) {
+            return 0;
+        }
+        seed(1234);
+        if random(ai.as_ptr(), ai.len() as i32) != first {
+            return 0;
+        }
+
+        // Step 3: Streams are independent
+        let loot = "loot";
+        seed(7);
+        let expected = random_range(loot.as_ptr(), loot.len() as i32, 1, 1000);
+        seed(7);
+        random(ai.as_ptr(), ai.len() as i32);
+        if random_range(loot.as_ptr(), loot.len() as i32, 1, 1000) != expected {
+            return 0;
+        }
+
+        // Step 4: Range bounds are inclusive
+        for _ in 0..100 {
+            let v = random_range(ai.as_ptr(), ai.len() as i32, 1, 3);
+            if !(1..=3).contains(&v) {
+                return 0;
+            }
+        }
+
+        1
+    }
+}
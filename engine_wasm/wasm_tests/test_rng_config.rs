@@ -0,0 +1,19 @@
+// This file is compiled to WASM and loaded by the Rust host test harness.
+// Exposes the world RNG so the host can check that the configured seed is used.
+
+#[link(wasm_import_module = "rng")]
+unsafe extern "C" {
+    fn get_seed() -> i64;
+    fn random_range(stream_ptr: *const u8, stream_len: i32, min: i64, max: i64) -> i64;
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn configured_seed() -> i64 {
+    unsafe { get_seed() }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn first_draw() -> i64 {
+    let ai = "ai";
+    unsafe { random_range(ai.as_ptr(), ai.len() as i32, 0, i64::MAX) }
+}
@@ -0,0 +1,96 @@
+// This file is compiled to WASM and loaded by the Rust host test harness.
+// Tests the world state hash API (state_hash, set_system_hash_debug, take_system_hashes).
+
+#[unsafe(no_mangle)]
+pub extern "C" fn test_state_hash_api() -> i32 {
+    #[link(wasm_import_module = "state")]
+    unsafe extern "C" {
+        fn state_hash() -> i64;
+    }
+    #[link(wasm_import_module = "turn")]
+    unsafe extern "C" {
+        fn tick();
+    }
+
+    unsafe {
+        // Step 1: The hash is stable while the state does not change
+        let before = state_hash();
+        if state_hash() != before {
+            return 0;
+        }
+
+        // Step 2: Advancing the simulation changes the hash
+        tick();
+        if state_hash() == before {
+            return 0;
+        }
+
+        1
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn test_system_hash_log() -> i32 {
+    #[link(wasm_import_module = "state")]
+    unsafe extern "C" {
+        fn set_system_hash_debug(enabled: i32);
+        fn take_system_hashes(out_ptr: *mut u8, out_len: i32) -> i32;
+    }
+    #[link(wasm_import_module = "system")]
+    unsafe extern "C" {
+        fn register_system(name_ptr: *const u8, name_len: i32, type_ptr: *const u8, type_len: i32);
+        fn run_system(name_ptr: *const u8, name_len: i32);
+    }
+    #[link(wasm_import_module = "turn")]
+    unsafe extern "C" {
+        fn tick();
+    }
+
+    let name = b"Growth";
+    let kind = b"script";
+    let mut buf = [0u8; 512];
+
+    unsafe {
+        // Step 1: Nothing is logged while debugging is off
+        tick();
+        if take_system_hashes(buf.as_mut_ptr(), buf.len() as i32) != 2 {
+            return 0;
+        }
+
+        // Step 2: A tick and a system run each log an entry
+        set_system_hash_debug(1);
+        register_system(
+            name.as_ptr(),
+            name.len() as i32,
+            kind.as_ptr(),
+            kind.len() as i32,
+        );
+        tick();
+        run_system(name.as_ptr(), name.len() as i32);
+
+        // Step 3: A buffer that is too small keeps the log
+        if take_system_hashes(buf.as_mut_ptr(), 4) != -1 {
+            return 0;
+        }
+        let len = take_system_hashes(buf.as_mut_ptr(), buf.len() as i32);
+        if len <= 0 {
+            return 0;
+        }
+        let log = &buf[..len as usize];
+        if !contains(log, b"\"system\":\"tick_start\"") || !contains(log, b"\"system\":\"Growth\"")
+        {
+            return 0;
+        }
+
+        // Step 4: Taking the log empties it
+        if take_system_hashes(buf.as_mut_ptr(), buf.len() as i32) != 2 {
+            return 0;
+        }
+
+        1
+    }
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|w| w == needle)
+}
@@ -0,0 +1,183 @@
+// This file is compiled to WASM and loaded by the Rust host test harness.
+// Tests that research charges and refunds a tech's resource cost and keeps
+// unrelated TechProgress fields such as base_rate.
+
+#[unsafe(no_mangle)]
+pub extern "C" fn test_tech_tree_api() -> i32 {
+    #[link(wasm_import_module = "entity")]
+    unsafe extern "C" {
+        fn spawn_entity() -> u32;
+    }
+
+    #[link(wasm_import_module = "component")]
+    unsafe extern "C" {
+        fn set_component(
+            entity: u32,
+            name_ptr: *const u8,
+            name_len: i32,
+            json_ptr: *const u8,
+            json_len: i32,
+        );
+        fn get_component(
+            entity: u32,
+            name_ptr: *const u8,
+            name_len: i32,
+            out_ptr: *mut u8,
+            out_len: i32,
+        ) -> i32;
+    }
+
+    #[link(wasm_import_module = "tech_tree")]
+    unsafe extern "C" {
+        fn research_tech(entity: u32, tech_id_ptr: *const u8, tech_id_len: i32) -> i32;
+        fn cancel_research(entity: u32, tech_id_ptr: *const u8, tech_id_len: i32) -> i32;
+        fn can_research_tech(
+            entity: u32,
+            tech_id_ptr: *const u8,
+            tech_id_len: i32,
+            out_bool_ptr: *mut i32,
+            out_reason_ptr: *mut u8,
+            out_reason_len: i32,
+        ) -> i32;
+    }
+
+    unsafe fn component_json(entity: u32, name: &str) -> String {
+        let mut buf = [0u8; 1024];
+        let n = unsafe {
+            get_component(
+                entity,
+                name.as_ptr(),
+                name.len() as i32,
+                buf.as_mut_ptr(),
+                buf.len() as i32,
+            )
+        };
+        if n < 0 {
+            return String::new();
+        }
+        String::from_utf8_lossy(&buf[..n as usize]).into_owned()
+    }
+
+    unsafe {
+        let eid = spawn_entity();
+        let tech = "masonry";
+        let stockpile = "Stockpile";
+        let progress_name = "TechProgress";
+
+        // Step 1: Too little stone to research
+        let poor = "{\"resources\":{\"stone\":5}}";
+        set_component(
+            eid,
+            stockpile.as_ptr(),
+            stockpile.len() as i32,
+            poor.as_ptr(),
+            poor.len() as i32,
+        );
+        let mut can = 1i32;
+        let mut reason = [0u8; 256];
+        if can_research_tech(
+            eid,
+            tech.as_ptr(),
+            tech.len() as i32,
+            &mut can,
+            reason.as_mut_ptr(),
+            reason.len() as i32,
+        ) != 0
+            || can != 0
+        {
+            return 0;
+        }
+        if research_tech(eid, tech.as_ptr(), tech.len() as i32) != -1 {
+            return 0;
+        }
+
+        // Step 2: Researching charges the cost and keeps base_rate
+        let rich = "{\"resources\":{\"stone\":25}}";
+        set_component(
+            eid,
+            stockpile.as_ptr(),
+            stockpile.len() as i32,
+            rich.as_ptr(),
+            rich.len() as i32,
+        );
+        let progress = "{\"completed\":{},\"queue\":[],\"queue_progress\":{},\"research_points\":0.0,\"base_rate\":2.0}";
+        set_component(
+            eid,
+            progress_name.as_ptr(),
+            progress_name.len() as i32,
+            progress.as_ptr(),
+            progress.len() as i32,
+        );
+        if research_tech(eid, tech.as_ptr(), tech.len() as i32) != 0 {
+            return 0;
+        }
+        if !component_json(eid, stockpile).contains("\"stone\":5") {
+            return 0;
+        }
+        let after = component_json(eid, progress_name);
+        if !after.contains("\"base_rate\":2.0") || !after.contains("\"masonry\"") {
+            return 0;
+        }
+
+        // Step 3: Cancelling refunds the cost and still keeps base_rate
+        if cancel_research(eid, tech.as_ptr(), tech.len() as i32) != 0 {
+            return 0;
+        }
+        if !component_json(eid, stockpile).contains("\"stone\":25") {
+            return 0;
+        }
+        if !component_json(eid, progress_name).contains("\"base_rate\":2.0") {
+            return 0;
+        }
+
+        1
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn test_load_and_validate_tech_tree() -> i32 {
+    #[link(wasm_import_module = "tech_tree")]
+    unsafe extern "C" {
+        fn load_tech_tree(path_ptr: *const u8, path_len: i32) -> i32;
+        fn validate_tech_tree(out_ptr: *mut u8, out_len: i32) -> i32;
+        fn get_tech_node(
+            tech_id_ptr: *const u8,
+            tech_id_len: i32,
+            out_ptr: *mut u8,
+            out_len: i32,
+        ) -> i32;
+    }
+
+    let mut buf = [0u8; 1024];
+    unsafe {
+        // Step 1: A missing path is an error
+        let missing = "no/such/tech/tree.json";
+        if load_tech_tree(missing.as_ptr(), missing.len() as i32) != -1 {
+            return 0;
+        }
+
+        // Step 2: Loading merges the engine's tech data into the tree
+        let assets = "../engine/assets/tech";
+        if load_tech_tree(assets.as_ptr(), assets.len() as i32) != 0 {
+            return 0;
+        }
+        let tech = "bronze_working";
+        if get_tech_node(
+            tech.as_ptr(),
+            tech.len() as i32,
+            buf.as_mut_ptr(),
+            buf.len() as i32,
+        ) <= 0
+        {
+            return 0;
+        }
+
+        // Step 3: The merged tree validates without problems
+        let n = validate_tech_tree(buf.as_mut_ptr(), buf.len() as i32);
+        if n != 2 || &buf[..2] != b"[]" {
+            return 0;
+        }
+
+        1
+    }
+}
@@ -7,9 +7,16 @@ use std::ffi::CString;
 use std::ptr;
 use std::ptr::addr_of_mut;
 
-/// Rust bindings for the engine API
+/// Rust bindings for the leading part of the engine API this plugin uses.
+///
+/// The engine's `EngineApi` only grows at the end, so a plugin may declare a
+/// prefix of it and check `struct_size` before using later fields.
 #[repr(C)]
 pub struct EngineApi {
+    /// Size of the engine's struct in bytes
+    pub struct_size: u32,
+    /// Engine API revision
+    pub version: u32,
     /// Spawns an entity
     pub spawn_entity: unsafe extern "C" fn(*mut c_void) -> c_uint,
     /// Sets a component
@@ -32,7 +39,7 @@ pub struct SystemPlugin {
 }
 
 /// Must match the engine's PLUGIN_ABI_VERSION in engine/core/src/plugins/types.rs
-pub const PLUGIN_ABI_VERSION: u32 = 1;
+pub const PLUGIN_ABI_VERSION: u32 = 2;
 
 /// Plugin vtable
 #[repr(C)]
@@ -93,6 +100,9 @@ unsafe extern "C" fn register_systems(
 unsafe extern "C" fn init(api: *mut EngineApi, world: *mut c_void) -> c_int {
     unsafe {
         let api = &*api;
+        if (api.struct_size as usize) < std::mem::size_of::<EngineApi>() {
+            return -1;
+        }
         let entity = (api.spawn_entity)(world);
         let pos_json = CString::new(r#"{"x": 10.0, "y": 42.0}"#).unwrap();
         let comp_name = CString::new("Position").unwrap();
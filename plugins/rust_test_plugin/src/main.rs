@@ -5,6 +5,12 @@ use std::env;
 use std::io::{BufRead, BufReader, Write};
 use std::os::unix::net::UnixStream;
 
+/// Tick-driven system: every entity with Health loses one point per tick.
+const BLEED_SYSTEM: &str = "bleed";
+/// Offered instead of `bleed` when the binary's file name ends in `_heal`:
+/// every entity with Health gains one point per tick.
+const HEAL_SYSTEM: &str = "heal";
+
 /// Plugin requests
 #[derive(Debug, Serialize, Deserialize)]
 pub enum PluginRequest {
@@ -21,6 +27,20 @@ pub enum PluginRequest {
         /// Arguments
         data: serde_json::Value,
     },
+    /// List systems
+    ListSystems,
+    /// Run a system
+    RunSystem {
+        /// System name
+        system: String,
+        /// Tick length
+        delta_time: f32,
+    },
+    /// Results of a world request
+    WorldResult {
+        /// One result per operation
+        results: Vec<serde_json::Value>,
+    },
 }
 
 /// Plugin responses
@@ -42,6 +62,82 @@ pub enum PluginResponse {
         /// Error message
         message: String,
     },
+    /// Systems to run every tick
+    Systems {
+        /// System names
+        systems: Vec<String>,
+    },
+    /// System finished
+    SystemDone,
+    /// Batch of world operations
+    WorldRequest {
+        /// Operations
+        ops: Vec<serde_json::Value>,
+    },
+}
+
+/// Connection to the engine.
+struct Engine {
+    reader: BufReader<UnixStream>,
+    writer: UnixStream,
+}
+
+impl Engine {
+    fn send(&mut self, response: &PluginResponse) -> std::io::Result<()> {
+        let msg = serde_json::to_string(response)? + "\n";
+        self.writer.write_all(msg.as_bytes())
+    }
+
+    /// Apply a batch of world operations, returning the value of each one
+    /// (null for failed operations).
+    fn world(&mut self, ops: Vec<serde_json::Value>) -> std::io::Result<Vec<serde_json::Value>> {
+        self.send(&PluginResponse::WorldRequest { ops })?;
+        let mut line = String::new();
+        self.reader.read_line(&mut line)?;
+        match serde_json::from_str(&line)? {
+            PluginRequest::WorldResult { results } => Ok(results
+                .into_iter()
+                .map(|result| result["Ok"]["value"].clone())
+                .collect()),
+            other => Err(std::io::Error::other(format!(
+                "expected WorldResult, got {other:?}"
+            ))),
+        }
+    }
+
+    /// Query every entity with Health and write each one back with `change` added.
+    fn shift_health(&mut self, change: f64) -> std::io::Result<()> {
+        let rows = self.world(vec![serde_json::json!({
+            "Query": { "query": { "with": ["Health"] } }
+        })])?;
+        let writes = rows[0]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|row| {
+                let mut health = row["components"]["Health"].clone();
+                let current = health["current"].as_f64().unwrap_or(0.0);
+                health["current"] = serde_json::json!((current + change).max(0.0));
+                serde_json::json!({
+                    "SetComponent": { "entity": row["entity"], "name": "Health", "value": health }
+                })
+            })
+            .collect();
+        self.world(writes)?;
+        Ok(())
+    }
+
+    /// Spawn an entity with the given Health.
+    fn spawn(&mut self, health: serde_json::Value) -> std::io::Result<serde_json::Value> {
+        let entity = self.world(vec![serde_json::json!("Spawn")])?.remove(0);
+        if entity.is_null() {
+            return Ok(serde_json::json!({ "entity": null, "set": false }));
+        }
+        let results = self.world(vec![serde_json::json!({
+            "SetComponent": { "entity": entity, "name": "Health", "value": health }
+        })])?;
+        Ok(serde_json::json!({ "entity": entity, "set": results[0].is_null() }))
+    }
 }
 
 fn main() -> std::io::Result<()> {
@@ -51,23 +147,28 @@ fn main() -> std::io::Result<()> {
         std::process::exit(1);
     }
     let socket_path = &args[1];
+    let system = if args[0].ends_with("_heal") {
+        HEAL_SYSTEM
+    } else {
+        BLEED_SYSTEM
+    };
     let stream = UnixStream::connect(socket_path)?;
-    let mut reader = BufReader::new(stream.try_clone()?);
-    let mut writer = stream;
+    let mut engine = Engine {
+        reader: BufReader::new(stream.try_clone()?),
+        writer: stream,
+    };
 
     loop {
         let mut line = String::new();
-        if reader.read_line(&mut line)? == 0 {
+        if engine.reader.read_line(&mut line)? == 0 {
             break;
         }
         let req: PluginRequest = match serde_json::from_str(&line) {
             Ok(r) => r,
             Err(e) => {
-                let err = PluginResponse::Error {
+                engine.send(&PluginResponse::Error {
                     message: e.to_string(),
-                };
-                let msg = serde_json::to_string(&err)? + "\n";
-                writer.write_all(msg.as_bytes())?;
+                })?;
                 continue;
             }
         };
@@ -81,15 +182,40 @@ fn main() -> std::io::Result<()> {
                 PluginResponse::Reloaded
             }
             PluginRequest::Shutdown => PluginResponse::Shutdown,
-            PluginRequest::RunCommand { data, .. } => {
+            PluginRequest::RunCommand { command, data } => match command.as_str() {
+                "spawn" => PluginResponse::CommandResult {
+                    result: engine.spawn(data)?,
+                },
+                // Misbehaving plugins, for the engine's crash isolation tests
+                "crash" => std::process::exit(3),
+                "hang" => {
+                    std::thread::sleep(std::time::Duration::from_secs(60));
+                    continue;
+                }
+                "garbage" => {
+                    engine.writer.write_all(b"not json\n")?;
+                    continue;
+                }
                 // Handle custom commands here
-                PluginResponse::CommandResult {
+                _ => PluginResponse::CommandResult {
                     result: serde_json::json!({"echo": data}),
-                }
+                },
+            },
+            PluginRequest::ListSystems => PluginResponse::Systems {
+                systems: vec![system.to_string()],
+            },
+            PluginRequest::RunSystem { system: name, .. } if name == system => {
+                engine.shift_health(if system == HEAL_SYSTEM { 1.0 } else { -1.0 })?;
+                PluginResponse::SystemDone
             }
+            PluginRequest::RunSystem { system: name, .. } => PluginResponse::Error {
+                message: format!("Unknown system '{name}'"),
+            },
+            PluginRequest::WorldResult { .. } => PluginResponse::Error {
+                message: "Unexpected WorldResult".to_string(),
+            },
         };
-        let msg = serde_json::to_string(&resp)? + "\n";
-        writer.write_all(msg.as_bytes())?;
+        engine.send(&resp)?;
         if matches!(resp, PluginResponse::Shutdown) {
             break;
         }
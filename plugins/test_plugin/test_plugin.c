@@ -51,6 +51,13 @@ static int init(struct EngineApi *api, void *world) {
   const char *position_json = "{\"x\": 1.0, \"y\": 2.0}";
   int result = api->set_component(world, entity, "Position", position_json);
   printf("Plugin initialized: spawned entity %u with Position\n", entity);
+  if (result == 0 && ENGINE_API_HAS(api, get_component)) {
+    char *stored = api->get_component(world, entity, "Position");
+    if (stored) {
+      printf("Plugin read back Position: %s\n", stored);
+      api->free_string(stored);
+    }
+  }
   return result;
 }
 
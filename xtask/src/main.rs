@@ -19,6 +19,7 @@ fn main() {
         "build-plugins" => build_and_deploy_plugins(),
         "build-c-plugins" => build_c_plugins(),
         "build-wasm-tests" => build_wasm_tests(),
+        "gen-plugin-header" => gen_plugin_header(),
         "build-all" => build_and_deploy_plugins()
             .and_then(|_| build_c_plugins())
             .and_then(|_| build_wasm_tests()),
@@ -139,6 +140,25 @@ fn build_and_deploy_plugins() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+fn gen_plugin_header() -> Result<(), Box<dyn Error>> {
+    // The header is generated from the Rust ABI definitions in engine_core
+    let status = Command::new("cargo")
+        .args([
+            "run",
+            "-p",
+            "engine_core",
+            "--example",
+            "gen_plugin_header",
+            "--",
+        ])
+        .arg(Path::new("engine").join("engine_plugin_abi.h"))
+        .status()?;
+    if !status.success() {
+        return Err("Plugin header generation failed".into());
+    }
+    Ok(())
+}
+
 fn build_c_plugins() -> Result<(), Box<dyn Error>> {
     let plugins_dir = Path::new("plugins");
     let engine_dir = Path::new("engine");